@@ -8,13 +8,137 @@
 ---@class pdf
 pdf = {}
 
+-------------------------------------------------------------------------------
+-- ARGUMENTS
+-------------------------------------------------------------------------------
+
+---Title of the PDF, such as via `makepdf make --title "Planner"`.
+---@type string
+pdf.title = ""
+
+---Path of the script, or the name of a script provided as a string.
+---@type string
+pdf.script = ""
+
+---Arguments passed to the script as strings, such as from the commandline
+---via `makepdf make --var year=2025`, available as `pdf.args.year`.
+---@type table<string, string>
+pdf.args = {}
+
+---If true, the PDF is produced as PDF/A-2b for archival, such as via
+---`makepdf make --pdfa`. PDF/A does not allow attachments.
+---@type boolean
+pdf.pdfa = false
+
+---If true, reduces the size of the PDF by merging identical objects, such as
+---the content shared by similar pages, and compressing streams, which can also
+---be enabled with `makepdf make --optimize`.
+---@type boolean
+pdf.optimize = false
+
+---If true, the script was allowed to be precompiled Luau bytecode, such as via
+---`makepdf make --allow-bytecode`. Changing this within the script has no
+---effect as the script is already loaded.
+---@type boolean
+pdf.allow_bytecode = false
+
+---Level (0 to 2) of optimizations applied when compiling the script, such as
+---via `makepdf make -O2`. Like `pdf.allow_bytecode`, changing this within the
+---script has no effect.
+---@type integer
+pdf.opt_level = 1
+
+---If true, the script was compiled to native code, such as via
+---`makepdf make --native`. Like `pdf.allow_bytecode`, changing this within the
+---script has no effect.
+---@type boolean
+pdf.native = false
+
+---If true, objects with fields they do not support, such as a typo like
+---`fill_colour`, fail to convert with an error suggesting the nearest field
+---rather than ignoring the fields, such as via `makepdf make --strict`. Can be
+---set at any point within the script to check the objects created afterwards.
+---@type boolean
+pdf.strict = false
+
+---If set, places the pages of the PDF onto larger sheets of paper (A4 unless
+---another `sheet` like "letter" or a `width` & `height` in millimeters is
+---given) for printing at home, two pages side by side on each side of a sheet,
+---which can also be enabled with `makepdf make --imposition booklet --sheet a4`.
+---
+---The "2up" layout places pages in order, while the "booklet" layout places
+---pages so that the sheets printed on both sides and folded in half form a
+---booklet. Links, bookmarks, and page labels are removed.
+---@type {layout:"2up"|"booklet", sheet?:string, width?:number, height?:number}|nil
+pdf.imposition = nil
+
+---Text or images stamped onto pages while they are built, managed through
+---`pdf.watermark.set()`, `pdf.watermark.add()`, and `pdf.watermark.clear()`.
+---@type pdf.watermark.Watermark[]
+pdf.watermarks = {}
+
+---Paths of the fonts registered before the script ran, by name. Use
+---`pdf.font.get()` to retrieve the id of a font by name.
+---@type table<string, string>
+pdf.fonts = {}
+
+-------------------------------------------------------------------------------
+-- THEME
+-------------------------------------------------------------------------------
+
+---Named values like colors and sizes that objects and page configuration can
+---reference by name with an `@` prefix, resolved when the object is created:
+---
+---```lua
+---pdf.theme.accent = "#c0392b"
+---pdf.theme.thin = 0.5
+---pdf.object.rect({ fill_color = "@accent", outline_thickness = "@thin" })
+---```
+---
+---Theme values can themselves reference other theme values. Strings that are
+---valid as-is, such as the text of a text object, are never resolved.
+---
+---A list of colors within `pdf.theme.chart` is used by `pdf.object.chart`.
+---@type table<string, any>
+pdf.theme = {}
+
+-------------------------------------------------------------------------------
+-- DEFAULTS
+-------------------------------------------------------------------------------
+
+---Defaults of each type of object, used for objects of that type that do not
+---specify their own in place of the defaults of pages (e.g. `pdf.page.fill_color`),
+---so the look of a whole document can be adjusted in one place:
+---
+---```lua
+---pdf.defaults.text = { size = 10, color = "#222" }
+---pdf.defaults.line = { thickness = 0.4 }
+---```
+---
+---The style of a group takes precedence over these defaults.
+---@class pdf.defaults
+pdf.defaults = {
+    ---@type pdf.defaults.Shape
+    circle = {},
+    ---@type {color?:pdf.common.ColorLike, thickness?:number, dash_pattern?:pdf.common.line.DashPatternLike, cap_style?:pdf.common.line.CapStyle, join_style?:pdf.common.line.JoinStyle}
+    line = {},
+    ---@type pdf.defaults.Shape
+    rect = {},
+    ---@type pdf.defaults.Shape
+    shape = {},
+    ---@type {size?:number, color?:pdf.common.ColorLike}
+    text = {},
+}
+
+---@alias pdf.defaults.Shape {fill_color?:pdf.common.ColorLike, outline_color?:pdf.common.ColorLike, outline_thickness?:number, dash_pattern?:pdf.common.line.DashPatternLike, cap_style?:pdf.common.line.CapStyle, join_style?:pdf.common.line.JoinStyle}
+
 -------------------------------------------------------------------------------
 -- PAGE CONFIGURATION
 -------------------------------------------------------------------------------
 
 ---@class pdf.page
 pdf.page = {
-    ---DPI of the page.
+    ---DPI of the page, used to convert pixels into millimeters.
     ---@type number
     dpi = 0,
     ---Path to an external font to load as the default font.
@@ -27,6 +151,27 @@ pdf.page = {
     ---Height of the page in millimeters.
     ---@type number
     height = 0,
+    ---Alias of `width`, which reads and writes the width of the page.
+    ---@type number
+    width_mm = 0,
+    ---Alias of `height`, which reads and writes the height of the page.
+    ---@type number
+    height_mm = 0,
+    ---Space in millimeters to leave empty along each edge of the page, used by
+    ---`pdf.page:content_bounds()`.
+    ---@type pdf.common.PaddingLike
+    margins = { top = 0, right = 0, bottom = 0, left = 0 },
+    ---If true, the left and right margins are swapped on left-hand pages, so
+    ---the left margin is the inner (binding) margin of every page.
+    ---@type boolean
+    mirror_margins = false,
+    ---Area in millimeters along each edge of the page that is hidden or covered
+    ---by the device, such as a toolbar, used by `pdf.page:content_bounds()`.
+    ---@type pdf.common.PaddingLike
+    unsafe_area = { top = 0, right = 0, bottom = 0, left = 0 },
+    ---Default depth of objects, used when an object has no depth.
+    ---@type integer
+    depth = 0,
     ---Default size of text font in points.
     ---@type number
     font_size = 0,
@@ -36,24 +181,90 @@ pdf.page = {
     ---Used for the exterior of rects and shapes, and for lines.
     ---@type pdf.common.ColorLike
     outline_color = "",
-    ---Default thickness of lines.
+    ---Default thickness of lines in points.
+    ---
+    ---A thickness of 0 is a hairline, which is the thinnest line the device
+    ---can render.
     ---@type number
     outline_thickness = 0,
+    ---Minimum thickness of lines in points, where thinner lines (other than
+    ---hairlines) are drawn at this thickness instead. Defaults to 0, meaning no minimum.
+    ---@type number
+    min_outline_thickness = 0,
     ---Default dash pattern of lines.
     ---@type pdf.common.line.DashPatternLike
-    dash_pattern = "solid",
+    line_dash_pattern = "solid",
     ---Default cap style of lines.
     ---@type pdf.common.line.CapStyle
-    cap_style = "round",
+    line_cap_style = "round",
     ---Default join style of lines.
     ---@type pdf.common.line.JoinStyle
-    join_style = "round",
+    line_join_style = "round",
+    ---If true, snaps the coordinates and thickness of lines and outlines to the
+    ---pixel grid of the page's DPI, which sharpens thin lines on e-ink screens.
+    ---Works best when the page dimensions are a whole number of pixels.
+    ---@type boolean
+    snap_to_pixels = false,
+    ---If true, converts all colors to grayscale when drawing, which can also be
+    ---enabled with `makepdf make --grayscale`.
+    ---@type boolean
+    grayscale = false,
+    ---If set, overlays each page with a labeled coordinate grid of this spacing
+    ---(in millimeters), the crop and art boxes, and the bounds and depth of
+    ---each object, which can also be enabled with `makepdf make --debug-layout`.
+    ---@type number|nil
+    debug_grid = nil,
+    ---Layers created with `pdf.page.layer`, in the order they are stacked.
+    ---@type pdf.common.Layer[]
+    layers = {},
 }
 
 ---Returns the bounds covering the entire page.
 ---@return pdf.common.Bounds
 function pdf.page:bounds() end
 
+---Returns the bounds of a page on `side` (default "right") inset by its
+---margins, and by its unsafe area wherever it reaches further than the margins.
+---@param side? pdf.common.PageSide
+---@return pdf.common.Bounds
+function pdf.page:content_bounds(side) end
+
+---Sets the default depth of objects, used when an object has no depth.
+---@param depth integer
+function pdf.page:set_default_depth(depth) end
+
+---Creates a layer named `name` that objects can be drawn within by setting
+---their `layer` field, returning the layer. Calling again with the same name
+---returns the existing layer, updated by any options provided.
+---
+---Layers are stacked above the rest of the page in the order they were
+---created, so depth only orders objects within the same layer.
+---
+---Optional layers can be toggled on and off by PDF viewers, such as to hide
+---a grid or guides, and can start hidden by setting `visible` to false.
+---@param name string
+---@param opts? {optional?:boolean, visible?:boolean}
+---@return pdf.common.Layer
+function pdf.page.layer(name, opts) end
+
+-------------------------------------------------------------------------------
+-- LINK CONFIGURATION
+-------------------------------------------------------------------------------
+
+---Style applied to text objects that carry a link. Text can opt out by
+---specifying its own `link_style`, including `false` to disable styling.
+---
+---Color is only applied when the text does not have a color of its own.
+---
+---Padding (in millimeters) grows the clickable area of the link beyond the
+---text on every side, making small links easier to tap on tablets.
+---
+---Border and highlight control how PDF readers draw the clickable area of
+---links, applying to the links of all objects. Links have no border by
+---default, and highlight when clicked by inverting their colors.
+---@type pdf.common.LinkStyleLike
+pdf.link_style = { underline = false }
+
 -------------------------------------------------------------------------------
 -- COMMON TYPES
 -------------------------------------------------------------------------------
@@ -62,8 +273,22 @@ function pdf.page:bounds() end
 ---@alias pdf.common.WindingOrder "even_odd"|"non_zero"
 ---@alias pdf.common.Align {h?:pdf.common.HorizontalAlign, v?:pdf.common.VerticalAlign}
 ---@alias pdf.common.HorizontalAlign "left"|"middle"|"right"
----@alias pdf.common.VerticalAlign "top"|"middle"|"bottom"
+---@alias pdf.common.VerticalAlign "top"|"middle"|"bottom"|"baseline"
+---@alias pdf.common.TextDirection "ltr"|"rtl"
+---@alias pdf.common.TextOverflow "truncate"|"ellipsis"|"shrink"
+---@alias pdf.common.TextScript "super"|"sub"
+---@alias pdf.common.PageSide "left"|"right"
+---@alias pdf.common.Background {color?:pdf.common.Color, padding:pdf.common.Padding, corner_radius:number}
+---@alias pdf.common.BackgroundLike
+---| string # color of the box, padded by 1mm
+---| {color?:pdf.common.ColorLike, padding?:pdf.common.PaddingLike, corner_radius?:number} # color defaults to light grey, padding to 1mm
+---@alias pdf.common.Shadow {offset:pdf.common.Point, color:pdf.common.Color, blur:number}
+---@alias pdf.common.ShadowLike
+---| true # shadow offset 1mm right and down in translucent black
+---| {offset?:pdf.common.PointLike, color?:pdf.common.ColorLike, blur?:number} # blur spreads the shadow by millimeters
 ---@alias pdf.common.Padding {top:number, right:number, bottom:number, left:number}
+---@alias pdf.common.Layer {name:string, optional:boolean, visible:boolean}
+---@alias pdf.common.LayerLike string|pdf.common.Layer
 
 ---@alias pdf.common.line.CapStyle "butt"|"round"|"projecting_square"
 ---@alias pdf.common.line.JoinStyle "limit"|"miter"|"round"
@@ -72,28 +297,60 @@ function pdf.page:bounds() end
 ---| pdf.common.line.DashPattern
 
 ---@alias pdf.common.ColorLike
----| string
+---| string #hex color like "#ff0000" (or "#ff000080" with alpha), CSS color name like "steelblue", or theme reference like "@accent"
 ---| {[1]:integer, [2]:integer, [3]:integer}
 ---| {r:integer, g:integer, b:integer}
+---| {c:number, m:number, y:number, k:number} #cmyk inks between 0 and 1
+---| {gray:number} #gray between 0 (black) and 1 (white)
+---| {r:integer, g:integer, b:integer, alpha:number} #any color table can include an alpha between 0 (transparent) and 1 (opaque)
 ---| pdf.common.Color
 
 ---@alias pdf.common.Link
----| {type:"goto", page:integer}
+---| {type:"goto", page:integer, at?:pdf.common.Point, zoom?:number, rect?:pdf.common.Bounds}
 ---| {type:"uri", uri:string}
 
 ---@alias pdf.common.LinkLike
 ---| integer #representing a page's id
 ---| string #representing a URI
----| pdf.common.Link
+---| {type:"goto", page:integer, at?:pdf.common.PointLike, zoom?:number, rect?:pdf.common.BoundsLike} #at is the upper-left of the view, rect is the area to fit within the view
+---| {type:"page", id:integer, at?:pdf.common.PointLike, zoom?:number, rect?:pdf.common.BoundsLike} #alias of goto
+---| {type:"uri", uri:string}
+
+---@alias pdf.common.LinkStyle {color?:pdf.common.Color, underline:boolean, padding:number, border:pdf.common.LinkBorder, highlight:pdf.common.LinkHighlight}
+
+---@alias pdf.common.LinkStyleLike
+---| false #representing no styling
+---| {color?:pdf.common.ColorLike, underline?:boolean, padding?:number, border?:pdf.common.LinkBorderLike, highlight?:pdf.common.LinkHighlight}
+
+---@alias pdf.common.LinkBorder {width:number, color?:pdf.common.Color}
+
+---@alias pdf.common.LinkBorderLike
+---| false #representing no border
+---| {width?:number, color?:pdf.common.ColorLike}
 
+---@alias pdf.common.LinkHighlight "none"|"invert"|"outline"
+
+---Millimeters, or a string of a number with units of `mm`, `cm`, `in`, `pt`,
+---or `px` (converted using `pdf.page.dpi`), such as `"1in"`.
+---@alias pdf.common.Length number|string
+
+---Length, or a string of a percentage such as `"50%"` relative to the content
+---bounds of pages (`pdf.page:content_bounds()`), or to the cell of a layout
+---when returned from a function given to the layout.
+---@alias pdf.common.Coord pdf.common.Length
+
+---Coordinates of points and bounds can be lengths with units or percentages,
+---such as `{ "1in", "72pt" }` or `{ "50%", "50%" }`.
 ---@alias pdf.common.PointLike
 ---| pdf.common.Point
----| {[1]:number, [2]:number}
+---| {x:pdf.common.Coord, y:pdf.common.Coord}
+---| {[1]:pdf.common.Coord, [2]:pdf.common.Coord}
 
 ---@alias pdf.common.BoundsLike
 ---| pdf.common.Bounds
----| {[1]:{[1]:number, [2]:number}, [2]:{[1]:number, [2]:number}}
----| {[1]:number, [2]:number, [3]:number, [4]:number}
+---| {ll:pdf.common.PointLike, ur:pdf.common.PointLike}
+---| {[1]:pdf.common.PointLike, [2]:pdf.common.PointLike}
+---| {[1]:pdf.common.Coord, [2]:pdf.common.Coord, [3]:pdf.common.Coord, [4]:pdf.common.Coord}
 
 ---@alias pdf.common.PaddingLike
 ---| {top?:number, right?:number, bottom?:number, left?:number}
@@ -117,6 +374,8 @@ function pdf.page:bounds() end
 ---@field gap_2 integer|nil
 ---@field gap_3 integer|nil
 
+---Two bounds are equal (`==`) when their points match to a thousandth of a
+---millimeter, ignoring float noise.
 ---@class pdf.common.Bounds
 ---@field ll pdf.common.Point
 ---@field ur pdf.common.Point
@@ -143,11 +402,23 @@ function PdfBounds:align_to(bounds, align) end
 ---@return pdf.common.Bounds
 function PdfBounds:with_padding(padding) end
 
+---Returns a copy of bounds grown by `amount` on every side, shrinking them when
+---`amount` is negative.
+---@param amount number
+---@return pdf.common.Bounds
+function PdfBounds:expand(amount) end
+
 ---Returns a copy of bounds with points rounded to precision.
 ---@param precision integer
 ---@return pdf.common.Bounds
 function PdfBounds:with_precision(precision) end
 
+---Returns a copy of bounds with points rounded to a number of decimal places,
+---such as to compare bounds without noise from floating point math.
+---@param decimals integer
+---@return pdf.common.Bounds
+function PdfBounds:round_to(decimals) end
+
 ---Moves the bounds to the specified x & y position for the lower-left point,
 ---returning updated bounds.
 ---
@@ -197,9 +468,15 @@ function PdfBounds:height() end
 function PdfBounds:to_coords() end
 
 ---@class pdf.common.Color
----@field red integer
----@field green integer
----@field blue integer
+---@field red integer #red of the color, or of its RGB equivalent when cmyk or gray
+---@field green integer #green of the color, or of its RGB equivalent when cmyk or gray
+---@field blue integer #blue of the color, or of its RGB equivalent when cmyk or gray
+---@field cyan number|nil #present when drawn as cmyk
+---@field magenta number|nil #present when drawn as cmyk
+---@field yellow number|nil #present when drawn as cmyk
+---@field black number|nil #present when drawn as cmyk
+---@field gray number|nil #present when drawn as gray
+---@field alpha number|nil #opacity between 0 (transparent) and 1 (opaque), present when translucent
 local PdfColor = {}
 
 ---Returns the luminance (brightness of the color) as a value between 0 and 1.
@@ -281,6 +558,22 @@ function PdfDate:beginning_of_month() end
 ---@return pdf.common.Date|nil
 function PdfDate:end_of_month() end
 
+---Returns current date moved to beginning of its quarter.
+---@return pdf.common.Date|nil
+function PdfDate:beginning_of_quarter() end
+
+---Returns current date moved to end of its quarter.
+---@return pdf.common.Date|nil
+function PdfDate:end_of_quarter() end
+
+---Returns the quarter (1 to 4) containing the date.
+---@return integer
+function PdfDate:quarter() end
+
+---Returns total days (28 to 31) within the month of the date.
+---@return integer
+function PdfDate:days_in_month() end
+
 ---Returns current date moved to beginning of week where beginning of week starts on Sunday.
 ---@return pdf.common.Date|nil
 function PdfDate:beginning_of_week_sunday() end
@@ -315,6 +608,38 @@ function PdfDate:calendar_week_sunday() end
 ---@return integer
 function PdfDate:calendar_week_monday() end
 
+---Returns the ISO 8601 week number within the year as a number from 1 to 53,
+---where weeks start on Monday and the first week contains the first Thursday.
+---
+---Dates near the start or end of a year can belong to a week of the adjacent year.
+---@return integer
+function PdfDate:iso_week() end
+
+---Returns the number of days from this date to `other`,
+---which is negative when `other` comes before this date.
+---@param other pdf.common.DateLike
+---@return integer
+function PdfDate:days_between(other) end
+
+---Returns true if this date is within `a` and `b` (inclusive),
+---regardless of whether `a` comes before or after `b`.
+---@param a pdf.common.DateLike
+---@param b pdf.common.DateLike
+---@return boolean
+function PdfDate:is_between(a, b) end
+
+---Returns an iterator over each day from this date up to and including `other`,
+---which yields nothing if `other` comes before this date.
+---
+---```lua
+---for date in start:upto(finish) do
+---    print(date)
+---end
+---```
+---@param other pdf.common.DateLike
+---@return fun():pdf.common.Date|nil
+function PdfDate:upto(other) end
+
 ---Converts date into a string in the format "YYYY-MM-DD".
 ---@return string
 function PdfDate:__tostring() end
@@ -367,9 +692,29 @@ function PdfDateWeekday:days_since(weekday) end
 ---@return string
 function PdfDateWeekday:__tostring() end
 
+---Placement beside an anchor, which is another object or bounds, given by
+---exactly one of `above`, `below`, `left_of`, or `right_of`.
+---
+---Objects placed above or below are aligned horizontally with the anchor,
+---defaulting to "left", while objects placed left or right of the anchor are
+---aligned vertically, defaulting to "top".
+---@class pdf.common.PlaceArgs
+---@field above? pdf.Object|pdf.common.BoundsLike
+---@field below? pdf.Object|pdf.common.BoundsLike
+---@field left_of? pdf.Object|pdf.common.BoundsLike
+---@field right_of? pdf.Object|pdf.common.BoundsLike
+---@field gap? number # space between the anchor and the object (default 0)
+---@field align? pdf.common.HorizontalAlign|pdf.common.VerticalAlign
+
+---Points can be added to and subtracted from other points, and multiplied by
+---a number, such as `(a + b) * 0.5`. Two points are equal (`==`) when their
+---coordinates match to a thousandth of a millimeter, ignoring float noise.
 ---@class pdf.common.Point
 ---@field x number
 ---@field y number
+---@operator add(pdf.common.PointLike): pdf.common.Point
+---@operator sub(pdf.common.PointLike): pdf.common.Point
+---@operator mul(number): pdf.common.Point
 local PdfPoint = {}
 
 ---Returns a copy of point with x & y rounded to precision.
@@ -377,6 +722,35 @@ local PdfPoint = {}
 ---@return pdf.common.Point
 function PdfPoint:with_precision(precision) end
 
+---Returns a copy of point with x & y rounded to a number of decimal places,
+---such as to compare points without noise from floating point math.
+---@param decimals integer
+---@return pdf.common.Point
+function PdfPoint:round_to(decimals) end
+
+---Returns the distance between the point and `other`.
+---@param other pdf.common.PointLike
+---@return number
+function PdfPoint:distance_to(other) end
+
+---Returns the point halfway between the point and `other`.
+---@param other pdf.common.PointLike
+---@return pdf.common.Point
+function PdfPoint:midpoint(other) end
+
+---Returns the point rotated counter-clockwise around `origin` by `deg` degrees.
+---@param origin pdf.common.PointLike
+---@param deg number
+---@return pdf.common.Point
+function PdfPoint:rotate_around(origin, deg) end
+
+---Returns the point that is `t` of the way from the point to `other`, where
+---`0` is the point and `1` is `other`.
+---@param other pdf.common.PointLike
+---@param t number
+---@return pdf.common.Point
+function PdfPoint:lerp(other, t) end
+
 -------------------------------------------------------------------------------
 -- RUNTIME TYPES
 -------------------------------------------------------------------------------
@@ -385,16 +759,73 @@ function PdfPoint:with_precision(precision) end
 
 ---@class pdf.runtime.Page
 ---@field id pdf.runtime.PageId # unique id associated with the page.
+---@field title string # title associated with the page.
+---@field section string|nil # name of the section containing the page, if any.
+---@field date pdf.common.Date|nil # date that the page is for, if any.
+---@field side pdf.common.PageSide # side of a two-page spread, where odd-numbered pages are on the right.
 local PdfRuntimePage = {}
 
 ---Pushes a new object onto the page to be rendered during PDF generation.
----@param obj pdf.Object
+---
+---A function can be pushed in place of an object, which is invoked once the
+---script finishes and page references are resolved, returning an object, a
+---list of objects, or nil. The objects take the place of the function:
+---
+---```lua
+---page.push(function(page, info)
+---    local notes = pdf.pages.get(pdf.pages.resolve(notes_ref))
+---    return pdf.object.text({ text = notes.title .. " of " .. info.total, x = 10, y = 10 })
+---end)
+---```
+---@param obj pdf.Object|fun(page:pdf.runtime.Page, info:pdf.runtime.PageDrawInfo):(pdf.Object|pdf.Object[]|nil)
 function PdfRuntimePage.push(obj) end
 
+---Overrides the crop box of the page, which is the region displayed or
+---printed, trimming everything outside of it without moving any objects.
+---Providing nil restores the default of the entire page.
+---@param bounds pdf.common.BoundsLike|nil
+function PdfRuntimePage:set_crop(bounds) end
+
+---Overrides the art box of the page, which is the region containing the
+---meaningful content of the page. Providing nil restores the default of the
+---entire page.
+---@param bounds pdf.common.BoundsLike|nil
+function PdfRuntimePage:set_art(bounds) end
+
+---@class pdf.runtime.PageDrawInfo
+---@field index integer # position of the page within the document, starting at 1.
+---@field total integer # total number of pages in the document.
+
+---Registers a function to draw onto the page once the script finishes and
+---every page exists, so it knows the position of the page and the total
+---number of pages. See `pdf.pages.on_draw` to draw onto every page.
+---@param f fun(page:pdf.runtime.Page, info:pdf.runtime.PageDrawInfo)
+function PdfRuntimePage:on_draw(f) end
+
+---Draws a page imported from an existing PDF beneath everything else on the
+---page, scaled to fit and centered. Providing nil removes the background.
+---@param import pdf.import.Page|nil
+function PdfRuntimePage:set_background(import) end
+
+---Returns the bounds of the page inset by the margins and unsafe area of
+---`pdf.page`, mirroring margins for the side of the page when configured.
+---@return pdf.common.Bounds
+function PdfRuntimePage:content_bounds() end
+
+---@class pdf.runtime.Section
+---@field name string # unique name of the section.
+---@field divider pdf.runtime.PageId|nil # id of the divider page opening the section, if any.
+---@field pages pdf.runtime.PageId[] # ids of pages within the section (including the divider).
+
 -------------------------------------------------------------------------------
 -- OBJECT FUNCTIONS
 -------------------------------------------------------------------------------
 
+---Objects are tables that can be read, changed, and iterated like any other
+---table. Methods of objects, such as `align_to` and `shift_by`, convert the
+---whole object between Lua and Rust on every call and return a new object,
+---which for a group includes every object within it, so chaining several
+---methods on a large group converts it several times.
 ---@class pdf.object
 pdf.object = {}
 
@@ -405,12 +836,81 @@ pdf.object = {}
 ---| pdf.object.Rect
 ---| pdf.object.Shape
 ---| pdf.object.Text
+---| pdf.object.Toc
+
+---@alias pdf.object.BarcodeFormat "code128"|"ean13"|"ean8"
+
+---@class pdf.object.BarcodeLike
+---@field format pdf.object.BarcodeFormat|nil # defaults to "code128"
+---@field data string # printable ASCII for Code 128, or digits for EAN where the check digit can be left out
+---@field bounds pdf.common.BoundsLike # bars fill the bounds, so leave space around them for scanners
+---@field color pdf.common.ColorLike|nil # defaults to the fill color of the page
+---@field depth integer|nil
+---@field link pdf.common.LinkLike|nil
+
+---Creates a group of rects drawing a barcode as vector bars, failing if the
+---data cannot be encoded by the format:
+---
+---```lua
+---page.push(pdf.object.barcode({ data = "INV-0042", bounds = { 10, 10, 60, 25 } }))
+---page.push(pdf.object.barcode({
+---    format = "ean13",
+---    data = "400638133393",
+---    bounds = { 10, 30, 48, 50 },
+---}))
+---```
+---
+---@param tbl pdf.object.BarcodeLike
+---@return pdf.object.Group
+function pdf.object.barcode(tbl) end
+
+---@alias pdf.object.CalloutSide "top"|"right"|"bottom"|"left"
+
+---@class pdf.object.CalloutPointer
+---@field side pdf.object.CalloutSide|nil # defaults to "bottom"
+---@field position number|nil # 0 to 1 along the side from its left or bottom, defaulting to 0.5
+---@field width number|nil # width of the base of the pointer, defaulting to 4mm
+---@field length number|nil # distance to the tip of the pointer, defaulting to 4mm
+
+---@class pdf.object.CalloutLike
+---@field bounds pdf.common.BoundsLike # bounds of the box, which the pointer sticks out of
+---@field text string|nil
+---@field pointer pdf.object.CalloutSide|pdf.object.CalloutPointer|false|nil # false removes the pointer
+---@field fill_color pdf.common.ColorLike|nil # defaults to a pale yellow
+---@field outline_color pdf.common.ColorLike|nil # box is only outlined when set
+---@field outline_thickness number|nil
+---@field corner_radius number|nil # defaults to 2mm
+---@field padding pdf.common.PaddingLike|nil # space around the text, defaulting to 2mm
+---@field font integer|nil
+---@field size number|nil
+---@field text_color pdf.common.ColorLike|nil
+---@field depth integer|nil
+---@field link pdf.common.LinkLike|nil
+
+---Creates a group of objects drawing a sticky note or speech bubble: a rounded
+---box with a pointer sticking out of one side, filled with text wrapped to fit
+---the width of the box:
+---
+---```lua
+---page.push(pdf.object.callout({
+---    bounds = { 20, 40, 80, 60 },
+---    text = "Remember to water the plants",
+---    pointer = { side = "left", position = 0.75 },
+---}))
+---```
+---
+---Lines that do not fit within the height of the box are still drawn below it.
+---
+---@param tbl pdf.object.CalloutLike
+---@return pdf.object.Group
+function pdf.object.callout(tbl) end
 
 ---@class pdf.object.Circle
 ---@field type "circle"
 ---@field center pdf.common.Point
 ---@field radius number
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field fill_color pdf.common.Color|nil
 ---@field outline_color pdf.common.Color|nil
 ---@field outline_thickness number|nil
@@ -420,6 +920,9 @@ pdf.object = {}
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.Link|nil
+---@field shadow pdf.common.Shadow|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 local PdfObjectCircle = {}
 
 ---Aligns the circle to the provided bounds, returning an updated circle.
@@ -428,14 +931,25 @@ local PdfObjectCircle = {}
 ---@return pdf.object.Circle
 function PdfObjectCircle:align_to(bounds, align) end
 
+---Places the circle beside another object or bounds, returning an updated circle.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Circle
+function PdfObjectCircle:place(opts) end
+
 ---Calculates the bounds that fully contains the circle.
 ---@return pdf.common.Bounds
 function PdfObjectCircle:bounds() end
 
+---Returns a deep copy of the circle, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Circle
+function PdfObjectCircle:clone(overrides) end
+
 ---@class pdf.object.CircleLike
 ---@field center pdf.common.PointLike|nil
 ---@field radius number|nil
 ---@field depth integer|nil
+---@field layer pdf.common.LayerLike|nil
 ---@field fill_color pdf.common.ColorLike|nil
 ---@field outline_color pdf.common.ColorLike|nil
 ---@field outline_thickness number|nil
@@ -445,6 +959,9 @@ function PdfObjectCircle:bounds() end
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.LinkLike|nil
+---@field shadow pdf.common.ShadowLike|nil # offset copy drawn beneath the object
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
 ---Creates a new shape object.
 ---
@@ -455,7 +972,16 @@ function pdf.object.circle(tbl) end
 ---@class pdf.object.Group
 ---@field [number] pdf.Object
 ---@field type "group"
+---@field layer string|nil
+---@field fill_color pdf.common.Color|nil
+---@field outline_color pdf.common.Color|nil
+---@field outline_thickness number|nil
+---@field font integer|nil
+---@field size number|nil
 ---@field link pdf.common.Link|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
+---@field xobject integer|nil # form the group is drawn as, set by `pdf.xobject.from_group`
 local PdfObjectGroup = {}
 
 ---Aligns the group to the provided bounds, returning an updated group.
@@ -464,30 +990,125 @@ local PdfObjectGroup = {}
 ---@return pdf.object.Group
 function PdfObjectGroup:align_to(bounds, align) end
 
+---Places the group beside another object or bounds, returning an updated group.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Group
+function PdfObjectGroup:place(opts) end
+
 ---Calculates the bounds that contains the entire set of objects within the group.
 ---@return pdf.common.Bounds
 function PdfObjectGroup:bounds() end
 
+---Moves the objects within the group so the lower-left of the group is at
+---the specified x & y position, returning an updated group.
+---
+---Both the `x` and `y` fields are optional, so you can supply just one to
+---only affect that axis.
+---@param opts? {x?:number, y?:number}
+---@return pdf.object.Group
+function PdfObjectGroup:move_to(opts) end
+
+---Shifts the objects within the group by the specified x & y offset,
+---returning an updated group.
+---
+---Both the `x` and `y` fields are optional, so you can supply just one to
+---only affect that axis.
+---@param opts? {x?:number, y?:number}
+---@return pdf.object.Group
+function PdfObjectGroup:shift_by(opts) end
+
+---Scales the objects within the group by a factor of width & height away from
+---the lower-left of the group, returning an updated group.
+---
+---Circles and the font size of text are resized by the smaller factor, unless
+---`text` is false, which only moves the position of text.
+---
+---Both the `width` and `height` fields are optional, so you can supply just one
+---to only affect that dimension.
+---@param opts? {width?:number, height?:number, text?:boolean}
+---@return pdf.object.Group
+function PdfObjectGroup:scale_by_factor(opts) end
+
+---Rotates the objects within the group counter-clockwise by `deg` degrees
+---around `origin`, defaulting to the center of the group, returning an
+---updated group.
+---
+---Rects, text, and tables of contents stay upright, only moving their position.
+---@param deg number
+---@param origin? pdf.common.PointLike
+---@return pdf.object.Group
+function PdfObjectGroup:rotate(deg, origin) end
+
+---Returns a deep copy of the group, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Group
+function PdfObjectGroup:clone(overrides) end
+
 ---@class pdf.object.GroupLike
 ---@field [number] pdf.Object
+---@field layer pdf.common.LayerLike|nil
+---@field fill_color pdf.common.ColorLike|nil
+---@field outline_color pdf.common.ColorLike|nil
+---@field outline_thickness number|nil
+---@field font integer|nil
+---@field size number|nil
 ---@field link pdf.common.LinkLike|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
 ---Creates a new group object.
 ---
+---Style fields (`fill_color`, `outline_color`, `outline_thickness`, `font`,
+---and `size`) and `layer` are defaults for objects within the group that do
+---not set their own, including objects within nested groups.
+---
 ---@param tbl pdf.object.GroupLike
 ---@return pdf.object.Group
 function pdf.object.group(tbl) end
 
+---@alias pdf.object.IconName
+---| "checkbox"|"checkbox_checked"|"checkbox_crossed"|"check"|"cross"
+---| "plus"|"minus"|"circle"|"dot"|"star"|"star_outline"|"heart"
+---| "arrow_up"|"arrow_down"|"arrow_left"|"arrow_right"
+---| "chevron_up"|"chevron_down"|"chevron_left"|"chevron_right"
+---| "phone"|"mail"|"calendar"|"clock"
+
+---@class pdf.object.IconOpts
+---@field color pdf.common.ColorLike|nil # defaults to the outline & fill colors of the page
+---@field thickness number|nil # thickness of strokes, defaulting to 8% of the size of the icon
+---@field depth integer|nil
+---@field link pdf.common.LinkLike|nil
+
+---Creates a group of objects drawing a builtin icon as vector paths, scaled to
+---fit the largest square centered within `bounds`, without needing an icon
+---font:
+---
+---```lua
+---page.push(pdf.object.icon("checkbox", { 10, 10, 15, 15 }))
+---page.push(pdf.object.icon("star", { 20, 10, 25, 15 }, { color = "#f1c40f" }))
+---```
+---
+---@param name pdf.object.IconName
+---@param bounds pdf.common.BoundsLike
+---@param opts? pdf.object.IconOpts
+---@return pdf.object.Group
+function pdf.object.icon(name, bounds, opts) end
+
 ---@class pdf.object.Line
 ---@field [number] pdf.common.Point
 ---@field type "line"
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field color pdf.common.Color|nil
 ---@field thickness number|nil
 ---@field dash_pattern pdf.common.line.DashPattern|nil
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
+---@field smooth boolean|nil
+---@field simplify number|nil
 ---@field link pdf.common.Link|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 local PdfObjectLine = {}
 
 ---Aligns the line to the provided bounds, returning an updated line.
@@ -496,19 +1117,34 @@ local PdfObjectLine = {}
 ---@return pdf.object.Line
 function PdfObjectLine:align_to(bounds, align) end
 
+---Places the line beside another object or bounds, returning an updated line.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Line
+function PdfObjectLine:place(opts) end
+
 ---Calculates the bounds that contain all points within the lines.
 ---@return pdf.common.Bounds
 function PdfObjectLine:bounds() end
 
+---Returns a deep copy of the line, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Line
+function PdfObjectLine:clone(overrides) end
+
 ---@class pdf.object.LineLike
 ---@field [number] pdf.common.PointLike
 ---@field depth integer|nil
+---@field layer pdf.common.LayerLike|nil
 ---@field color pdf.common.ColorLike|nil
 ---@field thickness number|nil
 ---@field dash_pattern pdf.common.line.DashPatternLike|nil
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
+---@field smooth boolean|nil #if true, draws smooth curves through the points
+---@field simplify number|nil #drops points within this distance (mm) of the line, useful for dense data
 ---@field link pdf.common.LinkLike|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
 ---Creates a new line object.
 ---
@@ -521,6 +1157,7 @@ function pdf.object.line(tbl) end
 ---@field ll pdf.common.Point
 ---@field ur pdf.common.Point
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field fill_color pdf.common.Color|nil
 ---@field outline_color pdf.common.Color|nil
 ---@field outline_thickness number|nil
@@ -530,6 +1167,9 @@ function pdf.object.line(tbl) end
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.Link|nil
+---@field shadow pdf.common.Shadow|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 local PdfObjectRect = {}
 
 ---Aligns the rect to the provided bounds, returning an updated rect.
@@ -538,6 +1178,11 @@ local PdfObjectRect = {}
 ---@return pdf.object.Rect
 function PdfObjectRect:align_to(bounds, align) end
 
+---Places the rect beside another object or bounds, returning an updated rect.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Rect
+function PdfObjectRect:place(opts) end
+
 ---Returns a copy of the rect with new bounds.
 ---@param bounds? pdf.common.BoundsLike
 ---@return pdf.object.Rect
@@ -547,8 +1192,14 @@ function PdfObjectRect:with_bounds(bounds) end
 ---@return pdf.common.Bounds
 function PdfObjectRect:bounds() end
 
+---Returns a deep copy of the rect, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Rect
+function PdfObjectRect:clone(overrides) end
+
 ---@class pdf.object.RectLikeBase
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field fill_color pdf.common.ColorLike|nil
 ---@field outline_color pdf.common.ColorLike|nil
 ---@field outline_thickness number|nil
@@ -558,6 +1209,9 @@ function PdfObjectRect:bounds() end
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.LinkLike|nil
+---@field shadow pdf.common.ShadowLike|nil # offset copy drawn beneath the object
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
 ---@class pdf.object.RectLike1: pdf.object.RectLikeBase
 ---@field ll {x:number, y:number}
@@ -589,6 +1243,7 @@ function pdf.object.rect(tbl) end
 ---@field [number] pdf.common.Point
 ---@field type "shape"
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field fill_color pdf.common.Color|nil
 ---@field outline_color pdf.common.Color|nil
 ---@field outline_thickness number|nil
@@ -598,6 +1253,8 @@ function pdf.object.rect(tbl) end
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.Link|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 local PdfObjectShape = {}
 
 ---Aligns the shape to the provided bounds, returning an updated shape.
@@ -606,13 +1263,34 @@ local PdfObjectShape = {}
 ---@return pdf.object.Shape
 function PdfObjectShape:align_to(bounds, align) end
 
+---Places the shape beside another object or bounds, returning an updated shape.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Shape
+function PdfObjectShape:place(opts) end
+
 ---Calculates the bounds that contain all points within the shape.
 ---@return pdf.common.Bounds
 function PdfObjectShape:bounds() end
 
+---Returns a deep copy of the shape, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Shape
+function PdfObjectShape:clone(overrides) end
+
+---Returns a copy of the shape grown outward by `distance`, shrinking it when
+---`distance` is negative, such as to draw a halo around the shape.
+---
+---Corners are joined with `join`, which defaults to "miter". Miter corners
+---sharper than the miter limit fall back to a bevel like "limit".
+---@param distance number
+---@param join? pdf.common.line.JoinStyle
+---@return pdf.object.Shape
+function PdfObjectShape:offset(distance, join) end
+
 ---@class pdf.object.ShapeLike
 ---@field [number] pdf.common.PointLike
 ---@field depth integer|nil
+---@field layer pdf.common.LayerLike|nil
 ---@field fill_color pdf.common.ColorLike|nil
 ---@field outline_color pdf.common.ColorLike|nil
 ---@field outline_thickness number|nil
@@ -622,12 +1300,38 @@ function PdfObjectShape:bounds() end
 ---@field cap_style pdf.common.line.CapStyle|nil
 ---@field join_style pdf.common.line.JoinStyle|nil
 ---@field link pdf.common.LinkLike|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
----Creates a new shape object.
+---Creates a new shape object when called with a table of points.
+---@class pdf.object.shape
+---@overload fun(tbl:pdf.object.ShapeLike):pdf.object.Shape
+pdf.object.shape = {}
+
+---@class pdf.object.shape.RegularArgs: pdf.object.ShapeLike
+---@field sides integer #number of sides, at least 3
+---@field radius number #distance from the center to each vertex
+---@field center pdf.common.PointLike|nil #defaults to the origin
+---@field rotation number|nil #degrees to rotate counter-clockwise, with the first vertex at the top by default
+
+---Creates a new shape object that is a regular polygon, such as a hexagon.
+---
+---@param tbl pdf.object.shape.RegularArgs
+---@return pdf.object.Shape
+function pdf.object.shape.regular(tbl) end
+
+---@class pdf.object.shape.StarArgs: pdf.object.ShapeLike
+---@field points integer|nil #number of tips, at least 2 (default 5)
+---@field radius number #distance from the center to each tip
+---@field inner_radius number|nil #distance from the center to each inner vertex (default half of radius)
+---@field center pdf.common.PointLike|nil #defaults to the origin
+---@field rotation number|nil #degrees to rotate counter-clockwise, with the first tip at the top by default
+
+---Creates a new shape object that is a star.
 ---
----@param tbl pdf.object.ShapeLike
+---@param tbl pdf.object.shape.StarArgs
 ---@return pdf.object.Shape
-function pdf.object.shape(tbl) end
+function pdf.object.shape.star(tbl) end
 
 ---@class pdf.object.Text
 ---@field type "text"
@@ -635,18 +1339,37 @@ function pdf.object.shape(tbl) end
 ---@field y number
 ---@field text string
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field font integer|nil
 ---@field size number|nil
+---@field direction pdf.common.TextDirection|nil
+---@field max_width number|nil
+---@field overflow pdf.common.TextOverflow|nil
+---@field script pdf.common.TextScript|nil
+---@field baseline_shift number|nil
 ---@field color pdf.common.Color|nil
 ---@field link pdf.common.Link|nil
+---@field link_style pdf.common.LinkStyle|nil
+---@field background pdf.common.Background|nil
+---@field shadow pdf.common.Shadow|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 local PdfObjectText = {}
 
 ---Aligns the text to the provided bounds, returning an updated text.
+---
+---Vertical alignment of "baseline" places the baseline of the text at the
+---bottom of the bounds, keeping text of different sizes lined up.
 ---@param bounds pdf.common.Bounds
 ---@param align pdf.common.Align
 ---@return pdf.object.Text
 function PdfObjectText:align_to(bounds, align) end
 
+---Places the text beside another object or bounds, returning an updated text.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Text
+function PdfObjectText:place(opts) end
+
 ---Calculates the bounds of the text object by using its baseline x & y
 ---coordinates alongside the associated font.
 ---
@@ -656,13 +1379,29 @@ function PdfObjectText:align_to(bounds, align) end
 ---@return pdf.common.Bounds
 function PdfObjectText:bounds() end
 
+---Returns a deep copy of the text, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Text
+function PdfObjectText:clone(overrides) end
+
 ---@class pdf.object.TextLikeBase
 ---@field text string
 ---@field depth integer|nil
+---@field layer string|nil
 ---@field font integer|nil
 ---@field size number|nil
+---@field direction pdf.common.TextDirection|nil
+---@field max_width number|nil
+---@field overflow pdf.common.TextOverflow|nil
+---@field script pdf.common.TextScript|nil
+---@field baseline_shift number|nil
 ---@field color pdf.common.ColorLike|nil
 ---@field link pdf.common.LinkLike|nil
+---@field link_style pdf.common.LinkStyleLike|nil
+---@field background pdf.common.BackgroundLike|nil # box drawn behind the text, like a label chip
+---@field shadow pdf.common.ShadowLike|nil # offset copy drawn beneath the object
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
 
 ---@class pdf.object.TextLike1: pdf.object.TextLikeBase
 ---@field x number
@@ -687,6 +1426,240 @@ function PdfObjectText:bounds() end
 ---@return pdf.object.Text
 function pdf.object.text(tbl) end
 
+---@class pdf.object.Toc
+---@field type "toc"
+---@field ll pdf.common.Point
+---@field ur pdf.common.Point
+---@field depth integer|nil
+---@field layer string|nil
+---@field font integer|nil
+---@field size number|nil
+---@field color pdf.common.Color|nil
+---@field leader string|nil # text repeated between titles and page numbers (default ".")
+---@field indent number|nil # indent of each level beyond the first (default 5)
+---@field line_height number|nil # distance between baselines of entries (default 1.5x font size)
+---@field max_level integer|nil # deepest level of entries to list (default is every level)
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
+local PdfObjectToc = {}
+
+---Aligns the table of contents to the provided bounds, returning an updated table of contents.
+---@param bounds pdf.common.Bounds
+---@param align pdf.common.Align
+---@return pdf.object.Toc
+function PdfObjectToc:align_to(bounds, align) end
+
+---Places the table of contents beside another object or bounds, returning an updated table of contents.
+---@param opts pdf.common.PlaceArgs
+---@return pdf.object.Toc
+function PdfObjectToc:place(opts) end
+
+---Returns the bounds of the table of contents.
+---@return pdf.common.Bounds
+function PdfObjectToc:bounds() end
+
+---Returns a deep copy of the table of contents, with fields of `overrides` replacing its own.
+---@param overrides? table
+---@return pdf.object.Toc
+function PdfObjectToc:clone(overrides) end
+
+---@class pdf.object.TocLikeBase
+---@field depth integer|nil
+---@field layer string|nil
+---@field font integer|nil
+---@field size number|nil
+---@field color pdf.common.ColorLike|nil
+---@field leader string|nil
+---@field indent number|nil
+---@field line_height number|nil
+---@field max_level integer|nil
+---@field hidden boolean|nil # if true, the object is neither drawn nor linked
+---@field tag string|nil
+
+---@class pdf.object.TocLike1: pdf.object.TocLikeBase
+---@field ll {x:number, y:number}
+---@field ur {x:number, y:number}
+
+---@class pdf.object.TocLike2: pdf.object.TocLikeBase
+---@field [1] {[1]:number, [2]:number}
+---@field [2] {[1]:number, [2]:number}
+
+---@class pdf.object.TocLike3: pdf.object.TocLikeBase
+---@field [1] number
+---@field [2] number
+---@field [3] number
+---@field [4] number
+
+---@alias pdf.object.TocLike
+---| pdf.object.TocLike1
+---| pdf.object.TocLike2
+---| pdf.object.TocLike3
+
+---Creates a new table of contents object, which lists the entries added via
+---`pdf.toc.add` from the top of its bounds down, each followed by leaders and
+---the number of its page, linked to the page.
+---
+---Entries are laid out once every page exists, so the table of contents can
+---be pushed onto a page before the pages it lists are created.
+---
+---@param tbl pdf.object.TocLike
+---@return pdf.object.Toc
+function pdf.object.toc(tbl) end
+
+-------------------------------------------------------------------------------
+-- DEFINE FUNCTIONS
+-------------------------------------------------------------------------------
+
+---Registers a reusable object named `name` that is generated by `f`,
+---returning a function to create new instances of the object, which is also
+---available as `pdf.object[name]`.
+---
+---Objects are cached by their arguments, so calling with the same arguments
+---will reuse the previously-generated object instead of invoking `f` again.
+---Arguments containing functions or other non-comparable values are never
+---cached.
+---
+---Fails if `pdf.object[name]` already exists.
+---@generic T
+---@param name string
+---@param f fun(args:T):pdf.Object
+---@return fun(args:T):pdf.Object
+function pdf.define(name, f) end
+
+---Returns `obj`, hidden unless `condition` is true, so that objects can be
+---included conditionally within lists such as groups.
+---@generic T: pdf.Object
+---@param condition any
+---@param obj T
+---@return T
+function pdf.when(condition, obj) end
+
+-------------------------------------------------------------------------------
+-- ASSERT FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.assert
+pdf.assert = {}
+
+---Fails if the document does not have exactly `count` pages.
+---@param count integer
+function pdf.assert.page_count(count) end
+
+---Fails if any link within the document targets a page that does not exist.
+function pdf.assert.link_targets_valid() end
+
+---Fails if any two objects with `tag` overlap on the same page.
+---
+---Objects within groups are checked, unless the group itself has the tag,
+---in which case the group is checked as a whole.
+---@param tag string
+function pdf.assert.no_overlaps(tag) end
+
+-------------------------------------------------------------------------------
+-- ATTACHMENTS FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.attachments
+pdf.attachments = {}
+
+---@class pdf.attachments.AddArgs
+---@field path string # path to the file to embed
+---@field name? string # name of the file within the PDF (default is the file name of path)
+---@field description? string # description of the file shown by viewers
+---@field mime? string # MIME type of the file, e.g. "text/csv"
+
+---Embeds the file at `tbl.path` within the PDF as an attachment, which
+---viewers make available to extract. The file is read immediately.
+---
+---Like `pdf.utils.read_json`, only files within allowed directories can be
+---read. Fails if an attachment with the same name already exists.
+---@param tbl pdf.attachments.AddArgs
+function pdf.attachments.add(tbl) end
+
+---Returns a list of the names of all attachments in the order they were added.
+---@return string[]
+function pdf.attachments.names() end
+
+-------------------------------------------------------------------------------
+-- CALENDAR FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.calendar
+pdf.calendar = {}
+
+---@class pdf.calendar.Event
+---@field date pdf.common.Date # date when the event occurs
+---@field summary string # short description of the event
+---@field all_day boolean # true if the event spans the entire day rather than a specific time
+
+---@class pdf.calendar.IcsOpts
+---@field from? pdf.common.DateLike # only include events on or after this date
+---@field to? pdf.common.DateLike # only include events on or before this date
+
+---Loads events from an iCalendar (`.ics`) file at `path`, sorted by date.
+---The file must be within a directory the script can read (see `pdf.utils.read_json`).
+---
+---Recurring events (e.g. yearly birthdays) are included once per occurrence,
+---which requires `opts.to` for events that repeat forever. Only the frequency,
+---interval, count, and end date of a recurrence are supported.
+---
+---Dates of timed events are taken as written in the file, without converting
+---between timezones.
+---@param path string
+---@param opts? pdf.calendar.IcsOpts
+---@return pdf.calendar.Event[]
+function pdf.calendar.load_ics(path, opts) end
+
+---Like `pdf.calendar.load_ics`, but parses the iCalendar `text` directly.
+---@param text string
+---@param opts? pdf.calendar.IcsOpts
+---@return pdf.calendar.Event[]
+function pdf.calendar.parse_ics(text, opts) end
+
+-------------------------------------------------------------------------------
+-- DEVICE FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.device
+pdf.device = {}
+
+---@class pdf.device.Profile
+---@field name string # e.g. "supernote"
+---@field width number # width of the screen in millimeters
+---@field height number # height of the screen in millimeters
+---@field dpi number
+---@field unsafe_area pdf.common.Padding # area in millimeters covered by toolbars
+local PdfDeviceProfile = {}
+
+---Returns the bounds covering the entire screen of the device.
+---@return pdf.common.Bounds
+function PdfDeviceProfile:bounds() end
+
+---Returns the bounds covering the screen of the device, excluding the area
+---covered by its toolbars.
+---@return pdf.common.Bounds
+function PdfDeviceProfile:safe_bounds() end
+
+---Returns the profile of the device named `name` (ignoring case), failing if
+---the device is not supported.
+---
+---Supported devices are supernote, supernote-a5x, supernote-a5x2,
+---remarkable2, and boox-note-air.
+---@param name string
+---@return pdf.device.Profile
+function pdf.device.get(name) end
+
+---Configures `pdf.page` to match the screen of the device named `name`,
+---setting its DPI, width, height, and unsafe area, returning the profile of
+---the device.
+---@param name string
+---@return pdf.device.Profile
+function pdf.device.apply(name) end
+
+---Returns the profiles of all supported devices.
+---@return pdf.device.Profile[]
+function pdf.device.list() end
+
 -------------------------------------------------------------------------------
 -- FONT FUNCTIONS
 -------------------------------------------------------------------------------
@@ -696,10 +1669,35 @@ pdf.font = {}
 
 ---Adds a new font into the runtime, returning the id associated with the font.
 ---
----If the font has already been added, this returns the cached id.
+---If the font has already been added, this returns the cached id. When a
+---`name` is given, the font can later be retrieved through `pdf.font.get()`.
 ---@param path string
+---@param name string|nil
 ---@return number id
-function pdf.font.add(path) end
+function pdf.font.add(path, name) end
+
+---Retrieves or sets the ordered list of fonts used for characters missing
+---from both the font of the text and the fallback font, such as checkboxes,
+---arrows, or emoji that would otherwise render as boxes:
+---
+---```lua
+---pdf.font.fallbacks({
+---    pdf.font.add("fonts/NotoSansSymbols2.ttf"),
+---    pdf.font.add("fonts/NotoEmoji.ttf"),
+---})
+---```
+---
+---Each character uses the first font that has a glyph for it.
+---@param ids number[]
+---@overload fun():number[]
+function pdf.font.fallbacks(ids) end
+
+---Retrieves the id of the font registered under `name`, either through
+---`pdf.font.add()` or before the script runs through `--font NAME=PATH`,
+---`--font-dir`, or the `[fonts]` table of a makepdf.toml.
+---@param name string
+---@return number|nil id
+function pdf.font.get(name) end
 
 ---Retrieves the id or sets the id of the fallback font.
 ---@param id number
@@ -717,10 +1715,104 @@ function pdf.font.ids() end
 ---@return string|nil path
 function pdf.font.path(id) end
 
+-------------------------------------------------------------------------------
+-- IMPORT FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.import
+pdf.import = {}
+
+---@class pdf.import.Page
+---@field id integer # unique id associated with the imported page.
+---@field path string # path to the PDF containing the page.
+---@field page integer # number of the page (starting at 1) within the PDF.
+---@field width number # width of the page in millimeters.
+---@field height number # height of the page in millimeters.
+
+---Imports a page of an existing PDF, such as a template designed in another
+---tool, which is stored once within the PDF and drawn as the background of
+---pages via `page:set_background()`. The PDF must be within the directory of
+---the script or the current working directory.
+---@param path string
+---@param number integer|nil # page to import, defaulting to 1
+---@return pdf.import.Page
+function pdf.import.page(path, number) end
+
+-------------------------------------------------------------------------------
+-- LAYOUT FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.layout
+pdf.layout = {}
+
+---Size of an item along the direction of a layout, which is one of
+---
+---* `number` - fixed size in millimeters
+---* `"25%"` - percentage of the container
+---* `{ flex = 2 }` - weighted share of the space left over (default `{ flex = 1 }`)
+---@alias pdf.layout.Size number|string|{flex:number}
+
+---Item within a layout, which is an object, a function creating an object
+---from the bounds of its cell, or either of those wrapped in a table
+---alongside a size and alignment.
+---
+---Rects without an alignment fill their cell, while other objects are
+---aligned within their cell.
+---@alias pdf.layout.Item
+---| pdf.Object
+---| fun(bounds:pdf.common.Bounds):pdf.Object
+---| {[1]:pdf.Object|fun(bounds:pdf.common.Bounds):pdf.Object, size:pdf.layout.Size|nil, align:pdf.common.Align|nil}
+
+---@class pdf.layout.ContainerArgs
+---@field [number] pdf.layout.Item
+---@field bounds pdf.common.BoundsLike # bounds of the container
+---@field padding? pdf.common.PaddingLike # padding applied to the bounds
+---@field gap? number # space between items (default 0)
+---@field align? pdf.common.Align # default alignment of items (default top-left)
+
+---@class pdf.layout.GridArgs: pdf.layout.ContainerArgs
+---@field columns integer|pdf.layout.Size[] # number of equal columns, or size of each
+---@field rows? integer|pdf.layout.Size[] # number of equal rows, or size of each (default fits items)
+
+---Positions items from top to bottom, returning a group of positioned copies.
+---@param tbl pdf.layout.ContainerArgs
+---@return pdf.object.Group
+function pdf.layout.vstack(tbl) end
+
+---Positions items from left to right, returning a group of positioned copies.
+---@param tbl pdf.layout.ContainerArgs
+---@return pdf.object.Group
+function pdf.layout.hstack(tbl) end
+
+---Positions items in rows from left to right, top to bottom, returning a
+---group of positioned copies. Sizes of items are ignored.
+---@param tbl pdf.layout.GridArgs
+---@return pdf.object.Group
+function pdf.layout.grid(tbl) end
+
+---@class pdf.layout.FlowArgs
+---@field [number] pdf.Object|{[1]:pdf.Object, keep_with_next:boolean|nil}
+---@field title? string # title of each page created (default "")
+---@field page? pdf.runtime.PageId # page to start on (default creates a new page)
+---@field margin? pdf.common.PaddingLike # padding applied to the bounds of each page
+---@field gap? number # space between items (default 0)
+
+---Places items one after another down pages, continuing onto a new page
+---whenever the next item does not fit, returning the ids of the pages used.
+---
+---Items are never split across pages. An item with `keep_with_next` moves
+---onto a new page alongside the item after it, such as a heading with its
+---paragraph.
+---@param tbl pdf.layout.FlowArgs
+---@return pdf.runtime.PageId[]
+function pdf.layout.flow(tbl) end
+
 -------------------------------------------------------------------------------
 -- LOG FUNCTIONS
 -------------------------------------------------------------------------------
 
+---Logs messages to stdout and the log file of the `makepdf` process, each
+---prefixed with the script name and line number of the caller.
 ---@class pdf.log
 pdf.log = {}
 
@@ -751,20 +1843,288 @@ function pdf.log.trace(...) end
 ---@class pdf.pages
 pdf.pages = {}
 
+---@class pdf.common.NewPage
+---@field title string
+---@field section? string # section to add the page to, created if missing without becoming active
+---@field date? pdf.common.DateLike # date that the page is for, used to find it later
+
 ---Creates a new, blank page, adding it to the end of the pages list.
----@param title string
+---
+---Pages created from a table can name their section and date, which takes
+---the place of the active section:
+---
+---```lua
+---local jan = pdf.pages.create({ title = "January", section = "monthly" })
+---pdf.pages.create({ title = "Jan 1", section = "daily", date = "2025-01-01" })
+---```
+---@param title string|pdf.common.NewPage
 ---@return pdf.runtime.PageId
 function pdf.pages.create(title) end
 
+---Creates a new, blank page like `pdf.pages.create`, placing it directly after
+---the page with id `after`. Fails if that page does not exist.
+---@param after pdf.runtime.PageId
+---@param title string|pdf.common.NewPage
+---@return pdf.runtime.PageId
+function pdf.pages.insert_after(after, title) end
+
+---Moves a page to `position` within the pages list, starting at 1, such as to
+---generate an index last but place it first:
+---
+---```lua
+---local index = pdf.pages.create("Index")
+---pdf.pages.move(index, 1)
+---```
+---
+---Links and outline entries target pages by id, so they follow the page. The
+---side of every page is updated, but objects already pushed onto pages are
+---not laid out again.
+---@param id pdf.runtime.PageId
+---@param position integer
+function pdf.pages.move(id, position) end
+
+---Removes a page from the pages list and its section, returning true if the
+---page existed. Links to the page go nowhere.
+---@param id pdf.runtime.PageId
+---@return boolean
+function pdf.pages.remove(id) end
+
 ---Retrieves a page with the specified id from the runtime collection.
 ---@param id pdf.runtime.PageId
 ---@return pdf.runtime.Page|nil
 function pdf.pages.get(id) end
 
+---Returns the number of pages created so far.
+---@return integer
+function pdf.pages.count() end
+
+---Registers a function to draw onto every page once the script finishes and
+---every page exists, such as a "Page X of Y" footer, which is invoked with
+---each page in order alongside its position and the total number of pages:
+---
+---```lua
+---pdf.pages.on_draw(function(page, info)
+---    page.push(pdf.object.text({
+---        text = "Page " .. info.index .. " of " .. info.total,
+---        x = 10,
+---        y = 10,
+---    }))
+---end)
+---```
+---
+---Pages created by these functions are not visited.
+---@param f fun(page:pdf.runtime.Page, info:pdf.runtime.PageDrawInfo)
+function pdf.pages.on_draw(f) end
+
 ---Returns a list of page ids in the order they will show up in the PDF document.
 ---@return pdf.runtime.PageId[]
 function pdf.pages.ids() end
 
+---@class pdf.common.PageQuery
+---@field title? string
+---@field section? string
+---@field date? pdf.common.DateLike
+
+---Returns the ids of pages matching every field of `query`, in the order they
+---will show up in the PDF document, such as to link a monthly page to its
+---days without keeping track of page ids:
+---
+---```lua
+---local day = pdf.pages.find({ section = "daily", date = "2025-01-01" })[1]
+---```
+---@param query? pdf.common.PageQuery
+---@return pdf.runtime.PageId[]
+function pdf.pages.find(query) end
+
+---References a page that may not exist yet, returning an id that links can
+---target in place of the id of the page. Once the script finishes, the
+---reference resolves to the first page, in order, that matches `predicate`,
+---which is either a query or a function invoked with each page:
+---
+---```lua
+---local july = pdf.pages.ref({ title = "July" })
+---page.push(pdf.object.text({ text = "July", link = { type = "goto", page = july } }))
+---```
+---
+---Links to references that do not match any page go nowhere.
+---@param predicate pdf.common.PageQuery|fun(page:pdf.runtime.Page):boolean
+---@return pdf.runtime.PageId
+function pdf.pages.ref(predicate) end
+
+---Returns the id of the page that a reference from `pdf.pages.ref` resolved
+---to, which is nil until the script finishes or if no page matched. Ids that
+---are not references are returned as they are.
+---@param id pdf.runtime.PageId
+---@return pdf.runtime.PageId|nil
+function pdf.pages.resolve(id) end
+
+---@alias pdf.common.PageLabelStyle "decimal"|"roman"|"upper_roman"|"alpha"|"upper_alpha"|"none"
+
+---@class pdf.common.PageLabel
+---@field start integer # number of the first page (starting at 1) of the range
+---@field style? pdf.common.PageLabelStyle # style of page numbers (default "decimal")
+---@field prefix? string # text placed before each page number, e.g. "A-"
+---@field first? integer # number of the first page of the range (default 1)
+
+---Replaces the labels that PDF viewers show in place of page numbers, where
+---each label applies to the pages from its start until the next label, such
+---as numbering front matter i, ii, iii and the rest of the document 1..N.
+---
+---Pages before the first label are numbered 1..N. Fails if a label starts
+---before page 1 or more than one label starts at the same page.
+---@param labels pdf.common.PageLabel[]
+function pdf.pages.set_labels(labels) end
+
+---Returns the labels of pages set via `pdf.pages.set_labels`.
+---@return pdf.common.PageLabel[]
+function pdf.pages.labels() end
+
+---@class pdf.common.NavTab
+---@field label string
+---@field page pdf.runtime.PageId # page that the tab goes to
+---@field pages? pdf.runtime.PageId[] # pages where the tab is highlighted, in addition to `page`
+
+---@class pdf.common.NavTabs
+---@field tabs? pdf.common.NavTab[] # defaults to one tab for each section, going to its first page
+---@field pages? pdf.runtime.PageId[] # pages to draw the tabs onto, defaulting to every page
+---@field edge? "top"|"right"|"bottom"|"left" # edge of the page to draw along (default "right")
+---@field width? number # distance that tabs extend from the edge (default 8mm)
+---@field gap? number # space between tabs (default 1mm)
+---@field margin? number # space before the first and after the last tab (default 10mm)
+---@field fill_color? pdf.common.ColorLike # defaults to light grey
+---@field active_fill_color? pdf.common.ColorLike # color of the current tab, defaulting to grey
+---@field text_color? pdf.common.ColorLike
+---@field active_text_color? pdf.common.ColorLike
+---@field font? integer
+---@field size? number
+---@field depth? integer
+
+---Draws a strip of tabs along an edge of pages, each linking to a page, such
+---as the month tabs of a planner. Tabs split the edge evenly, with labels
+---centered and shrunk to fit, and the tab of the current page is highlighted.
+---
+---Tabs are drawn onto pages that exist when this is called, so call it once
+---every page has been created:
+---
+---```lua
+---for _, month in ipairs({ "Jan", "Feb", "Mar" }) do
+---    pdf.sections.begin(month)
+---    pdf.pages.create(month)
+---end
+---pdf.pages.nav_tabs({ edge = "right", active_fill_color = "#3498db" })
+---```
+---@param opts? pdf.common.NavTabs
+function pdf.pages.nav_tabs(opts) end
+
+-------------------------------------------------------------------------------
+-- SECTIONS FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.sections
+pdf.sections = {}
+
+---@class pdf.sections.BeginOpts
+---@field title? string # title of the divider page (default is the section name)
+---@field divider? fun(page:pdf.runtime.Page, section:pdf.runtime.Section) # inserts a divider page drawn by this function
+---@field header? fun(page:pdf.runtime.Page, section:pdf.runtime.Section) # draws a running header on each new page of the section
+
+---Begins a new section named `name`, which contains every page created
+---afterwards until another section begins or `pdf.sections.finish` is called.
+---
+---When `opts.divider` is provided, a divider page is immediately inserted to
+---open the section and passed to the function to draw. When `opts.header` is
+---provided, it is invoked with every other page created within the section.
+---
+---Each section is added as a bookmark to the PDF outline, navigating to its
+---first page. Fails if a section with the same name already exists.
+---@param name string
+---@param opts? pdf.sections.BeginOpts
+---@return pdf.runtime.Section
+function pdf.sections.begin(name, opts) end
+
+---Ends the active section, so that new pages do not belong to any section.
+function pdf.sections.finish() end
+
+---Retrieves the active section, if any.
+---@return pdf.runtime.Section|nil
+function pdf.sections.current() end
+
+---Retrieves the section with the specified name.
+---@param name string
+---@return pdf.runtime.Section|nil
+function pdf.sections.get(name) end
+
+---Returns a list of all sections in the order they began.
+---@return pdf.runtime.Section[]
+function pdf.sections.list() end
+
+-------------------------------------------------------------------------------
+-- TEST FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.test
+pdf.test = {}
+
+---Groups tests under `name`, which prefixes the names of the tests registered
+---within `f`. The function is called immediately.
+---@param name string
+---@param f fun()
+function pdf.test.describe(name, f) end
+
+---Registers a test named `name`, which is only run (once the script finishes)
+---when testing the script with `makepdf test`.
+---@param name string
+---@param f fun()
+function pdf.test.it(name, f) end
+
+---Asserts that two values are deeply equal like `pdf.utils.assert_deep_equal`.
+---@param a any
+---@param b any
+---@param opts? {ignore_metatable:boolean|nil}
+function pdf.test.assert_deep_equal(a, b, opts) end
+
+---Asserts that two values are not deeply equal like
+---`pdf.utils.assert_not_deep_equal`.
+---@param a any
+---@param b any
+---@param opts? {ignore_metatable:boolean|nil}
+function pdf.test.assert_not_deep_equal(a, b, opts) end
+
+---Asserts that `inner` is entirely within `outer`, where each is either an
+---object or bounds.
+---@param inner pdf.Object|pdf.common.BoundsLike
+---@param outer pdf.Object|pdf.common.BoundsLike
+function pdf.test.assert_bounds_within(inner, outer) end
+
+---Asserts that no two of `objects` overlap, where each is either an object or
+---bounds. Objects that only touch do not overlap.
+---@param objects (pdf.Object|pdf.common.BoundsLike)[]
+function pdf.test.assert_no_overlap(objects) end
+
+-------------------------------------------------------------------------------
+-- TOC FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.toc
+pdf.toc = {}
+
+---@class pdf.toc.Entry
+---@field title string
+---@field page pdf.runtime.PageId
+---@field level integer # starting at 1, where higher levels nest beneath lower ones
+
+---Adds an entry titled `title` for the page with id `page` to the table of
+---contents drawn by `pdf.object.toc`, nested at `level` (default 1).
+---@param title string
+---@param page pdf.runtime.PageId
+---@param level? integer
+function pdf.toc.add(title, page, level) end
+
+---Returns a list of all entries of the table of contents in the order they
+---were added.
+---@return pdf.toc.Entry[]
+function pdf.toc.list() end
+
 -------------------------------------------------------------------------------
 -- UTILITY FUNCTIONS
 -------------------------------------------------------------------------------
@@ -855,6 +2215,37 @@ function pdf.utils.starts_with(s, prefix) end
 ---@return boolean
 function pdf.utils.ends_with(s, prefix) end
 
+---Reads the JSON file at `path`, returning its contents as Lua values.
+---Objects and arrays become tables, and null becomes nil.
+---
+---Only files within the current working directory or the directory of the
+---script (including subdirectories) can be read.
+---@param path string
+---@return any
+function pdf.utils.read_json(path) end
+
+---Converts `value` into a JSON string. Tables with keys `1..n` become arrays,
+---other tables become objects, and functions are skipped.
+---@param value any
+---@param opts? {pretty:boolean|nil}
+---@return string
+function pdf.utils.to_json(value, opts) end
+
+---@class pdf.utils.CsvOpts
+---@field header? boolean # if true (default), first row names the fields of each remaining row
+---@field delimiter? string # single character separating fields (default ",")
+
+---Reads the CSV file at `path`, returning a list of rows with each field as a string.
+---
+---When `opts.header` is true (default), each row is a table keyed by the
+---names from the first row; otherwise, each row is a list of fields.
+---
+---Like `pdf.utils.read_json`, only files within allowed directories can be read.
+---@param path string
+---@param opts? pdf.utils.CsvOpts
+---@return table[]
+function pdf.utils.read_csv(path, opts) end
+
 ---Converts millimeters to points (approximate).
 ---@param mm number
 ---@return number
@@ -864,3 +2255,70 @@ function pdf.utils.mm_to_pt(mm) end
 ---@param pt number
 ---@return number
 function pdf.utils.pt_to_mm(pt) end
+
+---Converts pixels to millimeters using the DPI of the page (`pdf.page.dpi`).
+---@param px number
+---@return number
+function pdf.utils.px_to_mm(px) end
+
+-------------------------------------------------------------------------------
+-- WATERMARK FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.watermark
+pdf.watermark = {}
+
+---Pages that a watermark is drawn onto, which is one of
+---
+---* `"all"` - every page
+---* `"odd"` - pages with odd numbers, on the right of a two-page spread
+---* `"even"` - pages with even numbers, on the left of a two-page spread
+---* `integer[]` - specific pages by number, starting at 1
+---@alias pdf.watermark.Pages "all"|"odd"|"even"|integer[]
+
+---Text or PNG image centered on each page, such as a "DRAFT" marking or a
+---logo, where an image is drawn instead of text when both are given.
+---@class pdf.watermark.Watermark
+---@field text? string
+---@field image? string # path to a PNG within the directory of the script or the current working directory
+---@field font? integer # font of the text, defaulting to the page font
+---@field size? number # size of the text (default 72)
+---@field color? pdf.common.ColorLike # color of the text, defaulting to the page fill color
+---@field width? number # width of the image in millimeters, defaulting to half the page width
+---@field angle? number # degrees to rotate counter-clockwise around the center of the page (default 0)
+---@field opacity? number # between 0 (transparent) and 1 (opaque) (default 0.15)
+---@field pages? pdf.watermark.Pages # pages to draw onto (default "all")
+---@field above? boolean # if true, drawn above everything else on the page rather than beneath it
+
+---Replaces all watermarks with `watermark`, drawn while building pages:
+---
+---```lua
+---pdf.watermark.set({ text = "DRAFT", angle = 45, opacity = 0.15, pages = "all" })
+---```
+---@param watermark pdf.watermark.Watermark
+function pdf.watermark.set(watermark) end
+
+---Adds `watermark` alongside any existing watermarks, which are drawn in the
+---order they were added.
+---@param watermark pdf.watermark.Watermark
+function pdf.watermark.add(watermark) end
+
+---Removes all watermarks.
+function pdf.watermark.clear() end
+
+-------------------------------------------------------------------------------
+-- XOBJECT FUNCTIONS
+-------------------------------------------------------------------------------
+
+---@class pdf.xobject
+pdf.xobject = {}
+
+---Registers `group` as a form XObject, which is stored once within the PDF no
+---matter how many pages it is pushed onto, returning a copy of the group that
+---is drawn as the form. Useful for headers and grids repeated across pages.
+---
+---The returned group can be moved (e.g. with `align_to`), but other changes to
+---its objects are not drawn.
+---@param group pdf.object.Group
+---@return pdf.object.Group
+function pdf.xobject.from_group(group) end
@@ -348,6 +348,389 @@ function pdf.object.calendar(tbl)
     return pdf.object.group(objects)
 end
 
+---@class pdf.object.HabitTrackerArgs
+---@field bounds pdf.common.Bounds
+---@field habits string[] #names of habits, one per row
+---@field from pdf.common.DateLike #first date (inclusive) to track
+---@field to pdf.common.DateLike #last date (inclusive) to track
+---@field label_width? number #width of the column containing habit names, defaulting to a quarter of the bounds
+---@field box_scale? number #size of each checkbox relative to its cell, defaulting to 0.6
+---@field text_color? pdf.common.ColorLike
+---@field text_size? number
+---@field outline_color? pdf.common.ColorLike
+---@field weekend_color? pdf.common.ColorLike #when provided, shades columns that fall on a weekend
+---@field link? fun(date:pdf.common.Date):pdf.common.LinkLike|nil #link to associate with each date's header
+
+---Creates a habit tracker: a labeled grid of checkboxes with one row per habit
+---and one column per date from `from` to `to`, fitting into `bounds`.
+---@param tbl pdf.object.HabitTrackerArgs
+---@return pdf.object.Group
+function pdf.object.habit_tracker(tbl)
+    local objects = {}
+    local bounds = pdf.utils.bounds(tbl.bounds)
+    local habits = tbl.habits
+    local from = pdf.utils.date(tbl.from)
+    local to = pdf.utils.date(tbl.to)
+    local box_scale = tbl.box_scale or 0.6
+
+    assert(from:days_between(to) >= 0, "habit tracker requires from to be no later than to")
+
+    -- Collect each date we are tracking, which maps to a column
+    ---@type pdf.common.Date[]
+    local dates = {}
+    for date in from:upto(to) do
+        table.insert(dates, date)
+    end
+
+    -- Split the bounds into a column of labels and a grid of checkboxes
+    local label_width = tbl.label_width or (bounds:width() / 4)
+    local grid = pdf.utils.grid({
+        bounds = pdf.utils.bounds({
+            ll = { x = bounds.ll.x + label_width, y = bounds.ll.y },
+            ur = bounds.ur,
+        }),
+        rows = #habits + 1,
+        columns = #dates,
+    })
+
+    for col, date in ipairs(dates) do
+        -- Shade the entire column, header included, when it is a weekend
+        if tbl.weekend_color and date.weekday:number_from_monday() >= 6 then
+            local column = grid.cell({ row = 1, col = col, height = #habits + 1 })
+            table.insert(objects, pdf.object.rect({
+                ll = column.ll,
+                ur = column.ur,
+                fill_color = tbl.weekend_color,
+                mode = "fill",
+            }))
+        end
+
+        -- Label the column with the day of the month, linking if requested
+        local header = grid.cell({ row = 1, col = col })
+        table.insert(objects, pdf.object.rect_text({
+            rect = {
+                ll = header.ll,
+                ur = header.ur,
+                outline_color = tbl.outline_color,
+                mode = "stroke",
+            },
+            text = {
+                text = tostring(date.day),
+                color = tbl.text_color,
+                size = tbl.text_size,
+            },
+            link = tbl.link and tbl.link(date) or nil,
+        }))
+
+        -- Place a checkbox in the middle of each habit's cell for the date
+        for row = 1, #habits do
+            local cell = grid.cell({ row = row + 1, col = col })
+            local size = math.min(cell:width(), cell:height()) * box_scale
+            local box = pdf.utils.bounds({ 0, 0, size, size })
+                :align_to(cell, { v = "middle", h = "middle" })
+            table.insert(objects, pdf.object.rect({
+                ll = box.ll,
+                ur = box.ur,
+                outline_color = tbl.outline_color,
+                mode = "stroke",
+            }))
+        end
+    end
+
+    -- Label each row with the name of the habit, aligned to the left
+    for row, habit in ipairs(habits) do
+        local cell = grid.cell({ row = row + 1, col = 1 })
+        local label = pdf.utils.bounds({
+            ll = { x = bounds.ll.x, y = cell.ll.y },
+            ur = { x = bounds.ll.x + label_width, y = cell.ur.y },
+        })
+        table.insert(objects, pdf.object.text({
+            text = habit,
+            color = tbl.text_color,
+            size = tbl.text_size,
+        }):align_to(label, { v = "middle", h = "left" }))
+    end
+
+    return pdf.object.group(objects)
+end
+
+---@class pdf.object.ChartArgs
+---@field type "bar"|"line"|"pie"
+---@field bounds pdf.common.BoundsLike
+---@field data (number|{label?:string, value:number})[] #values to chart in order, optionally labeled
+---@field colors? pdf.common.ColorLike[] #colors of bars, lines, and slices, defaulting to `pdf.theme.chart` when defined
+---@field max? number #largest value of the value axis, defaulting to the largest value within the data
+---@field ticks? integer #number of ticks along the value axis, defaulting to 4
+---@field bar_width? number #width of each bar relative to its space, defaulting to 0.6
+---@field thickness? number #thickness of the line within a line chart
+---@field smooth? boolean #if true, draws a smooth line within a line chart
+---@field axis_color? pdf.common.ColorLike
+---@field text_color? pdf.common.ColorLike
+---@field text_size? number
+
+---Returns points along the arc of a circle at `center` with `radius`, starting
+---at angle `start` (radians, counter-clockwise from the right) and sweeping
+---by `sweep` radians (negative for clockwise), with a point every five degrees.
+---@param center pdf.common.Point
+---@param radius number
+---@param start number
+---@param sweep number
+---@return pdf.common.Point[]
+local function arc_points(center, radius, start, sweep)
+    local points = {}
+    local steps = math.max(2, math.ceil(math.abs(sweep) / (math.pi / 36)))
+    for step = 0, steps do
+        local a = start + sweep * step / steps
+        table.insert(points, {
+            x = center.x + radius * math.cos(a),
+            y = center.y + radius * math.sin(a),
+        })
+    end
+    return points
+end
+
+---Default colors of a chart when neither the chart nor theme provide them.
+local CHART_COLORS = { "steelblue", "darkorange", "seagreen", "firebrick", "slateblue", "goldenrod" }
+
+---Creates a bar, line, or pie chart of `data` that fits into `bounds`.
+---
+---Bar and line charts include axes with ticks labeling the values alongside
+---the labels of the data, while pie charts label each slice.
+---@param tbl pdf.object.ChartArgs
+---@return pdf.object.Group
+function pdf.object.chart(tbl)
+    local objects = {}
+    local bounds = pdf.utils.bounds(tbl.bounds)
+    local kind = tbl.type or "bar"
+
+    assert(kind == "bar" or kind == "line" or kind == "pie", "unsupported chart type: " .. tostring(kind))
+
+    -- Split the data into values and their labels
+    ---@type number[]
+    local values = {}
+    ---@type string[]
+    local labels = {}
+    for i, item in ipairs(tbl.data or {}) do
+        if type(item) == "number" then
+            values[i] = item
+            labels[i] = ""
+        else
+            values[i] = item.value
+            labels[i] = item.label or ""
+        end
+    end
+
+    local colors = tbl.colors or (pdf.theme and pdf.theme.chart) or CHART_COLORS
+    local function color_at(i)
+        return colors[((i - 1) % #colors) + 1]
+    end
+
+    local function text(s)
+        return pdf.object.text({ text = s, size = tbl.text_size, color = tbl.text_color })
+    end
+
+    -- Pie charts are slices of a circle starting at the top and going clockwise
+    if kind == "pie" then
+        local total = 0
+        for _, value in ipairs(values) do
+            total = total + math.max(value, 0)
+        end
+
+        local center = {
+            x = (bounds.ll.x + bounds.ur.x) / 2,
+            y = (bounds.ll.y + bounds.ur.y) / 2,
+        }
+        local radius = math.min(bounds:width(), bounds:height()) / 2
+        local angle = math.pi / 2
+
+        for i, value in ipairs(values) do
+            local sweep = total > 0 and (math.max(value, 0) / total) * 2 * math.pi or 0
+            if sweep > 0 then
+                local slice = { center }
+                for _, point in ipairs(arc_points(center, radius, angle, -sweep)) do
+                    table.insert(slice, point)
+                end
+                slice.fill_color = color_at(i)
+                slice.mode = "fill"
+                table.insert(objects, pdf.object.shape(slice))
+
+                -- Label the slice within its middle
+                if labels[i] ~= "" then
+                    local mid = angle - sweep / 2
+                    local at = {
+                        x = center.x + radius * 0.65 * math.cos(mid),
+                        y = center.y + radius * 0.65 * math.sin(mid),
+                    }
+                    table.insert(objects, text(labels[i]):align_to(
+                        pdf.utils.bounds({ ll = at, ur = at }),
+                        { v = "middle", h = "middle" }
+                    ))
+                end
+            end
+
+            angle = angle - sweep
+        end
+
+        return pdf.object.group(objects)
+    end
+
+    -- Determine the largest value shown on the value axis
+    local max = tbl.max
+    if not max then
+        max = 0
+        for _, value in ipairs(values) do
+            max = math.max(max, value)
+        end
+    end
+    if max <= 0 then
+        max = 1
+    end
+
+    local ticks = tbl.ticks or 4
+    local function tick_label(t)
+        return string.format("%g", max * t / ticks)
+    end
+
+    -- Reserve space for tick labels to the left and data labels beneath the plot
+    local label_width = 0
+    for t = 0, ticks do
+        label_width = math.max(label_width, text(tick_label(t)):bounds():width())
+    end
+    local label_height = text("0"):bounds():height()
+    local gap = label_height / 2
+    local plot = pdf.utils.bounds({
+        ll = { x = bounds.ll.x + label_width + gap, y = bounds.ll.y + label_height + gap },
+        ur = { x = bounds.ur.x, y = bounds.ur.y - label_height / 2 },
+    })
+
+    -- Draw the value axis and the data axis
+    table.insert(objects, pdf.object.line({
+        { x = plot.ll.x, y = plot.ur.y },
+        plot.ll,
+        plot:lr(),
+        color = tbl.axis_color,
+    }))
+
+    -- Draw ticks along the value axis, labeled with their value
+    for t = 0, ticks do
+        local y = plot.ll.y + plot:height() * t / ticks
+        table.insert(objects, pdf.object.line({
+            { x = plot.ll.x - gap / 2, y = y },
+            { x = plot.ll.x, y = y },
+            color = tbl.axis_color,
+        }))
+        table.insert(objects, text(tick_label(t)):align_to(pdf.utils.bounds({
+            ll = { x = bounds.ll.x, y = y },
+            ur = { x = plot.ll.x - gap, y = y },
+        }), { v = "middle", h = "right" }))
+    end
+
+    -- Each value occupies an equal slice of the data axis
+    local width = #values > 0 and plot:width() / #values or 0
+    local function height_of(value)
+        return plot:height() * math.min(math.max(value / max, 0), 1)
+    end
+
+    ---@type pdf.common.PointLike[]
+    local line = {}
+    for i, value in ipairs(values) do
+        local x = plot.ll.x + width * (i - 0.5)
+
+        if kind == "bar" then
+            local bar_width = width * (tbl.bar_width or 0.6)
+            table.insert(objects, pdf.object.rect({
+                ll = { x = x - bar_width / 2, y = plot.ll.y },
+                ur = { x = x + bar_width / 2, y = plot.ll.y + height_of(value) },
+                fill_color = color_at(1),
+                mode = "fill",
+            }))
+        else
+            table.insert(line, { x = x, y = plot.ll.y + height_of(value) })
+        end
+
+        if labels[i] ~= "" then
+            table.insert(objects, text(labels[i]):align_to(pdf.utils.bounds({
+                ll = { x = x - width / 2, y = bounds.ll.y },
+                ur = { x = x + width / 2, y = plot.ll.y - gap / 2 },
+            }), { v = "top", h = "middle" }))
+        end
+    end
+
+    if #line > 0 then
+        line.color = color_at(1)
+        line.thickness = tbl.thickness
+        line.smooth = tbl.smooth
+        table.insert(objects, pdf.object.line(line))
+    end
+
+    return pdf.object.group(objects)
+end
+
+---@class pdf.object.GaugeArgs
+---@field percent number #progress between 0 and 1
+---@field bounds pdf.common.BoundsLike #bounds the ring is centered within
+---@field thickness? number #thickness of the ring, defaulting to a fifth of its radius
+---@field color? pdf.common.ColorLike #color of the progress, defaulting to the page's fill color
+---@field track_color? pdf.common.ColorLike #color of the remainder of the ring, defaulting to a lighter progress color
+---@field label? string|boolean #text in the center of the ring, where true shows the percentage
+---@field text_color? pdf.common.ColorLike
+---@field text_size? number
+
+---Creates a progress ring (donut gauge) filled clockwise from the top by
+---`percent`, centered within `bounds`.
+---@param tbl pdf.object.GaugeArgs
+---@return pdf.object.Group
+function pdf.object.gauge(tbl)
+    local objects = {}
+    local bounds = pdf.utils.bounds(tbl.bounds)
+    local percent = math.min(math.max(tbl.percent or 0, 0), 1)
+    local color = pdf.utils.color(tbl.color or pdf.page.fill_color)
+    local track_color = tbl.track_color or color:lighten(0.75)
+
+    local center = {
+        x = (bounds.ll.x + bounds.ur.x) / 2,
+        y = (bounds.ll.y + bounds.ur.y) / 2,
+    }
+    local radius = math.min(bounds:width(), bounds:height()) / 2
+    local thickness = math.min(tbl.thickness or radius / 5, radius)
+
+    ---Creates the part of the ring sweeping clockwise from `start` by `sweep`.
+    local function ring(start, sweep, fill_color)
+        local points = arc_points(center, radius, start, -sweep)
+        local inner = arc_points(center, radius - thickness, start - sweep, sweep)
+        for _, point in ipairs(inner) do
+            table.insert(points, point)
+        end
+        points.fill_color = fill_color
+        points.mode = "fill"
+        return pdf.object.shape(points)
+    end
+
+    -- Track covers the remainder of the ring, drawn beneath the progress
+    local top = math.pi / 2
+    local progress = percent * 2 * math.pi
+    if percent < 1 then
+        table.insert(objects, ring(top - progress, 2 * math.pi - progress, track_color))
+    end
+    if percent > 0 then
+        table.insert(objects, ring(top, progress, color))
+    end
+
+    local label = tbl.label
+    if label == true then
+        label = string.format("%d%%", math.floor(percent * 100 + 0.5))
+    end
+    if type(label) == "string" and label ~= "" then
+        table.insert(objects, pdf.object.text({
+            text = label,
+            color = tbl.text_color,
+            size = tbl.text_size,
+        }):align_to(pdf.utils.bounds({ ll = center, ur = center }), { v = "middle", h = "middle" }))
+    end
+
+    return pdf.object.group(objects)
+end
+
 -------------------------------------------------------------------------------
 -- PAGES ENHANCEMENTS
 -------------------------------------------------------------------------------
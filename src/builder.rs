@@ -0,0 +1,321 @@
+use crate::pdf::PdfConfig;
+use crate::runtime::{
+    Runtime, RuntimeCache, RuntimeCallback, RuntimeDoc, RuntimeFiles, RuntimeStats,
+};
+use anyhow::Context;
+use mlua::prelude::*;
+use std::io::Write;
+use std::path::Path;
+
+/// Builds PDFs from Rust, for embedding makepdf within other programs without going through the
+/// commandline.
+///
+/// The PDF is described by a Lua script provided as a string, by Rust callbacks invoked with the
+/// Lua runtime once the script finishes, or by both, and is written to any writer:
+///
+/// ```
+/// use makepdf::{mlua::prelude::*, Builder, PdfConfig};
+///
+/// let mut config = PdfConfig::default();
+/// config.title = String::from("Planner");
+///
+/// let mut bytes = Vec::new();
+/// Builder::new()
+///     .config(config)
+///     .script(r#"pdf.pages.get(pdf.pages.create("Cover")).push(pdf.object.text({ text = "2025" }))"#)
+///     .callback(|lua| {
+///         let pdf: LuaTable = lua.globals().raw_get("pdf")?;
+///         let pages: LuaTable = pdf.raw_get("pages")?;
+///         pages.call_function("create", "Notes")
+///     })
+///     .save_to_writer(&mut bytes)
+///     .unwrap();
+///
+/// assert!(bytes.starts_with(b"%PDF"));
+/// ```
+///
+/// Scripts provided as strings cannot read files unless their directories are allowed through
+/// [`Builder::allow_dir`].
+pub struct Builder {
+    /// Configuration of the PDF, which the script and callbacks can change.
+    config: PdfConfig,
+
+    /// Name & code of the script to run, or none to run the script configured by path.
+    code: Option<(String, Vec<u8>)>,
+
+    /// Files that a script provided as a string is allowed to read.
+    files: RuntimeFiles,
+
+    /// Callbacks to invoke once the script finishes, in the order they were added.
+    callbacks: Vec<RuntimeCallback>,
+
+    /// Cache of fonts and the compiled stdlib shared with other builds.
+    cache: RuntimeCache,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Creates a new builder using the default configuration and an empty script.
+    pub fn new() -> Self {
+        Self {
+            config: PdfConfig::default(),
+            code: Some((String::from("script"), Vec::new())),
+            files: RuntimeFiles::new(),
+            callbacks: Vec::new(),
+            cache: RuntimeCache::new(),
+        }
+    }
+
+    /// Replaces the configuration of the PDF, keeping the script provided to the builder.
+    pub fn config(mut self, config: PdfConfig) -> Self {
+        let script = self.config.script;
+        self.config = config;
+        self.config.script = script;
+        self
+    }
+
+    /// Runs the Lua `code` to describe the PDF.
+    pub fn script(self, code: impl Into<String>) -> Self {
+        self.named_script("script", code)
+    }
+
+    /// Runs the Lua `code` to describe the PDF, using `name` when reporting errors and logging.
+    pub fn named_script(mut self, name: impl Into<String>, code: impl Into<String>) -> Self {
+        let name = name.into();
+        self.config.script = name.clone();
+        self.code = Some((name, code.into().into_bytes()));
+        self
+    }
+
+    /// Runs the Lua script at `path` to describe the PDF, which can read files within its
+    /// directory and the current working directory like scripts run from the commandline.
+    pub fn script_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.config.script = path.as_ref().to_string_lossy().to_string();
+        self.code = None;
+        self
+    }
+
+    /// Allows a script provided as a string to read files within `dir`, failing if the directory
+    /// does not exist.
+    pub fn allow_dir(mut self, dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        self.files
+            .allow(dir)
+            .with_context(|| format!("Failed to allow {}", dir.display()))?;
+        Ok(self)
+    }
+
+    /// Invokes `f` with the Lua runtime once the script finishes, which can create pages and
+    /// objects like the script itself.
+    pub fn callback(mut self, f: impl FnOnce(&Lua) -> LuaResult<()> + 'static) -> Self {
+        self.callbacks.push(Box::new(f));
+        self
+    }
+
+    /// Reuses the fonts and compiled stdlib kept within `cache` by earlier builds, such as when
+    /// rebuilding the PDF each time its script changes, adding to the cache for later builds.
+    pub fn cache(mut self, cache: RuntimeCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Builds the PDF and writes it to `writer`, returning the statistics collected while
+    /// building and saving it.
+    pub fn save_to_writer(self, writer: impl Write) -> anyhow::Result<RuntimeStats> {
+        self.build()?.save_to_writer(writer)
+    }
+
+    /// Builds the PDF into memory, returning its bytes.
+    pub fn to_bytes(self) -> anyhow::Result<Vec<u8>> {
+        self.build()?.to_bytes()
+    }
+
+    /// Builds the PDF and saves it to the specified `filename`, returning the statistics collected
+    /// while building and saving it.
+    pub fn save(self, filename: impl Into<String>) -> anyhow::Result<RuntimeStats> {
+        self.build()?.save(filename)
+    }
+
+    /// Runs the script and callbacks, then builds the PDF.
+    fn build(self) -> anyhow::Result<Runtime<(RuntimeDoc, RuntimeStats)>> {
+        let path = self.config.script.clone();
+        let runtime = Runtime::new(self.config);
+        let runtime = match self.code {
+            Some((name, code)) => {
+                runtime.setup_from_code(&name, code, self.files, self.callbacks, &self.cache)
+            }
+            None => {
+                RuntimeFiles::check_fs(&path)?;
+                let code = std::fs::read(&path)
+                    .with_context(|| format!("Failed to load script '{path}'"))?;
+                let files = RuntimeFiles::for_script(&path)
+                    .context("Failed to determine files available to script")?;
+                runtime.setup_from_code(&path, code, files, self.callbacks, &self.cache)
+            }
+        }
+        .context("Failed to setup PDF")?;
+
+        runtime.build().context("Failed to build PDF")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DEFAULT_FONT;
+    use crate::pdf::PdfObjectText;
+    use crate::runtime::{RuntimePage, RuntimePages};
+    use printpdf::lopdf::Document;
+
+    #[test]
+    fn should_build_pdf_from_script_and_callbacks_without_files() {
+        let mut bytes = Vec::new();
+        let stats = Builder::new()
+            .script(r#"pdf.pages.create("Cover")"#)
+            .callback(|lua| {
+                let page = RuntimePage::new("Notes");
+                page.push(PdfObjectText {
+                    text: String::from("Notes"),
+                    ..Default::default()
+                });
+
+                lua.app_data_mut::<RuntimePages>()
+                    .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?
+                    .insert_page(page);
+                Ok(())
+            })
+            .save_to_writer(&mut bytes)
+            .unwrap();
+
+        assert_eq!(stats.pages.len(), 2);
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 2);
+    }
+
+    #[test]
+    fn should_build_pdf_into_bytes() {
+        let bytes = Builder::new()
+            .script(r#"pdf.pages.create("Cover")"#)
+            .to_bytes()
+            .unwrap();
+
+        assert!(bytes.starts_with(b"%PDF"));
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn should_register_configured_fonts_by_name() {
+        let mut config = PdfConfig::default();
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("fonts")
+            .join("JetBrainsMono-Regular.ttf");
+        config
+            .fonts
+            .insert(String::from("mono"), path.to_string_lossy().to_string());
+
+        Builder::new()
+            .config(config)
+            .script(
+                r#"
+                assert(pdf.font.get("mono") ~= nil, "Missing mono font")
+                assert(pdf.font.get("missing") == nil, "Unexpected missing font")
+                pdf.pages.create("Cover")
+                "#,
+            )
+            .to_bytes()
+            .unwrap();
+    }
+
+    #[test]
+    fn should_link_to_pages_referenced_before_they_exist() {
+        let bytes = Builder::new()
+            .script(
+                r#"
+                local july = pdf.pages.ref({ title = "July" })
+                pdf.pages.get(pdf.pages.create("January")).push(pdf.object.rect({
+                    ll = { x = 0, y = 0 },
+                    ur = { x = 10, y = 10 },
+                    link = { type = "goto", page = july, rect = { 0, 0, 10, 10 } },
+                }))
+                pdf.pages.create("July")
+                "#,
+            )
+            .to_bytes()
+            .unwrap();
+
+        // Links to areas of pages have their destinations written by us rather than printpdf
+        let doc = Document::load_mem(&bytes).unwrap();
+        let pages = doc.get_pages();
+        let annots = doc.get_page_annotations(pages[&1]);
+        assert_eq!(annots.len(), 1);
+        let dest = annots[0]
+            .get(b"A")
+            .and_then(|x| x.as_dict())
+            .and_then(|x| x.get(b"D"))
+            .and_then(|x| x.as_array())
+            .unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), pages[&2]);
+    }
+
+    #[test]
+    fn should_subset_embedded_fonts_to_glyphs_used() {
+        let bytes = Builder::new()
+            .script(
+                r#"
+                pdf.pages.get(pdf.pages.create("Cover")).push(pdf.object.text({
+                    x = 10,
+                    y = 10,
+                    text = "Hello",
+                }))
+                "#,
+            )
+            .to_bytes()
+            .unwrap();
+
+        // Builtin font is the only font embedded, and only a handful of its glyphs are used
+        let doc = Document::load_mem(&bytes).unwrap();
+        let fonts: Vec<_> = doc
+            .objects
+            .values()
+            .filter_map(|x| x.as_dict().ok())
+            .filter_map(|x| x.get(b"FontFile2").and_then(|x| x.as_reference()).ok())
+            .map(|id| {
+                // Length of the font program before any compression of its stream
+                doc.get_object(id)
+                    .and_then(|x| x.as_stream())
+                    .and_then(|x| x.dict.get(b"Length1"))
+                    .and_then(|x| x.as_i64())
+                    .unwrap() as usize
+            })
+            .collect();
+
+        assert_eq!(fonts.len(), 1);
+        assert!(
+            fonts[0] < DEFAULT_FONT.len() / 10,
+            "Embedded font is {} bytes, but the builtin font is {} bytes",
+            fonts[0],
+            DEFAULT_FONT.len()
+        );
+    }
+
+    #[test]
+    fn should_fail_if_script_fails() {
+        Builder::new()
+            .script("error('oops')")
+            .save_to_writer(Vec::new())
+            .expect_err("Unexpectedly built PDF from failing script");
+
+        // Scripts provided as strings cannot read files unless allowed
+        Builder::new()
+            .script("pdf.utils.read_json('Cargo.toml')")
+            .save_to_writer(Vec::new())
+            .expect_err("Unexpectedly read file that was not allowed");
+    }
+}
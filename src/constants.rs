@@ -4,6 +4,9 @@ pub const DEFAULT_FONT: &[u8] = include_bytes!("../assets/fonts/JetBrainsMono-Re
 /// Name of global variable representing PDF interface.
 pub const GLOBAL_PDF_VAR_NAME: &str = "pdf";
 
+/// LuaLS annotations of the Lua library, from which the Luau type definitions are generated.
+pub const DEFINITIONS: &str = include_str!("../assets/scripts/definitions.lua");
+
 /// Internal scripts available to be run.
 pub static SCRIPTS: phf::Map<&'static str, &[u8]> = phf::phf_map! {
     "stdlib" => include_bytes!("../assets/scripts/stdlib.lua"),
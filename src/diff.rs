@@ -0,0 +1,447 @@
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use log::*;
+use printpdf::lopdf::{Document, ObjectId};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Configuration for comparing the rendered output of two PDFs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfDiffVisual {
+    /// DPI used to render each page prior to comparing pixels.
+    pub dpi: f32,
+
+    /// Directory where an image is written for each page that differs.
+    pub output: PathBuf,
+}
+
+/// Differences between two PDF documents, page by page.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfDiff {
+    /// Pages that differ between the two documents.
+    pub pages: Vec<PdfPageDiff>,
+}
+
+/// Differences for a single page between two PDF documents.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfPageDiff {
+    /// Page number, starting at 1.
+    pub page: u32,
+
+    /// Descriptions of the structural changes to the page.
+    pub changes: Vec<String>,
+
+    /// Differences in pixels when the page was rendered, if compared visually.
+    pub pixels: Option<PdfPixelDiff>,
+}
+
+/// Differences in pixels for a single rendered page.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfPixelDiff {
+    /// Total pixels compared, which is the area of the larger of the two pages.
+    pub total: usize,
+
+    /// Pixels that differ between the two pages.
+    pub changed: usize,
+
+    /// Path to the image highlighting the differences, if any pixels changed.
+    pub image: Option<PathBuf>,
+}
+
+impl PdfPixelDiff {
+    /// Returns the percentage (0.0 to 100.0) of pixels that changed.
+    pub fn changed_percent(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.changed as f32 / self.total as f32) * 100.0
+        }
+    }
+}
+
+impl PdfPageDiff {
+    /// Returns true if the page has no structural or visual changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.pixels.iter().all(|x| x.changed == 0)
+    }
+}
+
+impl PdfDiff {
+    /// Returns true if no pages differ.
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Compares the PDF at `old` with the PDF at `new`, optionally comparing the rendered pages
+    /// when `visual` is provided.
+    ///
+    /// Rendering pages requires `pdftoppm` (from poppler) to be available on the path.
+    pub fn compare(
+        old: impl AsRef<Path>,
+        new: impl AsRef<Path>,
+        visual: Option<&PdfDiffVisual>,
+    ) -> anyhow::Result<Self> {
+        let (old, new) = (old.as_ref(), new.as_ref());
+        RuntimeFiles::check_fs(old)?;
+        RuntimeFiles::check_fs(new)?;
+        let old_doc =
+            Document::load(old).with_context(|| format!("Failed to load {}", old.display()))?;
+        let new_doc =
+            Document::load(new).with_context(|| format!("Failed to load {}", new.display()))?;
+
+        let mut diff = Self::compare_docs(&old_doc, &new_doc);
+
+        if let Some(visual) = visual {
+            fs::create_dir_all(&visual.output).with_context(|| {
+                format!("Failed to create directory {}", visual.output.display())
+            })?;
+
+            // Only pages that exist in both documents can be compared
+            let cnt = old_doc.get_pages().len().min(new_doc.get_pages().len()) as u32;
+            let mut pages = Vec::new();
+            for page in 1..=cnt {
+                debug!("Rendering page {page} / {cnt}");
+                let mut page_diff = diff
+                    .pages
+                    .iter()
+                    .find(|x| x.page == page)
+                    .cloned()
+                    .unwrap_or(PdfPageDiff {
+                        page,
+                        ..Default::default()
+                    });
+
+                let old_raster = Raster::render(old, page, visual.dpi)?;
+                let new_raster = Raster::render(new, page, visual.dpi)?;
+                let (raster, changed) = old_raster.diff(&new_raster);
+
+                let image = if changed > 0 {
+                    let path = visual.output.join(format!("page-{page}.png"));
+                    raster.save_png(&path)?;
+                    Some(path)
+                } else {
+                    None
+                };
+
+                page_diff.pixels = Some(PdfPixelDiff {
+                    total: raster.width * raster.height,
+                    changed,
+                    image,
+                });
+                pages.push(page_diff);
+            }
+
+            // Include pages that were added or removed, which come after the shared pages
+            pages.extend(diff.pages.into_iter().filter(|x| x.page > cnt));
+            pages.retain(|x| !x.is_empty());
+            diff.pages = pages;
+        }
+
+        Ok(diff)
+    }
+
+    /// Compares the structure of the pages within two documents.
+    fn compare_docs(old: &Document, new: &Document) -> Self {
+        let old_pages = old.get_pages();
+        let new_pages = new.get_pages();
+        let cnt = old_pages.len().max(new_pages.len()) as u32;
+
+        let mut pages = Vec::new();
+        for page in 1..=cnt {
+            let changes = match (old_pages.get(&page), new_pages.get(&page)) {
+                (Some(_), None) => vec![String::from("page removed")],
+                (None, Some(_)) => vec![String::from("page added")],
+                (Some(old_id), Some(new_id)) => compare_pages(old, *old_id, new, *new_id),
+                (None, None) => Vec::new(),
+            };
+
+            if !changes.is_empty() {
+                pages.push(PdfPageDiff {
+                    page,
+                    changes,
+                    pixels: None,
+                });
+            }
+        }
+
+        Self { pages }
+    }
+}
+
+/// Compares a single page from two documents, returning descriptions of what changed.
+fn compare_pages(
+    old: &Document,
+    old_id: ObjectId,
+    new: &Document,
+    new_id: ObjectId,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    let media_box = |doc: &Document, id: ObjectId| {
+        doc.get_dictionary(id)
+            .and_then(|x| x.get(b"MediaBox"))
+            .map(|x| format!("{x:?}"))
+            .ok()
+    };
+    let (old_size, new_size) = (media_box(old, old_id), media_box(new, new_id));
+    if old_size != new_size {
+        changes.push(format!(
+            "size changed from {} to {}",
+            old_size.as_deref().unwrap_or("none"),
+            new_size.as_deref().unwrap_or("none")
+        ));
+    }
+
+    if old.get_page_content(old_id).ok() != new.get_page_content(new_id).ok() {
+        changes.push(String::from("content changed"));
+    }
+
+    // Annotations reference other objects, so only compare where they are on the page
+    let annotations = |doc: &Document, id: ObjectId| {
+        doc.get_page_annotations(id)
+            .into_iter()
+            .map(|x| format!("{:?}", x.get(b"Rect").ok()))
+            .collect::<Vec<_>>()
+    };
+    if annotations(old, old_id) != annotations(new, new_id) {
+        changes.push(String::from("links changed"));
+    }
+
+    changes
+}
+
+/// RGB image of a rendered page.
+#[derive(Clone, Debug, PartialEq)]
+struct Raster {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Raster {
+    /// Renders `page` (starting at 1) of the PDF at `path` using `pdftoppm`.
+    fn render(path: &Path, page: u32, dpi: f32) -> anyhow::Result<Self> {
+        let prefix = std::env::temp_dir().join(format!(
+            "makepdf-diff-{}-{page}-{}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+
+        let output = Command::new("pdftoppm")
+            .arg("-r")
+            .arg(dpi.to_string())
+            .arg("-f")
+            .arg(page.to_string())
+            .arg("-l")
+            .arg(page.to_string())
+            .arg("-singlefile")
+            .arg(path)
+            .arg(&prefix)
+            .output()
+            .context("Failed to run pdftoppm, which is required for visual comparisons")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to render page {page} of {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let ppm_path = prefix.with_extension("ppm");
+        let bytes = fs::read(&ppm_path)
+            .with_context(|| format!("Failed to read {}", ppm_path.display()))?;
+        let _ = fs::remove_file(&ppm_path);
+        Self::from_ppm(&bytes)
+    }
+
+    /// Parses a binary (P6) PPM image.
+    fn from_ppm(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut pos = 0;
+        let mut fields = Vec::new();
+
+        // Header is magic, width, height, and max value separated by whitespace and comments
+        while fields.len() < 4 {
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+
+            if pos < bytes.len() && bytes[pos] == b'#' {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+                continue;
+            }
+
+            let start = pos;
+            while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+
+            if start == pos {
+                anyhow::bail!("Incomplete PPM header");
+            }
+
+            fields.push(String::from_utf8_lossy(&bytes[start..pos]).to_string());
+        }
+
+        if fields[0] != "P6" {
+            anyhow::bail!("Unsupported PPM format {}", fields[0]);
+        }
+
+        let width: usize = fields[1].parse().context("Invalid PPM width")?;
+        let height: usize = fields[2].parse().context("Invalid PPM height")?;
+        let max: usize = fields[3].parse().context("Invalid PPM max value")?;
+        if max > 255 {
+            anyhow::bail!("Unsupported PPM max value {max}");
+        }
+
+        // Single whitespace character separates header from data
+        let data = bytes.get(pos + 1..).unwrap_or_default();
+        if data.len() < width * height * 3 {
+            anyhow::bail!("PPM data is smaller than {width}x{height}");
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data: data[..width * height * 3].to_vec(),
+        })
+    }
+
+    /// Returns the RGB pixel at `(x, y)`, or none if out of bounds.
+    fn pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        if x < self.width && y < self.height {
+            let i = (y * self.width + x) * 3;
+            Some([self.data[i], self.data[i + 1], self.data[i + 2]])
+        } else {
+            None
+        }
+    }
+
+    /// Produces an image highlighting pixels that differ from `other` in red, with unchanged
+    /// pixels faded, alongside the total number of pixels that differ.
+    fn diff(&self, other: &Self) -> (Self, usize) {
+        let width = self.width.max(other.width);
+        let height = self.height.max(other.height);
+        let mut data = Vec::with_capacity(width * height * 3);
+        let mut changed = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                match (self.pixel(x, y), other.pixel(x, y)) {
+                    (Some(a), Some(b)) if a == b => {
+                        let gray =
+                            (a[0] as u32 * 299 + a[1] as u32 * 587 + a[2] as u32 * 114) / 1000;
+                        let faded = (191 + gray / 4) as u8;
+                        data.extend([faded, faded, faded]);
+                    }
+                    _ => {
+                        changed += 1;
+                        data.extend([255, 0, 0]);
+                    }
+                }
+            }
+        }
+
+        (
+            Self {
+                width,
+                height,
+                data,
+            },
+            changed,
+        )
+    }
+
+    /// Saves the image as a PNG to `path`.
+    fn save_png(&self, path: &Path) -> anyhow::Result<()> {
+        let f =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(f), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.data))
+            .with_context(|| format!("Failed to save {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_doc;
+
+    #[test]
+    fn compare_docs_should_report_nothing_for_identical_documents() {
+        let old = make_doc(&[(100.0, &[(0.0, 0.0, 10.0, 10.0)]), (100.0, &[])]);
+        let new = make_doc(&[(100.0, &[(0.0, 0.0, 10.0, 10.0)]), (100.0, &[])]);
+        assert_eq!(PdfDiff::compare_docs(&old, &new), PdfDiff::default());
+    }
+
+    #[test]
+    fn compare_docs_should_report_changed_pages() {
+        let old = make_doc(&[
+            (100.0, &[(0.0, 0.0, 10.0, 10.0)]),
+            (100.0, &[]),
+            (100.0, &[]),
+        ]);
+        let new = make_doc(&[(100.0, &[(0.0, 0.0, 20.0, 10.0)]), (50.0, &[])]);
+
+        let diff = PdfDiff::compare_docs(&old, &new);
+        assert_eq!(diff.pages.len(), 3);
+
+        assert_eq!(diff.pages[0].page, 1);
+        assert_eq!(diff.pages[0].changes, vec![String::from("content changed")]);
+
+        assert_eq!(diff.pages[1].page, 2);
+        assert_eq!(diff.pages[1].changes.len(), 1);
+        assert!(diff.pages[1].changes[0].starts_with("size changed"));
+
+        assert_eq!(diff.pages[2].page, 3);
+        assert_eq!(diff.pages[2].changes, vec![String::from("page removed")]);
+    }
+
+    #[test]
+    fn raster_should_support_parsing_ppm_with_comments() {
+        let mut bytes = b"P6\n# comment\n2 1\n255\n".to_vec();
+        bytes.extend([1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            Raster::from_ppm(&bytes).unwrap(),
+            Raster {
+                width: 2,
+                height: 1,
+                data: vec![1, 2, 3, 4, 5, 6],
+            }
+        );
+
+        Raster::from_ppm(b"P3\n1 1\n255\n1 2 3").expect_err("Unexpectedly parsed ascii ppm");
+        Raster::from_ppm(b"P6\n2 2\n255\n\x01\x02\x03").expect_err("Unexpectedly parsed ppm");
+    }
+
+    #[test]
+    fn raster_diff_should_highlight_changed_pixels() {
+        let old = Raster {
+            width: 2,
+            height: 1,
+            data: vec![0, 0, 0, 255, 255, 255],
+        };
+        let new = Raster {
+            width: 2,
+            height: 2,
+            data: vec![0, 0, 0, 0, 0, 0, 9, 9, 9, 9, 9, 9],
+        };
+
+        let (raster, changed) = old.diff(&new);
+        assert_eq!(changed, 3);
+        assert_eq!((raster.width, raster.height), (2, 2));
+        assert_eq!(raster.pixel(0, 0), Some([191, 191, 191]));
+        assert_eq!(raster.pixel(1, 0), Some([255, 0, 0]));
+        assert_eq!(raster.pixel(0, 1), Some([255, 0, 0]));
+        assert_eq!(raster.pixel(1, 1), Some([255, 0, 0]));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::runtime::RuntimeFiles;
+use crate::PdfPageDiff;
+use anyhow::Context;
+use printpdf::lopdf::content::Content;
+use printpdf::lopdf::{Document, ObjectId};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Fingerprints of the pages of a PDF, stored alongside a script so that changes to the script
+/// (or the modules it uses) that alter its pages are caught.
+///
+/// Stored as text with a line per page containing the page number, size of the page in points,
+/// and hash of its normalized content stream, e.g. `1 612x792 0123456789abcdef`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfGolden {
+    /// Fingerprint of each page, in order.
+    pub pages: Vec<PdfGoldenPage>,
+}
+
+/// Fingerprint of a single page of a PDF.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfGoldenPage {
+    /// Page number, starting at 1.
+    pub page: u32,
+
+    /// Size of the page in points, formatted as `{WIDTH}x{HEIGHT}`.
+    pub size: String,
+
+    /// Hash of the content stream of the page after it has been decoded and re-encoded, which
+    /// removes differences in whitespace and compression.
+    pub hash: u64,
+}
+
+impl PdfGolden {
+    /// Fingerprints the pages of the PDF at `path`.
+    pub fn from_pdf(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let doc =
+            Document::load(path).with_context(|| format!("Failed to load {}", path.display()))?;
+        Self::from_doc(&doc)
+    }
+
+    /// Fingerprints the pages of `doc`.
+    pub fn from_doc(doc: &Document) -> anyhow::Result<Self> {
+        let mut pages = Vec::new();
+        for (page, id) in doc.get_pages() {
+            pages.push(PdfGoldenPage {
+                page,
+                size: page_size(doc, id),
+                hash: fnv1a(&normalized_content(doc, id)?),
+            });
+        }
+
+        Ok(Self { pages })
+    }
+
+    /// Loads fingerprints from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Saves fingerprints to the file at `path`, replacing it if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Compares these (golden) fingerprints with `other`, returning the pages that differ.
+    pub fn compare(&self, other: &Self) -> Vec<PdfPageDiff> {
+        let cnt = self.pages.len().max(other.pages.len());
+
+        let mut pages = Vec::new();
+        for i in 0..cnt {
+            let changes = match (self.pages.get(i), other.pages.get(i)) {
+                (Some(_), None) => vec![String::from("page removed")],
+                (None, Some(_)) => vec![String::from("page added")],
+                (Some(old), Some(new)) => {
+                    let mut changes = Vec::new();
+                    if old.size != new.size {
+                        changes.push(format!("size changed from {} to {}", old.size, new.size));
+                    }
+
+                    if old.hash != new.hash {
+                        changes.push(String::from("content changed"));
+                    }
+
+                    changes
+                }
+                (None, None) => Vec::new(),
+            };
+
+            if !changes.is_empty() {
+                pages.push(PdfPageDiff {
+                    page: i as u32 + 1,
+                    changes,
+                    pixels: None,
+                });
+            }
+        }
+
+        pages
+    }
+}
+
+impl fmt::Display for PdfGolden {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for page in self.pages.iter() {
+            writeln!(f, "{} {} {:016x}", page.page, page.size, page.hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PdfGolden {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pages = Vec::new();
+        for (i, line) in s.lines().map(str::trim).enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [page, size, hash] = fields[..] else {
+                anyhow::bail!("Line {} is not in the format: PAGE SIZE HASH", i + 1);
+            };
+
+            pages.push(PdfGoldenPage {
+                page: page
+                    .parse()
+                    .with_context(|| format!("Line {} has invalid page {page}", i + 1))?,
+                size: size.to_string(),
+                hash: u64::from_str_radix(hash, 16)
+                    .with_context(|| format!("Line {} has invalid hash {hash}", i + 1))?,
+            });
+        }
+
+        Ok(Self { pages })
+    }
+}
+
+/// Returns the size of the page `id` in points as `{WIDTH}x{HEIGHT}`, or `unknown` if the page
+/// has no media box.
+fn page_size(doc: &Document, id: ObjectId) -> String {
+    let coords: Option<Vec<f32>> = doc
+        .get_dictionary(id)
+        .and_then(|x| x.get(b"MediaBox"))
+        .and_then(|x| x.as_array())
+        .ok()
+        .and_then(|x| x.iter().map(|x| x.as_float().ok()).collect());
+
+    match coords.as_deref() {
+        Some([llx, lly, urx, ury]) => format!("{}x{}", round(urx - llx), round(ury - lly)),
+        _ => String::from("unknown"),
+    }
+}
+
+/// Rounds `x` to two decimal places so that sizes are stable across platforms.
+fn round(x: f32) -> f32 {
+    (x * 100.0).round() / 100.0
+}
+
+/// Returns the content stream of the page `id`, decoded and re-encoded into a canonical form.
+fn normalized_content(doc: &Document, id: ObjectId) -> anyhow::Result<Vec<u8>> {
+    let content = doc.get_page_content(id)?;
+    Ok(Content::decode(&content)?.encode()?)
+}
+
+/// Hashes `bytes` using 64-bit FNV-1a, which (unlike the hasher of the standard library) is
+/// stable across versions of Rust.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::make_doc;
+
+    #[test]
+    fn should_be_able_to_convert_to_and_from_text() {
+        let golden =
+            PdfGolden::from_doc(&make_doc(&[(25.4, &[(0.0, 0.0, 10.0, 10.0)]), (50.8, &[])]))
+                .unwrap();
+        assert_eq!(golden.pages.len(), 2);
+        assert_eq!(golden.pages[0].size, "72x72");
+        assert_eq!(golden.pages[1].size, "144x144");
+
+        assert_eq!(golden.to_string().parse::<PdfGolden>().unwrap(), golden);
+        "1 72x72"
+            .parse::<PdfGolden>()
+            .expect_err("Unexpectedly parsed line");
+    }
+
+    #[test]
+    fn compare_should_report_changed_pages() {
+        let golden = PdfGolden::from_doc(&make_doc(&[
+            (100.0, &[(0.0, 0.0, 10.0, 10.0)]),
+            (100.0, &[]),
+            (100.0, &[]),
+        ]))
+        .unwrap();
+
+        // Same pages produce the same fingerprints
+        let same = PdfGolden::from_doc(&make_doc(&[
+            (100.0, &[(0.0, 0.0, 10.0, 10.0)]),
+            (100.0, &[]),
+            (100.0, &[]),
+        ]))
+        .unwrap();
+        assert!(golden.compare(&same).is_empty());
+
+        let other = PdfGolden::from_doc(&make_doc(&[
+            (100.0, &[(0.0, 0.0, 20.0, 10.0)]),
+            (50.0, &[]),
+        ]))
+        .unwrap();
+        let diff = golden.compare(&other);
+        assert_eq!(diff.len(), 3);
+        assert_eq!(diff[0].changes, vec![String::from("content changed")]);
+        assert!(diff[1].changes[0].starts_with("size changed"));
+        assert_eq!(diff[2].changes, vec![String::from("page removed")]);
+    }
+}
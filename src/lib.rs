@@ -1,6 +1,36 @@
+mod builder;
 pub mod constants;
+mod diff;
+mod golden;
+mod manifest;
+mod merge;
 mod pdf;
+mod preflight;
+mod preview;
+mod project;
 mod runtime;
+#[cfg(test)]
+mod test_utils;
+mod types;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+pub use builder::*;
+pub use diff::*;
+pub use golden::*;
+pub use manifest::*;
+pub use merge::*;
 pub use pdf::*;
-pub use runtime::Runtime;
+pub use preflight::*;
+pub use preview::*;
+pub use project::*;
+pub use runtime::{
+    Runtime, RuntimeCache, RuntimeCallback, RuntimePage, RuntimePageId, RuntimePageStats,
+    RuntimePages, RuntimeStats, RuntimeTestReport, RuntimeTestResult,
+};
+pub use types::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+
+/// Lua runtime used by makepdf, re-exported for callbacks provided through [`Builder::callback`].
+pub use mlua;
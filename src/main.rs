@@ -1,8 +1,13 @@
 use anyhow::Context;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use log::*;
-use makepdf::{PdfConfig, PdfConfigPage, Runtime};
+use makepdf::{
+    PdfConfig, PdfConfigPage, PdfDiff, PdfDiffVisual, PdfGolden, PdfImposition,
+    PdfImpositionLayout, PdfManifest, PdfManifestEntry, PdfMerge, PdfPreflight,
+    PdfPreflightProfile, PdfPreview, PdfPreviewFormat, PdfProject, PdfTypes, Runtime, RuntimeCache,
+};
 use simplelog::*;
+use std::collections::BTreeMap;
 use std::fs::File;
 
 #[derive(Debug, Parser)]
@@ -28,50 +33,349 @@ struct Cli {
 enum Commands {
     /// Construct a PDF using a Luau (https://luau.org/) script, which is also compatible with Lua
     /// 5.1.
-    Make {
-        /// Dimensions (WIDTHxHEIGHT) to use for the PDF output,
-        /// defaulting to the Supernote A6 X2 Nomad.
-        ///
-        /// Can be in one of the following formats:
-        ///
-        /// 1. `{WIDTH}x{HEIGHT}in` for inches
-        ///
-        /// 2. `{WIDTH}x{HEIGHT}mm` for millimeters
+    ///
+    /// Defaults for the dimensions, dpi, font, title, output directory, and vars are loaded from a
+    /// makepdf.toml within the directory of the script (or of the manifest with `--all`) or the
+    /// current working directory, with options on the commandline taking precedence.
+    Make(Box<MakeArgs>),
+
+    /// Compile a script into Luau bytecode, which `make` and `test` load in place of the script
+    /// with `--allow-bytecode` to skip parsing and compiling it, such as for very large generated
+    /// scripts.
+    ///
+    /// Modules required by the script are not compiled and still load from their source.
+    Compile {
+        /// Level (0 to 2) of optimizations applied when compiling the script, defaulting to 1.
         ///
-        /// 3. `{WIDTH}x{HEIGHT}px` for pixels
+        /// Level 2 inlines functions and unrolls loops, which speeds up scripts at the cost of
+        /// less helpful errors.
+        #[arg(short = 'O', long, value_parser = clap::value_parser!(u8).range(0..=2))]
+        opt_level: Option<u8>,
+
+        /// Path to the script to compile.
+        #[arg(short, long, default_value_t = PdfConfig::default().script)]
+        script: String,
+
+        /// Destination for the bytecode, defaulting to the script with a .luac extension.
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Compare two PDFs page by page, failing if any page differs.
+    Diff {
+        /// Path to the original PDF.
+        old: String,
+
+        /// Path to the new PDF to compare against the original.
+        new: String,
+
+        /// If specified, renders each page and compares pixels, writing an image highlighting
+        /// the differences for each page that changed.
         ///
-        /// Note that the DPI will influence conversion rates from pixels to PDF millimeters.
-        #[arg(short, long, default_value_t = PdfConfigPage::default().to_px_size_string())]
-        dimensions: String,
+        /// Requires `pdftoppm` (from poppler) to be available on the path.
+        #[arg(long)]
+        visual: bool,
 
-        /// DPI to use for the created PDF.
-        #[arg(long, default_value_t = PdfConfigPage::default().dpi)]
+        /// DPI to use when rendering pages for a visual comparison.
+        #[arg(long, default_value_t = 72.0)]
         dpi: f32,
 
-        /// Path to custom font to use in place of the default Jetbrains Mono font.
-        #[arg(long)]
-        font: Option<String>,
+        /// Directory where difference images are written for a visual comparison.
+        #[arg(short, long, default_value_t = String::from("diff"))]
+        output: String,
+    },
+
+    /// Concatenate multiple PDFs into one, such as the cover, calendar, and notes of a planner
+    /// generated by different scripts.
+    ///
+    /// Each PDF gets an entry in the outline (named after its file) with its own outline nested
+    /// beneath it, and links between its pages keep working.
+    Merge {
+        /// Paths to the PDFs to merge, in order.
+        #[arg(required = true, num_args = 2..)]
+        pdfs: Vec<String>,
+
+        /// Path to the merged PDF.
+        #[arg(short, long, default_value_t = String::from("merged.pdf"))]
+        output: String,
+    },
+
+    /// Export pages of a PDF as images, to view them without a PDF reader or embed them in
+    /// documentation.
+    ///
+    /// Requires `pdftoppm` (for png) or `pdftocairo` (for svg) from poppler to be available on the
+    /// path.
+    Preview {
+        /// Path to the PDF.
+        pdf: String,
+
+        /// Format of the images, either png or svg.
+        #[arg(short, long, default_value = "png")]
+        format: PdfPreviewFormat,
+
+        /// DPI to use when rendering pages as png.
+        #[arg(long, default_value_t = 72.0)]
+        dpi: f32,
+
+        /// Pages to export (e.g. `1,3-5`), defaulting to every page.
+        #[arg(short, long, default_value_t = String::new())]
+        pages: String,
+
+        /// Directory where images are written, named `page-{N}.{png|svg}`.
+        #[arg(short, long, default_value_t = String::from("preview"))]
+        output: String,
+    },
 
-        /// If specified, will open the PDF after it is created using the system-default method.
+    /// Build a script, running the tests it registers through `pdf.test.it` and comparing its
+    /// pages against a golden file of their fingerprints, failing if any test fails or page
+    /// differs.
+    ///
+    /// Each page is fingerprinted by its size and a hash of its content, so changes to the script
+    /// (or the modules it uses) that alter its pages are caught. Pages are only compared when the
+    /// golden file exists.
+    ///
+    /// Like `make`, defaults for the dimensions, dpi, font, and vars are loaded from a makepdf.toml
+    /// within the directory of the script or the current working directory.
+    Test {
+        /// If specified, allows the script to be precompiled Luau bytecode, like `make`.
         #[arg(long)]
-        open: bool,
+        allow_bytecode: bool,
 
-        /// Destination for the created PDF file.
-        ///
-        /// When no output provided, will use the title as the filename.
+        /// Dimensions (WIDTHxHEIGHT) to use for the PDF output, in the same formats as `make`.
         #[arg(short, long)]
-        output: Option<String>,
+        dimensions: Option<String>,
+
+        /// DPI to use for the created PDF, defaulting to 300.
+        #[arg(long)]
+        dpi: Option<f32>,
+
+        /// Path to the golden file containing the fingerprints of the expected pages.
+        #[arg(short, long, default_value_t = String::from("makepdf.golden"))]
+        golden: String,
+
+        /// If specified, will compile the script to native code, like `make`.
+        #[arg(long)]
+        native: bool,
+
+        /// Level (0 to 2) of optimizations applied when compiling the script, like `make`.
+        #[arg(short = 'O', long, value_parser = clap::value_parser!(u8).range(0..=2))]
+        opt_level: Option<u8>,
 
         /// Path to the script to use to build the PDF.
         #[arg(short, long, default_value_t = PdfConfig::default().script)]
         script: String,
 
-        /// Title of the PDF document.
-        #[arg(long, default_value_t = PdfConfig::default().title)]
-        title: String,
+        /// If specified, will fail when an object has a field it does not support, like `make`.
+        #[arg(long)]
+        strict: bool,
+
+        /// If specified, writes the fingerprints of the pages to the golden file instead of
+        /// comparing against it, such as after an intentional change.
+        #[arg(long)]
+        update: bool,
+
+        /// Argument (KEY=VALUE) passed to the script, available as `pdf.args.KEY`.
+        ///
+        /// Can be specified multiple times.
+        #[arg(long = "var", value_name = "KEY=VALUE", value_parser = PdfConfig::parse_arg)]
+        vars: Vec<(String, String)>,
+    },
+
+    /// Write the Luau type definitions of the `pdf` global, which luau-lsp uses to provide
+    /// completions and type checking when writing scripts.
+    ///
+    /// Definitions are translated from the annotations of the Lua library of this version of
+    /// makepdf, so they carry the types of its fields, parameters, and returns.
+    Types {
+        /// Destination for the definitions.
+        #[arg(short, long, default_value_t = String::from("pdf.d.luau"))]
+        output: String,
     },
 }
 
+/// Arguments of the `make` subcommand.
+#[derive(Debug, Args)]
+struct MakeArgs {
+    /// If specified, makes every PDF listed in the manifest instead of a single PDF, building
+    /// them in parallel.
+    ///
+    /// Options provided on the commandline are used for anything not specified by an entry
+    /// of the manifest.
+    #[arg(long)]
+    all: bool,
+
+    /// If specified, allows the script to be precompiled Luau bytecode produced by
+    /// `makepdf compile`, which loads faster for very large scripts.
+    ///
+    /// Only use with bytecode you trust, as it is not verified and malformed bytecode can
+    /// crash makepdf.
+    #[arg(long)]
+    allow_bytecode: bool,
+
+    /// If specified, will overlay each page with a labeled coordinate grid spaced this many
+    /// millimeters apart (10 if no spacing given), the crop and art boxes, and the bounds of
+    /// each object labeled with its depth, for debugging layouts.
+    #[arg(long, value_name = "SPACING", num_args = 0..=1, default_missing_value = "10")]
+    debug_layout: Option<f32>,
+
+    /// If specified, will write the pages and their objects (after the script has run and
+    /// before anything is drawn) as JSON next to the PDF, using the name of the PDF with a
+    /// .json extension, for inspection by external tools.
+    #[arg(long)]
+    dump_json: bool,
+
+    /// Dimensions (WIDTHxHEIGHT) to use for the PDF output,
+    /// defaulting to the Supernote A6 X2 Nomad.
+    ///
+    /// Can be in one of the following formats:
+    ///
+    /// 1. `{WIDTH}x{HEIGHT}in` for inches
+    ///
+    /// 2. `{WIDTH}x{HEIGHT}mm` for millimeters
+    ///
+    /// 3. `{WIDTH}x{HEIGHT}px` for pixels
+    ///
+    /// 4. a paper size (a3, a4, a5, a6, letter, legal, or tabloid)
+    ///
+    /// Any of these can end with `-landscape` or `-portrait`, such as `a4-landscape`.
+    ///
+    /// Note that the DPI will influence conversion rates from pixels to PDF millimeters.
+    #[arg(short, long)]
+    dimensions: Option<String>,
+
+    /// DPI to use for the created PDF, defaulting to 300.
+    #[arg(long)]
+    dpi: Option<f32>,
+
+    /// Font (NAME=PATH) registered before the script runs, available to the script through
+    /// `pdf.font.get("NAME")`, or the path to a custom font to use in place of the default
+    /// Jetbrains Mono font when no name is given.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "[NAME=]PATH", value_parser = PdfConfig::parse_font)]
+    font: Vec<(Option<String>, String)>,
+
+    /// Directory whose fonts (.ttf and .otf) are registered before the script runs, each
+    /// named after its file without the extension, e.g. `Inter-Bold` for `Inter-Bold.ttf`.
+    ///
+    /// Can be specified multiple times, where fonts given through `--font` take precedence.
+    #[arg(long, value_name = "DIR")]
+    font_dir: Vec<String>,
+
+    /// If specified, will convert all colors to grayscale when drawing, optimizing the PDF for
+    /// e-ink screens without changing the script.
+    #[arg(long)]
+    grayscale: bool,
+
+    /// If specified, will place the pages of the PDF onto larger sheets (see `--sheet`) for
+    /// printing at home, two pages side by side on each side of a sheet.
+    ///
+    /// Supported impositions are 2up, which places pages in order, and booklet, which places
+    /// pages so that the sheets printed on both sides and folded in half form a booklet.
+    #[arg(long, value_name = "LAYOUT")]
+    imposition: Option<PdfImpositionLayout>,
+
+    /// If specified, will linearize the PDF after it is created for fast web view, allowing
+    /// viewers to load it a page at a time such as when downloaded over HTTP.
+    ///
+    /// Requires `qpdf` (https://qpdf.readthedocs.io) to be available on the path, failing before
+    /// any PDF is built if it is not.
+    #[arg(long)]
+    linearize: bool,
+
+    /// Path to the manifest listing the PDFs to make with `--all`, which is a Lua script
+    /// that returns a list of tables each with an optional script, output, output_dir,
+    /// dimensions, dpi, font, title, args, and fonts.
+    #[arg(long, default_value_t = String::from("makepdf.manifest.lua"))]
+    manifest: String,
+
+    /// If specified, will compile the script and the modules it requires to native code,
+    /// speeding up scripts that do heavy layout math such as generating a calendar for each
+    /// day of the year.
+    ///
+    /// Requires makepdf to be built with the jit feature, and is ignored on platforms other
+    /// than x64 and arm64.
+    #[arg(long)]
+    native: bool,
+
+    /// If specified, will reduce the size of the PDF by merging identical objects, such as the
+    /// content shared by similar pages, and compressing streams.
+    #[arg(long)]
+    optimize: bool,
+
+    /// If specified, will open the PDF after it is created using the system-default method.
+    #[arg(long)]
+    open: bool,
+
+    /// Level (0 to 2) of optimizations applied when compiling the script, defaulting to 1.
+    ///
+    /// Level 2 inlines functions and unrolls loops, which speeds up scripts at the cost of
+    /// less helpful errors.
+    #[arg(short = 'O', long, value_parser = clap::value_parser!(u8).range(0..=2))]
+    opt_level: Option<u8>,
+
+    /// Destination for the created PDF file.
+    ///
+    /// When no output provided, will use the title as the filename.
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// If specified, will produce a PDF/A-2b document for archival, embedding an sRGB color
+    /// profile and XMP metadata, failing if the PDF cannot meet the requirements of PDF/A
+    /// such as when it has attachments.
+    #[arg(long)]
+    pdfa: bool,
+
+    /// If specified, will validate the created PDF against the capabilities of a device,
+    /// checking the page size, size of links, operators per page, thickness of strokes, and
+    /// use of color, failing if any check does not pass.
+    ///
+    /// Supported devices are supernote, supernote-a5x, and remarkable2.
+    #[arg(long)]
+    preflight: Option<PdfPreflightProfile>,
+
+    /// If specified, will export every page of the created PDF as a PNG into this directory,
+    /// rendered at the DPI of the PDF.
+    ///
+    /// Requires `pdftoppm` (from poppler) to be available on the path.
+    #[arg(long, value_name = "DIR")]
+    preview_dir: Option<String>,
+
+    /// If specified, will snap the coordinates and thickness of lines and outlines to the
+    /// pixel grid of the DPI, sharpening thin lines on e-ink screens.
+    #[arg(long)]
+    snap_to_pixels: bool,
+
+    /// Path to the script to use to build the PDF.
+    #[arg(short, long, default_value_t = PdfConfig::default().script)]
+    script: String,
+
+    /// Sheet of paper that pages are placed onto with `--imposition`, in any of the formats of
+    /// `--dimensions` such as a paper size (a3, a4, a5, a6, letter, legal, or tabloid).
+    #[arg(long, default_value_t = String::from("a4"))]
+    sheet: String,
+
+    /// If specified, will report statistics after the PDF is created, including the count of
+    /// objects by type, time spent drawing each page and saving, and the final file size.
+    #[arg(long)]
+    stats: bool,
+
+    /// If specified, will fail when an object has a field it does not support, such as a typo
+    /// like `fill_colour`, suggesting the nearest supported field.
+    #[arg(long)]
+    strict: bool,
+
+    /// Title of the PDF document, defaulting to "MakePDF" followed by today's date.
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Argument (KEY=VALUE) passed to the script, available as `pdf.args.KEY`.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = PdfConfig::parse_arg)]
+    vars: Vec<(String, String)>,
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     init_logger(&cli)?;
@@ -112,57 +416,591 @@ fn init_logger(cli: &Cli) -> anyhow::Result<()> {
 
 fn do_main(cli: Cli) -> anyhow::Result<()> {
     match cli.command {
-        Commands::Make {
-            dimensions,
-            dpi,
-            font,
-            open,
-            output,
+        Commands::Make(args) => {
+            let MakeArgs {
+                all,
+                allow_bytecode,
+                debug_layout,
+                dimensions,
+                dpi,
+                dump_json,
+                font,
+                font_dir,
+                grayscale,
+                imposition,
+                linearize,
+                manifest,
+                native,
+                open,
+                opt_level,
+                optimize,
+                output,
+                pdfa,
+                preflight,
+                preview_dir,
+                script,
+                sheet,
+                snap_to_pixels,
+                stats,
+                strict,
+                title,
+                vars,
+            } = *args;
+
+            // Commandline arguments serve as the defaults for anything not in the manifest,
+            // falling back to the defaults of the project for anything not on the commandline
+            let project = load_project(if all { &manifest } else { &script })?;
+            let mut fonts = BTreeMap::new();
+            for dir in font_dir.iter() {
+                fonts.extend(PdfConfig::find_fonts(dir)?);
+            }
+            let (default_fonts, named_fonts): (Vec<_>, Vec<_>) =
+                font.into_iter().partition(|(name, _)| name.is_none());
+            fonts.extend(
+                named_fonts
+                    .into_iter()
+                    .filter_map(|(name, path)| Some((name?, path))),
+            );
+
+            let defaults = PdfManifestEntry {
+                script: Some(script),
+                output,
+                dimensions,
+                dpi,
+                font: default_fonts.into_iter().last().map(|(_, path)| path),
+                title,
+                output_dir: None,
+                args: vars.into_iter().collect(),
+                fonts,
+            }
+            .or(&project);
+
+            let imposition = match imposition {
+                Some(layout) => {
+                    let (width, height) = PdfImposition::parse_sheet(&sheet)?;
+                    Some(PdfImposition {
+                        layout,
+                        width,
+                        height,
+                    })
+                }
+                None => None,
+            };
+
+            // Linearizing happens after the PDF is built, so check for qpdf before spending time
+            // building PDFs that would fail to save
+            if linearize {
+                Runtime::check_linearize().context("--linearize requires qpdf")?;
+            }
+
+            let opts = MakeOpts {
+                allow_bytecode,
+                debug_layout,
+                dump_json,
+                grayscale,
+                imposition,
+                linearize,
+                native,
+                open,
+                opt_level,
+                optimize,
+                pdfa,
+                preflight,
+                preview_dir,
+                snap_to_pixels,
+                stats,
+                strict,
+                cache: RuntimeCache::new(),
+            };
+
+            if !all {
+                return make(MakeJob::new(defaults, &opts)?, &opts);
+            }
+
+            info!("Loading manifest {manifest}");
+            let manifest = PdfManifest::load(&manifest)?;
+            let jobs = manifest
+                .entries
+                .into_iter()
+                .map(|entry| MakeJob::new(entry.or(&defaults), &opts))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            MakeJob::check_outputs(&jobs)?;
+
+            // Each PDF is built independently with its own runtime, so build them in parallel
+            let cnt = jobs.len();
+            info!("Making {cnt} PDFs");
+            let failed = std::thread::scope(|s| {
+                let handles: Vec<_> = jobs
+                    .into_iter()
+                    .map(|job| {
+                        let output = job.output.clone();
+                        (output, s.spawn(|| make(job, &opts)))
+                    })
+                    .collect();
+
+                let mut failed = 0;
+                for (output, handle) in handles {
+                    match handle.join() {
+                        Ok(Ok(())) => {}
+                        Ok(Err(x)) => {
+                            error!("Failed to make {output}: {x:?}");
+                            failed += 1;
+                        }
+                        Err(_) => {
+                            error!("Failed to make {output}: panicked");
+                            failed += 1;
+                        }
+                    }
+                }
+
+                failed
+            });
+
+            if failed > 0 {
+                anyhow::bail!("{failed} of {cnt} PDFs failed");
+            }
+
+            Ok(())
+        }
+        Commands::Compile {
+            opt_level,
             script,
-            title,
+            output,
         } => {
-            // Translate our dimensions into a width and height we will use for the PDF pages
-            let (width, height) = PdfConfigPage::parse_size(&dimensions, dpi)?;
-
-            // If output is not specified, we will use the title with a .pdf extension
             let output = output.unwrap_or_else(|| {
-                format!("{}.pdf", title.replace(|c: char| !c.is_alphanumeric(), "_"))
+                std::path::Path::new(&script)
+                    .with_extension("luac")
+                    .to_string_lossy()
+                    .to_string()
             });
-
-            // Build our initial configuration based on the commandline arguments and defaults
+            let default_config = PdfConfig::default();
             let config = PdfConfig {
-                page: PdfConfigPage {
-                    dpi,
-                    font,
-                    width,
-                    height,
-                    ..Default::default()
-                },
-                title,
+                opt_level: opt_level.unwrap_or(default_config.opt_level),
                 script,
+                ..default_config
             };
 
-            // Do the actual process of
-            //
-            // 1. Creating a runtime for the given configuration
-            // 2. Setup the configuration by running a Lua script to modify it
-            // 3. Translate the internal pages & objects into the actual PDF
-            // 4. Save the PDF to disk
-            Runtime::new(config)
-                .setup()
-                .context("Failed to setup PDF")?
+            let bytecode = Runtime::new(config).compile()?;
+            info!("Writing {} bytes to {output}", bytecode.len());
+            std::fs::write(&output, bytecode)
+                .with_context(|| format!("Failed to write {output}"))?;
+
+            Ok(())
+        }
+        Commands::Diff {
+            old,
+            new,
+            visual,
+            dpi,
+            output,
+        } => {
+            let visual = visual.then(|| PdfDiffVisual {
+                dpi,
+                output: output.into(),
+            });
+
+            info!("Comparing {old} to {new}");
+            let diff = PdfDiff::compare(&old, &new, visual.as_ref())
+                .with_context(|| format!("Failed to compare {old} to {new}"))?;
+
+            for page in diff.pages.iter() {
+                let mut changes = page.changes.clone();
+                if let Some(pixels) = page.pixels.as_ref().filter(|x| x.changed > 0) {
+                    changes.push(format!(
+                        "{} pixels changed ({:.2}%)",
+                        pixels.changed,
+                        pixels.changed_percent()
+                    ));
+
+                    if let Some(image) = pixels.image.as_ref() {
+                        changes.push(format!("see {}", image.display()));
+                    }
+                }
+
+                info!("Page {}: {}", page.page, changes.join(", "));
+            }
+
+            if !diff.is_empty() {
+                anyhow::bail!("{} page(s) differ", diff.pages.len());
+            }
+
+            info!("No differences found");
+            Ok(())
+        }
+        Commands::Test {
+            allow_bytecode,
+            dimensions,
+            dpi,
+            golden,
+            native,
+            opt_level,
+            script,
+            strict,
+            update,
+            vars,
+        } => {
+            // Build the PDF into a temporary file that is removed once fingerprinted
+            let output = std::env::temp_dir().join(format!(
+                "makepdf-test-{}-{}.pdf",
+                std::process::id(),
+                rand::random::<u32>()
+            ));
+            let project = load_project(&script)?;
+            let entry = PdfManifestEntry {
+                script: Some(script),
+                output: Some(output.to_string_lossy().to_string()),
+                dimensions,
+                dpi,
+                args: vars.into_iter().collect(),
+                ..Default::default()
+            }
+            .or(&project);
+            let opts = MakeOpts {
+                allow_bytecode,
+                native,
+                opt_level,
+                strict,
+                ..Default::default()
+            };
+            let job = MakeJob::new(entry, &opts)?;
+            let script = job.config.script.clone();
+
+            info!("Testing {script}");
+            let (runtime, report) = Runtime::new(job.config)
+                .test()
+                .context("Failed to setup PDF")?;
+            for result in report.results.iter() {
+                if result.passed() {
+                    info!("{result}");
+                } else {
+                    warn!("{result}");
+                }
+            }
+
+            let failed = report.failed().count();
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} test(s) failed", report.results.len());
+            }
+
+            if !update && !std::path::Path::new(&golden).exists() {
+                info!("Skipping comparison of pages as {golden} does not exist");
+                return Ok(());
+            }
+
+            info!("Building {script}");
+            let result = runtime
                 .build()
                 .context("Failed to build PDF")?
+                .save(&job.output)
+                .context("Failed to save PDF to file")
+                .and_then(|_| PdfGolden::from_pdf(&output));
+            let _ = std::fs::remove_file(&output);
+            let actual = result?;
+
+            if update {
+                info!("Writing {} page(s) to {golden}", actual.pages.len());
+                return actual.save(&golden);
+            }
+
+            let expected = PdfGolden::load(&golden)?;
+            let diff = expected.compare(&actual);
+            for page in diff.iter() {
+                warn!("Page {}: {}", page.page, page.changes.join(", "));
+            }
+
+            if !diff.is_empty() {
+                anyhow::bail!("{} page(s) differ from {golden}", diff.len());
+            }
+
+            info!("All {} page(s) match {golden}", actual.pages.len());
+            Ok(())
+        }
+        Commands::Merge { pdfs, output } => {
+            let mut merge = PdfMerge::new();
+            for pdf in pdfs.iter() {
+                debug!("Loading {pdf}");
+                merge.add_file(pdf)?;
+            }
+
+            info!("Merging {} PDFs into {output}", merge.len());
+            merge
                 .save(&output)
-                .context("Failed to save PDF to file")?;
+                .with_context(|| format!("Failed to merge into {output}"))?;
+
+            Ok(())
+        }
+        Commands::Types { output } => {
+            let types = PdfTypes::generate().context("Failed to generate types")?;
+            info!("Writing {} types to {output}", types.types.len());
+            std::fs::write(&output, types.to_definitions())
+                .with_context(|| format!("Failed to write {output}"))?;
 
-            // If indicated, we try to open the PDF automatically
-            if open {
-                info!("Opening {output}");
-                opener::open(&output).with_context(|| format!("Failed to open {output}"))?;
+            Ok(())
+        }
+        Commands::Preview {
+            pdf,
+            format,
+            dpi,
+            pages,
+            output,
+        } => {
+            let preview = PdfPreview {
+                format,
+                dpi,
+                pages: PdfPreview::parse_pages(&pages)?,
+                output: output.into(),
+            };
+
+            info!("Exporting pages of {pdf}");
+            let images = preview
+                .export(&pdf)
+                .with_context(|| format!("Failed to export pages of {pdf}"))?;
+            for image in images {
+                info!("Wrote {}", image.display());
             }
 
             Ok(())
         }
     }
 }
+
+/// Loads the defaults of the project next to `path` (a script or manifest) as a manifest entry,
+/// which is empty if there is no project.
+fn load_project(path: &str) -> anyhow::Result<PdfManifestEntry> {
+    match PdfProject::find(path) {
+        Some(path) => {
+            info!("Loading project {}", path.display());
+            Ok(PdfProject::load(&path)?.to_entry())
+        }
+        None => Ok(PdfManifestEntry::default()),
+    }
+}
+
+/// Options that apply to every PDF made by the `make` command.
+#[derive(Default)]
+struct MakeOpts {
+    allow_bytecode: bool,
+    debug_layout: Option<f32>,
+    dump_json: bool,
+    grayscale: bool,
+    imposition: Option<PdfImposition>,
+    linearize: bool,
+    native: bool,
+    open: bool,
+    opt_level: Option<u8>,
+    optimize: bool,
+    pdfa: bool,
+    preflight: Option<PdfPreflightProfile>,
+    preview_dir: Option<String>,
+    snap_to_pixels: bool,
+    stats: bool,
+    strict: bool,
+    cache: RuntimeCache,
+}
+
+/// Single PDF to make, alongside where to save it.
+struct MakeJob {
+    config: PdfConfig,
+    output: String,
+}
+
+impl MakeJob {
+    /// Creates a job from an `entry` whose fields have all been populated with defaults.
+    fn new(entry: PdfManifestEntry, opts: &MakeOpts) -> anyhow::Result<Self> {
+        let default_config = PdfConfig::default();
+        let default_page = PdfConfigPage::default();
+        let title = entry.title.unwrap_or(default_config.title);
+        let dpi = entry.dpi.unwrap_or(default_page.dpi);
+
+        // Translate our dimensions into a width and height we will use for the PDF pages
+        let (width, height) = match entry.dimensions.as_deref() {
+            Some(dimensions) => PdfConfigPage::parse_size(dimensions, dpi)?,
+            None => (default_page.width, default_page.height),
+        };
+
+        // If output is not specified, we will use the title with a .pdf extension, placing it
+        // within the output directory unless it is absolute
+        let output = entry.output.unwrap_or_else(|| {
+            format!("{}.pdf", title.replace(|c: char| !c.is_alphanumeric(), "_"))
+        });
+        let output = match entry.output_dir.as_deref() {
+            Some(dir) if std::path::Path::new(&output).is_relative() => {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create output directory {dir}"))?;
+                std::path::Path::new(dir)
+                    .join(output)
+                    .to_string_lossy()
+                    .to_string()
+            }
+            _ => output,
+        };
+
+        // Build our initial configuration based on the commandline arguments and defaults
+        let config = PdfConfig {
+            page: PdfConfigPage {
+                dpi,
+                font: entry.font,
+                width,
+                height,
+                snap_to_pixels: opts.snap_to_pixels,
+                grayscale: opts.grayscale,
+                debug_grid: opts.debug_layout,
+                ..default_page
+            },
+            title,
+            script: entry.script.unwrap_or(default_config.script),
+            args: entry.args,
+            fonts: entry.fonts,
+            pdfa: opts.pdfa,
+            optimize: opts.optimize,
+            allow_bytecode: opts.allow_bytecode,
+            opt_level: opts.opt_level.unwrap_or(default_config.opt_level),
+            native: opts.native,
+            strict: opts.strict,
+            imposition: opts.imposition,
+            ..Default::default()
+        };
+
+        Ok(Self { config, output })
+    }
+
+    /// Fails if any of the `jobs` would save to the same file, such as entries of a manifest
+    /// without an output that share a title, as they would overwrite each other.
+    fn check_outputs(jobs: &[MakeJob]) -> anyhow::Result<()> {
+        let mut outputs = std::collections::HashSet::new();
+        for job in jobs {
+            // Compare paths by their components, ignoring any leading ./
+            let path: std::path::PathBuf = std::path::Path::new(&job.output)
+                .components()
+                .filter(|c| !matches!(c, std::path::Component::CurDir))
+                .collect();
+            if !outputs.insert(path) {
+                anyhow::bail!(
+                    "Multiple PDFs would be saved to {}, so give each entry of the manifest its \
+                    own output or title",
+                    job.output
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Makes the PDF described by `job`.
+fn make(job: MakeJob, opts: &MakeOpts) -> anyhow::Result<()> {
+    let MakeJob { config, output } = job;
+
+    // Do the actual process of
+    //
+    // 1. Creating a runtime for the given configuration
+    // 2. Setup the configuration by running a Lua script to modify it
+    // 3. Dump the pages & objects as JSON if requested
+    // 4. Translate the internal pages & objects into the actual PDF
+    // 5. Save the PDF to disk
+    // 6. Linearize the PDF if requested
+    // 7. Report statistics if requested
+    // 8. Validate the PDF against a device if requested
+    // 9. Export previews of the pages if requested
+    let dpi = config.page.dpi;
+    let runtime = Runtime::new(config)
+        .setup_with_cache(&opts.cache)
+        .context("Failed to setup PDF")?;
+
+    if opts.dump_json {
+        let path = std::path::Path::new(&output).with_extension("json");
+        info!("Dumping pages to {}", path.display());
+        let json = runtime.dump_json().context("Failed to dump pages")?;
+        std::fs::write(&path, serde_json::to_string_pretty(&json)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    let runtime = runtime.build().context("Failed to build PDF")?;
+
+    let stats = if opts.linearize {
+        runtime
+            .save_linearized(&output)
+            .context("Failed to save linearized PDF to file")?
+    } else {
+        runtime
+            .save(&output)
+            .context("Failed to save PDF to file")?
+    };
+
+    if opts.stats {
+        info!("Statistics for {output}");
+        for line in stats.to_string().lines() {
+            info!("{line}");
+        }
+    }
+
+    if let Some(profile) = opts.preflight.as_ref() {
+        info!("Running preflight for {}", profile.name);
+        let preflight = PdfPreflight::check(&output, profile)
+            .with_context(|| format!("Failed to run preflight on {output}"))?;
+
+        for check in preflight.checks.iter() {
+            if check.passed() {
+                info!("{check}");
+            } else {
+                warn!("{check}");
+            }
+        }
+
+        if !preflight.passed() {
+            anyhow::bail!("Preflight failed for {}", profile.name);
+        }
+    }
+
+    if let Some(dir) = opts.preview_dir.as_ref() {
+        info!("Exporting previews of {output} to {dir}");
+        let preview = PdfPreview {
+            format: PdfPreviewFormat::Png,
+            dpi,
+            pages: Vec::new(),
+            output: dir.into(),
+        };
+        preview
+            .export(&output)
+            .with_context(|| format!("Failed to export previews of {output}"))?;
+    }
+
+    // If indicated, we try to open the PDF automatically
+    if opts.open {
+        #[cfg(feature = "open")]
+        {
+            info!("Opening {output}");
+            opener::open(&output).with_context(|| format!("Failed to open {output}"))?;
+        }
+
+        #[cfg(not(feature = "open"))]
+        anyhow::bail!("Opening {output} requires makepdf to be built with the open feature");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_job(output: &str) -> MakeJob {
+        MakeJob {
+            config: PdfConfig::default(),
+            output: output.to_string(),
+        }
+    }
+
+    #[test]
+    fn check_outputs_should_succeed_if_outputs_are_unique() {
+        let jobs = [make_job("a.pdf"), make_job("b.pdf"), make_job("out/a.pdf")];
+        assert!(MakeJob::check_outputs(&jobs).is_ok());
+    }
+
+    #[test]
+    fn check_outputs_should_fail_if_outputs_are_the_same_file() {
+        let jobs = [make_job("a.pdf"), make_job("b.pdf"), make_job("./a.pdf")];
+        let err = MakeJob::check_outputs(&jobs).unwrap_err();
+        assert!(err.to_string().contains("./a.pdf"), "{err}");
+    }
+}
@@ -0,0 +1,228 @@
+use crate::pdf::PdfLuaTableExt;
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use mlua::prelude::{FromLua, Lua, LuaError, LuaResult, LuaValue};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Collection of PDFs to build together, loaded from a Lua script that returns a list of tables.
+///
+/// ```lua
+/// return {
+///     { output = "phone.pdf", dimensions = "1080x2400px", dpi = 400, args = { theme = "dark" } },
+///     { output = "a4.pdf", dimensions = "210x297mm", title = "My Planner (A4)" },
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfManifest {
+    /// PDFs to build, in the order they were listed.
+    pub entries: Vec<PdfManifestEntry>,
+}
+
+/// Single PDF to build as part of a manifest, where missing fields use the defaults of the
+/// `make` command.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfManifestEntry {
+    /// Path to the script used to build the PDF.
+    pub script: Option<String>,
+
+    /// Destination for the created PDF file.
+    pub output: Option<String>,
+
+    /// Dimensions (WIDTHxHEIGHT) of the PDF, e.g. `210x297mm`.
+    pub dimensions: Option<String>,
+
+    /// DPI of the PDF.
+    pub dpi: Option<f32>,
+
+    /// Path to custom font to use in place of the default font.
+    pub font: Option<String>,
+
+    /// Title of the PDF document.
+    pub title: Option<String>,
+
+    /// Directory where the PDF is created when its output is relative or not provided.
+    pub output_dir: Option<String>,
+
+    /// Arguments passed to the script as `pdf.args`.
+    pub args: BTreeMap<String, String>,
+
+    /// Paths of fonts registered before the script runs, by name.
+    pub fonts: BTreeMap<String, String>,
+}
+
+impl PdfManifestEntry {
+    /// Returns a copy of the entry, using the fields of `defaults` for any that are missing.
+    ///
+    /// Arguments and fonts are merged, where those of the entry take precedence.
+    pub fn or(self, defaults: &Self) -> Self {
+        Self {
+            script: self.script.or_else(|| defaults.script.clone()),
+            output: self.output.or_else(|| defaults.output.clone()),
+            dimensions: self.dimensions.or_else(|| defaults.dimensions.clone()),
+            dpi: self.dpi.or(defaults.dpi),
+            font: self.font.or_else(|| defaults.font.clone()),
+            title: self.title.or_else(|| defaults.title.clone()),
+            output_dir: self.output_dir.or_else(|| defaults.output_dir.clone()),
+            args: defaults.args.clone().into_iter().chain(self.args).collect(),
+            fonts: defaults
+                .fonts
+                .clone()
+                .into_iter()
+                .chain(self.fonts)
+                .collect(),
+        }
+    }
+}
+
+impl PdfManifest {
+    /// Loads a manifest from the Lua script at `path`.
+    ///
+    /// Relative paths of scripts, outputs, and fonts within the manifest are resolved relative
+    /// to the directory containing the manifest.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let code = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to load manifest '{}'", path.display()))?;
+
+        let mut manifest = Self::from_lua_code(&code)
+            .with_context(|| format!("Failed to parse manifest '{}'", path.display()))?;
+
+        if let Some(dir) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+            let resolve = |x: &mut Option<String>| {
+                if let Some(x) = x.as_mut().filter(|x| Path::new(x.as_str()).is_relative()) {
+                    *x = dir.join(x.as_str()).to_string_lossy().to_string();
+                }
+            };
+
+            for entry in manifest.entries.iter_mut() {
+                resolve(&mut entry.script);
+                resolve(&mut entry.output);
+                resolve(&mut entry.font);
+                resolve(&mut entry.output_dir);
+                for path in entry.fonts.values_mut() {
+                    if Path::new(path.as_str()).is_relative() {
+                        *path = dir.join(path.as_str()).to_string_lossy().to_string();
+                    }
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Parses a manifest from Lua `code` that returns a list of entries.
+    pub fn from_lua_code(code: &str) -> anyhow::Result<Self> {
+        // Manifests are pure data, so run them in a sandbox
+        let lua = Lua::new();
+        lua.sandbox(true)
+            .context("Failed to set sandbox mode on Lua runtime")?;
+
+        let entries: Vec<PdfManifestEntry> =
+            lua.load(code).eval().map_err(|x| anyhow::anyhow!("{x}"))?;
+
+        if entries.is_empty() {
+            anyhow::bail!("Manifest does not contain any entries");
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfManifestEntry {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                script: table.raw_get_ext("script")?,
+                output: table.raw_get_ext("output")?,
+                dimensions: table.raw_get_ext("dimensions")?,
+                dpi: table.raw_get_ext("dpi")?,
+                font: table.raw_get_ext("font")?,
+                title: table.raw_get_ext("title")?,
+                output_dir: table.raw_get_ext("output_dir")?,
+                args: table
+                    .raw_get_ext::<_, Option<_>>("args")?
+                    .unwrap_or_default(),
+                fonts: table
+                    .raw_get_ext::<_, Option<_>>("fonts")?
+                    .unwrap_or_default(),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "manifest entry",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_lua_code_should_parse_entries() {
+        let manifest = PdfManifest::from_lua_code(
+            r#"
+            return {
+                { output = "phone.pdf", dimensions = "1080x2400px", dpi = 400 },
+                { script = "a4.lua", title = "A4", args = { year = "2025" } },
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.entries,
+            vec![
+                PdfManifestEntry {
+                    output: Some(String::from("phone.pdf")),
+                    dimensions: Some(String::from("1080x2400px")),
+                    dpi: Some(400.0),
+                    ..Default::default()
+                },
+                PdfManifestEntry {
+                    script: Some(String::from("a4.lua")),
+                    title: Some(String::from("A4")),
+                    args: [(String::from("year"), String::from("2025"))]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn from_lua_code_should_fail_if_no_entries() {
+        assert!(PdfManifest::from_lua_code("return {}").is_err());
+        assert!(PdfManifest::from_lua_code("return { { dpi = \"abc\" } }").is_err());
+    }
+
+    #[test]
+    fn load_should_resolve_paths_relative_to_manifest() {
+        let dir = std::env::temp_dir().join(format!("makepdf-manifest-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("manifest.lua");
+        std::fs::write(
+            &path,
+            "return { { script = \"planner.lua\", output = \"/abs/out.pdf\", fonts = { body = \"Inter.ttf\" } } }",
+        )
+        .unwrap();
+
+        let manifest = PdfManifest::load(&path).unwrap();
+        assert_eq!(
+            manifest.entries[0].script.as_deref(),
+            Some(dir.join("planner.lua").to_string_lossy().as_ref())
+        );
+        assert_eq!(manifest.entries[0].output.as_deref(), Some("/abs/out.pdf"));
+        assert_eq!(
+            manifest.entries[0].fonts.get("body").map(String::as_str),
+            Some(dir.join("Inter.ttf").to_string_lossy().as_ref())
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,490 @@
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use log::*;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Attributes of pages that can be inherited from the nodes of the page tree containing them.
+const INHERITABLE: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Entries of catalogs that are combined when merging, others being taken from the first PDF.
+const MERGED: [&[u8]; 6] = [
+    b"Type",
+    b"Pages",
+    b"Outlines",
+    b"PageLabels",
+    b"OCProperties",
+    b"PageMode",
+];
+
+/// Entry of the merged outline for a single PDF, described by its title, first page, and the
+/// first and last top-level entries of its own outline (if any).
+type OutlineEntry = (String, ObjectId, Option<(ObjectId, ObjectId)>);
+
+/// Concatenates multiple PDFs (such as the cover, calendar, and notes of a planner made by
+/// different scripts) into a single PDF.
+///
+/// Pages keep their content, links, and annotations. Each PDF gets an entry in the outline
+/// pointing at its first page, with the outline of the PDF nested beneath it. Page labels and
+/// layers are combined, while any other entries of the catalogs (like attachments) are taken
+/// from the first PDF.
+#[derive(Debug, Default)]
+pub struct PdfMerge {
+    /// PDFs to merge in order, alongside the title of their entry in the outline.
+    docs: Vec<(String, Document)>,
+}
+
+impl PdfMerge {
+    /// Creates a new instance without any PDFs to merge.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the total PDFs to merge.
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Returns `true` if there are no PDFs to merge.
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Loads the PDF at `path` to merge after any PDFs already added, titling its entry in the
+    /// outline with the name of the file (without its extension).
+    pub fn add_file(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let doc =
+            Document::load(path).with_context(|| format!("Failed to load {}", path.display()))?;
+        let title = path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.add_doc(title, doc);
+        Ok(())
+    }
+
+    /// Adds `doc` to merge after any PDFs already added, titling its entry in the outline with
+    /// `title`.
+    pub fn add_doc(&mut self, title: impl Into<String>, doc: Document) {
+        self.docs.push((title.into(), doc));
+    }
+
+    /// Merges the PDFs into a single document, failing if there are none to merge.
+    pub fn merge(self) -> anyhow::Result<Document> {
+        if self.docs.is_empty() {
+            anyhow::bail!("No PDFs to merge");
+        }
+
+        let mut merged = Document::with_version("1.7");
+        let mut pages = Vec::new();
+        let mut outlines = Vec::new();
+        let mut labels = Vec::new();
+        let mut has_labels = false;
+        let mut layers = Vec::new();
+        let mut extra: Option<Dictionary> = None;
+
+        for (title, mut doc) in self.docs {
+            // Renumber objects so that they do not collide with those of the other PDFs, which
+            // keeps references (such as the destinations of links) to pages intact
+            doc.renumber_objects_with(merged.max_id + 1);
+            merged.max_id = merged.max_id.max(doc.max_id);
+
+            // Keep the other entries of the first catalog, warning about any entries of later
+            // catalogs that are dropped as a result
+            let catalog = doc.catalog().context("Missing catalog")?.clone();
+            let others = catalog
+                .iter()
+                .filter(|(key, _)| !MERGED.contains(&key.as_slice()));
+            match extra.as_ref() {
+                None => {
+                    extra = Some(Dictionary::from_iter(
+                        others.map(|(k, v)| (k.clone(), v.clone())),
+                    ))
+                }
+                Some(extra) => {
+                    let dropped: Vec<String> = others
+                        .filter(|(key, _)| !extra.has(key))
+                        .map(|(key, _)| String::from_utf8_lossy(key).to_string())
+                        .collect();
+                    if !dropped.is_empty() {
+                        warn!(
+                            "Dropping {} from the catalog of {title}",
+                            dropped.join(", ")
+                        );
+                    }
+                }
+            }
+
+            // Flatten the page tree, copying inherited attributes onto each page
+            let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+            for id in page_ids.iter().copied() {
+                let inherited = inherited_attributes(&doc, id)?;
+                let page = doc.get_dictionary_mut(id)?;
+                for (key, value) in inherited {
+                    page.set(key, value);
+                }
+            }
+
+            if let Some(page) = page_ids.first().copied() {
+                outlines.push((title, page, top_level_outlines(&doc, &catalog)));
+            }
+
+            // Shift the ranges of page labels by the pages before this PDF, numbering pages of
+            // PDFs without labels from 1
+            let nums = page_labels(&doc, &catalog);
+            has_labels |= nums.is_some();
+            match nums {
+                Some(nums) => {
+                    for pair in nums.chunks(2) {
+                        if let [Object::Integer(start), label] = pair {
+                            labels.push(Object::Integer(start + pages.len() as i64));
+                            labels.push(label.clone());
+                        }
+                    }
+                }
+                None => {
+                    labels.push(Object::Integer(pages.len() as i64));
+                    labels.push(Object::Dictionary(Dictionary::from_iter(vec![(
+                        "S",
+                        Object::Name(b"D".to_vec()),
+                    )])));
+                }
+            }
+
+            if let Some(properties) = catalog
+                .get(b"OCProperties")
+                .ok()
+                .and_then(|x| resolve_dict(&doc, x))
+            {
+                layers.push(properties);
+            }
+
+            // Move everything but the structure of the document, which is rebuilt below
+            let mut skipped = structural_ids(&doc, &catalog);
+            skipped.extend(doc.trailer.get(b"Root").and_then(Object::as_reference));
+            for (id, obj) in doc.objects {
+                if !skipped.contains(&id) {
+                    merged.objects.insert(id, obj);
+                }
+            }
+
+            if !merged.trailer.has(b"Info") {
+                if let Ok(info) = doc.trailer.get(b"Info") {
+                    merged.trailer.set("Info", info.clone());
+                }
+            }
+
+            pages.extend(page_ids);
+        }
+
+        // Rebuild the page tree with every page as a direct child of the root
+        let pages_id = merged.new_object_id();
+        for id in pages.iter().copied() {
+            merged
+                .get_dictionary_mut(id)?
+                .set("Parent", Object::Reference(pages_id));
+        }
+        merged.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(pages.len() as i64)),
+                (
+                    "Kids",
+                    Object::Array(pages.iter().copied().map(Object::Reference).collect()),
+                ),
+            ])),
+        );
+
+        let mut catalog = extra.unwrap_or_default();
+        catalog.set("Type", Object::Name(b"Catalog".to_vec()));
+        catalog.set("Pages", Object::Reference(pages_id));
+        catalog.set("PageMode", Object::Name(b"UseOutlines".to_vec()));
+        catalog.set("Outlines", merge_outlines(&mut merged, outlines));
+
+        if has_labels {
+            catalog.set(
+                "PageLabels",
+                Dictionary::from_iter(vec![("Nums", Object::Array(labels))]),
+            );
+        }
+
+        if let Some(properties) = merge_layers(layers) {
+            catalog.set("OCProperties", properties);
+        }
+
+        let catalog_id = merged.add_object(catalog);
+        merged.trailer.set("Root", Object::Reference(catalog_id));
+
+        Ok(merged)
+    }
+
+    /// Merges the PDFs into a single document, saving it to `path`.
+    pub fn save(self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let mut doc = self.merge()?;
+        doc.save(path)
+            .with_context(|| format!("Failed to save {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Returns the attributes of page `id` that it inherits from its ancestors and does not override.
+fn inherited_attributes(doc: &Document, id: ObjectId) -> anyhow::Result<Vec<(Vec<u8>, Object)>> {
+    let page = doc.get_dictionary(id)?;
+    let mut attributes = Vec::new();
+    let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+    while let Some(id) = parent {
+        let node = doc.get_dictionary(id)?;
+        for key in INHERITABLE {
+            if !page.has(key) && !attributes.iter().any(|(x, _)| x == key) {
+                if let Ok(value) = node.get(key) {
+                    attributes.push((key.to_vec(), value.clone()));
+                }
+            }
+        }
+        parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    Ok(attributes)
+}
+
+/// Returns the ids of the first and last top-level entries of the outline of `doc`, if it has an
+/// outline.
+fn top_level_outlines(doc: &Document, catalog: &Dictionary) -> Option<(ObjectId, ObjectId)> {
+    let outlines = catalog
+        .get(b"Outlines")
+        .ok()
+        .and_then(|x| resolve_dict(doc, x))?;
+    let first = outlines.get(b"First").and_then(Object::as_reference).ok()?;
+    let last = outlines.get(b"Last").and_then(Object::as_reference).ok()?;
+    Some((first, last))
+}
+
+/// Returns the flat list of page labels of `doc` as pairs of the index of the first page of each
+/// range and its label, if it has any.
+fn page_labels(doc: &Document, catalog: &Dictionary) -> Option<Vec<Object>> {
+    catalog
+        .get(b"PageLabels")
+        .ok()
+        .and_then(|x| resolve_dict(doc, x))?
+        .get(b"Nums")
+        .and_then(Object::as_array)
+        .ok()
+        .cloned()
+}
+
+/// Returns the ids of the page tree nodes and the root of the outline of `doc`.
+fn structural_ids(doc: &Document, catalog: &Dictionary) -> BTreeSet<ObjectId> {
+    let mut ids = BTreeSet::new();
+    ids.extend(catalog.get(b"Outlines").and_then(Object::as_reference));
+
+    let mut queue: Vec<ObjectId> = catalog
+        .get(b"Pages")
+        .and_then(Object::as_reference)
+        .into_iter()
+        .collect();
+    while let Some(id) = queue.pop() {
+        let Ok(node) = doc.get_dictionary(id) else {
+            continue;
+        };
+
+        if node.get(b"Type").and_then(Object::as_name).ok() == Some(b"Pages".as_slice()) {
+            ids.insert(id);
+            if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+                queue.extend(kids.iter().filter_map(|x| x.as_reference().ok()));
+            }
+        }
+    }
+
+    ids
+}
+
+/// Returns the dictionary `obj`, resolving it if it is a reference.
+fn resolve_dict(doc: &Document, obj: &Object) -> Option<Dictionary> {
+    match obj {
+        Object::Reference(id) => doc.get_dictionary(*id).ok().cloned(),
+        Object::Dictionary(x) => Some(x.clone()),
+        _ => None,
+    }
+}
+
+/// Creates the root of the merged outline with an entry for each PDF, nesting the entries of the
+/// outline of each PDF beneath its entry.
+fn merge_outlines(doc: &mut Document, entries: Vec<OutlineEntry>) -> Object {
+    let root_id = doc.new_object_id();
+    let ids: Vec<ObjectId> = entries.iter().map(|_| doc.new_object_id()).collect();
+
+    for (i, (title, page, children)) in entries.into_iter().enumerate() {
+        let mut entry = Dictionary::from_iter(vec![
+            ("Title", Object::string_literal(title)),
+            ("Parent", Object::Reference(root_id)),
+            (
+                "Dest",
+                Object::Array(vec![Object::Reference(page), Object::Name(b"Fit".to_vec())]),
+            ),
+        ]);
+
+        if i > 0 {
+            entry.set("Prev", Object::Reference(ids[i - 1]));
+        }
+
+        if let Some(next) = ids.get(i + 1) {
+            entry.set("Next", Object::Reference(*next));
+        }
+
+        // Reparent the top-level entries of the outline of the PDF beneath its entry, keeping
+        // them collapsed
+        if let Some((first, last)) = children {
+            let mut cnt = 0;
+            let mut child = Some(first);
+            while let Some(id) = child {
+                let Ok(dict) = doc.get_dictionary_mut(id) else {
+                    break;
+                };
+                dict.set("Parent", Object::Reference(ids[i]));
+                cnt += 1;
+                child = dict
+                    .get(b"Next")
+                    .and_then(Object::as_reference)
+                    .ok()
+                    .filter(|_| id != last);
+            }
+
+            entry.set("First", Object::Reference(first));
+            entry.set("Last", Object::Reference(last));
+            entry.set("Count", Object::Integer(-cnt));
+        }
+
+        doc.objects.insert(ids[i], Object::Dictionary(entry));
+    }
+
+    let mut root = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Outlines".to_vec())),
+        ("Count", Object::Integer(ids.len() as i64)),
+    ]);
+    if let (Some(first), Some(last)) = (ids.first(), ids.last()) {
+        root.set("First", Object::Reference(*first));
+        root.set("Last", Object::Reference(*last));
+    }
+    doc.objects.insert(root_id, Object::Dictionary(root));
+
+    Object::Reference(root_id)
+}
+
+/// Combines the optional content properties (layers) of each PDF, listing every layer and using
+/// the default configuration of the first PDF with the orders and visibility of all layers.
+fn merge_layers(properties: Vec<Dictionary>) -> Option<Dictionary> {
+    let mut iter = properties.into_iter();
+    let mut merged = iter.next()?;
+
+    let mut config = merged
+        .get(b"D")
+        .and_then(Object::as_dict)
+        .cloned()
+        .unwrap_or_default();
+    for properties in iter {
+        append_array(&mut merged, &properties, b"OCGs");
+        if let Ok(other) = properties.get(b"D").and_then(Object::as_dict) {
+            for key in [b"Order".as_slice(), b"ON", b"OFF"] {
+                append_array(&mut config, other, key);
+            }
+        }
+    }
+    merged.set("D", config);
+
+    Some(merged)
+}
+
+/// Appends the array `key` of `other` onto the array `key` of `dict`.
+fn append_array(dict: &mut Dictionary, other: &Dictionary, key: &[u8]) {
+    if let Ok(items) = other.get(key).and_then(Object::as_array) {
+        let mut array = dict
+            .get(key)
+            .and_then(Object::as_array)
+            .cloned()
+            .unwrap_or_default();
+        array.extend(items.iter().cloned());
+        dict.set(key.to_vec(), array);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::{Mm, PdfDocument};
+
+    /// Creates a document named `title` with `cnt` pages, each with a bookmark.
+    fn make_doc(title: &str, cnt: usize) -> Document {
+        let doc = PdfDocument::empty(title);
+        for i in 0..cnt {
+            let (page, _) = doc.add_page(Mm(100.0), Mm(100.0), "page");
+            doc.add_bookmark(format!("{title} {}", i + 1), page);
+        }
+
+        Document::load_mem(&doc.save_to_bytes().unwrap()).unwrap()
+    }
+
+    /// Returns the titles of the entries of the outline starting at `first`, nested entries being
+    /// indented.
+    fn outline_titles(doc: &Document, first: Option<ObjectId>, depth: usize) -> Vec<String> {
+        let mut titles = Vec::new();
+        let mut next = first;
+        while let Some(id) = next {
+            let entry = doc.get_dictionary(id).unwrap();
+            let title = entry.get(b"Title").unwrap().as_str().unwrap();
+            titles.push(format!(
+                "{}{}",
+                "  ".repeat(depth),
+                String::from_utf8_lossy(title)
+            ));
+            titles.extend(outline_titles(
+                doc,
+                entry.get(b"First").and_then(Object::as_reference).ok(),
+                depth + 1,
+            ));
+            next = entry.get(b"Next").and_then(Object::as_reference).ok();
+        }
+        titles
+    }
+
+    #[test]
+    fn merge_should_concatenate_pages_and_nest_outlines() {
+        let mut merge = PdfMerge::new();
+        merge.add_doc("cover", make_doc("cover", 1));
+        merge.add_doc("notes", make_doc("notes", 2));
+        assert_eq!(merge.len(), 2);
+
+        let mut doc = merge.merge().unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 3);
+
+        let outlines = doc
+            .catalog()
+            .unwrap()
+            .get(b"Outlines")
+            .and_then(Object::as_reference)
+            .and_then(|id| doc.get_dictionary(id))
+            .unwrap();
+        let first = outlines.get(b"First").and_then(Object::as_reference).ok();
+        assert_eq!(
+            outline_titles(&doc, first, 0),
+            vec!["cover", "  cover 1", "notes", "  notes 1", "  notes 2"]
+        );
+
+        // The merged document can be saved and loaded again
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        assert_eq!(Document::load_mem(&bytes).unwrap().get_pages().len(), 3);
+    }
+
+    #[test]
+    fn merge_should_fail_without_pdfs() {
+        PdfMerge::new()
+            .merge()
+            .expect_err("Unexpectedly merged nothing");
+    }
+}
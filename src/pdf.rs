@@ -1,16 +1,50 @@
+mod assert;
+mod attachments;
+mod barcode;
+mod calendar;
+mod callout;
 mod common;
 mod config;
 mod context;
+mod define;
+mod device;
+mod icon;
+mod import;
+mod layout;
+mod nav_tabs;
 mod object;
 mod pages;
+mod sections;
+mod test;
+mod theme;
+mod toc;
 mod utils;
+mod watermark;
+mod xobject;
 
+pub use assert::*;
+pub use attachments::*;
+pub use barcode::*;
+pub use calendar::*;
+pub use callout::*;
 pub use common::*;
 pub use config::*;
 pub use context::*;
+pub use define::*;
+pub use device::*;
+pub use icon::*;
+pub use import::*;
+pub use layout::*;
+pub use nav_tabs::*;
 pub use object::*;
 pub use pages::*;
+pub use sections::*;
+pub use test::*;
+pub use theme::*;
+pub use toc::*;
 pub use utils::*;
+pub use watermark::*;
+pub use xobject::*;
 
 use crate::runtime::{RuntimeFontId, RuntimeFonts};
 use mlua::prelude::*;
@@ -35,9 +69,12 @@ impl Pdf {
 
         metatable.raw_set(
             "add",
-            lua.create_function(|lua, path: String| {
+            lua.create_function(|lua, (path, name): (String, Option<String>)| {
                 if let Some(mut fonts) = lua.app_data_mut::<RuntimeFonts>() {
                     let id = fonts.add_from_path(path).map_err(LuaError::external)?;
+                    if let Some(name) = name {
+                        fonts.add_name(name, id);
+                    }
                     Ok(id)
                 } else {
                     Err(LuaError::runtime("Runtime fonts are missing"))
@@ -61,6 +98,33 @@ impl Pdf {
             })?,
         )?;
 
+        metatable.raw_set(
+            "fallbacks",
+            lua.create_function(|lua, ids: Option<Vec<RuntimeFontId>>| {
+                if let Some(mut fonts) = lua.app_data_mut::<RuntimeFonts>() {
+                    if let Some(ids) = ids {
+                        fonts.set_fallback_chain(ids);
+                        Ok(None)
+                    } else {
+                        Ok(Some(fonts.fallback_chain().to_vec()))
+                    }
+                } else {
+                    Err(LuaError::runtime("Runtime fonts are missing"))
+                }
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "get",
+            lua.create_function(|lua, name: String| {
+                if let Some(fonts) = lua.app_data_ref::<RuntimeFonts>() {
+                    Ok(fonts.font_for_name(&name))
+                } else {
+                    Err(LuaError::runtime("Runtime fonts are missing"))
+                }
+            })?,
+        )?;
+
         metatable.raw_set(
             "ids",
             lua.create_function(|lua, ()| {
@@ -89,6 +153,8 @@ impl Pdf {
     }
 
     /// Creates a new Lua table that contains methods to log output.
+    ///
+    /// Each message is prefixed with the script name and line number of the caller.
     fn create_log_table(lua: &Lua) -> LuaResult<LuaTable> {
         let (table, metatable) = lua.create_table_ext()?;
 
@@ -101,53 +167,52 @@ impl Pdf {
             Ok(args_str.join(" "))
         }
 
-        metatable.raw_set(
-            "error",
-            lua.create_function(|_, args: Variadic<LuaValue>| {
-                log::error!("{}", make_arg_str(args)?);
-                Ok(())
-            })?,
-        )?;
+        for (name, level) in [
+            ("error", log::Level::Error),
+            ("warn", log::Level::Warn),
+            ("info", log::Level::Info),
+            ("debug", log::Level::Debug),
+            ("trace", log::Level::Trace),
+        ] {
+            metatable.raw_set(
+                name,
+                lua.create_function(move |lua, args: Variadic<LuaValue>| {
+                    // Level 1 is the Lua function that called this one
+                    let location = lua.inspect_stack(1).and_then(|debug| {
+                        let src = debug.source().short_src?.to_string();
+                        Some(format!("{src}:{}", debug.curr_line()))
+                    });
 
-        metatable.raw_set(
-            "warn",
-            lua.create_function(|_, args: Variadic<LuaValue>| {
-                log::warn!("{}", make_arg_str(args)?);
-                Ok(())
-            })?,
-        )?;
+                    match location {
+                        Some(location) => log::log!(level, "{location}: {}", make_arg_str(args)?),
+                        None => log::log!(level, "{}", make_arg_str(args)?),
+                    }
+                    Ok(())
+                })?,
+            )?;
+        }
 
-        metatable.raw_set(
-            "info",
-            lua.create_function(|_, args: Variadic<LuaValue>| {
-                log::info!("{}", make_arg_str(args)?);
-                Ok(())
-            })?,
-        )?;
+        Ok(table)
+    }
+
+    /// Creates a new Lua table that contains methods to create objects and other manipulation.
+    fn create_object_table(lua: &Lua) -> LuaResult<LuaTable> {
+        let (table, metatable) = lua.create_table_ext()?;
 
         metatable.raw_set(
-            "debug",
-            lua.create_function(|_, args: Variadic<LuaValue>| {
-                log::debug!("{}", make_arg_str(args)?);
-                Ok(())
+            "barcode",
+            lua.create_function(|lua, barcode: PdfBarcode| {
+                PdfObject::Group(barcode.to_group().map_err(LuaError::external)?).into_lua(lua)
             })?,
         )?;
 
         metatable.raw_set(
-            "trace",
-            lua.create_function(|_, args: Variadic<LuaValue>| {
-                log::trace!("{}", make_arg_str(args)?);
-                Ok(())
+            "callout",
+            lua.create_function(|lua, callout: PdfCallout| {
+                PdfObject::Group(callout.lua_to_group(lua)?).into_lua(lua)
             })?,
         )?;
 
-        Ok(table)
-    }
-
-    /// Creates a new Lua table that contains methods to create objects and other manipulation.
-    fn create_object_table(lua: &Lua) -> LuaResult<LuaTable> {
-        let (table, metatable) = lua.create_table_ext()?;
-
         metatable.raw_set(
             "circle",
             lua.create_function(|lua, tbl: LuaTable| {
@@ -166,6 +231,15 @@ impl Pdf {
             })?,
         )?;
 
+        metatable.raw_set(
+            "icon",
+            lua.create_function(
+                |lua, (icon, bounds, opts): (PdfIcon, PdfBounds, PdfIconOpts)| {
+                    PdfObject::Group(icon.to_group(bounds, opts)).into_lua(lua)
+                },
+            )?,
+        )?;
+
         metatable.raw_set(
             "line",
             lua.create_function(|lua, tbl: LuaTable| {
@@ -184,9 +258,37 @@ impl Pdf {
             })?,
         )?;
 
+        metatable.raw_set("shape", Pdf::create_shape_table(lua)?)?;
+
+        metatable.raw_set(
+            "text",
+            lua.create_function(|lua, tbl: LuaTable| {
+                PdfObjectText::from_lua(LuaValue::Table(tbl), lua)
+                    .map(PdfObject::Text)?
+                    .into_lua(lua)
+            })?,
+        )?;
+
         metatable.raw_set(
-            "shape",
+            "toc",
             lua.create_function(|lua, tbl: LuaTable| {
+                PdfObjectToc::from_lua(LuaValue::Table(tbl), lua)
+                    .map(PdfObject::Toc)?
+                    .into_lua(lua)
+            })?,
+        )?;
+
+        Ok(table)
+    }
+
+    /// Creates a new Lua table that is called to create shapes, containing methods to create
+    /// common shapes without needing to calculate their points.
+    fn create_shape_table(lua: &Lua) -> LuaResult<LuaTable> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        metatable.raw_set(
+            "__call",
+            lua.create_function(|lua, (_, tbl): (LuaTable, LuaTable)| {
                 PdfObjectShape::from_lua(LuaValue::Table(tbl), lua)
                     .map(PdfObject::Shape)?
                     .into_lua(lua)
@@ -194,11 +296,49 @@ impl Pdf {
         )?;
 
         metatable.raw_set(
-            "text",
+            "regular",
             lua.create_function(|lua, tbl: LuaTable| {
-                PdfObjectText::from_lua(LuaValue::Table(tbl), lua)
-                    .map(PdfObject::Text)?
-                    .into_lua(lua)
+                let center: Option<PdfPoint> = tbl.raw_get_ext("center")?;
+                let radius: f32 = tbl.raw_get_ext("radius")?;
+                let sides: usize = tbl.raw_get_ext("sides")?;
+                let rotation: Option<f32> = tbl.raw_get_ext("rotation")?;
+                if sides < 3 {
+                    return Err(LuaError::runtime("Regular shape needs at least 3 sides"));
+                }
+
+                let mut shape = PdfObjectShape::from_lua(LuaValue::Table(tbl), lua)?;
+                shape.points = PdfObjectShape::regular_points(
+                    center.unwrap_or_default(),
+                    radius,
+                    sides,
+                    rotation.unwrap_or_default(),
+                );
+                PdfObject::Shape(shape).into_lua(lua)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "star",
+            lua.create_function(|lua, tbl: LuaTable| {
+                let center: Option<PdfPoint> = tbl.raw_get_ext("center")?;
+                let radius: f32 = tbl.raw_get_ext("radius")?;
+                let inner_radius: Option<f32> = tbl.raw_get_ext("inner_radius")?;
+                let points: Option<usize> = tbl.raw_get_ext("points")?;
+                let rotation: Option<f32> = tbl.raw_get_ext("rotation")?;
+                let points = points.unwrap_or(5);
+                if points < 2 {
+                    return Err(LuaError::runtime("Star shape needs at least 2 points"));
+                }
+
+                let mut shape = PdfObjectShape::from_lua(LuaValue::Table(tbl), lua)?;
+                shape.points = PdfObjectShape::star_points(
+                    center.unwrap_or_default(),
+                    radius,
+                    inner_radius.unwrap_or(radius / 2.0),
+                    points,
+                    rotation.unwrap_or_default(),
+                );
+                PdfObject::Shape(shape).into_lua(lua)
             })?,
         )?;
 
@@ -216,11 +356,40 @@ impl<'lua> IntoLua<'lua> for Pdf {
         };
 
         // Add in the API instances to the base table
+        table.raw_set("assert", PdfAssert)?;
+        table.raw_set("attachments", PdfAttachments)?;
+        table.raw_set("calendar", PdfCalendar)?;
+        table.raw_set(
+            "define",
+            lua.create_function(|lua, (name, func): (String, LuaFunction)| {
+                PdfDefinition::register(lua, name, func)
+            })?,
+        )?;
+        table.raw_set("device", PdfDevice)?;
         table.raw_set("font", Pdf::create_font_table(lua)?)?;
+        table.raw_set("import", PdfImport)?;
+        table.raw_set("layout", PdfLayout)?;
         table.raw_set("log", Pdf::create_log_table(lua)?)?;
         table.raw_set("object", Pdf::create_object_table(lua)?)?;
         table.raw_set("pages", PdfPages)?;
+        table.raw_set("sections", PdfSections)?;
+        table.raw_set("test", PdfTest)?;
+        table.raw_set("theme", lua.create_table()?)?;
+        table.raw_set("toc", PdfToc)?;
         table.raw_set("utils", PdfUtils)?;
+        table.raw_set("watermark", PdfWatermark)?;
+        table.raw_set(
+            "when",
+            lua.create_function(|_, (condition, obj): (bool, LuaTable)| {
+                // Objects are hidden instead of removed so they can be placed within lists
+                if !condition {
+                    obj.raw_set("hidden", true)?;
+                }
+
+                Ok(obj)
+            })?,
+        )?;
+        table.raw_set("xobject", PdfXObject)?;
 
         Ok(LuaValue::Table(table))
     }
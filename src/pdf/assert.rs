@@ -0,0 +1,210 @@
+use crate::pdf::{PdfLink, PdfLuaExt};
+use crate::runtime::RuntimePages;
+use mlua::prelude::*;
+
+/// Collection of assertions about the document, failing the script when not met.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfAssert;
+
+impl PdfAssert {
+    /// Fails if the total number of pages is not `count`.
+    pub fn page_count(pages: &RuntimePages, count: usize) -> LuaResult<()> {
+        if pages.len() != count {
+            return Err(LuaError::runtime(format!(
+                "Expected {count} pages, but found {}",
+                pages.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Fails if any link within the document targets a page that does not exist.
+    pub fn link_targets_valid(pages: &RuntimePages) -> LuaResult<()> {
+        let mut errors = Vec::new();
+
+        for page in pages.ids().filter_map(|id| pages.get_page(id)) {
+            for obj in page.objects().iter() {
+                for link in obj.links() {
+                    if let PdfLink::GoTo { page: target, .. } = link {
+                        if pages.get_page(*target).is_none() && !pages.is_ref(*target) {
+                            errors.push(format!(
+                                "Page '{}' links to missing page {target}",
+                                page.title
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(LuaError::runtime(errors.join("\n")));
+        }
+
+        Ok(())
+    }
+
+    /// Fails if any two objects with `tag` overlap on the same page.
+    ///
+    /// Objects are compared using their bounds, so objects that only touch do not overlap.
+    pub fn no_overlaps(lua: &Lua, pages: &RuntimePages, tag: &str) -> LuaResult<()> {
+        let mut errors = Vec::new();
+
+        for page in pages.ids().filter_map(|id| pages.get_page(id)) {
+            let objects = page.objects();
+            let bounds = objects
+                .iter()
+                .flat_map(|obj| obj.find_tagged(tag))
+                .map(|obj| obj.lua_bounds(lua))
+                .collect::<LuaResult<Vec<_>>>()?;
+
+            for (i, a) in bounds.iter().enumerate() {
+                for b in bounds.iter().skip(i + 1) {
+                    if a.overlaps(b) {
+                        errors.push(format!(
+                            "Objects tagged '{tag}' overlap on page '{}': {:?} and {:?}",
+                            page.title,
+                            a.to_coords_f32(),
+                            b.to_coords_f32(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(LuaError::runtime(errors.join("\n")));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfAssert {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        metatable.raw_set(
+            "page_count",
+            lua.create_function(|lua, count: usize| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Self::page_count(&pages, count)
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "link_targets_valid",
+            lua.create_function(|lua, ()| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Self::link_targets_valid(&pages)
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "no_overlaps",
+            lua.create_function(|lua, tag: String| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Self::no_overlaps(lua, &pages, &tag)
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    /// Creates a Lua runtime with the global pdf instance and empty pages.
+    fn new_lua() -> Lua {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+        lua
+    }
+
+    #[test]
+    fn page_count_should_fail_if_count_does_not_match() {
+        let lua = new_lua();
+
+        lua.load(chunk! {
+            pdf.pages.create("one")
+            pdf.pages.create("two")
+            pdf.assert.page_count(2)
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        lua.load(chunk!(pdf.assert.page_count(3)))
+            .exec()
+            .expect_err("Assertion unexpectedly succeeded");
+    }
+
+    #[test]
+    fn link_targets_valid_should_fail_if_link_targets_missing_page() {
+        let lua = new_lua();
+
+        lua.load(chunk! {
+            local page = pdf.pages.get(pdf.pages.create("one"))
+            page.push(pdf.object.rect({ 0, 0, 1, 1, link = page.id }))
+            page.push(pdf.object.group({
+                pdf.object.rect({ 0, 0, 1, 1, link = "https://example.com" }),
+            }))
+            pdf.assert.link_targets_valid()
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        lua.load(chunk! {
+            local page = pdf.pages.get(pdf.pages.ids()[1])
+            page.push(pdf.object.group({
+                pdf.object.rect({ 0, 0, 1, 1, link = page.id + 1 }),
+            }))
+            pdf.assert.link_targets_valid()
+        })
+        .exec()
+        .expect_err("Assertion unexpectedly succeeded");
+    }
+
+    #[test]
+    fn no_overlaps_should_fail_if_tagged_objects_overlap() {
+        let lua = new_lua();
+
+        lua.load(chunk! {
+            local page = pdf.pages.get(pdf.pages.create("one"))
+            page.push(pdf.object.rect({ 0, 0, 10, 10, tag = "block" }))
+            page.push(pdf.object.rect({ 10, 0, 20, 10, tag = "block" }))
+
+            // Untagged objects are ignored
+            page.push(pdf.object.rect({ 5, 5, 15, 15 }))
+
+            pdf.assert.no_overlaps("block")
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        lua.load(chunk! {
+            local page = pdf.pages.get(pdf.pages.ids()[1])
+            page.push(pdf.object.group({
+                pdf.object.rect({ 15, 5, 25, 15, tag = "block" }),
+            }))
+            pdf.assert.no_overlaps("block")
+        })
+        .exec()
+        .expect_err("Assertion unexpectedly succeeded");
+    }
+}
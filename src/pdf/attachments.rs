@@ -0,0 +1,123 @@
+use crate::pdf::{PdfLuaExt, PdfLuaTableExt};
+use crate::runtime::{RuntimeAttachment, RuntimeAttachments, RuntimeFiles};
+use mlua::prelude::*;
+use std::path::Path;
+
+/// Collection of attachments functions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfAttachments;
+
+impl PdfAttachments {
+    /// Reads the file described by `tbl` to be embedded within the PDF when saved.
+    ///
+    /// Supports the following fields:
+    ///
+    /// * `path` - path to the file to embed, which must be within an allowed directory.
+    /// * `name` - name of the file within the PDF, defaulting to the file name of `path`.
+    /// * `description` - description of the file shown by viewers.
+    /// * `mime` - MIME type of the file, e.g. `text/csv`.
+    pub fn add(lua: &Lua, tbl: LuaTable) -> LuaResult<()> {
+        let path: String = tbl.raw_get_ext("path")?;
+        let name = match tbl.raw_get_ext::<_, Option<String>>("name")? {
+            Some(name) => name,
+            None => Path::new(&path)
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .ok_or_else(|| LuaError::runtime(format!("Cannot determine name of '{path}'")))?,
+        };
+
+        let data = if let Some(files) = lua.app_data_ref::<RuntimeFiles>() {
+            files
+                .read(&path)
+                .map_err(|x| LuaError::runtime(format!("Failed to read '{path}': {x}")))?
+        } else {
+            return Err(LuaError::runtime("Runtime files are missing"));
+        };
+
+        let attachment = RuntimeAttachment {
+            name,
+            data,
+            description: tbl.raw_get_ext("description")?,
+            mime: tbl.raw_get_ext("mime")?,
+        };
+
+        if let Some(mut attachments) = lua.app_data_mut::<RuntimeAttachments>() {
+            attachments.add(attachment).map_err(LuaError::runtime)
+        } else {
+            Err(LuaError::runtime("Runtime attachments are missing"))
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfAttachments {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to add a file to embed within the PDF.
+        metatable.raw_set(
+            "add",
+            lua.create_function(|lua, tbl: LuaTable| PdfAttachments::add(lua, tbl))?,
+        )?;
+
+        // Function to return the names of all attachments as a list.
+        metatable.raw_set(
+            "names",
+            lua.create_function(|lua, ()| {
+                if let Some(attachments) = lua.app_data_ref::<RuntimeAttachments>() {
+                    Ok(attachments
+                        .iter()
+                        .map(|x| x.name.clone())
+                        .collect::<Vec<_>>())
+                } else {
+                    Err(LuaError::runtime("Runtime attachments are missing"))
+                }
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_add_attachments_in_lua() {
+        let dir = std::env::temp_dir().join(format!("makepdf-attach-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("plan.csv"), "a,b\n1,2\n").unwrap();
+        let path = dir.join("plan.csv").to_string_lossy().to_string();
+
+        let lua = Lua::new();
+        let mut files = RuntimeFiles::new();
+        files.allow(&dir).unwrap();
+        lua.set_app_data(files);
+        lua.set_app_data(RuntimeAttachments::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let path_copy = path.clone();
+        lua.load(chunk! {
+            pdf.attachments.add({ path = $path_copy })
+            pdf.attachments.add({ path = $path_copy, name = "data.csv", mime = "text/csv" })
+            pdf.utils.assert_deep_equal(pdf.attachments.names(), { "plan.csv", "data.csv" })
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        // Names must be unique
+        lua.load(chunk!(pdf.attachments.add({ path = $path })))
+            .exec()
+            .expect_err("Unexpectedly added duplicate attachment");
+
+        let attachments = lua.app_data_ref::<RuntimeAttachments>().unwrap();
+        let data = attachments.iter().find(|x| x.name == "data.csv").unwrap();
+        assert_eq!(data.data, b"a,b\n1,2\n");
+        assert_eq!(data.mime.as_deref(), Some("text/csv"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
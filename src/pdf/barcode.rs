@@ -0,0 +1,516 @@
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfLink, PdfLuaTableExt, PdfObject, PdfObjectGroup, PdfObjectRect,
+    PdfPaintMode,
+};
+use mlua::prelude::*;
+use printpdf::Mm;
+
+/// Widths of the alternating bars and spaces of each Code 128 symbol, where the last symbol is the
+/// stop pattern.
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+/// Code 128 symbols that start data encoded using code set B (printable ASCII) or C (pairs of
+/// digits), and the symbol that stops the barcode.
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+/// Modules of each digit using the left-hand odd parity (L) encoding of EAN, where the even parity
+/// (G) and right-hand (R) encodings are derived from them.
+const EAN_L_PATTERNS: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+
+/// Parity of the six left-hand digits of an EAN-13, determined by its first digit, where `G`
+/// marks even parity.
+const EAN13_PARITIES: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLG", "LGLGLG", "LGLGGL",
+    "LGGLGL",
+];
+
+/// Format of a barcode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfBarcodeFormat {
+    /// Code 128, encoding printable ASCII.
+    #[default]
+    Code128,
+    /// EAN-13, encoding 12 digits followed by a check digit.
+    Ean13,
+    /// EAN-8, encoding 7 digits followed by a check digit.
+    Ean8,
+}
+
+impl PdfBarcodeFormat {
+    /// Every format alongside its name.
+    pub const ALL: [(&'static str, Self); 3] = [
+        ("code128", Self::Code128),
+        ("ean13", Self::Ean13),
+        ("ean8", Self::Ean8),
+    ];
+
+    /// Returns the format with `name`, if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|(x, _)| *x == name)
+            .map(|(_, format)| format)
+    }
+
+    /// Returns the name of the format.
+    pub fn to_name(self) -> &'static str {
+        Self::ALL
+            .into_iter()
+            .find(|(_, format)| *format == self)
+            .map(|(name, _)| name)
+            .unwrap_or_default()
+    }
+}
+
+/// One-dimensional barcode drawn as vector bars, such as for inventory pages that get scanned.
+///
+/// The bars fill the bounds of the barcode, so scanners need space to be left around it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfBarcode {
+    pub format: PdfBarcodeFormat,
+    /// Data to encode, where EAN data can leave out its check digit to have it calculated.
+    pub data: String,
+    pub bounds: PdfBounds,
+    /// Color of the bars, defaulting to the fill color of the page.
+    pub color: Option<PdfColor>,
+    pub depth: Option<i64>,
+    pub link: Option<PdfLink>,
+}
+
+impl PdfBarcode {
+    /// Returns the modules of the barcode from left to right, where each is true for a bar and
+    /// false for a space, failing if the data cannot be encoded by the format.
+    pub fn to_modules(&self) -> anyhow::Result<Vec<bool>> {
+        match self.format {
+            PdfBarcodeFormat::Code128 => code128_modules(&self.data),
+            PdfBarcodeFormat::Ean13 => ean13_modules(&self.data),
+            PdfBarcodeFormat::Ean8 => ean8_modules(&self.data),
+        }
+    }
+
+    /// Returns a group of filled rects, one for each bar spanning the height of the bounds,
+    /// failing if the data cannot be encoded by the format.
+    pub fn to_group(&self) -> anyhow::Result<PdfObjectGroup> {
+        let modules = self.to_modules()?;
+        let module_width = self.bounds.width().0 / modules.len() as f32;
+
+        // Adjacent bar modules are merged into a single rect to avoid seams between them
+        let mut objects = Vec::new();
+        let mut start = None;
+        for (i, bar) in modules.iter().copied().chain([false]).enumerate() {
+            match (bar, start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    start = None;
+                    objects.push(PdfObject::Rect(PdfObjectRect {
+                        bounds: PdfBounds::from_coords(
+                            self.bounds.ll.x + Mm(s as f32 * module_width),
+                            self.bounds.ll.y,
+                            self.bounds.ll.x + Mm(i as f32 * module_width),
+                            self.bounds.ur.y,
+                        ),
+                        depth: self.depth,
+                        fill_color: self.color,
+                        mode: Some(PdfPaintMode::fill()),
+                        ..Default::default()
+                    }));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(PdfObjectGroup {
+            objects,
+            link: self.link.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Appends the modules of a Code 128 symbol to `modules`, given the widths of its alternating bars
+/// and spaces.
+fn push_widths(modules: &mut Vec<bool>, widths: &str) {
+    for (i, width) in widths.bytes().enumerate() {
+        let bar = i & 1 == 0;
+        modules.extend(std::iter::repeat_n(bar, (width - b'0') as usize));
+    }
+}
+
+/// Encodes `data` as Code 128, using code set C for data made entirely of pairs of digits and
+/// code set B otherwise.
+fn code128_modules(data: &str) -> anyhow::Result<Vec<bool>> {
+    if data.is_empty() {
+        anyhow::bail!("Code 128 data is empty");
+    }
+
+    let is_digits = data.bytes().all(|b| b.is_ascii_digit());
+    let mut values = if is_digits && data.len() & 1 == 0 {
+        let mut values = vec![CODE128_START_C];
+        for pair in data.as_bytes().chunks(2) {
+            values.push(((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize);
+        }
+        values
+    } else {
+        let mut values = vec![CODE128_START_B];
+        for ch in data.chars() {
+            match ch {
+                ' '..='\u{7f}' => values.push(ch as usize - ' ' as usize),
+                _ => anyhow::bail!("Code 128 cannot encode {ch:?}"),
+            }
+        }
+        values
+    };
+
+    // Checksum weighs each symbol by its position, where the start symbol has a weight of one
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| i.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    let mut modules = Vec::new();
+    for value in values {
+        push_widths(&mut modules, CODE128_PATTERNS[value]);
+    }
+    Ok(modules)
+}
+
+/// Returns the digits of EAN `data` of `len` digits including its check digit, calculating the
+/// check digit when it is left out and failing when a provided one is wrong.
+fn ean_digits(data: &str, len: usize) -> anyhow::Result<Vec<u8>> {
+    let mut digits = Vec::new();
+    for ch in data.chars() {
+        match ch.to_digit(10) {
+            Some(digit) => digits.push(digit as u8),
+            None => anyhow::bail!("EAN-{len} cannot encode {ch:?}"),
+        }
+    }
+
+    if digits.len() != len && digits.len() != len - 1 {
+        anyhow::bail!("EAN-{len} needs {} or {len} digits", len - 1);
+    }
+
+    // Digits are weighted alternately by 3 and 1 from the right of the data before the check digit
+    let sum = digits[..len - 1]
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| *digit as u32 * if i & 1 == 0 { 3 } else { 1 })
+        .sum::<u32>();
+    let check = ((10 - sum % 10) % 10) as u8;
+
+    match digits.get(len - 1) {
+        Some(x) if *x != check => anyhow::bail!("EAN-{len} check digit should be {check}"),
+        Some(_) => {}
+        None => digits.push(check),
+    }
+
+    Ok(digits)
+}
+
+/// Returns the modules of an EAN digit using the L, G, or R encoding.
+fn ean_digit(digit: u8, encoding: char) -> String {
+    let l = EAN_L_PATTERNS[digit as usize];
+    let r: String = l
+        .chars()
+        .map(|x| if x == '0' { '1' } else { '0' })
+        .collect();
+    match encoding {
+        'L' => l.to_string(),
+        'G' => r.chars().rev().collect(),
+        _ => r,
+    }
+}
+
+/// Appends EAN modules written as a string of `1` bars and `0` spaces to `modules`.
+fn push_bits(modules: &mut Vec<bool>, bits: &str) {
+    modules.extend(bits.chars().map(|x| x == '1'));
+}
+
+/// Encodes `data` as EAN-13, where the first digit sets the parity of the left-hand digits.
+fn ean13_modules(data: &str) -> anyhow::Result<Vec<bool>> {
+    let digits = ean_digits(data, 13)?;
+    let parity = EAN13_PARITIES[digits[0] as usize];
+
+    let mut modules = Vec::new();
+    push_bits(&mut modules, "101");
+    for (digit, encoding) in digits[1..7].iter().zip(parity.chars()) {
+        push_bits(&mut modules, &ean_digit(*digit, encoding));
+    }
+    push_bits(&mut modules, "01010");
+    for digit in &digits[7..] {
+        push_bits(&mut modules, &ean_digit(*digit, 'R'));
+    }
+    push_bits(&mut modules, "101");
+    Ok(modules)
+}
+
+/// Encodes `data` as EAN-8.
+fn ean8_modules(data: &str) -> anyhow::Result<Vec<bool>> {
+    let digits = ean_digits(data, 8)?;
+
+    let mut modules = Vec::new();
+    push_bits(&mut modules, "101");
+    for digit in &digits[..4] {
+        push_bits(&mut modules, &ean_digit(*digit, 'L'));
+    }
+    push_bits(&mut modules, "01010");
+    for digit in &digits[4..] {
+        push_bits(&mut modules, &ean_digit(*digit, 'R'));
+    }
+    push_bits(&mut modules, "101");
+    Ok(modules)
+}
+
+impl<'lua> IntoLua<'lua> for PdfBarcodeFormat {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(self.to_name()).map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfBarcodeFormat {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => {
+                let name = s.to_string_lossy();
+                Self::from_name(name.as_ref()).ok_or_else(|| LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.barcode_format",
+                    message: Some(format!("unknown barcode format: {name}")),
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.barcode_format",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfBarcode {
+    /// Converts from a table with `data` and `bounds`, alongside an optional `format` that
+    /// defaults to Code 128.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                format: table
+                    .raw_get_ext::<_, Option<_>>("format")?
+                    .unwrap_or_default(),
+                data: table.raw_get_ext("data")?,
+                bounds: table.raw_get_ext("bounds")?,
+                color: table.raw_get_ext("color")?,
+                depth: table.raw_get_ext("depth")?,
+                link: table.raw_get_ext("link")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.barcode",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    /// Converts modules into a string of `1` bars and `0` spaces.
+    fn to_bits(modules: &[bool]) -> String {
+        modules.iter().map(|x| if *x { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn code128_patterns_should_be_distinct_with_eleven_modules() {
+        for (i, pattern) in CODE128_PATTERNS.iter().enumerate() {
+            let width: u32 = pattern.bytes().map(|x| (x - b'0') as u32).sum();
+            let expected = if i == CODE128_STOP { 13 } else { 11 };
+            assert_eq!(width, expected, "Symbol {i} has width {width}");
+            assert!(
+                !CODE128_PATTERNS[..i].contains(pattern),
+                "Symbol {i} is repeated"
+            );
+        }
+    }
+
+    #[test]
+    fn to_modules_should_encode_code128_with_checksum() {
+        let barcode = PdfBarcode {
+            data: String::from("AB"),
+            ..Default::default()
+        };
+
+        // Start B (104) + A (33) * 1 + B (34) * 2 = 205, whose checksum is 205 % 103 = 102
+        let mut expected = Vec::new();
+        for value in [104, 33, 34, 102, 106] {
+            push_widths(&mut expected, CODE128_PATTERNS[value]);
+        }
+        assert_eq!(barcode.to_modules().unwrap(), expected);
+
+        // Pairs of digits use code set C: Start C (105) + 12 * 1 + 34 * 2 = 185, whose checksum
+        // is 185 % 103 = 82
+        let barcode = PdfBarcode {
+            data: String::from("1234"),
+            ..Default::default()
+        };
+        let mut expected = Vec::new();
+        for value in [105, 12, 34, 82, 106] {
+            push_widths(&mut expected, CODE128_PATTERNS[value]);
+        }
+        assert_eq!(barcode.to_modules().unwrap(), expected);
+
+        let barcode = PdfBarcode {
+            data: String::from("café"),
+            ..Default::default()
+        };
+        barcode
+            .to_modules()
+            .expect_err("Unexpectedly encoded non-ASCII character");
+    }
+
+    #[test]
+    fn to_modules_should_encode_ean_with_check_digit() {
+        let barcode = PdfBarcode {
+            format: PdfBarcodeFormat::Ean13,
+            data: String::from("400638133393"),
+            ..Default::default()
+        };
+        let modules = barcode.to_modules().unwrap();
+        assert_eq!(modules.len(), 95);
+        assert_eq!(
+            to_bits(&modules),
+            [
+                "101", "0001101", "0100111", "0101111", "0111101", "0001001", "0110011", "01010",
+                "1000010", "1000010", "1000010", "1110100", "1000010", "1100110", "101",
+            ]
+            .concat()
+        );
+
+        // Providing the right check digit is the same as leaving it out
+        let full = PdfBarcode {
+            data: String::from("4006381333931"),
+            ..barcode.clone()
+        };
+        assert_eq!(full.to_modules().unwrap(), modules);
+
+        let wrong = PdfBarcode {
+            data: String::from("4006381333932"),
+            ..barcode.clone()
+        };
+        wrong
+            .to_modules()
+            .expect_err("Unexpectedly encoded wrong check digit");
+
+        let barcode = PdfBarcode {
+            format: PdfBarcodeFormat::Ean8,
+            data: String::from("9638507"),
+            ..Default::default()
+        };
+        let modules = barcode.to_modules().unwrap();
+        assert_eq!(modules.len(), 67);
+        assert_eq!(
+            to_bits(&modules),
+            [
+                "101", "0001011", "0101111", "0111101", "0110111", "01010", "1001110", "1110010",
+                "1000100", "1011100", "101",
+            ]
+            .concat()
+        );
+    }
+
+    #[test]
+    fn to_group_should_merge_adjacent_bars_into_rects() {
+        let barcode = PdfBarcode {
+            format: PdfBarcodeFormat::Ean8,
+            data: String::from("96385074"),
+            bounds: PdfBounds::from_coords_f32(0.0, 0.0, 67.0, 20.0),
+            ..Default::default()
+        };
+        let group = barcode.to_group().unwrap();
+
+        // Start guard is a 1 module bar followed by a 1 module space and bar
+        let rects: Vec<PdfBounds> = group
+            .objects
+            .iter()
+            .map(|obj| match obj {
+                PdfObject::Rect(x) => x.bounds,
+                obj => panic!("Unexpected object {obj:?}"),
+            })
+            .collect();
+        assert_eq!(rects[0], PdfBounds::from_coords_f32(0.0, 0.0, 1.0, 20.0));
+        assert_eq!(rects[1], PdfBounds::from_coords_f32(2.0, 0.0, 3.0, 20.0));
+
+        // Bars cover as many modules as are set
+        let bars = barcode
+            .to_modules()
+            .unwrap()
+            .into_iter()
+            .filter(|x| *x)
+            .count();
+        let width: f32 = rects.iter().map(|x| x.width().0).sum();
+        assert!((width - bars as f32).abs() < 0.001);
+    }
+
+    #[test]
+    fn should_create_barcodes_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let group: PdfObjectGroup = lua
+            .load(chunk! {
+                return pdf.object.barcode({
+                    format = "ean13",
+                    data = "400638133393",
+                    bounds = { 0, 0, 38, 20 },
+                    color = "#ff0000",
+                })
+            })
+            .eval()
+            .unwrap();
+        match &group.objects[0] {
+            PdfObject::Rect(rect) => {
+                assert_eq!(rect.fill_color, Some(PdfColor::from_rgb_u8(255, 0, 0)))
+            }
+            obj => panic!("Unexpected object {obj:?}"),
+        }
+
+        lua.load(
+            chunk!(pdf.object.barcode({ format = "qr", data = "1", bounds = { 0, 0, 1, 1 } })),
+        )
+        .exec()
+        .expect_err("Unexpectedly created barcode of unknown format");
+        lua.load(
+            chunk!(pdf.object.barcode({ format = "ean8", data = "abc", bounds = { 0, 0, 1, 1 } })),
+        )
+        .exec()
+        .expect_err("Unexpectedly created barcode of invalid data");
+    }
+}
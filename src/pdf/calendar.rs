@@ -0,0 +1,173 @@
+mod ics;
+
+pub use ics::{parse_ics, IcsEvent, IcsFrequency, IcsRecurrence};
+
+use crate::pdf::{PdfDate, PdfLuaExt, PdfLuaTableExt, PdfUtils};
+use mlua::prelude::*;
+
+/// Event on a calendar, such as a birthday or appointment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfCalendarEvent {
+    /// Date when the event occurs.
+    pub date: PdfDate,
+
+    /// Short description of the event.
+    pub summary: String,
+
+    /// If true, the event spans the entire day rather than a specific time.
+    pub all_day: bool,
+}
+
+/// Collection of calendar functions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfCalendar;
+
+impl PdfCalendar {
+    /// Parses iCalendar `text` into events sorted by date, limited to those occurring within
+    /// `from` and `to` (inclusive) when provided.
+    ///
+    /// Recurring events are repeated for each occurrence, which requires `to` when the event
+    /// repeats forever.
+    pub fn parse_ics(
+        text: &str,
+        from: Option<PdfDate>,
+        to: Option<PdfDate>,
+    ) -> Vec<PdfCalendarEvent> {
+        let mut events: Vec<PdfCalendarEvent> = parse_ics(text)
+            .into_iter()
+            .flat_map(|event| {
+                event
+                    .occurrences(from.map(|x| *x), to.map(|x| *x))
+                    .into_iter()
+                    .map(move |date| PdfCalendarEvent {
+                        date: date.into(),
+                        summary: event.summary.clone(),
+                        all_day: event.all_day,
+                    })
+            })
+            .collect();
+
+        events.sort_by_key(|event| *event.date);
+        events
+    }
+
+    /// Retrieves the optional `from` and `to` dates from Lua options.
+    fn range_from_opts(opts: Option<LuaTable>) -> LuaResult<(Option<PdfDate>, Option<PdfDate>)> {
+        match opts {
+            Some(opts) => Ok((opts.raw_get_ext("from")?, opts.raw_get_ext("to")?)),
+            None => Ok((None, None)),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfCalendarEvent {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("date", self.date)?;
+        table.raw_set("summary", self.summary)?;
+        table.raw_set("all_day", self.all_day)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfCalendarEvent {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                date: table.raw_get_ext("date")?,
+                summary: table.raw_get_ext("summary")?,
+                all_day: table.raw_get_ext("all_day")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.calendar.event",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfCalendar {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to load events from an iCalendar file.
+        metatable.raw_set(
+            "load_ics",
+            lua.create_function(|lua, (path, opts): (String, Option<LuaTable>)| {
+                let text = PdfUtils::read_to_string(lua, &path)?;
+                let (from, to) = Self::range_from_opts(opts)?;
+                Ok(Self::parse_ics(&text, from, to))
+            })?,
+        )?;
+
+        // Function to parse events from iCalendar text.
+        metatable.raw_set(
+            "parse_ics",
+            lua.create_function(|_, (text, opts): (String, Option<LuaTable>)| {
+                let (from, to) = Self::range_from_opts(opts)?;
+                Ok(Self::parse_ics(&text, from, to))
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_parse_ics_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let text = "BEGIN:VCALENDAR\n\
+                    BEGIN:VEVENT\n\
+                    DTSTART:20240920T140000Z\n\
+                    SUMMARY:Appointment\n\
+                    END:VEVENT\n\
+                    BEGIN:VEVENT\n\
+                    DTSTART;VALUE=DATE:19900915\n\
+                    RRULE:FREQ=YEARLY\n\
+                    SUMMARY:Birthday\n\
+                    END:VEVENT\n\
+                    END:VCALENDAR\n";
+
+        lua.load(chunk! {
+            local events = pdf.calendar.parse_ics($text, { from = "2024-01-01", to = "2025-12-31" })
+            pdf.utils.assert_deep_equal(#events, 3)
+
+            pdf.utils.assert_deep_equal(events[1].summary, "Birthday")
+            pdf.utils.assert_deep_equal(tostring(events[1].date), "2024-09-15")
+            pdf.utils.assert_deep_equal(events[1].all_day, true)
+
+            pdf.utils.assert_deep_equal(events[2].summary, "Appointment")
+            pdf.utils.assert_deep_equal(tostring(events[2].date), "2024-09-20")
+            pdf.utils.assert_deep_equal(events[2].all_day, false)
+
+            pdf.utils.assert_deep_equal(events[3].summary, "Birthday")
+            pdf.utils.assert_deep_equal(tostring(events[3].date), "2025-09-15")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_fail_to_load_ics_that_does_not_exist() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk!(pdf.calendar.load_ics("does-not-exist.ics")))
+            .exec()
+            .expect_err("Unexpectedly loaded missing file");
+    }
+}
@@ -0,0 +1,423 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use log::*;
+
+/// Maximum number of occurrences generated for a single recurring event.
+const MAX_OCCURRENCES: usize = 10_000;
+
+/// Event parsed from an iCalendar (`.ics`) file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IcsEvent {
+    /// Date when the event (first) starts.
+    pub start: NaiveDate,
+
+    /// If true, the event spans entire days rather than a specific time.
+    pub all_day: bool,
+
+    /// Short description of the event.
+    pub summary: String,
+
+    /// How the event repeats, if at all.
+    pub recurrence: Option<IcsRecurrence>,
+}
+
+/// Frequency that a recurring event repeats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IcsFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Simplified recurrence rule (`RRULE`) supporting a frequency, interval, count, and end date.
+///
+/// Other parts of a rule (e.g. `BYDAY`) are ignored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IcsRecurrence {
+    pub frequency: IcsFrequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+}
+
+impl IcsEvent {
+    /// Returns the dates the event occurs that fall within `from` and `to` (inclusive).
+    ///
+    /// Recurring events without a count or end date are only repeated when `to` is provided.
+    pub fn occurrences(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<NaiveDate> {
+        let in_range = |date: &NaiveDate| {
+            from.iter().all(|from| date >= from) && to.iter().all(|to| date <= to)
+        };
+
+        let Some(rule) = self.recurrence else {
+            return [self.start].into_iter().filter(in_range).collect();
+        };
+
+        // Without an end, we cannot repeat forever, so only include the first occurrence
+        if rule.count.is_none() && rule.until.is_none() && to.is_none() {
+            return [self.start].into_iter().filter(in_range).collect();
+        }
+
+        let mut dates = Vec::new();
+        let mut cnt = 0;
+        for n in 0..MAX_OCCURRENCES as u32 {
+            let Some(step) = n.checked_mul(rule.interval) else {
+                break;
+            };
+
+            // Dates that do not exist (e.g. Feb 30) are skipped and not counted
+            let Some(date) = nth_date(self.start, rule.frequency, step) else {
+                continue;
+            };
+
+            if rule.until.is_some_and(|until| date > until) || to.is_some_and(|to| date > to) {
+                break;
+            }
+
+            if rule.count.is_some_and(|count| cnt >= count) {
+                break;
+            }
+
+            cnt += 1;
+            if in_range(&date) {
+                dates.push(date);
+            }
+        }
+
+        dates
+    }
+}
+
+/// Returns the date `step` periods of `frequency` after `start`, or none if it does not exist.
+fn nth_date(start: NaiveDate, frequency: IcsFrequency, step: u32) -> Option<NaiveDate> {
+    match frequency {
+        IcsFrequency::Daily => start.checked_add_days(Days::new(step as u64)),
+        IcsFrequency::Weekly => start.checked_add_days(Days::new(step as u64 * 7)),
+        IcsFrequency::Monthly => {
+            let date = start.with_day(1)?.checked_add_months(Months::new(step))?;
+            date.with_day(start.day())
+        }
+        IcsFrequency::Yearly => {
+            let year = start.year().checked_add(step as i32)?;
+            NaiveDate::from_ymd_opt(year, start.month(), start.day())
+        }
+    }
+}
+
+/// Parses the contents of an iCalendar (`.ics`) file, returning the events it contains.
+///
+/// Parsing is lenient: events that are missing a start date or have values that cannot be
+/// parsed are skipped.
+pub fn parse_ics(text: &str) -> Vec<IcsEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<PartialEvent> = None;
+
+    for line in unfold_lines(text) {
+        let Some(property) = IcsProperty::parse(&line) else {
+            continue;
+        };
+
+        match (property.name.as_str(), current.as_mut()) {
+            ("BEGIN", _) if property.value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(PartialEvent::default());
+            }
+            ("END", Some(_)) if property.value.eq_ignore_ascii_case("VEVENT") => {
+                match current.take().and_then(PartialEvent::finish) {
+                    Some(event) => events.push(event),
+                    None => warn!("Skipping iCalendar event without a valid start date"),
+                }
+            }
+            ("DTSTART", Some(event)) => {
+                let is_date = property
+                    .param("VALUE")
+                    .is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+                event.start = parse_date_time(&property.value)
+                    .map(|(date, has_time)| (date, is_date || !has_time));
+            }
+            ("SUMMARY", Some(event)) => event.summary = unescape_text(&property.value),
+            ("RRULE", Some(event)) => event.recurrence = parse_recurrence(&property.value),
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Event that is still being parsed.
+#[derive(Default)]
+struct PartialEvent {
+    /// Start date and whether the event is all day.
+    start: Option<(NaiveDate, bool)>,
+    summary: String,
+    recurrence: Option<IcsRecurrence>,
+}
+
+impl PartialEvent {
+    /// Converts into a complete event, or none if missing a start date.
+    fn finish(self) -> Option<IcsEvent> {
+        let (start, all_day) = self.start?;
+        Some(IcsEvent {
+            start,
+            all_day,
+            summary: self.summary,
+            recurrence: self.recurrence,
+        })
+    }
+}
+
+/// Content line of the form `NAME;PARAM=VALUE:VALUE`.
+struct IcsProperty {
+    /// Name of the property, uppercased.
+    name: String,
+
+    /// Parameters of the property, with names uppercased.
+    params: Vec<(String, String)>,
+
+    /// Raw value of the property.
+    value: String,
+}
+
+impl IcsProperty {
+    /// Parses a content line, returning none if it is missing a value.
+    fn parse(line: &str) -> Option<Self> {
+        // Find the first colon that is not within a quoted parameter value
+        let mut in_quotes = false;
+        let idx = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                None
+            }
+            ':' if !in_quotes => Some(i),
+            _ => None,
+        })?;
+
+        let (head, value) = (&line[..idx], &line[idx + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_uppercase();
+        let params = parts
+            .filter_map(|param| param.split_once('='))
+            .map(|(k, v)| (k.trim().to_uppercase(), v.trim_matches('"').to_string()))
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    /// Returns the value of the parameter named `name` (uppercase), if it exists.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find_map(|(k, v)| (k == name).then_some(v.as_str()))
+    }
+}
+
+/// Splits `text` into lines, joining folded lines (those beginning with a space or tab) onto the
+/// line before them.
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Parses a date (`YYYYMMDD`) or date-time (`YYYYMMDDTHHMMSS[Z]`), returning the date and
+/// whether a time was included.
+fn parse_date_time(value: &str) -> Option<(NaiveDate, bool)> {
+    let value = value.trim();
+    let date = NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?;
+    Some((date, value.len() > 8))
+}
+
+/// Parses a recurrence rule such as `FREQ=YEARLY;INTERVAL=2;COUNT=5`.
+fn parse_recurrence(value: &str) -> Option<IcsRecurrence> {
+    let mut frequency = None;
+    let mut interval = 1;
+    let mut count = None;
+    let mut until = None;
+
+    for (k, v) in value.split(';').filter_map(|part| part.split_once('=')) {
+        match k.trim().to_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match v.trim().to_uppercase().as_str() {
+                    "DAILY" => IcsFrequency::Daily,
+                    "WEEKLY" => IcsFrequency::Weekly,
+                    "MONTHLY" => IcsFrequency::Monthly,
+                    "YEARLY" => IcsFrequency::Yearly,
+                    _ => return None,
+                })
+            }
+            "INTERVAL" => interval = v.trim().parse().ok().filter(|x| *x > 0)?,
+            "COUNT" => count = Some(v.trim().parse().ok()?),
+            "UNTIL" => until = Some(parse_date_time(v)?.0),
+            _ => {}
+        }
+    }
+
+    Some(IcsRecurrence {
+        frequency: frequency?,
+        interval,
+        count,
+        until,
+    })
+}
+
+/// Unescapes a text value, converting `\n`, `\,`, `\;`, and `\\` into their characters.
+fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => text.push('\n'),
+                Some(c) => text.push(c),
+                None => text.push('\\'),
+            }
+        } else {
+            text.push(c);
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parse_ics_should_support_all_day_and_timed_events() {
+        let events = parse_ics(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART;VALUE=DATE:20240915\r\n\
+             SUMMARY:Mom's birthday\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART;TZID=\"America/Chicago\":20240916T093000\r\n\
+             SUMMARY:Dentist\\, downtown\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Missing start\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                IcsEvent {
+                    start: date(2024, 9, 15),
+                    all_day: true,
+                    summary: String::from("Mom's birthday"),
+                    recurrence: None,
+                },
+                IcsEvent {
+                    start: date(2024, 9, 16),
+                    all_day: false,
+                    summary: String::from("Dentist, downtown"),
+                    recurrence: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ics_should_unfold_lines() {
+        let events = parse_ics(
+            "BEGIN:VEVENT\n\
+             DTSTART:20240915\n\
+             SUMMARY:A very long\n  summary\n\
+             END:VEVENT\n",
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "A very long summary");
+        assert!(events[0].all_day);
+    }
+
+    #[test]
+    fn parse_ics_should_support_recurrence() {
+        let events = parse_ics(
+            "BEGIN:VEVENT\n\
+             DTSTART;VALUE=DATE:20200229\n\
+             RRULE:FREQ=YEARLY\n\
+             SUMMARY:Leap day\n\
+             END:VEVENT\n",
+        );
+
+        assert_eq!(
+            events[0].recurrence,
+            Some(IcsRecurrence {
+                frequency: IcsFrequency::Yearly,
+                interval: 1,
+                count: None,
+                until: None,
+            })
+        );
+
+        // Without an end, only the first occurrence is included
+        assert_eq!(events[0].occurrences(None, None), vec![date(2020, 2, 29)]);
+
+        // Dates that do not exist are skipped
+        assert_eq!(
+            events[0].occurrences(Some(date(2021, 1, 1)), Some(date(2028, 12, 31))),
+            vec![date(2024, 2, 29), date(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn occurrences_should_respect_interval_count_and_until() {
+        let event = IcsEvent {
+            start: date(2024, 1, 31),
+            all_day: true,
+            summary: String::new(),
+            recurrence: Some(IcsRecurrence {
+                frequency: IcsFrequency::Monthly,
+                interval: 1,
+                count: Some(4),
+                until: None,
+            }),
+        };
+
+        // Months without a 31st are skipped and not counted
+        assert_eq!(
+            event.occurrences(None, None),
+            vec![
+                date(2024, 1, 31),
+                date(2024, 3, 31),
+                date(2024, 5, 31),
+                date(2024, 7, 31)
+            ]
+        );
+
+        let event = IcsEvent {
+            recurrence: Some(IcsRecurrence {
+                frequency: IcsFrequency::Weekly,
+                interval: 2,
+                count: None,
+                until: Some(date(2024, 2, 28)),
+            }),
+            ..event
+        };
+
+        assert_eq!(
+            event.occurrences(Some(date(2024, 2, 1)), None),
+            vec![date(2024, 2, 14), date(2024, 2, 28)]
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfLink, PdfLuaTableExt, PdfObject, PdfObjectGroup, PdfObjectShape,
+    PdfObjectText, PdfPadding, PdfPaintMode, PdfPoint,
+};
+use crate::runtime::RuntimeFontId;
+use mlua::prelude::*;
+
+/// Side of a callout that its pointer sticks out of.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfCalloutSide {
+    Top,
+    Right,
+    #[default]
+    Bottom,
+    Left,
+}
+
+impl PdfCalloutSide {
+    /// Names of the sides as they appear in Lua.
+    const NAMES: [(&'static str, Self); 4] = [
+        ("top", Self::Top),
+        ("right", Self::Right),
+        ("bottom", Self::Bottom),
+        ("left", Self::Left),
+    ];
+
+    /// Returns the name of the side as it appears in Lua.
+    pub fn to_name(self) -> &'static str {
+        Self::NAMES
+            .into_iter()
+            .find(|(_, side)| *side == self)
+            .map(|(name, _)| name)
+            .unwrap_or_default()
+    }
+}
+
+/// Triangle sticking out of a side of a callout, pointing at what the callout describes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfCalloutPointer {
+    pub side: PdfCalloutSide,
+    /// Position of the pointer along its side from 0 to 1, measured from the left of the top and
+    /// bottom sides and from the bottom of the left and right sides.
+    pub position: f32,
+    /// Width in millimeters of the base of the pointer along its side.
+    pub width: f32,
+    /// Distance in millimeters from the side to the tip of the pointer.
+    pub length: f32,
+}
+
+impl Default for PdfCalloutPointer {
+    fn default() -> Self {
+        Self {
+            side: PdfCalloutSide::default(),
+            position: 0.5,
+            width: 4.0,
+            length: 4.0,
+        }
+    }
+}
+
+/// Sticky note or speech bubble made of a rounded box with a pointer, filled with text wrapped to
+/// fit the width of the box.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfCallout {
+    /// Bounds of the box, which the pointer sticks out of.
+    pub bounds: PdfBounds,
+    pub text: String,
+    /// Pointer of the callout, or none for a box on its own.
+    pub pointer: Option<PdfCalloutPointer>,
+    /// Color of the box, defaulting to a pale yellow.
+    pub fill_color: Option<PdfColor>,
+    /// Color of the outline of the box, which is only outlined when set.
+    pub outline_color: Option<PdfColor>,
+    pub outline_thickness: Option<f32>,
+    /// Radius in millimeters of the rounded corners of the box, or 0 for square corners.
+    pub corner_radius: f32,
+    /// Space in millimeters between the edges of the box and its text.
+    pub padding: PdfPadding,
+    pub font: Option<RuntimeFontId>,
+    pub size: Option<f32>,
+    /// Color of the text, defaulting to the fill color of the page.
+    pub text_color: Option<PdfColor>,
+    pub depth: Option<i64>,
+    pub link: Option<PdfLink>,
+}
+
+impl Default for PdfCallout {
+    fn default() -> Self {
+        Self {
+            bounds: PdfBounds::default(),
+            text: String::new(),
+            pointer: Some(PdfCalloutPointer::default()),
+            fill_color: None,
+            outline_color: None,
+            outline_thickness: None,
+            corner_radius: 2.0,
+            padding: PdfPadding::from_single_f32(2.0),
+            font: None,
+            size: None,
+            text_color: None,
+            depth: None,
+            link: None,
+        }
+    }
+}
+
+impl PdfCallout {
+    /// Color of callouts that do not specify their own.
+    pub fn default_fill_color() -> PdfColor {
+        PdfColor::from_rgb_u8(255, 243, 160)
+    }
+
+    /// Returns the shape of the box and its pointer as a single outline.
+    ///
+    /// The base of the pointer is kept along the straight part of its side, between the rounded
+    /// corners of the box.
+    pub fn to_shape(&self) -> PdfObjectShape {
+        let [lr, ur, ul, ll] = self.bounds.to_rounded_corners(self.corner_radius);
+
+        // Pointer is inserted between the corners that its side connects
+        let pointer = self.pointer.map(|x| (x.side, self.pointer_points(x)));
+        let pointer_on = |side| match pointer.as_ref() {
+            Some((s, points)) if *s == side => points.clone(),
+            _ => Vec::new(),
+        };
+
+        let points = [
+            lr,
+            pointer_on(PdfCalloutSide::Right),
+            ur,
+            pointer_on(PdfCalloutSide::Top),
+            ul,
+            pointer_on(PdfCalloutSide::Left),
+            ll,
+            pointer_on(PdfCalloutSide::Bottom),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        PdfObjectShape {
+            points,
+            depth: self.depth,
+            fill_color: Some(self.fill_color.unwrap_or_else(Self::default_fill_color)),
+            outline_color: self.outline_color,
+            outline_thickness: self.outline_thickness,
+            mode: Some(if self.outline_color.is_some() {
+                PdfPaintMode::fill_stroke()
+            } else {
+                PdfPaintMode::fill()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the base, tip, and base of the pointer in the counter-clockwise order that the
+    /// outline of the box travels along its side.
+    fn pointer_points(&self, pointer: PdfCalloutPointer) -> Vec<PdfPoint> {
+        let PdfBounds { ll, ur } = self.bounds;
+        let (start, end) = match pointer.side {
+            PdfCalloutSide::Top | PdfCalloutSide::Bottom => (ll.x.0, ur.x.0),
+            PdfCalloutSide::Left | PdfCalloutSide::Right => (ll.y.0, ur.y.0),
+        };
+
+        // Keep the base of the pointer clear of the rounded corners where possible
+        let radius = self
+            .corner_radius
+            .min(self.bounds.width().0 / 2.0)
+            .min(self.bounds.height().0 / 2.0)
+            .max(0.0);
+        let half = (pointer.width / 2.0)
+            .min((end - start) / 2.0 - radius)
+            .max(0.0);
+        let (min, max) = (start + radius + half, end - radius - half);
+        let center = start + (end - start) * pointer.position.clamp(0.0, 1.0);
+        let center = if min <= max {
+            center.clamp(min, max)
+        } else {
+            (start + end) / 2.0
+        };
+
+        let (a, b) = (center - half, center + half);
+        let length = pointer.length;
+        let points = match pointer.side {
+            PdfCalloutSide::Right => [(ur.x.0, a), (ur.x.0 + length, center), (ur.x.0, b)],
+            PdfCalloutSide::Top => [(b, ur.y.0), (center, ur.y.0 + length), (a, ur.y.0)],
+            PdfCalloutSide::Left => [(ll.x.0, b), (ll.x.0 - length, center), (ll.x.0, a)],
+            PdfCalloutSide::Bottom => [(a, ll.y.0), (center, ll.y.0 - length), (b, ll.y.0)],
+        };
+
+        points
+            .into_iter()
+            .map(|(x, y)| PdfPoint::from_coords_f32(x, y))
+            .collect()
+    }
+
+    /// Returns the box, pointer, and lines of text of the callout as a group, wrapping the text to
+    /// fit within the padded width of the box. Lines that do not fit within the height of the box
+    /// are still drawn below it.
+    ///
+    /// Calculates lines from a [`Lua`] runtime, which occurs earlier than when a
+    /// [`PdfContext`](crate::pdf::PdfContext) is available.
+    pub(crate) fn lua_to_group(&self, lua: &Lua) -> LuaResult<PdfObjectGroup> {
+        let area = self.bounds.with_padding(self.padding);
+        let text = PdfObjectText {
+            depth: self.depth,
+            font: self.font,
+            size: self.size,
+            color: self.text_color,
+            ..Default::default()
+        };
+
+        // Every line shares the height of the font, so measure an empty line at the origin to
+        // find the distance from its top to its baseline
+        let metrics = text.lua_bounds(lua)?;
+        let line_height = metrics.height();
+        let ascent = metrics.ur.y;
+
+        let lines = PdfObjectText {
+            text: self.text.clone(),
+            ..text.clone()
+        }
+        .lua_wrap(lua, area.width().0)?;
+
+        let mut objects = vec![PdfObject::Shape(self.to_shape())];
+        for (i, line) in lines.into_iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            objects.push(PdfObject::Text(PdfObjectText {
+                point: PdfPoint::new(area.ll.x, area.ur.y - ascent - line_height * i as f32),
+                text: line,
+                ..text.clone()
+            }));
+        }
+
+        Ok(PdfObjectGroup {
+            objects,
+            link: self.link.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfCalloutSide {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(self.to_name()).map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfCalloutSide {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => {
+                let name = s.to_string_lossy();
+                Self::NAMES
+                    .into_iter()
+                    .find(|(x, _)| *x == name.as_ref())
+                    .map(|(_, side)| side)
+                    .ok_or_else(|| LuaError::FromLuaConversionError {
+                        from,
+                        to: "pdf.common.callout_side",
+                        message: Some(format!("unknown side: {name}")),
+                    })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.callout_side",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfCalloutPointer {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("side", self.side)?;
+        table.raw_set("position", self.position)?;
+        table.raw_set("width", self.width)?;
+        table.raw_set("length", self.length)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfCalloutPointer {
+    /// Converts from a table with optional `side`, `position`, `width`, and `length`, or the name
+    /// of a side on its own.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        let default = Self::default();
+        match value {
+            LuaValue::String(_) => Ok(Self {
+                side: PdfCalloutSide::from_lua(value, lua)?,
+                ..default
+            }),
+            LuaValue::Table(table) => Ok(Self {
+                side: table
+                    .raw_get_ext::<_, Option<_>>("side")?
+                    .unwrap_or(default.side),
+                position: table
+                    .raw_get_ext::<_, Option<_>>("position")?
+                    .unwrap_or(default.position),
+                width: table
+                    .raw_get_ext::<_, Option<f32>>("width")?
+                    .map(|x| x.max(0.0))
+                    .unwrap_or(default.width),
+                length: table
+                    .raw_get_ext::<_, Option<_>>("length")?
+                    .unwrap_or(default.length),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.callout_pointer",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfCallout {
+    /// Converts from a table with `bounds` and `text`, where a missing `pointer` points down from
+    /// the middle of the bottom side and a `pointer` of `false` removes it.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        let default = Self::default();
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                bounds: table.raw_get_ext("bounds")?,
+                text: table
+                    .raw_get_ext::<_, Option<_>>("text")?
+                    .unwrap_or_default(),
+                pointer: match table.raw_get_ext::<_, LuaValue>("pointer")? {
+                    LuaValue::Nil => default.pointer,
+                    LuaValue::Boolean(false) => None,
+                    value => Some(PdfCalloutPointer::from_lua(value, lua)?),
+                },
+                fill_color: table.raw_get_ext("fill_color")?,
+                outline_color: table.raw_get_ext("outline_color")?,
+                outline_thickness: table.raw_get_ext("outline_thickness")?,
+                corner_radius: table
+                    .raw_get_ext::<_, Option<f32>>("corner_radius")?
+                    .map(|x| x.max(0.0))
+                    .unwrap_or(default.corner_radius),
+                padding: table
+                    .raw_get_ext::<_, Option<_>>("padding")?
+                    .unwrap_or(default.padding),
+                font: table.raw_get_ext("font")?,
+                size: table.raw_get_ext("size")?,
+                text_color: table.raw_get_ext("text_color")?,
+                depth: table.raw_get_ext("depth")?,
+                link: table.raw_get_ext("link")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.callout",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use crate::runtime::RuntimeFonts;
+    use mlua::chunk;
+    use printpdf::Mm;
+
+    #[test]
+    fn to_shape_should_add_pointer_to_side_of_box() {
+        let callout = PdfCallout {
+            bounds: PdfBounds::from_coords_f32(10.0, 20.0, 50.0, 40.0),
+            pointer: Some(PdfCalloutPointer {
+                side: PdfCalloutSide::Bottom,
+                position: 0.25,
+                width: 4.0,
+                length: 5.0,
+            }),
+            corner_radius: 0.0,
+            ..Default::default()
+        };
+
+        let shape = callout.to_shape();
+        assert_eq!(
+            shape.points,
+            vec![
+                PdfPoint::from_coords_f32(50.0, 20.0),
+                PdfPoint::from_coords_f32(50.0, 40.0),
+                PdfPoint::from_coords_f32(10.0, 40.0),
+                PdfPoint::from_coords_f32(10.0, 20.0),
+                PdfPoint::from_coords_f32(18.0, 20.0),
+                PdfPoint::from_coords_f32(20.0, 15.0),
+                PdfPoint::from_coords_f32(22.0, 20.0),
+            ]
+        );
+        assert_eq!(
+            shape.bounds(),
+            PdfBounds::from_coords_f32(10.0, 15.0, 50.0, 40.0)
+        );
+
+        // Pointer is kept clear of rounded corners, sticking out of the right side
+        let callout = PdfCallout {
+            pointer: Some(PdfCalloutPointer {
+                side: PdfCalloutSide::Right,
+                position: 0.0,
+                ..Default::default()
+            }),
+            corner_radius: 3.0,
+            ..callout
+        };
+        let shape = callout.to_shape();
+        assert!(shape
+            .points
+            .contains(&PdfPoint::from_coords_f32(54.0, 25.0)));
+        assert_eq!(
+            shape.bounds().to_precision(3),
+            PdfBounds::from_coords_f32(10.0, 20.0, 54.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn should_create_callouts_with_wrapped_text_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        let group: PdfObjectGroup = lua
+            .load(chunk! {
+                return pdf.object.callout({
+                    bounds = { 0, 0, 40, 30 },
+                    text = "Remember to water the plants before leaving",
+                    pointer = { side = "left", position = 0.75 },
+                    size = 12,
+                    text_color = "#ff0000",
+                })
+            })
+            .eval()
+            .unwrap();
+
+        let lines: Vec<&PdfObjectText> = group
+            .objects
+            .iter()
+            .filter_map(|obj| match obj {
+                PdfObject::Text(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+        assert!(lines.len() > 1, "Text was not wrapped");
+        for pair in lines.windows(2) {
+            assert_eq!(pair[0].point.x, Mm(2.0));
+            assert!(pair[1].point.y < pair[0].point.y);
+        }
+        assert_eq!(lines[0].color, Some(PdfColor::from_rgb_u8(255, 0, 0)));
+
+        match &group.objects[0] {
+            PdfObject::Shape(shape) => assert!(shape.bounds().ll.x < Mm(0.0)),
+            obj => panic!("Unexpected object {obj:?}"),
+        }
+
+        lua.load(chunk!(pdf.object.callout({ text = "missing bounds" })))
+            .exec()
+            .expect_err("Unexpectedly created callout without bounds");
+        lua.load(chunk!(pdf.object.callout({ bounds = { 0, 0, 1, 1 }, pointer = "up" })))
+            .exec()
+            .expect_err("Unexpectedly created callout with unknown side");
+    }
+}
@@ -1,23 +1,45 @@
 mod align;
+mod background;
 mod bounds;
 mod color;
 mod date;
+mod direction;
 mod ext;
+mod label;
+mod layer;
+mod length;
 mod line;
 mod link;
 mod mode;
 mod order;
+mod overflow;
 mod padding;
+mod place;
 mod point;
+mod script;
+mod shadow;
+mod side;
+mod style;
 
 pub use align::{PdfAlign, PdfHorizontalAlign, PdfVerticalAlign};
+pub use background::PdfBackground;
 pub use bounds::PdfBounds;
-pub use color::PdfColor;
+pub use color::{PdfColor, PdfColorSpace};
 pub use date::PdfDate;
+pub use direction::PdfTextDirection;
 pub use ext::{PdfLuaExt, PdfLuaTableExt};
+pub use label::{PdfPageLabel, PdfPageLabelStyle};
+pub use layer::PdfLayer;
+pub use length::{PdfCoord, PdfLength};
 pub use line::{PdfLineCapStyle, PdfLineDashPattern, PdfLineJoinStyle};
-pub use link::{PdfLink, PdfLinkAnnotation};
+pub use link::{PdfLink, PdfLinkAnnotation, PdfLinkBorder, PdfLinkHighlight, PdfLinkStyle};
 pub use mode::PdfPaintMode;
 pub use order::PdfWindingOrder;
+pub use overflow::PdfTextOverflow;
 pub use padding::PdfPadding;
+pub use place::{PdfPlace, PdfPlaceSide};
 pub use point::PdfPoint;
+pub use script::PdfTextScript;
+pub use shadow::PdfShadow;
+pub use side::PdfPageSide;
+pub use style::PdfStyle;
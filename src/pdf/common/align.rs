@@ -98,6 +98,8 @@ pub enum PdfVerticalAlign {
     #[default]
     Middle,
     Bottom,
+    /// Aligns by the baseline of text, which for anything other than text is the same as bottom.
+    Baseline,
 }
 
 impl<'lua> IntoLua<'lua> for PdfVerticalAlign {
@@ -107,6 +109,7 @@ impl<'lua> IntoLua<'lua> for PdfVerticalAlign {
             Self::Top => "top",
             Self::Middle => "middle",
             Self::Bottom => "bottom",
+            Self::Baseline => "baseline",
         })
         .map(LuaValue::String)
     }
@@ -121,6 +124,7 @@ impl<'lua> FromLua<'lua> for PdfVerticalAlign {
                 "top" => Ok(Self::Top),
                 "middle" => Ok(Self::Middle),
                 "bottom" => Ok(Self::Bottom),
+                "baseline" => Ok(Self::Baseline),
                 ty => Err(LuaError::FromLuaConversionError {
                     from,
                     to: "pdf.common.vertical_align",
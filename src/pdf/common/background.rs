@@ -0,0 +1,167 @@
+use crate::pdf::{PdfBounds, PdfColor, PdfLuaTableExt, PdfObjectShape, PdfPadding, PdfPaintMode};
+use mlua::prelude::*;
+
+/// Box filled behind an object, sized to the bounds of the object grown by padding, such as the
+/// highlight of a label.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfBackground {
+    /// Color of the box, defaulting to a light grey.
+    pub color: Option<PdfColor>,
+    /// Space in millimeters between the bounds of the object and the edges of the box.
+    pub padding: PdfPadding,
+    /// Radius in millimeters of the rounded corners of the box, or 0 for square corners.
+    pub corner_radius: f32,
+}
+
+impl Default for PdfBackground {
+    fn default() -> Self {
+        Self {
+            color: None,
+            padding: PdfPadding::from_single_f32(1.0),
+            corner_radius: 0.0,
+        }
+    }
+}
+
+impl PdfBackground {
+    /// Color of boxes that do not specify their own.
+    pub fn default_color() -> PdfColor {
+        PdfColor::from_rgb_u8(224, 224, 224)
+    }
+
+    /// Returns the bounds of the box drawn behind an object with `bounds`.
+    pub fn to_bounds(&self, bounds: PdfBounds) -> PdfBounds {
+        PdfBounds::from_coords(
+            bounds.ll.x - self.padding.left,
+            bounds.ll.y - self.padding.bottom,
+            bounds.ur.x + self.padding.right,
+            bounds.ur.y + self.padding.top,
+        )
+    }
+
+    /// Returns the filled shape of the box drawn behind an object with `bounds`.
+    ///
+    /// The radius of rounded corners is limited to half of the width and height of the box.
+    pub fn to_shape(&self, bounds: PdfBounds) -> PdfObjectShape {
+        let points = self
+            .to_bounds(bounds)
+            .to_rounded_corners(self.corner_radius)
+            .into_iter()
+            .flatten()
+            .collect();
+
+        PdfObjectShape {
+            points,
+            fill_color: Some(self.color.unwrap_or_else(Self::default_color)),
+            mode: Some(PdfPaintMode::fill()),
+            ..Default::default()
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfBackground {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("color", self.color)?;
+        table.raw_set("padding", self.padding)?;
+        table.raw_set("corner_radius", self.corner_radius)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfBackground {
+    /// Converts from a table with optional `color`, `padding`, and `corner_radius`, or the string
+    /// of a color on its own.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        let default = Self::default();
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                color: table.raw_get_ext("color")?,
+                padding: table
+                    .raw_get_ext::<_, Option<_>>("padding")?
+                    .unwrap_or(default.padding),
+                corner_radius: table
+                    .raw_get_ext::<_, Option<f32>>("corner_radius")?
+                    .map(|x| x.max(0.0))
+                    .unwrap_or(default.corner_radius),
+            }),
+            LuaValue::String(_) => Ok(Self {
+                color: Some(PdfColor::from_lua(value, lua)?),
+                ..default
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.background",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        assert_eq!(
+            lua.load(chunk!({ color = "#ff0000", padding = { 1, 2 }, corner_radius = 1.5 }))
+                .eval::<PdfBackground>()
+                .unwrap(),
+            PdfBackground {
+                color: Some(PdfColor::from_rgb_u8(255, 0, 0)),
+                padding: PdfPadding::from_pair_f32(1.0, 2.0),
+                corner_radius: 1.5,
+            }
+        );
+        assert_eq!(
+            lua.load(chunk!("#ff0000")).eval::<PdfBackground>().unwrap(),
+            PdfBackground {
+                color: Some(PdfColor::from_rgb_u8(255, 0, 0)),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            lua.load(chunk!({})).eval::<PdfBackground>().unwrap(),
+            PdfBackground::default()
+        );
+        lua.load(chunk!(true))
+            .eval::<PdfBackground>()
+            .expect_err("Unexpectedly converted boolean into background");
+    }
+
+    #[test]
+    fn to_shape_should_surround_padded_bounds_with_rounded_corners() {
+        let bounds = PdfBounds::from_coords_f32(10.0, 10.0, 30.0, 16.0);
+
+        let background = PdfBackground {
+            padding: PdfPadding::from_pair_f32(1.0, 2.0),
+            ..Default::default()
+        };
+        let shape = background.to_shape(bounds);
+        assert_eq!(shape.points.len(), 4);
+        assert_eq!(
+            shape.bounds(),
+            PdfBounds::from_coords_f32(8.0, 9.0, 32.0, 17.0)
+        );
+
+        // Radius is limited to half of the height of the box
+        let background = PdfBackground {
+            corner_radius: 10.0,
+            ..background
+        };
+        let shape = background.to_shape(bounds);
+        assert!(shape.points.len() > 4, "Missing points of rounded corners");
+        assert_eq!(
+            shape.bounds().to_precision(3),
+            PdfBounds::from_coords_f32(8.0, 9.0, 32.0, 17.0)
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use crate::pdf::{
-    PdfAlign, PdfHorizontalAlign, PdfLuaExt, PdfLuaTableExt, PdfPadding, PdfPoint, PdfVerticalAlign,
+    PdfAlign, PdfCoord, PdfHorizontalAlign, PdfLuaExt, PdfLuaTableExt, PdfPadding, PdfPoint,
+    PdfVerticalAlign,
 };
 use mlua::prelude::*;
 use printpdf::{Mm, Rect};
 
 /// Coordinate bounds for something within a PDF.
+///
+/// Like [`PdfPoint`], bounds are equal when their points match to a thousandth of a millimeter.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct PdfBounds {
     /// Lower-left coordinates
@@ -62,7 +65,7 @@ impl PdfBounds {
     }
 
     /// Creates a copy of the bounds where the points have been rounded to the specified
-    /// `precision`.
+    /// `precision` (number of decimal places).
     pub fn to_precision(&self, precision: u32) -> Self {
         Self::new(
             self.ll.to_precision(precision),
@@ -70,6 +73,12 @@ impl PdfBounds {
         )
     }
 
+    /// Creates a copy of the bounds where the points have been rounded to `decimals` decimal
+    /// places, such as to compare bounds without noise from floating point math.
+    pub fn round_to(&self, decimals: u32) -> Self {
+        Self::new(self.ll.round_to(decimals), self.ur.round_to(decimals))
+    }
+
     /// Converts coordinates into bounds.
     #[inline]
     pub const fn from_coords(llx: Mm, lly: Mm, urx: Mm, ury: Mm) -> Self {
@@ -110,6 +119,60 @@ impl PdfBounds {
         this
     }
 
+    /// Returns the points of the corners of the bounds counter-clockwise from the lower-right, each
+    /// rounded with an arc of `radius` in millimeters.
+    ///
+    /// The radius is limited to half of the width and height of the bounds.
+    pub fn to_rounded_corners(&self, radius: f32) -> [Vec<PdfPoint>; 4] {
+        let radius = radius
+            .min(self.width().0 / 2.0)
+            .min(self.height().0 / 2.0)
+            .max(0.0);
+        let inner = self.expand(Mm(-radius));
+
+        // Corners counter-clockwise from the lower-right, each with an arc starting at its angle
+        let corners = [
+            (inner.ur.x, inner.ll.y, -90.0_f32),
+            (inner.ur.x, inner.ur.y, 0.0),
+            (inner.ll.x, inner.ur.y, 90.0),
+            (inner.ll.x, inner.ll.y, 180.0),
+        ];
+
+        // Add a point every five degrees along each arc
+        let steps = if radius > 0.0 { 18 } else { 0 };
+        corners.map(|(x, y, start)| {
+            (0..=steps)
+                .map(|step| {
+                    let angle = (start + 90.0 * step as f32 / steps.max(1) as f32).to_radians();
+                    PdfPoint::new(x + Mm(radius * angle.cos()), y + Mm(radius * angle.sin()))
+                })
+                .collect()
+        })
+    }
+
+    /// Grows the bounds by `amount` on every side, or shrinks them when `amount` is negative,
+    /// returning a copy of the newly-adjusted bounds.
+    pub fn expand(&self, amount: Mm) -> Self {
+        let mut this = *self;
+
+        this.ll.x -= amount;
+        this.ll.y -= amount;
+        this.ur.x += amount;
+        this.ur.y += amount;
+
+        this
+    }
+
+    /// Returns true if the bounds overlap with `other`, meaning that they share some area.
+    ///
+    /// Bounds whose edges touch do not overlap.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.ll.x < other.ur.x
+            && other.ll.x < self.ur.x
+            && self.ll.y < other.ur.y
+            && other.ll.y < self.ur.y
+    }
+
     /// Returns a new bounds aligned to some other bounds based on `align`.
     pub fn align_to(&self, other: Self, align: (PdfVerticalAlign, PdfHorizontalAlign)) -> Self {
         let (valign, halign) = align;
@@ -126,7 +189,7 @@ impl PdfBounds {
             PdfVerticalAlign::Middle => {
                 other.ll.y - self.ll.y + ((other.height() - self.height()) / 2.0)
             }
-            PdfVerticalAlign::Bottom => other.ll.y - self.ll.y,
+            PdfVerticalAlign::Bottom | PdfVerticalAlign::Baseline => other.ll.y - self.ll.y,
         };
 
         self.shift_by(x_offset, y_offset)
@@ -189,141 +252,161 @@ impl PdfBounds {
 impl<'lua> IntoLua<'lua> for PdfBounds {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.common.bounds", |metatable| {
+            metatable.raw_set(
+                "__eq",
+                lua.create_function(|_, (a, b): (Self, Self)| Ok(a == b))?,
+            )?;
+
+            metatable.raw_set(
+                "lr",
+                lua.create_function(move |_, this: Self| Ok(this.to_lr_point()))?,
+            )?;
+
+            metatable.raw_set(
+                "ul",
+                lua.create_function(move |_, this: Self| Ok(this.to_ul_point()))?,
+            )?;
+
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(move |_, (this, other, align): (Self, Self, PdfAlign)| {
+                    Ok(this.align_to(other, align.to_v_h()))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "with_padding",
+                lua.create_function(
+                    move |_, (this, opts): (Self, Option<PdfPadding>)| match opts {
+                        Some(padding) => Ok(this.with_padding(padding)),
+                        None => Ok(this),
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "expand",
+                lua.create_function(|_, (this, amount): (Self, f32)| Ok(this.expand(Mm(amount))))?,
+            )?;
+
+            metatable.raw_set(
+                "with_precision",
+                lua.create_function(|_, (this, precision): (Self, u32)| {
+                    Ok(this.to_precision(precision))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "round_to",
+                lua.create_function(
+                    |_, (this, decimals): (Self, u32)| Ok(this.round_to(decimals)),
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "move_to",
+                lua.create_function(
+                    move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
+                        Some(opts) => {
+                            let x = opts
+                                .raw_get_ext::<_, Option<f32>>("x")?
+                                .map(Mm)
+                                .unwrap_or(this.ll.x);
+                            let y = opts
+                                .raw_get_ext::<_, Option<f32>>("y")?
+                                .map(Mm)
+                                .unwrap_or(this.ll.y);
+
+                            Ok(this.move_to(x, y))
+                        }
+                        None => Ok(this),
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "shift_by",
+                lua.create_function(
+                    move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
+                        Some(opts) => {
+                            let x = opts
+                                .raw_get_ext::<_, Option<f32>>("x")?
+                                .map(Mm)
+                                .unwrap_or_default();
+                            let y = opts
+                                .raw_get_ext::<_, Option<f32>>("y")?
+                                .map(Mm)
+                                .unwrap_or_default();
+
+                            Ok(this.shift_by(x, y))
+                        }
+                        None => Ok(this),
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "scale_to",
+                lua.create_function(
+                    move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
+                        Some(opts) => {
+                            let width = opts
+                                .raw_get_ext::<_, Option<f32>>("width")?
+                                .map(Mm)
+                                .unwrap_or_else(|| this.width());
+                            let height = opts
+                                .raw_get_ext::<_, Option<f32>>("height")?
+                                .map(Mm)
+                                .unwrap_or_else(|| this.height());
+
+                            Ok(this.scale_to(width, height))
+                        }
+                        None => Ok(this),
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "scale_by_factor",
+                lua.create_function(
+                    move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
+                        Some(opts) => {
+                            let width = opts.raw_get_ext::<_, Option<f32>>("width")?.unwrap_or(1.0);
+                            let height =
+                                opts.raw_get_ext::<_, Option<f32>>("height")?.unwrap_or(1.0);
+                            Ok(this.scale_by_factor(width, height))
+                        }
+                        None => Ok(this),
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "width",
+                lua.create_function(move |_, this: Self| Ok(this.width().0))?,
+            )?;
+
+            metatable.raw_set(
+                "height",
+                lua.create_function(move |_, this: Self| Ok(this.height().0))?,
+            )?;
+
+            metatable.raw_set(
+                "to_coords",
+                lua.create_function(move |_, this: Self| {
+                    // NOTE: We need to return a Vec<f32> to make it a table {nunber, nunber, ...}
+                    //       as returning a tuple makes it act like a vararg return instead.
+                    let (llx, lly, urx, ury) = this.to_coords_f32();
+                    Ok(vec![llx, lly, urx, ury])
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         self.add_to_table(&table)?;
 
-        metatable.raw_set(
-            "lr",
-            lua.create_function(move |_, this: Self| Ok(this.to_lr_point()))?,
-        )?;
-
-        metatable.raw_set(
-            "ul",
-            lua.create_function(move |_, this: Self| Ok(this.to_ul_point()))?,
-        )?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(move |_, (this, other, align): (Self, Self, PdfAlign)| {
-                Ok(this.align_to(other, align.to_v_h()))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "with_padding",
-            lua.create_function(
-                move |_, (this, opts): (Self, Option<PdfPadding>)| match opts {
-                    Some(padding) => Ok(this.with_padding(padding)),
-                    None => Ok(this),
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "with_precision",
-            lua.create_function(|_, (this, precision): (Self, u32)| {
-                Ok(this.to_precision(precision))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "move_to",
-            lua.create_function(
-                move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
-                    Some(opts) => {
-                        let x = opts
-                            .raw_get_ext::<_, Option<f32>>("x")?
-                            .map(Mm)
-                            .unwrap_or(this.ll.x);
-                        let y = opts
-                            .raw_get_ext::<_, Option<f32>>("y")?
-                            .map(Mm)
-                            .unwrap_or(this.ll.y);
-
-                        Ok(this.move_to(x, y))
-                    }
-                    None => Ok(this),
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "shift_by",
-            lua.create_function(
-                move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
-                    Some(opts) => {
-                        let x = opts
-                            .raw_get_ext::<_, Option<f32>>("x")?
-                            .map(Mm)
-                            .unwrap_or_default();
-                        let y = opts
-                            .raw_get_ext::<_, Option<f32>>("y")?
-                            .map(Mm)
-                            .unwrap_or_default();
-
-                        Ok(this.shift_by(x, y))
-                    }
-                    None => Ok(this),
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "scale_to",
-            lua.create_function(
-                move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
-                    Some(opts) => {
-                        let width = opts
-                            .raw_get_ext::<_, Option<f32>>("width")?
-                            .map(Mm)
-                            .unwrap_or_else(|| this.width());
-                        let height = opts
-                            .raw_get_ext::<_, Option<f32>>("height")?
-                            .map(Mm)
-                            .unwrap_or_else(|| this.height());
-
-                        Ok(this.scale_to(width, height))
-                    }
-                    None => Ok(this),
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "scale_by_factor",
-            lua.create_function(
-                move |_, (this, opts): (Self, Option<LuaTable>)| match opts {
-                    Some(opts) => {
-                        let width = opts.raw_get_ext::<_, Option<f32>>("width")?.unwrap_or(1.0);
-                        let height = opts.raw_get_ext::<_, Option<f32>>("height")?.unwrap_or(1.0);
-                        Ok(this.scale_by_factor(width, height))
-                    }
-                    None => Ok(this),
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "width",
-            lua.create_function(move |_, this: Self| Ok(this.width().0))?,
-        )?;
-
-        metatable.raw_set(
-            "height",
-            lua.create_function(move |_, this: Self| Ok(this.height().0))?,
-        )?;
-
-        metatable.raw_set(
-            "to_coords",
-            lua.create_function(move |_, this: Self| {
-                // NOTE: We need to return a Vec<f32> to make it a table {nunber, nunber, ...}
-                //       as returning a tuple makes it act like a vararg return instead.
-                let (llx, lly, urx, ury) = this.to_coords_f32();
-                Ok(vec![llx, lly, urx, ury])
-            })?,
-        )?;
-
         Ok(LuaValue::Table(table))
     }
 }
@@ -335,12 +418,15 @@ impl<'lua> FromLua<'lua> for PdfBounds {
     /// - `{ll:{number, number}, ur:{number, number}}`
     /// - `{{number, number}, {number, number}}`
     /// - `{number, number, number, number}`
+    ///
+    /// Where each number can instead be a string with units, such as `"1in"`, or a percentage of
+    /// the content bounds of pages, such as `"50%"`.
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         let from = value.type_name();
         match value {
             LuaValue::Table(table) => {
-                let maybe_coords: Option<Vec<f32>> = table
+                let maybe_coords: Option<Vec<PdfCoord>> = table
                     .clone()
                     .sequence_values()
                     .collect::<LuaResult<_>>()
@@ -355,8 +441,11 @@ impl<'lua> FromLua<'lua> for PdfBounds {
                 // If we have coordinates, check to make sure we have four, and use them as bounds
                 if let Some(coords) = maybe_coords {
                     if coords.len() >= 4 {
-                        return Ok(Self::from_coords_f32(
-                            coords[0], coords[1], coords[2], coords[3],
+                        return Ok(Self::from_coords(
+                            coords[0].into_x(lua)?,
+                            coords[1].into_y(lua)?,
+                            coords[2].into_x(lua)?,
+                            coords[3].into_y(lua)?,
                         ));
                     }
                 }
@@ -526,6 +615,72 @@ mod tests {
             .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_support_expand() {
+        let bounds = PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0);
+
+        assert_eq!(
+            bounds.expand(Mm(1.0)),
+            PdfBounds::from_coords_f32(0.0, 1.0, 4.0, 5.0)
+        );
+
+        // Negative amounts shrink the bounds
+        assert_eq!(
+            bounds.expand(Mm(-0.5)),
+            PdfBounds::from_coords_f32(1.5, 2.5, 2.5, 3.5)
+        );
+    }
+
+    #[test]
+    fn should_support_expand_in_lua() {
+        let bounds = PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0);
+
+        Lua::new()
+            .load(chunk! {
+                local u = $PdfUtils
+
+                u.assert_deep_equal($bounds:expand(1), {
+                    ll = { x = 0, y = 1 },
+                    ur = { x = 4, y = 5 },
+                })
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_compare_bounds_ignoring_float_noise_in_lua() {
+        let bounds = PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0);
+
+        Lua::new()
+            .load(chunk! {
+                local bounds = $bounds
+
+                assert(bounds:expand(0.1):expand(-0.1) == bounds, "expanded bounds differ")
+                assert(bounds:with_padding({ top = 0.3 }):expand(0.1) ~= bounds, "bounds are equal")
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_round_to_decimals() {
+        let bounds = PdfBounds::from_coords_f32(0.123456, 1.5, 2.00049, 9.87654);
+        assert_eq!(bounds.round_to(0).to_coords_f32(), (0.0, 2.0, 2.0, 10.0));
+        assert_eq!(bounds.round_to(3).to_coords_f32(), (0.123, 1.5, 2.0, 9.877));
+
+        let bounds = PdfBounds::from_coords_f32(0.46, 1.5, 2.04, 9.96);
+        Lua::new()
+            .load(chunk! {
+                $PdfUtils.assert_deep_equal($bounds:round_to(1), {
+                    ll = { x = 0.5, y = 1.5 },
+                    ur = { x = 2, y = 10 },
+                })
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
+
     #[test]
     fn should_support_move_to() {
         let bounds = PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0);
@@ -792,6 +947,27 @@ mod tests {
         // Bottom-right
         let actual = this.align_to(other, (V::Bottom, H::Right));
         assert_eq!(actual.to_coords_f32(), (20.0, 5.0, 25.0, 10.0));
+
+        // Baseline-left (bounds have no baseline, so this is the same as bottom)
+        let actual = this.align_to(other, (V::Baseline, H::Left));
+        assert_eq!(actual.to_coords_f32(), (5.0, 5.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn should_be_able_to_check_if_bounds_overlap() {
+        let this = PdfBounds::from_coords_f32(0.0, 0.0, 10.0, 10.0);
+
+        // Overlapping partially
+        assert!(this.overlaps(&PdfBounds::from_coords_f32(5.0, 5.0, 15.0, 15.0)));
+
+        // Overlapping entirely
+        assert!(this.overlaps(&PdfBounds::from_coords_f32(2.0, 2.0, 3.0, 3.0)));
+
+        // Touching edges
+        assert!(!this.overlaps(&PdfBounds::from_coords_f32(10.0, 0.0, 20.0, 10.0)));
+
+        // Elsewhere entirely
+        assert!(!this.overlaps(&PdfBounds::from_coords_f32(20.0, 20.0, 30.0, 30.0)));
     }
 
     #[test]
@@ -1,33 +1,124 @@
 use crate::pdf::{PdfLuaExt, PdfLuaTableExt};
 use mlua::prelude::*;
-use palette::Srgb;
+use palette::{Srgb, Srgba};
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
 
-/// Color for some object in a PDF.
+/// Color space that a color is drawn within.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
-pub struct PdfColor(Srgb);
+pub enum PdfColorSpace {
+    /// Red, green, and blue.
+    #[default]
+    Rgb,
+    /// Cyan, magenta, yellow, and black (key) inks, each between 0 and 1.
+    Cmyk {
+        cyan: f32,
+        magenta: f32,
+        yellow: f32,
+        black: f32,
+    },
+    /// Single level of gray between 0 (black) and 1 (white).
+    Gray(f32),
+}
+
+/// Color for some object in a PDF.
+///
+/// Colors always carry an RGB equivalent (available through deref) used for calculations like
+/// luminance, alongside the color space they are drawn within and their opacity.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfColor {
+    rgb: Srgb,
+    space: PdfColorSpace,
+    alpha: f32,
+}
+
+impl Default for PdfColor {
+    /// Produces an opaque black color.
+    fn default() -> Self {
+        Self::black()
+    }
+}
 
 impl PdfColor {
     /// Produces color from RGB where each value is between 0 and 1.
     pub const fn from_rgb_f32(red: f32, green: f32, blue: f32) -> Self {
-        Self(Srgb::new(red, green, blue))
+        Self {
+            rgb: Srgb::new(red, green, blue),
+            space: PdfColorSpace::Rgb,
+            alpha: 1.0,
+        }
     }
 
     /// Produces color from RGB where each value is between 0 and 255.
     pub fn from_rgb_u8(red: u8, green: u8, blue: u8) -> Self {
-        Self(Srgb::new(red, green, blue).into())
+        Self {
+            rgb: Srgb::new(red, green, blue).into(),
+            space: PdfColorSpace::Rgb,
+            alpha: 1.0,
+        }
+    }
+
+    /// Produces color from CMYK where each value is between 0 and 1.
+    pub fn from_cmyk_f32(cyan: f32, magenta: f32, yellow: f32, black: f32) -> Self {
+        let [cyan, magenta, yellow, black] =
+            [cyan, magenta, yellow, black].map(|x| x.clamp(0.0, 1.0));
+        Self {
+            rgb: Srgb::new(
+                (1.0 - cyan) * (1.0 - black),
+                (1.0 - magenta) * (1.0 - black),
+                (1.0 - yellow) * (1.0 - black),
+            ),
+            space: PdfColorSpace::Cmyk {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            },
+            alpha: 1.0,
+        }
+    }
+
+    /// Produces color from a level of gray between 0 (black) and 1 (white).
+    pub fn from_gray_f32(gray: f32) -> Self {
+        let gray = gray.clamp(0.0, 1.0);
+        Self {
+            rgb: Srgb::new(gray, gray, gray),
+            space: PdfColorSpace::Gray(gray),
+            alpha: 1.0,
+        }
+    }
+
+    /// Returns the color space the color is drawn within.
+    pub fn space(self) -> PdfColorSpace {
+        self.space
+    }
+
+    /// Returns the opacity of the color between 0 (transparent) and 1 (opaque).
+    pub fn alpha(self) -> f32 {
+        self.alpha
+    }
+
+    /// Consumes the color, returning a new variant with the opacity of `alpha` between 0
+    /// (transparent) and 1 (opaque).
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns true if the color is not fully opaque.
+    pub fn is_translucent(self) -> bool {
+        self.alpha < 1.0
     }
 
     /// Returns the color as (red, green, blue) float tuple.
     pub fn into_colors_f32(self) -> (f32, f32, f32) {
-        (self.0.red, self.0.green, self.0.blue)
+        (self.rgb.red, self.rgb.green, self.rgb.blue)
     }
 
     /// Returns the color as (red, green, blue) byte tuple.
     pub fn into_colors_u8(self) -> (u8, u8, u8) {
-        let inner: Srgb<u8> = self.0.into();
+        let inner: Srgb<u8> = self.rgb.into();
         (inner.red, inner.green, inner.blue)
     }
 
@@ -40,6 +131,12 @@ impl PdfColor {
         0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
     }
 
+    /// Consumes the color, returning a grey variant whose channels all equal its luminance.
+    pub fn into_grayscale(self) -> Self {
+        let l = self.into_luminance().clamp(0.0, 1.0);
+        Self::from_rgb_f32(l, l, l).with_alpha(self.alpha)
+    }
+
     /// Returns true if the color is considered light, meaning luminance is greater than `0.5`.
     #[inline]
     pub fn is_light(self) -> bool {
@@ -47,19 +144,53 @@ impl PdfColor {
     }
 
     /// Consumes the color, returning a new variant lightened by `percentage`.
-    pub fn lighten(mut self, percentage: f32) -> Self {
-        self.red = (self.red + (1.0 - self.red) * percentage).min(1.0);
-        self.green = (self.green + (1.0 - self.green) * percentage).min(1.0);
-        self.blue = (self.blue + (1.0 - self.blue) * percentage).min(1.0);
-        self
+    ///
+    /// CMYK colors are lightened by removing `percentage` of each ink.
+    pub fn lighten(self, percentage: f32) -> Self {
+        let lighten = |x: f32| (x + (1.0 - x) * percentage).min(1.0);
+        let color = match self.space {
+            PdfColorSpace::Rgb => {
+                Self::from_rgb_f32(lighten(self.red), lighten(self.green), lighten(self.blue))
+            }
+            PdfColorSpace::Cmyk {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            } => {
+                let remove = |x: f32| (x * (1.0 - percentage)).max(0.0);
+                Self::from_cmyk_f32(remove(cyan), remove(magenta), remove(yellow), remove(black))
+            }
+            PdfColorSpace::Gray(gray) => Self::from_gray_f32(lighten(gray)),
+        };
+
+        color.with_alpha(self.alpha)
     }
 
     /// Consumes the color, returning a new variant darkened by `percentage`.
-    pub fn darken(mut self, percentage: f32) -> Self {
-        self.red = (self.red * (1.0 - percentage)).max(0.0);
-        self.green = (self.green * (1.0 - percentage)).max(0.0);
-        self.blue = (self.blue * (1.0 - percentage)).max(0.0);
-        self
+    ///
+    /// CMYK colors are darkened by adding `percentage` of the remaining black ink.
+    pub fn darken(self, percentage: f32) -> Self {
+        let darken = |x: f32| (x * (1.0 - percentage)).max(0.0);
+        let color = match self.space {
+            PdfColorSpace::Rgb => {
+                Self::from_rgb_f32(darken(self.red), darken(self.green), darken(self.blue))
+            }
+            PdfColorSpace::Cmyk {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            } => Self::from_cmyk_f32(
+                cyan,
+                magenta,
+                yellow,
+                (black + (1.0 - black) * percentage).min(1.0),
+            ),
+            PdfColorSpace::Gray(gray) => Self::from_gray_f32(darken(gray)),
+        };
+
+        color.with_alpha(self.alpha)
     }
 
     /// Produces a traditional black color.
@@ -104,47 +235,129 @@ impl Deref for PdfColor {
     type Target = Srgb;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.rgb
     }
 }
 
 impl DerefMut for PdfColor {
+    /// Mutably borrows the RGB equivalent, making the color an RGB color as its channels are
+    /// what gets changed.
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        self.space = PdfColorSpace::Rgb;
+        &mut self.rgb
     }
 }
 
 impl fmt::Display for PdfColor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:X}", Srgb::<u8>::from(self.0))
+        write!(f, "{:X}", Srgb::<u8>::from(self.rgb))?;
+
+        // Only include the alpha when translucent to keep opaque colors as six digits
+        if self.is_translucent() {
+            write!(f, "{:02X}", (self.alpha * 255.0).round() as u8)?;
+        }
+
+        Ok(())
     }
 }
 
 impl FromStr for PdfColor {
     type Err = palette::rgb::FromHexError;
 
-    /// Parses a hex string into a color.
+    /// Parses a hex string into a color, where four or eight digits include the alpha.
+    ///
+    /// Also supports the standard CSS color keywords like `steelblue` (case insensitive),
+    /// including `transparent`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.parse::<Srgb<u8>>()?.into()))
+        let name = s.to_ascii_lowercase();
+        if name == "transparent" {
+            return Ok(Self::black().with_alpha(0.0));
+        }
+
+        if let Some(rgb) = palette::named::from_str(&name) {
+            return Ok(Self::from_rgb_u8(rgb.red, rgb.green, rgb.blue));
+        }
+
+        match s.strip_prefix('#').unwrap_or(s).len() {
+            4 | 8 => {
+                let rgba: Srgba<u8> = s.parse()?;
+                Ok(Self::from_rgb_u8(rgba.red, rgba.green, rgba.blue)
+                    .with_alpha(rgba.alpha as f32 / 255.0))
+            }
+            _ => {
+                let rgb: Srgb<u8> = s.parse()?;
+                Ok(Self::from_rgb_u8(rgb.red, rgb.green, rgb.blue))
+            }
+        }
     }
 }
 
 impl From<PdfColor> for printpdf::Color {
-    /// Converts a PDF color into an RGB format.
+    /// Converts a PDF color into the format of its color space.
     fn from(color: PdfColor) -> Self {
-        Self::Rgb(printpdf::Rgb {
-            r: color.red,
-            g: color.green,
-            b: color.blue,
-            icc_profile: None,
-        })
+        match color.space {
+            PdfColorSpace::Rgb => Self::Rgb(printpdf::Rgb {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                icc_profile: None,
+            }),
+            PdfColorSpace::Cmyk {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            } => Self::Cmyk(printpdf::Cmyk {
+                c: cyan,
+                m: magenta,
+                y: yellow,
+                k: black,
+                icc_profile: None,
+            }),
+            PdfColorSpace::Gray(gray) => Self::Greyscale(printpdf::Greyscale {
+                percent: gray,
+                icc_profile: None,
+            }),
+        }
     }
 }
 
 impl<'lua> IntoLua<'lua> for PdfColor {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.common.color", |metatable| {
+            metatable.raw_set(
+                "luminance",
+                lua.create_function(|_, this: PdfColor| Ok(this.into_luminance()))?,
+            )?;
+
+            metatable.raw_set(
+                "is_light",
+                lua.create_function(|_, this: PdfColor| Ok(this.is_light()))?,
+            )?;
+
+            metatable.raw_set(
+                "lighten",
+                lua.create_function(|_, (this, percentage): (PdfColor, f32)| {
+                    Ok(this.lighten(percentage))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "darken",
+                lua.create_function(|_, (this, percentage): (PdfColor, f32)| {
+                    Ok(this.darken(percentage))
+                })?,
+            )?;
+
+            // Return copy of the color as a hex string.
+            metatable.raw_set(
+                "__tostring",
+                lua.create_function(|_, this: PdfColor| Ok(this.to_string()))?,
+            )?;
+
+            Ok(())
+        })?;
 
         // Store fields as u8, not float
         let (red, green, blue) = self.into_colors_u8();
@@ -152,35 +365,27 @@ impl<'lua> IntoLua<'lua> for PdfColor {
         table.raw_set("green", green)?;
         table.raw_set("blue", blue)?;
 
-        metatable.raw_set(
-            "luminance",
-            lua.create_function(|_, this: PdfColor| Ok(this.into_luminance()))?,
-        )?;
-
-        metatable.raw_set(
-            "is_light",
-            lua.create_function(|_, this: PdfColor| Ok(this.is_light()))?,
-        )?;
-
-        metatable.raw_set(
-            "lighten",
-            lua.create_function(|_, (this, percentage): (PdfColor, f32)| {
-                Ok(this.lighten(percentage))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "darken",
-            lua.create_function(|_, (this, percentage): (PdfColor, f32)| {
-                Ok(this.darken(percentage))
-            })?,
-        )?;
-
-        // Return copy of the color as a hex string.
-        metatable.raw_set(
-            "__tostring",
-            lua.create_function(|_, this: PdfColor| Ok(this.to_string()))?,
-        )?;
+        // Store fields of other color spaces so they survive being passed back
+        match self.space {
+            PdfColorSpace::Rgb => {}
+            PdfColorSpace::Cmyk {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            } => {
+                table.raw_set("cyan", cyan)?;
+                table.raw_set("magenta", magenta)?;
+                table.raw_set("yellow", yellow)?;
+                table.raw_set("black", black)?;
+            }
+            PdfColorSpace::Gray(gray) => table.raw_set("gray", gray)?,
+        }
+
+        // Only include the alpha when translucent to keep opaque colors unchanged
+        if self.is_translucent() {
+            table.raw_set("alpha", self.alpha)?;
+        }
 
         Ok(LuaValue::Table(table))
     }
@@ -198,13 +403,39 @@ impl<'lua> FromLua<'lua> for PdfColor {
                     .collect::<LuaResult<_>>()
                     .ok();
 
+                // Opacity between 0 and 1 applies to any of the color formats
+                let alpha = table.raw_get_ext::<_, Option<f32>>("alpha")?.unwrap_or(1.0);
+
                 // If we have color vec, check to make sure we have three, and use them as rgb
                 if let Some(v) = maybe_vec_u8 {
                     if v.len() >= 3 {
-                        return Ok(Self::from_rgb_u8(v[0], v[1], v[2]));
+                        return Ok(Self::from_rgb_u8(v[0], v[1], v[2]).with_alpha(alpha));
                     }
                 }
 
+                // Look for a gray level between 0 and 1
+                if let Some(gray) = table.raw_get_ext::<_, Option<f32>>("gray")? {
+                    return Ok(Self::from_gray_f32(gray).with_alpha(alpha));
+                }
+
+                let get_f32_field = |long_name: &str, short_name: &str| match table
+                    .raw_get_ext::<_, Option<f32>>(short_name)?
+                {
+                    Some(value) => Ok(value),
+                    None => table.raw_get_ext::<_, f32>(long_name),
+                };
+
+                // Look for cyan, magenta, yellow, black fields between 0 and 1
+                if table.contains_key("c")? || table.contains_key("cyan")? {
+                    return Ok(Self::from_cmyk_f32(
+                        get_f32_field("cyan", "c")?,
+                        get_f32_field("magenta", "m")?,
+                        get_f32_field("yellow", "y")?,
+                        get_f32_field("black", "k")?,
+                    )
+                    .with_alpha(alpha));
+                }
+
                 let get_field = |long_name: &str, short_name: &str| match table
                     .raw_get_ext::<_, Option<u8>>(short_name)?
                 {
@@ -217,7 +448,8 @@ impl<'lua> FromLua<'lua> for PdfColor {
                     get_field("red", "r")?,
                     get_field("green", "g")?,
                     get_field("blue", "b")?,
-                ))
+                )
+                .with_alpha(alpha))
             }
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
@@ -280,6 +512,25 @@ mod tests {
             .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_be_able_to_convert_to_grayscale() {
+        let color = PdfColor::from_rgb_u8(50, 150, 200).into_grayscale();
+        assert_eq!(color.into_colors_u8(), (132, 132, 132));
+        assert_eq!(PdfColor::white().into_grayscale(), PdfColor::white());
+
+        let color = PdfColor::from_cmyk_f32(0.0, 0.0, 0.0, 0.5).into_grayscale();
+        assert_eq!(color.into_colors_u8(), (128, 128, 128));
+        assert_eq!(PdfColor::from_gray_f32(1.0).into_grayscale(), PdfColor::white());
+    }
+
+    #[test]
+    fn should_become_rgb_when_channels_are_changed() {
+        let mut color = PdfColor::from_cmyk_f32(0.0, 0.0, 0.0, 1.0);
+        color.red = 1.0;
+        assert_eq!(color.space(), PdfColorSpace::Rgb);
+        assert_eq!(color, PdfColor::from_rgb_f32(1.0, 0.0, 0.0));
+    }
+
     #[test]
     fn should_be_able_to_lighten() {
         let color = PdfColor::from_rgb_u8(50, 150, 200).lighten(0.2);
@@ -414,4 +665,151 @@ mod tests {
             .exec()
             .expect("Assertion failed");
     }
+
+    #[test]
+    fn should_be_able_to_convert_cmyk_and_gray_from_lua() {
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ c = 1, m = 0.5, y = 0, k = 0.2 }))
+                .eval::<PdfColor>()
+                .unwrap()
+                .space(),
+            PdfColorSpace::Cmyk {
+                cyan: 1.0,
+                magenta: 0.5,
+                yellow: 0.0,
+                black: 0.2,
+            },
+        );
+
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ cyan = 0, magenta = 0, yellow = 0, black = 1 }))
+                .eval::<PdfColor>()
+                .unwrap(),
+            PdfColor::from_cmyk_f32(0.0, 0.0, 0.0, 1.0),
+        );
+
+        let color = Lua::new()
+            .load(chunk!({ gray = 0.5 }))
+            .eval::<PdfColor>()
+            .unwrap();
+        assert_eq!(color.space(), PdfColorSpace::Gray(0.5));
+        assert_eq!(color.into_colors_f32(), (0.5, 0.5, 0.5));
+
+        // Missing inks fail rather than defaulting
+        Lua::new()
+            .load(chunk!({ c = 1, m = 0.5 }))
+            .eval::<PdfColor>()
+            .expect_err("Unexpectedly converted incomplete cmyk");
+    }
+
+    #[test]
+    fn should_keep_color_space_when_converting_into_and_back_from_lua() {
+        let lua = Lua::new();
+        for color in [
+            PdfColor::from_rgb_u8(0, 128, 255),
+            PdfColor::from_cmyk_f32(0.25, 0.5, 0.75, 0.1),
+            PdfColor::from_gray_f32(0.25),
+        ] {
+            let value = color.into_lua(&lua).unwrap();
+            assert_eq!(PdfColor::from_lua(value, &lua).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn should_be_able_to_lighten_and_darken_within_color_space() {
+        let color = PdfColor::from_cmyk_f32(0.5, 0.0, 0.0, 0.5);
+        assert_eq!(
+            color.lighten(0.5).space(),
+            PdfColorSpace::Cmyk {
+                cyan: 0.25,
+                magenta: 0.0,
+                yellow: 0.0,
+                black: 0.25,
+            },
+        );
+        assert_eq!(
+            color.darken(0.5).space(),
+            PdfColorSpace::Cmyk {
+                cyan: 0.5,
+                magenta: 0.0,
+                yellow: 0.0,
+                black: 0.75,
+            },
+        );
+
+        let color = PdfColor::from_gray_f32(0.5);
+        assert_eq!(color.lighten(0.5).space(), PdfColorSpace::Gray(0.75));
+        assert_eq!(color.darken(0.5).space(), PdfColorSpace::Gray(0.25));
+    }
+
+    #[test]
+    fn should_be_able_to_convert_alpha_from_lua() {
+        // Eight digit hex includes the alpha
+        let color = Lua::new()
+            .load(chunk!("#0080FF80"))
+            .eval::<PdfColor>()
+            .unwrap();
+        assert_eq!(color.into_colors_u8(), (0, 128, 255));
+        assert_eq!(color.alpha(), 128.0 / 255.0);
+        assert_eq!(color.to_string(), "0080FF80");
+
+        // Alpha field applies to any color format
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ r = 0, g = 128, b = 255, alpha = 0.25 }))
+                .eval::<PdfColor>()
+                .unwrap(),
+            PdfColor::from_rgb_u8(0, 128, 255).with_alpha(0.25),
+        );
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ gray = 0.5, alpha = 0.25 }))
+                .eval::<PdfColor>()
+                .unwrap(),
+            PdfColor::from_gray_f32(0.5).with_alpha(0.25),
+        );
+
+        // Opaque colors do not include the alpha
+        let color = PdfColor::from_rgb_u8(0, 128, 255);
+        assert!(!color.is_translucent());
+        assert_eq!(color.to_string(), "0080FF");
+    }
+
+    #[test]
+    fn should_keep_alpha_when_adjusting_color() {
+        let color = PdfColor::from_rgb_u8(50, 150, 200).with_alpha(0.5);
+        assert_eq!(color.lighten(0.2).alpha(), 0.5);
+        assert_eq!(color.darken(0.2).alpha(), 0.5);
+        assert_eq!(color.into_grayscale().alpha(), 0.5);
+
+        let lua = Lua::new();
+        let value = color.into_lua(&lua).unwrap();
+        assert_eq!(PdfColor::from_lua(value, &lua).unwrap(), color);
+    }
+
+    #[test]
+    fn should_be_able_to_parse_named_colors() {
+        assert_eq!(
+            "rebeccapurple".parse::<PdfColor>().unwrap(),
+            PdfColor::from_rgb_u8(102, 51, 153)
+        );
+        assert_eq!(
+            "SteelBlue".parse::<PdfColor>().unwrap(),
+            PdfColor::from_rgb_u8(70, 130, 180)
+        );
+        assert_eq!("transparent".parse::<PdfColor>().unwrap().alpha(), 0.0);
+        "notacolor"
+            .parse::<PdfColor>()
+            .expect_err("Unexpectedly parsed unknown color name");
+
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("steelblue"))
+                .eval::<PdfColor>()
+                .unwrap(),
+            PdfColor::from_rgb_u8(70, 130, 180),
+        );
+    }
 }
@@ -130,6 +130,44 @@ impl PdfDate {
         Self::end_of_month(self.year(), self.month()).unwrap()
     }
 
+    /// Returns the quarter (1 to 4) containing the date.
+    pub fn quarter(self) -> u32 {
+        self.month0() / 3 + 1
+    }
+
+    /// Creates a new date for `year` at beginning of `quarter` (1 to 4). Returns None if invalid.
+    pub fn beginning_of_quarter(year: i32, quarter: u32) -> Option<Self> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+
+        Self::beginning_of_month(year, (quarter - 1) * 3 + 1)
+    }
+
+    /// Returns a new date representing beginning of quarter for current date.
+    pub fn into_beginning_of_quarter(self) -> Self {
+        Self::beginning_of_quarter(self.year(), self.quarter()).unwrap()
+    }
+
+    /// Creates a new date for `year` at end of `quarter` (1 to 4). Returns None if invalid.
+    pub fn end_of_quarter(year: i32, quarter: u32) -> Option<Self> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+
+        Self::end_of_month(year, quarter * 3)
+    }
+
+    /// Returns a new date representing end of quarter for current date.
+    pub fn into_end_of_quarter(self) -> Self {
+        Self::end_of_quarter(self.year(), self.quarter()).unwrap()
+    }
+
+    /// Returns the total days (28 to 31) within the month of the date.
+    pub fn days_in_month(self) -> u32 {
+        self.into_end_of_month().day()
+    }
+
     /// Returns a new date representing beginning of week (Sunday-based) for current date.
     pub fn into_beginning_of_week_sunday(self) -> Self {
         let weekday = self.weekday();
@@ -225,6 +263,35 @@ impl PdfDate {
         self.add_months(-1)
     }
 
+    /// Returns the number of days from the current date to `other`, which is negative when
+    /// `other` comes before the current date.
+    pub fn days_between(self, other: Self) -> i64 {
+        other.0.signed_duration_since(self.0).num_days()
+    }
+
+    /// Returns true if the current date is within `a` and `b` (inclusive), regardless of whether
+    /// `a` comes before or after `b`.
+    pub fn is_between(self, a: Self, b: Self) -> bool {
+        let (start, end) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+        start.0 <= self.0 && self.0 <= end.0
+    }
+
+    /// Returns an iterator over each day from the current date up to and including `other`,
+    /// which is empty if `other` comes before the current date.
+    pub fn upto(self, other: Self) -> impl Iterator<Item = Self> {
+        std::iter::successors(Some(self), |date| date.tomorrow())
+            .take_while(move |date| date.0 <= other.0)
+    }
+
+    /// Returns the ISO 8601 week number, where weeks start on Monday and the first week of the
+    /// year contains the first Thursday. The value can be between 1 and 53.
+    ///
+    /// Note that dates near the beginning or end of a year can belong to a week of the adjacent
+    /// year (e.g. 2024-12-30 is week 1 of 2025).
+    pub fn iso_week(self) -> u32 {
+        self.0.iso_week().week()
+    }
+
     /// Returns the week where Sunday is the start of the week. The value can be between 1 and 53.
     pub fn calendar_week_sunday(self) -> u32 {
         let ordinal = self.0.ordinal0();
@@ -310,7 +377,225 @@ impl FromStr for PdfDate {
 impl<'lua> IntoLua<'lua> for PdfDate {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.common.date", |metatable| {
+            metatable.raw_set(
+                "format",
+                lua.create_function(move |_, (this, format): (PdfDate, String)| {
+                    Ok(this.0.format(format.as_str()).to_string())
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "add_days",
+                lua.create_function(move |_, (this, days): (PdfDate, i64)| {
+                    this.add_days(days)
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "tomorrow",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.tomorrow()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "yesterday",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.yesterday()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "add_weeks",
+                lua.create_function(move |_, (this, weeks): (PdfDate, i64)| {
+                    this.add_weeks(weeks)
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "next_week",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.next_week()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "last_week",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.last_week()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "add_months",
+                lua.create_function(move |_, (this, months): (PdfDate, i32)| {
+                    this.add_months(months)
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "next_month",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.next_month()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "last_month",
+                lua.create_function(move |_, this: PdfDate| {
+                    this.last_month()
+                        .ok_or_else(|| LuaError::runtime("resulting date out of range"))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "beginning_of_year",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_year()))?,
+            )?;
+
+            metatable.raw_set(
+                "end_of_year",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_year()))?,
+            )?;
+
+            metatable.raw_set(
+                "beginning_of_month",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_month()))?,
+            )?;
+
+            metatable.raw_set(
+                "end_of_month",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_month()))?,
+            )?;
+
+            metatable.raw_set(
+                "beginning_of_quarter",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_quarter()))?,
+            )?;
+
+            metatable.raw_set(
+                "end_of_quarter",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_quarter()))?,
+            )?;
+
+            metatable.raw_set(
+                "quarter",
+                lua.create_function(move |_, this: PdfDate| Ok(this.quarter()))?,
+            )?;
+
+            metatable.raw_set(
+                "days_in_month",
+                lua.create_function(move |_, this: PdfDate| Ok(this.days_in_month()))?,
+            )?;
+
+            metatable.raw_set(
+                "beginning_of_week_sunday",
+                lua.create_function(move |_, this: PdfDate| {
+                    Ok(this.into_beginning_of_week_sunday())
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "end_of_week_sunday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_week_sunday()))?,
+            )?;
+
+            metatable.raw_set(
+                "beginning_of_week_monday",
+                lua.create_function(move |_, this: PdfDate| {
+                    Ok(this.into_beginning_of_week_monday())
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "end_of_week_monday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_week_monday()))?,
+            )?;
+
+            metatable.raw_set(
+                "weeks_in_month_sunday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.weeks_in_month_sunday()))?,
+            )?;
+
+            metatable.raw_set(
+                "weeks_in_month_monday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.weeks_in_month_monday()))?,
+            )?;
+
+            metatable.raw_set(
+                "calendar_week_sunday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.calendar_week_sunday()))?,
+            )?;
+
+            metatable.raw_set(
+                "calendar_week_monday",
+                lua.create_function(move |_, this: PdfDate| Ok(this.calendar_week_monday()))?,
+            )?;
+
+            metatable.raw_set(
+                "iso_week",
+                lua.create_function(move |_, this: PdfDate| Ok(this.iso_week()))?,
+            )?;
+
+            metatable.raw_set(
+                "days_between",
+                lua.create_function(move |_, (this, other): (PdfDate, PdfDate)| {
+                    Ok(this.days_between(other))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "is_between",
+                lua.create_function(move |_, (this, a, b): (PdfDate, PdfDate, PdfDate)| {
+                    Ok(this.is_between(a, b))
+                })?,
+            )?;
+
+            // Returns an iterator function to use with a generic for loop
+            metatable.raw_set(
+                "upto",
+                lua.create_function(move |lua, (this, other): (PdfDate, PdfDate)| {
+                    let mut dates = this.upto(other);
+                    lua.create_function_mut(move |_, ()| Ok(dates.next()))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "__eq",
+                lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 == b.0))?,
+            )?;
+
+            metatable.raw_set(
+                "__lt",
+                lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 < b.0))?,
+            )?;
+
+            metatable.raw_set(
+                "__le",
+                lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 <= b.0))?,
+            )?;
+
+            // Return copy of the date as a string.
+            metatable.raw_set(
+                "__tostring",
+                // NOTE: We have to use `LuaTable` instead of `PdfDate` as leveraging `PdfDate`
+                //       here causes infinite recursion when trying to resolve!
+                lua.create_function(move |_, tbl: LuaTable| {
+                    Ok(Self::from_lua_table(&tbl)?.to_string())
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         table.raw_set("year", self.0.year())?;
         table.raw_set("month", self.0.month())?;
@@ -319,168 +604,6 @@ impl<'lua> IntoLua<'lua> for PdfDate {
         table.raw_set("weekday", self.weekday())?;
         table.raw_set("ordinal", self.0.ordinal())?;
 
-        metatable.raw_set(
-            "format",
-            lua.create_function(move |_, (this, format): (PdfDate, String)| {
-                Ok(this.0.format(format.as_str()).to_string())
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "add_days",
-            lua.create_function(move |_, (this, days): (PdfDate, i64)| {
-                this.add_days(days)
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "tomorrow",
-            lua.create_function(move |_, this: PdfDate| {
-                this.tomorrow()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "yesterday",
-            lua.create_function(move |_, this: PdfDate| {
-                this.yesterday()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "add_weeks",
-            lua.create_function(move |_, (this, weeks): (PdfDate, i64)| {
-                this.add_weeks(weeks)
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "next_week",
-            lua.create_function(move |_, this: PdfDate| {
-                this.next_week()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "last_week",
-            lua.create_function(move |_, this: PdfDate| {
-                this.last_week()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "add_months",
-            lua.create_function(move |_, (this, months): (PdfDate, i32)| {
-                this.add_months(months)
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "next_month",
-            lua.create_function(move |_, this: PdfDate| {
-                this.next_month()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "last_month",
-            lua.create_function(move |_, this: PdfDate| {
-                this.last_month()
-                    .ok_or_else(|| LuaError::runtime("resulting date out of range"))
-            })?,
-        )?;
-
-        metatable.raw_set(
-            "beginning_of_year",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_year()))?,
-        )?;
-
-        metatable.raw_set(
-            "end_of_year",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_year()))?,
-        )?;
-
-        metatable.raw_set(
-            "beginning_of_month",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_month()))?,
-        )?;
-
-        metatable.raw_set(
-            "end_of_month",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_month()))?,
-        )?;
-
-        metatable.raw_set(
-            "beginning_of_week_sunday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_week_sunday()))?,
-        )?;
-
-        metatable.raw_set(
-            "end_of_week_sunday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_week_sunday()))?,
-        )?;
-
-        metatable.raw_set(
-            "beginning_of_week_monday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_beginning_of_week_monday()))?,
-        )?;
-
-        metatable.raw_set(
-            "end_of_week_monday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.into_end_of_week_monday()))?,
-        )?;
-
-        metatable.raw_set(
-            "weeks_in_month_sunday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.weeks_in_month_sunday()))?,
-        )?;
-
-        metatable.raw_set(
-            "weeks_in_month_monday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.weeks_in_month_monday()))?,
-        )?;
-
-        metatable.raw_set(
-            "calendar_week_sunday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.calendar_week_sunday()))?,
-        )?;
-
-        metatable.raw_set(
-            "calendar_week_monday",
-            lua.create_function(move |_, this: PdfDate| Ok(this.calendar_week_monday()))?,
-        )?;
-
-        metatable.raw_set(
-            "__eq",
-            lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 == b.0))?,
-        )?;
-        metatable.raw_set(
-            "__lt",
-            lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 < b.0))?,
-        )?;
-        metatable.raw_set(
-            "__le",
-            lua.create_function(|_, (a, b): (PdfDate, PdfDate)| Ok(a.0 <= b.0))?,
-        )?;
-
-        // Return copy of the date as a string.
-        metatable.raw_set(
-            "__tostring",
-            // NOTE: We have to use `LuaTable` instead of `PdfDate` as leveraging `PdfDate`
-            //       here causes infinite recursion when trying to resolve!
-            lua.create_function(move |_, tbl: LuaTable| {
-                Ok(Self::from_lua_table(&tbl)?.to_string())
-            })?,
-        )?;
-
         Ok(LuaValue::Table(table))
     }
 }
@@ -1108,6 +1231,250 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_be_able_to_get_quarter_in_lua() {
+        for (month, quarter) in [
+            (1, 1),
+            (3, 1),
+            (4, 2),
+            (6, 2),
+            (7, 3),
+            (9, 3),
+            (10, 4),
+            (12, 4),
+        ] {
+            let date = PdfDate(NaiveDate::from_ymd_opt(2024, month, 15).unwrap());
+            assert_eq!(
+                Lua::new()
+                    .load(chunk!($date:quarter()))
+                    .eval::<u32>()
+                    .unwrap(),
+                quarter,
+                "Wrong quarter for month {month}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_be_able_to_get_beginning_of_quarter_in_lua() {
+        // From middle of a quarter
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 8, 14).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:beginning_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()),
+        );
+
+        // From beginning of a quarter
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:beginning_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()),
+        );
+
+        // From end of year
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:beginning_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()),
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_get_end_of_quarter_in_lua() {
+        // From middle of a quarter
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 8, 14).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:end_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 9, 30).unwrap()),
+        );
+
+        // From end of a quarter
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 3, 31).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:end_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()),
+        );
+
+        // From beginning of year
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:end_of_quarter()))
+                .eval::<PdfDate>()
+                .unwrap(),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()),
+        );
+    }
+
+    #[test]
+    fn should_fail_to_create_quarter_outside_of_range() {
+        assert_eq!(PdfDate::beginning_of_quarter(2024, 0), None);
+        assert_eq!(PdfDate::beginning_of_quarter(2024, 5), None);
+        assert_eq!(PdfDate::end_of_quarter(2024, 0), None);
+        assert_eq!(PdfDate::end_of_quarter(2024, 5), None);
+    }
+
+    #[test]
+    fn should_be_able_to_get_days_in_month_in_lua() {
+        // From a leap year february, regular february, 30-day month, and 31-day month
+        for ((year, month), days) in [
+            ((2024, 2), 29),
+            ((2023, 2), 28),
+            ((2024, 9), 30),
+            ((2024, 12), 31),
+        ] {
+            let date = PdfDate(NaiveDate::from_ymd_opt(year, month, 10).unwrap());
+            assert_eq!(
+                Lua::new()
+                    .load(chunk!($date:days_in_month()))
+                    .eval::<u32>()
+                    .unwrap(),
+                days,
+                "Wrong days for {year}/{month}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_be_able_to_get_iso_week_in_lua() {
+        // From first days of a year belonging to the last week of the previous year
+        let date = PdfDate(NaiveDate::from_ymd_opt(2021, 1, 3).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:iso_week()))
+                .eval::<u32>()
+                .unwrap(),
+            53
+        );
+
+        // From middle of a year
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 9, 14).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:iso_week()))
+                .eval::<u32>()
+                .unwrap(),
+            37
+        );
+
+        // From last days of a year belonging to the first week of the next year
+        let date = PdfDate(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!($date:iso_week()))
+                .eval::<u32>()
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_get_days_between_in_lua() {
+        let a = PdfDate(NaiveDate::from_ymd_opt(2024, 2, 27).unwrap());
+        let b = PdfDate(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ $a:days_between($b), $b:days_between($a), $a:days_between($a) }))
+                .eval::<Vec<i64>>()
+                .unwrap(),
+            vec![4, -4, 0],
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_check_if_date_is_between_in_lua() {
+        let a = PdfDate(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap());
+        let b = PdfDate(NaiveDate::from_ymd_opt(2024, 9, 30).unwrap());
+
+        // Dates within the range, including the ends, regardless of order
+        for date in [a, b, PdfDate(NaiveDate::from_ymd_opt(2024, 9, 14).unwrap())] {
+            assert!(Lua::new()
+                .load(chunk!($date:is_between($a, $b) and $date:is_between($b, $a)))
+                .eval::<bool>()
+                .unwrap());
+        }
+
+        // Dates outside of the range
+        for date in [
+            PdfDate(NaiveDate::from_ymd_opt(2024, 8, 31).unwrap()),
+            PdfDate(NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()),
+        ] {
+            assert!(!Lua::new()
+                .load(chunk!($date:is_between($a, $b) or $date:is_between($b, $a)))
+                .eval::<bool>()
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn should_be_able_to_iterate_days_upto_date_in_lua() {
+        // From a range spanning months, which includes the final date
+        let a = PdfDate(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap());
+        let b = PdfDate(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!(
+            Lua::new()
+                .load(chunk! {
+                    local dates = {}
+                    for date in $a:upto($b) do
+                        table.insert(dates, date)
+                    end
+                    return dates
+                })
+                .eval::<Vec<PdfDate>>()
+                .unwrap(),
+            vec![
+                PdfDate(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap()),
+                PdfDate(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()),
+                PdfDate(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()),
+            ],
+        );
+
+        // From a single day range
+        assert_eq!(
+            Lua::new()
+                .load(chunk! {
+                    local dates = {}
+                    for date in $a:upto($a) do
+                        table.insert(dates, date)
+                    end
+                    return dates
+                })
+                .eval::<Vec<PdfDate>>()
+                .unwrap(),
+            vec![a],
+        );
+
+        // From a range that ends before it begins
+        assert_eq!(
+            Lua::new()
+                .load(chunk! {
+                    local dates = {}
+                    for date in $b:upto($a) do
+                        table.insert(dates, date)
+                    end
+                    return dates
+                })
+                .eval::<Vec<PdfDate>>()
+                .unwrap(),
+            vec![],
+        );
+    }
+
     #[test]
     fn should_be_able_to_get_beginning_of_week_sunday_in_lua() {
         // From a Sunday within same month
@@ -0,0 +1,432 @@
+use mlua::prelude::*;
+use std::borrow::Cow;
+
+/// Direction that text is written in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfTextDirection {
+    /// Left-to-right, such as Latin, Cyrillic, and Greek.
+    #[default]
+    Ltr,
+    /// Right-to-left, such as Hebrew and Arabic.
+    Rtl,
+}
+
+impl PdfTextDirection {
+    /// Detects the direction of `text` from its first strongly directional character, defaulting
+    /// to left-to-right.
+    pub fn detect(text: &str) -> Self {
+        text.chars()
+            .find_map(|ch| match BidiClass::of(ch) {
+                BidiClass::L => Some(Self::Ltr),
+                BidiClass::R => Some(Self::Rtl),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the direction is right-to-left.
+    #[inline]
+    pub fn is_rtl(self) -> bool {
+        matches!(self, Self::Rtl)
+    }
+
+    /// Converts `text` from the order it is written in (logical) into the order that its
+    /// characters are drawn from left to right (visual), using this as the direction of the text
+    /// as a whole.
+    ///
+    /// Arabic letters are replaced with their joined forms, right-to-left runs are reversed with
+    /// their brackets mirrored, and numbers within them keep reading left-to-right.
+    pub fn to_visual(self, text: &str) -> Cow<'_, str> {
+        if self == Self::Ltr && !text.chars().any(|ch| BidiClass::of(ch) == BidiClass::R) {
+            return Cow::Borrowed(text);
+        }
+
+        let chars = shape_arabic(text);
+        let levels = resolve_levels(&chars, self);
+        Cow::Owned(reorder(chars, levels))
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfTextDirection {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfTextDirection {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "ltr" => Ok(Self::Ltr),
+                "rtl" => Ok(Self::Rtl),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.text_direction",
+                    message: Some(format!("unknown direction: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.text_direction",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Simplified classes of the unicode bidirectional algorithm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum BidiClass {
+    /// Strong left-to-right.
+    L,
+    /// Strong right-to-left.
+    R,
+    /// Numbers, which are read left-to-right even within right-to-left text.
+    N,
+    /// Neutral, such as spaces and punctuation, which take the direction of the text around them.
+    O,
+}
+
+impl BidiClass {
+    fn of(ch: char) -> Self {
+        match ch {
+            '0'..='9' | '\u{0660}'..='\u{0669}' | '\u{06F0}'..='\u{06F9}' => Self::N,
+            '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' => Self::R,
+            ch if ch.is_alphabetic() => Self::L,
+            _ => Self::O,
+        }
+    }
+}
+
+/// Resolves the embedding level of each character, where odd levels are drawn right-to-left.
+fn resolve_levels(chars: &[char], direction: PdfTextDirection) -> Vec<u8> {
+    let base = direction.is_rtl() as u8;
+
+    // Numbers take the direction of the strong text before them, and afterwards neutrals take
+    // the direction shared by the text on either side of them or otherwise that of the paragraph
+    let mut classes: Vec<BidiClass> = chars.iter().copied().map(BidiClass::of).collect();
+    let mut prev = if direction.is_rtl() {
+        BidiClass::R
+    } else {
+        BidiClass::L
+    };
+    let mut numbers = vec![false; chars.len()];
+    for (i, class) in classes.iter_mut().enumerate() {
+        match class {
+            BidiClass::L | BidiClass::R => prev = *class,
+            BidiClass::N => {
+                numbers[i] = true;
+                *class = prev;
+            }
+            BidiClass::O => (),
+        }
+    }
+
+    let mut i = 0;
+    while i < classes.len() {
+        if classes[i] != BidiClass::O {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < classes.len() && classes[i] == BidiClass::O {
+            i += 1;
+        }
+
+        let before = start.checked_sub(1).map(|j| classes[j]);
+        let after = classes.get(i).copied();
+        let class = match (before, after) {
+            (Some(a), Some(b)) if a == b => a,
+            _ if direction.is_rtl() => BidiClass::R,
+            _ => BidiClass::L,
+        };
+        classes[start..i].fill(class);
+    }
+
+    classes
+        .into_iter()
+        .zip(numbers)
+        .map(|(class, number)| match class {
+            // Numbers within right-to-left text are embedded one level deeper so they stay
+            // left-to-right once the text around them is reversed
+            BidiClass::R if number => 2,
+            BidiClass::R => 1,
+            _ => base + base,
+        })
+        .collect()
+}
+
+/// Reverses each run of characters at or above each odd level from the highest level down,
+/// mirroring brackets drawn right-to-left.
+fn reorder(mut chars: Vec<char>, levels: Vec<u8>) -> String {
+    for (ch, level) in chars.iter_mut().zip(levels.iter()) {
+        if level % 2 == 1 {
+            *ch = mirror(*ch);
+        }
+    }
+
+    let highest = levels.iter().copied().max().unwrap_or_default();
+    for level in (1..=highest).rev() {
+        let mut i = 0;
+        while i < chars.len() {
+            if levels[i] < level {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < chars.len() && levels[i] >= level {
+                i += 1;
+            }
+            chars[start..i].reverse();
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Returns the mirrored form of `ch` when drawn right-to-left, such as `)` for `(`.
+fn mirror(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        ch => ch,
+    }
+}
+
+/// Joining behavior of an Arabic letter alongside its isolated presentation form, where the
+/// final, initial, and medial forms follow it in that order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Joining {
+    /// Joins on both sides.
+    Dual(u32),
+    /// Joins only to the letter before it.
+    Right(u32),
+    /// Causes joins on both sides without changing form, such as tatweel.
+    Causing,
+    /// Does not affect joining, such as vowel marks.
+    Transparent,
+}
+
+impl Joining {
+    fn of(ch: char) -> Option<Self> {
+        // Isolated forms of the letters from U+0621 to U+064A, where those joining only to the
+        // letter before them have two forms and the rest have four
+        const FORMS: [(u32, bool); 42] = [
+            (0xFE80, false),
+            (0xFE81, false),
+            (0xFE83, false),
+            (0xFE85, false),
+            (0xFE87, false),
+            (0xFE89, true),
+            (0xFE8D, false),
+            (0xFE8F, true),
+            (0xFE93, false),
+            (0xFE95, true),
+            (0xFE99, true),
+            (0xFE9D, true),
+            (0xFEA1, true),
+            (0xFEA5, true),
+            (0xFEA9, false),
+            (0xFEAB, false),
+            (0xFEAD, false),
+            (0xFEAF, false),
+            (0xFEB1, true),
+            (0xFEB5, true),
+            (0xFEB9, true),
+            (0xFEBD, true),
+            (0xFEC1, true),
+            (0xFEC5, true),
+            (0xFEC9, true),
+            (0xFECD, true),
+            (0, false),
+            (0, false),
+            (0, false),
+            (0, false),
+            (0, false),
+            (0, false),
+            (0xFED1, true),
+            (0xFED5, true),
+            (0xFED9, true),
+            (0xFEDD, true),
+            (0xFEE1, true),
+            (0xFEE5, true),
+            (0xFEE9, true),
+            (0xFEED, false),
+            (0xFEEF, false),
+            (0xFEF1, true),
+        ];
+
+        match ch {
+            // Hamza has only an isolated form, so it never joins
+            '\u{0621}' => None,
+            '\u{0640}' => Some(Self::Causing),
+            '\u{064B}'..='\u{065F}' | '\u{0670}' => Some(Self::Transparent),
+            '\u{0622}'..='\u{064A}' => match FORMS[ch as usize - 0x0621] {
+                (0, _) => None,
+                (form, true) => Some(Self::Dual(form)),
+                (form, false) => Some(Self::Right(form)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if the letter joins to the letter after it.
+    fn joins_next(self) -> bool {
+        matches!(self, Self::Dual(_) | Self::Causing)
+    }
+
+    /// Returns true if the letter joins to the letter before it.
+    fn joins_prev(self) -> bool {
+        matches!(self, Self::Dual(_) | Self::Right(_) | Self::Causing)
+    }
+}
+
+/// Replaces Arabic letters within `text` with the presentation forms that join them together,
+/// including the ligatures of lam followed by alef.
+fn shape_arabic(text: &str) -> Vec<char> {
+    let chars: Vec<char> = text.chars().collect();
+    let joining: Vec<Option<Joining>> = chars.iter().copied().map(Joining::of).collect();
+
+    // Finds the closest letter in the given direction, skipping over vowel marks
+    let neighbor = |i: usize, forward: bool| -> Option<(usize, Joining)> {
+        let mut indices: Box<dyn Iterator<Item = usize>> = if forward {
+            Box::new(i + 1..chars.len())
+        } else {
+            Box::new((0..i).rev())
+        };
+        indices.find_map(|j| match joining[j] {
+            Some(Joining::Transparent) => None,
+            x => Some(x.map(|x| (j, x))),
+        })?
+    };
+
+    let mut shaped = Vec::with_capacity(chars.len());
+    let mut skip = None;
+    for (i, ch) in chars.iter().copied().enumerate() {
+        if skip == Some(i) {
+            continue;
+        }
+
+        let form = match joining[i] {
+            Some(Joining::Dual(form)) | Some(Joining::Right(form)) => form,
+            _ => {
+                shaped.push(ch);
+                continue;
+            }
+        };
+        let this = joining[i].unwrap();
+        let prev = neighbor(i, false).is_some_and(|(_, x)| x.joins_next());
+        let next = neighbor(i, true).filter(|(_, x)| this.joins_next() && x.joins_prev());
+
+        // Lam followed by alef is drawn as a single ligature
+        let ligature = match (ch, next.map(|(j, _)| (j, chars[j]))) {
+            ('\u{0644}', Some((j, '\u{0622}'))) => Some((j, 0xFEF5)),
+            ('\u{0644}', Some((j, '\u{0623}'))) => Some((j, 0xFEF7)),
+            ('\u{0644}', Some((j, '\u{0625}'))) => Some((j, 0xFEF9)),
+            ('\u{0644}', Some((j, '\u{0627}'))) => Some((j, 0xFEFB)),
+            _ => None,
+        };
+        let code = match ligature {
+            Some((j, form)) => {
+                skip = Some(j);
+                form + prev as u32
+            }
+            None => match (prev, next.is_some()) {
+                (false, false) => form,
+                (true, false) => form + 1,
+                (false, true) => form + 2,
+                (true, true) => form + 3,
+            },
+        };
+
+        shaped.push(char::from_u32(code).unwrap_or(ch));
+    }
+
+    shaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn detect_should_use_first_strong_character() {
+        assert_eq!(PdfTextDirection::detect("123 hello"), PdfTextDirection::Ltr);
+        assert_eq!(PdfTextDirection::detect("123 שלום"), PdfTextDirection::Rtl);
+        assert_eq!(
+            PdfTextDirection::detect("مرحبا hello"),
+            PdfTextDirection::Rtl
+        );
+        assert_eq!(PdfTextDirection::detect("123"), PdfTextDirection::Ltr);
+    }
+
+    #[test]
+    fn to_visual_should_reverse_right_to_left_runs() {
+        let ltr = PdfTextDirection::Ltr;
+        let rtl = PdfTextDirection::Rtl;
+
+        // Text without right-to-left characters is untouched when left-to-right
+        assert!(matches!(ltr.to_visual("hello (world)"), Cow::Borrowed(_)));
+
+        // Hebrew is reversed, keeping numbers left-to-right and mirroring brackets
+        assert_eq!(rtl.to_visual("שלום עולם"), "םלוע םולש");
+        assert_eq!(rtl.to_visual("יום 12 (א)"), "(א) 12 םוי");
+
+        // Embedded runs of the other direction keep their own order
+        assert_eq!(ltr.to_visual("hello שלום world"), "hello םולש world");
+        assert_eq!(rtl.to_visual("שלום hello world"), "hello world םולש");
+    }
+
+    #[test]
+    fn to_visual_should_join_arabic_letters() {
+        let rtl = PdfTextDirection::Rtl;
+
+        // Beh + Alef + Beh: initial beh joins final alef, which does not join the isolated beh
+        assert_eq!(
+            rtl.to_visual("\u{0628}\u{0627}\u{0628}"),
+            "\u{FE8F}\u{FE8E}\u{FE91}"
+        );
+
+        // Beh + Beh + Beh: initial, medial, and final forms
+        assert_eq!(
+            rtl.to_visual("\u{0628}\u{0628}\u{0628}"),
+            "\u{FE90}\u{FE92}\u{FE91}"
+        );
+
+        // Lam + Alef forms a ligature
+        assert_eq!(rtl.to_visual("\u{0644}\u{0627}"), "\u{FEFB}");
+    }
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("rtl"))
+                .eval::<PdfTextDirection>()
+                .unwrap(),
+            PdfTextDirection::Rtl,
+        );
+        assert!(Lua::new()
+            .load(chunk!("up"))
+            .eval::<PdfTextDirection>()
+            .is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::PdfThemed;
 use mlua::chunk;
 use mlua::prelude::*;
 use std::fmt;
@@ -9,6 +11,18 @@ pub trait PdfLuaExt {
     /// Returns the (table, metatable) pair.
     fn create_table_ext(&self) -> LuaResult<(LuaTable, LuaTable)>;
 
+    /// Creates a new [`LuaTable`] whose metatable is shared with every other table created using
+    /// the same `name`, invoking `init` to populate the metatable the first time. Like
+    /// [`PdfLuaExt::create_table_ext`], the metatable has `__index` preset to itself.
+    ///
+    /// Sharing the metatable avoids creating it (and its functions) every time a value is
+    /// converted into Lua, so its functions must not capture anything specific to one table.
+    fn create_table_with_metatable<'lua>(
+        &'lua self,
+        name: &str,
+        init: impl FnOnce(&LuaTable<'lua>) -> LuaResult<()>,
+    ) -> LuaResult<LuaTable<'lua>>;
+
     /// Marks a table as read-only. This both sets the flag for `Luau` and also overwrites the
     /// `__newindex` metatable field to fail when attempting to change a field.
     fn mark_readonly(&self, tbl: LuaTable) -> LuaResult<()>;
@@ -25,6 +39,27 @@ impl PdfLuaExt for Lua {
         Ok((table, metatable))
     }
 
+    fn create_table_with_metatable<'lua>(
+        &'lua self,
+        name: &str,
+        init: impl FnOnce(&LuaTable<'lua>) -> LuaResult<()>,
+    ) -> LuaResult<LuaTable<'lua>> {
+        let metatable = match self.named_registry_value::<Option<LuaTable>>(name)? {
+            Some(metatable) => metatable,
+            None => {
+                let metatable = self.create_table()?;
+                metatable.raw_set("__index", metatable.clone())?;
+                init(&metatable)?;
+                self.set_named_registry_value(name, metatable.clone())?;
+                metatable
+            }
+        };
+
+        let table = self.create_table()?;
+        table.set_metatable(Some(metatable));
+        Ok(table)
+    }
+
     fn mark_readonly(&self, tbl: LuaTable) -> LuaResult<()> {
         let metatable = match tbl.get_metatable() {
             Some(x) => x,
@@ -46,11 +81,17 @@ impl PdfLuaExt for Lua {
 }
 
 pub trait PdfLuaTableExt {
-    /// Like [`LuaTable::raw_get`], but provides a more detailed error message.
+    /// Like [`LuaTable::raw_get`], but provides a more detailed error message and resolves
+    /// references to theme values (e.g. `"@accent"`) that cannot be converted directly.
     fn raw_get_ext<'lua, K, V>(&'lua self, key: K) -> LuaResult<V>
     where
         K: IntoLua<'lua> + Copy + fmt::Display,
         V: FromLua<'lua>;
+
+    /// Fails if strict mode is enabled through `pdf.strict` and the table has a named field other
+    /// than those within `fields`, suggesting the field with the nearest name. Positional fields
+    /// are always allowed.
+    fn check_fields(&self, lua: &Lua, to: &'static str, fields: &[&str]) -> LuaResult<()>;
 }
 
 impl PdfLuaTableExt for LuaTable<'_> {
@@ -59,7 +100,7 @@ impl PdfLuaTableExt for LuaTable<'_> {
         K: IntoLua<'lua> + Copy + fmt::Display,
         V: FromLua<'lua>,
     {
-        match self.raw_get(key) {
+        match self.raw_get::<_, PdfThemed<V>>(key).map(|x| x.0) {
             Err(LuaError::FromLuaConversionError { from, to, message }) => {
                 Err(LuaError::FromLuaConversionError {
                     from,
@@ -76,4 +117,154 @@ impl PdfLuaTableExt for LuaTable<'_> {
             x => x,
         }
     }
+
+    fn check_fields(&self, lua: &Lua, to: &'static str, fields: &[&str]) -> LuaResult<()> {
+        let strict = lua
+            .globals()
+            .raw_get::<_, Option<LuaTable>>(GLOBAL_PDF_VAR_NAME)?
+            .and_then(|pdf| pdf.raw_get::<_, Option<bool>>("strict").ok().flatten())
+            .unwrap_or_default();
+        if !strict {
+            return Ok(());
+        }
+
+        for pair in self.clone().pairs::<LuaValue, LuaValue>() {
+            let key = match pair?.0 {
+                LuaValue::String(key) => key.to_string_lossy().to_string(),
+                _ => continue,
+            };
+
+            if !fields.contains(&key.as_str()) {
+                let suggestion = fields
+                    .iter()
+                    .map(|field| (edit_distance(&key, field), field))
+                    .filter(|(distance, _)| *distance <= 3)
+                    .min_by_key(|(distance, _)| *distance)
+                    .map(|(_, field)| format!(", did you mean '{field}'?"))
+                    .unwrap_or_default();
+
+                return Err(LuaError::FromLuaConversionError {
+                    from: "table",
+                    to,
+                    message: Some(format!("unknown field '{key}'{suggestion}")),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the number of characters that need to be inserted, removed, or replaced to turn `a`
+/// into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(cur)
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{Pdf, PdfConfig, PdfObject};
+    use mlua::chunk;
+
+    /// Creates a runtime with the pdf global in strict mode when `strict` is true.
+    fn lua_with_strict(strict: bool) -> Lua {
+        let lua = Lua::new();
+        lua.globals()
+            .raw_set(
+                GLOBAL_PDF_VAR_NAME,
+                Pdf::new(PdfConfig {
+                    strict,
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+        lua
+    }
+
+    #[test]
+    fn check_fields_should_reject_unknown_fields_in_strict_mode() {
+        let lua = lua_with_strict(false);
+        lua.load(chunk!({ type = "rect", fill_colour = "#ff0000" }))
+            .eval::<PdfObject>()
+            .expect("Unexpectedly rejected unknown field outside of strict mode");
+
+        let lua = lua_with_strict(true);
+        let err = lua
+            .load(chunk!({ type = "rect", fill_colour = "#ff0000" }))
+            .eval::<PdfObject>()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("unknown field 'fill_colour', did you mean 'fill_color'?"),
+            "Unexpected error: {err}"
+        );
+
+        // Fields without a similar name have no suggestion
+        let err = lua
+            .load(chunk!({ type = "text", something = true }))
+            .eval::<PdfObject>()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("unknown field 'something')"),
+            "Unexpected error: {err}"
+        );
+
+        // Strict mode can be toggled by the script and positional fields are always allowed
+        lua.load(chunk! {
+            pdf.object.line({ { 0, 0 }, { 10, 10 }, color = "#ff0000" })
+            pdf.object.group({ pdf.object.rect({ ll = { 0, 0 }, ur = { 5, 5 } }), size = 12 })
+            pdf.strict = false
+            pdf.object.circle({ radious = 5 })
+        })
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn check_fields_should_accept_objects_converted_into_lua_in_strict_mode() {
+        let lua = lua_with_strict(true);
+        lua.load(chunk! {
+            local objects = {
+                pdf.object.circle({ center = { 5, 5 }, radius = 5, fill_color = "#ff0000" }),
+                pdf.object.line({ { 0, 0 }, { 10, 10 }, thickness = 1, smooth = true }),
+                pdf.object.rect({ ll = { 0, 0 }, ur = { 5, 5 }, mode = "fill", tag = "box" }),
+                pdf.object.shape({ { 0, 0 }, { 10, 0 }, { 5, 5 }, order = "non_zero" }),
+                pdf.object.text({ x = 1, y = 2, text = "hi", link = { type = "uri", uri = "x" } }),
+                pdf.object.group({ pdf.object.rect({ ll = { 0, 0 }, ur = { 5, 5 } }) }),
+            }
+            for _, obj in ipairs(objects) do
+                pdf.object.group({ obj:clone(), obj })
+            end
+        })
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn edit_distance_should_count_characters_changed() {
+        assert_eq!(edit_distance("fill_color", "fill_color"), 0);
+        assert_eq!(edit_distance("fill_colour", "fill_color"), 1);
+        assert_eq!(edit_distance("colr", "color"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
 }
@@ -0,0 +1,165 @@
+use crate::pdf::PdfLuaTableExt;
+use mlua::prelude::*;
+
+/// Label shown by PDF viewers in place of the number of a page, applied to a range of pages.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfPageLabel {
+    /// Number of the first page (starting at 1) of the range, which continues until the next
+    /// range begins.
+    pub start: u32,
+
+    /// Style of the numbers of the pages within the range.
+    pub style: PdfPageLabelStyle,
+
+    /// Text placed before the number of each page within the range, e.g. `A-`.
+    pub prefix: Option<String>,
+
+    /// Number of the first page of the range, defaulting to 1.
+    pub first: Option<u32>,
+}
+
+impl<'lua> IntoLua<'lua> for PdfPageLabel {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("start", self.start)?;
+        table.raw_set("style", self.style)?;
+        table.raw_set("prefix", self.prefix)?;
+        table.raw_set("first", self.first)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfPageLabel {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                start: table.raw_get_ext("start")?,
+                style: table
+                    .raw_get_ext::<_, Option<PdfPageLabelStyle>>("style")?
+                    .unwrap_or_default(),
+                prefix: table.raw_get_ext("prefix")?,
+                first: table.raw_get_ext("first")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.page_label",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Style of the numbers within page labels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfPageLabelStyle {
+    /// Arabic numerals, e.g. 1, 2, 3.
+    #[default]
+    Decimal,
+    /// Lowercase roman numerals, e.g. i, ii, iii.
+    Roman,
+    /// Uppercase roman numerals, e.g. I, II, III.
+    UpperRoman,
+    /// Lowercase letters, e.g. a, b, c, ..., aa, bb.
+    Alpha,
+    /// Uppercase letters, e.g. A, B, C, ..., AA, BB.
+    UpperAlpha,
+    /// No number, leaving only the prefix of the label.
+    None,
+}
+
+impl PdfPageLabelStyle {
+    /// Returns the name of the style within the PDF, or `None` if the label has no number.
+    pub fn to_pdf_name(self) -> Option<&'static str> {
+        match self {
+            Self::Decimal => Some("D"),
+            Self::Roman => Some("r"),
+            Self::UpperRoman => Some("R"),
+            Self::Alpha => Some("a"),
+            Self::UpperAlpha => Some("A"),
+            Self::None => None,
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfPageLabelStyle {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::Decimal => "decimal",
+            Self::Roman => "roman",
+            Self::UpperRoman => "upper_roman",
+            Self::Alpha => "alpha",
+            Self::UpperAlpha => "upper_alpha",
+            Self::None => "none",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfPageLabelStyle {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "decimal" => Ok(Self::Decimal),
+                "roman" => Ok(Self::Roman),
+                "upper_roman" => Ok(Self::UpperRoman),
+                "alpha" => Ok(Self::Alpha),
+                "upper_alpha" => Ok(Self::UpperAlpha),
+                "none" => Ok(Self::None),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.page_label_style",
+                    message: Some(format!("unknown style: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.page_label_style",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        assert_eq!(
+            lua.load(chunk!({ start = 1 }))
+                .eval::<PdfPageLabel>()
+                .unwrap(),
+            PdfPageLabel {
+                start: 1,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            lua.load(chunk!({ start = 5, style = "upper_roman", prefix = "A-", first = 3 }))
+                .eval::<PdfPageLabel>()
+                .unwrap(),
+            PdfPageLabel {
+                start: 5,
+                style: PdfPageLabelStyle::UpperRoman,
+                prefix: Some(String::from("A-")),
+                first: Some(3),
+            },
+        );
+
+        lua.load(chunk!({ start = 1, style = "greek" }))
+            .eval::<PdfPageLabel>()
+            .expect_err("Unexpectedly converted label with unknown style");
+    }
+}
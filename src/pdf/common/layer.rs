@@ -0,0 +1,101 @@
+use crate::pdf::PdfLuaTableExt;
+use mlua::prelude::*;
+
+/// Named layer of a page that objects can be drawn within.
+///
+/// Layers are stacked above the default layer of each page in the order they were created.
+/// Optional layers can be toggled on and off by PDF viewers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PdfLayer {
+    /// Name of the layer, shown by PDF viewers for optional layers.
+    pub name: String,
+
+    /// If true, the layer is an optional content group that viewers can toggle.
+    pub optional: bool,
+
+    /// If false, the layer starts hidden. Only applies to optional layers.
+    pub visible: bool,
+}
+
+impl PdfLayer {
+    /// Creates a new, non-optional layer named `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            optional: false,
+            visible: true,
+        }
+    }
+
+    /// Returns true if the layer is hidden when the document is opened.
+    pub fn is_hidden(&self) -> bool {
+        self.optional && !self.visible
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLayer {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("name", self.name)?;
+        table.raw_set("optional", self.optional)?;
+        table.raw_set("visible", self.visible)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLayer {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::String(s) => Ok(Self::new(s.to_str()?)),
+            LuaValue::Table(table) => Ok(Self {
+                name: table.raw_get_ext("name")?,
+                optional: table
+                    .raw_get_ext::<_, Option<bool>>("optional")?
+                    .unwrap_or_default(),
+                visible: table
+                    .raw_get_ext::<_, Option<bool>>("visible")?
+                    .unwrap_or(true),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.layer",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        assert_eq!(
+            lua.load(chunk!("grid")).eval::<PdfLayer>().unwrap(),
+            PdfLayer::new("grid"),
+        );
+
+        assert_eq!(
+            lua.load(chunk!({ name = "grid", optional = true, visible = false }))
+                .eval::<PdfLayer>()
+                .unwrap(),
+            PdfLayer {
+                name: String::from("grid"),
+                optional: true,
+                visible: false,
+            },
+        );
+
+        lua.load(chunk!({ optional = true }))
+            .eval::<PdfLayer>()
+            .expect_err("Unexpectedly converted layer without name");
+    }
+}
@@ -0,0 +1,267 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{PdfBounds, PdfConfigPage, PdfPageSide};
+use mlua::prelude::*;
+use printpdf::Mm;
+use std::str::FromStr;
+
+/// Distance within a PDF, converted from a number of millimeters or a string of a number with
+/// units such as `"1in"`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PdfLength(pub Mm);
+
+impl PdfLength {
+    /// Parses a number followed by optional units, using `dpi` to convert pixels.
+    ///
+    /// Supported units are `mm` (the default when none are given), `cm`, `in`, `pt`, and `px`.
+    pub fn parse(s: &str, dpi: f32) -> Result<Self, String> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (value, units) = s.split_at(split);
+        let value = f32::from_str(value.trim()).map_err(|_| format!("invalid length '{s}'"))?;
+
+        let mm = match units.to_lowercase().as_str() {
+            "" | "mm" => value,
+            "cm" => value * 10.0,
+            "in" => value * 25.4,
+            "pt" => value * 25.4 / 72.0,
+            "px" => Self::px_to_mm(value, dpi),
+            _ => return Err(format!("unknown units '{units}' of length '{s}'")),
+        };
+
+        Ok(Self(Mm(mm)))
+    }
+
+    /// Converts `px` pixels into millimeters at `dpi`.
+    pub fn px_to_mm(px: f32, dpi: f32) -> f32 {
+        px * 25.4 / dpi
+    }
+
+    /// Returns the DPI of pages configured within `lua`, used to convert pixels.
+    pub fn lua_dpi(lua: &Lua) -> f32 {
+        lua.globals()
+            .raw_get::<_, Option<LuaTable>>(GLOBAL_PDF_VAR_NAME)
+            .ok()
+            .flatten()
+            .and_then(|pdf| pdf.raw_get::<_, Option<LuaTable>>("page").ok().flatten())
+            .and_then(|page| page.raw_get::<_, Option<f32>>("dpi").ok().flatten())
+            .unwrap_or_else(|| PdfConfigPage::default().dpi)
+    }
+}
+
+/// Coordinate along the x or y axis, converted from a length or from a string of a percentage
+/// such as `"50%"` that is relative to reference bounds.
+///
+/// The reference bounds are those of the cell whose object is being created by a layout, or
+/// otherwise the content bounds of a right-hand page, so scripts can position objects without
+/// knowing the size of pages.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PdfCoord {
+    Length(PdfLength),
+    Percent(f32),
+}
+
+impl PdfCoord {
+    /// Returns the x coordinate within `lua`, resolving a percentage against the width of the
+    /// reference bounds.
+    pub fn into_x(self, lua: &Lua) -> LuaResult<Mm> {
+        match self {
+            Self::Length(PdfLength(x)) => Ok(x),
+            Self::Percent(pct) => {
+                let bounds = Self::lua_reference_bounds(lua)?;
+                Ok(bounds.ll.x + bounds.width() * (pct / 100.0))
+            }
+        }
+    }
+
+    /// Returns the y coordinate within `lua`, resolving a percentage against the height of the
+    /// reference bounds.
+    pub fn into_y(self, lua: &Lua) -> LuaResult<Mm> {
+        match self {
+            Self::Length(PdfLength(y)) => Ok(y),
+            Self::Percent(pct) => {
+                let bounds = Self::lua_reference_bounds(lua)?;
+                Ok(bounds.ll.y + bounds.height() * (pct / 100.0))
+            }
+        }
+    }
+
+    /// Calls `f` with percentages resolved against `bounds` instead, such as those of a cell.
+    pub fn with_reference_bounds<R>(lua: &Lua, bounds: PdfBounds, f: impl FnOnce() -> R) -> R {
+        let prev = lua.set_app_data(PdfCoordReference(bounds));
+        let result = f();
+        match prev {
+            Some(prev) => lua.set_app_data(prev),
+            None => lua.remove_app_data::<PdfCoordReference>(),
+        };
+        result
+    }
+
+    /// Returns the bounds that percentages are relative to within `lua`.
+    fn lua_reference_bounds(lua: &Lua) -> LuaResult<PdfBounds> {
+        if let Some(reference) = lua.app_data_ref::<PdfCoordReference>() {
+            return Ok(reference.0);
+        }
+
+        let page: Option<PdfConfigPage> = lua
+            .globals()
+            .raw_get::<_, Option<LuaTable>>(GLOBAL_PDF_VAR_NAME)?
+            .map(|pdf| pdf.raw_get("page"))
+            .transpose()?;
+        Ok(page
+            .unwrap_or_default()
+            .content_bounds(PdfPageSide::default()))
+    }
+}
+
+/// Bounds that percentages are resolved against in place of the content bounds of pages.
+struct PdfCoordReference(PdfBounds);
+
+impl<'lua> FromLua<'lua> for PdfCoord {
+    /// Converts from a length or a string like `"50%"`.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        if let LuaValue::String(s) = &value {
+            let s = s.to_str()?;
+            if let Some(pct) = s.trim().strip_suffix('%') {
+                return pct.trim().parse::<f32>().map(Self::Percent).map_err(|_| {
+                    LuaError::FromLuaConversionError {
+                        from: "string",
+                        to: "pdf.common.coord",
+                        message: Some(format!("invalid percentage '{s}'")),
+                    }
+                });
+            }
+        }
+
+        Ok(Self::Length(PdfLength::from_lua(value, lua)?))
+    }
+}
+
+impl From<PdfLength> for Mm {
+    fn from(length: PdfLength) -> Self {
+        length.0
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLength {
+    #[inline]
+    fn into_lua(self, _lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        Ok(LuaValue::Number(self.0 .0 as f64))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLength {
+    /// Converts from a number of millimeters or a string like `"1in"`, `"25pt"`, or `"300px"`
+    /// where pixels are converted using the DPI of pages.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Integer(x) => Ok(Self(Mm(x as f32))),
+            LuaValue::Number(x) => Ok(Self(Mm(x as f32))),
+            LuaValue::String(s) => {
+                Self::parse(s.to_str()?, Self::lua_dpi(lua)).map_err(|message| {
+                    LuaError::FromLuaConversionError {
+                        from: "string",
+                        to: "pdf.common.length",
+                        message: Some(message),
+                    }
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.length",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{Pdf, PdfConfig, PdfPoint};
+    use mlua::chunk;
+
+    #[test]
+    fn parse_should_convert_units_into_millimeters() {
+        let parse = |s| PdfLength::parse(s, 300.0).unwrap().0;
+
+        assert_eq!(parse("12"), Mm(12.0));
+        assert_eq!(parse("12mm"), Mm(12.0));
+        assert_eq!(parse("1.5cm"), Mm(15.0));
+        assert_eq!(parse("1in"), Mm(25.4));
+        assert_eq!(parse(" -2 IN "), Mm(-50.8));
+        assert_eq!(parse("72pt"), Mm(25.4));
+        assert_eq!(parse("300px"), Mm(25.4));
+
+        PdfLength::parse("1ft", 300.0).expect_err("Unexpectedly parsed unknown units");
+        PdfLength::parse("in", 300.0).expect_err("Unexpectedly parsed missing number");
+    }
+
+    #[test]
+    fn should_convert_units_of_points_and_bounds_in_lua_using_dpi() {
+        let lua = Lua::new();
+        let mut config = PdfConfig::default();
+        config.page.dpi = 150.0;
+        lua.globals().raw_set("pdf", Pdf::new(config)).unwrap();
+
+        assert_eq!(
+            lua.load(chunk!({ x = "1in", y = "150px" }))
+                .eval::<PdfPoint>()
+                .unwrap(),
+            PdfPoint::from_coords_f32(25.4, 25.4)
+        );
+
+        lua.load(chunk! {
+            local rect = pdf.object.rect({ ll = { "1cm", 0 }, ur = { "2in", "72pt" } })
+            assert(rect.ll == pdf.utils.point({ 10, 0 }), "unexpected lower-left")
+            assert(rect.ur == pdf.utils.point({ 50.8, 25.4 }), "unexpected upper-right")
+
+            local bounds = pdf.utils.bounds({ 0, 0, "1in", "300px" })
+            assert(bounds.ur == pdf.utils.point({ 25.4, 50.8 }), "unexpected bounds")
+
+            assert(not pcall(pdf.utils.point, { "1ft", 0 }), "unexpectedly converted feet")
+        })
+        .exec()
+        .unwrap();
+    }
+
+    #[test]
+    fn should_resolve_percentages_against_content_bounds_or_layout_cells_in_lua() {
+        let lua = Lua::new();
+        let mut config = PdfConfig::default();
+        config.page.width = Mm(120.0);
+        config.page.height = Mm(220.0);
+        config.page.margins = crate::pdf::PdfPadding::new_f32(10.0, 10.0, 10.0, 10.0);
+        lua.globals().raw_set("pdf", Pdf::new(config)).unwrap();
+
+        assert_eq!(
+            lua.load(chunk!({ x = "50%", y = "25 %" }))
+                .eval::<PdfPoint>()
+                .unwrap(),
+            PdfPoint::from_coords_f32(60.0, 60.0)
+        );
+
+        lua.load(chunk! {
+            local bounds = pdf.utils.bounds({ "0%", "0%", "100%", "100%" })
+            assert(bounds == pdf.page:content_bounds(), "unexpected bounds")
+
+            local group = pdf.layout.hstack({
+                bounds = { 0, 0, 100, 50 },
+                function() return pdf.object.rect({ ll = { "50%", "0%" }, ur = { "100%", "50%" } }) end,
+                function() return pdf.object.rect({ ll = { "0%", "0%" }, ur = { "100%", "100%" } }) end,
+            })
+            assert(group[1].ll == pdf.utils.point({ 25, 0 }), "unexpected first cell lower-left")
+            assert(group[1].ur == pdf.utils.point({ 50, 25 }), "unexpected first cell upper-right")
+            assert(group[2].ll == pdf.utils.point({ 50, 0 }), "unexpected second cell lower-left")
+
+            // Outside of a layout, percentages are relative to pages again
+            local point = pdf.utils.point({ "100%", "100%" })
+            assert(point == pdf.utils.point({ 110, 210 }), "unexpected point after layout")
+
+            assert(not pcall(pdf.utils.point, { "half%", 0 }), "unexpectedly converted percentage")
+        })
+        .exec()
+        .unwrap();
+    }
+}
@@ -1,20 +1,35 @@
-use super::PdfBounds;
+mod appearance;
+mod style;
+
+use super::{PdfBounds, PdfPoint};
 use crate::pdf::PdfLuaTableExt;
 use mlua::prelude::*;
 
+pub use appearance::{PdfLinkBorder, PdfLinkHighlight};
+pub use style::PdfLinkStyle;
+
 /// Represents an annotation on a PDF that provides a link.
 #[derive(Clone, Debug)]
 pub struct PdfLinkAnnotation {
     pub bounds: PdfBounds,
     pub depth: i64,
     pub link: PdfLink,
+    pub border: PdfLinkBorder,
+    pub highlight: PdfLinkHighlight,
 }
 
 /// Represents an action to take as a link.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PdfLink {
-    /// Link should go to an internal page denoted by the page's id.
-    GoTo { page: u32 },
+    /// Link should go to an internal page denoted by the page's id, optionally scrolling to a
+    /// point on the page (as the upper-left of the view) and zooming, or fitting an area of the
+    /// page (`rect`) within the view, which takes precedence.
+    GoTo {
+        page: u32,
+        at: Option<PdfPoint>,
+        zoom: Option<f32>,
+        rect: Option<PdfBounds>,
+    },
 
     /// Link should go to an external URI.
     Uri { uri: String },
@@ -40,7 +55,17 @@ impl<'lua> IntoLua<'lua> for PdfLink {
 
         // Set action-specific fields
         match self {
-            Self::GoTo { page } => table.raw_set("page", page)?,
+            Self::GoTo {
+                page,
+                at,
+                zoom,
+                rect,
+            } => {
+                table.raw_set("page", page)?;
+                table.raw_set("at", at)?;
+                table.raw_set("zoom", zoom)?;
+                table.raw_set("rect", rect)?;
+            }
             Self::Uri { uri } => table.raw_set("uri", uri)?,
         }
 
@@ -53,14 +78,34 @@ impl<'lua> FromLua<'lua> for PdfLink {
     fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
         let from = value.type_name();
         match value {
-            LuaValue::Number(num) => Ok(Self::GoTo { page: num as u32 }),
-            LuaValue::Integer(num) => Ok(Self::GoTo { page: num as u32 }),
+            LuaValue::Number(num) => Ok(Self::GoTo {
+                page: num as u32,
+                at: None,
+                zoom: None,
+                rect: None,
+            }),
+            LuaValue::Integer(num) => Ok(Self::GoTo {
+                page: num as u32,
+                at: None,
+                zoom: None,
+                rect: None,
+            }),
             LuaValue::String(s) => Ok(Self::Uri {
                 uri: s.to_str()?.to_string(),
             }),
             LuaValue::Table(tbl) => match tbl.raw_get_ext::<_, String>("type")?.as_str() {
                 "goto" => Ok(Self::GoTo {
                     page: tbl.raw_get_ext("page")?,
+                    at: tbl.raw_get_ext("at")?,
+                    zoom: tbl.raw_get_ext("zoom")?,
+                    rect: tbl.raw_get_ext("rect")?,
+                }),
+                // Alias of goto that refers to the page by its id
+                "page" => Ok(Self::GoTo {
+                    page: tbl.raw_get_ext("id")?,
+                    at: tbl.raw_get_ext("at")?,
+                    zoom: tbl.raw_get_ext("zoom")?,
+                    rect: tbl.raw_get_ext("rect")?,
                 }),
                 "uri" => Ok(Self::Uri {
                     uri: tbl.raw_get_ext("uri")?,
@@ -79,3 +124,108 @@ impl<'lua> FromLua<'lua> for PdfLink {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        // Can convert a number into a link to a page
+        assert_eq!(
+            Lua::new().load(chunk!(123)).eval::<PdfLink>().unwrap(),
+            PdfLink::GoTo {
+                page: 123,
+                at: None,
+                zoom: None,
+                rect: None,
+            },
+        );
+
+        // Can convert a string into a link to a uri
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("https://example.com"))
+                .eval::<PdfLink>()
+                .unwrap(),
+            PdfLink::Uri {
+                uri: String::from("https://example.com"),
+            },
+        );
+
+        // Can convert a goto table with a point and zoom into a link
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ type = "goto", page = 123, at = { x = 1, y = 2 }, zoom = 1.5 }))
+                .eval::<PdfLink>()
+                .unwrap(),
+            PdfLink::GoTo {
+                page: 123,
+                at: Some(PdfPoint::from_coords_f32(1.0, 2.0)),
+                zoom: Some(1.5),
+                rect: None,
+            },
+        );
+
+        // Can convert a goto table with a rect into a link
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ type = "goto", page = 123, rect = { 1, 2, 3, 4 } }))
+                .eval::<PdfLink>()
+                .unwrap(),
+            PdfLink::GoTo {
+                page: 123,
+                at: None,
+                zoom: None,
+                rect: Some(PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0)),
+            },
+        );
+
+        // Can convert a page table with an id and point into a link
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ type = "page", id = 123, at = { 1, 2 } }))
+                .eval::<PdfLink>()
+                .unwrap(),
+            PdfLink::GoTo {
+                page: 123,
+                at: Some(PdfPoint::from_coords_f32(1.0, 2.0)),
+                zoom: None,
+                rect: None,
+            },
+        );
+
+        // Fails if the type is unknown
+        Lua::new()
+            .load(chunk!({ type = "unknown" }))
+            .eval::<PdfLink>()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn should_be_able_to_convert_into_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let link = PdfLink::GoTo {
+            page: 123,
+            at: Some(PdfPoint::from_coords_f32(1.0, 2.0)),
+            zoom: Some(1.5),
+            rect: None,
+        };
+
+        lua.load(chunk! {
+            pdf.utils.assert_deep_equal($link, {
+                type = "goto",
+                page = 123,
+                at = { x = 1, y = 2 },
+                zoom = 1.5,
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::pdf::{PdfColor, PdfLuaTableExt};
+use mlua::prelude::*;
+use printpdf::{BorderArray, ColorArray, HighlightingMode};
+
+/// Border drawn by PDF readers around the clickable area of a link.
+///
+/// Default border is not drawn at all, as readers otherwise draw a box around every link.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfLinkBorder {
+    /// Thickness of the border in points, where zero means no border.
+    pub width: f32,
+    /// Color of the border, defaulting to black.
+    pub color: Option<PdfColor>,
+}
+
+impl PdfLinkBorder {
+    /// Returns the color of the border as used by link annotations.
+    pub fn to_color_array(self) -> ColorArray {
+        let (red, green, blue) = self.color.unwrap_or_default().into_colors_f32();
+        ColorArray::RGB([red, green, blue])
+    }
+}
+
+impl From<PdfLinkBorder> for BorderArray {
+    fn from(border: PdfLinkBorder) -> Self {
+        // Horizontal and vertical corner radius followed by width
+        BorderArray::Solid([0.0, 0.0, border.width])
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLinkBorder {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("width", self.width)?;
+        table.raw_set("color", self.color)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLinkBorder {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            // Support removing the border by specifying false
+            LuaValue::Boolean(false) => Ok(Self::default()),
+            LuaValue::Table(tbl) => Ok(Self {
+                width: tbl.raw_get_ext::<_, Option<f32>>("width")?.unwrap_or(1.0),
+                color: tbl.raw_get_ext("color")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.link_border",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Visual effect applied by PDF readers while a link is being clicked.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfLinkHighlight {
+    /// No highlighting.
+    None,
+    /// Inverts the colors within the clickable area of the link.
+    #[default]
+    Invert,
+    /// Inverts the border of the link.
+    Outline,
+}
+
+impl From<PdfLinkHighlight> for HighlightingMode {
+    fn from(highlight: PdfLinkHighlight) -> Self {
+        match highlight {
+            PdfLinkHighlight::None => HighlightingMode::None,
+            PdfLinkHighlight::Invert => HighlightingMode::Invert,
+            PdfLinkHighlight::Outline => HighlightingMode::Outline,
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLinkHighlight {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::None => "none",
+            Self::Invert => "invert",
+            Self::Outline => "outline",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLinkHighlight {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "none" => Ok(Self::None),
+                "invert" => Ok(Self::Invert),
+                "outline" => Ok(Self::Outline),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.link_highlight",
+                    message: Some(format!("unknown highlight: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.link_highlight",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        // Can convert false into no border
+        assert_eq!(
+            Lua::new()
+                .load(chunk!(false))
+                .eval::<PdfLinkBorder>()
+                .unwrap(),
+            PdfLinkBorder::default(),
+        );
+
+        // Border defaults to a width of one point
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({ color = "#123456" }))
+                .eval::<PdfLinkBorder>()
+                .unwrap(),
+            PdfLinkBorder {
+                width: 1.0,
+                color: Some("#123456".parse().unwrap()),
+            },
+        );
+
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("outline"))
+                .eval::<PdfLinkHighlight>()
+                .unwrap(),
+            PdfLinkHighlight::Outline,
+        );
+        assert!(Lua::new()
+            .load(chunk!("flash"))
+            .eval::<PdfLinkHighlight>()
+            .is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::pdf::{PdfColor, PdfLinkBorder, PdfLinkHighlight, PdfLuaTableExt};
+use mlua::prelude::*;
+
+/// Appearance applied to text that carries a link.
+///
+/// The border and highlight of the configured style also apply to the links of other objects.
+///
+/// Default style leaves the text untouched.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfLinkStyle {
+    /// Color of the text, used when the text does not specify its own color.
+    pub color: Option<PdfColor>,
+    /// If true, draws a line underneath the text.
+    pub underline: bool,
+    /// Distance in millimeters to grow the clickable area of the link beyond the bounds of the
+    /// text on every side, making small links easier to tap.
+    pub padding: f32,
+    /// Border drawn by PDF readers around the clickable area of the link.
+    pub border: PdfLinkBorder,
+    /// Effect applied by PDF readers while the link is being clicked.
+    pub highlight: PdfLinkHighlight,
+}
+
+impl PdfLinkStyle {
+    /// Creates a style that leaves text untouched.
+    #[inline]
+    pub const fn none() -> Self {
+        Self {
+            color: None,
+            underline: false,
+            padding: 0.0,
+            border: PdfLinkBorder {
+                width: 0.0,
+                color: None,
+            },
+            highlight: PdfLinkHighlight::Invert,
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLinkStyle {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("color", self.color)?;
+        table.raw_set("underline", self.underline)?;
+        table.raw_set("padding", self.padding)?;
+        table.raw_set("border", self.border)?;
+        table.raw_set("highlight", self.highlight)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLinkStyle {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            // Support opting out of styling by specifying false
+            LuaValue::Boolean(false) => Ok(Self::none()),
+            LuaValue::Table(tbl) => Ok(Self {
+                color: tbl.raw_get_ext("color")?,
+                underline: tbl
+                    .raw_get_ext::<_, Option<bool>>("underline")?
+                    .unwrap_or_default(),
+                padding: tbl
+                    .raw_get_ext::<_, Option<f32>>("padding")?
+                    .unwrap_or_default(),
+                border: tbl
+                    .raw_get_ext::<_, Option<_>>("border")?
+                    .unwrap_or_default(),
+                highlight: tbl
+                    .raw_get_ext::<_, Option<_>>("highlight")?
+                    .unwrap_or_default(),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.link_style",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        // Can convert false into a style that does nothing
+        assert_eq!(
+            Lua::new()
+                .load(chunk!(false))
+                .eval::<PdfLinkStyle>()
+                .unwrap(),
+            PdfLinkStyle::none(),
+        );
+
+        // Can convert empty table into a style that does nothing
+        assert_eq!(
+            Lua::new().load(chunk!({})).eval::<PdfLinkStyle>().unwrap(),
+            PdfLinkStyle::none(),
+        );
+
+        // Can convert table with everything into a style
+        assert_eq!(
+            Lua::new()
+                .load(chunk!({
+                    color = "#123456",
+                    underline = true,
+                    padding = 2,
+                    border = { width = 0.5 },
+                    highlight = "none",
+                }))
+                .eval::<PdfLinkStyle>()
+                .unwrap(),
+            PdfLinkStyle {
+                color: Some("#123456".parse().unwrap()),
+                underline: true,
+                padding: 2.0,
+                border: PdfLinkBorder {
+                    width: 0.5,
+                    color: None,
+                },
+                highlight: PdfLinkHighlight::None,
+            },
+        );
+
+        // Fails if not false or a table
+        Lua::new()
+            .load(chunk!(true))
+            .eval::<PdfLinkStyle>()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn should_be_able_to_convert_into_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let style = PdfLinkStyle {
+            color: Some("#123456".parse().unwrap()),
+            underline: true,
+            padding: 2.0,
+            border: PdfLinkBorder::default(),
+            highlight: PdfLinkHighlight::Outline,
+        };
+
+        lua.load(chunk! {
+            pdf.utils.assert_deep_equal($style, {
+                color = { red = 18, green = 52, blue = 86 },
+                underline = true,
+                padding = 2,
+                border = { width = 0 },
+                highlight = "outline",
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+}
@@ -25,6 +25,12 @@ impl PdfPaintMode {
     pub const fn stroke() -> Self {
         Self(PaintMode::Stroke)
     }
+
+    /// Returns true if the mode strokes the outline of a shape.
+    #[inline]
+    pub const fn is_stroke(&self) -> bool {
+        matches!(self.0, PaintMode::Stroke | PaintMode::FillStroke)
+    }
 }
 
 impl From<PdfPaintMode> for PaintMode {
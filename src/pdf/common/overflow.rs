@@ -0,0 +1,81 @@
+use mlua::prelude::*;
+
+/// Behavior of text that is wider than its maximum width.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfTextOverflow {
+    /// Cuts off the characters that do not fit.
+    Truncate,
+    /// Cuts off the characters that do not fit, ending the text with an ellipsis.
+    #[default]
+    Ellipsis,
+    /// Reduces the font size of the text until it fits.
+    Shrink,
+}
+
+impl<'lua> IntoLua<'lua> for PdfTextOverflow {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::Truncate => "truncate",
+            Self::Ellipsis => "ellipsis",
+            Self::Shrink => "shrink",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfTextOverflow {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "truncate" => Ok(Self::Truncate),
+                "ellipsis" => Ok(Self::Ellipsis),
+                "shrink" => Ok(Self::Shrink),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.text_overflow",
+                    message: Some(format!("unknown overflow: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.text_overflow",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("shrink"))
+                .eval::<PdfTextOverflow>()
+                .unwrap(),
+            PdfTextOverflow::Shrink,
+        );
+        assert!(Lua::new()
+            .load(chunk!("wrap"))
+            .eval::<PdfTextOverflow>()
+            .is_err());
+    }
+
+    #[test]
+    fn should_be_able_to_convert_into_lua() {
+        let lua = Lua::new();
+        let overflow = PdfTextOverflow::Truncate;
+        lua.load(chunk! {
+            assert($overflow == "truncate", "unexpected overflow: " .. $overflow)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+}
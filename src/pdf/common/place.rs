@@ -0,0 +1,224 @@
+use crate::pdf::{
+    PdfBounds, PdfHorizontalAlign, PdfLuaTableExt, PdfObject, PdfPoint, PdfVerticalAlign,
+};
+use mlua::prelude::*;
+use printpdf::Mm;
+
+/// Side of an anchor that an object is placed on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PdfPlaceSide {
+    Above,
+    Below,
+    LeftOf,
+    RightOf,
+}
+
+impl PdfPlaceSide {
+    /// Names of the sides as they appear as fields in Lua.
+    const NAMES: [(&'static str, Self); 4] = [
+        ("above", Self::Above),
+        ("below", Self::Below),
+        ("left_of", Self::LeftOf),
+        ("right_of", Self::RightOf),
+    ];
+}
+
+/// Placement of an object next to the bounds of another, such as below it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfPlace {
+    /// Bounds of the object being placed next to.
+    pub anchor: PdfBounds,
+    pub side: PdfPlaceSide,
+    /// Space between the anchor and the placed object.
+    pub gap: Mm,
+    /// Alignment with the anchor for objects placed above or below it.
+    pub h: PdfHorizontalAlign,
+    /// Alignment with the anchor for objects placed left or right of it.
+    pub v: PdfVerticalAlign,
+}
+
+impl PdfPlace {
+    /// Returns the region beside the anchor that an object of `size` (width, height) should be
+    /// aligned to, alongside the alignment to use.
+    pub fn region(&self, size: (Mm, Mm)) -> (PdfBounds, (PdfVerticalAlign, PdfHorizontalAlign)) {
+        let (width, height) = size;
+        let PdfBounds { ll, ur } = self.anchor;
+        match self.side {
+            PdfPlaceSide::Above => (
+                PdfBounds::new(
+                    PdfPoint::new(ll.x, ur.y + self.gap),
+                    PdfPoint::new(ur.x, ur.y + self.gap + height),
+                ),
+                (PdfVerticalAlign::Bottom, self.h),
+            ),
+            PdfPlaceSide::Below => (
+                PdfBounds::new(
+                    PdfPoint::new(ll.x, ll.y - self.gap - height),
+                    PdfPoint::new(ur.x, ll.y - self.gap),
+                ),
+                (PdfVerticalAlign::Top, self.h),
+            ),
+            PdfPlaceSide::LeftOf => (
+                PdfBounds::new(
+                    PdfPoint::new(ll.x - self.gap - width, ll.y),
+                    PdfPoint::new(ll.x - self.gap, ur.y),
+                ),
+                (self.v, PdfHorizontalAlign::Right),
+            ),
+            PdfPlaceSide::RightOf => (
+                PdfBounds::new(
+                    PdfPoint::new(ur.x + self.gap, ll.y),
+                    PdfPoint::new(ur.x + self.gap + width, ur.y),
+                ),
+                (self.v, PdfHorizontalAlign::Left),
+            ),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfPlace {
+    /// Converts from a table with one of `above`, `below`, `left_of`, or `right_of` set to an
+    /// object or bounds, alongside an optional `gap` and `align`.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        let table = match value {
+            LuaValue::Table(table) => table,
+            _ => {
+                return Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.place",
+                    message: None,
+                })
+            }
+        };
+
+        let mut anchors = Vec::new();
+        for (name, side) in PdfPlaceSide::NAMES {
+            if let Some(value) = table.raw_get_ext::<_, Option<LuaValue>>(name)? {
+                anchors.push((side, value));
+            }
+        }
+
+        let (side, anchor) = match anchors.len() {
+            1 => anchors.remove(0),
+            _ => {
+                return Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.place",
+                    message: Some(String::from(
+                        "expected exactly one of above, below, left_of, or right_of",
+                    )),
+                })
+            }
+        };
+
+        // Anchor is either an object, which has a type, or bounds
+        let anchor = match anchor {
+            LuaValue::Table(table) if !table.raw_get::<_, LuaValue>("type")?.is_nil() => {
+                PdfObject::from_lua(LuaValue::Table(table), lua)?.lua_bounds(lua)?
+            }
+            value => PdfBounds::from_lua(value, lua)?,
+        };
+
+        let gap = table
+            .raw_get_ext::<_, Option<f32>>("gap")?
+            .unwrap_or_default();
+        let (h, v) = match side {
+            PdfPlaceSide::Above | PdfPlaceSide::Below => (
+                table
+                    .raw_get_ext::<_, Option<_>>("align")?
+                    .unwrap_or(PdfHorizontalAlign::Left),
+                PdfVerticalAlign::Top,
+            ),
+            PdfPlaceSide::LeftOf | PdfPlaceSide::RightOf => (
+                PdfHorizontalAlign::Left,
+                table
+                    .raw_get_ext::<_, Option<_>>("align")?
+                    .unwrap_or(PdfVerticalAlign::Top),
+            ),
+        };
+
+        Ok(Self {
+            anchor,
+            side,
+            gap: Mm(gap),
+            h,
+            v,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn region_should_be_beside_anchor_with_gap() {
+        let place = |side| PdfPlace {
+            anchor: PdfBounds::from_coords_f32(10.0, 10.0, 20.0, 20.0),
+            side,
+            gap: Mm(2.0),
+            h: PdfHorizontalAlign::Left,
+            v: PdfVerticalAlign::Top,
+        };
+        let size = (Mm(4.0), Mm(3.0));
+
+        assert_eq!(
+            place(PdfPlaceSide::Above).region(size).0,
+            PdfBounds::from_coords_f32(10.0, 22.0, 20.0, 25.0)
+        );
+        assert_eq!(
+            place(PdfPlaceSide::Below).region(size).0,
+            PdfBounds::from_coords_f32(10.0, 5.0, 20.0, 8.0)
+        );
+        assert_eq!(
+            place(PdfPlaceSide::LeftOf).region(size).0,
+            PdfBounds::from_coords_f32(4.0, 10.0, 8.0, 20.0)
+        );
+        assert_eq!(
+            place(PdfPlaceSide::RightOf).region(size).0,
+            PdfBounds::from_coords_f32(22.0, 10.0, 26.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_place_objects_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local u = pdf.utils
+            local anchor = pdf.object.rect({ ll = { 10, 10 }, ur = { 20, 20 } })
+            local rect = pdf.object.rect({ ll = { 0, 0 }, ur = { 4, 3 } })
+
+            u.assert_deep_equal(rect:place({ below = anchor, gap = 2 }):bounds(), {
+                ll = { x = 10, y = 5 },
+                ur = { x = 14, y = 8 },
+            })
+            u.assert_deep_equal(rect:place({ above = anchor, align = "right" }):bounds(), {
+                ll = { x = 16, y = 20 },
+                ur = { x = 20, y = 23 },
+            })
+            u.assert_deep_equal(rect:place({ right_of = anchor, align = "bottom" }):bounds(), {
+                ll = { x = 20, y = 10 },
+                ur = { x = 24, y = 13 },
+            })
+
+            // Anchors can also be bounds, and other objects can be placed
+            local shape = pdf.object.shape({ { 0, 0 }, { 4, 0 }, { 4, 3 } })
+            u.assert_deep_equal(shape:place({ left_of = anchor:bounds(), gap = 1 }):bounds(), {
+                ll = { x = 5, y = 17 },
+                ur = { x = 9, y = 20 },
+            })
+
+            local ok = pcall(rect.place, rect, { below = anchor, above = anchor })
+            assert(not ok, "placed object on multiple sides")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+}
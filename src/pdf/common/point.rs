@@ -1,8 +1,12 @@
-use crate::pdf::{PdfLuaExt, PdfLuaTableExt};
+use crate::pdf::{PdfCoord, PdfLuaExt, PdfLuaTableExt};
 use mlua::prelude::*;
 use printpdf::{Mm, Point};
+use std::ops::{Add, Mul, Sub};
 
 /// Coordinate x,y for something within a PDF.
+///
+/// Points are equal when their coordinates match to a thousandth of a millimeter, so noise from
+/// floating point math (such as rotating a point) does not make otherwise equal points differ.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct PdfPoint {
     pub x: Mm,
@@ -29,7 +33,7 @@ impl PdfPoint {
     }
 
     /// Creates a copy of the point where the x & y coordinates have been
-    /// rounded to the specified `precision`.
+    /// rounded to the specified `precision` (number of decimal places).
     pub fn to_precision(&self, precision: u32) -> Self {
         let scale = 10_f32.powi(precision as i32);
         Self::from_coords_f32(
@@ -38,6 +42,12 @@ impl PdfPoint {
         )
     }
 
+    /// Creates a copy of the point where the x & y coordinates have been rounded to `decimals`
+    /// decimal places, such as to compare points without noise from floating point math.
+    pub fn round_to(&self, decimals: u32) -> Self {
+        self.to_precision(decimals)
+    }
+
     /// Converts point into (x, y).
     #[inline]
     pub const fn to_coords(&self) -> (Mm, Mm) {
@@ -51,6 +61,31 @@ impl PdfPoint {
         (x.0, y.0)
     }
 
+    /// Returns the distance between this point and `other`.
+    pub fn distance_to(&self, other: Self) -> Mm {
+        let (dx, dy) = ((other.x - self.x).0, (other.y - self.y).0);
+        Mm((dx * dx + dy * dy).sqrt())
+    }
+
+    /// Returns the point halfway between this point and `other`.
+    #[inline]
+    pub fn midpoint(&self, other: Self) -> Self {
+        self.lerp(other, 0.5)
+    }
+
+    /// Returns the point that is `t` of the way from this point to `other`, where `0` is this
+    /// point and `1` is `other`. Values outside of that range extend past either point.
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        *self + (other - *self) * t
+    }
+
+    /// Returns a copy of the point rotated counter-clockwise around `origin` by `degrees`.
+    pub fn rotate_around(&self, origin: Self, degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (dx, dy) = (*self - origin).to_coords_f32();
+        origin + Self::from_coords_f32(dx * cos - dy * sin, dx * sin + dy * cos)
+    }
+
     /// Adds point fields to an existing Lua table.
     pub fn add_to_table(&self, table: &LuaTable) -> LuaResult<()> {
         table.raw_set("x", self.x.0)?;
@@ -59,6 +94,30 @@ impl PdfPoint {
     }
 }
 
+impl Add for PdfPoint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for PdfPoint {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f32> for PdfPoint {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
 impl From<Point> for PdfPoint {
     fn from(point: Point) -> Self {
         Self::new(point.x.into(), point.y.into())
@@ -74,15 +133,85 @@ impl From<PdfPoint> for Point {
 impl<'lua> IntoLua<'lua> for PdfPoint {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
-        self.add_to_table(&table)?;
+        let table = lua.create_table_with_metatable("pdf.common.point", |metatable| {
+            metatable.raw_set(
+                "with_precision",
+                lua.create_function(|_, (this, precision): (Self, u32)| {
+                    Ok(this.to_precision(precision))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "round_to",
+                lua.create_function(
+                    |_, (this, decimals): (Self, u32)| Ok(this.round_to(decimals)),
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "__eq",
+                lua.create_function(|_, (a, b): (Self, Self)| Ok(a == b))?,
+            )?;
+
+            metatable.raw_set(
+                "__add",
+                lua.create_function(|_, (a, b): (Self, Self)| Ok(a + b))?,
+            )?;
+
+            metatable.raw_set(
+                "__sub",
+                lua.create_function(|_, (a, b): (Self, Self)| Ok(a - b))?,
+            )?;
+
+            metatable.raw_set(
+                "__mul",
+                lua.create_function(|lua, (a, b): (LuaValue, LuaValue)| {
+                    // Scalar can be on either side of the point
+                    match (a, b) {
+                        (LuaValue::Integer(x), point) | (point, LuaValue::Integer(x)) => {
+                            Ok(Self::from_lua(point, lua)? * x as f32)
+                        }
+                        (LuaValue::Number(x), point) | (point, LuaValue::Number(x)) => {
+                            Ok(Self::from_lua(point, lua)? * x as f32)
+                        }
+                        (a, b) => Err(LuaError::runtime(format!(
+                            "attempt to multiply a {} with a {}",
+                            a.type_name(),
+                            b.type_name()
+                        ))),
+                    }
+                })?,
+            )?;
 
-        metatable.raw_set(
-            "with_precision",
-            lua.create_function(|_, (this, precision): (Self, u32)| {
-                Ok(this.to_precision(precision))
-            })?,
-        )?;
+            metatable.raw_set(
+                "distance_to",
+                lua.create_function(|_, (this, other): (Self, Self)| {
+                    Ok(this.distance_to(other).0)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "midpoint",
+                lua.create_function(|_, (this, other): (Self, Self)| Ok(this.midpoint(other)))?,
+            )?;
+
+            metatable.raw_set(
+                "lerp",
+                lua.create_function(|_, (this, other, t): (Self, Self, f32)| {
+                    Ok(this.lerp(other, t))
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "rotate_around",
+                lua.create_function(|_, (this, origin, degrees): (Self, Self, f32)| {
+                    Ok(this.rotate_around(origin, degrees))
+                })?,
+            )?;
+
+            Ok(())
+        })?;
+        self.add_to_table(&table)?;
 
         Ok(LuaValue::Table(table))
     }
@@ -93,21 +222,31 @@ impl<'lua> FromLua<'lua> for PdfPoint {
     ///
     /// - `{x:number, y:number}`
     /// - `{number, number}`
+    ///
+    /// Where each number can instead be a string with units, such as `"1in"`, or a percentage of
+    /// the content bounds of pages, such as `"50%"`.
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         let from = value.type_name();
         match value {
             LuaValue::Table(table) => {
-                let coords: Vec<f32> = table.clone().sequence_values().collect::<LuaResult<_>>()?;
+                let coords: Vec<PdfCoord> =
+                    table.clone().sequence_values().collect::<LuaResult<_>>()?;
 
                 // If we have coordinates, make sure there are two, and use them as point
                 if coords.len() >= 2 {
-                    return Ok(Self::from_coords_f32(coords[0], coords[1]));
+                    return Ok(Self::from_coords(
+                        coords[0].into_x(lua)?,
+                        coords[1].into_y(lua)?,
+                    ));
                 }
 
                 // If we have point fields, use them as a point
-                if let (Ok(x), Ok(y)) = (table.raw_get_ext("x"), table.raw_get_ext("y")) {
-                    return Ok(Self::from_coords_f32(x, y));
+                if let (Ok(x), Ok(y)) = (
+                    table.raw_get_ext::<_, PdfCoord>("x"),
+                    table.raw_get_ext::<_, PdfCoord>("y"),
+                ) {
+                    return Ok(Self::from_coords(x.into_x(lua)?, y.into_y(lua)?));
                 }
 
                 // Otherwise, this table is not valid point
@@ -157,6 +296,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_be_able_to_round_to_decimals() {
+        let point = PdfPoint::from_coords_f32(0.123456, 9.87654);
+        assert_eq!(point.round_to(0).to_coords_f32(), (0.0, 10.0));
+        assert_eq!(point.round_to(2).to_coords_f32(), (0.12, 9.88));
+        assert_eq!(point.round_to(4).to_coords_f32(), (0.1235, 9.8765));
+
+        let point = PdfPoint::from_coords_f32(0.46, 2.04);
+        Lua::new()
+            .load(chunk! {
+                $PdfUtils.assert_deep_equal($point:round_to(1), { x = 0.5, y = 2 })
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_support_point_math() {
+        let a = PdfPoint::from_coords_f32(1.0, 2.0);
+        let b = PdfPoint::from_coords_f32(4.0, 6.0);
+
+        assert_eq!(a + b, PdfPoint::from_coords_f32(5.0, 8.0));
+        assert_eq!(b - a, PdfPoint::from_coords_f32(3.0, 4.0));
+        assert_eq!(a * 2.0, PdfPoint::from_coords_f32(2.0, 4.0));
+        assert_eq!(a.distance_to(b), Mm(5.0));
+        assert_eq!(a.midpoint(b), PdfPoint::from_coords_f32(2.5, 4.0));
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 2.0), PdfPoint::from_coords_f32(7.0, 10.0));
+        assert_eq!(
+            b.rotate_around(a, 90.0).to_precision(3),
+            PdfPoint::from_coords_f32(-3.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn should_support_point_math_in_lua() {
+        let a = PdfPoint::from_coords_f32(1.0, 2.0);
+        let b = PdfPoint::from_coords_f32(4.0, 6.0);
+
+        Lua::new()
+            .load(chunk! {
+                local u = $PdfUtils
+                local a = $a
+                local b = $b
+
+                u.assert_deep_equal(a + b, { x = 5, y = 8 })
+                u.assert_deep_equal(b - a, { x = 3, y = 4 })
+                u.assert_deep_equal(a * 2, { x = 2, y = 4 })
+                u.assert_deep_equal(2 * a, { x = 2, y = 4 })
+                u.assert_deep_equal(a * 0.5, { x = 0.5, y = 1 })
+
+                // Plain tables can be used on either side
+                u.assert_deep_equal(a + { x = 1, y = 1 }, { x = 2, y = 3 })
+                u.assert_deep_equal({ 1, 1 } - a, { x = 0, y = -1 })
+
+                assert(a:distance_to(b) == 5, "distance is not 5")
+                u.assert_deep_equal(a:midpoint(b), { x = 2.5, y = 4 })
+                u.assert_deep_equal(a:lerp(b, 0.5), { x = 2.5, y = 4 })
+                u.assert_deep_equal(b:rotate_around(a, 90):with_precision(3), { x = -3, y = 5 })
+
+                // Results are also points
+                u.assert_deep_equal((a + b):midpoint(a), { x = 3, y = 5 })
+
+                // Points are equal when they match to a thousandth, ignoring float noise
+                assert(b:rotate_around(a, 90) == a + { x = -4, y = 3 }, "rotated point differs")
+                assert(a + { x = 0.0001, y = 0 } == a, "point with noise differs")
+                assert(a + { x = 0.01, y = 0 } ~= a, "different points are equal")
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_convert_from_lua() {
         let point = PdfPoint::from_coords_f32(1.0, 2.0);
@@ -189,4 +401,23 @@ mod tests {
             .exec()
             .expect("Assertion failed");
     }
+
+    #[test]
+    fn should_share_metatable_between_points_in_lua() {
+        let (a, b) = (
+            PdfPoint::from_coords_f32(1.0, 2.0),
+            PdfPoint::from_coords_f32(3.0, 4.0),
+        );
+
+        Lua::new()
+            .load(chunk! {
+                local a, b = $a, $b
+                assert(getmetatable(a) == getmetatable(b), "Metatable is not shared")
+                local c = a + b
+                assert(c.x == 4 and c.y == 6, "Methods of shared metatable failed")
+                assert(a.x == 1 and b.x == 3, "Points were changed")
+            })
+            .exec()
+            .expect("Assertion failed");
+    }
 }
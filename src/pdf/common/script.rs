@@ -0,0 +1,65 @@
+use mlua::prelude::*;
+
+/// Script of text that is drawn smaller and shifted from the baseline, such as the "st" of "1st".
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PdfTextScript {
+    /// Raised above the baseline, such as ordinals and footnote markers.
+    Super,
+    /// Lowered below the baseline, such as chemical formulas.
+    Sub,
+}
+
+impl<'lua> IntoLua<'lua> for PdfTextScript {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::Super => "super",
+            Self::Sub => "sub",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfTextScript {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "super" => Ok(Self::Super),
+                "sub" => Ok(Self::Sub),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.text_script",
+                    message: Some(format!("unknown script: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.text_script",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        assert_eq!(
+            Lua::new()
+                .load(chunk!("sub"))
+                .eval::<PdfTextScript>()
+                .unwrap(),
+            PdfTextScript::Sub,
+        );
+        assert!(Lua::new()
+            .load(chunk!("middle"))
+            .eval::<PdfTextScript>()
+            .is_err());
+    }
+}
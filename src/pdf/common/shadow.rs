@@ -0,0 +1,154 @@
+use crate::pdf::{PdfColor, PdfLuaTableExt, PdfPoint};
+use mlua::prelude::*;
+
+/// Number of rings of copies drawn around the offset to approximate a blurred shadow.
+const BLUR_RINGS: usize = 3;
+
+/// Number of copies drawn within each ring of a blurred shadow.
+const BLUR_COPIES_PER_RING: usize = 8;
+
+/// Shadow drawn beneath an object as an offset copy of the object in a single color.
+///
+/// Blurring is approximated by layering translucent copies spread around the offset.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfShadow {
+    /// Distance in millimeters to move the shadow from the object.
+    pub offset: PdfPoint,
+    /// Color of the shadow, whose opacity is the opacity of the shadow where it is solid.
+    pub color: PdfColor,
+    /// Distance in millimeters to spread the shadow beyond its edges, or 0 for a sharp shadow.
+    pub blur: f32,
+}
+
+impl Default for PdfShadow {
+    fn default() -> Self {
+        Self {
+            offset: PdfPoint::from_coords_f32(1.0, -1.0),
+            color: PdfColor::black().with_alpha(0.35),
+            blur: 0.0,
+        }
+    }
+}
+
+impl PdfShadow {
+    /// Returns the offsets of the copies of the object drawn to form the shadow, alongside the
+    /// color of each copy.
+    ///
+    /// Copies of a blurred shadow are translucent so that where all of them overlap, the shadow
+    /// has the opacity of its color.
+    pub fn copies(&self) -> (Vec<PdfPoint>, PdfColor) {
+        if self.blur <= 0.0 {
+            return (vec![self.offset], self.color);
+        }
+
+        let mut offsets = vec![self.offset];
+        for ring in 1..=BLUR_RINGS {
+            let radius = self.blur * ring as f32 / BLUR_RINGS as f32;
+            for i in 0..BLUR_COPIES_PER_RING {
+                let angle = std::f32::consts::TAU * i as f32 / BLUR_COPIES_PER_RING as f32;
+                offsets.push(
+                    self.offset
+                        + PdfPoint::from_coords_f32(radius * angle.cos(), radius * angle.sin()),
+                );
+            }
+        }
+
+        let alpha = 1.0 - (1.0 - self.color.alpha()).powf(1.0 / offsets.len() as f32);
+        (offsets, self.color.with_alpha(alpha))
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfShadow {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("offset", self.offset)?;
+        table.raw_set("color", self.color)?;
+        table.raw_set("blur", self.blur)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfShadow {
+    /// Converts from a table with optional `offset`, `color`, and `blur`, or `true` for the
+    /// default shadow.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Boolean(true) => Ok(Self::default()),
+            LuaValue::Table(table) => {
+                let default = Self::default();
+                Ok(Self {
+                    offset: table
+                        .raw_get_ext::<_, Option<_>>("offset")?
+                        .unwrap_or(default.offset),
+                    color: table
+                        .raw_get_ext::<_, Option<_>>("color")?
+                        .unwrap_or(default.color),
+                    blur: table
+                        .raw_get_ext::<_, Option<f32>>("blur")?
+                        .map(|x| x.max(0.0))
+                        .unwrap_or(default.blur),
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.shadow",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        assert_eq!(
+            lua.load(chunk!(true)).eval::<PdfShadow>().unwrap(),
+            PdfShadow::default()
+        );
+        assert_eq!(
+            lua.load(chunk!({ offset = { 2, -3 }, color = "#ff0000", blur = 1.5 }))
+                .eval::<PdfShadow>()
+                .unwrap(),
+            PdfShadow {
+                offset: PdfPoint::from_coords_f32(2.0, -3.0),
+                color: PdfColor::from_rgb_u8(255, 0, 0),
+                blur: 1.5,
+            }
+        );
+        lua.load(chunk!(false))
+            .eval::<PdfShadow>()
+            .expect_err("Unexpectedly converted false into shadow");
+    }
+
+    #[test]
+    fn copies_should_spread_translucent_copies_when_blurred() {
+        let shadow = PdfShadow::default();
+        let (offsets, color) = shadow.copies();
+        assert_eq!(offsets, vec![shadow.offset]);
+        assert_eq!(color, shadow.color);
+
+        let shadow = PdfShadow {
+            blur: 2.0,
+            ..Default::default()
+        };
+        let (offsets, color) = shadow.copies();
+        assert_eq!(offsets.len(), 1 + BLUR_RINGS * BLUR_COPIES_PER_RING);
+        assert!(offsets
+            .iter()
+            .all(|x| x.distance_to(shadow.offset).0 <= 2.0 + f32::EPSILON * 4.0));
+
+        // Overlapping every copy results in the opacity of the shadow
+        let combined = 1.0 - (1.0 - color.alpha()).powi(offsets.len() as i32);
+        assert!((combined - shadow.color.alpha()).abs() < 0.001);
+    }
+}
@@ -0,0 +1,74 @@
+use mlua::prelude::*;
+
+/// Side of a two-page spread that a page falls on once printed and bound.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfPageSide {
+    /// Left-hand (verso) page, which is every even-numbered page.
+    Left,
+    /// Right-hand (recto) page, which is every odd-numbered page including the first.
+    #[default]
+    Right,
+}
+
+impl PdfPageSide {
+    /// Returns the side of the page numbered `number`, starting at 1.
+    pub fn from_number(number: usize) -> Self {
+        if number % 2 == 1 {
+            Self::Right
+        } else {
+            Self::Left
+        }
+    }
+
+    /// Returns true if the side is the left-hand page.
+    #[inline]
+    pub fn is_left(self) -> bool {
+        matches!(self, Self::Left)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfPageSide {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfPageSide {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_string_lossy().as_ref() {
+                "left" => Ok(Self::Left),
+                "right" => Ok(Self::Right),
+                ty => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.page_side",
+                    message: Some(format!("unknown side: {ty}")),
+                }),
+            },
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.page_side",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_number_should_place_odd_pages_on_the_right() {
+        assert_eq!(PdfPageSide::from_number(1), PdfPageSide::Right);
+        assert_eq!(PdfPageSide::from_number(2), PdfPageSide::Left);
+        assert_eq!(PdfPageSide::from_number(3), PdfPageSide::Right);
+    }
+}
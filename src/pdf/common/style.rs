@@ -0,0 +1,67 @@
+use crate::pdf::{PdfColor, PdfContext, PdfLuaTableExt};
+use crate::runtime::RuntimeFontId;
+use mlua::prelude::*;
+
+/// Default style applied to objects that do not specify their own, such as the objects within a
+/// group.
+///
+/// Each field overrides the equivalent page default (e.g. `pdf.page.fill_color`) when set,
+/// alongside the equivalent defaults of objects by type (e.g. `pdf.defaults.rect.fill_color`).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfStyle {
+    pub fill_color: Option<PdfColor>,
+    pub outline_color: Option<PdfColor>,
+    pub outline_thickness: Option<f32>,
+    pub font: Option<RuntimeFontId>,
+    pub size: Option<f32>,
+}
+
+impl PdfStyle {
+    /// Returns true if the style does not override anything.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns a style using the fields of this style, falling back to those of `parent`.
+    pub fn or(self, parent: Self) -> Self {
+        Self {
+            fill_color: self.fill_color.or(parent.fill_color),
+            outline_color: self.outline_color.or(parent.outline_color),
+            outline_thickness: self.outline_thickness.or(parent.outline_thickness),
+            font: self.font.or(parent.font),
+            size: self.size.or(parent.size),
+        }
+    }
+
+    /// Invokes `f` with a copy of `ctx` styled by this style, falling back to the style of `ctx`
+    /// for anything this style does not override, and using the font of this style as the
+    /// fallback font.
+    pub fn with_context<R>(&self, ctx: PdfContext<'_>, f: impl FnOnce(PdfContext<'_>) -> R) -> R {
+        f(PdfContext {
+            style: self.or(ctx.style),
+            fallback_font_id: self.font.unwrap_or(ctx.fallback_font_id),
+            ..ctx
+        })
+    }
+
+    /// Adds the fields of the style to an existing Lua table.
+    pub fn add_to_table(&self, table: &LuaTable) -> LuaResult<()> {
+        table.raw_set("fill_color", self.fill_color)?;
+        table.raw_set("outline_color", self.outline_color)?;
+        table.raw_set("outline_thickness", self.outline_thickness)?;
+        table.raw_set("font", self.font)?;
+        table.raw_set("size", self.size)?;
+        Ok(())
+    }
+
+    /// Reads the fields of the style from a Lua table, where missing fields are not overridden.
+    pub fn from_table(table: &LuaTable) -> LuaResult<Self> {
+        Ok(Self {
+            fill_color: table.raw_get_ext("fill_color")?,
+            outline_color: table.raw_get_ext("outline_color")?,
+            outline_thickness: table.raw_get_ext("outline_thickness")?,
+            font: table.raw_get_ext("font")?,
+            size: table.raw_get_ext("size")?,
+        })
+    }
+}
@@ -1,18 +1,53 @@
+mod defaults;
+mod imposition;
 mod page;
+mod watermark;
 
-use crate::pdf::PdfLuaTableExt;
+use crate::pdf::{PdfLinkStyle, PdfLuaTableExt, PdfUtils};
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
 use chrono::offset::Local;
 use mlua::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+pub use defaults::{PdfConfigDefaults, PdfLineDefaults, PdfShapeDefaults, PdfTextDefaults};
+pub use imposition::{PdfImposition, PdfImpositionLayout};
 pub use page::PdfConfigPage;
+pub use watermark::{PdfConfigWatermark, PdfWatermarkPages};
 
 /// Configuration for PDFs.
 ///
 /// Supports converting to & from a Lua table.
 #[derive(Clone, Debug)]
 pub struct PdfConfig {
+    /// Arguments passed to the script, e.g. from the commandline
+    pub args: BTreeMap<String, String>,
     /// Configuration tied to a PDF page
     pub page: PdfConfigPage,
+    /// Style applied to text objects that carry links
+    pub link_style: PdfLinkStyle,
+    /// If true, the pdf is produced as PDF/A-2b for archival
+    pub pdfa: bool,
+    /// If true, duplicate objects are merged and streams compressed to reduce the pdf size
+    pub optimize: bool,
+    /// If true, the script can be precompiled Luau bytecode, which is not verified before it runs
+    pub allow_bytecode: bool,
+    /// Level (0 to 2) of optimizations applied when compiling the script, where 2 inlines
+    /// functions and unrolls loops at the cost of less helpful errors
+    pub opt_level: u8,
+    /// If true, the script is compiled to native code where supported, which needs makepdf to be
+    /// built with the jit feature
+    pub native: bool,
+    /// If true, objects with unknown fields are rejected rather than ignoring the fields, such as
+    /// typos like `fill_colour`
+    pub strict: bool,
+    /// If provided, pages are placed onto larger sheets for printing when the pdf is saved
+    pub imposition: Option<PdfImposition>,
+    /// Text or images stamped onto pages while they are built
+    pub watermarks: Vec<PdfConfigWatermark>,
+    /// Paths of fonts loaded before the script runs, by the names used to look them up
+    pub fonts: BTreeMap<String, String>,
     /// Path of script
     pub script: String,
     /// Title of the pdf document
@@ -24,19 +59,129 @@ impl Default for PdfConfig {
         let page = PdfConfigPage::default();
 
         Self {
+            args: BTreeMap::new(),
             page,
+            link_style: PdfLinkStyle::default(),
+            pdfa: false,
+            optimize: false,
+            allow_bytecode: false,
+            opt_level: 1,
+            native: false,
+            strict: false,
+            imposition: None,
+            watermarks: Vec::new(),
+            fonts: BTreeMap::new(),
             script: String::from("makepdf.lua"),
             title: format!("MakePDF {}", Local::now().naive_local().date()),
         }
     }
 }
 
+impl PdfConfig {
+    /// Parses an argument in the form `key=value`, failing if there is no `=` or the key is
+    /// empty.
+    pub fn parse_arg(s: &str) -> anyhow::Result<(String, String)> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => anyhow::bail!("Invalid argument '{s}', expected key=value"),
+        }
+    }
+
+    /// Parses a font in the form `name=path`, or `path` for the default font.
+    pub fn parse_font(s: &str) -> anyhow::Result<(Option<String>, String)> {
+        match s.split_once('=') {
+            Some((name, path)) if !name.trim().is_empty() && !path.is_empty() => {
+                Ok((Some(name.trim().to_string()), path.to_string()))
+            }
+            Some(_) => anyhow::bail!("Invalid font '{s}', expected name=path or path"),
+            None if s.is_empty() => anyhow::bail!("Invalid font, expected name=path or path"),
+            None => Ok((None, s.to_string())),
+        }
+    }
+
+    /// Finds the TrueType and OpenType fonts (`.ttf` and `.otf`) directly within `dir`, returning
+    /// their paths by their file names without extensions.
+    pub fn find_fonts(dir: impl AsRef<Path>) -> anyhow::Result<BTreeMap<String, String>> {
+        let dir = dir.as_ref();
+        RuntimeFiles::check_fs(dir)?;
+        let mut fonts = BTreeMap::new();
+        for entry in std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read font directory {}", dir.display()))?
+        {
+            let path = entry?.path();
+            let is_font = path
+                .extension()
+                .and_then(|x| x.to_str())
+                .is_some_and(|x| x.eq_ignore_ascii_case("ttf") || x.eq_ignore_ascii_case("otf"));
+            if let (true, Some(name)) = (is_font, path.file_stem().and_then(|x| x.to_str())) {
+                fonts.insert(name.to_string(), path.to_string_lossy().to_string());
+            }
+        }
+
+        Ok(fonts)
+    }
+
+    /// Creates a configuration from the JSON object `text`, such as
+    /// `{"title": "Planner", "page": {"width": 210, "height": 297}}`, using the default for any
+    /// field missing from the object (including fields of the page).
+    pub fn from_json(text: &str) -> anyhow::Result<Self> {
+        let lua = Lua::new();
+        let table = match Self::default().into_lua(&lua)? {
+            LuaValue::Table(table) => table,
+            _ => unreachable!("Configuration is always a table"),
+        };
+
+        let overrides = match PdfUtils::parse_json(&lua, text)? {
+            LuaValue::Table(table) if table.raw_len() == 0 => table,
+            LuaValue::Table(_) => {
+                anyhow::bail!("Invalid configuration, expected object but got array")
+            }
+            value => anyhow::bail!(
+                "Invalid configuration, expected object but got {}",
+                value.type_name()
+            ),
+        };
+
+        for pair in overrides.pairs::<LuaValue, LuaValue>() {
+            let (key, value) = pair?;
+
+            // Fields of the page are applied individually so missing fields keep their defaults
+            match (key.as_str(), value) {
+                (Some("page"), LuaValue::Table(page)) => {
+                    let existing: LuaTable = table.raw_get("page")?;
+                    for pair in page.pairs::<LuaValue, LuaValue>() {
+                        let (key, value) = pair?;
+                        existing.raw_set(key, value)?;
+                    }
+                }
+                (_, value) => table.raw_set(key, value)?,
+            }
+        }
+
+        Ok(Self::from_lua(LuaValue::Table(table), &lua)?)
+    }
+}
+
 impl<'lua> IntoLua<'lua> for PdfConfig {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
         let table = lua.create_table()?;
 
+        table.raw_set("args", self.args)?;
+        table.raw_set("defaults", self.page.defaults)?;
         table.raw_set("page", self.page)?;
+        table.raw_set("link_style", self.link_style)?;
+        table.raw_set("pdfa", self.pdfa)?;
+        table.raw_set("optimize", self.optimize)?;
+        table.raw_set("allow_bytecode", self.allow_bytecode)?;
+        table.raw_set("opt_level", self.opt_level)?;
+        table.raw_set("native", self.native)?;
+        table.raw_set("strict", self.strict)?;
+        table.raw_set("imposition", self.imposition)?;
+        table.raw_set("watermarks", self.watermarks)?;
+        table.raw_set("fonts", self.fonts)?;
         table.raw_set("script", self.script)?;
         table.raw_set("title", self.title)?;
 
@@ -49,7 +194,44 @@ impl<'lua> FromLua<'lua> for PdfConfig {
     fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
         match value {
             LuaValue::Table(table) => Ok(Self {
-                page: table.raw_get_ext("page")?,
+                args: table
+                    .raw_get_ext::<_, Option<_>>("args")?
+                    .unwrap_or_default(),
+                page: PdfConfigPage {
+                    defaults: table
+                        .raw_get_ext::<_, Option<_>>("defaults")?
+                        .unwrap_or_default(),
+                    ..table.raw_get_ext("page")?
+                },
+                link_style: table
+                    .raw_get_ext::<_, Option<_>>("link_style")?
+                    .unwrap_or_default(),
+                pdfa: table
+                    .raw_get_ext::<_, Option<_>>("pdfa")?
+                    .unwrap_or_default(),
+                optimize: table
+                    .raw_get_ext::<_, Option<_>>("optimize")?
+                    .unwrap_or_default(),
+                allow_bytecode: table
+                    .raw_get_ext::<_, Option<_>>("allow_bytecode")?
+                    .unwrap_or_default(),
+                opt_level: table
+                    .raw_get_ext::<_, Option<u8>>("opt_level")?
+                    .map(|x| x.min(2))
+                    .unwrap_or(1),
+                native: table
+                    .raw_get_ext::<_, Option<_>>("native")?
+                    .unwrap_or_default(),
+                strict: table
+                    .raw_get_ext::<_, Option<_>>("strict")?
+                    .unwrap_or_default(),
+                imposition: table.raw_get_ext("imposition")?,
+                watermarks: table
+                    .raw_get_ext::<_, Option<_>>("watermarks")?
+                    .unwrap_or_default(),
+                fonts: table
+                    .raw_get_ext::<_, Option<_>>("fonts")?
+                    .unwrap_or_default(),
                 script: table.raw_get_ext("script").unwrap_or_default(),
                 title: table.raw_get_ext("title").unwrap_or_default(),
             }),
@@ -61,3 +243,122 @@ impl<'lua> FromLua<'lua> for PdfConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn parse_arg_should_split_key_and_value() {
+        assert_eq!(
+            PdfConfig::parse_arg("year=2025").unwrap(),
+            (String::from("year"), String::from("2025"))
+        );
+        assert_eq!(
+            PdfConfig::parse_arg("title=a=b").unwrap(),
+            (String::from("title"), String::from("a=b"))
+        );
+        assert_eq!(
+            PdfConfig::parse_arg("empty=").unwrap(),
+            (String::from("empty"), String::new())
+        );
+        assert!(PdfConfig::parse_arg("year").is_err());
+        assert!(PdfConfig::parse_arg("=2025").is_err());
+    }
+
+    #[test]
+    fn parse_font_should_split_optional_name_and_path() {
+        assert_eq!(
+            PdfConfig::parse_font("heading=fonts/Lora.ttf").unwrap(),
+            (
+                Some(String::from("heading")),
+                String::from("fonts/Lora.ttf")
+            )
+        );
+        assert_eq!(
+            PdfConfig::parse_font("fonts/Lora.ttf").unwrap(),
+            (None, String::from("fonts/Lora.ttf"))
+        );
+        assert!(PdfConfig::parse_font("").is_err());
+        assert!(PdfConfig::parse_font("=fonts/Lora.ttf").is_err());
+        assert!(PdfConfig::parse_font("heading=").is_err());
+    }
+
+    #[test]
+    fn find_fonts_should_name_fonts_by_file_stem() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("fonts");
+        let fonts = PdfConfig::find_fonts(&dir).unwrap();
+        assert_eq!(
+            fonts.keys().collect::<Vec<_>>(),
+            vec!["JetBrainsMono-Regular"]
+        );
+        assert!(PdfConfig::find_fonts(dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn from_json_should_use_defaults_for_missing_fields() {
+        let config =
+            PdfConfig::from_json(r#"{"title": "Planner", "page": {"width": 210}, "pdfa": true}"#)
+                .unwrap();
+        assert_eq!(config.title, "Planner");
+        assert_eq!(config.page.width.0, 210.0);
+        assert_eq!(config.page.height, PdfConfigPage::default().height);
+        assert!(config.pdfa);
+        assert!(!config.optimize);
+
+        assert!(PdfConfig::from_json("[1, 2]").is_err());
+        assert!(PdfConfig::from_json("{").is_err());
+    }
+
+    #[test]
+    fn should_expose_args_in_lua() {
+        let lua = Lua::new();
+        let mut config = PdfConfig::default();
+        config
+            .args
+            .insert(String::from("year"), String::from("2025"));
+        lua.globals().raw_set("pdf", Pdf::new(config)).unwrap();
+
+        lua.load(chunk! {
+            pdf.utils.assert_deep_equal(pdf.args, { year = "2025" })
+            pdf.args.theme = "dark"
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        assert_eq!(
+            pdf.config.args.get("theme").map(String::as_str),
+            Some("dark")
+        );
+    }
+
+    #[test]
+    fn should_read_defaults_of_objects_from_lua() {
+        let lua = Lua::new();
+        lua.globals()
+            .raw_set("pdf", Pdf::new(PdfConfig::default()))
+            .unwrap();
+
+        lua.load(chunk! {
+            pdf.defaults.text = { size = 10, color = "#222222" }
+            pdf.defaults.line.thickness = 0.4
+        })
+        .exec()
+        .expect("Failed to set defaults");
+
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        let defaults = pdf.config.page.defaults;
+        assert_eq!(defaults.text.size, Some(10.0));
+        assert_eq!(
+            defaults.text.color.map(|x| x.into_colors_u8()),
+            Some((34, 34, 34))
+        );
+        assert_eq!(defaults.line.thickness, Some(0.4));
+        assert_eq!(defaults.rect, PdfShapeDefaults::default());
+    }
+}
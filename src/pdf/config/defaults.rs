@@ -0,0 +1,189 @@
+use crate::pdf::{PdfColor, PdfLineCapStyle, PdfLineDashPattern, PdfLineJoinStyle, PdfLuaTableExt};
+use mlua::prelude::*;
+
+/// Defaults of each type of object, used in place of the defaults of pages (e.g.
+/// `pdf.page.fill_color`) for objects of that type that do not specify their own.
+///
+/// Supports converting to & from a Lua table.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfConfigDefaults {
+    pub circle: PdfShapeDefaults,
+    pub line: PdfLineDefaults,
+    pub rect: PdfShapeDefaults,
+    pub shape: PdfShapeDefaults,
+    pub text: PdfTextDefaults,
+}
+
+impl<'lua> IntoLua<'lua> for PdfConfigDefaults {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("circle", self.circle)?;
+        table.raw_set("line", self.line)?;
+        table.raw_set("rect", self.rect)?;
+        table.raw_set("shape", self.shape)?;
+        table.raw_set("text", self.text)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfConfigDefaults {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                circle: table
+                    .raw_get_ext::<_, Option<_>>("circle")?
+                    .unwrap_or_default(),
+                line: table
+                    .raw_get_ext::<_, Option<_>>("line")?
+                    .unwrap_or_default(),
+                rect: table
+                    .raw_get_ext::<_, Option<_>>("rect")?
+                    .unwrap_or_default(),
+                shape: table
+                    .raw_get_ext::<_, Option<_>>("shape")?
+                    .unwrap_or_default(),
+                text: table
+                    .raw_get_ext::<_, Option<_>>("text")?
+                    .unwrap_or_default(),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.defaults",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Defaults of lines, named after the fields of lines.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfLineDefaults {
+    pub color: Option<PdfColor>,
+    pub thickness: Option<f32>,
+    pub dash_pattern: Option<PdfLineDashPattern>,
+    pub cap_style: Option<PdfLineCapStyle>,
+    pub join_style: Option<PdfLineJoinStyle>,
+}
+
+impl<'lua> IntoLua<'lua> for PdfLineDefaults {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("color", self.color)?;
+        table.raw_set("thickness", self.thickness)?;
+        table.raw_set("dash_pattern", self.dash_pattern)?;
+        table.raw_set("cap_style", self.cap_style)?;
+        table.raw_set("join_style", self.join_style)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLineDefaults {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                color: table.raw_get_ext("color")?,
+                thickness: table.raw_get_ext("thickness")?,
+                dash_pattern: table.raw_get_ext("dash_pattern")?,
+                cap_style: table.raw_get_ext("cap_style")?,
+                join_style: table.raw_get_ext("join_style")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.defaults.line",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Defaults of circles, rects, and shapes, named after the fields of those objects.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfShapeDefaults {
+    pub fill_color: Option<PdfColor>,
+    pub outline_color: Option<PdfColor>,
+    pub outline_thickness: Option<f32>,
+    pub dash_pattern: Option<PdfLineDashPattern>,
+    pub cap_style: Option<PdfLineCapStyle>,
+    pub join_style: Option<PdfLineJoinStyle>,
+}
+
+impl<'lua> IntoLua<'lua> for PdfShapeDefaults {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("fill_color", self.fill_color)?;
+        table.raw_set("outline_color", self.outline_color)?;
+        table.raw_set("outline_thickness", self.outline_thickness)?;
+        table.raw_set("dash_pattern", self.dash_pattern)?;
+        table.raw_set("cap_style", self.cap_style)?;
+        table.raw_set("join_style", self.join_style)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfShapeDefaults {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                fill_color: table.raw_get_ext("fill_color")?,
+                outline_color: table.raw_get_ext("outline_color")?,
+                outline_thickness: table.raw_get_ext("outline_thickness")?,
+                dash_pattern: table.raw_get_ext("dash_pattern")?,
+                cap_style: table.raw_get_ext("cap_style")?,
+                join_style: table.raw_get_ext("join_style")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.defaults.shape",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Defaults of text, named after the fields of text.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PdfTextDefaults {
+    pub size: Option<f32>,
+    pub color: Option<PdfColor>,
+}
+
+impl<'lua> IntoLua<'lua> for PdfTextDefaults {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("size", self.size)?;
+        table.raw_set("color", self.color)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfTextDefaults {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                size: table.raw_get_ext("size")?,
+                color: table.raw_get_ext("color")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.defaults.text",
+                message: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::pdf::{PdfConfigPage, PdfLuaTableExt};
+use mlua::prelude::*;
+use printpdf::Mm;
+use std::str::FromStr;
+
+/// Arrangement of the pages of a PDF onto larger sheets of paper for printing at home, applied
+/// when the PDF is saved.
+///
+/// Supports converting to & from a Lua table.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PdfImposition {
+    /// Order that pages are placed onto sheets.
+    pub layout: PdfImpositionLayout,
+    /// Width of a sheet in millimeters.
+    pub width: Mm,
+    /// Height of a sheet in millimeters.
+    pub height: Mm,
+}
+
+impl PdfImposition {
+    /// Creates a new imposition of `layout` onto A4 sheets.
+    pub fn new(layout: PdfImpositionLayout) -> Self {
+        let (width, height) = Self::parse_sheet("a4").unwrap();
+        Self {
+            layout,
+            width,
+            height,
+        }
+    }
+
+    /// Parses the dimensions `(width, height)` of a sheet, which is either the name of a paper
+    /// size (e.g. `a4` or `letter`) or dimensions supported by [`PdfConfigPage::parse_size`].
+    pub fn parse_sheet(s: &str) -> anyhow::Result<(Mm, Mm)> {
+        PdfConfigPage::parse_size(s, 72.0)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfImposition {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("layout", self.layout)?;
+        table.raw_set("width", self.width.0)?;
+        table.raw_set("height", self.height.0)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfImposition {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => {
+                let mut this = Self::new(table.raw_get_ext("layout")?);
+
+                // Sheets are either named or given explicit dimensions
+                if let Some(sheet) = table.raw_get_ext::<_, Option<String>>("sheet")? {
+                    (this.width, this.height) = Self::parse_sheet(&sheet).map_err(|x| {
+                        LuaError::FromLuaConversionError {
+                            from: "table",
+                            to: "pdf.config.imposition",
+                            message: Some(x.to_string()),
+                        }
+                    })?;
+                }
+
+                if let Some(width) = table.raw_get_ext::<_, Option<f32>>("width")? {
+                    this.width = Mm(width);
+                }
+
+                if let Some(height) = table.raw_get_ext::<_, Option<f32>>("height")? {
+                    this.height = Mm(height);
+                }
+
+                Ok(this)
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.imposition",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Order that pages are placed onto sheets, two pages side by side on each side of a sheet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PdfImpositionLayout {
+    /// Pages are placed in order, two to a side.
+    TwoUp,
+    /// Pages are placed so that the sheets, printed on both sides and folded in half, form a
+    /// booklet.
+    Booklet,
+}
+
+impl PdfImpositionLayout {
+    /// Returns the pages (by index) placed on the left and right of each side of the sheets for
+    /// a document of `count` pages, in the order the sides are printed.
+    ///
+    /// Blank pages are added to fill out the last side (or the last sheet of a booklet), which
+    /// are represented by `None`.
+    pub fn order(self, count: usize) -> Vec<[Option<usize>; 2]> {
+        let page = |i: usize| if i < count { Some(i) } else { None };
+
+        match self {
+            Self::TwoUp => (0..count.div_ceil(2))
+                .map(|i| [page(i * 2), page(i * 2 + 1)])
+                .collect(),
+
+            // Each sheet holds four pages, working inward from the outermost pages of the booklet
+            Self::Booklet => {
+                let total = count.div_ceil(4) * 4;
+                (0..total / 4)
+                    .flat_map(|i| {
+                        let (first, last) = (i * 2, total - 1 - i * 2);
+                        [[page(last), page(first)], [page(first + 1), page(last - 1)]]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl FromStr for PdfImpositionLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2up" => Ok(Self::TwoUp),
+            "booklet" => Ok(Self::Booklet),
+            _ => Err(format!(
+                "Unknown imposition {s}, expected one of 2up, booklet"
+            )),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfImpositionLayout {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(match self {
+            Self::TwoUp => "2up",
+            Self::Booklet => "booklet",
+        })
+        .map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfImpositionLayout {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => {
+                s.to_string_lossy()
+                    .parse()
+                    .map_err(|x| LuaError::FromLuaConversionError {
+                        from,
+                        to: "pdf.config.imposition_layout",
+                        message: Some(x),
+                    })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.config.imposition_layout",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn order_should_place_pages_in_pairs_for_two_up() {
+        assert_eq!(
+            PdfImpositionLayout::TwoUp.order(3),
+            vec![[Some(0), Some(1)], [Some(2), None]]
+        );
+    }
+
+    #[test]
+    fn order_should_place_pages_in_signature_order_for_booklet() {
+        // Six pages are padded to eight, printed on the fronts and backs of two sheets
+        assert_eq!(
+            PdfImpositionLayout::Booklet.order(6),
+            vec![
+                [None, Some(0)],
+                [Some(1), None],
+                [Some(5), Some(2)],
+                [Some(3), Some(4)],
+            ]
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        assert_eq!(
+            lua.load(chunk!({ layout = "booklet" }))
+                .eval::<PdfImposition>()
+                .unwrap(),
+            PdfImposition::new(PdfImpositionLayout::Booklet),
+        );
+
+        assert_eq!(
+            lua.load(chunk!({ layout = "2up", sheet = "Letter" }))
+                .eval::<PdfImposition>()
+                .unwrap(),
+            PdfImposition {
+                layout: PdfImpositionLayout::TwoUp,
+                width: Mm(215.9),
+                height: Mm(279.4),
+            },
+        );
+
+        lua.load(chunk!({ layout = "4up" }))
+            .eval::<PdfImposition>()
+            .expect_err("Unexpectedly converted unknown layout");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
 use crate::pdf::*;
 use mlua::prelude::*;
 use printpdf::{Mm, Px};
@@ -15,7 +16,17 @@ pub struct PdfConfigPage {
     pub width: Mm,
     /// Height of a page in millimeters.
     pub height: Mm,
+    /// Space to leave empty along each edge of a page.
+    pub margins: PdfPadding,
+    /// If true, margins are mirrored on left-hand pages, making the left margin the inner
+    /// (binding) margin of every page.
+    pub mirror_margins: bool,
+    /// Area along each edge of a page hidden or covered by the device, such as a toolbar, where
+    /// content should not be placed.
+    pub unsafe_area: PdfPadding,
 
+    /// Default depth of objects used when none specified.
+    pub depth: i64,
     /// Default font size used when none specified.
     pub font_size: f32,
     /// Default fill color used when none specified.
@@ -24,12 +35,29 @@ pub struct PdfConfigPage {
     pub outline_color: PdfColor,
     /// Default thickness for an outline when none specified.
     pub outline_thickness: f32,
+    /// Minimum thickness of any outline, where thinner outlines (other than hairlines) are
+    /// thickened to this value.
+    pub min_outline_thickness: f32,
     /// Default dash pattern of lines when none specified.
     pub line_dash_pattern: PdfLineDashPattern,
     /// Default cap style of lines when none specified.
     pub line_cap_style: PdfLineCapStyle,
     /// Default join style of lines when none specified.
     pub line_join_style: PdfLineJoinStyle,
+    /// Defaults of each type of object, used in place of the defaults above when set, which
+    /// are found at `pdf.defaults` in Lua rather than within the page.
+    pub defaults: PdfConfigDefaults,
+
+    /// Layers that objects can be drawn within, stacked in the order they were created.
+    pub layers: Vec<PdfLayer>,
+
+    /// If true, snaps stroke coordinates and thicknesses to the pixel grid of the page's DPI.
+    pub snap_to_pixels: bool,
+    /// If true, converts all colors to grayscale when drawing.
+    pub grayscale: bool,
+    /// If set, overlays each page with a coordinate grid of this spacing (in millimeters) and
+    /// the bounds of each object for debugging layouts.
+    pub debug_grid: Option<f32>,
 }
 
 impl Default for PdfConfigPage {
@@ -41,14 +69,26 @@ impl Default for PdfConfigPage {
             font: None,
             width: Px(1404).into_pt(dpi).into(),
             height: Px(1872).into_pt(dpi).into(),
+            margins: PdfPadding::default(),
+            mirror_margins: false,
+            unsafe_area: PdfPadding::default(),
 
+            depth: 0,
             font_size: 32.0,
             fill_color: PdfColor::grey(),
             outline_color: PdfColor::black(),
             outline_thickness: 1.0,
+            min_outline_thickness: 0.0,
             line_dash_pattern: PdfLineDashPattern::solid(),
             line_cap_style: PdfLineCapStyle::round(),
             line_join_style: PdfLineJoinStyle::round(),
+            defaults: PdfConfigDefaults::default(),
+
+            layers: Vec::new(),
+
+            snap_to_pixels: false,
+            grayscale: false,
+            debug_grid: None,
         }
     }
 }
@@ -60,6 +100,94 @@ impl PdfConfigPage {
         let (urx, ury) = (llx + self.width, lly + self.height);
         PdfBounds::from_coords(llx, lly, urx, ury)
     }
+
+    /// Returns the margins of a page on `side`, swapping the left and right margins of left-hand
+    /// pages when mirroring margins.
+    pub fn margins_for(&self, side: PdfPageSide) -> PdfPadding {
+        let margins = self.margins;
+        if self.mirror_margins && side.is_left() {
+            PdfPadding::new(margins.top, margins.left, margins.bottom, margins.right)
+        } else {
+            margins
+        }
+    }
+
+    /// Returns bounds covering a page on `side` inset by its margins, and by its unsafe area
+    /// wherever the unsafe area reaches further than the margins.
+    pub fn content_bounds(&self, side: PdfPageSide) -> PdfBounds {
+        let (margins, area) = (self.margins_for(side), self.unsafe_area);
+        self.bounds().with_padding(PdfPadding::new_f32(
+            margins.top.0.max(area.top.0),
+            margins.right.0.max(area.right.0),
+            margins.bottom.0.max(area.bottom.0),
+            margins.left.0.max(area.left.0),
+        ))
+    }
+}
+
+impl PdfConfigPage {
+    /// Returns the color to draw in place of `color`, converted to grayscale when configured.
+    pub fn draw_color(&self, color: PdfColor) -> PdfColor {
+        if self.grayscale {
+            color.into_grayscale()
+        } else {
+            color
+        }
+    }
+
+    /// Returns the size of a single pixel at the page's DPI.
+    fn pixel_size(&self) -> Mm {
+        Mm(25.4 / self.dpi)
+    }
+
+    /// Returns the thickness (in points) to use when stroking with `thickness`.
+    ///
+    /// 1. A thickness of zero (or less) is a hairline, the thinnest line the device can render,
+    ///    and is left as zero.
+    /// 2. Any other thickness is raised to the minimum outline thickness.
+    /// 3. When snapping to pixels, the thickness is rounded to a whole number of pixels (at
+    ///    least one).
+    pub fn stroke_thickness(&self, thickness: f32) -> f32 {
+        if thickness <= 0.0 {
+            return 0.0;
+        }
+
+        let thickness = thickness.max(self.min_outline_thickness);
+        if !self.snap_to_pixels || self.dpi <= 0.0 {
+            return thickness;
+        }
+
+        let px = (thickness * self.dpi / 72.0).round().max(1.0);
+        px * 72.0 / self.dpi
+    }
+
+    /// Returns `point` moved to the pixel grid for a stroke of `thickness` (in points) when
+    /// snapping to pixels, otherwise returning it unchanged.
+    ///
+    /// Strokes spanning an odd number of pixels are centered on a pixel while strokes spanning
+    /// an even number are centered between pixels, so the stroke covers whole pixels rather
+    /// than being blurred across partial ones.
+    pub fn snap_point(&self, point: PdfPoint, thickness: f32) -> PdfPoint {
+        if !self.snap_to_pixels || self.dpi <= 0.0 {
+            return point;
+        }
+
+        let px = self.pixel_size().0;
+        let width = (thickness * self.dpi / 72.0).round().max(1.0) as u32;
+        let offset = if width % 2 == 1 { 0.5 } else { 0.0 };
+        let snap = |x: Mm| Mm(((x.0 / px - offset).round() + offset) * px);
+
+        PdfPoint::new(snap(point.x), snap(point.y))
+    }
+
+    /// Returns `bounds` with both corners moved to the pixel grid for a stroke of `thickness`
+    /// (in points) when snapping to pixels, otherwise returning it unchanged.
+    pub fn snap_bounds(&self, bounds: PdfBounds, thickness: f32) -> PdfBounds {
+        PdfBounds::new(
+            self.snap_point(bounds.ll, thickness),
+            self.snap_point(bounds.ur, thickness),
+        )
+    }
 }
 
 impl<'lua> IntoLua<'lua> for PdfConfigPage {
@@ -72,22 +200,119 @@ impl<'lua> IntoLua<'lua> for PdfConfigPage {
         table.raw_set("font", self.font)?;
         table.raw_set("width", self.width.0)?;
         table.raw_set("height", self.height.0)?;
+        table.raw_set("margins", self.margins)?;
+        table.raw_set("mirror_margins", self.mirror_margins)?;
+        table.raw_set("unsafe_area", self.unsafe_area)?;
 
         // Defaults for page
+        table.raw_set("depth", self.depth)?;
         table.raw_set("font_size", self.font_size)?;
         table.raw_set("fill_color", self.fill_color)?;
         table.raw_set("outline_color", self.outline_color)?;
         table.raw_set("outline_thickness", self.outline_thickness)?;
+        table.raw_set("min_outline_thickness", self.min_outline_thickness)?;
         table.raw_set("line_dash_pattern", self.line_dash_pattern)?;
         table.raw_set("line_cap_style", self.line_cap_style)?;
         table.raw_set("line_join_style", self.line_join_style)?;
 
+        // Layers for page
+        table.raw_set("layers", self.layers)?;
+
+        // Output adjustments for page
+        table.raw_set("snap_to_pixels", self.snap_to_pixels)?;
+        table.raw_set("grayscale", self.grayscale)?;
+        table.raw_set("debug_grid", self.debug_grid)?;
+
         // Specialized helper functions
         metatable.raw_set(
             "bounds",
             lua.create_function(|_, this: PdfConfigPage| Ok(this.bounds()))?,
         )?;
 
+        metatable.raw_set(
+            "content_bounds",
+            lua.create_function(|_, (this, side): (PdfConfigPage, Option<PdfPageSide>)| {
+                Ok(this.content_bounds(side.unwrap_or_default()))
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "set_default_depth",
+            lua.create_function(|_, (this, depth): (LuaTable, i64)| this.raw_set("depth", depth))?,
+        )?;
+
+        // Creates (or updates) a layer that objects can be drawn within by name, returning it
+        metatable.raw_set(
+            "layer",
+            lua.create_function(|lua, (name, opts): (String, Option<LuaTable>)| {
+                let layers: LuaTable = lua
+                    .globals()
+                    .raw_get::<_, LuaTable>(GLOBAL_PDF_VAR_NAME)?
+                    .raw_get::<_, LuaTable>("page")?
+                    .raw_get("layers")?;
+
+                let existing: Vec<PdfLayer> =
+                    layers.clone().sequence_values().collect::<LuaResult<_>>()?;
+                let idx = existing.iter().position(|x| x.name == name);
+
+                let mut layer = match idx {
+                    Some(idx) => existing[idx].clone(),
+                    None => PdfLayer::new(name),
+                };
+
+                if let Some(opts) = opts {
+                    if let Some(optional) = opts.raw_get_ext("optional")? {
+                        layer.optional = optional;
+                    }
+
+                    if let Some(visible) = opts.raw_get_ext("visible")? {
+                        layer.visible = visible;
+                    }
+                }
+
+                // Lua sequences are 1-based, so an existing layer is replaced in place
+                let lua_idx = idx.unwrap_or(existing.len()) + 1;
+                layers.raw_set(lua_idx, layer.clone())?;
+
+                Ok(layer)
+            })?,
+        )?;
+
+        // Aliases of fields, such as of the size in millimeters, which read and write the aliased
+        // field so they always match even as scripts change the page
+        let aliases = lua.create_table()?;
+        aliases.raw_set("width_mm", "width")?;
+        aliases.raw_set("height_mm", "height")?;
+        metatable.raw_set("__aliases", aliases)?;
+
+        metatable.raw_set(
+            "__index",
+            lua.create_function(|_, (this, key): (LuaTable, LuaValue)| {
+                let metatable = this
+                    .get_metatable()
+                    .ok_or_else(|| LuaError::runtime("Page is missing its metatable"))?;
+                let aliases: LuaTable = metatable.raw_get("__aliases")?;
+                match aliases.raw_get::<_, Option<String>>(key.clone())? {
+                    Some(field) => this.raw_get::<_, LuaValue>(field),
+                    None => metatable.raw_get(key),
+                }
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "__newindex",
+            lua.create_function(|_, (this, key, value): (LuaTable, LuaValue, LuaValue)| {
+                let metatable = this
+                    .get_metatable()
+                    .ok_or_else(|| LuaError::runtime("Page is missing its metatable"))?;
+                let aliases: LuaTable = metatable.raw_get("__aliases")?;
+                match aliases.raw_get::<_, Option<String>>(key.clone())? {
+                    Some(field) => this.raw_set(field, value),
+                    None => this.raw_set(key, value),
+                }
+            })?,
+        )?;
+
         Ok(LuaValue::Table(table))
     }
 }
@@ -102,15 +327,38 @@ impl<'lua> FromLua<'lua> for PdfConfigPage {
                 font: table.raw_get_ext("font")?,
                 width: Mm(table.raw_get_ext("width")?),
                 height: Mm(table.raw_get_ext("height")?),
+                margins: table
+                    .raw_get_ext::<_, Option<PdfPadding>>("margins")?
+                    .unwrap_or_default(),
+                mirror_margins: table
+                    .raw_get_ext::<_, Option<bool>>("mirror_margins")?
+                    .unwrap_or_default(),
+                unsafe_area: table
+                    .raw_get_ext::<_, Option<PdfPadding>>("unsafe_area")?
+                    .unwrap_or_default(),
 
                 // Defaults for page
+                depth: table.raw_get_ext("depth")?,
                 font_size: table.raw_get_ext("font_size")?,
                 fill_color: table.raw_get_ext("fill_color")?,
                 outline_color: table.raw_get_ext("outline_color")?,
                 outline_thickness: table.raw_get_ext("outline_thickness")?,
+                min_outline_thickness: table.raw_get_ext("min_outline_thickness")?,
                 line_dash_pattern: table.raw_get_ext("line_dash_pattern")?,
                 line_cap_style: table.raw_get_ext("line_cap_style")?,
                 line_join_style: table.raw_get_ext("line_join_style")?,
+                // Defaults of objects are read from `pdf.defaults` by the config instead
+                defaults: PdfConfigDefaults::default(),
+
+                // Layers for page
+                layers: table
+                    .raw_get_ext::<_, Option<Vec<PdfLayer>>>("layers")?
+                    .unwrap_or_default(),
+
+                // Output adjustments for page
+                snap_to_pixels: table.raw_get_ext("snap_to_pixels")?,
+                grayscale: table.raw_get_ext("grayscale")?,
+                debug_grid: table.raw_get_ext("debug_grid")?,
             }),
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
@@ -130,21 +378,57 @@ impl PdfConfigPage {
         format!("{width}x{height}px")
     }
 
+    /// Paper sizes that can be referenced by name, alongside their width and height in
+    /// millimeters in portrait orientation.
+    pub const PAPER_SIZES: &'static [(&'static str, f32, f32)] = &[
+        ("a3", 297.0, 420.0),
+        ("a4", 210.0, 297.0),
+        ("a5", 148.0, 210.0),
+        ("a6", 105.0, 148.0),
+        ("letter", 215.9, 279.4),
+        ("legal", 215.9, 355.6),
+        ("tabloid", 279.4, 431.8),
+    ];
+
     /// Parse a string into dimensions `(width, height)`, supporting the following formats:
     ///
     /// 1. `{WIDTH}x{HEIGHT}in` for inches
     /// 2. `{WIDTH}x{HEIGHT}mm` for millimeters
     /// 3. `{WIDTH}x{HEIGHT}px` for pixels
+    /// 4. `{NAME}` for a paper size within [`PdfConfigPage::PAPER_SIZES`], such as `a4`
+    ///
+    /// Any of these can end with `-landscape` or `-portrait` to swap the width and height if
+    /// needed to match the orientation, such as `a4-landscape`.
     pub fn parse_size(s: &str, dpi: f32) -> anyhow::Result<(Mm, Mm)> {
+        let s = s.trim().to_lowercase();
+        if let Some(s) = s.strip_suffix("-landscape") {
+            let (width, height) = Self::parse_size(s, dpi)?;
+            return Ok((Mm(width.0.max(height.0)), Mm(width.0.min(height.0))));
+        } else if let Some(s) = s.strip_suffix("-portrait") {
+            let (width, height) = Self::parse_size(s, dpi)?;
+            return Ok((Mm(width.0.min(height.0)), Mm(width.0.max(height.0))));
+        }
+
+        if let Some((_, width, height)) = Self::PAPER_SIZES.iter().find(|(name, _, _)| *name == s) {
+            return Ok((Mm(*width), Mm(*height)));
+        }
+
         if s.len() < 2 {
             anyhow::bail!("Missing dimension units");
         }
 
-        let s = s.to_lowercase();
         let (s, units) = s.split_at(s.len() - 2);
-        let (width, height) = s.split_once('x').ok_or(anyhow::anyhow!(
-            "Missing 'x' separator between dimension width & height"
-        ))?;
+        let (width, height) = s.split_once('x').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Missing 'x' separator between dimension width & height, or unknown paper size \
+                 (expected one of {})",
+                Self::PAPER_SIZES
+                    .iter()
+                    .map(|(name, _, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
         let width: f32 = width
             .trim()
             .parse()
@@ -171,3 +455,226 @@ impl PdfConfigPage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    /// Creates a config with a DPI where a pixel is exactly 1mm, snapping enabled.
+    fn snapping_config() -> PdfConfigPage {
+        PdfConfigPage {
+            dpi: 25.4,
+            snap_to_pixels: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn content_bounds_should_inset_by_margins_or_unsafe_area_whichever_is_larger() {
+        let page = PdfConfigPage {
+            width: Mm(100.0),
+            height: Mm(200.0),
+            margins: PdfPadding::from_single_f32(5.0),
+            unsafe_area: PdfPadding::new_f32(20.0, 0.0, 0.0, 2.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            page.content_bounds(PdfPageSide::Right),
+            PdfBounds::from_coords_f32(5.0, 5.0, 95.0, 180.0)
+        );
+    }
+
+    #[test]
+    fn content_bounds_should_mirror_margins_on_left_pages_if_configured() {
+        let page = PdfConfigPage {
+            width: Mm(100.0),
+            height: Mm(200.0),
+            margins: PdfPadding::new_f32(0.0, 5.0, 0.0, 20.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            page.content_bounds(PdfPageSide::Left),
+            PdfBounds::from_coords_f32(20.0, 0.0, 95.0, 200.0)
+        );
+
+        let page = PdfConfigPage {
+            mirror_margins: true,
+            ..page
+        };
+
+        assert_eq!(
+            page.content_bounds(PdfPageSide::Right),
+            PdfBounds::from_coords_f32(20.0, 0.0, 95.0, 200.0)
+        );
+        assert_eq!(
+            page.content_bounds(PdfPageSide::Left),
+            PdfBounds::from_coords_f32(5.0, 0.0, 80.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn stroke_thickness_should_keep_hairlines() {
+        let page = PdfConfigPage {
+            min_outline_thickness: 0.5,
+            ..snapping_config()
+        };
+
+        assert_eq!(page.stroke_thickness(0.0), 0.0);
+        assert_eq!(page.stroke_thickness(-1.0), 0.0);
+    }
+
+    #[test]
+    fn stroke_thickness_should_clamp_to_minimum() {
+        let page = PdfConfigPage {
+            min_outline_thickness: 0.5,
+            ..Default::default()
+        };
+
+        assert_eq!(page.stroke_thickness(0.1), 0.5);
+        assert_eq!(page.stroke_thickness(0.7), 0.7);
+    }
+
+    #[test]
+    fn stroke_thickness_should_round_to_whole_pixels_when_snapping() {
+        let page = snapping_config();
+
+        // 1px at 25.4 DPI is 72 / 25.4 points
+        let px = 72.0 / 25.4;
+        assert_eq!(page.stroke_thickness(0.1), px);
+        assert_eq!(page.stroke_thickness(px * 1.4), px);
+        assert_eq!(page.stroke_thickness(px * 1.6), px * 2.0);
+
+        // Without snapping, thickness is unchanged
+        let page = PdfConfigPage::default();
+        assert_eq!(page.stroke_thickness(0.1), 0.1);
+    }
+
+    #[test]
+    fn snap_point_should_center_odd_strokes_on_pixels() {
+        let page = snapping_config();
+        let px = 72.0 / 25.4;
+
+        assert_eq!(
+            page.snap_point(PdfPoint::from_coords_f32(10.2, 3.9), px),
+            PdfPoint::from_coords_f32(10.5, 3.5)
+        );
+        assert_eq!(
+            page.snap_point(PdfPoint::from_coords_f32(10.2, 3.9), px * 3.0),
+            PdfPoint::from_coords_f32(10.5, 3.5)
+        );
+    }
+
+    #[test]
+    fn snap_point_should_center_even_strokes_between_pixels() {
+        let page = snapping_config();
+        let px = 72.0 / 25.4;
+
+        assert_eq!(
+            page.snap_point(PdfPoint::from_coords_f32(10.2, 3.6), px * 2.0),
+            PdfPoint::from_coords_f32(10.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn snap_point_should_do_nothing_if_not_snapping() {
+        let page = PdfConfigPage {
+            dpi: 25.4,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            page.snap_point(PdfPoint::from_coords_f32(10.2, 3.9), 1.0),
+            PdfPoint::from_coords_f32(10.2, 3.9)
+        );
+    }
+
+    #[test]
+    fn draw_color_should_convert_to_grayscale_if_configured() {
+        let color = PdfColor::from_rgb_u8(50, 150, 200);
+
+        let page = PdfConfigPage::default();
+        assert_eq!(page.draw_color(color), color);
+
+        let page = PdfConfigPage {
+            grayscale: true,
+            ..Default::default()
+        };
+        assert_eq!(page.draw_color(color).into_colors_u8(), (132, 132, 132));
+    }
+
+    #[test]
+    fn should_be_able_to_create_layers_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local grid = pdf.page.layer("grid", { optional = true })
+            pdf.utils.assert_deep_equal(grid, { name = "grid", optional = true, visible = true })
+
+            // Creating a layer with the same name updates the existing layer
+            pdf.page.layer("guides")
+            grid = pdf.page.layer("grid", { visible = false })
+            pdf.utils.assert_deep_equal(grid, { name = "grid", optional = true, visible = false })
+            pdf.utils.assert_deep_equal(pdf.page.layer("grid"), grid)
+
+            // Objects can target layers either by handle or by name
+            pdf.utils.assert_deep_equal(pdf.object.rect({ layer = grid }).layer, "grid")
+            pdf.utils.assert_deep_equal(pdf.object.rect({ layer = "guides" }).layer, "guides")
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        assert_eq!(
+            pdf.config.page.layers,
+            vec![
+                PdfLayer {
+                    name: String::from("grid"),
+                    optional: true,
+                    visible: false,
+                },
+                PdfLayer::new("guides"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_alias_size_in_millimeters_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            pdf.page.width = 100
+            assert(pdf.page.width_mm == 100, "width_mm did not follow width")
+            pdf.page.height_mm = 200
+            assert(pdf.page.height == 200, "height did not follow height_mm")
+            assert(rawget(pdf.page, "height_mm") == nil, "alias was stored")
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        assert_eq!(pdf.config.page.width, Mm(100.0));
+        assert_eq!(pdf.config.page.height, Mm(200.0));
+    }
+
+    #[test]
+    fn parse_size_should_support_paper_sizes_and_orientations() {
+        let parse = |s| PdfConfigPage::parse_size(s, 300.0).unwrap();
+
+        assert_eq!(parse("A4"), (Mm(210.0), Mm(297.0)));
+        assert_eq!(parse("a4-landscape"), (Mm(297.0), Mm(210.0)));
+        assert_eq!(parse("letter"), (Mm(215.9), Mm(279.4)));
+        assert_eq!(parse(" legal-portrait "), (Mm(215.9), Mm(355.6)));
+        assert_eq!(parse("100x50mm-portrait"), (Mm(50.0), Mm(100.0)));
+        assert_eq!(parse("2x1in"), (Mm(50.8), Mm(25.4)));
+
+        let err = PdfConfigPage::parse_size("b5", 300.0)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a4, a5"), "Unexpected error: {err}");
+    }
+}
@@ -0,0 +1,225 @@
+use crate::pdf::{PdfColor, PdfLuaTableExt};
+use crate::runtime::RuntimeFontId;
+use mlua::prelude::*;
+
+/// Text or image stamped onto pages while they are built, such as a "DRAFT" marking or a logo,
+/// centered on each page and drawn either beneath or above everything else.
+///
+/// Supports converting to & from a Lua table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfConfigWatermark {
+    /// Text to draw.
+    pub text: Option<String>,
+    /// Path to a PNG image to draw, used instead of text.
+    pub image: Option<String>,
+    /// Font of the text, defaulting to the fallback font.
+    pub font: Option<RuntimeFontId>,
+    /// Size of the text, defaulting to 72.
+    pub size: Option<f32>,
+    /// Color of the text, defaulting to the fill color of pages.
+    pub color: Option<PdfColor>,
+    /// Width of the image in millimeters, defaulting to half the width of the page.
+    pub width: Option<f32>,
+    /// Degrees to rotate counter-clockwise around the center of the page.
+    pub angle: f32,
+    /// Opacity between 0 (transparent) and 1 (opaque).
+    pub opacity: f32,
+    /// Pages to draw onto.
+    pub pages: PdfWatermarkPages,
+    /// If true, drawn above everything else on the page rather than beneath it.
+    pub above: bool,
+}
+
+impl Default for PdfConfigWatermark {
+    fn default() -> Self {
+        Self {
+            text: None,
+            image: None,
+            font: None,
+            size: None,
+            color: None,
+            width: None,
+            angle: 0.0,
+            opacity: 0.15,
+            pages: PdfWatermarkPages::default(),
+            above: false,
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfConfigWatermark {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("text", self.text)?;
+        table.raw_set("image", self.image)?;
+        table.raw_set("font", self.font)?;
+        table.raw_set("size", self.size)?;
+        table.raw_set("color", self.color)?;
+        table.raw_set("width", self.width)?;
+        table.raw_set("angle", self.angle)?;
+        table.raw_set("opacity", self.opacity)?;
+        table.raw_set("pages", self.pages)?;
+        table.raw_set("above", self.above)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfConfigWatermark {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => {
+                let default = Self::default();
+                let this = Self {
+                    text: table.raw_get_ext("text")?,
+                    image: table.raw_get_ext("image")?,
+                    font: table.raw_get_ext("font")?,
+                    size: table.raw_get_ext("size")?,
+                    color: table.raw_get_ext("color")?,
+                    width: table.raw_get_ext("width")?,
+                    angle: table
+                        .raw_get_ext::<_, Option<_>>("angle")?
+                        .unwrap_or(default.angle),
+                    opacity: table
+                        .raw_get_ext::<_, Option<f32>>("opacity")?
+                        .map(|x| x.clamp(0.0, 1.0))
+                        .unwrap_or(default.opacity),
+                    pages: table
+                        .raw_get_ext::<_, Option<_>>("pages")?
+                        .unwrap_or_default(),
+                    above: table
+                        .raw_get_ext::<_, Option<_>>("above")?
+                        .unwrap_or_default(),
+                };
+
+                if this.text.is_none() && this.image.is_none() {
+                    return Err(LuaError::FromLuaConversionError {
+                        from: "table",
+                        to: "pdf.config.watermark",
+                        message: Some(String::from("Missing text or image")),
+                    });
+                }
+
+                Ok(this)
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.config.watermark",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Pages that a watermark is drawn onto.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfWatermarkPages {
+    /// Every page.
+    #[default]
+    All,
+    /// Pages with odd numbers, which are on the right of a two-page spread.
+    Odd,
+    /// Pages with even numbers, which are on the left of a two-page spread.
+    Even,
+    /// Specific pages by number, starting at 1.
+    Numbers(Vec<u32>),
+}
+
+impl PdfWatermarkPages {
+    /// Returns true if page `number` (starting at 1) is included.
+    pub fn contains(&self, number: u32) -> bool {
+        match self {
+            Self::All => true,
+            Self::Odd => number % 2 == 1,
+            Self::Even => number > 0 && !Self::Odd.contains(number),
+            Self::Numbers(numbers) => numbers.contains(&number),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfWatermarkPages {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        match self {
+            Self::All => "all".into_lua(lua),
+            Self::Odd => "odd".into_lua(lua),
+            Self::Even => "even".into_lua(lua),
+            Self::Numbers(numbers) => numbers.into_lua(lua),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfWatermarkPages {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => match s.to_str()? {
+                "all" => Ok(Self::All),
+                "odd" => Ok(Self::Odd),
+                "even" => Ok(Self::Even),
+                s => Err(LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.config.watermark_pages",
+                    message: Some(format!("Unknown pages {s}, expected one of all, odd, even")),
+                }),
+            },
+            LuaValue::Table(_) => Ok(Self::Numbers(Vec::from_lua(value, lua)?)),
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.config.watermark_pages",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_convert_from_lua() {
+        let lua = Lua::new();
+
+        let watermark: PdfConfigWatermark = lua
+            .load(chunk!({ text = "DRAFT", angle = 45, opacity = 2, pages = { 1, 3 } }))
+            .eval()
+            .unwrap();
+        assert_eq!(watermark.text.as_deref(), Some("DRAFT"));
+        assert_eq!(watermark.angle, 45.0);
+        assert_eq!(watermark.opacity, 1.0);
+        assert_eq!(watermark.pages, PdfWatermarkPages::Numbers(vec![1, 3]));
+        assert!(!watermark.above);
+
+        let watermark: PdfConfigWatermark = lua
+            .load(chunk!({ image = "logo.png", pages = "even", above = true }))
+            .eval()
+            .unwrap();
+        assert_eq!(watermark.opacity, 0.15);
+        assert_eq!(watermark.pages, PdfWatermarkPages::Even);
+        assert!(watermark.above);
+
+        lua.load(chunk!({ angle = 45 }))
+            .eval::<PdfConfigWatermark>()
+            .expect_err("Unexpectedly converted watermark without text or image");
+        lua.load(chunk!({ text = "DRAFT", pages = "some" }))
+            .eval::<PdfConfigWatermark>()
+            .expect_err("Unexpectedly converted unknown pages");
+    }
+
+    #[test]
+    fn pages_should_contain_matching_page_numbers() {
+        assert!(PdfWatermarkPages::All.contains(2));
+        assert!(PdfWatermarkPages::Odd.contains(3));
+        assert!(!PdfWatermarkPages::Odd.contains(2));
+        assert!(PdfWatermarkPages::Even.contains(2));
+        assert!(!PdfWatermarkPages::Even.contains(3));
+        assert!(PdfWatermarkPages::Numbers(vec![1, 4]).contains(4));
+        assert!(!PdfWatermarkPages::Numbers(vec![1, 4]).contains(2));
+    }
+}
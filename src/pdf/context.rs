@@ -1,5 +1,7 @@
-use crate::pdf::PdfConfig;
-use crate::runtime::{RuntimeFontId, RuntimeFonts};
+use crate::pdf::{PdfColor, PdfConfig, PdfStyle};
+use crate::runtime::{RuntimeAlpha, RuntimeFontId, RuntimeFonts, RuntimeToc, RuntimeXObjects};
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::Object;
 use printpdf::PdfLayerReference;
 
 /// Context provided to a [`PdfObject`] in order to draw it.
@@ -9,4 +11,64 @@ pub struct PdfContext<'a> {
     pub layer: &'a PdfLayerReference,
     pub fonts: &'a RuntimeFonts,
     pub fallback_font_id: RuntimeFontId,
+    pub alpha: &'a RuntimeAlpha,
+    pub xobjects: &'a RuntimeXObjects,
+    pub toc: &'a RuntimeToc,
+
+    /// Style of the group being drawn, which takes precedence over the defaults of objects by
+    /// type and of pages.
+    pub style: PdfStyle,
+}
+
+impl PdfContext<'_> {
+    /// Returns the fill color of an object that does not specify its own, falling back to the
+    /// style, then `default` of the type of object, and then the fill color of pages.
+    pub fn fill_color(&self, default: Option<PdfColor>) -> PdfColor {
+        self.style
+            .fill_color
+            .or(default)
+            .unwrap_or(self.config.page.fill_color)
+    }
+
+    /// Returns the outline color of an object that does not specify its own, falling back to the
+    /// style, then `default` of the type of object, and then the outline color of pages.
+    pub fn outline_color(&self, default: Option<PdfColor>) -> PdfColor {
+        self.style
+            .outline_color
+            .or(default)
+            .unwrap_or(self.config.page.outline_color)
+    }
+
+    /// Returns the outline thickness of an object that does not specify its own, falling back to
+    /// the style, then `default` of the type of object, and then the outline thickness of pages.
+    pub fn outline_thickness(&self, default: Option<f32>) -> f32 {
+        self.style
+            .outline_thickness
+            .or(default)
+            .unwrap_or(self.config.page.outline_thickness)
+    }
+
+    /// Returns the font size of an object that does not specify its own, falling back to the
+    /// style, then `default` of the type of object, and then the font size of pages.
+    pub fn font_size(&self, default: Option<f32>) -> f32 {
+        self.style
+            .size
+            .or(default)
+            .unwrap_or(self.config.page.font_size)
+    }
+
+    /// Invokes `f` to draw with the opacity of `fill` and `stroke` (between 0 and 1), restoring
+    /// the previous opacity afterwards. Fully opaque drawing leaves the graphics state untouched.
+    pub fn with_alpha(&self, fill: f32, stroke: f32, f: impl FnOnce()) {
+        if fill >= 1.0 && stroke >= 1.0 {
+            return f();
+        }
+
+        let name = self.alpha.state_name(fill, stroke);
+        self.layer.save_graphics_state();
+        self.layer
+            .add_operation(Operation::new("gs", vec![Object::Name(name.into_bytes())]));
+        f();
+        self.layer.restore_graphics_state();
+    }
 }
@@ -0,0 +1,197 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::PdfObject;
+use mlua::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Maximum depth of nested tables within arguments that will be used to cache an object.
+const MAX_CACHE_KEY_DEPTH: usize = 16;
+
+/// Parametric object definition registered via `pdf.define`, which generates objects using a Lua
+/// function and caches each object by the arguments used to generate it.
+#[derive(Debug)]
+pub struct PdfDefinition {
+    /// Name of the definition, available as `pdf.object.{name}`.
+    pub name: String,
+
+    /// Function used to generate an object from arguments.
+    func: LuaRegistryKey,
+
+    /// Objects that have already been generated, keyed by their arguments.
+    cache: RefCell<HashMap<String, PdfObject>>,
+}
+
+impl PdfDefinition {
+    /// Creates a new definition named `name` that will use `func` to generate objects.
+    pub fn new(lua: &Lua, name: impl Into<String>, func: LuaFunction) -> LuaResult<Self> {
+        Ok(Self {
+            name: name.into(),
+            func: lua.create_registry_value(func)?,
+            cache: Default::default(),
+        })
+    }
+
+    /// Generates an object using `args`, returning a cached copy when the definition has already
+    /// been used with the same arguments.
+    ///
+    /// Arguments that cannot be compared (e.g. containing functions) are never cached.
+    pub fn instantiate<'lua>(&self, lua: &'lua Lua, args: LuaValue<'lua>) -> LuaResult<PdfObject> {
+        let key = cache_key(&args, 0);
+        if let Some(obj) = key
+            .as_ref()
+            .and_then(|key| self.cache.borrow().get(key).cloned())
+        {
+            return Ok(obj);
+        }
+
+        let func: LuaFunction = lua.registry_value(&self.func)?;
+        let obj: PdfObject = func.call(args).map_err(|x| {
+            LuaError::runtime(format!("Failed to instantiate '{}': {x}", self.name))
+        })?;
+
+        if let Some(key) = key {
+            self.cache.borrow_mut().insert(key, obj.clone());
+        }
+
+        Ok(obj)
+    }
+
+    /// Registers a new definition as `pdf.object.{name}`, returning the function used to
+    /// instantiate the definition.
+    pub(crate) fn register<'lua>(
+        lua: &'lua Lua,
+        name: String,
+        func: LuaFunction<'lua>,
+    ) -> LuaResult<LuaFunction<'lua>> {
+        let objects: LuaTable = lua
+            .globals()
+            .raw_get::<_, LuaTable>(GLOBAL_PDF_VAR_NAME)?
+            .raw_get("object")?;
+
+        // Prevent replacing an existing object (or definition) as that would be confusing
+        if !objects.get::<_, LuaValue>(name.as_str())?.is_nil() {
+            return Err(LuaError::runtime(format!(
+                "pdf.object.{name} is already defined"
+            )));
+        }
+
+        let definition = Rc::new(Self::new(lua, name.as_str(), func)?);
+        let instantiate = lua.create_function(move |lua, args: LuaValue| {
+            definition.instantiate(lua, args)?.into_lua(lua)
+        })?;
+
+        objects.raw_set(name, instantiate.clone())?;
+        Ok(instantiate)
+    }
+}
+
+/// Produces a string uniquely representing `value`, or none if the value cannot be represented.
+fn cache_key(value: &LuaValue, depth: usize) -> Option<String> {
+    match value {
+        LuaValue::Nil => Some(String::from("nil")),
+        LuaValue::Boolean(x) => Some(x.to_string()),
+        LuaValue::Integer(x) => Some(format!("{}", *x as f64)),
+        LuaValue::Number(x) => Some(format!("{x}")),
+        LuaValue::String(x) => Some(format!("{:?}", x.to_string_lossy())),
+        LuaValue::Table(tbl) if depth < MAX_CACHE_KEY_DEPTH => {
+            let mut entries = Vec::new();
+            for pair in tbl.clone().pairs::<LuaValue, LuaValue>() {
+                let (key, value) = pair.ok()?;
+                entries.push(format!(
+                    "{}={}",
+                    cache_key(&key, depth + 1)?,
+                    cache_key(&value, depth + 1)?
+                ));
+            }
+
+            // Order of pairs is not guaranteed, so sort to ensure the same key for equal tables
+            entries.sort();
+            Some(format!("{{{}}}", entries.join(",")))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_define_and_instantiate_objects_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local calls = 0
+            local labelled_box = pdf.define("labelled_box", function(args)
+                calls = calls + 1
+                return pdf.object.rect({ 0, 0, args.size, args.size, tag = args.tag })
+            end)
+
+            // Definition is available both as the returned function and within pdf.object
+            local a = labelled_box({ size = 5, tag = "a" })
+            local b = pdf.object.labelled_box({ tag = "a", size = 5 })
+            pdf.utils.assert_deep_equal(a, b)
+            pdf.utils.assert_deep_equal(a:bounds(), { ll = { x = 0, y = 0 }, ur = { x = 5, y = 5 } })
+
+            // Same arguments should reuse the cached object
+            assert(calls == 1, "expected 1 call, got " .. calls)
+
+            // Different arguments should generate a new object
+            labelled_box({ size = 6, tag = "a" })
+            assert(calls == 2, "expected 2 calls, got " .. calls)
+
+            // Arguments with functions are never cached
+            labelled_box({ size = 5, tag = "a", f = function() end })
+            labelled_box({ size = 5, tag = "a", f = function() end })
+            assert(calls == 4, "expected 4 calls, got " .. calls)
+
+            // Instances are copies, so changing one does not impact the cache
+            a.tag = "changed"
+            assert(labelled_box({ size = 5, tag = "a" }).tag == "a", "cache was modified")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_fail_to_define_an_object_that_already_exists() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk!(pdf.define("rect", function() end)))
+            .exec()
+            .expect_err("Unexpectedly redefined rect");
+
+        lua.load(chunk! {
+            pdf.define("box", function() return pdf.object.rect({}) end)
+            pdf.define("box", function() return pdf.object.rect({}) end)
+        })
+        .exec()
+        .expect_err("Unexpectedly redefined box");
+    }
+
+    #[test]
+    fn cache_key_should_be_the_same_for_equal_tables() {
+        let lua = Lua::new();
+
+        let a = lua
+            .load(chunk!({ x = 1, y = { 2, 3 }, z = "abc" }))
+            .eval::<LuaValue>()
+            .unwrap();
+        let b = lua
+            .load(chunk!({ z = "abc", y = { 2, 3 }, x = 1.0 }))
+            .eval::<LuaValue>()
+            .unwrap();
+        let c = lua
+            .load(chunk!({ x = 1, y = { 3, 2 }, z = "abc" }))
+            .eval::<LuaValue>()
+            .unwrap();
+
+        assert_eq!(cache_key(&a, 0), cache_key(&b, 0));
+        assert_ne!(cache_key(&a, 0), cache_key(&c, 0));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{PdfBounds, PdfLuaExt, PdfPadding};
+use mlua::prelude::*;
+use printpdf::{Mm, Px};
+
+/// Screen of a device that PDFs are made for, alongside the regions of the screen obscured by
+/// the device's own interface such as toolbars.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfDeviceProfile {
+    /// Name of the device, e.g. `supernote`.
+    pub name: &'static str,
+
+    /// Width of the device's screen in pixels.
+    pub width: u32,
+
+    /// Height of the device's screen in pixels.
+    pub height: u32,
+
+    /// DPI of the device's screen.
+    pub dpi: f32,
+
+    /// Area (in millimeters) along each edge of the screen covered by the device's toolbars.
+    pub unsafe_area: PdfPadding,
+}
+
+impl PdfDeviceProfile {
+    /// Profiles of supported devices.
+    pub const BUILTIN: &'static [Self] = &[
+        // Supernote A6 X2 Nomad, whose toolbar docks along the left edge
+        Self {
+            name: "supernote",
+            width: 1404,
+            height: 1872,
+            dpi: 300.0,
+            unsafe_area: PdfPadding::new_f32(0.0, 0.0, 0.0, 10.0),
+        },
+        // Supernote A5 X, whose toolbar docks along the left edge
+        Self {
+            name: "supernote-a5x",
+            width: 1404,
+            height: 1872,
+            dpi: 226.0,
+            unsafe_area: PdfPadding::new_f32(0.0, 0.0, 0.0, 12.0),
+        },
+        // Supernote A5 X2 Manta, whose toolbar docks along the left edge
+        Self {
+            name: "supernote-a5x2",
+            width: 1920,
+            height: 2560,
+            dpi: 300.0,
+            unsafe_area: PdfPadding::new_f32(0.0, 0.0, 0.0, 12.0),
+        },
+        // reMarkable 2, whose menu opens along the left edge
+        Self {
+            name: "remarkable2",
+            width: 1404,
+            height: 1872,
+            dpi: 226.0,
+            unsafe_area: PdfPadding::new_f32(0.0, 0.0, 0.0, 14.0),
+        },
+        // Boox Note Air, whose status bar runs along the top edge
+        Self {
+            name: "boox-note-air",
+            width: 1404,
+            height: 1872,
+            dpi: 227.0,
+            unsafe_area: PdfPadding::new_f32(6.0, 0.0, 0.0, 0.0),
+        },
+    ];
+
+    /// Returns the profile of the supported device named `name`, ignoring case.
+    pub fn find(name: &str) -> Option<&'static Self> {
+        Self::BUILTIN
+            .iter()
+            .find(|x| x.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the width and height of the device's screen in millimeters.
+    pub fn size(&self) -> (Mm, Mm) {
+        (
+            Px(self.width as usize).into_pt(self.dpi).into(),
+            Px(self.height as usize).into_pt(self.dpi).into(),
+        )
+    }
+
+    /// Returns bounds covering the entire screen of the device.
+    pub fn bounds(&self) -> PdfBounds {
+        let (width, height) = self.size();
+        PdfBounds::from_coords(Mm(0.0), Mm(0.0), width, height)
+    }
+
+    /// Returns bounds covering the screen of the device, excluding the area covered by its
+    /// toolbars.
+    pub fn safe_bounds(&self) -> PdfBounds {
+        self.bounds().with_padding(self.unsafe_area)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfDeviceProfile {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+        let (width, height) = self.size();
+
+        table.raw_set("name", self.name)?;
+        table.raw_set("width", width.0)?;
+        table.raw_set("height", height.0)?;
+        table.raw_set("dpi", self.dpi)?;
+        table.raw_set("unsafe_area", self.unsafe_area)?;
+
+        let bounds = self.bounds();
+        metatable.raw_set(
+            "bounds",
+            lua.create_function(move |_, _: LuaValue| Ok(bounds))?,
+        )?;
+
+        let safe_bounds = self.safe_bounds();
+        metatable.raw_set(
+            "safe_bounds",
+            lua.create_function(move |_, _: LuaValue| Ok(safe_bounds))?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+/// Collection of functions to look up the profiles of devices.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfDevice;
+
+impl PdfDevice {
+    /// Returns the profile of the device named `name`, failing if the device is not supported.
+    pub fn get(name: &str) -> LuaResult<PdfDeviceProfile> {
+        PdfDeviceProfile::find(name).cloned().ok_or_else(|| {
+            let names: Vec<&str> = PdfDeviceProfile::BUILTIN.iter().map(|x| x.name).collect();
+            LuaError::runtime(format!(
+                "Unknown device {name}, expected one of {}",
+                names.join(", ")
+            ))
+        })
+    }
+
+    /// Configures pages to match the screen of the device named `name`, setting the DPI, width,
+    /// height, and unsafe area of `pdf.page`, returning the profile of the device.
+    pub fn apply(lua: &Lua, name: &str) -> LuaResult<PdfDeviceProfile> {
+        let device = Self::get(name)?;
+        let (width, height) = device.size();
+
+        let page: LuaTable = lua
+            .globals()
+            .raw_get::<_, LuaTable>(GLOBAL_PDF_VAR_NAME)?
+            .raw_get("page")?;
+        page.raw_set("dpi", device.dpi)?;
+        page.raw_set("width", width.0)?;
+        page.raw_set("height", height.0)?;
+        page.raw_set("unsafe_area", device.unsafe_area)?;
+
+        Ok(device)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfDevice {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        metatable.raw_set(
+            "get",
+            lua.create_function(|_, name: String| PdfDevice::get(&name))?,
+        )?;
+
+        metatable.raw_set(
+            "apply",
+            lua.create_function(|lua, name: String| PdfDevice::apply(lua, &name))?,
+        )?;
+
+        // Function to return the profiles of all supported devices.
+        metatable.raw_set(
+            "list",
+            lua.create_function(|_, ()| Ok(PdfDeviceProfile::BUILTIN.to_vec()))?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{Pdf, PdfConfig};
+    use mlua::chunk;
+
+    #[test]
+    fn safe_bounds_should_exclude_unsafe_area_of_device() {
+        let device = PdfDeviceProfile {
+            name: "test",
+            width: 254,
+            height: 508,
+            dpi: 25.4,
+            unsafe_area: PdfPadding::new_f32(10.0, 0.0, 0.0, 20.0),
+        };
+
+        assert_eq!(
+            device.bounds().to_precision(3),
+            PdfBounds::from_coords_f32(0.0, 0.0, 254.0, 508.0)
+        );
+        assert_eq!(
+            device.safe_bounds().to_precision(3),
+            PdfBounds::from_coords_f32(20.0, 0.0, 254.0, 498.0)
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_apply_device_to_pages_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local device = pdf.device.apply("reMarkable2")
+            pdf.utils.assert_deep_equal(device.name, "remarkable2")
+            pdf.utils.assert_deep_equal(pdf.page.dpi, 226)
+            pdf.utils.assert_deep_equal(pdf.page.width, device.width)
+            pdf.utils.assert_deep_equal(pdf.page.unsafe_area, device.unsafe_area)
+            pdf.utils.assert_deep_equal(pdf.page:content_bounds(), device:safe_bounds())
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        lua.load(chunk!(pdf.device.get("kindle")))
+            .exec()
+            .expect_err("Unexpectedly found unknown device");
+
+        let config: PdfConfig = lua.globals().raw_get("pdf").unwrap();
+        assert_eq!(config.page.unsafe_area.left, Mm(14.0));
+    }
+}
@@ -0,0 +1,413 @@
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfLineCapStyle, PdfLineJoinStyle, PdfLink, PdfLuaTableExt, PdfObject,
+    PdfObjectCircle, PdfObjectGroup, PdfObjectLine, PdfObjectShape, PdfPaintMode, PdfPoint,
+};
+use mlua::prelude::*;
+use printpdf::{Mm, Pt};
+
+/// Builtin symbol drawn as vector paths, such as checkboxes, stars, and arrows, so that planners
+/// do not need an icon font.
+///
+/// Icons are designed within a unit square and scaled to fit the largest square centered within
+/// the bounds they are drawn into.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PdfIcon {
+    Checkbox,
+    CheckboxChecked,
+    CheckboxCrossed,
+    Check,
+    Cross,
+    Plus,
+    Minus,
+    Circle,
+    Dot,
+    Star,
+    StarOutline,
+    Heart,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ChevronUp,
+    ChevronDown,
+    ChevronLeft,
+    ChevronRight,
+    Phone,
+    Mail,
+    Calendar,
+    Clock,
+}
+
+/// Options used when drawing an icon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfIconOpts {
+    /// Color of the icon, defaulting to the outline color of the page for strokes and the fill
+    /// color of the page for filled parts.
+    pub color: Option<PdfColor>,
+    /// Thickness of strokes in points, defaulting to 8% of the size of the icon.
+    pub thickness: Option<f32>,
+    pub depth: Option<i64>,
+    pub link: Option<PdfLink>,
+}
+
+impl PdfIcon {
+    /// Every icon alongside its name.
+    pub const ALL: [(&'static str, Self); 24] = [
+        ("checkbox", Self::Checkbox),
+        ("checkbox_checked", Self::CheckboxChecked),
+        ("checkbox_crossed", Self::CheckboxCrossed),
+        ("check", Self::Check),
+        ("cross", Self::Cross),
+        ("plus", Self::Plus),
+        ("minus", Self::Minus),
+        ("circle", Self::Circle),
+        ("dot", Self::Dot),
+        ("star", Self::Star),
+        ("star_outline", Self::StarOutline),
+        ("heart", Self::Heart),
+        ("arrow_up", Self::ArrowUp),
+        ("arrow_down", Self::ArrowDown),
+        ("arrow_left", Self::ArrowLeft),
+        ("arrow_right", Self::ArrowRight),
+        ("chevron_up", Self::ChevronUp),
+        ("chevron_down", Self::ChevronDown),
+        ("chevron_left", Self::ChevronLeft),
+        ("chevron_right", Self::ChevronRight),
+        ("phone", Self::Phone),
+        ("mail", Self::Mail),
+        ("calendar", Self::Calendar),
+        ("clock", Self::Clock),
+    ];
+
+    /// Returns the icon with the specified `name`, if it exists.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find_map(|(x, icon)| if *x == name { Some(*icon) } else { None })
+    }
+
+    /// Returns the name of the icon.
+    pub fn to_name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find_map(|(name, icon)| if *icon == self { Some(*name) } else { None })
+            .unwrap_or_default()
+    }
+
+    /// Returns a group of the objects that draw the icon within `bounds`.
+    pub fn to_group(self, bounds: PdfBounds, opts: PdfIconOpts) -> PdfObjectGroup {
+        let side = bounds.width().0.min(bounds.height().0);
+        let origin = PdfPoint::from_coords_f32(
+            bounds.ll.x.0 + (bounds.width().0 - side) / 2.0,
+            bounds.ll.y.0 + (bounds.height().0 - side) / 2.0,
+        );
+        let builder = PdfIconBuilder {
+            origin,
+            side,
+            thickness: opts
+                .thickness
+                .unwrap_or_else(|| Pt::from(Mm(side * 0.08)).0),
+            opts: &opts,
+        };
+
+        PdfObjectGroup {
+            objects: builder.build(self),
+            link: opts.link.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts the unit square of icons into objects within the bounds of an icon.
+struct PdfIconBuilder<'a> {
+    origin: PdfPoint,
+    side: f32,
+    thickness: f32,
+    opts: &'a PdfIconOpts,
+}
+
+impl PdfIconBuilder<'_> {
+    /// Returns the objects that draw `icon`.
+    fn build(&self, icon: PdfIcon) -> Vec<PdfObject> {
+        let center = (0.5, 0.5);
+        let checkbox = || self.outline(&[(0.1, 0.1), (0.9, 0.1), (0.9, 0.9), (0.1, 0.9)]);
+        let arrow = [
+            (0.1, 0.4),
+            (0.55, 0.4),
+            (0.55, 0.18),
+            (0.9, 0.5),
+            (0.55, 0.82),
+            (0.55, 0.6),
+            (0.1, 0.6),
+        ];
+        let chevron = [(0.35, 0.15), (0.7, 0.5), (0.35, 0.85)];
+        let star = || {
+            PdfObjectShape::star_points(PdfPoint::from_coords_f32(0.5, 0.53), 0.45, 0.19, 5, 0.0)
+                .into_iter()
+                .map(|p| (p.x.0, p.y.0))
+                .collect::<Vec<_>>()
+        };
+
+        match icon {
+            PdfIcon::Checkbox => vec![checkbox()],
+            PdfIcon::CheckboxChecked => vec![
+                checkbox(),
+                self.line(&[(0.27, 0.5), (0.43, 0.32), (0.74, 0.7)]),
+            ],
+            PdfIcon::CheckboxCrossed => vec![
+                checkbox(),
+                self.line(&[(0.3, 0.3), (0.7, 0.7)]),
+                self.line(&[(0.3, 0.7), (0.7, 0.3)]),
+            ],
+            PdfIcon::Check => vec![self.line(&[(0.12, 0.5), (0.38, 0.2), (0.88, 0.8)])],
+            PdfIcon::Cross => vec![
+                self.line(&[(0.2, 0.2), (0.8, 0.8)]),
+                self.line(&[(0.2, 0.8), (0.8, 0.2)]),
+            ],
+            PdfIcon::Plus => vec![
+                self.line(&[(0.5, 0.15), (0.5, 0.85)]),
+                self.line(&[(0.15, 0.5), (0.85, 0.5)]),
+            ],
+            PdfIcon::Minus => vec![self.line(&[(0.15, 0.5), (0.85, 0.5)])],
+            PdfIcon::Circle => vec![self.circle(center, 0.4, false)],
+            PdfIcon::Dot => vec![self.circle(center, 0.2, true)],
+            PdfIcon::Star => vec![self.fill(&star())],
+            PdfIcon::StarOutline => vec![self.outline(&star())],
+            PdfIcon::Heart => {
+                // Parametric heart spanning roughly -16 to 16 horizontally and -17 to 12 vertically
+                let points: Vec<_> = (0..48)
+                    .map(|i| {
+                        let t = std::f32::consts::TAU * i as f32 / 48.0;
+                        let x = 16.0 * t.sin().powi(3);
+                        let y = 13.0 * t.cos()
+                            - 5.0 * (2.0 * t).cos()
+                            - 2.0 * (3.0 * t).cos()
+                            - (4.0 * t).cos();
+                        (0.5 + x * 0.025, 0.5 + (y + 2.5) * 0.025)
+                    })
+                    .collect();
+                vec![self.fill(&points)]
+            }
+            PdfIcon::ArrowRight => vec![self.fill(&arrow)],
+            PdfIcon::ArrowUp => vec![self.fill(&rotate(&arrow, 90.0))],
+            PdfIcon::ArrowLeft => vec![self.fill(&rotate(&arrow, 180.0))],
+            PdfIcon::ArrowDown => vec![self.fill(&rotate(&arrow, 270.0))],
+            PdfIcon::ChevronRight => vec![self.line(&chevron)],
+            PdfIcon::ChevronUp => vec![self.line(&rotate(&chevron, 90.0))],
+            PdfIcon::ChevronLeft => vec![self.line(&rotate(&chevron, 180.0))],
+            PdfIcon::ChevronDown => vec![self.line(&rotate(&chevron, 270.0))],
+            PdfIcon::Phone => vec![
+                self.outline(&[(0.28, 0.06), (0.72, 0.06), (0.72, 0.94), (0.28, 0.94)]),
+                self.line(&[(0.44, 0.82), (0.56, 0.82)]),
+                self.circle((0.5, 0.17), 0.04, true),
+            ],
+            PdfIcon::Mail => vec![
+                self.outline(&[(0.08, 0.2), (0.92, 0.2), (0.92, 0.8), (0.08, 0.8)]),
+                self.line(&[(0.08, 0.8), (0.5, 0.42), (0.92, 0.8)]),
+            ],
+            PdfIcon::Calendar => vec![
+                self.outline(&[(0.12, 0.08), (0.88, 0.08), (0.88, 0.82), (0.12, 0.82)]),
+                self.line(&[(0.12, 0.62), (0.88, 0.62)]),
+                self.line(&[(0.32, 0.72), (0.32, 0.94)]),
+                self.line(&[(0.68, 0.72), (0.68, 0.94)]),
+            ],
+            PdfIcon::Clock => vec![
+                self.circle(center, 0.42, false),
+                self.line(&[(0.5, 0.76), (0.5, 0.5), (0.68, 0.38)]),
+            ],
+        }
+    }
+
+    /// Converts a point within the unit square to a point within the bounds of the icon.
+    fn point(&self, (x, y): (f32, f32)) -> PdfPoint {
+        PdfPoint::from_coords_f32(
+            self.origin.x.0 + x * self.side,
+            self.origin.y.0 + y * self.side,
+        )
+    }
+
+    /// Returns an open line through `points`.
+    fn line(&self, points: &[(f32, f32)]) -> PdfObject {
+        PdfObject::Line(PdfObjectLine {
+            points: points.iter().map(|p| self.point(*p)).collect(),
+            depth: self.opts.depth,
+            color: self.opts.color,
+            thickness: Some(self.thickness),
+            cap_style: Some(PdfLineCapStyle::round()),
+            join_style: Some(PdfLineJoinStyle::round()),
+            ..Default::default()
+        })
+    }
+
+    /// Returns a closed outline through `points`.
+    fn outline(&self, points: &[(f32, f32)]) -> PdfObject {
+        PdfObject::Shape(PdfObjectShape {
+            points: points.iter().map(|p| self.point(*p)).collect(),
+            depth: self.opts.depth,
+            outline_color: self.opts.color,
+            outline_thickness: Some(self.thickness),
+            mode: Some(PdfPaintMode::stroke()),
+            join_style: Some(PdfLineJoinStyle::round()),
+            ..Default::default()
+        })
+    }
+
+    /// Returns a filled shape through `points`.
+    fn fill(&self, points: &[(f32, f32)]) -> PdfObject {
+        PdfObject::Shape(PdfObjectShape {
+            points: points.iter().map(|p| self.point(*p)).collect(),
+            depth: self.opts.depth,
+            fill_color: self.opts.color,
+            mode: Some(PdfPaintMode::fill()),
+            ..Default::default()
+        })
+    }
+
+    /// Returns a circle at `center` with `radius`, either filled or outlined.
+    fn circle(&self, center: (f32, f32), radius: f32, filled: bool) -> PdfObject {
+        PdfObject::Circle(PdfObjectCircle {
+            center: self.point(center),
+            radius: Mm(radius * self.side),
+            depth: self.opts.depth,
+            fill_color: if filled { self.opts.color } else { None },
+            outline_color: if filled { None } else { self.opts.color },
+            outline_thickness: Some(self.thickness),
+            mode: Some(if filled {
+                PdfPaintMode::fill()
+            } else {
+                PdfPaintMode::stroke()
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+/// Rotates `points` within the unit square counter-clockwise around its center by `degrees`.
+fn rotate(points: &[(f32, f32)], degrees: f32) -> Vec<(f32, f32)> {
+    let center = PdfPoint::from_coords_f32(0.5, 0.5);
+    points
+        .iter()
+        .map(|(x, y)| {
+            PdfPoint::from_coords_f32(*x, *y)
+                .rotate_around(center, degrees)
+                .to_coords_f32()
+        })
+        .collect()
+}
+
+impl<'lua> IntoLua<'lua> for PdfIcon {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        lua.create_string(self.to_name()).map(LuaValue::String)
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfIcon {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => {
+                let name = s.to_string_lossy();
+                Self::from_name(name.as_ref()).ok_or_else(|| LuaError::FromLuaConversionError {
+                    from,
+                    to: "pdf.common.icon",
+                    message: Some(format!("unknown icon: {name}")),
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.icon",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfIconOpts {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Nil => Ok(Self::default()),
+            LuaValue::Table(table) => Ok(Self {
+                color: table.raw_get_ext("color")?,
+                thickness: table.raw_get_ext("thickness")?,
+                depth: table.raw_get_ext("depth")?,
+                link: table.raw_get_ext("link")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.icon_opts",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn names_should_map_to_and_from_icons() {
+        for (name, icon) in PdfIcon::ALL {
+            assert_eq!(PdfIcon::from_name(name), Some(icon));
+            assert_eq!(icon.to_name(), name);
+        }
+        assert_eq!(PdfIcon::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn to_group_should_fit_icon_within_centered_square_of_bounds() {
+        let bounds = PdfBounds::from_coords_f32(10.0, 20.0, 50.0, 40.0);
+        for (name, icon) in PdfIcon::ALL {
+            let group = icon.to_group(bounds, PdfIconOpts::default());
+            assert!(!group.objects.is_empty(), "{name} has no objects");
+
+            // Square of 20mm is centered horizontally within the bounds
+            for obj in group.objects.iter() {
+                let points = match obj {
+                    PdfObject::Line(x) => x.points.clone(),
+                    PdfObject::Shape(x) => x.points.clone(),
+                    PdfObject::Circle(x) => x.iter_points().collect(),
+                    _ => unreachable!(),
+                };
+                for p in points {
+                    let (x, y) = p.to_coords_f32();
+                    assert!((19.99..=40.01).contains(&x), "{name} has x of {x}");
+                    assert!((19.99..=40.01).contains(&y), "{name} has y of {y}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_create_icons_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let group: PdfObjectGroup = lua
+            .load(chunk! {
+                return pdf.object.icon("checkbox_checked", { 0, 0, 10, 10 }, {
+                    color = "#ff0000",
+                    thickness = 2,
+                })
+            })
+            .eval()
+            .unwrap();
+        assert_eq!(group.objects.len(), 2);
+        match &group.objects[1] {
+            PdfObject::Line(line) => {
+                assert_eq!(line.thickness, Some(2.0));
+                assert_eq!(line.color, Some(PdfColor::from_rgb_u8(255, 0, 0)));
+            }
+            obj => panic!("Unexpected object {obj:?}"),
+        }
+
+        lua.load(chunk!(pdf.object.icon("unknown", { 0, 0, 10, 10 })))
+            .exec()
+            .expect_err("Unexpectedly created unknown icon");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::pdf::PdfLuaExt;
+use crate::runtime::{RuntimeFiles, RuntimeImports};
+use mlua::prelude::*;
+
+/// Collection of functions to import pages of existing PDFs, such as templates designed in other
+/// tools, to draw as the backgrounds of pages.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfImport;
+
+impl PdfImport {
+    /// Imports page `number` (starting at 1) of the PDF at `path`, which must be within an allowed
+    /// directory, returning a table describing the imported page.
+    ///
+    /// The table contains the `id` of the import alongside the `path` and `page` it came from and
+    /// the `width` and `height` of the page in millimeters.
+    pub fn page<'lua>(lua: &'lua Lua, path: String, number: u32) -> LuaResult<LuaTable<'lua>> {
+        let resolved = if let Some(files) = lua.app_data_ref::<RuntimeFiles>() {
+            files
+                .resolve(&path)
+                .map_err(|x| LuaError::runtime(format!("Failed to read '{path}': {x}")))?
+        } else {
+            return Err(LuaError::runtime("Runtime files are missing"));
+        };
+
+        let (id, (width, height)) = if let Some(mut imports) = lua.app_data_mut::<RuntimeImports>()
+        {
+            let id = imports.add(resolved, number).map_err(|x| {
+                LuaError::runtime(format!("Failed to import page {number} of '{path}': {x:#}"))
+            })?;
+            let size = imports.get(id).map(|x| x.size()).unwrap_or_default();
+            (id, size)
+        } else {
+            return Err(LuaError::runtime("Runtime imports are missing"));
+        };
+
+        let table = lua.create_table()?;
+        table.raw_set("id", id)?;
+        table.raw_set("path", path)?;
+        table.raw_set("page", number)?;
+        table.raw_set("width", width.0)?;
+        table.raw_set("height", height.0)?;
+        Ok(table)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfImport {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to import a page of an existing PDF, defaulting to the first page
+        metatable.raw_set(
+            "page",
+            lua.create_function(|lua, (path, number): (String, Option<u32>)| {
+                PdfImport::page(lua, path, number.unwrap_or(1))
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use crate::runtime::RuntimePage;
+    use mlua::chunk;
+    use printpdf::{Mm, PdfDocument};
+
+    #[test]
+    fn should_be_able_to_import_pages_as_backgrounds_in_lua() {
+        let dir = std::env::temp_dir().join(format!("makepdf-import-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let doc = PdfDocument::empty("base");
+        doc.add_page(Mm(100.0), Mm(200.0), "page");
+        std::fs::write(dir.join("base.pdf"), doc.save_to_bytes().unwrap()).unwrap();
+        let path = dir.join("base.pdf").to_string_lossy().to_string();
+
+        let lua = Lua::new();
+        let mut files = RuntimeFiles::new();
+        files.allow(&dir).unwrap();
+        lua.set_app_data(files);
+        lua.set_app_data(RuntimeImports::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let page = RuntimePage::new("page");
+        lua.globals().raw_set("page", page.clone()).unwrap();
+
+        let path_copy = path.clone();
+        lua.load(chunk! {
+            local base = pdf.import.page($path_copy)
+            assert(base.id == 0, "unexpected id " .. tostring(base.id))
+            assert(base.page == 1, "unexpected page " .. tostring(base.page))
+            assert(math.abs(base.width - 100) < 0.01, "unexpected width " .. tostring(base.width))
+            assert(math.abs(base.height - 200) < 0.01, "unexpected height " .. tostring(base.height))
+
+            // Importing the same page again reuses it
+            assert(pdf.import.page($path_copy, 1).id == 0, "page imported twice")
+
+            page:set_background(base)
+        })
+        .exec()
+        .expect("Assertion failed");
+        assert_eq!(page.background(), Some(0));
+
+        // Pages must exist within the PDF
+        lua.load(chunk!(pdf.import.page($path, 2)))
+            .exec()
+            .expect_err("Unexpectedly imported missing page");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
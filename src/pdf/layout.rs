@@ -0,0 +1,620 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{
+    PdfAlign, PdfBounds, PdfConfig, PdfCoord, PdfHorizontalAlign, PdfLuaExt, PdfLuaTableExt,
+    PdfObject, PdfObjectGroup, PdfPadding, PdfPages, PdfPoint, PdfVerticalAlign,
+};
+use crate::runtime::{RuntimePage, RuntimePageId, RuntimePages};
+use mlua::prelude::*;
+use printpdf::Mm;
+
+/// Collection of layout functions, which position objects within the cells of a container.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfLayout;
+
+impl PdfLayout {
+    /// Positions items from top to bottom within the container described by `tbl`.
+    pub fn vstack<'lua>(lua: &'lua Lua, tbl: LuaTable<'lua>) -> LuaResult<PdfObjectGroup> {
+        let container = PdfLayoutContainer::from_lua(LuaValue::Table(tbl), lua)?;
+        let bounds = container.bounds;
+        let sizes: Vec<_> = container.items.iter().map(|x| x.size).collect();
+        let cells = PdfLayoutSize::split(bounds.height(), container.gap, &sizes)
+            .into_iter()
+            .map(|(offset, length)| {
+                let top = bounds.ur.y - offset;
+                PdfBounds::new(
+                    PdfPoint::new(bounds.ll.x, top - length),
+                    PdfPoint::new(bounds.ur.x, top),
+                )
+            });
+
+        container.place(lua, cells)
+    }
+
+    /// Positions items from left to right within the container described by `tbl`.
+    pub fn hstack<'lua>(lua: &'lua Lua, tbl: LuaTable<'lua>) -> LuaResult<PdfObjectGroup> {
+        let container = PdfLayoutContainer::from_lua(LuaValue::Table(tbl), lua)?;
+        let bounds = container.bounds;
+        let sizes: Vec<_> = container.items.iter().map(|x| x.size).collect();
+        let cells = PdfLayoutSize::split(bounds.width(), container.gap, &sizes)
+            .into_iter()
+            .map(|(offset, length)| {
+                let left = bounds.ll.x + offset;
+                PdfBounds::new(
+                    PdfPoint::new(left, bounds.ll.y),
+                    PdfPoint::new(left + length, bounds.ur.y),
+                )
+            });
+
+        container.place(lua, cells)
+    }
+
+    /// Positions items in rows from left to right, top to bottom, within the container described
+    /// by `tbl`.
+    ///
+    /// Supports the following options in addition to those of every container:
+    ///
+    /// * `columns` - number of equally-sized columns, or the size of each column.
+    /// * `rows` - number of equally-sized rows, or the size of each row, defaulting to as many
+    ///   equally-sized rows as needed to fit the items.
+    pub fn grid<'lua>(lua: &'lua Lua, tbl: LuaTable<'lua>) -> LuaResult<PdfObjectGroup> {
+        let columns = Self::grid_sizes(tbl.raw_get_ext("columns")?)?;
+        let rows: Option<LuaValue> = tbl.raw_get_ext("rows")?;
+        let container = PdfLayoutContainer::from_lua(LuaValue::Table(tbl.clone()), lua)?;
+        let bounds = container.bounds;
+
+        if columns.is_empty() {
+            return Err(LuaError::runtime("Grid needs at least one column"));
+        }
+
+        let rows = match rows {
+            Some(rows) => Self::grid_sizes(rows)?,
+            None => vec![PdfLayoutSize::default(); container.items.len().div_ceil(columns.len())],
+        };
+
+        if container.items.len() > rows.len() * columns.len() {
+            return Err(LuaError::runtime(format!(
+                "Grid has {} items but only {} cells",
+                container.items.len(),
+                rows.len() * columns.len()
+            )));
+        }
+
+        let xs = PdfLayoutSize::split(bounds.width(), container.gap, &columns);
+        let ys = PdfLayoutSize::split(bounds.height(), container.gap, &rows);
+        let cells: Vec<_> = ys
+            .iter()
+            .flat_map(|(y_offset, height)| {
+                xs.iter().map(move |(x_offset, width)| {
+                    let left = bounds.ll.x + *x_offset;
+                    let top = bounds.ur.y - *y_offset;
+                    PdfBounds::new(
+                        PdfPoint::new(left, top - *height),
+                        PdfPoint::new(left + *width, top),
+                    )
+                })
+            })
+            .collect();
+
+        container.place(lua, cells)
+    }
+
+    /// Places items one after another down pages, continuing onto a newly-created page whenever
+    /// the next item does not fit on the current one, returning the ids of the pages used.
+    ///
+    /// Items are never split across pages, and an item marked `keep_with_next` moves onto a new
+    /// page alongside the item after it, such as to keep a heading with its paragraph. Items
+    /// taller than an entire page are placed at the top of their own page.
+    ///
+    /// Supports the following options:
+    ///
+    /// * `title` - title of each page created, defaulting to an empty title.
+    /// * `page` - id of the page to start on, defaulting to creating a new page.
+    /// * `margin` - padding applied to the bounds of each page before placing items.
+    /// * `gap` - space between each item.
+    pub fn flow<'lua>(lua: &'lua Lua, tbl: LuaTable<'lua>) -> LuaResult<Vec<RuntimePageId>> {
+        let title: String = tbl
+            .raw_get_ext::<_, Option<_>>("title")?
+            .unwrap_or_default();
+        let start: Option<RuntimePageId> = tbl.raw_get_ext("page")?;
+        let margin: Option<PdfPadding> = tbl.raw_get_ext("margin")?;
+        let gap = Mm(tbl
+            .raw_get_ext::<_, Option<f32>>("gap")?
+            .unwrap_or_default());
+
+        // Group items kept with the next item into blocks that move between pages together
+        let mut blocks: Vec<Vec<(PdfObject, PdfBounds)>> = vec![Vec::new()];
+        for value in tbl.sequence_values::<LuaValue>() {
+            let (obj, keep_with_next) = match value? {
+                LuaValue::Table(table) if table.raw_get::<_, LuaValue>("type")?.is_nil() => (
+                    table.raw_get::<_, PdfObject>(1)?,
+                    table
+                        .raw_get_ext::<_, Option<bool>>("keep_with_next")?
+                        .unwrap_or_default(),
+                ),
+                value => (PdfObject::from_lua(value, lua)?, false),
+            };
+
+            let bounds = obj.lua_bounds(lua)?;
+            if let Some(block) = blocks.last_mut() {
+                block.push((obj, bounds));
+            }
+
+            if !keep_with_next {
+                blocks.push(Vec::new());
+            }
+        }
+
+        let config = lua.globals().raw_get::<_, PdfConfig>(GLOBAL_PDF_VAR_NAME)?;
+        let page_of = |id: RuntimePageId| -> LuaResult<(RuntimePage, PdfBounds)> {
+            let page = lua
+                .app_data_ref::<RuntimePages>()
+                .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?
+                .get_page(id)
+                .ok_or_else(|| LuaError::runtime(format!("Page {id} does not exist")))?;
+            let bounds = PdfBounds::from_coords(
+                Mm(0.0),
+                Mm(0.0),
+                page.width.unwrap_or(config.page.width),
+                page.height.unwrap_or(config.page.height),
+            );
+            let bounds = match margin {
+                Some(margin) => bounds.with_padding(margin),
+                None => bounds,
+            };
+            Ok((page, bounds))
+        };
+
+        let mut ids = vec![match start {
+            Some(id) => id,
+            None => PdfPages::create_page(lua, title.clone())?,
+        }];
+        let (mut page, mut bounds) = page_of(ids[0])?;
+        let mut top = bounds.ur.y;
+
+        for block in blocks.into_iter().filter(|x| !x.is_empty()) {
+            let height = block.iter().map(|(_, b)| b.height().0).sum::<f32>()
+                + gap.0 * (block.len() - 1) as f32;
+
+            // Move onto a new page unless the block does not fit on an empty page anyway
+            if top - Mm(height) < bounds.ll.y && top < bounds.ur.y {
+                ids.push(PdfPages::create_page(lua, title.clone())?);
+                (page, bounds) = page_of(ids[ids.len() - 1])?;
+                top = bounds.ur.y;
+            }
+
+            for (mut obj, obj_bounds) in block {
+                let cell = PdfBounds::new(
+                    PdfPoint::new(bounds.ll.x, top - obj_bounds.height()),
+                    PdfPoint::new(bounds.ur.x, top),
+                );
+                obj.lua_align_to(lua, cell, (PdfVerticalAlign::Top, PdfHorizontalAlign::Left))?;
+                page.push(obj);
+                top -= obj_bounds.height() + gap;
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Converts a count or list of sizes into sizes for the rows or columns of a grid.
+    fn grid_sizes(value: LuaValue) -> LuaResult<Vec<PdfLayoutSize>> {
+        match value {
+            LuaValue::Integer(count) => Ok(vec![PdfLayoutSize::default(); count.max(0) as usize]),
+            LuaValue::Table(table) => table.sequence_values().collect(),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.layout.grid_sizes",
+                message: Some(String::from("expected count or list of sizes")),
+            }),
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfLayout {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        metatable.raw_set(
+            "vstack",
+            lua.create_function(|lua, tbl: LuaTable| {
+                PdfObject::Group(PdfLayout::vstack(lua, tbl)?).into_lua(lua)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "hstack",
+            lua.create_function(|lua, tbl: LuaTable| {
+                PdfObject::Group(PdfLayout::hstack(lua, tbl)?).into_lua(lua)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "grid",
+            lua.create_function(|lua, tbl: LuaTable| {
+                PdfObject::Group(PdfLayout::grid(lua, tbl)?).into_lua(lua)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "flow",
+            lua.create_function(|lua, tbl: LuaTable| PdfLayout::flow(lua, tbl))?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+/// Size of an item along the direction of a layout.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PdfLayoutSize {
+    /// Fixed size in millimeters.
+    Fixed(Mm),
+    /// Percentage (0 to 100) of the size of the container.
+    Percent(f32),
+    /// Weighted share of the space left over once fixed and percentage sizes are taken.
+    Flex(f32),
+}
+
+impl Default for PdfLayoutSize {
+    fn default() -> Self {
+        Self::Flex(1.0)
+    }
+}
+
+impl PdfLayoutSize {
+    /// Splits `length` into consecutive spans for each of `sizes` separated by `gap`, returning
+    /// the offset from the start and the length of each span.
+    ///
+    /// Flexible sizes share whatever space remains, which is none when the other sizes and gaps
+    /// already exceed `length`.
+    pub fn split(length: Mm, gap: Mm, sizes: &[Self]) -> Vec<(Mm, Mm)> {
+        let gaps = gap.0 * sizes.len().saturating_sub(1) as f32;
+        let mut lengths: Vec<f32> = sizes
+            .iter()
+            .map(|size| match size {
+                Self::Fixed(x) => x.0,
+                Self::Percent(x) => length.0 * x / 100.0,
+                Self::Flex(_) => 0.0,
+            })
+            .collect();
+
+        let remaining = (length.0 - gaps - lengths.iter().sum::<f32>()).max(0.0);
+        let weight = |size: &Self| match size {
+            Self::Flex(x) => x.max(0.0),
+            _ => 0.0,
+        };
+        let total_weight: f32 = sizes.iter().map(weight).sum();
+        if total_weight > 0.0 {
+            for (length, size) in lengths.iter_mut().zip(sizes) {
+                if let Self::Flex(_) = size {
+                    *length = remaining * weight(size) / total_weight;
+                }
+            }
+        }
+
+        let mut offset = 0.0;
+        lengths
+            .into_iter()
+            .map(|length| {
+                let span = (Mm(offset), Mm(length));
+                offset += length + gap.0;
+                span
+            })
+            .collect()
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLayoutSize {
+    /// Converts from any of
+    ///
+    /// - `number` - fixed size in millimeters
+    /// - `"{number}%"` - percentage of the container
+    /// - `{flex:number}` - weighted share of the remaining space
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::Integer(x) => Ok(Self::Fixed(Mm(x as f32))),
+            LuaValue::Number(x) => Ok(Self::Fixed(Mm(x as f32))),
+            LuaValue::String(s) => {
+                let s = s.to_str()?;
+                s.strip_suffix('%')
+                    .and_then(|x| x.trim().parse::<f32>().ok())
+                    .map(Self::Percent)
+                    .ok_or_else(|| LuaError::FromLuaConversionError {
+                        from,
+                        to: "pdf.layout.size",
+                        message: Some(format!("invalid percentage: {s}")),
+                    })
+            }
+            LuaValue::Table(table) => Ok(Self::Flex(table.raw_get_ext("flex")?)),
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.layout.size",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Container of items shared by all layouts.
+struct PdfLayoutContainer<'lua> {
+    /// Bounds of the container, with padding already applied.
+    bounds: PdfBounds,
+    /// Space between each item.
+    gap: Mm,
+    /// Default alignment of items within their cells.
+    align: PdfAlign,
+    items: Vec<PdfLayoutItem<'lua>>,
+}
+
+impl<'lua> PdfLayoutContainer<'lua> {
+    /// Places each item within the cell at the same position, returning a group of the
+    /// positioned objects.
+    fn place(
+        self,
+        lua: &'lua Lua,
+        cells: impl IntoIterator<Item = PdfBounds>,
+    ) -> LuaResult<PdfObjectGroup> {
+        let align = self.align;
+        let objects = self
+            .items
+            .into_iter()
+            .zip(cells)
+            .map(|(item, cell)| item.place(lua, cell, align))
+            .collect::<LuaResult<_>>()?;
+
+        Ok(PdfObjectGroup {
+            objects,
+            ..Default::default()
+        })
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLayoutContainer<'lua> {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => {
+                let bounds: PdfBounds = table.raw_get_ext("bounds")?;
+                let padding: Option<PdfPadding> = table.raw_get_ext("padding")?;
+                Ok(Self {
+                    bounds: match padding {
+                        Some(padding) => bounds.with_padding(padding),
+                        None => bounds,
+                    },
+                    gap: Mm(table
+                        .raw_get_ext::<_, Option<f32>>("gap")?
+                        .unwrap_or_default()),
+                    align: table
+                        .raw_get_ext::<_, Option<PdfAlign>>("align")?
+                        .unwrap_or(PdfAlign {
+                            h: PdfHorizontalAlign::Left,
+                            v: PdfVerticalAlign::Top,
+                        }),
+                    items: table.sequence_values().collect::<LuaResult<_>>()?,
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.layout.container",
+                message: None,
+            }),
+        }
+    }
+}
+
+/// Item within a layout, which is either an object or a function that creates an object from
+/// the bounds of its cell.
+struct PdfLayoutItem<'lua> {
+    content: LuaValue<'lua>,
+    size: PdfLayoutSize,
+    align: Option<PdfAlign>,
+}
+
+impl<'lua> PdfLayoutItem<'lua> {
+    /// Places the item within `cell`, returning the positioned object.
+    ///
+    /// Functions are called with the cell to create their object, where percentages of
+    /// coordinates are relative to the cell, rects without an alignment fill the cell, and all
+    /// other objects are aligned within the cell.
+    fn place(self, lua: &'lua Lua, cell: PdfBounds, align: PdfAlign) -> LuaResult<PdfObject> {
+        match self.content {
+            LuaValue::Function(f) => PdfCoord::with_reference_bounds(lua, cell, || f.call(cell)),
+            value => {
+                let mut obj = PdfObject::from_lua(value, lua)?;
+                match (&mut obj, self.align) {
+                    (PdfObject::Rect(rect), None) => rect.bounds = cell,
+                    (obj, x) => obj.lua_align_to(lua, cell, x.unwrap_or(align).to_v_h())?,
+                }
+                Ok(obj)
+            }
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfLayoutItem<'lua> {
+    /// Converts from any of
+    ///
+    /// - `pdf.Object` - object with a flexible size
+    /// - `function(bounds):pdf.Object` - function creating an object with a flexible size
+    /// - `{pdf.Object|function, size:pdf.layout.Size|nil, align:pdf.common.Align|nil}`
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            // Objects always have a type, so anything else is an item wrapping an object
+            LuaValue::Table(table) if table.raw_get::<_, LuaValue>("type")?.is_nil() => Ok(Self {
+                content: table.raw_get(1)?,
+                size: table
+                    .raw_get_ext::<_, Option<_>>("size")?
+                    .unwrap_or_default(),
+                align: table.raw_get_ext("align")?,
+            }),
+            LuaValue::Table(_) | LuaValue::Function(_) => Ok(Self {
+                content: value,
+                size: PdfLayoutSize::default(),
+                align: None,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.layout.item",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn split_should_share_remaining_space_between_flexible_sizes() {
+        let spans = |length: f32, gap: f32, sizes: &[PdfLayoutSize]| {
+            PdfLayoutSize::split(Mm(length), Mm(gap), sizes)
+                .into_iter()
+                .map(|(offset, length)| (offset.0, length.0))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            spans(
+                100.0,
+                5.0,
+                &[
+                    PdfLayoutSize::Fixed(Mm(10.0)),
+                    PdfLayoutSize::Percent(25.0),
+                    PdfLayoutSize::Flex(1.0),
+                    PdfLayoutSize::Flex(3.0),
+                ]
+            ),
+            [(0.0, 10.0), (15.0, 25.0), (45.0, 12.5), (62.5, 37.5)]
+        );
+
+        // Flexible sizes collapse when there is no space remaining
+        assert_eq!(
+            spans(
+                10.0,
+                0.0,
+                &[PdfLayoutSize::Fixed(Mm(20.0)), PdfLayoutSize::Flex(1.0)]
+            ),
+            [(0.0, 20.0), (20.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn should_support_stacks_and_grids_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local u = pdf.utils
+            local rect = pdf.object.rect({ ll = { 0, 0 }, ur = { 1, 1 } })
+            local circle = pdf.object.circle({ center = { 0, 0 }, radius = 1 })
+
+            local stack = pdf.layout.vstack({
+                bounds = { ll = { 0, 0 }, ur = { 100, 100 } },
+                padding = 10,
+                gap = 5,
+                { rect, size = 20 },
+                { rect, size = "50%" },
+                { circle, align = { v = "bottom", h = "right" } },
+            })
+            u.assert_deep_equal(stack[1]:bounds(), { ll = { x = 10, y = 70 }, ur = { x = 90, y = 90 } })
+            u.assert_deep_equal(stack[2]:bounds(), { ll = { x = 10, y = 25 }, ur = { x = 90, y = 65 } })
+            u.assert_deep_equal(stack[3]:bounds():with_precision(3), { ll = { x = 88, y = 10 }, ur = { x = 90, y = 12 } })
+
+            local stack = pdf.layout.hstack({
+                bounds = { ll = { 0, 0 }, ur = { 30, 10 } },
+                { rect, size = { flex = 2 } },
+                function(bounds) return rect:with_bounds(bounds) end,
+                circle,
+            })
+            u.assert_deep_equal(stack[1]:bounds(), { ll = { x = 0, y = 0 }, ur = { x = 15, y = 10 } })
+            u.assert_deep_equal(stack[2]:bounds(), { ll = { x = 15, y = 0 }, ur = { x = 22.5, y = 10 } })
+            u.assert_deep_equal(stack[3]:bounds():with_precision(3), { ll = { x = 22.5, y = 8 }, ur = { x = 24.5, y = 10 } })
+
+            local grid = pdf.layout.grid({
+                bounds = { ll = { 0, 0 }, ur = { 20, 20 } },
+                columns = { 5, { flex = 1 } },
+                rect, rect, rect,
+            })
+            u.assert_deep_equal(grid[1]:bounds(), { ll = { x = 0, y = 10 }, ur = { x = 5, y = 20 } })
+            u.assert_deep_equal(grid[2]:bounds(), { ll = { x = 5, y = 10 }, ur = { x = 20, y = 20 } })
+            u.assert_deep_equal(grid[3]:bounds(), { ll = { x = 0, y = 0 }, ur = { x = 5, y = 10 } })
+
+            local ok = pcall(pdf.layout.grid, {
+                bounds = { ll = { 0, 0 }, ur = { 20, 20 } },
+                columns = 1,
+                rows = 1,
+                rect, rect,
+            })
+            assert(not ok, "grid with more items than cells was created")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn flow_should_continue_onto_new_pages_when_items_do_not_fit() {
+        let lua = Lua::new();
+        lua.set_app_data(RuntimePages::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let ids: Vec<RuntimePageId> = lua
+            .load(chunk! {
+                pdf.page.width = 100
+                pdf.page.height = 100
+
+                local function rect(height)
+                    return pdf.object.rect({ ll = { 0, 0 }, ur = { 20, height } })
+                end
+
+                return pdf.layout.flow({
+                    title = "Journal",
+                    margin = 10,
+                    gap = 5,
+                    rect(30),
+                    rect(30),
+                    { rect(10), keep_with_next = true },
+                    rect(30),
+                })
+            })
+            .eval()
+            .expect("Failed to flow items");
+        assert_eq!(ids.len(), 2);
+
+        let pages = lua.app_data_ref::<RuntimePages>().unwrap();
+        let bounds = |id: RuntimePageId| {
+            let page = pages.get_page(id).unwrap();
+            assert_eq!(page.title, "Journal");
+            let objects = page.objects();
+            objects
+                .iter()
+                .map(|obj| obj.lua_bounds(&lua).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            bounds(ids[0]),
+            [
+                PdfBounds::from_coords_f32(10.0, 60.0, 30.0, 90.0),
+                PdfBounds::from_coords_f32(10.0, 25.0, 30.0, 55.0),
+            ]
+        );
+
+        // Item kept with the next moves onto the new page with it
+        assert_eq!(
+            bounds(ids[1]),
+            [
+                PdfBounds::from_coords_f32(10.0, 80.0, 30.0, 90.0),
+                PdfBounds::from_coords_f32(10.0, 45.0, 30.0, 75.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfConfig, PdfHorizontalAlign, PdfLink, PdfLuaTableExt, PdfObjectGroup,
+    PdfObjectRect, PdfObjectText, PdfPaintMode, PdfTextOverflow, PdfVerticalAlign,
+};
+use crate::runtime::{RuntimeFontId, RuntimePageId, RuntimePages};
+use mlua::prelude::*;
+use printpdf::Mm;
+
+/// Edge of the page that a strip of navigation tabs runs along.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfNavTabsEdge {
+    Top,
+    #[default]
+    Right,
+    Bottom,
+    Left,
+}
+
+impl PdfNavTabsEdge {
+    /// Names of the edges as they appear in Lua.
+    const NAMES: [(&'static str, Self); 4] = [
+        ("top", Self::Top),
+        ("right", Self::Right),
+        ("bottom", Self::Bottom),
+        ("left", Self::Left),
+    ];
+}
+
+/// Tab within a strip of navigation tabs, linking to a page.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfNavTab {
+    pub label: String,
+    /// Page that the tab goes to.
+    pub page: RuntimePageId,
+    /// Pages where the tab is highlighted as the current tab, in addition to the page it goes to.
+    pub pages: Vec<RuntimePageId>,
+}
+
+impl PdfNavTab {
+    /// Returns true if the tab is highlighted on the page with `id`.
+    pub fn is_active(&self, id: RuntimePageId) -> bool {
+        self.page == id || self.pages.contains(&id)
+    }
+}
+
+/// Strip of tabs along an edge of pages, each linking to a page, such as the month tabs of a
+/// planner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfNavTabs {
+    /// Tabs to draw, defaulting to one for each section that links to its first page and is
+    /// highlighted on every page of the section.
+    pub tabs: Option<Vec<PdfNavTab>>,
+    /// Pages to draw the tabs onto, defaulting to every page.
+    pub pages: Option<Vec<RuntimePageId>>,
+    pub edge: PdfNavTabsEdge,
+    /// Distance in millimeters that tabs extend from the edge of the page.
+    pub width: f32,
+    /// Space in millimeters between tabs.
+    pub gap: f32,
+    /// Space in millimeters along the edge before the first tab and after the last tab.
+    pub margin: f32,
+    pub fill_color: Option<PdfColor>,
+    pub active_fill_color: Option<PdfColor>,
+    /// Color of labels, defaulting to the fill color of the page.
+    pub text_color: Option<PdfColor>,
+    pub active_text_color: Option<PdfColor>,
+    pub font: Option<RuntimeFontId>,
+    pub size: Option<f32>,
+    pub depth: Option<i64>,
+}
+
+impl Default for PdfNavTabs {
+    fn default() -> Self {
+        Self {
+            tabs: None,
+            pages: None,
+            edge: PdfNavTabsEdge::default(),
+            width: 8.0,
+            gap: 1.0,
+            margin: 10.0,
+            fill_color: None,
+            active_fill_color: None,
+            text_color: None,
+            active_text_color: None,
+            font: None,
+            size: None,
+            depth: None,
+        }
+    }
+}
+
+impl PdfNavTabs {
+    /// Color of tabs that do not specify their own.
+    pub fn default_fill_color() -> PdfColor {
+        PdfColor::from_rgb_u8(224, 224, 224)
+    }
+
+    /// Color of the current tab when it does not specify its own.
+    pub fn default_active_fill_color() -> PdfColor {
+        PdfColor::from_rgb_u8(160, 160, 160)
+    }
+
+    /// Returns the bounds of `count` tabs on a page of `width` and `height`, splitting the edge
+    /// evenly between them from the top or the left.
+    pub fn tab_bounds(&self, count: usize, width: Mm, height: Mm) -> Vec<PdfBounds> {
+        let vertical = matches!(self.edge, PdfNavTabsEdge::Left | PdfNavTabsEdge::Right);
+        let length = if vertical { height.0 } else { width.0 };
+        let available = length - self.margin * 2.0 - self.gap * count.saturating_sub(1) as f32;
+        let tab_length = (available / count.max(1) as f32).max(0.0);
+
+        (0..count)
+            .map(|i| {
+                let start = self.margin + (tab_length + self.gap) * i as f32;
+                let end = start + tab_length;
+                match self.edge {
+                    PdfNavTabsEdge::Top => {
+                        PdfBounds::from_coords_f32(start, height.0 - self.width, end, height.0)
+                    }
+                    PdfNavTabsEdge::Bottom => {
+                        PdfBounds::from_coords_f32(start, 0.0, end, self.width)
+                    }
+                    PdfNavTabsEdge::Left => PdfBounds::from_coords_f32(
+                        0.0,
+                        height.0 - end,
+                        self.width,
+                        height.0 - start,
+                    ),
+                    PdfNavTabsEdge::Right => PdfBounds::from_coords_f32(
+                        width.0 - self.width,
+                        height.0 - end,
+                        width.0,
+                        height.0 - start,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the tabs to draw, which are either the explicit tabs or one for each section of
+    /// `pages` that has at least one page.
+    pub fn to_tabs(&self, pages: &RuntimePages) -> Vec<PdfNavTab> {
+        match self.tabs.as_ref() {
+            Some(tabs) => tabs.clone(),
+            None => pages
+                .sections()
+                .iter()
+                .filter_map(|section| {
+                    section.first_page().map(|page| PdfNavTab {
+                        label: section.name.clone(),
+                        page,
+                        pages: section.pages.clone(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the strip of `tabs` drawn on the page with `id` of `width` and `height`, with
+    /// labels centered and shrunk to fit within their tabs.
+    ///
+    /// Calculates the bounds of labels from a [`Lua`] runtime, which occurs earlier than when a
+    /// [`PdfContext`](crate::pdf::PdfContext) is available.
+    pub(crate) fn lua_to_group(
+        &self,
+        lua: &Lua,
+        tabs: &[PdfNavTab],
+        id: RuntimePageId,
+        (width, height): (Mm, Mm),
+    ) -> LuaResult<PdfObjectGroup> {
+        let mut objects = Vec::new();
+        for (tab, bounds) in tabs.iter().zip(self.tab_bounds(tabs.len(), width, height)) {
+            let active = tab.is_active(id);
+            let fill_color = if active {
+                self.active_fill_color
+                    .unwrap_or_else(Self::default_active_fill_color)
+            } else {
+                self.fill_color.unwrap_or_else(Self::default_fill_color)
+            };
+
+            objects.push(
+                PdfObjectRect {
+                    bounds,
+                    depth: self.depth,
+                    fill_color: Some(fill_color),
+                    mode: Some(PdfPaintMode::fill()),
+                    link: Some(PdfLink::GoTo {
+                        page: tab.page,
+                        at: None,
+                        zoom: None,
+                        rect: None,
+                    }),
+                    ..Default::default()
+                }
+                .into(),
+            );
+
+            let mut text = PdfObjectText {
+                text: tab.label.clone(),
+                depth: self.depth,
+                font: self.font,
+                size: self.size,
+                max_width: Some((bounds.width().0 - 1.0).max(0.0)),
+                overflow: Some(PdfTextOverflow::Shrink),
+                color: if active {
+                    self.active_text_color.or(self.text_color)
+                } else {
+                    self.text_color
+                },
+                ..Default::default()
+            };
+            text.lua_align_to(
+                lua,
+                bounds,
+                (PdfVerticalAlign::Middle, PdfHorizontalAlign::Middle),
+            )?;
+            objects.push(text.into());
+        }
+
+        Ok(PdfObjectGroup {
+            objects,
+            ..Default::default()
+        })
+    }
+
+    /// Draws the tabs onto their pages, sized to each page or otherwise the configured page size.
+    pub(crate) fn lua_push(&self, lua: &Lua) -> LuaResult<()> {
+        let config: PdfConfig = lua.globals().raw_get(GLOBAL_PDF_VAR_NAME)?;
+
+        // Collect everything needed from the pages up front as laying out labels needs the runtime
+        let (tabs, pages) = {
+            let pages = lua
+                .app_data_ref::<RuntimePages>()
+                .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?;
+            let ids: Vec<RuntimePageId> = match self.pages.as_ref() {
+                Some(ids) => ids.clone(),
+                None => pages.ids().collect(),
+            };
+
+            let mut targets = Vec::new();
+            for id in ids {
+                let page = pages
+                    .get_page(id)
+                    .ok_or_else(|| LuaError::runtime(format!("Page {id} does not exist")))?;
+                targets.push(page);
+            }
+            (self.to_tabs(&pages), targets)
+        };
+
+        if tabs.is_empty() {
+            return Ok(());
+        }
+
+        for page in pages {
+            let size = (
+                page.width.unwrap_or(config.page.width),
+                page.height.unwrap_or(config.page.height),
+            );
+            page.push(self.lua_to_group(lua, &tabs, page.id, size)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfNavTabsEdge {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let from = value.type_name();
+        match value {
+            LuaValue::String(s) => {
+                let name = s.to_string_lossy();
+                Self::NAMES
+                    .into_iter()
+                    .find(|(x, _)| *x == name.as_ref())
+                    .map(|(_, edge)| edge)
+                    .ok_or_else(|| LuaError::FromLuaConversionError {
+                        from,
+                        to: "pdf.common.nav_tabs_edge",
+                        message: Some(format!("unknown edge: {name}")),
+                    })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from,
+                to: "pdf.common.nav_tabs_edge",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfNavTab {
+    /// Converts from a table with `label` and `page`, alongside optional `pages` where the tab is
+    /// highlighted.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => Ok(Self {
+                label: table.raw_get_ext("label")?,
+                page: table.raw_get_ext("page")?,
+                pages: table
+                    .raw_get_ext::<_, Option<_>>("pages")?
+                    .unwrap_or_default(),
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.nav_tab",
+                message: None,
+            }),
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfNavTabs {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        let default = Self::default();
+        match value {
+            LuaValue::Nil => Ok(default),
+            LuaValue::Table(table) => Ok(Self {
+                tabs: table.raw_get_ext("tabs")?,
+                pages: table.raw_get_ext("pages")?,
+                edge: table
+                    .raw_get_ext::<_, Option<_>>("edge")?
+                    .unwrap_or(default.edge),
+                width: table
+                    .raw_get_ext::<_, Option<_>>("width")?
+                    .unwrap_or(default.width),
+                gap: table
+                    .raw_get_ext::<_, Option<_>>("gap")?
+                    .unwrap_or(default.gap),
+                margin: table
+                    .raw_get_ext::<_, Option<_>>("margin")?
+                    .unwrap_or(default.margin),
+                fill_color: table.raw_get_ext("fill_color")?,
+                active_fill_color: table.raw_get_ext("active_fill_color")?,
+                text_color: table.raw_get_ext("text_color")?,
+                active_text_color: table.raw_get_ext("active_text_color")?,
+                font: table.raw_get_ext("font")?,
+                size: table.raw_get_ext("size")?,
+                depth: table.raw_get_ext("depth")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.nav_tabs",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{Pdf, PdfObject};
+    use crate::runtime::{RuntimeFonts, RuntimeSection};
+    use mlua::chunk;
+
+    #[test]
+    fn tab_bounds_should_split_edge_between_tabs() {
+        let tabs = PdfNavTabs {
+            width: 8.0,
+            gap: 2.0,
+            margin: 10.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            tabs.tab_bounds(3, Mm(100.0), Mm(204.0)),
+            vec![
+                PdfBounds::from_coords_f32(92.0, 134.0, 100.0, 194.0),
+                PdfBounds::from_coords_f32(92.0, 72.0, 100.0, 132.0),
+                PdfBounds::from_coords_f32(92.0, 10.0, 100.0, 70.0),
+            ]
+        );
+
+        let tabs = PdfNavTabs {
+            edge: PdfNavTabsEdge::Top,
+            ..tabs
+        };
+        assert_eq!(
+            tabs.tab_bounds(2, Mm(100.0), Mm(200.0)),
+            vec![
+                PdfBounds::from_coords_f32(10.0, 192.0, 49.0, 200.0),
+                PdfBounds::from_coords_f32(51.0, 192.0, 90.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn to_tabs_should_default_to_sections() {
+        let mut pages = RuntimePages::new();
+        pages.begin_section(RuntimeSection::new("Jan")).unwrap();
+        let jan = pages.page("2025-01").id;
+        let jan_day = pages.page("2025-01-01").id;
+        pages.begin_section(RuntimeSection::new("Empty")).unwrap();
+        pages.begin_section(RuntimeSection::new("Feb")).unwrap();
+        let feb = pages.page("2025-02").id;
+
+        assert_eq!(
+            PdfNavTabs::default().to_tabs(&pages),
+            vec![
+                PdfNavTab {
+                    label: String::from("Jan"),
+                    page: jan,
+                    pages: vec![jan, jan_day],
+                },
+                PdfNavTab {
+                    label: String::from("Feb"),
+                    page: feb,
+                    pages: vec![feb],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_push_tabs_onto_pages_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        let (cover, jan, feb): (RuntimePageId, RuntimePageId, RuntimePageId) = lua
+            .load(chunk! {
+                local cover = pdf.pages.create("Cover")
+                local jan = pdf.pages.create("January")
+                local feb = pdf.pages.create("February")
+                pdf.pages.nav_tabs({
+                    tabs = {
+                        { label = "Jan", page = jan },
+                        { label = "Feb", page = feb },
+                    },
+                    pages = { jan, feb },
+                    active_fill_color = "#ff0000",
+                })
+                return cover, jan, feb
+            })
+            .eval()
+            .unwrap();
+
+        let pages = lua.app_data_ref::<RuntimePages>().unwrap();
+        assert!(pages.get_page(cover).unwrap().objects().is_empty());
+
+        let page = pages.get_page(feb).unwrap();
+        let objects = page.objects();
+        let group = match &objects[..] {
+            [PdfObject::Group(group)] => group.clone(),
+            objects => panic!("Unexpected objects {objects:?}"),
+        };
+        let rects: Vec<&PdfObjectRect> = group
+            .objects
+            .iter()
+            .filter_map(|obj| match obj {
+                PdfObject::Rect(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rects.len(), 2);
+        assert_eq!(
+            rects[0].link,
+            Some(PdfLink::GoTo {
+                page: jan,
+                at: None,
+                zoom: None,
+                rect: None,
+            })
+        );
+        assert_eq!(rects[0].fill_color, Some(PdfNavTabs::default_fill_color()));
+        assert_eq!(rects[1].fill_color, Some(PdfColor::from_rgb_u8(255, 0, 0)));
+    }
+}
@@ -4,6 +4,7 @@ mod line;
 mod rect;
 mod shape;
 mod text;
+mod toc;
 mod r#type;
 
 pub use circle::PdfObjectCircle;
@@ -13,9 +14,35 @@ pub use r#type::PdfObjectType;
 pub use rect::PdfObjectRect;
 pub use shape::PdfObjectShape;
 pub use text::PdfObjectText;
+pub use toc::PdfObjectToc;
 
-use crate::pdf::{PdfBounds, PdfContext, PdfLinkAnnotation, PdfLuaTableExt};
+use crate::pdf::{
+    PdfBounds, PdfContext, PdfHorizontalAlign, PdfLink, PdfLinkAnnotation, PdfLuaTableExt,
+    PdfPoint, PdfShadow, PdfStyle, PdfVerticalAlign,
+};
 use mlua::prelude::*;
+use printpdf::Mm;
+
+/// Returns a deep copy of `obj` with the fields of `overrides` (if any) replacing its own, which
+/// is how objects are cloned in Lua.
+pub(crate) fn lua_clone<'lua, T>(
+    lua: &'lua Lua,
+    obj: T,
+    overrides: Option<LuaTable<'lua>>,
+) -> LuaResult<T>
+where
+    T: IntoLua<'lua> + FromLua<'lua>,
+{
+    let value = obj.into_lua(lua)?;
+    if let (LuaValue::Table(table), Some(overrides)) = (&value, overrides) {
+        for pair in overrides.pairs::<LuaValue, LuaValue>() {
+            let (key, value) = pair?;
+            table.raw_set(key, value)?;
+        }
+    }
+
+    T::from_lua(value, lua)
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum PdfObject {
@@ -25,6 +52,7 @@ pub enum PdfObject {
     Rect(PdfObjectRect),
     Shape(PdfObjectShape),
     Text(PdfObjectText),
+    Toc(PdfObjectToc),
 }
 
 impl PdfObject {
@@ -37,6 +65,7 @@ impl PdfObject {
             Self::Rect(_) => PdfObjectType::Rect,
             Self::Shape(_) => PdfObjectType::Shape,
             Self::Text(_) => PdfObjectType::Text,
+            Self::Toc(_) => PdfObjectType::Toc,
         }
     }
 
@@ -54,6 +83,7 @@ impl PdfObject {
             Self::Rect(x) => x.bounds,
             Self::Shape(x) => x.bounds(),
             Self::Text(x) => x.bounds(ctx),
+            Self::Toc(x) => x.bounds,
         }
     }
 
@@ -67,43 +97,303 @@ impl PdfObject {
             Self::Rect(x) => x.bounds,
             Self::Shape(x) => x.bounds(),
             Self::Text(x) => x.lua_bounds(lua)?,
+            Self::Toc(x) => x.bounds,
         })
     }
 
+    /// Aligns the object to a set of bounds.
+    ///
+    /// Calculates bounds from a [`Lua`] runtime, which occurs earlier than when a [`PdfContext`]
+    /// is available.
+    pub(crate) fn lua_align_to(
+        &mut self,
+        lua: &Lua,
+        bounds: PdfBounds,
+        align: (PdfVerticalAlign, PdfHorizontalAlign),
+    ) -> LuaResult<()> {
+        match self {
+            Self::Circle(x) => x.align_to(bounds, align),
+            Self::Group(x) => x.lua_align_to(lua, bounds, align)?,
+            Self::Line(x) => x.align_to(bounds, align),
+            Self::Rect(x) => x.align_to(bounds, align),
+            Self::Shape(x) => x.align_to(bounds, align),
+            Self::Text(x) => x.lua_align_to(lua, bounds, align)?,
+            Self::Toc(x) => x.align_to(bounds, align),
+        }
+
+        Ok(())
+    }
+
+    /// Moves the object by `x_offset` and `y_offset`, including objects nested within groups.
+    pub fn shift_by(&mut self, x_offset: Mm, y_offset: Mm) {
+        let offset = PdfPoint::new(x_offset, y_offset);
+        match self {
+            Self::Circle(x) => x.center = x.center + offset,
+            Self::Group(x) => x.shift_by(x_offset, y_offset),
+            Self::Line(x) => x.points.iter_mut().for_each(|pt| *pt = *pt + offset),
+            Self::Rect(x) => x.bounds = x.bounds.shift_by(x_offset, y_offset),
+            Self::Shape(x) => x.points.iter_mut().for_each(|pt| *pt = *pt + offset),
+            Self::Text(x) => x.point = x.point + offset,
+            Self::Toc(x) => x.bounds = x.bounds.shift_by(x_offset, y_offset),
+        }
+    }
+
+    /// Scales the object by a factor of `width` and `height` away from `origin`, including
+    /// objects nested within groups.
+    ///
+    /// Circles are resized by the smaller factor so they remain circles, and text is moved
+    /// without changing the size of its font.
+    pub fn scale_by_factor(&mut self, origin: PdfPoint, width: f32, height: f32) {
+        let scale = |pt: PdfPoint| {
+            PdfPoint::new(
+                origin.x + (pt.x - origin.x) * width,
+                origin.y + (pt.y - origin.y) * height,
+            )
+        };
+        let scale_bounds = |bounds: PdfBounds| PdfBounds::new(scale(bounds.ll), scale(bounds.ur));
+
+        match self {
+            Self::Circle(x) => {
+                x.center = scale(x.center);
+                x.radius *= width.abs().min(height.abs());
+            }
+            Self::Group(x) => x.scale_by_factor(origin, width, height),
+            Self::Line(x) => x.points.iter_mut().for_each(|pt| *pt = scale(*pt)),
+            Self::Rect(x) => x.bounds = scale_bounds(x.bounds),
+            Self::Shape(x) => x.points.iter_mut().for_each(|pt| *pt = scale(*pt)),
+            Self::Text(x) => x.point = scale(x.point),
+            Self::Toc(x) => x.bounds = scale_bounds(x.bounds),
+        }
+    }
+
+    /// Rotates the object counter-clockwise around `origin` by `degrees`, including objects
+    /// nested within groups.
+    ///
+    /// Rects, text, and tables of contents are always drawn upright, so their positions are
+    /// rotated (using their centers for rects and tables of contents) without turning them.
+    pub fn rotate_around(&mut self, origin: PdfPoint, degrees: f32) {
+        let rotate_bounds = |bounds: PdfBounds| {
+            let center = bounds.ll.midpoint(bounds.ur);
+            let offset = center.rotate_around(origin, degrees) - center;
+            bounds.shift_by(offset.x, offset.y)
+        };
+
+        match self {
+            Self::Circle(x) => x.center = x.center.rotate_around(origin, degrees),
+            Self::Group(x) => x.rotate_around(origin, degrees),
+            Self::Line(x) => x
+                .points
+                .iter_mut()
+                .for_each(|pt| *pt = pt.rotate_around(origin, degrees)),
+            Self::Rect(x) => x.bounds = rotate_bounds(x.bounds),
+            Self::Shape(x) => x
+                .points
+                .iter_mut()
+                .for_each(|pt| *pt = pt.rotate_around(origin, degrees)),
+            Self::Text(x) => x.point = x.point.rotate_around(origin, degrees),
+            Self::Toc(x) => x.bounds = rotate_bounds(x.bounds),
+        }
+    }
+
     /// Returns depth of the object with 0 being the default.
     pub fn depth(&self) -> i64 {
+        self.depth_or(0)
+    }
+
+    /// Returns depth of the object, using `default` when the object has no explicit depth.
+    pub fn depth_or(&self, default: i64) -> i64 {
         match self {
             Self::Circle(x) => x.depth,
-            Self::Group(x) => Some(x.depth()),
+            Self::Group(x) => Some(x.depth_or(default)),
             Self::Line(x) => x.depth,
             Self::Rect(x) => x.depth,
             Self::Shape(x) => x.depth,
             Self::Text(x) => x.depth,
+            Self::Toc(x) => x.depth,
+        }
+        .unwrap_or(default)
+    }
+
+    /// Returns the objects that would be drawn, in order, replacing groups with the objects
+    /// nested within them.
+    pub fn flatten(&self) -> Vec<&PdfObject> {
+        match self {
+            _ if self.is_hidden() => Vec::new(),
+            Self::Group(x) => x.iter().flat_map(PdfObject::flatten).collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// Like [`PdfObject::flatten`], but pairs each object with the style and layer inherited from
+    /// the groups containing it, starting with `style` and `layer`.
+    ///
+    /// The layer of an object takes precedence over the layer of any group containing it.
+    pub fn flatten_styled<'a>(
+        &'a self,
+        style: PdfStyle,
+        layer: Option<&'a str>,
+    ) -> Vec<(&'a PdfObject, PdfStyle, Option<&'a str>)> {
+        match self {
+            _ if self.is_hidden() => Vec::new(),
+            // Groups drawn as forms are drawn as a whole
+            Self::Group(x) if x.xobject.is_some() => {
+                vec![(self, style, x.layer.as_deref().or(layer))]
+            }
+            Self::Group(x) => {
+                let style = x.style.or(style);
+                let layer = x.layer.as_deref().or(layer);
+                x.iter()
+                    .flat_map(|obj| obj.flatten_styled(style, layer))
+                    .collect()
+            }
+            _ => vec![(self, style, self.layer().or(layer))],
+        }
+    }
+
+    /// Returns the name of the layer the object is drawn within, if not the default layer.
+    pub fn layer(&self) -> Option<&str> {
+        match self {
+            Self::Circle(x) => x.layer.as_deref(),
+            Self::Group(x) => x.layer.as_deref(),
+            Self::Line(x) => x.layer.as_deref(),
+            Self::Rect(x) => x.layer.as_deref(),
+            Self::Shape(x) => x.layer.as_deref(),
+            Self::Text(x) => x.layer.as_deref(),
+            Self::Toc(x) => x.layer.as_deref(),
+        }
+    }
+
+    /// Returns true if the object is hidden, meaning that it (and any objects nested within it) is
+    /// neither drawn nor given link annotations.
+    pub fn is_hidden(&self) -> bool {
+        let hidden = match self {
+            Self::Circle(x) => x.hidden,
+            Self::Group(x) => x.hidden,
+            Self::Line(x) => x.hidden,
+            Self::Rect(x) => x.hidden,
+            Self::Shape(x) => x.hidden,
+            Self::Text(x) => x.hidden,
+            Self::Toc(x) => x.hidden,
+        };
+
+        hidden.unwrap_or(false)
+    }
+
+    /// Returns the tag associated with the object, if any.
+    pub fn tag(&self) -> Option<&str> {
+        match self {
+            Self::Circle(x) => x.tag.as_deref(),
+            Self::Group(x) => x.tag.as_deref(),
+            Self::Line(x) => x.tag.as_deref(),
+            Self::Rect(x) => x.tag.as_deref(),
+            Self::Shape(x) => x.tag.as_deref(),
+            Self::Text(x) => x.tag.as_deref(),
+            Self::Toc(x) => x.tag.as_deref(),
         }
-        .unwrap_or_default()
+    }
+
+    /// Returns the objects with the given `tag`, searching within groups that do not have the tag.
+    pub fn find_tagged(&self, tag: &str) -> Vec<&PdfObject> {
+        match self {
+            _ if self.tag() == Some(tag) => vec![self],
+            Self::Group(x) => x.iter().flat_map(|obj| obj.find_tagged(tag)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the links of the object, including links of objects nested within groups.
+    pub fn links(&self) -> Vec<&PdfLink> {
+        let link = match self {
+            Self::Circle(x) => x.link.as_ref(),
+            Self::Group(x) => x.link.as_ref(),
+            Self::Line(x) => x.link.as_ref(),
+            Self::Rect(x) => x.link.as_ref(),
+            Self::Shape(x) => x.link.as_ref(),
+            Self::Text(x) => x.link.as_ref(),
+            // Links of entries are only known once every page exists
+            Self::Toc(_) => None,
+        };
+
+        let mut links: Vec<&PdfLink> = link.into_iter().collect();
+        if let Self::Group(x) = self {
+            links.extend(x.iter().flat_map(PdfObject::links));
+        }
+
+        links
     }
 
     /// Returns a collection of link annotations.
     pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         match self {
+            _ if self.is_hidden() => Vec::new(),
             Self::Circle(x) => x.link_annotations(ctx),
             Self::Group(x) => x.link_annotations(ctx),
             Self::Line(x) => x.link_annotations(ctx),
             Self::Rect(x) => x.link_annotations(ctx),
             Self::Shape(x) => x.link_annotations(ctx),
             Self::Text(x) => x.link_annotations(ctx),
+            Self::Toc(x) => x.link_annotations(ctx),
         }
     }
 
-    /// Draws the object within the PDF.
+    /// Returns the shadow drawn beneath the object, if any.
+    pub fn shadow(&self) -> Option<PdfShadow> {
+        match self {
+            Self::Circle(x) => x.shadow,
+            Self::Rect(x) => x.shadow,
+            Self::Text(x) => x.shadow,
+            Self::Group(_) | Self::Line(_) | Self::Shape(_) | Self::Toc(_) => None,
+        }
+    }
+
+    /// Draws `shadow` beneath the object as copies of the object in the color of the shadow.
+    fn draw_shadow(&self, shadow: PdfShadow, ctx: PdfContext<'_>) {
+        let (offsets, color) = shadow.copies();
+        let mut obj = self.clone();
+        match &mut obj {
+            Self::Circle(x) => {
+                x.fill_color = Some(color);
+                x.outline_color = Some(color);
+                x.shadow = None;
+            }
+            Self::Rect(x) => {
+                x.fill_color = Some(color);
+                x.outline_color = Some(color);
+                x.shadow = None;
+            }
+            Self::Text(x) => {
+                x.color = Some(color);
+                x.link = None;
+                x.shadow = None;
+                if let Some(background) = x.background.as_mut() {
+                    background.color = Some(color);
+                }
+            }
+            Self::Group(_) | Self::Line(_) | Self::Shape(_) | Self::Toc(_) => return,
+        }
+
+        for offset in offsets {
+            let mut copy = obj.clone();
+            copy.shift_by(offset.x, offset.y);
+            copy.draw(ctx);
+        }
+    }
+
+    /// Draws the object within the PDF, preceded by its shadow if it has one.
     pub fn draw(&self, ctx: PdfContext<'_>) {
+        if let (Some(shadow), false) = (self.shadow(), self.is_hidden()) {
+            self.draw_shadow(shadow, ctx);
+        }
+
         match self {
+            _ if self.is_hidden() => {}
             Self::Circle(x) => x.draw(ctx),
             Self::Group(x) => x.draw(ctx),
             Self::Line(x) => x.draw(ctx),
             Self::Rect(x) => x.draw(ctx),
             Self::Shape(x) => x.draw(ctx),
             Self::Text(x) => x.draw(ctx),
+            Self::Toc(x) => x.draw(ctx),
         }
     }
 }
@@ -144,6 +434,12 @@ impl From<PdfObjectText> for PdfObject {
     }
 }
 
+impl From<PdfObjectToc> for PdfObject {
+    fn from(obj: PdfObjectToc) -> Self {
+        Self::Toc(obj)
+    }
+}
+
 impl<'lua> IntoLua<'lua> for PdfObject {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
@@ -155,6 +451,7 @@ impl<'lua> IntoLua<'lua> for PdfObject {
             Self::Rect(x) => x.into_lua(lua)?,
             Self::Shape(x) => x.into_lua(lua)?,
             Self::Text(x) => x.into_lua(lua)?,
+            Self::Toc(x) => x.into_lua(lua)?,
         };
 
         match value {
@@ -204,6 +501,10 @@ impl<'lua> FromLua<'lua> for PdfObject {
                         LuaValue::Table(table),
                         lua,
                     )?)),
+                    Some(PdfObjectType::Toc) => Ok(Self::Toc(PdfObjectToc::from_lua(
+                        LuaValue::Table(table),
+                        lua,
+                    )?)),
                     None => Err(LuaError::FromLuaConversionError {
                         from,
                         to: "pdf.object",
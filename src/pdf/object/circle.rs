@@ -9,6 +9,7 @@ pub struct PdfObjectCircle {
     pub center: PdfPoint,
     pub radius: Mm,
     pub depth: Option<i64>,
+    pub layer: Option<String>,
     pub fill_color: Option<PdfColor>,
     pub outline_color: Option<PdfColor>,
     pub outline_thickness: Option<f32>,
@@ -18,6 +19,9 @@ pub struct PdfObjectCircle {
     pub cap_style: Option<PdfLineCapStyle>,
     pub join_style: Option<PdfLineJoinStyle>,
     pub link: Option<PdfLink>,
+    pub shadow: Option<PdfShadow>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
 }
 
 impl PdfObjectCircle {
@@ -79,12 +83,14 @@ impl PdfObjectCircle {
     }
 
     /// Returns a collection of link annotations.
-    pub fn link_annotations(&self, _ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+    pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
                 bounds: self.bounds(),
-                depth: self.depth.unwrap_or_default(),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
                 link,
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
             }],
             None => Vec::new(),
         }
@@ -92,34 +98,52 @@ impl PdfObjectCircle {
 
     /// Draws the object within the PDF.
     pub fn draw(&self, ctx: PdfContext) {
-        // Get optional values, setting defaults when not specified
-        let fill_color = self.fill_color.unwrap_or(ctx.config.page.fill_color);
-        let outline_color = self.fill_color.unwrap_or(ctx.config.page.outline_color);
-        let outline_thickness = self
-            .outline_thickness
-            .unwrap_or(ctx.config.page.outline_thickness);
-        let line_cap_style = self.cap_style.unwrap_or(ctx.config.page.line_cap_style);
-        let line_join_style = self.join_style.unwrap_or(ctx.config.page.line_join_style);
+        // Get optional values, falling back to the style, the defaults of circles, and then of
+        // pages
+        let defaults = ctx.config.page.defaults.circle;
+        let fill_color = self
+            .fill_color
+            .unwrap_or_else(|| ctx.fill_color(defaults.fill_color));
+        let outline_color = self
+            .fill_color
+            .unwrap_or_else(|| ctx.outline_color(defaults.outline_color));
+        let outline_thickness = ctx.config.page.stroke_thickness(
+            self.outline_thickness
+                .unwrap_or_else(|| ctx.outline_thickness(defaults.outline_thickness)),
+        );
+        let line_cap_style = self
+            .cap_style
+            .or(defaults.cap_style)
+            .unwrap_or(ctx.config.page.line_cap_style);
+        let line_join_style = self
+            .join_style
+            .or(defaults.join_style)
+            .unwrap_or(ctx.config.page.line_join_style);
         let line_dash_pattern = self
             .dash_pattern
+            .or(defaults.dash_pattern)
             .unwrap_or(ctx.config.page.line_dash_pattern);
 
-        // Set layer configurations before adding the circle
-        ctx.layer.set_fill_color(fill_color.into());
-        ctx.layer.set_outline_color(outline_color.into());
-        ctx.layer.set_outline_thickness(outline_thickness);
-        ctx.layer.set_line_cap_style(line_cap_style.into());
-        ctx.layer.set_line_join_style(line_join_style.into());
-        ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
-
-        ctx.layer.add_polygon(Polygon {
-            rings: vec![printpdf_calculate_points_for_circle(
-                self.radius,
-                self.center.x,
-                self.center.y,
-            )],
-            mode: self.mode.unwrap_or_default().into(),
-            winding_order: self.order.unwrap_or_default().into(),
+        ctx.with_alpha(fill_color.alpha(), outline_color.alpha(), || {
+            // Set layer configurations before adding the circle
+            ctx.layer
+                .set_fill_color(ctx.config.page.draw_color(fill_color).into());
+            ctx.layer
+                .set_outline_color(ctx.config.page.draw_color(outline_color).into());
+            ctx.layer.set_outline_thickness(outline_thickness);
+            ctx.layer.set_line_cap_style(line_cap_style.into());
+            ctx.layer.set_line_join_style(line_join_style.into());
+            ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
+
+            ctx.layer.add_polygon(Polygon {
+                rings: vec![printpdf_calculate_points_for_circle(
+                    self.radius,
+                    self.center.x,
+                    self.center.y,
+                )],
+                mode: self.mode.unwrap_or_default().into(),
+                winding_order: self.order.unwrap_or_default().into(),
+            });
         });
     }
 }
@@ -127,13 +151,48 @@ impl PdfObjectCircle {
 impl<'lua> IntoLua<'lua> for PdfObjectCircle {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.circle", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.align_to(bounds, align.to_v_h());
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |_, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.bounds();
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.align_to(region, align);
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         // Add properties as extra named fields
         table.raw_set("type", PdfObjectType::Circle)?;
         table.raw_set("center", self.center)?;
         table.raw_set("radius", self.radius.0)?;
         table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("fill_color", self.fill_color)?;
         table.raw_set("outline_color", self.outline_color)?;
         table.raw_set("outline_thickness", self.outline_thickness)?;
@@ -143,48 +202,66 @@ impl<'lua> IntoLua<'lua> for PdfObjectCircle {
         table.raw_set("cap_style", self.cap_style)?;
         table.raw_set("join_style", self.join_style)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.align_to(bounds, align.to_v_h());
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
-        )?;
+        table.raw_set("shadow", self.shadow)?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of circle objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "center",
+    "radius",
+    "depth",
+    "layer",
+    "fill_color",
+    "outline_color",
+    "outline_thickness",
+    "mode",
+    "order",
+    "dash_pattern",
+    "cap_style",
+    "join_style",
+    "link",
+    "shadow",
+    "hidden",
+    "tag",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectCircle {
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
-            LuaValue::Table(table) => Ok(Self {
-                center: table
-                    .raw_get_ext::<_, Option<_>>("center")?
-                    .unwrap_or_default(),
-                radius: Mm(table
-                    .raw_get_ext::<_, Option<_>>("radius")?
-                    .unwrap_or_default()),
-                depth: table.raw_get_ext("depth")?,
-                fill_color: table.raw_get_ext("fill_color")?,
-                outline_color: table.raw_get_ext("outline_color")?,
-                outline_thickness: table.raw_get_ext("outline_thickness")?,
-                mode: table.raw_get_ext("mode")?,
-                order: table.raw_get_ext("order")?,
-                dash_pattern: table.raw_get_ext("dash_pattern")?,
-                cap_style: table.raw_get_ext("cap_style")?,
-                join_style: table.raw_get_ext("join_style")?,
-                link: table.raw_get_ext("link")?,
-            }),
+            LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.circle", FIELDS)?;
+                Ok(Self {
+                    center: table
+                        .raw_get_ext::<_, Option<_>>("center")?
+                        .unwrap_or_default(),
+                    radius: Mm(table
+                        .raw_get_ext::<_, Option<_>>("radius")?
+                        .unwrap_or_default()),
+                    depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
+                    fill_color: table.raw_get_ext("fill_color")?,
+                    outline_color: table.raw_get_ext("outline_color")?,
+                    outline_thickness: table.raw_get_ext("outline_thickness")?,
+                    mode: table.raw_get_ext("mode")?,
+                    order: table.raw_get_ext("order")?,
+                    dash_pattern: table.raw_get_ext("dash_pattern")?,
+                    cap_style: table.raw_get_ext("cap_style")?,
+                    join_style: table.raw_get_ext("join_style")?,
+                    link: table.raw_get_ext("link")?,
+                    shadow: table.raw_get_ext("shadow")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
+                })
+            }
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "pdf.object.circle",
@@ -324,6 +401,7 @@ mod tests {
                     center = { x = 1, y = 2 },
                     radius = 3,
                     depth = 123,
+                    layer = "layer",
                     fill_color = "123456",
                     outline_color = "789abc",
                     outline_thickness = 456,
@@ -336,6 +414,9 @@ mod tests {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    shadow = { offset = { 2, -2 }, color = "112233", blur = 1 },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectCircle>()
                 .unwrap(),
@@ -343,6 +424,7 @@ mod tests {
                 center: PdfPoint::from_coords_f32(1.0, 2.0),
                 radius: Mm(3.0),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 fill_color: Some("#123456".parse().unwrap()),
                 outline_color: Some("#789ABC".parse().unwrap()),
                 outline_thickness: Some(456.0),
@@ -354,6 +436,13 @@ mod tests {
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                shadow: Some(PdfShadow {
+                    offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                    color: "#112233".parse().unwrap(),
+                    blur: 1.0,
+                }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
 
@@ -380,6 +469,7 @@ mod tests {
                     center = { x = 1, y = 2 },
                     radius = 3,
                     depth = 123,
+                    layer = "layer",
                     fill_color = "123456",
                     outline_color = "789ABC",
                     outline_thickness = 456,
@@ -392,6 +482,9 @@ mod tests {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    shadow = { offset = { 2, -2 }, color = "112233", blur = 1 },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectCircle>()
                 .unwrap(),
@@ -399,6 +492,7 @@ mod tests {
                 center: PdfPoint::from_coords_f32(1.0, 2.0),
                 radius: Mm(3.0),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 fill_color: Some("#123456".parse().unwrap()),
                 outline_color: Some("#789ABC".parse().unwrap()),
                 outline_thickness: Some(456.0),
@@ -410,6 +504,13 @@ mod tests {
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                shadow: Some(PdfShadow {
+                    offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                    color: "#112233".parse().unwrap(),
+                    blur: 1.0,
+                }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
     }
@@ -438,6 +539,7 @@ mod tests {
             center: PdfPoint::from_coords_f32(1.0, 2.0),
             radius: Mm(3.0),
             depth: Some(123),
+            layer: Some(String::from("layer")),
             fill_color: Some("#123456".parse().unwrap()),
             outline_color: Some("#789ABC".parse().unwrap()),
             outline_thickness: Some(456.0),
@@ -449,6 +551,13 @@ mod tests {
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            shadow: Some(PdfShadow {
+                offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                color: "#112233".parse().unwrap(),
+                blur: 1.0,
+            }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
         };
 
         lua.load(chunk! {
@@ -457,6 +566,7 @@ mod tests {
                 center = { x = 1, y = 2 },
                 radius = 3,
                 depth = 123,
+                layer = "layer",
                 fill_color = { red = 18, green = 52, blue = 86 },
                 outline_color = { red = 120, green = 154, blue = 188 },
                 outline_thickness = 456,
@@ -469,6 +579,13 @@ mod tests {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                shadow = {
+                    offset = { x = 2, y = -2 },
+                    color = { red = 17, green = 34, blue = 51 },
+                    blur = 1,
+                },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
@@ -1,20 +1,40 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
 use crate::pdf::{
-    PdfAlign, PdfBounds, PdfContext, PdfHorizontalAlign, PdfLink, PdfLinkAnnotation, PdfLuaExt,
-    PdfLuaTableExt, PdfObject, PdfObjectType, PdfVerticalAlign,
+    lua_clone, PdfAlign, PdfBounds, PdfConfig, PdfContext, PdfHorizontalAlign, PdfLayer, PdfLink,
+    PdfLinkAnnotation, PdfLuaExt, PdfLuaTableExt, PdfObject, PdfObjectType, PdfPlace, PdfPoint,
+    PdfStyle, PdfVerticalAlign,
 };
+use crate::runtime::RuntimeXObjectId;
 use mlua::prelude::*;
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::Object;
+use printpdf::{Mm, Pt};
 
 /// Represents a group of objects to be drawn in the PDF.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct PdfObjectGroup {
     pub objects: Vec<PdfObject>,
+    /// Default layer of objects within the group that do not specify their own.
+    pub layer: Option<String>,
     pub link: Option<PdfLink>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
+    /// Default style of objects within the group that do not specify their own.
+    pub style: PdfStyle,
+    /// Form XObject the group is drawn as, registered via `pdf.xobject.from_group`.
+    pub xobject: Option<RuntimeXObjectId>,
 }
 
 impl PdfObjectGroup {
     /// Returns bounds for the group by calculating the bounds of each object within the group and
     /// returning the minimum bounds that will contain all of them.
     pub fn bounds(&self, ctx: PdfContext) -> PdfBounds {
+        self.style.with_context(ctx, |ctx| self.styled_bounds(ctx))
+    }
+
+    /// Returns bounds for the group like [`PdfObjectGroup::bounds`], where `ctx` already
+    /// reflects the style of the group.
+    fn styled_bounds(&self, ctx: PdfContext) -> PdfBounds {
         let mut bounds = if let Some(obj) = self.objects.first() {
             obj.bounds(ctx)
         } else {
@@ -49,14 +69,34 @@ impl PdfObjectGroup {
     /// Calculates bounds from a [`Lua`] runtime, which occurs earlier than when a [`PdfContext`]
     /// is available.
     pub(crate) fn lua_bounds(&self, lua: &Lua) -> LuaResult<PdfBounds> {
+        self.lua_styled_bounds(lua, PdfStyle::default())
+    }
+
+    /// Returns bounds for the group like [`PdfObjectGroup::lua_bounds`], where `style` is
+    /// inherited from any parent groups.
+    fn lua_styled_bounds(&self, lua: &Lua, style: PdfStyle) -> LuaResult<PdfBounds> {
+        let style = self.style.or(style);
+
+        // Only text depends on the style for its bounds, so apply the font and size to a copy
+        let obj_bounds = |obj: &PdfObject| match obj {
+            PdfObject::Group(x) => x.lua_styled_bounds(lua, style),
+            PdfObject::Text(x) if x.font.is_none() || x.size.is_none() => {
+                let mut x = x.clone();
+                x.font = x.font.or(style.font);
+                x.size = x.size.or(style.size);
+                x.lua_bounds(lua)
+            }
+            obj => obj.lua_bounds(lua),
+        };
+
         let mut bounds = if let Some(obj) = self.objects.first() {
-            obj.lua_bounds(lua)?
+            obj_bounds(obj)?
         } else {
             PdfBounds::default()
         };
 
         for obj in self.objects.iter() {
-            let b = obj.lua_bounds(lua)?;
+            let b = obj_bounds(obj)?;
             if b.ll.x < bounds.ll.x {
                 bounds.ll.x = b.ll.x;
             }
@@ -88,57 +128,70 @@ impl PdfObjectGroup {
         let src_bounds = self.lua_bounds(lua)?;
         let dst_bounds = src_bounds.align_to(bounds, align);
 
-        // Figure out the shift from original to new bounds
-        let x_offset = dst_bounds.ll.x - src_bounds.ll.x;
-        let y_offset = dst_bounds.ll.y - src_bounds.ll.y;
+        // Shift all of the objects from original to new bounds
+        self.shift_by(
+            dst_bounds.ll.x - src_bounds.ll.x,
+            dst_bounds.ll.y - src_bounds.ll.y,
+        );
+
+        Ok(())
+    }
+
+    /// Moves the objects of the group by `x_offset` and `y_offset`.
+    pub fn shift_by(&mut self, x_offset: Mm, y_offset: Mm) {
+        for obj in self.objects.iter_mut() {
+            obj.shift_by(x_offset, y_offset);
+        }
+    }
+
+    /// Scales the objects of the group by a factor of `width` and `height` away from `origin`.
+    pub fn scale_by_factor(&mut self, origin: PdfPoint, width: f32, height: f32) {
+        for obj in self.objects.iter_mut() {
+            obj.scale_by_factor(origin, width, height);
+        }
+    }
+
+    /// Scales the font size of text within the group by `factor`, where `size` is the font size
+    /// of text that has no size of its own and inherits none from the group.
+    pub fn scale_text_by_factor(&mut self, factor: f32, size: f32) {
+        let size = self.style.size.unwrap_or(size);
+        if let Some(size) = self.style.size.as_mut() {
+            *size *= factor;
+        }
 
-        // Apply the changes to all of the points
         for obj in self.objects.iter_mut() {
             match obj {
-                PdfObject::Circle(obj) => {
-                    obj.center.x += x_offset;
-                    obj.center.y += y_offset;
-                }
-                PdfObject::Group(obj) => {
-                    obj.lua_align_to(lua, bounds, align)?;
-                }
-                PdfObject::Line(obj) => {
-                    for pt in obj.points.iter_mut() {
-                        pt.x += x_offset;
-                        pt.y += y_offset;
-                    }
-                }
-                PdfObject::Rect(obj) => {
-                    obj.bounds.ll.x += x_offset;
-                    obj.bounds.ur.x += x_offset;
-
-                    obj.bounds.ll.y += y_offset;
-                    obj.bounds.ur.y += y_offset;
-                }
-                PdfObject::Shape(obj) => {
-                    for pt in obj.points.iter_mut() {
-                        pt.x += x_offset;
-                        pt.y += y_offset;
-                    }
-                }
-                PdfObject::Text(obj) => {
-                    obj.point.x += x_offset;
-                    obj.point.y += y_offset;
-                }
+                PdfObject::Group(x) => x.scale_text_by_factor(factor, size),
+                PdfObject::Text(x) => x.size = Some(x.size.unwrap_or(size) * factor),
+                _ => {}
             }
         }
+    }
 
-        Ok(())
+    /// Rotates the objects of the group counter-clockwise around `origin` by `degrees`.
+    pub fn rotate_around(&mut self, origin: PdfPoint, degrees: f32) {
+        for obj in self.objects.iter_mut() {
+            obj.rotate_around(origin, degrees);
+        }
     }
 
     /// Returns a collection of link annotations.
     pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+        self.style
+            .with_context(ctx, |ctx| self.styled_link_annotations(ctx))
+    }
+
+    /// Returns a collection of link annotations like [`PdfObjectGroup::link_annotations`], where
+    /// `ctx` already reflects the style of the group.
+    fn styled_link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         // Get initial links for group overall
         let mut links = match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
-                bounds: self.bounds(ctx),
-                depth: self.depth(),
+                bounds: self.styled_bounds(ctx),
+                depth: self.depth_or(ctx.config.page.depth),
                 link,
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
             }],
             None => Vec::new(),
         };
@@ -155,11 +208,17 @@ impl PdfObjectGroup {
     /// largest depth that will include them all. This means that objects with an earlier depth
     /// will be drawn at a later position.
     pub fn depth(&self) -> i64 {
+        self.depth_or(0)
+    }
+
+    /// Returns depth for the group like [`PdfObjectGroup::depth`], using `default` for objects
+    /// without an explicit depth.
+    pub fn depth_or(&self, default: i64) -> i64 {
         self.objects
             .iter()
-            .map(|obj| obj.depth())
+            .map(|obj| obj.depth_or(default))
             .max()
-            .unwrap_or_default()
+            .unwrap_or(default)
     }
 
     /// Pushes a [`PdfObject`] into the end of the group.
@@ -167,11 +226,39 @@ impl PdfObjectGroup {
         self.objects.push(obj.into());
     }
 
-    /// Draws the object within the PDF.
+    /// Draws the object within the PDF, applying the style of the group to its objects.
     pub fn draw(&self, ctx: PdfContext<'_>) {
-        for obj in self.objects.iter() {
-            obj.draw(ctx);
+        // Groups registered as forms are stamped, moved from where they were registered
+        if let Some(id) = self.xobject {
+            if let Some(group) = ctx.xobjects.get(id) {
+                let (from, to) = (group.bounds(ctx), self.bounds(ctx));
+                let (dx, dy) = (Pt::from(to.ll.x - from.ll.x), Pt::from(to.ll.y - from.ll.y));
+                let name = ctx.xobjects.form_name(id);
+
+                ctx.layer.save_graphics_state();
+                ctx.layer.add_operation(Operation::new(
+                    "cm",
+                    vec![
+                        1.into(),
+                        0.into(),
+                        0.into(),
+                        1.into(),
+                        dx.0.into(),
+                        dy.0.into(),
+                    ],
+                ));
+                ctx.layer
+                    .add_operation(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+                ctx.layer.restore_graphics_state();
+                return;
+            }
         }
+
+        self.style.with_context(ctx, |ctx| {
+            for obj in self.objects.iter() {
+                obj.draw(ctx);
+            }
+        })
     }
 
     /// Returns an iterator over the objects grouped together.
@@ -216,7 +303,12 @@ impl FromIterator<PdfObject> for PdfObjectGroup {
     fn from_iter<I: IntoIterator<Item = PdfObject>>(iter: I) -> Self {
         Self {
             objects: iter.into_iter().collect(),
+            layer: None,
             link: None,
+            hidden: None,
+            tag: None,
+            style: PdfStyle::default(),
+            xobject: None,
         }
     }
 }
@@ -224,42 +316,177 @@ impl FromIterator<PdfObject> for PdfObjectGroup {
 impl<'lua> IntoLua<'lua> for PdfObjectGroup {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.group", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |lua, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.lua_align_to(lua, bounds, align.to_v_h())?;
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |lua, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.lua_bounds(lua)?;
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.lua_align_to(lua, region, align)?;
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |lua, this: Self| this.lua_bounds(lua))?,
+            )?;
+
+            metatable.raw_set(
+                "move_to",
+                lua.create_function(move |lua, (mut this, opts): (Self, Option<LuaTable>)| {
+                    if let Some(opts) = opts {
+                        let bounds = this.lua_bounds(lua)?;
+                        let x = opts
+                            .raw_get_ext::<_, Option<f32>>("x")?
+                            .map(Mm)
+                            .unwrap_or(bounds.ll.x);
+                        let y = opts
+                            .raw_get_ext::<_, Option<f32>>("y")?
+                            .map(Mm)
+                            .unwrap_or(bounds.ll.y);
+
+                        this.shift_by(x - bounds.ll.x, y - bounds.ll.y);
+                    }
+
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "shift_by",
+                lua.create_function(move |_, (mut this, opts): (Self, Option<LuaTable>)| {
+                    if let Some(opts) = opts {
+                        let x = opts
+                            .raw_get_ext::<_, Option<f32>>("x")?
+                            .map(Mm)
+                            .unwrap_or_default();
+                        let y = opts
+                            .raw_get_ext::<_, Option<f32>>("y")?
+                            .map(Mm)
+                            .unwrap_or_default();
+
+                        this.shift_by(x, y);
+                    }
+
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "scale_by_factor",
+                lua.create_function(move |lua, (mut this, opts): (Self, Option<LuaTable>)| {
+                    if let Some(opts) = opts {
+                        let width = opts.raw_get_ext::<_, Option<f32>>("width")?.unwrap_or(1.0);
+                        let height = opts.raw_get_ext::<_, Option<f32>>("height")?.unwrap_or(1.0);
+                        let text = opts.raw_get_ext::<_, Option<bool>>("text")?.unwrap_or(true);
+
+                        // Like bounds, the lower-left of the group stays in place
+                        let bounds = this.lua_bounds(lua)?;
+                        this.scale_by_factor(bounds.ll, width, height);
+
+                        // Text grows by the smaller factor, like circles, so it keeps its proportions
+                        if text {
+                            let size = lua
+                                .globals()
+                                .raw_get::<_, PdfConfig>(GLOBAL_PDF_VAR_NAME)?
+                                .page
+                                .font_size;
+                            this.scale_text_by_factor(width.abs().min(height.abs()), size);
+                        }
+                    }
+
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "rotate",
+                lua.create_function(
+                    move |lua, (mut this, degrees, origin): (Self, f32, Option<PdfPoint>)| {
+                        let origin = match origin {
+                            Some(origin) => origin,
+                            None => {
+                                let bounds = this.lua_bounds(lua)?;
+                                bounds.ll.midpoint(bounds.ur)
+                            }
+                        };
+
+                        this.rotate_around(origin, degrees);
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         for obj in self.objects {
             table.raw_push(obj)?;
         }
 
         table.raw_set("type", PdfObjectType::Group)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |lua, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.lua_align_to(lua, bounds, align.to_v_h())?;
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |lua, this: Self| this.lua_bounds(lua))?,
-        )?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
+        table.raw_set("xobject", self.xobject)?;
+        self.style.add_to_table(&table)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of group objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "layer",
+    "link",
+    "hidden",
+    "tag",
+    "xobject",
+    "fill_color",
+    "outline_color",
+    "outline_thickness",
+    "font",
+    "size",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectGroup {
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
-            LuaValue::Table(table) => Ok(Self {
-                objects: table.clone().sequence_values().collect::<LuaResult<_>>()?,
-                link: table.raw_get_ext("link")?,
-            }),
+            LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.group", FIELDS)?;
+                Ok(Self {
+                    objects: table.clone().sequence_values().collect::<LuaResult<_>>()?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
+                    link: table.raw_get_ext("link")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
+                    style: PdfStyle::from_table(&table)?,
+                    xobject: table.raw_get_ext("xobject")?,
+                })
+            }
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "pdf.object.group",
@@ -273,7 +500,7 @@ impl<'lua> FromLua<'lua> for PdfObjectGroup {
 mod tests {
     use super::*;
     use crate::pdf::{Pdf, PdfConfig, PdfObjectRect, PdfObjectText, PdfPoint};
-    use crate::runtime::RuntimeFonts;
+    use crate::runtime::{RuntimeAlpha, RuntimeFonts, RuntimeToc, RuntimeXObjects};
     use mlua::chunk;
     use printpdf::{Mm, PdfDocument};
 
@@ -340,6 +567,10 @@ mod tests {
             layer: &layer,
             fonts: &font,
             fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
         };
 
         // Calculate the bounds of the group
@@ -399,6 +630,188 @@ mod tests {
         .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_apply_group_style_to_objects_without_their_own_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        // Text without a size inherits the size of the group (or an outer group), while text
+        // with a size keeps its own
+        lua.load(chunk! {
+            local styled = pdf.object.group({
+                pdf.object.text({ x = 0, y = 0, text = "hello world" }),
+                size = 36.0,
+            })
+            pdf.utils.assert_deep_equal(styled:bounds(), {
+                ll = { x = 0,                   y = -3.810002326965332  },
+                ur = { x = 83.82005310058594,   y = 12.954007148742676  },
+            })
+
+            local nested = pdf.object.group({ styled, size = 12.0, fill_color = "#123456" })
+            pdf.utils.assert_deep_equal(nested:bounds(), styled:bounds())
+
+            local inherited = pdf.object.group({
+                pdf.object.group({ pdf.object.text({ x = 0, y = 0, text = "hello world" }) }),
+                size = 36.0,
+            })
+            pdf.utils.assert_deep_equal(inherited:bounds(), styled:bounds())
+
+            local overridden = pdf.object.group({
+                pdf.object.text({ x = 0, y = 0, text = "hello world", size = 36.0 }),
+                size = 12.0,
+            })
+            pdf.utils.assert_deep_equal(overridden:bounds(), styled:bounds())
+
+            // Style fields are retained on the group
+            pdf.utils.assert_deep_equal(nested.size, 12.0)
+            pdf.utils.assert_deep_equal(tostring(nested.fill_color), "123456")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_clone_group_with_overrides_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local group = pdf.object.group({
+                pdf.object.rect({ ll = { 0, 0 }, ur = { 10, 10 } }),
+                tag = "cell",
+            })
+
+            // Objects within the clone are copies that can be changed independently
+            local clone = group:clone({ tag = "today" })
+            clone[1].depth = 3
+            pdf.utils.assert_deep_equal(clone.tag, "today")
+            pdf.utils.assert_deep_equal(group.tag, "cell")
+            pdf.utils.assert_deep_equal(group[1].depth, nil)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_move_shift_scale_and_rotate_group_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local group = pdf.object.group({
+                pdf.object.rect({ ll = { 0, 0 }, ur = { 10, 10 } }),
+                pdf.object.group({
+                    pdf.object.line({ { 10, 10 }, { 20, 10 } }),
+                    pdf.object.circle({ center = { 20, 20 }, radius = 2 }),
+                }),
+            })
+
+            // Nested objects move alongside the rest of the group
+            group = group:move_to({ x = 5, y = 5 })
+            pdf.utils.assert_deep_equal(group:bounds():with_precision(3), {
+                ll = { x = 5,  y = 5 },
+                ur = { x = 27, y = 27 },
+            })
+            pdf.utils.assert_deep_equal(group[2][2].center, { x = 25, y = 25 })
+
+            group = group:shift_by({ x = -5 })
+            pdf.utils.assert_deep_equal(group:bounds():with_precision(3), {
+                ll = { x = 0,  y = 5 },
+                ur = { x = 22, y = 27 },
+            })
+
+            // Scaling keeps the lower-left in place, resizing circles by the smaller factor
+            group = group:scale_by_factor({ width = 2, height = 0.5, text = false })
+            pdf.utils.assert_deep_equal(group[1]:bounds(), {
+                ll = { x = 0,  y = 5 },
+                ur = { x = 20, y = 10 },
+            })
+            pdf.utils.assert_deep_equal(group[2][1][2], { x = 40, y = 10 })
+            pdf.utils.assert_deep_equal(group[2][2].radius, 1)
+
+            // Rotating defaults to around the center of the group
+            group = pdf.object.group({
+                pdf.object.line({ { 0, 0 }, { 10, 0 } }),
+            }):rotate(90)
+            pdf.utils.assert_deep_equal(group[1][1]:with_precision(3), { x = 5, y = -5 })
+            pdf.utils.assert_deep_equal(group[1][2]:with_precision(3), { x = 5, y = 5 })
+
+            group = group:rotate(90, { 0, 0 })
+            pdf.utils.assert_deep_equal(group[1][1]:with_precision(3), { x = 5, y = 5 })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_scale_font_size_of_text_when_scaling_group_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        lua.load(chunk! {
+            local group = pdf.object.group({
+                pdf.object.text({ text = "one", size = 10 }),
+                pdf.object.text({ text = "two" }),
+                pdf.object.group({
+                    pdf.object.text({ text = "three" }),
+                    size = 12,
+                }),
+            })
+
+            // Text without a size of its own is given the scaled default size
+            local scaled = group:scale_by_factor({ width = 2, height = 3 })
+            pdf.utils.assert_deep_equal(scaled[1].size, 20)
+            pdf.utils.assert_deep_equal(scaled[2].size, pdf.page.font_size * 2)
+            pdf.utils.assert_deep_equal(scaled[3].size, 24)
+            pdf.utils.assert_deep_equal(scaled[3][1].size, 24)
+
+            // Opting out leaves the size of text alone
+            local scaled = group:scale_by_factor({ width = 2, text = false })
+            pdf.utils.assert_deep_equal(scaled[1].size, 10)
+            pdf.utils.assert_deep_equal(scaled[2].size, nil)
+            pdf.utils.assert_deep_equal(scaled[3].size, 12)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_hide_objects_of_group_when_condition_is_false_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        let group: PdfObjectGroup = lua
+            .load(chunk! {
+                pdf.object.group({
+                    pdf.when(true, pdf.object.rect({ ll = { 0, 0 }, ur = { 1, 1 } })),
+                    pdf.when(false, pdf.object.rect({ ll = { 0, 0 }, ur = { 2, 2 } })),
+                    pdf.when(nil, pdf.object.rect({ ll = { 0, 0 }, ur = { 3, 3 } })),
+                })
+            })
+            .eval()
+            .unwrap();
+
+        let hidden: Vec<bool> = group.iter().map(PdfObject::is_hidden).collect();
+        assert_eq!(hidden, vec![false, true, true]);
+    }
+
     #[test]
     fn should_be_able_to_convert_from_lua() {
         // Can convert from empty table into a group
@@ -418,9 +831,14 @@ mod tests {
                 .unwrap(),
             PdfObjectGroup {
                 objects: Vec::new(),
+                layer: None,
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com")
-                })
+                }),
+                hidden: None,
+                tag: None,
+                style: PdfStyle::default(),
+                xobject: None,
             },
         );
 
@@ -438,7 +856,12 @@ mod tests {
                     PdfObjectRect::default().into(),
                     PdfObjectText::default().into(),
                 ],
+                layer: None,
                 link: None,
+                hidden: None,
+                tag: None,
+                style: PdfStyle::default(),
+                xobject: None,
             },
         );
 
@@ -457,9 +880,14 @@ mod tests {
                     PdfObjectRect::default().into(),
                     PdfObjectText::default().into(),
                 ],
+                layer: None,
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com")
-                })
+                }),
+                hidden: None,
+                tag: None,
+                style: PdfStyle::default(),
+                xobject: None,
             },
         );
     }
@@ -473,7 +901,12 @@ mod tests {
         // Test group with nothing
         let group = PdfObjectGroup {
             objects: vec![],
+            layer: None,
             link: None,
+            hidden: None,
+            tag: None,
+            style: PdfStyle::default(),
+            xobject: None,
         };
 
         lua.load(chunk! {
@@ -490,9 +923,14 @@ mod tests {
                 PdfObjectRect::default().into(),
                 PdfObjectText::default().into(),
             ],
+            layer: None,
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
+            style: PdfStyle::default(),
+            xobject: None,
         };
 
         lua.load(chunk! {
@@ -504,6 +942,8 @@ mod tests {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
@@ -7,12 +7,17 @@ use printpdf::Line;
 pub struct PdfObjectLine {
     pub points: Vec<PdfPoint>,
     pub depth: Option<i64>,
+    pub layer: Option<String>,
     pub color: Option<PdfColor>,
     pub thickness: Option<f32>,
     pub dash_pattern: Option<PdfLineDashPattern>,
     pub cap_style: Option<PdfLineCapStyle>,
     pub join_style: Option<PdfLineJoinStyle>,
+    pub smooth: Option<bool>,
+    pub simplify: Option<f32>,
     pub link: Option<PdfLink>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
 }
 
 impl PdfObjectLine {
@@ -71,13 +76,64 @@ impl PdfObjectLine {
         }
     }
 
+    /// Returns the points of the line after simplification, dropping points that are within the
+    /// `simplify` tolerance (in millimeters) of the line using the Douglas-Peucker algorithm.
+    pub fn simplified_points(&self) -> Vec<PdfPoint> {
+        match self.simplify {
+            Some(tolerance) if tolerance > 0.0 && self.points.len() > 2 => {
+                let mut keep = vec![false; self.points.len()];
+                keep[0] = true;
+                keep[self.points.len() - 1] = true;
+                douglas_peucker(&self.points, tolerance, &mut keep);
+
+                self.points
+                    .iter()
+                    .zip(keep)
+                    .filter_map(|(p, keep)| keep.then_some(*p))
+                    .collect()
+            }
+            _ => self.points.clone(),
+        }
+    }
+
+    /// Returns the points of the path to draw, where a point is flagged when it and the point
+    /// following it are the start and first control point of a cubic bezier curve.
+    ///
+    /// When smoothing, each segment becomes a curve through the points using Catmull-Rom
+    /// interpolation, otherwise segments are straight.
+    fn path_points(&self) -> Vec<(PdfPoint, bool)> {
+        let points = self.simplified_points();
+        if !self.smooth.unwrap_or_default() || points.len() < 3 {
+            return points.into_iter().map(|p| (p, false)).collect();
+        }
+
+        let mut path = Vec::with_capacity(points.len() * 3);
+        for i in 0..points.len() - 1 {
+            // Endpoints act as their own neighbors so the curve starts and ends at them
+            let p0 = points[i.saturating_sub(1)];
+            let (p1, p2) = (points[i], points[i + 1]);
+            let p3 = points[(i + 2).min(points.len() - 1)];
+
+            let c1 = PdfPoint::new(p1.x + (p2.x - p0.x) / 6.0, p1.y + (p2.y - p0.y) / 6.0);
+            let c2 = PdfPoint::new(p2.x - (p3.x - p1.x) / 6.0, p2.y - (p3.y - p1.y) / 6.0);
+            path.push((p1, true));
+            path.push((c1, true));
+            path.push((c2, false));
+        }
+
+        path.push((points[points.len() - 1], false));
+        path
+    }
+
     /// Returns a collection of link annotations.
-    pub fn link_annotations(&self, _ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+    pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
                 bounds: self.bounds(),
-                depth: self.depth.unwrap_or_default(),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
                 link,
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
             }],
             None => Vec::new(),
         }
@@ -85,33 +141,124 @@ impl PdfObjectLine {
 
     /// Draws the object within the PDF.
     pub fn draw(&self, ctx: PdfContext<'_>) {
-        // Get optional values, setting defaults when not specified
-        let outline_color = self.color.unwrap_or(ctx.config.page.outline_color);
-        let thickness = self.thickness.unwrap_or(ctx.config.page.outline_thickness);
-        let line_cap_style = self.cap_style.unwrap_or(ctx.config.page.line_cap_style);
-        let line_join_style = self.join_style.unwrap_or(ctx.config.page.line_join_style);
+        // Get optional values, falling back to the style, the defaults of lines, and then of pages
+        let defaults = ctx.config.page.defaults.line;
+        let outline_color = self
+            .color
+            .unwrap_or_else(|| ctx.outline_color(defaults.color));
+        let thickness = ctx.config.page.stroke_thickness(
+            self.thickness
+                .unwrap_or_else(|| ctx.outline_thickness(defaults.thickness)),
+        );
+        let line_cap_style = self
+            .cap_style
+            .or(defaults.cap_style)
+            .unwrap_or(ctx.config.page.line_cap_style);
+        let line_join_style = self
+            .join_style
+            .or(defaults.join_style)
+            .unwrap_or(ctx.config.page.line_join_style);
         let line_dash_pattern = self
             .dash_pattern
+            .or(defaults.dash_pattern)
             .unwrap_or(ctx.config.page.line_dash_pattern);
 
-        // Set layer configurations before adding the line
-        ctx.layer.set_outline_color(outline_color.into());
-        ctx.layer.set_outline_thickness(thickness);
-        ctx.layer.set_line_cap_style(line_cap_style.into());
-        ctx.layer.set_line_join_style(line_join_style.into());
-        ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
-
-        ctx.layer.add_line(Line {
-            points: self.points.iter().map(|p| ((*p).into(), false)).collect(),
-            is_closed: false,
+        ctx.with_alpha(1.0, outline_color.alpha(), || {
+            // Set layer configurations before adding the line
+            ctx.layer
+                .set_outline_color(ctx.config.page.draw_color(outline_color).into());
+            ctx.layer.set_outline_thickness(thickness);
+            ctx.layer.set_line_cap_style(line_cap_style.into());
+            ctx.layer.set_line_join_style(line_join_style.into());
+            ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
+
+            ctx.layer.add_line(Line {
+                points: self
+                    .path_points()
+                    .into_iter()
+                    .map(|(p, curve)| (ctx.config.page.snap_point(p, thickness).into(), curve))
+                    .collect(),
+                is_closed: false,
+            });
         });
     }
 }
 
+/// Marks the points between the first and last of `points` to `keep` when they are further than
+/// `tolerance` from the line between them, recursing on either side of the furthest point.
+fn douglas_peucker(points: &[PdfPoint], tolerance: f32, keep: &mut [bool]) {
+    if points.len() < 3 {
+        return;
+    }
+
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (dx, dy) = ((last.x - first.x).0, (last.y - first.y).0);
+    let len = (dx * dx + dy * dy).sqrt();
+
+    // Perpendicular distance from the line, or from the first point if the line has no length
+    let distance = |p: &PdfPoint| {
+        let (px, py) = ((p.x - first.x).0, (p.y - first.y).0);
+        if len == 0.0 {
+            (px * px + py * py).sqrt()
+        } else {
+            (dx * py - dy * px).abs() / len
+        }
+    };
+
+    let (idx, max) = points[1..points.len() - 1]
+        .iter()
+        .map(distance)
+        .enumerate()
+        .fold(
+            (0, 0.0),
+            |acc, (i, d)| if d > acc.1 { (i + 1, d) } else { acc },
+        );
+
+    if max > tolerance {
+        keep[idx] = true;
+        douglas_peucker(&points[..=idx], tolerance, &mut keep[..=idx]);
+        douglas_peucker(&points[idx..], tolerance, &mut keep[idx..]);
+    }
+}
+
 impl<'lua> IntoLua<'lua> for PdfObjectLine {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.line", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.align_to(bounds, align.to_v_h());
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |_, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.bounds();
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.align_to(region, align);
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         // Add the points as a list
         for point in self.points {
@@ -121,46 +268,63 @@ impl<'lua> IntoLua<'lua> for PdfObjectLine {
         // Add properties as extra named fields
         table.raw_set("type", PdfObjectType::Line)?;
         table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("color", self.color)?;
         table.raw_set("thickness", self.thickness)?;
         table.raw_set("dash_pattern", self.dash_pattern)?;
         table.raw_set("cap_style", self.cap_style)?;
         table.raw_set("join_style", self.join_style)?;
+        table.raw_set("smooth", self.smooth)?;
+        table.raw_set("simplify", self.simplify)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.align_to(bounds, align.to_v_h());
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
-        )?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of line objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "depth",
+    "layer",
+    "color",
+    "thickness",
+    "dash_pattern",
+    "cap_style",
+    "join_style",
+    "smooth",
+    "simplify",
+    "link",
+    "hidden",
+    "tag",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectLine {
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
-            LuaValue::Table(table) => Ok(Self {
-                points: table.clone().sequence_values().collect::<LuaResult<_>>()?,
-                depth: table.raw_get_ext("depth")?,
-                color: table.raw_get_ext("color")?,
-                thickness: table.raw_get_ext("thickness")?,
-                dash_pattern: table.raw_get_ext("dash_pattern")?,
-                cap_style: table.raw_get_ext("cap_style")?,
-                join_style: table.raw_get_ext("join_style")?,
-                link: table.raw_get_ext("link")?,
-            }),
+            LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.line", FIELDS)?;
+                Ok(Self {
+                    points: table.clone().sequence_values().collect::<LuaResult<_>>()?,
+                    depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
+                    color: table.raw_get_ext("color")?,
+                    thickness: table.raw_get_ext("thickness")?,
+                    dash_pattern: table.raw_get_ext("dash_pattern")?,
+                    cap_style: table.raw_get_ext("cap_style")?,
+                    join_style: table.raw_get_ext("join_style")?,
+                    smooth: table.raw_get_ext("smooth")?,
+                    simplify: table.raw_get_ext("simplify")?,
+                    link: table.raw_get_ext("link")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
+                })
+            }
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "pdf.object.line",
@@ -295,29 +459,39 @@ mod tests {
             Lua::new()
                 .load(chunk!({
                     depth = 123,
+                    layer = "layer",
                     color = "123456",
                     thickness = 456,
                     dash_pattern = "dashed:999",
                     cap_style = "butt",
                     join_style = "miter",
+                    smooth = true,
+                    simplify = 0.5,
                     link = {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectLine>()
                 .unwrap(),
             PdfObjectLine {
                 points: Vec::new(),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 color: Some("#123456".parse().unwrap()),
                 thickness: Some(456.0),
                 dash_pattern: Some(PdfLineDashPattern::dashed(999)),
                 cap_style: Some(PdfLineCapStyle::butt()),
                 join_style: Some(PdfLineJoinStyle::miter()),
+                smooth: Some(true),
+                simplify: Some(0.5),
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
 
@@ -346,15 +520,20 @@ mod tests {
                     { x = 1, y = 2 },
                     { x = 3, y = 4 },
                     depth = 123,
+                    layer = "layer",
                     color = "123456",
                     thickness = 456,
                     dash_pattern = "dashed:999",
                     cap_style = "butt",
                     join_style = "miter",
+                    smooth = true,
+                    simplify = 0.5,
                     link = {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectLine>()
                 .unwrap(),
@@ -364,14 +543,19 @@ mod tests {
                     PdfPoint::from_coords_f32(3.0, 4.0),
                 ],
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 color: Some("#123456".parse().unwrap()),
                 thickness: Some(456.0),
                 dash_pattern: Some(PdfLineDashPattern::dashed(999)),
                 cap_style: Some(PdfLineCapStyle::butt()),
                 join_style: Some(PdfLineJoinStyle::miter()),
+                smooth: Some(true),
+                simplify: Some(0.5),
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
     }
@@ -400,14 +584,19 @@ mod tests {
                 PdfPoint::from_coords_f32(3.0, 4.0),
             ],
             depth: Some(123),
+            layer: Some(String::from("layer")),
             color: Some("#123456".parse().unwrap()),
             thickness: Some(456.0),
             dash_pattern: Some(PdfLineDashPattern::dashed(999)),
             cap_style: Some(PdfLineCapStyle::butt()),
             join_style: Some(PdfLineJoinStyle::miter()),
+            smooth: Some(true),
+            simplify: Some(0.5),
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
         };
 
         lua.load(chunk! {
@@ -416,18 +605,82 @@ mod tests {
                 { x = 3, y = 4 },
                 type = "line",
                 depth = 123,
+                layer = "layer",
                 color = { red = 18, green = 52, blue = 86 },
                 thickness = 456,
                 dash_pattern = { offset = 0, dash_1 = 999 },
                 cap_style = "butt",
                 join_style = "miter",
+                smooth = true,
+                simplify = 0.5,
                 link = {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
         .expect("Assertion failed");
     }
+
+    #[test]
+    fn should_simplify_points_within_tolerance() {
+        let line = PdfObjectLine {
+            points: vec![
+                PdfPoint::from_coords_f32(0.0, 0.0),
+                PdfPoint::from_coords_f32(1.0, 0.1),
+                PdfPoint::from_coords_f32(2.0, -0.1),
+                PdfPoint::from_coords_f32(3.0, 5.0),
+                PdfPoint::from_coords_f32(4.0, 6.0),
+                PdfPoint::from_coords_f32(5.0, 7.0),
+            ],
+            simplify: Some(0.5),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            line.simplified_points(),
+            vec![
+                PdfPoint::from_coords_f32(0.0, 0.0),
+                PdfPoint::from_coords_f32(2.0, -0.1),
+                PdfPoint::from_coords_f32(3.0, 5.0),
+                PdfPoint::from_coords_f32(5.0, 7.0),
+            ]
+        );
+
+        // Without a tolerance, all points are kept
+        let line = PdfObjectLine {
+            simplify: None,
+            ..line
+        };
+        assert_eq!(line.simplified_points(), line.points);
+    }
+
+    #[test]
+    fn should_smooth_points_into_curves_passing_through_them() {
+        let line = PdfObjectLine {
+            points: vec![
+                PdfPoint::from_coords_f32(0.0, 0.0),
+                PdfPoint::from_coords_f32(6.0, 6.0),
+                PdfPoint::from_coords_f32(12.0, 0.0),
+            ],
+            smooth: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            line.path_points(),
+            vec![
+                (PdfPoint::from_coords_f32(0.0, 0.0), true),
+                (PdfPoint::from_coords_f32(1.0, 1.0), true),
+                (PdfPoint::from_coords_f32(4.0, 6.0), false),
+                (PdfPoint::from_coords_f32(6.0, 6.0), true),
+                (PdfPoint::from_coords_f32(8.0, 6.0), true),
+                (PdfPoint::from_coords_f32(11.0, 1.0), false),
+                (PdfPoint::from_coords_f32(12.0, 0.0), false),
+            ]
+        );
+    }
 }
@@ -7,6 +7,7 @@ use printpdf::Rect;
 pub struct PdfObjectRect {
     pub bounds: PdfBounds,
     pub depth: Option<i64>,
+    pub layer: Option<String>,
     pub fill_color: Option<PdfColor>,
     pub outline_color: Option<PdfColor>,
     pub outline_thickness: Option<f32>,
@@ -16,6 +17,9 @@ pub struct PdfObjectRect {
     pub cap_style: Option<PdfLineCapStyle>,
     pub join_style: Option<PdfLineJoinStyle>,
     pub link: Option<PdfLink>,
+    pub shadow: Option<PdfShadow>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
 }
 
 impl PdfObjectRect {
@@ -25,12 +29,14 @@ impl PdfObjectRect {
     }
 
     /// Returns a collection of link annotations.
-    pub fn link_annotations(&self, _ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+    pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
                 bounds: self.bounds,
-                depth: self.depth.unwrap_or_default(),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
                 link,
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
             }],
             None => Vec::new(),
         }
@@ -38,31 +44,55 @@ impl PdfObjectRect {
 
     /// Draws the object within the PDF.
     pub fn draw(&self, ctx: PdfContext) {
-        // Get optional values, setting defaults when not specified
-        let fill_color = self.fill_color.unwrap_or(ctx.config.page.fill_color);
-        let outline_color = self.outline_color.unwrap_or(ctx.config.page.outline_color);
-        let outline_thickness = self
-            .outline_thickness
-            .unwrap_or(ctx.config.page.outline_thickness);
-        let line_cap_style = self.cap_style.unwrap_or(ctx.config.page.line_cap_style);
-        let line_join_style = self.join_style.unwrap_or(ctx.config.page.line_join_style);
+        // Get optional values, falling back to the style, the defaults of rects, and then of pages
+        let defaults = ctx.config.page.defaults.rect;
+        let fill_color = self
+            .fill_color
+            .unwrap_or_else(|| ctx.fill_color(defaults.fill_color));
+        let outline_color = self
+            .outline_color
+            .unwrap_or_else(|| ctx.outline_color(defaults.outline_color));
+        let mode = self.mode.unwrap_or_default();
+        let outline_thickness = ctx.config.page.stroke_thickness(
+            self.outline_thickness
+                .unwrap_or_else(|| ctx.outline_thickness(defaults.outline_thickness)),
+        );
+        let line_cap_style = self
+            .cap_style
+            .or(defaults.cap_style)
+            .unwrap_or(ctx.config.page.line_cap_style);
+        let line_join_style = self
+            .join_style
+            .or(defaults.join_style)
+            .unwrap_or(ctx.config.page.line_join_style);
         let line_dash_pattern = self
             .dash_pattern
+            .or(defaults.dash_pattern)
             .unwrap_or(ctx.config.page.line_dash_pattern);
 
-        // Set layer configurations before adding the rect
-        ctx.layer.set_fill_color(fill_color.into());
-        ctx.layer.set_outline_color(outline_color.into());
-        ctx.layer.set_outline_thickness(outline_thickness);
-        ctx.layer.set_line_cap_style(line_cap_style.into());
-        ctx.layer.set_line_join_style(line_join_style.into());
-        ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
-
-        ctx.layer.add_rect(Rect {
-            ll: self.bounds.ll.into(),
-            ur: self.bounds.ur.into(),
-            mode: self.mode.unwrap_or_default().into(),
-            winding: self.order.unwrap_or_default().into(),
+        ctx.with_alpha(fill_color.alpha(), outline_color.alpha(), || {
+            // Set layer configurations before adding the rect
+            ctx.layer
+                .set_fill_color(ctx.config.page.draw_color(fill_color).into());
+            ctx.layer
+                .set_outline_color(ctx.config.page.draw_color(outline_color).into());
+            ctx.layer.set_outline_thickness(outline_thickness);
+            ctx.layer.set_line_cap_style(line_cap_style.into());
+            ctx.layer.set_line_join_style(line_join_style.into());
+            ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
+
+            let bounds = if mode.is_stroke() {
+                ctx.config.page.snap_bounds(self.bounds, outline_thickness)
+            } else {
+                self.bounds
+            };
+
+            ctx.layer.add_rect(Rect {
+                ll: bounds.ll.into(),
+                ur: bounds.ur.into(),
+                mode: mode.into(),
+                winding: self.order.unwrap_or_default().into(),
+            });
         });
     }
 }
@@ -70,11 +100,59 @@ impl PdfObjectRect {
 impl<'lua> IntoLua<'lua> for PdfObjectRect {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.rect", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.align_to(bounds, align.to_v_h());
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |_, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.bounds;
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.align_to(region, align);
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |_, this: Self| Ok(this.bounds))?,
+            )?;
+
+            metatable.raw_set(
+                "with_bounds",
+                lua.create_function(move |_, (mut this, bounds): (Self, Option<PdfBounds>)| {
+                    match bounds {
+                        Some(bounds) => {
+                            this.bounds = bounds;
+                            Ok(this)
+                        }
+                        None => Ok(this),
+                    }
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         self.bounds.add_to_table(&table)?;
         table.raw_set("type", PdfObjectType::Rect)?;
         table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("fill_color", self.fill_color)?;
         table.raw_set("outline_color", self.outline_color)?;
         table.raw_set("outline_thickness", self.outline_thickness)?;
@@ -84,44 +162,42 @@ impl<'lua> IntoLua<'lua> for PdfObjectRect {
         table.raw_set("cap_style", self.cap_style)?;
         table.raw_set("join_style", self.join_style)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.align_to(bounds, align.to_v_h());
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |_, this: Self| Ok(this.bounds))?,
-        )?;
-
-        metatable.raw_set(
-            "with_bounds",
-            lua.create_function(
-                move |_, (mut this, bounds): (Self, Option<PdfBounds>)| match bounds {
-                    Some(bounds) => {
-                        this.bounds = bounds;
-                        Ok(this)
-                    }
-                    None => Ok(this),
-                },
-            )?,
-        )?;
+        table.raw_set("shadow", self.shadow)?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of rect objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "ll",
+    "ur",
+    "depth",
+    "layer",
+    "fill_color",
+    "outline_color",
+    "outline_thickness",
+    "mode",
+    "order",
+    "dash_pattern",
+    "cap_style",
+    "join_style",
+    "link",
+    "shadow",
+    "hidden",
+    "tag",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectRect {
     #[inline]
     fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
             LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.rect", FIELDS)?;
+
                 // Support missing bounds converting into default bounds
                 //
                 // TODO: This will result in invalid bounds becoming default bounds.
@@ -132,6 +208,9 @@ impl<'lua> FromLua<'lua> for PdfObjectRect {
                 Ok(Self {
                     bounds,
                     depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
                     fill_color: table.raw_get_ext("fill_color")?,
                     outline_color: table.raw_get_ext("outline_color")?,
                     outline_thickness: table.raw_get_ext("outline_thickness")?,
@@ -141,6 +220,9 @@ impl<'lua> FromLua<'lua> for PdfObjectRect {
                     cap_style: table.raw_get_ext("cap_style")?,
                     join_style: table.raw_get_ext("join_style")?,
                     link: table.raw_get_ext("link")?,
+                    shadow: table.raw_get_ext("shadow")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
                 })
             }
             _ => Err(LuaError::FromLuaConversionError {
@@ -252,6 +334,39 @@ mod tests {
         .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_be_able_to_clone_rect_with_overrides_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local rect = pdf.object.rect({
+                ll = { x = 1, y = 2 },
+                ur = { x = 3, y = 4 },
+                fill_color = "#123456",
+            })
+
+            // Cloning without overrides produces an identical rect
+            pdf.utils.assert_deep_equal(rect:clone(), rect)
+
+            // Overrides replace fields of the original, keeping the rest
+            local moved = rect:clone({ ll = { x = 0, y = 0 }, depth = 5 })
+            pdf.utils.assert_deep_equal(moved:bounds(), {
+                ll = { x = 0, y = 0 },
+                ur = { x = 3, y = 4 },
+            })
+            pdf.utils.assert_deep_equal(moved.depth, 5)
+            pdf.utils.assert_deep_equal(moved.fill_color, rect.fill_color)
+
+            // Original is unchanged
+            pdf.utils.assert_deep_equal(rect.depth, nil)
+            pdf.utils.assert_deep_equal(rect:bounds().ll, { x = 1, y = 2 })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_convert_from_lua() {
         // Can convert from empty table into a rect
@@ -303,6 +418,7 @@ mod tests {
                     ll = { x = 1, y = 2 },
                     ur = { x = 3, y = 4 },
                     depth = 123,
+                    layer = "layer",
                     fill_color = "123456",
                     outline_color = "789ABC",
                     outline_thickness = 456,
@@ -315,12 +431,16 @@ mod tests {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    shadow = { offset = { 2, -2 }, color = "112233", blur = 1 },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectRect>()
                 .unwrap(),
             PdfObjectRect {
                 bounds: PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 fill_color: Some("#123456".parse().unwrap()),
                 outline_color: Some("#789ABC".parse().unwrap()),
                 outline_thickness: Some(456.0),
@@ -332,6 +452,13 @@ mod tests {
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                shadow: Some(PdfShadow {
+                    offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                    color: "#112233".parse().unwrap(),
+                    blur: 1.0,
+                }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
     }
@@ -359,6 +486,7 @@ mod tests {
         let rect = PdfObjectRect {
             bounds: PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0),
             depth: Some(123),
+            layer: Some(String::from("layer")),
             fill_color: Some("#123456".parse().unwrap()),
             outline_color: Some("#789ABC".parse().unwrap()),
             outline_thickness: Some(456.0),
@@ -370,6 +498,13 @@ mod tests {
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            shadow: Some(PdfShadow {
+                offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                color: "#112233".parse().unwrap(),
+                blur: 1.0,
+            }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
         };
 
         lua.load(chunk! {
@@ -378,6 +513,7 @@ mod tests {
                 ll = { x = 1, y = 2 },
                 ur = { x = 3, y = 4 },
                 depth = 123,
+                layer = "layer",
                 fill_color = { red = 18, green = 52, blue = 86 },
                 outline_color = { red = 120, green = 154, blue = 188 },
                 outline_thickness = 456,
@@ -390,6 +526,13 @@ mod tests {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                shadow = {
+                    offset = { x = 2, y = -2 },
+                    color = { red = 17, green = 34, blue = 51 },
+                    blur = 1,
+                },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
@@ -1,12 +1,13 @@
 use crate::pdf::*;
 use mlua::prelude::*;
-use printpdf::Polygon;
+use printpdf::{LineJoinStyle, Mm, Polygon};
 
 /// Represents a polygonal shape to be drawn in the PDF.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct PdfObjectShape {
     pub points: Vec<PdfPoint>,
     pub depth: Option<i64>,
+    pub layer: Option<String>,
     pub fill_color: Option<PdfColor>,
     pub outline_color: Option<PdfColor>,
     pub outline_thickness: Option<f32>,
@@ -16,9 +17,61 @@ pub struct PdfObjectShape {
     pub cap_style: Option<PdfLineCapStyle>,
     pub join_style: Option<PdfLineJoinStyle>,
     pub link: Option<PdfLink>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
 }
 
 impl PdfObjectShape {
+    /// Returns the points of a regular polygon with `sides` sides whose vertices lie on a circle
+    /// at `center` with `radius`, rotated counter-clockwise by `rotation` degrees.
+    ///
+    /// The first vertex is placed at the top of the circle, with the rest following clockwise.
+    pub fn regular_points(
+        center: PdfPoint,
+        radius: f32,
+        sides: usize,
+        rotation: f32,
+    ) -> Vec<PdfPoint> {
+        Self::radial_points(center, &[radius], sides, rotation)
+    }
+
+    /// Returns the points of a star with `points` tips on a circle at `center` with `radius`,
+    /// whose inner vertices lie on a circle with `inner_radius`, rotated counter-clockwise by
+    /// `rotation` degrees.
+    ///
+    /// The first tip is placed at the top of the circle, with the rest following clockwise.
+    pub fn star_points(
+        center: PdfPoint,
+        radius: f32,
+        inner_radius: f32,
+        points: usize,
+        rotation: f32,
+    ) -> Vec<PdfPoint> {
+        Self::radial_points(center, &[radius, inner_radius], points, rotation)
+    }
+
+    /// Returns `count` groups of evenly-spaced points around `center`, where each group has a
+    /// point for each of `radii`, starting at the top and moving clockwise.
+    fn radial_points(
+        center: PdfPoint,
+        radii: &[f32],
+        count: usize,
+        rotation: f32,
+    ) -> Vec<PdfPoint> {
+        let total = count * radii.len();
+        let start = std::f32::consts::FRAC_PI_2 + rotation.to_radians();
+        (0..total)
+            .map(|i| {
+                let angle = start - std::f32::consts::TAU * i as f32 / total as f32;
+                let radius = radii[i % radii.len()];
+                PdfPoint::from_coords_f32(
+                    center.x.0 + radius * angle.cos(),
+                    center.y.0 + radius * angle.sin(),
+                )
+            })
+            .collect()
+    }
+
     /// Returns bounds for the shape by getting the lower and upper point ranges.
     pub fn bounds(&self) -> PdfBounds {
         // Set default for lower-left and upper-right to our first point, or
@@ -57,6 +110,97 @@ impl PdfObjectShape {
         PdfBounds::new(ll, ur)
     }
 
+    /// Returns a copy of the shape grown outward by `distance`, or shrunk inward when `distance`
+    /// is negative, using `join` to fill the gaps left at corners.
+    ///
+    /// Miter joins extend the edges until they meet (falling back to a bevel for corners sharper
+    /// than the miter limit), round joins add an arc around the corner, and limit joins bevel the
+    /// corner with a straight edge. Shrinking a shape by more than it can fit is not detected and
+    /// results in a self-intersecting shape.
+    pub fn offset(&self, distance: Mm, join: PdfLineJoinStyle) -> Self {
+        /// Ratio of miter length to offset distance past which corners are beveled instead.
+        const MITER_LIMIT: f32 = 4.0;
+
+        // Drop repeated points (including a closing point) as they have no edge direction
+        let mut points: Vec<(f32, f32)> = Vec::with_capacity(self.points.len());
+        for point in self.points.iter().map(PdfPoint::to_coords_f32) {
+            if points.last() != Some(&point) {
+                points.push(point);
+            }
+        }
+        while points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        if points.len() < 3 || distance.0 == 0.0 {
+            return self.clone();
+        }
+
+        // Signed area tells us the winding of the points, and therefore which side is outward
+        let area: f32 = (0..points.len())
+            .map(|i| {
+                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum();
+        let d = if area < 0.0 { -distance.0 } else { distance.0 };
+
+        // Unit normal pointing to the right of the edge from a to b
+        let normal = |a: (f32, f32), b: (f32, f32)| {
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let len = (dx * dx + dy * dy).sqrt();
+            (dy / len, -dx / len)
+        };
+
+        let mut offset = Vec::new();
+        for i in 0..points.len() {
+            let prev = points[(i + points.len() - 1) % points.len()];
+            let curr = points[i];
+            let next = points[(i + 1) % points.len()];
+            let n1 = normal(prev, curr);
+            let n2 = normal(curr, next);
+            let at = |n: (f32, f32)| PdfPoint::from_coords_f32(curr.0 + n.0 * d, curr.1 + n.1 * d);
+
+            // Corners where the offset edges separate need a join, while others meet at a point
+            let cross =
+                (curr.0 - prev.0) * (next.1 - curr.1) - (curr.1 - prev.1) * (next.0 - curr.0);
+            let separates = cross * d > 0.0;
+            let dot = n1.0 * n2.0 + n1.1 * n2.1;
+            let miter = (1.0 + dot > f32::EPSILON)
+                .then(|| ((n1.0 + n2.0) / (1.0 + dot), (n1.1 + n2.1) / (1.0 + dot)))
+                .filter(|m| !separates || (m.0 * m.0 + m.1 * m.1).sqrt() <= MITER_LIMIT);
+
+            match (separates, LineJoinStyle::from(join), miter) {
+                (false, _, Some(m)) | (true, LineJoinStyle::Miter, Some(m)) => offset.push(at(m)),
+                (true, LineJoinStyle::Round, _) => {
+                    let start = n1.1.atan2(n1.0);
+                    let mut sweep = n2.1.atan2(n2.0) - start;
+                    if sweep > std::f32::consts::PI {
+                        sweep -= std::f32::consts::TAU;
+                    } else if sweep < -std::f32::consts::PI {
+                        sweep += std::f32::consts::TAU;
+                    }
+
+                    // Add a point every five degrees along the arc
+                    let steps = (sweep.abs() / 5.0_f32.to_radians()).ceil().max(1.0) as usize;
+                    for step in 0..=steps {
+                        let angle = start + sweep * step as f32 / steps as f32;
+                        offset.push(at((angle.cos(), angle.sin())));
+                    }
+                }
+                _ => {
+                    offset.push(at(n1));
+                    offset.push(at(n2));
+                }
+            }
+        }
+
+        Self {
+            points: offset,
+            ..self.clone()
+        }
+    }
+
     /// Aligns the shape to a set of bounds.
     pub fn align_to(&mut self, bounds: PdfBounds, align: (PdfVerticalAlign, PdfHorizontalAlign)) {
         // Get new bounds for series of points
@@ -75,12 +219,14 @@ impl PdfObjectShape {
     }
 
     /// Returns a collection of link annotations.
-    pub fn link_annotations(&self, _ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+    pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
                 bounds: self.bounds(),
-                depth: self.depth.unwrap_or_default(),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
                 link,
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
             }],
             None => Vec::new(),
         }
@@ -88,30 +234,60 @@ impl PdfObjectShape {
 
     /// Draws the object within the PDF.
     pub fn draw(&self, ctx: PdfContext) {
-        // Get optional values, setting defaults when not specified
-        let fill_color = self.fill_color.unwrap_or(ctx.config.page.fill_color);
-        let outline_color = self.fill_color.unwrap_or(ctx.config.page.outline_color);
-        let outline_thickness = self
-            .outline_thickness
-            .unwrap_or(ctx.config.page.outline_thickness);
-        let line_cap_style = self.cap_style.unwrap_or(ctx.config.page.line_cap_style);
-        let line_join_style = self.join_style.unwrap_or(ctx.config.page.line_join_style);
+        // Get optional values, falling back to the style, the defaults of shapes, and then of pages
+        let defaults = ctx.config.page.defaults.shape;
+        let fill_color = self
+            .fill_color
+            .unwrap_or_else(|| ctx.fill_color(defaults.fill_color));
+        let outline_color = self
+            .outline_color
+            .unwrap_or_else(|| ctx.outline_color(defaults.outline_color));
+        let mode = self.mode.unwrap_or_default();
+        let outline_thickness = ctx.config.page.stroke_thickness(
+            self.outline_thickness
+                .unwrap_or_else(|| ctx.outline_thickness(defaults.outline_thickness)),
+        );
+        let line_cap_style = self
+            .cap_style
+            .or(defaults.cap_style)
+            .unwrap_or(ctx.config.page.line_cap_style);
+        let line_join_style = self
+            .join_style
+            .or(defaults.join_style)
+            .unwrap_or(ctx.config.page.line_join_style);
         let line_dash_pattern = self
             .dash_pattern
+            .or(defaults.dash_pattern)
             .unwrap_or(ctx.config.page.line_dash_pattern);
 
-        // Set layer configurations before adding the shape
-        ctx.layer.set_fill_color(fill_color.into());
-        ctx.layer.set_outline_color(outline_color.into());
-        ctx.layer.set_outline_thickness(outline_thickness);
-        ctx.layer.set_line_cap_style(line_cap_style.into());
-        ctx.layer.set_line_join_style(line_join_style.into());
-        ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
-
-        ctx.layer.add_polygon(Polygon {
-            rings: vec![self.points.iter().map(|p| ((*p).into(), false)).collect()],
-            mode: self.mode.unwrap_or_default().into(),
-            winding_order: self.order.unwrap_or_default().into(),
+        ctx.with_alpha(fill_color.alpha(), outline_color.alpha(), || {
+            // Set layer configurations before adding the shape
+            ctx.layer
+                .set_fill_color(ctx.config.page.draw_color(fill_color).into());
+            ctx.layer
+                .set_outline_color(ctx.config.page.draw_color(outline_color).into());
+            ctx.layer.set_outline_thickness(outline_thickness);
+            ctx.layer.set_line_cap_style(line_cap_style.into());
+            ctx.layer.set_line_join_style(line_join_style.into());
+            ctx.layer.set_line_dash_pattern(line_dash_pattern.into());
+
+            ctx.layer.add_polygon(Polygon {
+                rings: vec![self
+                    .points
+                    .iter()
+                    .map(|p| {
+                        let p = if mode.is_stroke() {
+                            ctx.config.page.snap_point(*p, outline_thickness)
+                        } else {
+                            *p
+                        };
+
+                        (p.into(), false)
+                    })
+                    .collect()],
+                mode: mode.into(),
+                winding_order: self.order.unwrap_or_default().into(),
+            });
         });
     }
 }
@@ -119,7 +295,50 @@ impl PdfObjectShape {
 impl<'lua> IntoLua<'lua> for PdfObjectShape {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.shape", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.align_to(bounds, align.to_v_h());
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |_, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.bounds();
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.align_to(region, align);
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
+            )?;
+
+            metatable.raw_set(
+                "offset",
+                lua.create_function(
+                    move |_, (this, distance, join): (Self, f32, Option<PdfLineJoinStyle>)| {
+                        Ok(this.offset(Mm(distance), join.unwrap_or_else(PdfLineJoinStyle::miter)))
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         // Add the points as a list
         for point in self.points {
@@ -129,6 +348,7 @@ impl<'lua> IntoLua<'lua> for PdfObjectShape {
         // Add properties as extra named fields
         table.raw_set("type", PdfObjectType::Shape)?;
         table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("fill_color", self.fill_color)?;
         table.raw_set("outline_color", self.outline_color)?;
         table.raw_set("outline_thickness", self.outline_thickness)?;
@@ -138,43 +358,56 @@ impl<'lua> IntoLua<'lua> for PdfObjectShape {
         table.raw_set("cap_style", self.cap_style)?;
         table.raw_set("join_style", self.join_style)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.align_to(bounds, align.to_v_h());
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |_, this: Self| Ok(this.bounds()))?,
-        )?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of shape objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "depth",
+    "layer",
+    "fill_color",
+    "outline_color",
+    "outline_thickness",
+    "mode",
+    "order",
+    "dash_pattern",
+    "cap_style",
+    "join_style",
+    "link",
+    "hidden",
+    "tag",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectShape {
     #[inline]
-    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
-            LuaValue::Table(table) => Ok(Self {
-                points: table.clone().sequence_values().collect::<LuaResult<_>>()?,
-                depth: table.raw_get_ext("depth")?,
-                fill_color: table.raw_get_ext("fill_color")?,
-                outline_color: table.raw_get_ext("outline_color")?,
-                outline_thickness: table.raw_get_ext("outline_thickness")?,
-                mode: table.raw_get_ext("mode")?,
-                order: table.raw_get_ext("order")?,
-                dash_pattern: table.raw_get_ext("dash_pattern")?,
-                cap_style: table.raw_get_ext("cap_style")?,
-                join_style: table.raw_get_ext("join_style")?,
-                link: table.raw_get_ext("link")?,
-            }),
+            LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.shape", FIELDS)?;
+                Ok(Self {
+                    points: table.clone().sequence_values().collect::<LuaResult<_>>()?,
+                    depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
+                    fill_color: table.raw_get_ext("fill_color")?,
+                    outline_color: table.raw_get_ext("outline_color")?,
+                    outline_thickness: table.raw_get_ext("outline_thickness")?,
+                    mode: table.raw_get_ext("mode")?,
+                    order: table.raw_get_ext("order")?,
+                    dash_pattern: table.raw_get_ext("dash_pattern")?,
+                    cap_style: table.raw_get_ext("cap_style")?,
+                    join_style: table.raw_get_ext("join_style")?,
+                    link: table.raw_get_ext("link")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
+                })
+            }
             _ => Err(LuaError::FromLuaConversionError {
                 from: value.type_name(),
                 to: "pdf.object.shape",
@@ -226,6 +459,152 @@ mod tests {
         .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_be_able_to_calculate_points_of_regular_shapes_and_stars() {
+        let center = PdfPoint::from_coords_f32(10.0, 10.0);
+        let to_coords = |points: Vec<PdfPoint>| {
+            points
+                .into_iter()
+                .map(|p| p.to_precision(3).to_coords_f32())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            to_coords(PdfObjectShape::regular_points(center, 5.0, 4, 0.0)),
+            [(10.0, 15.0), (15.0, 10.0), (10.0, 5.0), (5.0, 10.0)]
+        );
+
+        assert_eq!(
+            to_coords(PdfObjectShape::regular_points(center, 5.0, 4, 90.0)),
+            [(5.0, 10.0), (10.0, 15.0), (15.0, 10.0), (10.0, 5.0)]
+        );
+
+        assert_eq!(
+            to_coords(PdfObjectShape::star_points(center, 5.0, 2.0, 2, 0.0)),
+            [(10.0, 15.0), (12.0, 10.0), (10.0, 5.0), (8.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_create_regular_shapes_and_stars_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local shape = pdf.object.shape.regular({
+                sides = 6,
+                radius = 10,
+                center = { x = 20, y = 20 },
+                tag = "hexagon",
+            })
+            assert(shape.type == "shape", "regular shape is not a shape")
+            assert(#shape == 6, "regular shape does not have 6 points")
+            assert(shape.tag == "hexagon", "regular shape is missing its tag")
+            assert(math.abs(shape[1].x - 20) < 0.001, "regular shape does not start at top")
+            assert(math.abs(shape[1].y - 30) < 0.001, "regular shape does not start at top")
+
+            local star = pdf.object.shape.star({ radius = 10 })
+            assert(star.type == "shape", "star is not a shape")
+            assert(#star == 10, "star does not have 10 points")
+
+            // Shapes can still be created from points
+            assert(#pdf.object.shape({ { x = 1, y = 2 } }) == 1, "shape is missing its point")
+
+            local ok = pcall(pdf.object.shape.regular, { sides = 2, radius = 10 })
+            assert(not ok, "regular shape with 2 sides was created")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_offset_shape() {
+        let square = |points: &[(f32, f32)]| PdfObjectShape {
+            points: points
+                .iter()
+                .map(|(x, y)| PdfPoint::from_coords_f32(*x, *y))
+                .collect(),
+            ..Default::default()
+        };
+        let to_coords = |shape: PdfObjectShape| {
+            shape
+                .points
+                .into_iter()
+                .map(|p| p.to_precision(3).to_coords_f32())
+                .collect::<Vec<_>>()
+        };
+
+        // Growing works regardless of the winding of the points
+        let ccw = square(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        assert_eq!(
+            to_coords(ccw.offset(Mm(1.0), PdfLineJoinStyle::miter())),
+            [(-1.0, -1.0), (11.0, -1.0), (11.0, 11.0), (-1.0, 11.0)]
+        );
+        let cw = square(&[(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)]);
+        assert_eq!(
+            to_coords(cw.offset(Mm(1.0), PdfLineJoinStyle::miter())),
+            [(-1.0, -1.0), (-1.0, 11.0), (11.0, 11.0), (11.0, -1.0)]
+        );
+
+        // Shrinking moves the points inward
+        assert_eq!(
+            to_coords(ccw.offset(Mm(-1.0), PdfLineJoinStyle::round())),
+            [(1.0, 1.0), (9.0, 1.0), (9.0, 9.0), (1.0, 9.0)]
+        );
+
+        // Bevels replace each corner with two points
+        assert_eq!(
+            to_coords(ccw.offset(Mm(1.0), PdfLineJoinStyle::limit())),
+            [
+                (-1.0, 0.0),
+                (0.0, -1.0),
+                (10.0, -1.0),
+                (11.0, 0.0),
+                (11.0, 10.0),
+                (10.0, 11.0),
+                (0.0, 11.0),
+                (-1.0, 10.0),
+            ]
+        );
+
+        // Rounds replace each corner with an arc that stays within the grown bounds
+        let rounded = ccw.offset(Mm(1.0), PdfLineJoinStyle::round());
+        assert!(rounded.points.len() > 8, "Missing arc points");
+        assert_eq!(
+            rounded.bounds().to_precision(3),
+            PdfBounds::from_coords_f32(-1.0, -1.0, 11.0, 11.0)
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_offset_shape_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local shape = pdf.object.shape({
+                { x = 0,  y = 0 },
+                { x = 10, y = 0 },
+                { x = 10, y = 10 },
+                { x = 0,  y = 10 },
+            })
+
+            pdf.utils.assert_deep_equal(shape:offset(2):bounds(), {
+                ll = { x = -2, y = -2 },
+                ur = { x = 12, y = 12 },
+            })
+
+            pdf.utils.assert_deep_equal(shape:offset(-2, "round"):bounds(), {
+                ll = { x = 2, y = 2 },
+                ur = { x = 8, y = 8 },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_calculate_bounds_of_shape() {
         // No points
@@ -312,6 +691,7 @@ mod tests {
             Lua::new()
                 .load(chunk!({
                     depth = 123,
+                    layer = "layer",
                     fill_color = "123456",
                     outline_color = "789abc",
                     outline_thickness = 456,
@@ -324,12 +704,15 @@ mod tests {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectShape>()
                 .unwrap(),
             PdfObjectShape {
                 points: Vec::new(),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 fill_color: Some("#123456".parse().unwrap()),
                 outline_color: Some("#789ABC".parse().unwrap()),
                 outline_thickness: Some(456.0),
@@ -341,6 +724,8 @@ mod tests {
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
 
@@ -369,6 +754,7 @@ mod tests {
                     { x = 1, y = 2 },
                     { x = 3, y = 4 },
                     depth = 123,
+                    layer = "layer",
                     fill_color = "123456",
                     outline_color = "789ABC",
                     outline_thickness = 456,
@@ -381,6 +767,8 @@ mod tests {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectShape>()
                 .unwrap(),
@@ -390,6 +778,7 @@ mod tests {
                     PdfPoint::from_coords_f32(3.0, 4.0),
                 ],
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 fill_color: Some("#123456".parse().unwrap()),
                 outline_color: Some("#789ABC".parse().unwrap()),
                 outline_thickness: Some(456.0),
@@ -401,6 +790,8 @@ mod tests {
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
     }
@@ -429,6 +820,7 @@ mod tests {
                 PdfPoint::from_coords_f32(3.0, 4.0),
             ],
             depth: Some(123),
+            layer: Some(String::from("layer")),
             fill_color: Some("#123456".parse().unwrap()),
             outline_color: Some("#789ABC".parse().unwrap()),
             outline_thickness: Some(456.0),
@@ -440,6 +832,8 @@ mod tests {
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
         };
 
         lua.load(chunk! {
@@ -448,6 +842,7 @@ mod tests {
                 { x = 3, y = 4 },
                 type = "shape",
                 depth = 123,
+                layer = "layer",
                 fill_color = { red = 18, green = 52, blue = 86 },
                 outline_color = { red = 120, green = 154, blue = 188 },
                 outline_thickness = 456,
@@ -460,6 +855,8 @@ mod tests {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
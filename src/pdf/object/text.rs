@@ -1,12 +1,15 @@
 use crate::constants::GLOBAL_PDF_VAR_NAME;
 use crate::pdf::{
-    PdfAlign, PdfBounds, PdfColor, PdfConfig, PdfContext, PdfHorizontalAlign, PdfLink,
-    PdfLinkAnnotation, PdfLuaExt, PdfLuaTableExt, PdfObjectType, PdfPoint, PdfVerticalAlign,
+    lua_clone, PdfAlign, PdfBackground, PdfBounds, PdfColor, PdfConfig, PdfContext,
+    PdfHorizontalAlign, PdfLayer, PdfLink, PdfLinkAnnotation, PdfLinkStyle, PdfLuaExt,
+    PdfLuaTableExt, PdfObjectType, PdfPlace, PdfPoint, PdfShadow, PdfTextDirection,
+    PdfTextOverflow, PdfTextScript, PdfVerticalAlign,
 };
 use crate::runtime::{RuntimeFontId, RuntimeFonts};
 use mlua::prelude::*;
 use owned_ttf_parser::{Face, GlyphId};
-use printpdf::{GlyphMetrics, Mm, Pt};
+use printpdf::{GlyphMetrics, Line, Mm, Pt};
+use std::borrow::Cow;
 
 /// Represents text to be drawn in the PDF.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -14,44 +17,110 @@ pub struct PdfObjectText {
     pub point: PdfPoint,
     pub text: String,
     pub depth: Option<i64>,
+    pub layer: Option<String>,
     pub font: Option<RuntimeFontId>,
     pub size: Option<f32>,
+    /// Direction of the text, detected from its first strongly directional character if not set.
+    /// The x position of right-to-left text is where it starts, which is its right edge.
+    pub direction: Option<PdfTextDirection>,
+    /// Maximum width of the text in millimeters, beyond which `overflow` is applied.
+    pub max_width: Option<f32>,
+    pub overflow: Option<PdfTextOverflow>,
+    /// Draws the text smaller and shifted from its baseline using the metrics of its font.
+    pub script: Option<PdfTextScript>,
+    /// Distance in millimeters to raise (or lower when negative) the text from its baseline,
+    /// replacing the shift of its script.
+    pub baseline_shift: Option<f32>,
     pub color: Option<PdfColor>,
     pub link: Option<PdfLink>,
+    pub link_style: Option<PdfLinkStyle>,
+    pub background: Option<PdfBackground>,
+    pub shadow: Option<PdfShadow>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
 }
 
 impl PdfObjectText {
-    /// Draws the object within the PDF.
+    /// Draws the object within the PDF, preceded by its background if it has one.
     pub fn draw(&self, ctx: PdfContext) {
-        // Get optional values, setting defaults when not specified
-        let size = self.size.unwrap_or(ctx.config.page.font_size);
-        let fill_color = self.color.unwrap_or(ctx.config.page.fill_color);
-        let (x, y) = self.point.to_coords();
-
-        // Retrieve the font to use for the text, leveraging the configured font first, otherwise
-        // falling back to a default font
-        if let Some(font_ref) = self
-            .font
-            .and_then(|id| ctx.fonts.get_font_doc_ref(id))
-            .or_else(|| ctx.fonts.get_font_doc_ref(ctx.fallback_font_id))
-        {
-            ctx.layer.set_fill_color(fill_color.into());
-            ctx.layer.use_text(&self.text, size, x, y, font_ref);
+        if let Some(background) = self.background {
+            background.to_shape(self.bounds(ctx)).draw(ctx);
         }
+
+        let link_style = self.to_link_style(ctx);
+
+        // Get optional values, falling back to the style, the defaults of text, and then of pages
+        let size = self.size_in(ctx);
+        let fill_color = self
+            .color
+            .or(link_style.color)
+            .unwrap_or_else(|| ctx.fill_color(ctx.config.page.defaults.text.color));
+
+        // Characters missing from the font of the text are drawn using fallback fonts, so the text
+        // is drawn as runs of characters that share the same font starting from its left edge
+        let faces = self.faces(ctx.fonts, Some(ctx.fallback_font_id));
+        let (text, PdfPoint { x, y }, size) = self.to_visual(&faces, size);
+
+        ctx.with_alpha(fill_color.alpha(), fill_color.alpha(), || {
+            ctx.layer
+                .set_fill_color(ctx.config.page.draw_color(fill_color).into());
+
+            let mut offset = Mm(0.0);
+            for (idx, run) in font_runs(&text, &faces.faces) {
+                if let Some(font_ref) = ctx.fonts.get_font_doc_ref(faces.ids[idx]) {
+                    ctx.layer.use_text(run, size, x + offset, y, font_ref);
+                }
+                offset += text_width(run, &faces.faces, size);
+            }
+
+            if link_style.underline {
+                if let Some(face) = faces.faces.first() {
+                    let (offset, thickness) = underline_metrics(face, size);
+                    let thickness = ctx.config.page.stroke_thickness(thickness);
+                    let width = faces.width(&text, size);
+
+                    ctx.layer
+                        .set_outline_color(ctx.config.page.draw_color(fill_color).into());
+                    ctx.layer.set_outline_thickness(thickness);
+                    ctx.layer
+                        .set_line_dash_pattern(ctx.config.page.line_dash_pattern.into());
+                    ctx.layer.add_line(Line {
+                        points: vec![
+                            (PdfPoint::new(x, y + offset).into(), false),
+                            (PdfPoint::new(x + width, y + offset).into(), false),
+                        ],
+                        is_closed: false,
+                    });
+                }
+            }
+        });
     }
 
-    /// Returns a collection of link annotations.
+    /// Returns a collection of link annotations, whose clickable area is grown by the padding of
+    /// the link style.
     pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+        let link_style = self.to_link_style(ctx);
         match self.link.clone() {
             Some(link) => vec![PdfLinkAnnotation {
-                bounds: self.bounds(ctx),
-                depth: self.depth.unwrap_or_default(),
+                bounds: self.bounds(ctx).expand(Mm(link_style.padding)),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
                 link,
+                border: link_style.border,
+                highlight: link_style.highlight,
             }],
             None => Vec::new(),
         }
     }
 
+    /// Returns the style of the link carried by the text, which is its own link style or otherwise
+    /// the configured one. Text without a link is left unstyled.
+    fn to_link_style(&self, ctx: PdfContext) -> PdfLinkStyle {
+        match self.link {
+            Some(_) => self.link_style.unwrap_or(ctx.config.link_style),
+            None => PdfLinkStyle::none(),
+        }
+    }
+
     /// Aligns the text to a set of bounds.
     pub fn align_to(
         &mut self,
@@ -59,32 +128,137 @@ impl PdfObjectText {
         bounds: PdfBounds,
         align: (PdfVerticalAlign, PdfHorizontalAlign),
     ) {
-        // Get new bounds for the text
         let src_bounds = self.bounds(ctx);
-        let dst_bounds = src_bounds.align_to(bounds, align);
-
-        // Figure out changes from original bounds of points
-        let x_offset = dst_bounds.width() - src_bounds.width();
-        let y_offset = dst_bounds.height() - src_bounds.height();
-
-        // Apply the changes to the text coordinates
-        self.point.x += x_offset;
-        self.point.y += y_offset;
+        self.align_bounds_to(src_bounds, bounds, align);
     }
 
     /// Returns bounds for the text by calculating the width and height and applying to
     /// get the upper-right point.
     pub fn bounds(&self, ctx: PdfContext) -> PdfBounds {
-        let size = self.size.unwrap_or(ctx.config.page.font_size);
-        if let Some(face) = self
-            .font
-            .and_then(|id| ctx.fonts.get_font_face(id))
-            .or_else(|| ctx.fonts.get_font_face(ctx.fallback_font_id))
-        {
-            bounds(&self.text, face, size, self.point.x, self.point.y)
-        } else {
+        let size = self.size_in(ctx);
+        let faces = self.faces(ctx.fonts, Some(ctx.fallback_font_id));
+        if faces.faces.is_empty() {
             unreachable!("Fallback font should always be available");
         }
+
+        let (text, point, size) = self.to_visual(&faces, size);
+        bounds(&text, &faces, size, point.x, point.y)
+    }
+
+    /// Returns the text in the order that its characters are drawn from left to right, alongside
+    /// the point of its left edge on its (shifted) baseline and the font size to draw it with.
+    fn to_visual(&self, faces: &TextFaces, size: f32) -> (Cow<'_, str>, PdfPoint, f32) {
+        let direction = self
+            .direction
+            .unwrap_or_else(|| PdfTextDirection::detect(&self.text));
+        let (size, shift) = self.shift(faces.faces[0], size);
+        let (text, size) = self.fit(faces, size);
+        let text = match text {
+            Cow::Borrowed(text) => direction.to_visual(text),
+            Cow::Owned(text) => Cow::Owned(direction.to_visual(&text).into_owned()),
+        };
+        let x = if direction.is_rtl() {
+            self.point.x - faces.width(&text, size)
+        } else {
+            self.point.x
+        };
+        (text, PdfPoint::new(x, self.point.y + shift), size)
+    }
+
+    /// Returns the font size of the text after applying its script, alongside how far to shift
+    /// its baseline up.
+    ///
+    /// Fonts without script metrics fall back to text that is 60% of the size, raised by a third
+    /// of the size for superscript or lowered by a seventh of the size for subscript.
+    fn shift(&self, face: &Face, size: f32) -> (f32, Mm) {
+        let units_per_em = face.units_per_em() as f32;
+        let (scale, offset) = match self.script {
+            Some(PdfTextScript::Super) => face
+                .superscript_metrics()
+                .map(|m| {
+                    (
+                        m.y_size as f32 / units_per_em,
+                        m.y_offset as f32 / units_per_em,
+                    )
+                })
+                .unwrap_or((0.6, 0.33)),
+            // Subscript offsets within fonts are positive when moving down
+            Some(PdfTextScript::Sub) => face
+                .subscript_metrics()
+                .map(|m| {
+                    (
+                        m.y_size as f32 / units_per_em,
+                        -m.y_offset as f32 / units_per_em,
+                    )
+                })
+                .unwrap_or((0.6, -0.14)),
+            None => (1.0, 0.0),
+        };
+
+        let shift = match self.baseline_shift {
+            Some(shift) => Mm(shift),
+            None => Pt(size * offset).into(),
+        };
+        (size * scale, shift)
+    }
+
+    /// Fits the text within its maximum width (if it has one) based on its overflow behavior,
+    /// returning the text alongside the font size to draw it with.
+    fn fit(&self, faces: &TextFaces, size: f32) -> (Cow<'_, str>, f32) {
+        let max_width = match self.max_width {
+            Some(max_width) => max_width,
+            None => return (Cow::Borrowed(&self.text), size),
+        };
+
+        let width = faces.width(&self.text, size).0;
+        if width <= max_width {
+            return (Cow::Borrowed(&self.text), size);
+        }
+
+        match self.overflow.unwrap_or_default() {
+            // Width of text is proportional to its size, so scale the size down by the overflow
+            PdfTextOverflow::Shrink => (Cow::Borrowed(&self.text), size * max_width / width),
+            PdfTextOverflow::Truncate => (
+                Cow::Owned(truncate(&self.text, &faces.faces, size, max_width, "")),
+                size,
+            ),
+            PdfTextOverflow::Ellipsis => (
+                Cow::Owned(truncate(
+                    &self.text,
+                    &faces.faces,
+                    size,
+                    max_width,
+                    "\u{2026}",
+                )),
+                size,
+            ),
+        }
+    }
+
+    /// Returns the faces of the fonts used to draw the text in order of preference, starting with
+    /// the font of the text, followed by `fallback_font_id`, the fallback font of `fonts`, and the
+    /// fallback chain of `fonts`.
+    fn faces<'a>(
+        &self,
+        fonts: &'a RuntimeFonts,
+        fallback_font_id: Option<RuntimeFontId>,
+    ) -> TextFaces<'a> {
+        let mut ids: Vec<RuntimeFontId> = Vec::new();
+        for id in [self.font, fallback_font_id, fonts.fallback_font_id()]
+            .into_iter()
+            .flatten()
+            .chain(fonts.fallback_chain().iter().copied())
+        {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        let (ids, faces) = ids
+            .into_iter()
+            .filter_map(|id| fonts.get_font_face(id).map(|face| (id, face)))
+            .unzip();
+        TextFaces { fonts, ids, faces }
     }
 
     /// Returns bounds for the text by calculating the width and height and applying to get the
@@ -93,42 +267,67 @@ impl PdfObjectText {
     /// Calculates bounds from a [`Lua`] runtime, which occurs earlier than when a [`PdfContext`]
     /// is available.
     pub(crate) fn lua_bounds(&self, lua: &Lua) -> LuaResult<PdfBounds> {
-        // Figure out the font's size by loading the explicit size or searching our global
-        // pdf instance for the default page font size
-        let font_size = match self.size {
-            Some(size) => size,
-            None => {
-                lua.globals()
-                    .raw_get::<_, PdfConfig>(GLOBAL_PDF_VAR_NAME)?
-                    .page
-                    .font_size
-            }
-        };
+        let font_size = self.lua_font_size(lua)?;
 
         // Retrieve the loaded fonts so we can figure out the actual text bounds
         // for the associated font
         if let Some(fonts) = lua.app_data_ref::<RuntimeFonts>() {
-            let font_id = match self.font {
-                Some(id) => Some(id),
-                None => fonts.fallback_font_id(),
-            };
-
-            if let Some(face) = font_id.and_then(|id| fonts.get_font_face(id)) {
-                Ok(bounds(
-                    &self.text,
-                    face,
-                    font_size,
-                    self.point.x,
-                    self.point.y,
-                ))
-            } else {
+            let faces = self.faces(&fonts, None);
+            if faces.faces.is_empty() {
                 Err(LuaError::runtime("Runtime fallback font is missing"))
+            } else {
+                let (text, point, font_size) = self.to_visual(&faces, font_size);
+                Ok(bounds(&text, &faces, font_size, point.x, point.y))
             }
         } else {
             Err(LuaError::runtime("Runtime fonts are missing"))
         }
     }
 
+    /// Returns the lines of the text once wrapped to fit within `max_width` (in millimeters),
+    /// breaking between words and at line breaks within the text.
+    ///
+    /// Calculates lines from a [`Lua`] runtime like [`PdfObjectText::lua_bounds`].
+    pub(crate) fn lua_wrap(&self, lua: &Lua, max_width: f32) -> LuaResult<Vec<String>> {
+        let font_size = self.lua_font_size(lua)?;
+        let fonts = lua
+            .app_data_ref::<RuntimeFonts>()
+            .ok_or_else(|| LuaError::runtime("Runtime fonts are missing"))?;
+        let faces = self.faces(&fonts, None);
+        if faces.faces.is_empty() {
+            Err(LuaError::runtime("Runtime fallback font is missing"))
+        } else {
+            Ok(wrap(&self.text, &faces.faces, font_size, max_width))
+        }
+    }
+
+    /// Returns the size of the text, which is its explicit size or the default size of text
+    /// found within our global pdf instance.
+    fn lua_font_size(&self, lua: &Lua) -> LuaResult<f32> {
+        match self.size {
+            Some(size) => Ok(size),
+            None => {
+                Ok(self
+                    .size_or_default(&lua.globals().raw_get::<_, PdfConfig>(GLOBAL_PDF_VAR_NAME)?))
+            }
+        }
+    }
+
+    /// Returns the size of the text when drawn within `ctx`, falling back to the style of `ctx`,
+    /// the default size of text, and then the default font size of pages.
+    fn size_in(&self, ctx: PdfContext) -> f32 {
+        self.size
+            .unwrap_or_else(|| ctx.font_size(ctx.config.page.defaults.text.size))
+    }
+
+    /// Returns the size of the text, falling back to the default size of text and then the
+    /// default font size of pages within `config`.
+    fn size_or_default(&self, config: &PdfConfig) -> f32 {
+        self.size
+            .or(config.page.defaults.text.size)
+            .unwrap_or(config.page.font_size)
+    }
+
     /// Aligns the text to a set of bounds.
     ///
     /// Calculates bounds from a [`Lua`] runtime, which occurs earlier than when a [`PdfContext`]
@@ -139,24 +338,39 @@ impl PdfObjectText {
         bounds: PdfBounds,
         align: (PdfVerticalAlign, PdfHorizontalAlign),
     ) -> LuaResult<()> {
-        // Get new bounds for the text
         let src_bounds = self.lua_bounds(lua)?;
+        self.align_bounds_to(src_bounds, bounds, align);
+        Ok(())
+    }
+
+    /// Shifts the text such that `src_bounds` (the current bounds of the text) are aligned to
+    /// `bounds`.
+    ///
+    /// Baseline alignment places the baseline of the text at the bottom of `bounds`, which keeps
+    /// text of different sizes lined up when aligned to the same bounds.
+    fn align_bounds_to(
+        &mut self,
+        src_bounds: PdfBounds,
+        bounds: PdfBounds,
+        align: (PdfVerticalAlign, PdfHorizontalAlign),
+    ) {
+        // Get new bounds for the text
         let dst_bounds = src_bounds.align_to(bounds, align);
 
         // Figure out the shift from original to new bounds
         let x_offset = dst_bounds.ll.x - src_bounds.ll.x;
-        let y_offset = dst_bounds.ll.y - src_bounds.ll.y;
+        let y_offset = match align.0 {
+            PdfVerticalAlign::Baseline => bounds.ll.y - self.point.y,
+            _ => dst_bounds.ll.y - src_bounds.ll.y,
+        };
 
         // Apply the changes to the text coordinates
         self.point.x += x_offset;
         self.point.y += y_offset;
-
-        Ok(())
     }
 }
 
-fn glyph_metrics(face: &Face, glyph_id: u16) -> Option<GlyphMetrics> {
-    let glyph_id = GlyphId(glyph_id);
+fn glyph_metrics(face: &Face, glyph_id: GlyphId) -> Option<GlyphMetrics> {
     if let Some(width) = face.glyph_hor_advance(glyph_id) {
         let width = width as u32;
         let height = face
@@ -169,44 +383,127 @@ fn glyph_metrics(face: &Face, glyph_id: u16) -> Option<GlyphMetrics> {
     }
 }
 
+/// Returns the index of the first face within `faces` that has a glyph for `ch`, defaulting to the
+/// first face (which draws the character as its missing glyph) when none of them do.
+fn face_for_char(faces: &[&Face], ch: char) -> usize {
+    faces
+        .iter()
+        .position(|face| face.glyph_index(ch).is_some())
+        .unwrap_or(0)
+}
+
+/// Splits `text` into runs of characters that are drawn using the same face, returning the index
+/// of the face within `faces` alongside each run.
+fn font_runs<'a>(text: &'a str, faces: &[&Face]) -> Vec<(usize, &'a str)> {
+    let mut runs: Vec<(usize, &'a str)> = Vec::new();
+    let mut start = 0;
+    for (i, ch) in text.char_indices() {
+        let idx = face_for_char(faces, ch);
+        match runs.last_mut() {
+            Some((last, run)) if *last == idx => *run = &text[start..i + ch.len_utf8()],
+            _ => {
+                start = i;
+                runs.push((idx, &text[i..i + ch.len_utf8()]));
+            }
+        }
+    }
+    runs
+}
+
 impl<'lua> IntoLua<'lua> for PdfObjectText {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let (table, metatable) = lua.create_table_ext()?;
+        let table = lua.create_table_with_metatable("pdf.object.text", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |lua, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.lua_align_to(lua, bounds, align.to_v_h())?;
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |lua, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.lua_bounds(lua)?;
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.lua_align_to(lua, region, align)?;
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |lua, this: Self| this.lua_bounds(lua))?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
 
         self.point.add_to_table(&table)?;
         table.raw_set("type", PdfObjectType::Text)?;
         table.raw_set("text", self.text)?;
         table.raw_set("size", self.size)?;
+        table.raw_set("direction", self.direction)?;
+        table.raw_set("max_width", self.max_width)?;
+        table.raw_set("overflow", self.overflow)?;
+        table.raw_set("script", self.script)?;
+        table.raw_set("baseline_shift", self.baseline_shift)?;
         table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
         table.raw_set("font", self.font)?;
         table.raw_set("color", self.color)?;
         table.raw_set("link", self.link)?;
-
-        metatable.raw_set(
-            "align_to",
-            lua.create_function(
-                move |lua, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
-                    this.lua_align_to(lua, bounds, align.to_v_h())?;
-                    Ok(this)
-                },
-            )?,
-        )?;
-
-        metatable.raw_set(
-            "bounds",
-            lua.create_function(move |lua, this: Self| this.lua_bounds(lua))?,
-        )?;
+        table.raw_set("link_style", self.link_style)?;
+        table.raw_set("background", self.background)?;
+        table.raw_set("shadow", self.shadow)?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
 
         Ok(LuaValue::Table(table))
     }
 }
 
+/// Names of the fields of text objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "x",
+    "y",
+    "text",
+    "size",
+    "direction",
+    "max_width",
+    "overflow",
+    "script",
+    "baseline_shift",
+    "depth",
+    "layer",
+    "font",
+    "color",
+    "link",
+    "link_style",
+    "background",
+    "shadow",
+    "hidden",
+    "tag",
+];
+
 impl<'lua> FromLua<'lua> for PdfObjectText {
     #[inline]
     fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
         match value {
             LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.text", FIELDS)?;
+
                 // Support missing point converting into default point
                 //
                 // TODO: This will result in invalid point becoming default point.
@@ -229,10 +526,23 @@ impl<'lua> FromLua<'lua> for PdfObjectText {
                         .raw_get_ext::<_, Option<_>>("text")?
                         .unwrap_or_default(),
                     size: table.raw_get_ext("size")?,
+                    direction: table.raw_get_ext("direction")?,
+                    max_width: table.raw_get_ext("max_width")?,
+                    overflow: table.raw_get_ext("overflow")?,
+                    script: table.raw_get_ext("script")?,
+                    baseline_shift: table.raw_get_ext("baseline_shift")?,
                     depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
                     font: table.raw_get_ext("font")?,
                     color: table.raw_get_ext("color")?,
                     link: table.raw_get_ext("link")?,
+                    link_style: table.raw_get_ext("link_style")?,
+                    background: table.raw_get_ext("background")?,
+                    shadow: table.raw_get_ext("shadow")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
                 })
             }
             _ => Err(LuaError::FromLuaConversionError {
@@ -246,24 +556,104 @@ impl<'lua> FromLua<'lua> for PdfObjectText {
 
 /// Returns bounds for the text by calculating the width and height and applying to
 /// get the upper-right point.
-fn bounds(text: &str, face: &Face, font_size: f32, baseline_x: Mm, baseline_y: Mm) -> PdfBounds {
+///
+/// The first of `faces` determines the height of the text, while the width accounts for characters
+/// drawn using the other faces.
+fn bounds(
+    text: &str,
+    faces: &TextFaces,
+    font_size: f32,
+    baseline_x: Mm,
+    baseline_y: Mm,
+) -> PdfBounds {
+    let face = faces.faces[0];
     let x = baseline_x;
     let y = text_ll_y(face, font_size, baseline_y);
-    let width = text_width(text, face, font_size);
+    let width = faces.width(text, font_size);
     let height = text_height(face, font_size);
     PdfBounds::from_coords(x, y, x + width, y + height)
 }
 
-/// Returns the width of the text in millimeters for the given font face.
-fn text_width(text: &str, face: &Face, font_size: f32) -> Mm {
-    let units_per_em = face.units_per_em() as f64;
-    let scale = font_size as f64 / units_per_em;
+/// Returns the longest start of `text` that fits within `max_width` (in millimeters) once `suffix`
+/// is added to it, or nothing if even `suffix` does not fit.
+fn truncate(text: &str, faces: &[&Face], font_size: f32, max_width: f32, suffix: &str) -> String {
+    let mut width = text_width(suffix, faces, font_size).0;
+    if width > max_width {
+        return String::new();
+    }
+
+    let mut end = 0;
+    for (i, ch) in text.char_indices() {
+        width += text_width(&text[i..i + ch.len_utf8()], faces, font_size).0;
+        if width > max_width {
+            break;
+        }
+        end = i + ch.len_utf8();
+    }
+
+    // Avoid leaving a gap between the text and the suffix
+    let text = if suffix.is_empty() {
+        &text[..end]
+    } else {
+        text[..end].trim_end()
+    };
+    format!("{text}{suffix}")
+}
 
-    // Calculate the total width of the text
+/// Splits `text` into lines no wider than `max_width` (in millimeters), breaking between words and
+/// at line breaks within the text. Words wider than `max_width` are placed on lines of their own.
+fn wrap(text: &str, faces: &[&Face], font_size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if line.is_empty() {
+                line.push_str(word);
+                continue;
+            }
+
+            let candidate = format!("{line} {word}");
+            if text_width(&candidate, faces, font_size).0 > max_width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Faces of the fonts used to draw text in order of preference, alongside the ids of the fonts.
+struct TextFaces<'a> {
+    fonts: &'a RuntimeFonts,
+    ids: Vec<RuntimeFontId>,
+    faces: Vec<&'a Face<'a>>,
+}
+
+impl TextFaces<'_> {
+    /// Returns the width of `text` in millimeters like [`text_width`], reusing the width
+    /// remembered by the fonts when the same text was measured at the same size before.
+    fn width(&self, text: &str, font_size: f32) -> Mm {
+        self.fonts.text_width(&self.ids, text, font_size, || {
+            text_width(text, &self.faces, font_size)
+        })
+    }
+}
+
+/// Returns the width of the text in millimeters for the given font faces, measuring each character
+/// using the first face that has a glyph for it.
+fn text_width(text: &str, faces: &[&Face], font_size: f32) -> Mm {
+    // Calculate the total width of the text, mapping characters to glyphs through the cmap of
+    // each face as glyph ids do not match unicode code points
     let text_width = text
         .chars()
         .map(|ch| {
-            glyph_metrics(face, ch as u16)
+            let face = faces[face_for_char(faces, ch)];
+            let scale = font_size as f64 / face.units_per_em() as f64;
+            let glyph_id = face.glyph_index(ch).unwrap_or(GlyphId(0));
+            glyph_metrics(face, glyph_id)
                 .map(|glyph| glyph.width as f64 * scale)
                 .unwrap_or(0.0)
         })
@@ -301,11 +691,28 @@ fn text_ll_y(face: &Face, font_size: f32, baseline_y: Mm) -> Mm {
     baseline_y + descender_mm
 }
 
+/// Returns the offset from the baseline and the thickness (in points) of an underline for the
+/// given font face.
+fn underline_metrics(face: &Face, font_size: f32) -> (Mm, f32) {
+    let units_per_em = face.units_per_em() as f64;
+    let scale = font_size as f64 / units_per_em;
+
+    // Fall back to an underline placed a tenth of the font size below the baseline whose
+    // thickness is a twentieth of the font size when the font does not tell us
+    let (position, thickness) = face
+        .underline_metrics()
+        .map(|m| (m.position as f64 * scale, m.thickness as f64 * scale))
+        .unwrap_or((font_size as f64 * -0.1, font_size as f64 * 0.05));
+
+    (Pt(position as f32).into(), thickness as f32)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::pdf::Pdf;
-    use crate::runtime::RuntimeFonts;
+    use crate::constants::DEFAULT_FONT;
+    use crate::pdf::{Pdf, PdfLinkBorder, PdfLinkHighlight, PdfPadding, PdfStyle};
+    use crate::runtime::{RuntimeAlpha, RuntimeFonts, RuntimeToc, RuntimeXObjects};
     use mlua::chunk;
     use printpdf::{Mm, PdfDocument};
 
@@ -352,6 +759,40 @@ mod tests {
         .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_be_able_to_align_text_of_different_sizes_by_baseline_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        lua.load(chunk! {
+            local bounds = {
+                ll = { x = 5,  y = 5 },
+                ur = { x = 50, y = 50 },
+            }
+
+            local big = pdf.object.text({ x = 0, y = 0, text = "31", size = 36.0 })
+                :align_to(bounds, { v = "baseline", h = "left" })
+            local small = pdf.object.text({ x = 0, y = 0, text = "Mon", size = 12.0 })
+                :align_to(bounds, { v = "baseline", h = "right" })
+
+            // Both texts should share the bottom of the bounds as their baseline
+            pdf.utils.assert_deep_equal({ big.x, big.y }, { 5, 5 })
+            pdf.utils.assert_deep_equal(small.y, 5)
+
+            // Text is still aligned horizontally using its bounds
+            pdf.utils.assert_deep_equal(small:bounds().ur.x, 50)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_calculate_bounds_of_text() {
         // Create a pdf context that we need for bounds calculations
@@ -366,6 +807,10 @@ mod tests {
             layer: &layer,
             fonts: &font,
             fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
         };
 
         let text = PdfObjectText {
@@ -381,6 +826,338 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_use_size_of_style_over_default_size_of_text_for_bounds() {
+        let doc = PdfDocument::empty("");
+        let (page_idx, layer_idx) = doc.add_page(Mm(0.0), Mm(0.0), "");
+        let layer = doc.get_page(page_idx).get_layer(layer_idx);
+        let mut font = RuntimeFonts::new();
+        let font_id = font.add_builtin_font().unwrap();
+        font.add_font_as_fallback(font_id);
+        let mut config = PdfConfig::default();
+        config.page.defaults.text.size = Some(10.0);
+        let ctx = PdfContext {
+            config: &config,
+            layer: &layer,
+            fonts: &font,
+            fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
+        };
+
+        let text = PdfObjectText {
+            point: PdfPoint::from_coords_f32(0.0, 0.0),
+            text: String::from("hello world"),
+            ..Default::default()
+        };
+        let sized = |size| PdfObjectText {
+            size: Some(size),
+            ..text.clone()
+        };
+        let style = PdfStyle {
+            size: Some(36.0),
+            ..Default::default()
+        };
+
+        // Style takes precedence over the default size of text, even through nested styles that
+        // do not set a size, but not over the size of the text itself
+        assert_eq!(text.bounds(ctx), sized(10.0).bounds(ctx));
+        assert_eq!(
+            style.with_context(ctx, |ctx| text.bounds(ctx)),
+            sized(36.0).bounds(ctx)
+        );
+        let nested = PdfStyle {
+            fill_color: Some(PdfColor::black()),
+            ..Default::default()
+        };
+        assert_eq!(
+            style.with_context(ctx, |ctx| nested.with_context(ctx, |ctx| text.bounds(ctx))),
+            sized(36.0).bounds(ctx)
+        );
+        assert_eq!(
+            style.with_context(ctx, |ctx| sized(10.0).bounds(ctx)),
+            sized(10.0).bounds(ctx)
+        );
+    }
+
+    #[test]
+    fn link_annotations_should_grow_bounds_by_link_style_padding() {
+        let doc = PdfDocument::empty("");
+        let (page_idx, layer_idx) = doc.add_page(Mm(0.0), Mm(0.0), "");
+        let layer = doc.get_page(page_idx).get_layer(layer_idx);
+        let mut font = RuntimeFonts::new();
+        let font_id = font.add_builtin_font().unwrap();
+        font.add_font_as_fallback(font_id);
+        let config = PdfConfig {
+            link_style: PdfLinkStyle {
+                padding: 2.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let ctx = PdfContext {
+            config: &config,
+            layer: &layer,
+            fonts: &font,
+            fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
+        };
+
+        let text = PdfObjectText {
+            text: String::from("hello"),
+            link: Some(PdfLink::Uri {
+                uri: String::from("https://example.com"),
+            }),
+            ..Default::default()
+        };
+
+        // Configured link style pads the clickable area, unless the text has its own style
+        let annotations = text.link_annotations(ctx);
+        assert_eq!(annotations[0].bounds, text.bounds(ctx).expand(Mm(2.0)));
+
+        let text = PdfObjectText {
+            link_style: Some(PdfLinkStyle::none()),
+            ..text
+        };
+        let annotations = text.link_annotations(ctx);
+        assert_eq!(annotations[0].bounds, text.bounds(ctx));
+    }
+
+    #[test]
+    fn lua_bounds_should_remember_widths_of_text_within_fonts() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        let id = {
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            lua.set_app_data(fonts);
+            id
+        };
+
+        let text = PdfObjectText {
+            text: String::from("hello"),
+            size: Some(12.0),
+            ..Default::default()
+        };
+        let width = text.lua_bounds(&lua).unwrap().width();
+
+        // Measuring the same text again reuses the width instead of measuring it
+        let fonts = lua.app_data_ref::<RuntimeFonts>().unwrap();
+        assert_eq!(
+            fonts.text_width(&[id], "hello", 12.0, || unreachable!(
+                "Width was not remembered"
+            )),
+            width
+        );
+        assert_eq!(fonts.text_width(&[id], "hello", 14.0, || Mm(1.0)), Mm(1.0));
+    }
+
+    #[test]
+    fn should_be_able_to_calculate_bounds_of_non_latin_text() {
+        let mut fonts = RuntimeFonts::new();
+        let font_id = fonts.add_builtin_font().unwrap();
+        fonts.add_font_as_fallback(font_id);
+        let faces = vec![fonts.get_font_face(font_id).unwrap()];
+
+        // Builtin font is monospaced, so text of the same length should have the same width
+        // regardless of the script as long as the characters are mapped to their glyphs
+        let width = |text: &str| text_width(text, &faces, 36.0);
+        assert_eq!(width("Привет мир"), width("hello worl"));
+        assert_eq!(width("Καλημέρα"), width("good day"));
+        assert_eq!(width("café"), width("cafe"));
+
+        // Characters missing from every font are measured as the missing glyph of the first font
+        assert_eq!(width("日"), width("a"));
+        assert_eq!(font_runs("hi 日", &faces), vec![(0, "hi 日")]);
+    }
+
+    #[test]
+    fn faces_should_follow_font_with_fallback_font_and_chain() {
+        let mut fonts = RuntimeFonts::new();
+        let fallback_id = fonts.add_builtin_font().unwrap();
+        let symbols_id = fonts.add_from_bytes(DEFAULT_FONT.to_vec()).unwrap();
+        let emoji_id = fonts.add_from_bytes(DEFAULT_FONT.to_vec()).unwrap();
+        fonts.add_font_as_fallback(fallback_id);
+        fonts.set_fallback_chain(vec![symbols_id, fallback_id, 0, emoji_id]);
+
+        // Duplicate and unknown fonts within the chain are skipped
+        let ids = |text: &PdfObjectText| -> Vec<RuntimeFontId> { text.faces(&fonts, None).ids };
+        assert_eq!(
+            ids(&PdfObjectText::default()),
+            vec![fallback_id, symbols_id, emoji_id]
+        );
+        assert_eq!(
+            ids(&PdfObjectText {
+                font: Some(emoji_id),
+                ..Default::default()
+            }),
+            vec![emoji_id, fallback_id, symbols_id]
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_calculate_bounds_of_right_to_left_text_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        // Right-to-left text starts at its x position, extending to the left of it
+        lua.load(chunk! {
+            local ltr = pdf.object.text({ x = 100, y = 0, text = "hello", size = 36.0 })
+            local rtl = pdf.object.text({ x = 100, y = 0, text = "שלום!", size = 36.0 })
+            local forced = pdf.object.text({
+                x = 100,
+                y = 0,
+                text = "hello",
+                size = 36.0,
+                direction = "rtl",
+            })
+
+            pdf.utils.assert_deep_equal(ltr:bounds().ll.x, 100)
+            pdf.utils.assert_deep_equal(rtl:bounds().ur.x, 100)
+            pdf.utils.assert_deep_equal(forced:bounds().ur.x, 100)
+            pdf.utils.assert_deep_equal(rtl:bounds().ll.x, 100 - ltr:bounds():width())
+
+            // Alignment still moves the bounds of the text into place
+            rtl = rtl:align_to({ ll = { x = 0, y = 0 }, ur = { x = 50, y = 50 } }, { h = "left" })
+            pdf.utils.assert_deep_equal(rtl:bounds().ll.x, 0)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_be_able_to_fit_text_within_max_width_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        lua.load(chunk! {
+            local function text(tbl)
+                tbl.x = 0
+                tbl.y = 0
+                tbl.text = "hello world"
+                tbl.size = 36.0
+                return pdf.object.text(tbl)
+            end
+
+            local full = text({}):bounds()
+            local char_width = full:width() / 11
+            local function assert_near(a, b)
+                assert(math.abs(a - b) < 0.001, "expected " .. b .. ", got " .. a)
+            end
+
+            // Text that fits is left alone
+            pdf.utils.assert_deep_equal(text({ max_width = 100 }):bounds(), full)
+
+            // Truncating cuts off the characters that do not fit, while an ellipsis takes
+            // the place of one more character and drops the space before it
+            local truncate = text({ max_width = 7.5 * char_width, overflow = "truncate" })
+            local ellipsis = text({ max_width = 7.5 * char_width })
+            assert_near(truncate:bounds():width(), 7 * char_width)
+            assert_near(ellipsis:bounds():width(), 6 * char_width)
+
+            // Shrinking reduces the size of the text to fit exactly
+            local shrink = text({ max_width = full:width() / 2, overflow = "shrink" }):bounds()
+            assert_near(shrink:width(), full:width() / 2)
+            assert_near(shrink:height(), full:height() / 2)
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn lua_wrap_should_break_lines_between_words() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        let text = PdfObjectText {
+            text: String::from("one two three\n\nfour extraordinarily"),
+            size: Some(12.0),
+            ..Default::default()
+        };
+        let char_width = PdfObjectText {
+            text: String::from("x"),
+            ..text.clone()
+        }
+        .lua_bounds(&lua)
+        .unwrap()
+        .width()
+        .0;
+
+        assert_eq!(
+            text.lua_wrap(&lua, char_width * 9.5).unwrap(),
+            vec!["one two", "three", "", "four", "extraordinarily"]
+        );
+    }
+
+    #[test]
+    fn should_be_able_to_shift_text_from_baseline_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        lua.load(chunk! {
+            local function bounds(tbl)
+                tbl.x = 0
+                tbl.y = 0
+                tbl.text = "st"
+                tbl.size = 36.0
+                return pdf.object.text(tbl):bounds()
+            end
+
+            local normal = bounds({})
+            local sup = bounds({ script = "super" })
+            local sub = bounds({ script = "sub" })
+
+            // Scripts are smaller, with superscript raised and subscript lowered compared to
+            // the same script without any shift
+            local sup_unshifted = bounds({ script = "super", baseline_shift = 0 })
+            local sub_unshifted = bounds({ script = "sub", baseline_shift = 0 })
+            assert(sup:height() < normal:height(), "superscript is not smaller")
+            assert(sub:height() < normal:height(), "subscript is not smaller")
+            assert(sup.ll.y > sup_unshifted.ll.y, "superscript is not raised")
+            assert(sub.ll.y < sub_unshifted.ll.y, "subscript is not lowered")
+
+            // Explicit shifts move the text without changing its size
+            local shifted = bounds({ baseline_shift = 2 })
+            pdf.utils.assert_deep_equal(shifted:height(), normal:height())
+            assert(math.abs(shifted.ll.y - normal.ll.y - 2) < 0.001, "text not shifted")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_calculate_bounds_of_text_in_lua() {
         // Stand up Lua runtime with everything configured properly for tests
@@ -410,6 +1187,33 @@ mod tests {
         .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_use_default_size_of_text_for_bounds_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+
+        // Default size of text takes precedence over the font size of pages, but not over size
+        lua.load(chunk! {
+            pdf.defaults.text = { size = 36.0 }
+            pdf.page.font_size = 10.0
+
+            local text = pdf.object.text({ x = 0, y = 0, text = "hello world" })
+            local sized = pdf.object.text({ x = 0, y = 0, text = "hello world", size = 36.0 })
+            pdf.utils.assert_deep_equal(text:bounds(), sized:bounds())
+
+            sized.size = 10.0
+            assert(sized:bounds().ur.x < text:bounds().ur.x, "size did not take precedence")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_be_able_to_convert_from_lua() {
         // Can convert from empty table into a text
@@ -450,13 +1254,24 @@ mod tests {
                     x = 1,
                     y = 2,
                     depth = 123,
+                    layer = "layer",
                     font = 456,
                     size = 789,
+                    direction = "rtl",
+                    max_width = 50,
+                    overflow = "shrink",
+                    script = "super",
+                    baseline_shift = 1.5,
                     color = "123456",
                     link = {
                         type = "uri",
                         uri = "https://example.com",
                     },
+                    link_style = false,
+                    background = { color = "445566", padding = 2, corner_radius = 1 },
+                    shadow = { offset = { 2, -2 }, color = "112233", blur = 1 },
+                    hidden = true,
+                    tag = "tag",
                 }))
                 .eval::<PdfObjectText>()
                 .unwrap(),
@@ -464,12 +1279,31 @@ mod tests {
                 point: PdfPoint::from_coords_f32(1.0, 2.0),
                 text: String::from("hello world"),
                 depth: Some(123),
+                layer: Some(String::from("layer")),
                 font: Some(456),
                 size: Some(789.0),
+                direction: Some(PdfTextDirection::Rtl),
+                max_width: Some(50.0),
+                overflow: Some(PdfTextOverflow::Shrink),
+                script: Some(PdfTextScript::Super),
+                baseline_shift: Some(1.5),
                 color: Some("#123456".parse().unwrap()),
                 link: Some(PdfLink::Uri {
                     uri: String::from("https://example.com"),
                 }),
+                link_style: Some(PdfLinkStyle::none()),
+                background: Some(PdfBackground {
+                    color: Some("#445566".parse().unwrap()),
+                    padding: PdfPadding::from_single_f32(2.0),
+                    corner_radius: 1.0,
+                }),
+                shadow: Some(PdfShadow {
+                    offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                    color: "#112233".parse().unwrap(),
+                    blur: 1.0,
+                }),
+                hidden: Some(true),
+                tag: Some(String::from("tag")),
             },
         );
     }
@@ -499,12 +1333,37 @@ mod tests {
             point: PdfPoint::from_coords_f32(1.0, 2.0),
             text: String::from("hello world"),
             depth: Some(123),
+            layer: Some(String::from("layer")),
             font: Some(456),
             size: Some(789.0),
+            direction: Some(PdfTextDirection::Rtl),
+            max_width: Some(50.0),
+            overflow: Some(PdfTextOverflow::Shrink),
+            script: Some(PdfTextScript::Super),
+            baseline_shift: Some(1.5),
             color: Some("#123456".parse().unwrap()),
             link: Some(PdfLink::Uri {
                 uri: String::from("https://example.com"),
             }),
+            link_style: Some(PdfLinkStyle {
+                color: Some("#654321".parse().unwrap()),
+                underline: true,
+                padding: 0.0,
+                border: PdfLinkBorder::default(),
+                highlight: PdfLinkHighlight::Invert,
+            }),
+            background: Some(PdfBackground {
+                color: Some("#445566".parse().unwrap()),
+                padding: PdfPadding::from_single_f32(2.0),
+                corner_radius: 1.0,
+            }),
+            shadow: Some(PdfShadow {
+                offset: PdfPoint::from_coords_f32(2.0, -2.0),
+                color: "#112233".parse().unwrap(),
+                blur: 1.0,
+            }),
+            hidden: Some(true),
+            tag: Some(String::from("tag")),
         };
 
         lua.load(chunk! {
@@ -514,13 +1373,38 @@ mod tests {
                 x = 1,
                 y = 2,
                 depth = 123,
+                layer = "layer",
                 font = 456,
                 size = 789,
+                direction = "rtl",
+                max_width = 50,
+                overflow = "shrink",
+                script = "super",
+                baseline_shift = 1.5,
                 color = { red = 18, green = 52, blue = 86 },
                 link = {
                     type = "uri",
                     uri = "https://example.com",
                 },
+                link_style = {
+                    color = { red = 101, green = 67, blue = 33 },
+                    underline = true,
+                    padding = 0,
+                    border = { width = 0 },
+                    highlight = "invert",
+                },
+                background = {
+                    color = { red = 68, green = 85, blue = 102 },
+                    padding = { top = 2, right = 2, bottom = 2, left = 2 },
+                    corner_radius = 1,
+                },
+                shadow = {
+                    offset = { x = 2, y = -2 },
+                    color = { red = 17, green = 34, blue = 51 },
+                    blur = 1,
+                },
+                hidden = true,
+                tag = "tag",
             })
         })
         .exec()
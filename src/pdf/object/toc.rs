@@ -0,0 +1,325 @@
+use crate::pdf::*;
+use crate::runtime::{RuntimeFontId, RuntimePageId};
+use mlua::prelude::*;
+use printpdf::{Mm, Pt};
+
+/// Represents a table of contents to be drawn in the PDF, listing the entries added through
+/// `pdf.toc.add` with leaders between their titles and the (linked) numbers of their pages.
+///
+/// Entries are laid out when the table is drawn, which happens once every page exists so that
+/// the numbers of pages are correct.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfObjectToc {
+    /// Area to list the entries within from the top down, where entries that do not fit are not
+    /// drawn.
+    pub bounds: PdfBounds,
+    pub depth: Option<i64>,
+    pub layer: Option<String>,
+    pub font: Option<RuntimeFontId>,
+    pub size: Option<f32>,
+    pub color: Option<PdfColor>,
+    /// Text repeated between the title and page number of each entry, defaulting to a period.
+    pub leader: Option<String>,
+    /// Distance in millimeters to indent each level beyond the first, defaulting to 5.
+    pub indent: Option<f32>,
+    /// Distance in millimeters between the baselines of entries, defaulting to one and a half
+    /// times the font size.
+    pub line_height: Option<f32>,
+    /// Deepest level of entries to list, defaulting to every level.
+    pub max_level: Option<usize>,
+    pub hidden: Option<bool>,
+    pub tag: Option<String>,
+}
+
+impl PdfObjectToc {
+    /// Aligns the table of contents to a set of bounds.
+    pub fn align_to(&mut self, bounds: PdfBounds, align: (PdfVerticalAlign, PdfHorizontalAlign)) {
+        self.bounds = self.bounds.align_to(bounds, align);
+    }
+
+    /// Returns a collection of link annotations, one for each entry that goes to its page.
+    pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
+        self.rows(ctx)
+            .into_iter()
+            .map(|(page, [title, _, number])| PdfLinkAnnotation {
+                bounds: PdfBounds::new(title.bounds(ctx).ll, number.bounds(ctx).ur),
+                depth: self.depth.unwrap_or(ctx.config.page.depth),
+                link: PdfLink::GoTo {
+                    page,
+                    at: None,
+                    zoom: None,
+                    rect: None,
+                },
+                border: ctx.config.link_style.border,
+                highlight: ctx.config.link_style.highlight,
+            })
+            .collect()
+    }
+
+    /// Draws the object within the PDF.
+    pub fn draw(&self, ctx: PdfContext) {
+        for (_, texts) in self.rows(ctx) {
+            for text in texts {
+                text.draw(ctx);
+            }
+        }
+    }
+
+    /// Lays out the entries that fit within the bounds, returning the id of the page of each
+    /// entry alongside the text of its title, leader, and page number.
+    ///
+    /// Titles that are too long are cut off with an ellipsis so that there is always room for at
+    /// least one leader before the page number.
+    fn rows(&self, ctx: PdfContext) -> Vec<(RuntimePageId, [PdfObjectText; 3])> {
+        let size = self.size.unwrap_or_else(|| ctx.font_size(None));
+        let line_height = self
+            .line_height
+            .map(Mm)
+            .unwrap_or_else(|| Pt(size * 1.5).into());
+        let indent = Mm(self.indent.unwrap_or(5.0));
+        let leader = self.leader.as_deref().unwrap_or(".");
+
+        let text = |text: String, x: Mm, y: Mm| PdfObjectText {
+            point: PdfPoint::new(x, y),
+            text,
+            depth: self.depth,
+            font: self.font,
+            size: Some(size),
+            color: self.color,
+            ..Default::default()
+        };
+        let leader_width = text(leader.to_string(), Mm(0.0), Mm(0.0))
+            .bounds(ctx)
+            .width();
+
+        let mut rows = Vec::new();
+        let mut y = self.bounds.ur.y - line_height;
+        for (entry, number) in ctx.toc.iter_numbered() {
+            if self.max_level.is_some_and(|max| entry.level > max) {
+                continue;
+            }
+
+            if y < self.bounds.ll.y {
+                break;
+            }
+
+            let x = self.bounds.ll.x + indent * (entry.level - 1) as f32;
+            let mut number = text(number.to_string(), self.bounds.ur.x, y);
+            number.point.x -= number.bounds(ctx).width();
+
+            // Leaders are separated from the title and page number by the width of a leader
+            let leaders_end = number.point.x - leader_width;
+            let mut title = text(entry.title.clone(), x, y);
+            title.max_width = Some((leaders_end - leader_width * 2.0 - x).0.max(0.0));
+            let leaders_start = title.bounds(ctx).ur.x + leader_width;
+
+            let cnt = if leader_width.0 > 0.0 {
+                ((leaders_end - leaders_start) / leader_width)
+                    .floor()
+                    .max(0.0) as usize
+            } else {
+                0
+            };
+            let leaders = text(
+                leader.repeat(cnt),
+                leaders_end - leader_width * cnt as f32,
+                y,
+            );
+
+            rows.push((entry.page, [title, leaders, number]));
+            y -= line_height;
+        }
+
+        rows
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfObjectToc {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table_with_metatable("pdf.object.toc", |metatable| {
+            metatable.raw_set(
+                "align_to",
+                lua.create_function(
+                    move |_, (mut this, bounds, align): (Self, PdfBounds, PdfAlign)| {
+                        this.align_to(bounds, align.to_v_h());
+                        Ok(this)
+                    },
+                )?,
+            )?;
+
+            metatable.raw_set(
+                "place",
+                lua.create_function(move |_, (mut this, place): (Self, PdfPlace)| {
+                    let bounds = this.bounds;
+                    let (region, align) = place.region((bounds.width(), bounds.height()));
+                    this.align_to(region, align);
+                    Ok(this)
+                })?,
+            )?;
+
+            metatable.raw_set(
+                "bounds",
+                lua.create_function(move |_, this: Self| Ok(this.bounds))?,
+            )?;
+
+            metatable.raw_set(
+                "clone",
+                lua.create_function(move |lua, (this, overrides): (Self, Option<LuaTable>)| {
+                    lua_clone(lua, this, overrides)
+                })?,
+            )?;
+
+            Ok(())
+        })?;
+
+        self.bounds.add_to_table(&table)?;
+        table.raw_set("type", PdfObjectType::Toc)?;
+        table.raw_set("depth", self.depth)?;
+        table.raw_set("layer", self.layer)?;
+        table.raw_set("font", self.font)?;
+        table.raw_set("size", self.size)?;
+        table.raw_set("color", self.color)?;
+        table.raw_set("leader", self.leader)?;
+        table.raw_set("indent", self.indent)?;
+        table.raw_set("line_height", self.line_height)?;
+        table.raw_set("max_level", self.max_level)?;
+        table.raw_set("hidden", self.hidden)?;
+        table.raw_set("tag", self.tag)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+/// Names of the fields of table of contents objects, which are the only fields allowed in strict mode.
+const FIELDS: &[&str] = &[
+    "type",
+    "ll",
+    "ur",
+    "depth",
+    "layer",
+    "font",
+    "size",
+    "color",
+    "leader",
+    "indent",
+    "line_height",
+    "max_level",
+    "hidden",
+    "tag",
+];
+
+impl<'lua> FromLua<'lua> for PdfObjectToc {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Table(table) => {
+                table.check_fields(lua, "pdf.object.toc", FIELDS)?;
+                Ok(Self {
+                    bounds: PdfBounds::from_lua(LuaValue::Table(table.clone()), lua)?,
+                    depth: table.raw_get_ext("depth")?,
+                    layer: table
+                        .raw_get_ext::<_, Option<PdfLayer>>("layer")?
+                        .map(|x| x.name),
+                    font: table.raw_get_ext("font")?,
+                    size: table.raw_get_ext("size")?,
+                    color: table.raw_get_ext("color")?,
+                    leader: table.raw_get_ext("leader")?,
+                    indent: table.raw_get_ext("indent")?,
+                    line_height: table.raw_get_ext("line_height")?,
+                    max_level: table.raw_get_ext("max_level")?,
+                    hidden: table.raw_get_ext("hidden")?,
+                    tag: table.raw_get_ext("tag")?,
+                })
+            }
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.object.toc",
+                message: None,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{
+        RuntimeAlpha, RuntimeFonts, RuntimeToc, RuntimeTocEntry, RuntimeXObjects,
+    };
+    use printpdf::PdfDocument;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_lay_out_entries_that_fit_with_leaders_and_page_numbers() {
+        // Create a pdf context that we need for laying out entries
+        let doc = PdfDocument::empty("");
+        let (page_idx, layer_idx) = doc.add_page(Mm(0.0), Mm(0.0), "");
+        let layer = doc.get_page(page_idx).get_layer(layer_idx);
+        let mut font = RuntimeFonts::new();
+        let font_id = font.add_builtin_font().unwrap();
+        font.add_font_as_fallback(font_id);
+
+        let mut toc = RuntimeToc::new();
+        for (title, page, level) in [("January", 10, 1), ("Week 1", 11, 2), ("February", 12, 1)] {
+            toc.add(RuntimeTocEntry {
+                title: title.to_string(),
+                page,
+                level,
+            });
+        }
+        toc.set_numbers(HashMap::from([(10, 3), (11, 4), (12, 8)]));
+
+        let ctx = PdfContext {
+            config: &PdfConfig::default(),
+            layer: &layer,
+            fonts: &font,
+            fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &toc,
+            style: PdfStyle::default(),
+        };
+
+        // Only room for two entries
+        let obj = PdfObjectToc {
+            bounds: PdfBounds::from_coords_f32(10.0, 80.0, 90.0, 90.0),
+            line_height: Some(4.0),
+            ..Default::default()
+        };
+        let rows = obj.rows(ctx);
+        assert_eq!(rows.len(), 2);
+
+        let (page, [title, leaders, number]) = &rows[1];
+        assert_eq!(*page, 11);
+        assert_eq!(title.text, "Week 1");
+        assert_eq!(title.point, PdfPoint::from_coords_f32(15.0, 82.0));
+        assert_eq!(number.text, "4");
+        assert!((number.bounds(ctx).ur.x.0 - 90.0).abs() < 0.001);
+        assert!(leaders.text.chars().all(|ch| ch == '.'));
+        assert!(leaders.bounds(ctx).ll.x > title.bounds(ctx).ur.x);
+        assert!(leaders.bounds(ctx).ur.x < number.bounds(ctx).ll.x);
+
+        // Each entry links to its page, skipping levels beyond the maximum
+        let obj = PdfObjectToc {
+            max_level: Some(1),
+            ..obj
+        };
+        let pages: Vec<_> = obj
+            .link_annotations(ctx)
+            .into_iter()
+            .map(|x| x.link)
+            .collect();
+        assert_eq!(
+            pages,
+            [10, 12]
+                .map(|page| PdfLink::GoTo {
+                    page,
+                    at: None,
+                    zoom: None,
+                    rect: None,
+                })
+                .to_vec()
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub enum PdfObjectType {
     Rect,
     Shape,
     Text,
+    Toc,
 }
 
 impl PdfObjectType {
@@ -21,6 +22,7 @@ impl PdfObjectType {
             Self::Rect => "rect",
             Self::Shape => "shape",
             Self::Text => "text",
+            Self::Toc => "toc",
         }
     }
 
@@ -33,6 +35,7 @@ impl PdfObjectType {
             "rect" => Some(Self::Rect),
             "shape" => Some(Self::Shape),
             "text" => Some(Self::Text),
+            "toc" => Some(Self::Toc),
             _ => None,
         }
     }
@@ -1,22 +1,283 @@
-use crate::pdf::PdfLuaExt;
-use crate::runtime::{RuntimePage, RuntimePageId, RuntimePages};
+use crate::pdf::{PdfDate, PdfLuaExt, PdfLuaTableExt, PdfNavTabs, PdfObject, PdfPageLabel};
+use crate::runtime::{RuntimePage, RuntimePageId, RuntimePageRef, RuntimePages};
 use mlua::prelude::*;
+use std::rc::Rc;
 
 /// Collection of pages functions.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct PdfPages;
 
+impl PdfPages {
+    /// Creates a new page with the specified `title`, returning its id.
+    ///
+    /// If a section is active and has a running header, the header is drawn onto the new page.
+    pub fn create_page(lua: &Lua, title: String) -> LuaResult<RuntimePageId> {
+        Self::insert_page(lua, RuntimePage::new(title))
+    }
+
+    /// Inserts `page` at the end of the pages, returning its id.
+    ///
+    /// If the section of the page has a running header, the header is drawn onto the page.
+    pub fn insert_page(lua: &Lua, page: RuntimePage) -> LuaResult<RuntimePageId> {
+        let (id, header) = if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+            let id = pages.insert_page(page);
+            let header = pages
+                .get_page(id)
+                .and_then(|page| page.section)
+                .and_then(|name| pages.get_section(&name))
+                .and_then(|section| section.header.clone().map(|x| (x, section.clone())));
+            (id, header)
+        } else {
+            return Err(LuaError::runtime("Runtime pages are missing"));
+        };
+
+        // Invoke the header after releasing the pages as the header can access them
+        if let Some((key, section)) = header {
+            let page = lua
+                .app_data_ref::<RuntimePages>()
+                .and_then(|pages| pages.get_page(id));
+            let header: LuaFunction = lua.registry_value(&key)?;
+            header.call::<_, ()>((page, section))?;
+        }
+
+        Ok(id)
+    }
+}
+
+impl PdfPages {
+    /// Inserts `page` directly after the page with `after`, returning its id.
+    ///
+    /// Like [`PdfPages::insert_page`], the running header of the section of the page is drawn
+    /// onto it. Fails without inserting the page if the page to insert after does not exist.
+    pub fn insert_page_after(
+        lua: &Lua,
+        after: RuntimePageId,
+        page: RuntimePage,
+    ) -> LuaResult<RuntimePageId> {
+        match lua.app_data_ref::<RuntimePages>() {
+            Some(pages) if pages.index_of(after).is_none() => {
+                return Err(LuaError::runtime(format!("Page {after} does not exist")));
+            }
+            Some(_) => {}
+            None => return Err(LuaError::runtime("Runtime pages are missing")),
+        }
+
+        let id = Self::insert_page(lua, page)?;
+        let mut pages = lua
+            .app_data_mut::<RuntimePages>()
+            .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?;
+        let index = pages
+            .index_of(after)
+            .ok_or_else(|| LuaError::runtime(format!("Page {after} does not exist")))?;
+        pages.move_page(id, index + 1).map_err(LuaError::runtime)?;
+        Ok(id)
+    }
+
+    /// Creates a page from a title, or from a table with the title alongside the section and date
+    /// of the page, without inserting it.
+    fn new_page(lua: &Lua, value: LuaValue) -> LuaResult<RuntimePage> {
+        match value {
+            LuaValue::Table(table) => {
+                let mut page = RuntimePage::new(table.raw_get_ext::<_, String>("title")?);
+                page.section = table.raw_get_ext("section")?;
+                page.date = table.raw_get_ext("date")?;
+                Ok(page)
+            }
+            value => Ok(RuntimePage::new(String::from_lua(value, lua)?)),
+        }
+    }
+
+    /// Invokes the functions registered to draw onto pages, visiting pages in order, with each
+    /// page and a table of its `index` (starting at 1) and the `total` number of pages.
+    ///
+    /// Only the pages that exist when this is called are visited, so that the total is known.
+    pub fn run_hooks(lua: &Lua) -> LuaResult<()> {
+        // Copy what we need so hooks can access the pages while they run
+        let (hooks, pages) = if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+            let list: Vec<RuntimePage> = pages.ids().filter_map(|id| pages.get_page(id)).collect();
+            (pages.hooks().to_vec(), list)
+        } else {
+            return Err(LuaError::runtime("Runtime pages are missing"));
+        };
+
+        let total = pages.len();
+        for (i, page) in pages.into_iter().enumerate() {
+            for (_, key) in hooks
+                .iter()
+                .filter(|(id, _)| id.is_none_or(|id| id == page.id))
+            {
+                let hook: LuaFunction = lua.registry_value(key)?;
+                hook.call::<_, ()>((page.clone(), Self::draw_info(lua, i, total)?))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates the functions pushed onto pages in place of objects, visiting pages in order,
+    /// replacing each function with the object or list of objects that it returns.
+    ///
+    /// Like [`PdfPages::run_hooks`], each function is invoked with its page and a table of the
+    /// `index` of the page and the `total` number of pages. This happens once page references
+    /// are resolved, so functions can look up the pages that references point to.
+    pub fn evaluate_lazy(lua: &Lua) -> LuaResult<()> {
+        let (lazy, pages) = if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+            let list: Vec<RuntimePage> = pages.ids().filter_map(|id| pages.get_page(id)).collect();
+            (pages.lazy().to_vec(), list)
+        } else {
+            return Err(LuaError::runtime("Runtime pages are missing"));
+        };
+
+        let total = pages.len();
+        for (i, page) in pages.into_iter().enumerate() {
+            // Objects returned by earlier functions shift where later functions were pushed
+            let mut offset = 0;
+            for (_, index, key) in lazy.iter().filter(|(id, ..)| *id == page.id) {
+                let f: LuaFunction = lua.registry_value(key)?;
+                let objects =
+                    match f.call::<_, LuaValue>((page.clone(), Self::draw_info(lua, i, total)?))? {
+                        LuaValue::Nil => Vec::new(),
+                        LuaValue::Table(table) if !table.contains_key("type")? => {
+                            Vec::<PdfObject>::from_lua(LuaValue::Table(table), lua)?
+                        }
+                        value => vec![PdfObject::from_lua(value, lua)?],
+                    };
+
+                let len = objects.len();
+                page.insert(index + offset, objects);
+                offset += len;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the table passed to functions drawing onto the page at `index` (starting at 0) of
+    /// `total` pages.
+    fn draw_info(lua: &Lua, index: usize, total: usize) -> LuaResult<LuaTable> {
+        let info = lua.create_table()?;
+        info.raw_set("index", index + 1)?;
+        info.raw_set("total", total)?;
+        Ok(info)
+    }
+
+    /// Resolves every page reference to the first page, in order, that satisfies its predicate,
+    /// returning the placeholder ids of references that did not match any page.
+    pub fn resolve_refs(lua: &Lua) -> LuaResult<Vec<RuntimePageId>> {
+        // Copy what we need so predicates can access the pages while they run
+        let (refs, pages) = if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+            let list: Vec<RuntimePage> = pages.ids().filter_map(|id| pages.get_page(id)).collect();
+            (pages.refs().to_vec(), list)
+        } else {
+            return Err(LuaError::runtime("Runtime pages are missing"));
+        };
+
+        let mut missing = Vec::new();
+        for (id, page_ref) in refs {
+            let mut target = None;
+            for page in pages.iter() {
+                if page_ref.matches(lua, page)? {
+                    target = Some(page.id);
+                    break;
+                }
+            }
+
+            match target {
+                Some(target) => lua
+                    .app_data_mut::<RuntimePages>()
+                    .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?
+                    .set_resolved(id, target),
+                None => missing.push(id),
+            }
+        }
+
+        Ok(missing)
+    }
+}
+
+/// Query of the pages to find, where every field that is set must match.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfPageQuery {
+    pub title: Option<String>,
+    pub section: Option<String>,
+    pub date: Option<PdfDate>,
+}
+
+impl PdfPageQuery {
+    /// Returns true if `page` matches every field of the query that is set.
+    pub fn matches(&self, page: &RuntimePage) -> bool {
+        self.title.as_ref().is_none_or(|x| *x == page.title)
+            && self
+                .section
+                .as_ref()
+                .is_none_or(|x| page.section.as_ref() == Some(x))
+            && self.date.is_none_or(|x| page.date == Some(x))
+    }
+}
+
+impl<'lua> FromLua<'lua> for PdfPageQuery {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, _lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Nil => Ok(Self::default()),
+            LuaValue::Table(table) => Ok(Self {
+                title: table.raw_get_ext("title")?,
+                section: table.raw_get_ext("section")?,
+                date: table.raw_get_ext("date")?,
+            }),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.common.page_query",
+                message: None,
+            }),
+        }
+    }
+}
+
 impl<'lua> IntoLua<'lua> for PdfPages {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
         let (table, metatable) = lua.create_table_ext()?;
 
-        // Function to create a new page with the specified title.
+        // Function to create a new page with the specified title, or from a table with the title
+        // alongside the section and date of the page.
         metatable.raw_set(
             "create",
-            lua.create_function(|lua, title: String| {
+            lua.create_function(|lua, value: LuaValue| {
+                PdfPages::insert_page(lua, PdfPages::new_page(lua, value)?)
+            })?,
+        )?;
+
+        // Function to create a new page like `create`, placing it directly after another page.
+        metatable.raw_set(
+            "insert_after",
+            lua.create_function(|lua, (after, value): (RuntimePageId, LuaValue)| {
+                PdfPages::insert_page_after(lua, after, PdfPages::new_page(lua, value)?)
+            })?,
+        )?;
+
+        // Function to move a page to a position (starting at 1) within the pages.
+        metatable.raw_set(
+            "move",
+            lua.create_function(|lua, (id, index): (RuntimePageId, usize)| {
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    if index == 0 {
+                        return Err(LuaError::runtime("Page positions start at 1"));
+                    }
+
+                    pages.move_page(id, index - 1).map_err(LuaError::runtime)
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to remove a page by its id, returning true if the page existed.
+        metatable.raw_set(
+            "remove",
+            lua.create_function(|lua, id: RuntimePageId| {
                 if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
-                    Ok(pages.insert_page(RuntimePage::new(title)))
+                    Ok(pages.remove_page(id).is_some())
                 } else {
                     Err(LuaError::runtime("Runtime pages are missing"))
                 }
@@ -35,6 +296,107 @@ impl<'lua> IntoLua<'lua> for PdfPages {
             })?,
         )?;
 
+        // Function to draw a strip of tabs linking to pages along an edge of every page.
+        metatable.raw_set(
+            "nav_tabs",
+            lua.create_function(|lua, tabs: PdfNavTabs| tabs.lua_push(lua))?,
+        )?;
+
+        // Function to replace the labels of ranges of pages, shown by viewers in place of page
+        // numbers.
+        metatable.raw_set(
+            "set_labels",
+            lua.create_function(|lua, labels: Vec<PdfPageLabel>| {
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    pages.set_labels(labels).map_err(LuaError::runtime)
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to return the labels of ranges of pages as a list.
+        metatable.raw_set(
+            "labels",
+            lua.create_function(|lua, ()| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Ok(pages.labels().to_vec())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to return the ids of pages matching a query as a list, in order.
+        metatable.raw_set(
+            "find",
+            lua.create_function(|lua, query: PdfPageQuery| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Ok(pages.find(|page| query.matches(page)))
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to reference a page that may not exist yet, returning an id that links can
+        // target and that resolves to the first page satisfying the predicate once every page
+        // has been created.
+        metatable.raw_set(
+            "ref",
+            lua.create_function(|lua, page_ref: RuntimePageRef| {
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    Ok(pages.add_ref(page_ref))
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to return the id of the page that a reference resolved to, which is only known
+        // once the script finishes, or the id itself if it is not a reference.
+        metatable.raw_set(
+            "resolve",
+            lua.create_function(|lua, id: RuntimePageId| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    if pages.is_ref(id) {
+                        Ok(pages.get_resolved(id))
+                    } else {
+                        Ok(Some(id))
+                    }
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to return the total number of pages created so far.
+        metatable.raw_set(
+            "count",
+            lua.create_function(|lua, ()| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Ok(pages.len())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to register a function that draws onto every page once every page exists,
+        // invoked with each page and a table of its `index` and the `total` pages.
+        metatable.raw_set(
+            "on_draw",
+            lua.create_function(|lua, f: LuaFunction| {
+                let key = Rc::new(lua.create_registry_value(f)?);
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    pages.add_hook(None, key);
+                    Ok(())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
         // Function to return all page ids as a list.
         metatable.raw_set(
             "ids",
@@ -50,3 +412,190 @@ impl<'lua> IntoLua<'lua> for PdfPages {
         Ok(LuaValue::Table(table))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_find_pages_by_section_and_date_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+
+        lua.load(chunk! {
+            local jan = pdf.pages.create({ title = "January", section = "monthly" })
+            local day = pdf.pages.create({
+                title = "2025-01-01",
+                section = "daily",
+                date = "2025-01-01",
+            })
+            local feb = pdf.pages.create({ title = "February", section = "monthly" })
+            pdf.pages.create("Notes")
+
+            pdf.utils.assert_deep_equal(pdf.pages.find({ section = "monthly" }), { jan, feb })
+            pdf.utils.assert_deep_equal(
+                pdf.pages.find({ section = "daily", date = "2025-01-01" }),
+                { day }
+            )
+            pdf.utils.assert_deep_equal(pdf.pages.find({ title = "Missing" }), {})
+            assert(#pdf.pages.find() == 4, "Expected every page")
+            assert(tostring(pdf.pages.get(day).date) == "2025-01-01", "Missing date of page")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_reorder_pages_in_lua() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+
+        lua.load(chunk! {
+            local jan = pdf.pages.create("January")
+            local mar = pdf.pages.create("March")
+            local index = pdf.pages.create("Index")
+            local feb = pdf.pages.insert_after(jan, { title = "February", section = "months" })
+
+            pdf.pages.move(index, 1)
+            pdf.utils.assert_deep_equal(pdf.pages.ids(), { index, jan, feb, mar })
+            assert(pdf.pages.get(feb).section == "months", "Missing section of page")
+
+            assert(pdf.pages.remove(mar), "Expected page to be removed")
+            assert(not pdf.pages.remove(mar), "Expected page to be missing")
+            pdf.utils.assert_deep_equal(pdf.pages.ids(), { index, jan, feb })
+
+            assert(not pcall(pdf.pages.move, jan, 0), "Expected position 0 to fail")
+            assert(not pcall(pdf.pages.move, jan, 4), "Expected position 4 to fail")
+            assert(not pcall(pdf.pages.insert_after, mar, "April"), "Expected missing page to fail")
+            assert(#pdf.pages.ids() == 3, "Unexpectedly inserted page after missing page")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn run_hooks_should_draw_onto_pages_knowing_their_index_and_total() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+
+        lua.load(chunk! {
+            local first = pdf.pages.get(pdf.pages.create("first"))
+            pdf.pages.create("second")
+            pdf.pages.create("third")
+            assert(pdf.pages.count() == 3, "Unexpected count of pages")
+
+            pdf.pages.on_draw(function(page, info)
+                page.push(pdf.object.text({
+                    text = "Page " .. info.index .. " of " .. info.total,
+                }))
+            end)
+            first:on_draw(function(page, info)
+                assert(page.title == "first", "Unexpected page")
+                assert(info.index == 1 and info.total == 3, "Unexpected index or total")
+            end)
+        })
+        .exec()
+        .unwrap();
+
+        PdfPages::run_hooks(&lua).expect("Hook failed");
+
+        let pages = lua.app_data_ref::<RuntimePages>().unwrap();
+        let texts: Vec<String> = pages
+            .ids()
+            .filter_map(|id| pages.get_page(id))
+            .map(|page| match &page.objects()[0] {
+                PdfObject::Text(x) => x.text.clone(),
+                x => panic!("Unexpected object: {x:?}"),
+            })
+            .collect();
+        assert_eq!(texts, vec!["Page 1 of 3", "Page 2 of 3", "Page 3 of 3"]);
+    }
+
+    #[test]
+    fn evaluate_lazy_should_replace_functions_with_their_objects_once_refs_resolve() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+
+        lua.load(chunk! {
+            local index = pdf.pages.get(pdf.pages.create("index"))
+            local notes = pdf.pages.ref({ title = "notes" })
+
+            index.push(pdf.object.text({ text = "first" }))
+            index.push(function(page, info)
+                assert(info.index == 1 and info.total == 2, "Unexpected index or total")
+                assert(pdf.pages.resolve(notes) == pdf.pages.ids()[2], "Unresolved reference")
+                return {
+                    pdf.object.text({ text = "second" }),
+                    pdf.object.text({ text = "third" }),
+                }
+            end)
+            index.push(pdf.object.text({ text = "fourth" }))
+            index.push(function(page)
+                return pdf.object.text({ text = "fifth" })
+            end)
+            index.push(function() end)
+
+            assert(pdf.pages.resolve(notes) == nil, "Reference resolved too early")
+            pdf.pages.create("notes")
+        })
+        .exec()
+        .unwrap();
+
+        PdfPages::resolve_refs(&lua).unwrap();
+        PdfPages::evaluate_lazy(&lua).expect("Function failed");
+
+        let pages = lua.app_data_ref::<RuntimePages>().unwrap();
+        let page = pages.get_page(pages.ids().next().unwrap()).unwrap();
+        let texts: Vec<String> = page
+            .objects()
+            .iter()
+            .map(|obj| match obj {
+                PdfObject::Text(x) => x.text.clone(),
+                x => panic!("Unexpected object: {x:?}"),
+            })
+            .collect();
+        assert_eq!(texts, vec!["first", "second", "third", "fourth", "fifth"]);
+    }
+
+    #[test]
+    fn resolve_refs_should_resolve_to_first_matching_page() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data(RuntimePages::new());
+
+        let (by_query, by_function, missing, july): (
+            RuntimePageId,
+            RuntimePageId,
+            RuntimePageId,
+            RuntimePageId,
+        ) = lua
+            .load(chunk! {
+                local by_query = pdf.pages.ref({ title = "July" })
+                local by_function = pdf.pages.ref(function(page)
+                    return page.section == "monthly"
+                end)
+                local missing = pdf.pages.ref({ title = "Missing" })
+                pdf.pages.create("Cover")
+                local july = pdf.pages.create({ title = "July", section = "monthly" })
+                pdf.pages.create({ title = "August", section = "monthly" })
+                return by_query, by_function, missing, july
+            })
+            .eval()
+            .unwrap();
+
+        assert_eq!(PdfPages::resolve_refs(&lua).unwrap(), vec![missing]);
+
+        let pages = lua.app_data_ref::<RuntimePages>().unwrap();
+        let mut resolved: Vec<_> = pages.resolved().collect();
+        resolved.sort();
+        let mut expected = vec![(by_query, july), (by_function, july)];
+        expected.sort();
+        assert_eq!(resolved, expected);
+    }
+}
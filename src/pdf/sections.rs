@@ -0,0 +1,209 @@
+use crate::pdf::{PdfLuaExt, PdfLuaTableExt};
+use crate::runtime::{RuntimePage, RuntimePages, RuntimeSection};
+use mlua::prelude::*;
+use std::rc::Rc;
+
+/// Collection of sections functions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfSections;
+
+impl PdfSections {
+    /// Begins a new section named `name`, grouping together all pages created afterwards.
+    ///
+    /// Supports the following options:
+    ///
+    /// * `divider` - function invoked with a new page inserted to open the section.
+    /// * `title` - title of the divider page, defaulting to the name of the section.
+    /// * `header` - function invoked with each new page of the section to draw a running header.
+    pub fn begin<'lua>(
+        lua: &'lua Lua,
+        name: String,
+        opts: Option<LuaTable<'lua>>,
+    ) -> LuaResult<RuntimeSection> {
+        let (divider, title, header) = match opts {
+            Some(opts) => (
+                opts.raw_get::<_, Option<LuaFunction>>("divider")?,
+                opts.raw_get_ext::<_, Option<String>>("title")?,
+                opts.raw_get::<_, Option<LuaFunction>>("header")?,
+            ),
+            None => (None, None, None),
+        };
+
+        let mut section = RuntimeSection::new(name);
+        if let Some(header) = header {
+            section.header = Some(Rc::new(lua.create_registry_value(header)?));
+        }
+
+        // Begin the section and insert the divider, which we do directly rather than through
+        // pdf.pages.create to avoid drawing the running header on the divider
+        let divider_page = if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+            let title = title.unwrap_or_else(|| section.name.clone());
+            pages.begin_section(section).map_err(LuaError::runtime)?;
+
+            if divider.is_some() {
+                let page = RuntimePage::new(title);
+                pages.insert_page(page.clone());
+                if let Some(section) = pages.current_section_mut() {
+                    section.divider = Some(page.id);
+                }
+
+                Some(page)
+            } else {
+                None
+            }
+        } else {
+            return Err(LuaError::runtime("Runtime pages are missing"));
+        };
+
+        let section = Self::current(lua)?.expect("Section just began");
+
+        // Invoke the divider after releasing the pages as the divider can access them
+        if let (Some(divider), Some(page)) = (divider, divider_page) {
+            divider.call::<_, ()>((page, section.clone()))?;
+        }
+
+        Ok(section)
+    }
+
+    /// Returns a copy of the active section, if any.
+    pub fn current(lua: &Lua) -> LuaResult<Option<RuntimeSection>> {
+        if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+            Ok(pages.current_section().cloned())
+        } else {
+            Err(LuaError::runtime("Runtime pages are missing"))
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfSections {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to begin a new section, optionally opened by a divider page.
+        metatable.raw_set(
+            "begin",
+            lua.create_function(|lua, (name, opts): (String, Option<LuaTable>)| {
+                PdfSections::begin(lua, name, opts)
+            })?,
+        )?;
+
+        // Function to end the active section so new pages do not belong to any section.
+        metatable.raw_set(
+            "finish",
+            lua.create_function(|lua, ()| {
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    pages.end_section();
+                    Ok(())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to retrieve the active section.
+        metatable.raw_set(
+            "current",
+            lua.create_function(|lua, ()| PdfSections::current(lua))?,
+        )?;
+
+        // Function to retrieve a section by its name.
+        metatable.raw_set(
+            "get",
+            lua.create_function(|lua, name: String| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Ok(pages.get_section(&name).cloned())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        // Function to return all sections as a list, in the order they began.
+        metatable.raw_set(
+            "list",
+            lua.create_function(|lua, ()| {
+                if let Some(pages) = lua.app_data_ref::<RuntimePages>() {
+                    Ok(pages.sections().to_vec())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::Pdf;
+    use crate::runtime::RuntimePages;
+    use mlua::chunk;
+    use mlua::prelude::*;
+
+    #[test]
+    fn should_group_pages_into_sections_in_lua() {
+        let lua = Lua::new();
+        lua.set_app_data(RuntimePages::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local cover = pdf.pages.create("Cover")
+
+            local divided = {}
+            local headers = {}
+            local q1 = pdf.sections.begin("Q1", {
+                title = "First Quarter",
+                divider = function(page, section)
+                    table.insert(divided, page.title .. ":" .. section.name)
+                end,
+                header = function(page, section)
+                    table.insert(headers, page.title .. ":" .. section.name)
+                end,
+            })
+
+            local jan = pdf.pages.create("January")
+            local feb = pdf.pages.create("February")
+            pdf.utils.assert_deep_equal(pdf.pages.get(jan).section, "Q1")
+            pdf.utils.assert_deep_equal(pdf.sections.current().name, "Q1")
+
+            pdf.sections.begin("Q2")
+            local apr = pdf.pages.create("April")
+            pdf.sections.finish()
+            local notes = pdf.pages.create("Notes")
+
+            pdf.utils.assert_deep_equal(divided, { "First Quarter:Q1" })
+            pdf.utils.assert_deep_equal(headers, { "January:Q1", "February:Q1" })
+            pdf.utils.assert_deep_equal(pdf.sections.current(), nil)
+            pdf.utils.assert_deep_equal(pdf.pages.get(cover).section, nil)
+            pdf.utils.assert_deep_equal(pdf.pages.get(notes).section, nil)
+
+            pdf.utils.assert_deep_equal(pdf.sections.get("Q1"), {
+                name = "Q1",
+                divider = q1.divider,
+                pages = { q1.divider, jan, feb },
+            })
+            pdf.utils.assert_deep_equal(pdf.sections.list(), {
+                pdf.sections.get("Q1"),
+                { name = "Q2", pages = { apr } },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_fail_to_begin_a_section_that_already_exists() {
+        let lua = Lua::new();
+        lua.set_app_data(RuntimePages::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            pdf.sections.begin("Q1")
+            pdf.sections.begin("Q1")
+        })
+        .exec()
+        .expect_err("Unexpectedly began duplicate section");
+    }
+}
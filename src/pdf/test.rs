@@ -0,0 +1,219 @@
+use crate::pdf::{PdfBounds, PdfLuaExt, PdfObject, PdfUtils};
+use crate::runtime::RuntimeTestSuite;
+use mlua::prelude::*;
+
+/// Collection of functions to write tests of a script's layout in Lua, which are run by
+/// `makepdf test`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfTest;
+
+impl PdfTest {
+    /// Returns the bounds of `value`, which is either an object or bounds.
+    pub fn bounds_of(lua: &Lua, value: LuaValue) -> LuaResult<PdfBounds> {
+        // Objects are distinguished from bounds by their type
+        let is_object = value
+            .as_table()
+            .map(|x| x.raw_get::<_, LuaValue>("type"))
+            .transpose()?
+            .is_some_and(|x| !x.is_nil());
+
+        if is_object {
+            PdfObject::from_lua(value, lua)?.lua_bounds(lua)
+        } else {
+            PdfBounds::from_lua(value, lua)
+        }
+    }
+
+    /// Fails if `inner` is not entirely within `outer`, where both are objects or bounds.
+    pub fn assert_bounds_within(lua: &Lua, inner: LuaValue, outer: LuaValue) -> LuaResult<()> {
+        let inner = Self::bounds_of(lua, inner)?;
+        let outer = Self::bounds_of(lua, outer)?;
+
+        if inner.ll.x < outer.ll.x
+            || inner.ll.y < outer.ll.y
+            || inner.ur.x > outer.ur.x
+            || inner.ur.y > outer.ur.y
+        {
+            return Err(LuaError::runtime(format!(
+                "Expected {:?} to be within {:?}",
+                inner.to_coords_f32(),
+                outer.to_coords_f32(),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Fails if any two of `objects` overlap, where each is an object or bounds.
+    ///
+    /// Objects are compared using their bounds, so objects that only touch do not overlap.
+    pub fn assert_no_overlap(lua: &Lua, objects: Vec<LuaValue>) -> LuaResult<()> {
+        let bounds = objects
+            .into_iter()
+            .map(|x| Self::bounds_of(lua, x))
+            .collect::<LuaResult<Vec<_>>>()?;
+
+        let mut errors = Vec::new();
+        for (i, a) in bounds.iter().enumerate() {
+            for (j, b) in bounds.iter().enumerate().skip(i + 1) {
+                if a.overlaps(b) {
+                    errors.push(format!(
+                        "Objects {} and {} overlap: {:?} and {:?}",
+                        i + 1,
+                        j + 1,
+                        a.to_coords_f32(),
+                        b.to_coords_f32(),
+                    ));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(LuaError::runtime(errors.join("\n")));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfTest {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to group tests, running `func` immediately to register the tests within it
+        metatable.raw_set(
+            "describe",
+            lua.create_function(|lua, (name, func): (String, LuaFunction)| {
+                match lua.app_data_mut::<RuntimeTestSuite>() {
+                    Some(mut suite) => suite.enter(name),
+                    None => return Err(LuaError::runtime("Runtime test suite is missing")),
+                }
+
+                let result = func.call::<_, ()>(());
+                if let Some(mut suite) = lua.app_data_mut::<RuntimeTestSuite>() {
+                    suite.exit();
+                }
+
+                result
+            })?,
+        )?;
+
+        // Function to register a test, which is only run when testing the script
+        metatable.raw_set(
+            "it",
+            lua.create_function(|lua, (name, func): (String, LuaFunction)| {
+                if let Some(mut suite) = lua.app_data_mut::<RuntimeTestSuite>() {
+                    suite.add(lua, &name, func)
+                } else {
+                    Err(LuaError::runtime("Runtime test suite is missing"))
+                }
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "assert_deep_equal",
+            lua.create_function(|_, (a, b, opts): (LuaValue, LuaValue, Option<LuaTable>)| {
+                let ignore_metatable = opts
+                    .map(|opts| opts.raw_get("ignore_metatable").unwrap_or(false))
+                    .unwrap_or(false);
+                PdfUtils::try_assert_deep_equal(a, b, true, ignore_metatable)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "assert_not_deep_equal",
+            lua.create_function(|_, (a, b, opts): (LuaValue, LuaValue, Option<LuaTable>)| {
+                let ignore_metatable = opts
+                    .map(|opts| opts.raw_get("ignore_metatable").unwrap_or(false))
+                    .unwrap_or(false);
+                PdfUtils::try_assert_deep_equal(a, b, false, ignore_metatable)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "assert_bounds_within",
+            lua.create_function(|lua, (inner, outer): (LuaValue, LuaValue)| {
+                Self::assert_bounds_within(lua, inner, outer)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "assert_no_overlap",
+            lua.create_function(|lua, objects: Vec<LuaValue>| {
+                Self::assert_no_overlap(lua, objects)
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use crate::runtime::RuntimeFonts;
+    use mlua::chunk;
+
+    /// Creates a Lua runtime with the global pdf instance, fonts, and an empty test suite.
+    fn new_lua() -> Lua {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+        lua.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+        lua.set_app_data(RuntimeTestSuite::new());
+        lua
+    }
+
+    #[test]
+    fn should_be_able_to_register_tests_within_describe_blocks() {
+        let lua = new_lua();
+
+        lua.load(chunk! {
+            pdf.test.describe("header", function()
+                pdf.test.it("fits within the page", function()
+                    local rect = pdf.object.rect({ ll = { x = 10, y = 10 }, ur = { x = 20, y = 20 } })
+                    pdf.test.assert_bounds_within(rect, { ll = { x = 0, y = 0 }, ur = { x = 30, y = 30 } })
+                end)
+
+                pdf.test.it("does not overlap", function()
+                    pdf.test.assert_no_overlap({
+                        { ll = { x = 0, y = 0 }, ur = { x = 10, y = 10 } },
+                        { ll = { x = 5, y = 5 }, ur = { x = 15, y = 15 } },
+                    })
+                end)
+            end)
+        })
+        .exec()
+        .expect("Failed to register tests");
+
+        let suite = lua.remove_app_data::<RuntimeTestSuite>().unwrap();
+        let report = suite.run(&lua);
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.results[0].name, "header fits within the page");
+        assert!(report.results[0].passed());
+        assert_eq!(report.results[1].name, "header does not overlap");
+        assert!(report.results[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Objects 1 and 2 overlap"));
+    }
+
+    #[test]
+    fn assert_bounds_within_should_fail_if_outside_bounds() {
+        let lua = new_lua();
+
+        lua.load(chunk! {
+            local text = pdf.object.text({ x = 25, y = 25, text = "Hello" })
+            pdf.test.assert_bounds_within(text, { ll = { x = 0, y = 0 }, ur = { x = 30, y = 30 } })
+        })
+        .exec()
+        .expect_err("Unexpectedly passed with text outside bounds");
+    }
+}
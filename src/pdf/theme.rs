@@ -0,0 +1,134 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use mlua::prelude::*;
+
+/// Maximum number of theme references followed when resolving a value, guarding against cycles.
+const MAX_THEME_DEPTH: usize = 8;
+
+/// Collection of theme functions, where a theme is the table `pdf.theme` of named values (e.g.
+/// colors and sizes) that objects reference by name such as `fill_color = "@accent"`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfTheme;
+
+impl PdfTheme {
+    /// Prefix marking a string as a reference to a value within the theme.
+    pub const PREFIX: char = '@';
+
+    /// Resolves `value` if it is a reference to a value within the theme (e.g. `"@accent"`),
+    /// following references to other theme values, otherwise returning it unchanged.
+    ///
+    /// Fails if the referenced value is not defined in the theme.
+    pub fn resolve<'lua>(lua: &'lua Lua, value: LuaValue<'lua>) -> LuaResult<LuaValue<'lua>> {
+        let mut value = value;
+
+        for _ in 0..MAX_THEME_DEPTH {
+            let name = match Self::reference_name(&value) {
+                Some(name) => name,
+                None => return Ok(value),
+            };
+
+            let theme: Option<LuaTable> = lua
+                .globals()
+                .raw_get::<_, Option<LuaTable>>(GLOBAL_PDF_VAR_NAME)?
+                .map(|pdf| pdf.raw_get("theme"))
+                .transpose()?
+                .flatten();
+
+            value = match theme.map(|x| x.raw_get::<_, LuaValue>(name.as_str())) {
+                Some(Ok(LuaValue::Nil)) | None => {
+                    return Err(LuaError::runtime(format!(
+                        "Theme value {}{name} is not defined",
+                        Self::PREFIX
+                    )))
+                }
+                Some(x) => x?,
+            };
+        }
+
+        Err(LuaError::runtime(format!(
+            "Theme references nested more than {MAX_THEME_DEPTH} deep"
+        )))
+    }
+
+    /// Returns the name of the theme value referenced by `value`, if it is a reference.
+    fn reference_name(value: &LuaValue) -> Option<String> {
+        match value {
+            LuaValue::String(s) => s
+                .to_str()
+                .ok()
+                .and_then(|s| s.strip_prefix(Self::PREFIX))
+                .filter(|s| !s.is_empty())
+                .map(ToString::to_string),
+            _ => None,
+        }
+    }
+}
+
+/// Wrapper that converts a Lua value into `T`, resolving a theme reference when `T` cannot be
+/// converted from the value directly.
+///
+/// Values are converted directly first so that strings that happen to look like references,
+/// such as the text `"@home"`, are left alone when `T` accepts any string.
+pub struct PdfThemed<T>(pub T);
+
+impl<'lua, T: FromLua<'lua>> FromLua<'lua> for PdfThemed<T> {
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        match T::from_lua(value.clone(), lua) {
+            Ok(x) => Ok(Self(x)),
+            Err(x) if PdfTheme::reference_name(&value).is_none() => Err(x),
+            Err(_) => T::from_lua(PdfTheme::resolve(lua, value)?, lua).map(Self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+    use mlua::prelude::*;
+
+    #[test]
+    fn should_resolve_theme_references_when_converting_objects() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            pdf.theme.accent = "#112233"
+            pdf.theme.primary = "@accent"
+            pdf.theme.thin = 0.5
+
+            local rect = pdf.object.rect({
+                ll = { x = 0, y = 0 },
+                ur = { x = 1, y = 1 },
+                fill_color = "@primary",
+                outline_thickness = "@thin",
+            })
+            pdf.utils.assert_deep_equal(rect.fill_color, pdf.utils.color("#112233"))
+            pdf.utils.assert_deep_equal(rect.outline_thickness, 0.5)
+
+            // Strings that can be used directly are not treated as references
+            local text = pdf.object.text({ text = "@accent" })
+            pdf.utils.assert_deep_equal(text.text, "@accent")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn should_fail_to_resolve_undefined_or_cyclic_theme_references() {
+        let lua = Lua::new();
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk!(pdf.object.rect({ fill_color = "@missing" })))
+            .exec()
+            .expect_err("Unexpectedly resolved missing theme value");
+
+        lua.load(chunk! {
+            pdf.theme.a = "@b"
+            pdf.theme.b = "@a"
+            pdf.object.rect({ fill_color = "@a" })
+        })
+        .exec()
+        .expect_err("Unexpectedly resolved cyclic theme value");
+    }
+}
@@ -0,0 +1,95 @@
+use crate::pdf::PdfLuaExt;
+use crate::runtime::{RuntimePageId, RuntimeToc, RuntimeTocEntry};
+use mlua::prelude::*;
+
+/// Collection of table of contents functions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfToc;
+
+impl PdfToc {
+    /// Adds an entry titled `title` for the page with id `page` to the table of contents, nested
+    /// at `level` (defaulting to 1).
+    ///
+    /// Pages are numbered once every page exists, so entries can refer to pages that are not
+    /// yet created.
+    pub fn add(
+        lua: &Lua,
+        title: String,
+        page: RuntimePageId,
+        level: Option<usize>,
+    ) -> LuaResult<()> {
+        let level = level.unwrap_or(1);
+        if level == 0 {
+            return Err(LuaError::runtime("Table of contents levels start at 1"));
+        }
+
+        if let Some(mut toc) = lua.app_data_mut::<RuntimeToc>() {
+            toc.add(RuntimeTocEntry { title, page, level });
+            Ok(())
+        } else {
+            Err(LuaError::runtime("Runtime toc is missing"))
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfToc {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to add an entry to the table of contents.
+        metatable.raw_set(
+            "add",
+            lua.create_function(
+                |lua, (title, page, level): (String, RuntimePageId, Option<usize>)| {
+                    PdfToc::add(lua, title, page, level)
+                },
+            )?,
+        )?;
+
+        // Function to return all entries as a list, in the order they were added.
+        metatable.raw_set(
+            "list",
+            lua.create_function(|lua, ()| {
+                if let Some(toc) = lua.app_data_ref::<RuntimeToc>() {
+                    Ok(toc.iter().cloned().collect::<Vec<_>>())
+                } else {
+                    Err(LuaError::runtime("Runtime toc is missing"))
+                }
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::Pdf;
+    use crate::runtime::RuntimeToc;
+    use mlua::chunk;
+    use mlua::prelude::*;
+
+    #[test]
+    fn should_collect_entries_of_table_of_contents_in_lua() {
+        let lua = Lua::new();
+        lua.set_app_data(RuntimeToc::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            pdf.toc.add("January", 3)
+            pdf.toc.add("Week 1", 4, 2)
+
+            pdf.utils.assert_deep_equal(pdf.toc.list(), {
+                { title = "January", page = 3, level = 1 },
+                { title = "Week 1", page = 4, level = 2 },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        lua.load(chunk!(pdf.toc.add("Invalid", 1, 0)))
+            .exec()
+            .expect_err("Unexpectedly added entry at level 0");
+    }
+}
@@ -1,4 +1,8 @@
-use crate::pdf::{PdfBounds, PdfColor, PdfDate, PdfLink, PdfLuaExt, PdfPadding, PdfPoint};
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfDate, PdfLength, PdfLink, PdfLuaExt, PdfLuaTableExt, PdfPadding,
+    PdfPoint,
+};
+use crate::runtime::RuntimeFiles;
 use mlua::prelude::*;
 use printpdf::{Mm, Pt};
 use tailcall::tailcall;
@@ -51,6 +55,204 @@ impl PdfUtils {
         }
     }
 
+    /// Converts numeric pixels to millimeters using the DPI of pages.
+    pub fn px_to_mm(lua: &Lua, px: LuaValue) -> LuaResult<f32> {
+        match px {
+            LuaValue::Integer(value) => {
+                Ok(PdfLength::px_to_mm(value as f32, PdfLength::lua_dpi(lua)))
+            }
+            LuaValue::Number(value) => {
+                Ok(PdfLength::px_to_mm(value as f32, PdfLength::lua_dpi(lua)))
+            }
+            _ => Err(LuaError::runtime("value not numeric")),
+        }
+    }
+
+    /// Reads the file at `path` as a string, failing if the file is not within a directory the
+    /// script is allowed to access.
+    pub fn read_to_string(lua: &Lua, path: &str) -> LuaResult<String> {
+        if let Some(files) = lua.app_data_ref::<RuntimeFiles>() {
+            files
+                .read_to_string(path)
+                .map_err(|x| LuaError::runtime(format!("Failed to read '{path}': {x}")))
+        } else {
+            Err(LuaError::runtime("Runtime files are missing"))
+        }
+    }
+
+    /// Parses JSON `text` into a Lua value, where objects and arrays become tables and null
+    /// becomes nil.
+    pub fn parse_json<'lua>(lua: &'lua Lua, text: &str) -> LuaResult<LuaValue<'lua>> {
+        fn convert<'lua>(lua: &'lua Lua, value: serde_json::Value) -> LuaResult<LuaValue<'lua>> {
+            use serde_json::Value;
+            Ok(match value {
+                Value::Null => LuaValue::Nil,
+                Value::Bool(x) => LuaValue::Boolean(x),
+                Value::Number(x) => match x.as_i64() {
+                    Some(x) => LuaValue::Integer(x as _),
+                    None => LuaValue::Number(x.as_f64().unwrap_or(f64::NAN)),
+                },
+                Value::String(x) => LuaValue::String(lua.create_string(x)?),
+                Value::Array(x) => {
+                    let table = lua.create_table_with_capacity(x.len(), 0)?;
+                    for (i, value) in x.into_iter().enumerate() {
+                        table.raw_set(i + 1, convert(lua, value)?)?;
+                    }
+                    LuaValue::Table(table)
+                }
+                Value::Object(x) => {
+                    let table = lua.create_table_with_capacity(0, x.len())?;
+                    for (key, value) in x {
+                        table.raw_set(key, convert(lua, value)?)?;
+                    }
+                    LuaValue::Table(table)
+                }
+            })
+        }
+
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|x| LuaError::runtime(format!("Invalid JSON: {x}")))?;
+        convert(lua, value)
+    }
+
+    /// Converts a Lua `value` into JSON, where tables whose keys are the integers `1..n` become
+    /// arrays and all other tables become objects. Functions and other values that cannot be
+    /// represented in JSON are skipped.
+    ///
+    /// Fails if tables are nested too deeply, such as when a table contains itself.
+    pub fn to_json(value: LuaValue) -> LuaResult<serde_json::Value> {
+        /// Maximum depth of nested tables, which guards against tables that contain themselves.
+        const MAX_DEPTH: usize = 64;
+
+        fn convert(value: LuaValue, depth: usize) -> LuaResult<Option<serde_json::Value>> {
+            use serde_json::Value;
+            Ok(Some(match value {
+                LuaValue::Nil => Value::Null,
+                LuaValue::Boolean(x) => Value::Bool(x),
+                LuaValue::Integer(x) => Value::from(x),
+                LuaValue::Number(x) => Value::from(x),
+                LuaValue::String(x) => Value::String(x.to_string_lossy().to_string()),
+                LuaValue::Table(_) if depth >= MAX_DEPTH => {
+                    return Err(LuaError::runtime(
+                        "Cannot convert table to JSON as it is nested too deeply",
+                    ))
+                }
+                LuaValue::Table(x) => {
+                    let len = x.raw_len();
+                    let pairs = x
+                        .clone()
+                        .pairs::<LuaValue, LuaValue>()
+                        .collect::<LuaResult<Vec<_>>>()?;
+
+                    if len > 0 && pairs.len() == len {
+                        let mut values = Vec::with_capacity(len);
+                        for value in x.sequence_values::<LuaValue>() {
+                            values.push(convert(value?, depth + 1)?.unwrap_or(Value::Null));
+                        }
+                        Value::Array(values)
+                    } else {
+                        let mut map = serde_json::Map::new();
+                        for (key, value) in pairs {
+                            let key = match key {
+                                LuaValue::String(x) => x.to_string_lossy().to_string(),
+                                LuaValue::Integer(x) => x.to_string(),
+                                LuaValue::Number(x) => x.to_string(),
+                                LuaValue::Boolean(x) => x.to_string(),
+                                _ => continue,
+                            };
+
+                            if let Some(value) = convert(value, depth + 1)? {
+                                map.insert(key, value);
+                            }
+                        }
+                        Value::Object(map)
+                    }
+                }
+                _ => return Ok(None),
+            }))
+        }
+
+        Ok(convert(value, 0)?.unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Parses CSV `text` into rows of fields, separated by `delimiter`.
+    ///
+    /// Fields can be wrapped in double quotes to contain delimiters, newlines, and escaped double
+    /// quotes (`""`). Blank lines are skipped.
+    pub fn parse_csv(text: &str, delimiter: char) -> LuaResult<Vec<Vec<String>>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+                '"' if field.is_empty() => in_quotes = true,
+                c if in_quotes => field.push(c),
+                c if c == delimiter => row.push(std::mem::take(&mut field)),
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' | '\r' => {
+                    row.push(std::mem::take(&mut field));
+                    if !(row.len() == 1 && row[0].is_empty()) {
+                        rows.push(std::mem::take(&mut row));
+                    }
+                    row.clear();
+                }
+                c => field.push(c),
+            }
+        }
+
+        if in_quotes {
+            return Err(LuaError::runtime("Invalid CSV: unterminated quoted field"));
+        }
+
+        // Include the last row if the text does not end with a newline
+        if !field.is_empty() || !row.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    /// Converts CSV `rows` into a Lua list. When `header` is true, the first row names the
+    /// fields and each remaining row becomes a table keyed by those names; otherwise, each row
+    /// becomes a list of fields.
+    pub fn csv_rows_into_lua<'lua>(
+        lua: &'lua Lua,
+        rows: Vec<Vec<String>>,
+        header: bool,
+    ) -> LuaResult<LuaTable<'lua>> {
+        let table = lua.create_table()?;
+        let mut rows = rows.into_iter();
+
+        if header {
+            let names = rows.next().unwrap_or_default();
+            for row in rows {
+                let record = lua.create_table()?;
+                for (name, field) in names.iter().zip(row) {
+                    record.raw_set(name.as_str(), field)?;
+                }
+                table.raw_push(record)?;
+            }
+        } else {
+            for row in rows {
+                table.raw_push(row)?;
+            }
+        }
+
+        Ok(table)
+    }
+
     /// Deep compare values for equality, throwing an error if not matching equality expectation.
     ///
     /// Like [`PdfUtils::try_deep_equal`], but fails instead of returning equality.
@@ -226,6 +428,60 @@ impl<'lua> IntoLua<'lua> for PdfUtils {
             })?,
         )?;
 
+        metatable.raw_set(
+            "read_json",
+            lua.create_function(|lua, path: String| {
+                let text = PdfUtils::read_to_string(lua, &path)?;
+                PdfUtils::parse_json(lua, &text)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "to_json",
+            lua.create_function(|_, (value, opts): (LuaValue, Option<LuaTable>)| {
+                let pretty = opts
+                    .map(|opts| opts.raw_get_ext::<_, Option<bool>>("pretty"))
+                    .transpose()?
+                    .flatten()
+                    .unwrap_or(false);
+                let json = PdfUtils::to_json(value)?;
+                let text = if pretty {
+                    serde_json::to_string_pretty(&json)
+                } else {
+                    serde_json::to_string(&json)
+                };
+                text.map_err(LuaError::external)
+            })?,
+        )?;
+
+        metatable.raw_set(
+            "read_csv",
+            lua.create_function(|lua, (path, opts): (String, Option<LuaTable>)| {
+                let (header, delimiter) = match opts {
+                    Some(opts) => (
+                        opts.raw_get_ext::<_, Option<bool>>("header")?
+                            .unwrap_or(true),
+                        opts.raw_get_ext::<_, Option<String>>("delimiter")?,
+                    ),
+                    None => (true, None),
+                };
+
+                let delimiter = match delimiter.as_deref().map(|x| x.chars().collect::<Vec<_>>()) {
+                    None => ',',
+                    Some(chars) if chars.len() == 1 => chars[0],
+                    Some(_) => {
+                        return Err(LuaError::runtime(
+                            "CSV delimiter must be a single character",
+                        ))
+                    }
+                };
+
+                let text = PdfUtils::read_to_string(lua, &path)?;
+                let rows = PdfUtils::parse_csv(&text, delimiter)?;
+                PdfUtils::csv_rows_into_lua(lua, rows, header)
+            })?,
+        )?;
+
         metatable.raw_set(
             "mm_to_pt",
             lua.create_function(|_, value: LuaValue| PdfUtils::mm_to_pt(value))?,
@@ -236,6 +492,11 @@ impl<'lua> IntoLua<'lua> for PdfUtils {
             lua.create_function(|_, value: LuaValue| PdfUtils::pt_to_mm(value))?,
         )?;
 
+        metatable.raw_set(
+            "px_to_mm",
+            lua.create_function(|lua, value: LuaValue| PdfUtils::px_to_mm(lua, value))?,
+        )?;
+
         Ok(LuaValue::Table(table))
     }
 }
@@ -245,6 +506,137 @@ mod tests {
     use super::*;
     use mlua::chunk;
 
+    /// Creates a Lua runtime with utils available as `u`, allowing files to be read from a new
+    /// temporary directory that is returned.
+    fn new_lua_with_files() -> (Lua, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("makepdf-utils-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let lua = Lua::new();
+        let mut files = RuntimeFiles::new();
+        files.allow(&dir).unwrap();
+        lua.set_app_data(files);
+        lua.globals().raw_set("u", PdfUtils).unwrap();
+
+        (lua, dir)
+    }
+
+    #[test]
+    fn should_support_reading_json_files() {
+        let (lua, dir) = new_lua_with_files();
+        let path = dir.join("data.json");
+        std::fs::write(
+            &path,
+            r#"{"books": [{"title": "Dune", "pages": 412, "rating": 4.5, "read": true}], "next": null}"#,
+        )
+        .unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        lua.load(chunk! {
+            local data = u.read_json($path)
+            u.assert_deep_equal(data, {
+                books = {
+                    { title = "Dune", pages = 412, rating = 4.5, read = true },
+                },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        std::fs::write(dir.join("bad.json"), "{").unwrap();
+        let path = dir.join("bad.json").to_string_lossy().to_string();
+        lua.load(chunk!(u.read_json($path)))
+            .exec()
+            .expect_err("Unexpectedly read invalid json");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn should_support_converting_values_to_json() {
+        let lua = Lua::new();
+        lua.globals().raw_set("u", PdfUtils).unwrap();
+
+        let json: String = lua
+            .load(chunk! {
+                return u.to_json({
+                    title = "Dune",
+                    tags = { "scifi", "classic" },
+                    pages = 412,
+                    rating = 4.5,
+                    empty = {},
+                    skipped = function() end,
+                })
+            })
+            .eval()
+            .unwrap();
+        assert_eq!(
+            json,
+            r#"{"empty":{},"pages":412,"rating":4.5,"tags":["scifi","classic"],"title":"Dune"}"#
+        );
+
+        lua.load(chunk! {
+            local t = {}
+            t.self = t
+            u.to_json(t)
+        })
+        .exec()
+        .expect_err("Unexpectedly converted table containing itself");
+    }
+
+    #[test]
+    fn should_support_reading_csv_files() {
+        let (lua, dir) = new_lua_with_files();
+        let path = dir.join("data.csv");
+        std::fs::write(
+            &path,
+            "meal,notes\r\nPasta,\"with \"\"extra\"\" cheese, please\"\r\n\r\nSoup,\"two\nlines\"",
+        )
+        .unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        lua.load(chunk! {
+            u.assert_deep_equal(u.read_csv($path), {
+                { meal = "Pasta", notes = "with \"extra\" cheese, please" },
+                { meal = "Soup", notes = "two\nlines" },
+            })
+
+            u.assert_deep_equal(u.read_csv($path, { header = false }), {
+                { "meal", "notes" },
+                { "Pasta", "with \"extra\" cheese, please" },
+                { "Soup", "two\nlines" },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        std::fs::write(dir.join("data.tsv"), "a\tb\n1\t2\n").unwrap();
+        let path = dir.join("data.tsv").to_string_lossy().to_string();
+        lua.load(chunk! {
+            u.assert_deep_equal(u.read_csv($path, { delimiter = "\t" }), { { a = "1", b = "2" } })
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn should_fail_to_read_files_outside_of_allowed_directories() {
+        let (lua, dir) = new_lua_with_files();
+        let path = dir.join("..").join("outside.json");
+
+        // Even if the file exists, it should not be readable
+        std::fs::write(&path, "{}").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+        lua.load(chunk!(u.read_json($path_str)))
+            .exec()
+            .expect_err("Unexpectedly read file outside of allowed directory");
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn should_support_deeply_comparing_two_values_for_equality() {
         Lua::new()
@@ -390,6 +782,25 @@ mod tests {
             .expect("Assertion failed");
     }
 
+    #[test]
+    fn should_support_converting_pixels_to_millimeters_using_dpi_of_pages() {
+        let lua = Lua::new();
+        let mut config = crate::pdf::PdfConfig::default();
+        config.page.dpi = 300.0;
+        lua.globals()
+            .raw_set("pdf", crate::pdf::Pdf::new(config))
+            .unwrap();
+
+        lua.load(chunk! {
+            assert(math.abs(pdf.utils.px_to_mm(300) - 25.4) < 0.001, "unexpected mm at 300 dpi")
+            pdf.page.dpi = 150
+            assert(math.abs(pdf.utils.px_to_mm(75) - 12.7) < 0.001, "unexpected mm at 150 dpi")
+            assert(not pcall(pdf.utils.px_to_mm, "75"), "unexpectedly converted string")
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
     #[test]
     fn should_support_converting_value_to_bounds() {
         Lua::new()
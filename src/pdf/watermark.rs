@@ -0,0 +1,123 @@
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{PdfConfigWatermark, PdfLuaExt};
+use crate::runtime::RuntimeFiles;
+use mlua::prelude::*;
+
+/// Collection of functions to stamp text or images onto pages, such as a "DRAFT" marking, which
+/// are stored within `pdf.watermarks` and drawn while building pages.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfWatermark;
+
+impl PdfWatermark {
+    /// Adds `watermark` to `pdf.watermarks`, replacing the existing watermarks if `replace` is
+    /// true.
+    ///
+    /// The image of the watermark, if any, must be within an allowed directory and is replaced
+    /// by its canonical path so that it can be read once the script finishes.
+    pub fn add(lua: &Lua, mut watermark: PdfConfigWatermark, replace: bool) -> LuaResult<()> {
+        if let Some(path) = watermark.image.as_deref() {
+            let resolved = if let Some(files) = lua.app_data_ref::<RuntimeFiles>() {
+                files
+                    .resolve(path)
+                    .map_err(|x| LuaError::runtime(format!("Failed to read '{path}': {x}")))?
+            } else {
+                return Err(LuaError::runtime("Runtime files are missing"));
+            };
+            watermark.image = Some(resolved.to_string_lossy().to_string());
+        }
+
+        let pdf: LuaTable = lua.globals().raw_get(GLOBAL_PDF_VAR_NAME)?;
+        let mut watermarks: Vec<PdfConfigWatermark> = if replace {
+            Vec::new()
+        } else {
+            pdf.raw_get::<_, Option<_>>("watermarks")?
+                .unwrap_or_default()
+        };
+        watermarks.push(watermark);
+        pdf.raw_set("watermarks", watermarks)
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfWatermark {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        // Function to replace all watermarks with a single watermark
+        metatable.raw_set(
+            "set",
+            lua.create_function(|lua, watermark: PdfConfigWatermark| {
+                PdfWatermark::add(lua, watermark, true)
+            })?,
+        )?;
+
+        // Function to add a watermark alongside any others
+        metatable.raw_set(
+            "add",
+            lua.create_function(|lua, watermark: PdfConfigWatermark| {
+                PdfWatermark::add(lua, watermark, false)
+            })?,
+        )?;
+
+        // Function to remove all watermarks
+        metatable.raw_set(
+            "clear",
+            lua.create_function(|lua, ()| {
+                lua.globals()
+                    .raw_get::<_, LuaTable>(GLOBAL_PDF_VAR_NAME)?
+                    .raw_set("watermarks", lua.create_table()?)
+            })?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{Pdf, PdfWatermarkPages};
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_set_add_and_clear_watermarks_in_lua() {
+        let dir = std::env::temp_dir().join(format!("makepdf-watermark-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("logo.png"), b"").unwrap();
+        let path = dir.join("logo.png").to_string_lossy().to_string();
+
+        let lua = Lua::new();
+        let mut files = RuntimeFiles::new();
+        files.allow(&dir).unwrap();
+        lua.set_app_data(files);
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            pdf.watermark.set({ text = "DRAFT", angle = 45, opacity = 0.15, pages = "all" })
+            pdf.watermark.set({ text = "COPY" })
+            pdf.watermark.add({ image = $path, pages = { 1 }, above = true })
+        })
+        .exec()
+        .expect("Failed to set watermarks");
+
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        let watermarks = pdf.config.watermarks;
+        assert_eq!(watermarks.len(), 2);
+        assert_eq!(watermarks[0].text.as_deref(), Some("COPY"));
+        assert_eq!(watermarks[1].pages, PdfWatermarkPages::Numbers(vec![1]));
+        assert!(watermarks[1].above);
+
+        lua.load(chunk!(pdf.watermark.clear()))
+            .exec()
+            .expect("Failed to clear watermarks");
+        let pdf: Pdf = lua.globals().raw_get("pdf").unwrap();
+        assert!(pdf.config.watermarks.is_empty());
+
+        // Images must be within an allowed directory
+        lua.load(chunk!(pdf.watermark.set({ image = "/missing/logo.png" })))
+            .exec()
+            .expect_err("Unexpectedly set watermark with missing image");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
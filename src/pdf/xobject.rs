@@ -0,0 +1,78 @@
+use crate::pdf::{PdfLuaExt, PdfObjectGroup};
+use crate::runtime::RuntimeXObjects;
+use mlua::prelude::*;
+
+/// Collection of functions to create form XObjects, which are stored once within the PDF and
+/// stamped onto pages.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PdfXObject;
+
+impl PdfXObject {
+    /// Registers `group` as a form XObject, returning a copy of the group that is drawn as the
+    /// form, which can be pushed onto any number of pages (and moved) cheaply.
+    ///
+    /// Changes to the objects within the returned group, other than moving them, are not drawn.
+    pub fn from_group(lua: &Lua, mut group: PdfObjectGroup) -> LuaResult<PdfObjectGroup> {
+        if let Some(mut xobjects) = lua.app_data_mut::<RuntimeXObjects>() {
+            group.xobject = Some(xobjects.register(group.clone()));
+            Ok(group)
+        } else {
+            Err(LuaError::runtime("Runtime xobjects are missing"))
+        }
+    }
+}
+
+impl<'lua> IntoLua<'lua> for PdfXObject {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let (table, metatable) = lua.create_table_ext()?;
+
+        metatable.raw_set(
+            "from_group",
+            lua.create_function(|lua, group: PdfObjectGroup| PdfXObject::from_group(lua, group))?,
+        )?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+    use mlua::chunk;
+
+    #[test]
+    fn should_be_able_to_register_groups_as_xobjects_in_lua() {
+        // Stand up Lua runtime with everything configured properly for tests
+        let lua = Lua::new();
+        lua.set_app_data(RuntimeXObjects::new());
+        lua.globals().raw_set("pdf", Pdf::default()).unwrap();
+
+        lua.load(chunk! {
+            local group = pdf.object.group({
+                pdf.object.rect({ ll = { 0, 0 }, ur = { 10, 10 } }),
+            })
+
+            local header = pdf.xobject.from_group(group)
+            assert(header.xobject == 0, "unexpected xobject " .. tostring(header.xobject))
+            assert(#header == 1, "objects of group missing")
+
+            // Moving the form keeps it as a form
+            local moved = header:align_to({ 20, 20, 40, 40 }, { v = "middle", h = "middle" })
+            assert(moved.xobject == 0, "moved group lost xobject")
+            pdf.utils.assert_deep_equal(moved:bounds(), {
+                ll = { x = 25, y = 25 },
+                ur = { x = 35, y = 35 },
+            })
+
+            assert(pdf.xobject.from_group(group).xobject == 1, "xobject not registered")
+        })
+        .exec()
+        .expect("Assertion failed");
+
+        let xobjects = lua.app_data_ref::<RuntimeXObjects>().unwrap();
+        assert_eq!(xobjects.get(0).map(|x| x.objects.len()), Some(1));
+        assert_eq!(xobjects.get(0).and_then(|x| x.xobject), None);
+    }
+}
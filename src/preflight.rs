@@ -0,0 +1,378 @@
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use printpdf::lopdf::{Document, Object, ObjectId};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Capabilities of a device that a PDF is validated against prior to loading it on the device.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfPreflightProfile {
+    /// Name of the profile, e.g. `supernote`.
+    pub name: &'static str,
+
+    /// Width of the device's screen in pixels.
+    pub width: u32,
+
+    /// Height of the device's screen in pixels.
+    pub height: u32,
+
+    /// DPI of the device's screen.
+    pub dpi: f32,
+
+    /// Smallest width or height (in millimeters) of a link that can be reliably tapped.
+    pub min_link_size: f32,
+
+    /// Most content stream operators on a single page before the device renders it too slowly.
+    pub max_operators: usize,
+
+    /// If true, the device only displays shades of gray.
+    pub grayscale: bool,
+}
+
+impl PdfPreflightProfile {
+    /// Profiles of supported devices.
+    pub const BUILTIN: &'static [Self] = &[
+        Self {
+            name: "supernote",
+            width: 1404,
+            height: 1872,
+            dpi: 300.0,
+            min_link_size: 4.0,
+            max_operators: 50_000,
+            grayscale: true,
+        },
+        Self {
+            name: "supernote-a5x",
+            width: 1404,
+            height: 1872,
+            dpi: 226.0,
+            min_link_size: 4.0,
+            max_operators: 50_000,
+            grayscale: true,
+        },
+        Self {
+            name: "remarkable2",
+            width: 1404,
+            height: 1872,
+            dpi: 226.0,
+            min_link_size: 5.0,
+            max_operators: 30_000,
+            grayscale: true,
+        },
+    ];
+
+    /// Returns the width and height of the device's screen in points.
+    pub fn size_in_points(&self) -> (f32, f32) {
+        (
+            self.width as f32 * 72.0 / self.dpi,
+            self.height as f32 * 72.0 / self.dpi,
+        )
+    }
+
+    /// Returns the thinnest stroke (in points) that the device can display, which is one pixel.
+    pub fn min_stroke_width(&self) -> f32 {
+        72.0 / self.dpi
+    }
+}
+
+impl FromStr for PdfPreflightProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::BUILTIN
+            .iter()
+            .find(|x| x.name.eq_ignore_ascii_case(s))
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::BUILTIN.iter().map(|x| x.name).collect();
+                format!("Unknown profile {s}, expected one of {}", names.join(", "))
+            })
+    }
+}
+
+/// Result of validating a PDF against a device profile.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfPreflight {
+    /// Name of the profile used to validate the PDF.
+    pub profile: &'static str,
+
+    /// Checks performed, in order.
+    pub checks: Vec<PdfPreflightCheck>,
+}
+
+/// Single check performed as part of a preflight, alongside any issues found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfPreflightCheck {
+    /// Name of the check, e.g. `page size`.
+    pub name: &'static str,
+
+    /// Descriptions of issues found, where no issues means the check passed.
+    pub issues: Vec<String>,
+}
+
+impl PdfPreflightCheck {
+    /// Returns true if the check found no issues.
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for PdfPreflightCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed() {
+            write!(f, "[PASS] {}", self.name)
+        } else {
+            write!(f, "[FAIL] {}: {}", self.name, self.issues.join("; "))
+        }
+    }
+}
+
+impl PdfPreflight {
+    /// Returns true if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(PdfPreflightCheck::passed)
+    }
+
+    /// Validates the PDF at `path` against `profile`.
+    pub fn check(path: impl AsRef<Path>, profile: &PdfPreflightProfile) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let doc =
+            Document::load(path).with_context(|| format!("Failed to load {}", path.display()))?;
+        Ok(Self::check_doc(&doc, profile))
+    }
+
+    /// Validates the pages of `doc` against `profile`.
+    ///
+    /// Stroke widths and link sizes are measured as written, without factoring in any
+    /// transformations applied to the page.
+    fn check_doc(doc: &Document, profile: &PdfPreflightProfile) -> Self {
+        let mut size = Vec::new();
+        let mut links = Vec::new();
+        let mut operators = Vec::new();
+        let mut strokes = Vec::new();
+        let mut colors = Vec::new();
+
+        let (width, height) = profile.size_in_points();
+        let min_stroke_width = profile.min_stroke_width();
+
+        for (page, id) in doc.get_pages() {
+            if let Some((w, h)) = page_size(doc, id) {
+                if (w - width).abs() > 1.0 || (h - height).abs() > 1.0 {
+                    size.push(format!(
+                        "page {page} is {w:.1}x{h:.1}pt, expected {width:.1}x{height:.1}pt"
+                    ));
+                }
+            }
+
+            for annotation in doc.get_page_annotations(id) {
+                let is_link = matches!(
+                    annotation.get(b"Subtype").and_then(Object::as_name),
+                    Ok(b"Link")
+                );
+                if let (true, Some((w, h))) = (is_link, annotation_size(annotation)) {
+                    let (w, h) = (w * 25.4 / 72.0, h * 25.4 / 72.0);
+                    if w < profile.min_link_size || h < profile.min_link_size {
+                        links.push(format!(
+                            "page {page} has a {w:.1}x{h:.1}mm link, smaller than {:.1}mm",
+                            profile.min_link_size
+                        ));
+                    }
+                }
+            }
+
+            let content = match doc.get_and_decode_page_content(id) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+
+            if content.operations.len() > profile.max_operators {
+                operators.push(format!(
+                    "page {page} has {} operators, more than {}",
+                    content.operations.len(),
+                    profile.max_operators
+                ));
+            }
+
+            let mut thinnest: Option<f32> = None;
+            let mut has_color = false;
+            for op in content.operations.iter() {
+                let operands: Vec<f32> = op
+                    .operands
+                    .iter()
+                    .filter_map(|x| x.as_float().ok())
+                    .collect();
+
+                match op.operator.as_str() {
+                    // Zero width is a hairline, which is always the thinnest the device can show
+                    "w" => {
+                        if let Some(w) = operands.first().copied().filter(|w| *w > 0.0) {
+                            if w < min_stroke_width {
+                                thinnest = Some(thinnest.map_or(w, |x| x.min(w)));
+                            }
+                        }
+                    }
+                    "rg" | "RG" if operands.len() == 3 => {
+                        let is_gray = (operands[0] - operands[1]).abs() < 0.01
+                            && (operands[1] - operands[2]).abs() < 0.01;
+                        has_color |= !is_gray;
+                    }
+                    "k" | "K" if operands.len() == 4 => {
+                        has_color |= operands[..3].iter().any(|x| *x > 0.01);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(w) = thinnest {
+                strokes.push(format!(
+                    "page {page} has a {w:.2}pt stroke, thinner than one pixel ({min_stroke_width:.2}pt)"
+                ));
+            }
+
+            if has_color && profile.grayscale {
+                colors.push(format!("page {page} uses color on a grayscale device"));
+            }
+        }
+
+        Self {
+            profile: profile.name,
+            checks: vec![
+                PdfPreflightCheck {
+                    name: "page size",
+                    issues: size,
+                },
+                PdfPreflightCheck {
+                    name: "link size",
+                    issues: links,
+                },
+                PdfPreflightCheck {
+                    name: "operator count",
+                    issues: operators,
+                },
+                PdfPreflightCheck {
+                    name: "stroke width",
+                    issues: strokes,
+                },
+                PdfPreflightCheck {
+                    name: "color",
+                    issues: colors,
+                },
+            ],
+        }
+    }
+}
+
+/// Returns the width and height (in points) of the page with `id`.
+fn page_size(doc: &Document, id: ObjectId) -> Option<(f32, f32)> {
+    let media_box = doc
+        .get_dictionary(id)
+        .and_then(|x| x.get(b"MediaBox"))
+        .and_then(Object::as_array)
+        .ok()?;
+    rect_size(media_box)
+}
+
+/// Returns the width and height (in points) of an annotation's rect.
+fn annotation_size(annotation: &printpdf::lopdf::Dictionary) -> Option<(f32, f32)> {
+    let rect = annotation.get(b"Rect").and_then(Object::as_array).ok()?;
+    rect_size(rect)
+}
+
+/// Returns the width and height of a rect in the form `[llx lly urx ury]`.
+fn rect_size(rect: &[Object]) -> Option<(f32, f32)> {
+    let coords: Vec<f32> = rect.iter().filter_map(|x| x.as_float().ok()).collect();
+    match coords.as_slice() {
+        [llx, lly, urx, ury] => Some(((urx - llx).abs(), (ury - lly).abs())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::{Actions, Color, Greyscale, LinkAnnotation, Mm, PdfDocument, Pt, Rect, Rgb};
+
+    /// Profile matching a 100x100pt page at 72 DPI.
+    fn profile() -> PdfPreflightProfile {
+        PdfPreflightProfile {
+            name: "test",
+            width: 100,
+            height: 100,
+            dpi: 72.0,
+            min_link_size: 5.0,
+            max_operators: 100,
+            grayscale: true,
+        }
+    }
+
+    /// Creates a document with a single page of `size` points, whose layer is populated by `f`.
+    fn make_doc(size: f32, f: impl FnOnce(&printpdf::PdfLayerReference)) -> Document {
+        let doc = PdfDocument::empty("test");
+        let (page, layer) = doc.add_page(Mm::from(Pt(size)), Mm::from(Pt(size)), "page");
+        f(&doc.get_page(page).get_layer(layer));
+        Document::load_mem(&doc.save_to_bytes().unwrap()).unwrap()
+    }
+
+    /// Returns the names of the checks that failed.
+    fn failed(preflight: &PdfPreflight) -> Vec<&'static str> {
+        preflight
+            .checks
+            .iter()
+            .filter(|x| !x.passed())
+            .map(|x| x.name)
+            .collect()
+    }
+
+    #[test]
+    fn check_doc_should_pass_pdf_matching_profile() {
+        let doc = make_doc(100.0, |layer| {
+            layer.set_outline_thickness(1.0);
+            layer.set_outline_color(Color::Greyscale(Greyscale::new(0.5, None)));
+            layer.add_rect(Rect::new(Mm(1.0), Mm(1.0), Mm(10.0), Mm(10.0)));
+        });
+
+        let preflight = PdfPreflight::check_doc(&doc, &profile());
+        assert!(preflight.passed(), "{:?}", preflight);
+    }
+
+    #[test]
+    fn check_doc_should_fail_pdf_exceeding_profile() {
+        let doc = make_doc(200.0, |layer| {
+            layer.set_outline_thickness(0.5);
+            layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 0.0, 0.0, None)));
+            for _ in 0..50 {
+                layer.add_rect(Rect::new(Mm(1.0), Mm(1.0), Mm(10.0), Mm(10.0)));
+            }
+            layer.add_link_annotation(LinkAnnotation::new(
+                printpdf::Rect::new(Mm(0.0), Mm(0.0), Mm(2.0), Mm(2.0)),
+                None,
+                None,
+                Actions::uri(String::from("https://example.com")),
+                None,
+            ));
+        });
+
+        let preflight = PdfPreflight::check_doc(&doc, &profile());
+        assert_eq!(
+            failed(&preflight),
+            vec![
+                "page size",
+                "link size",
+                "operator count",
+                "stroke width",
+                "color"
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_should_be_parsed_by_name() {
+        assert_eq!(
+            "Supernote".parse::<PdfPreflightProfile>().unwrap().name,
+            "supernote"
+        );
+        assert!("unknown".parse::<PdfPreflightProfile>().is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use log::*;
+use printpdf::lopdf::Document;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+/// Format of the images exported as previews of pages.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PdfPreviewFormat {
+    /// Rasterized image, rendered using `pdftoppm`.
+    #[default]
+    Png,
+    /// Vector image, exported using `pdftocairo`.
+    Svg,
+}
+
+impl PdfPreviewFormat {
+    /// Returns the file extension of images in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for PdfPreviewFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("Unknown format {s}, expected one of png, svg")),
+        }
+    }
+}
+
+/// Configuration for exporting pages of a PDF as images, such as to view the results of a script
+/// without a PDF reader or to embed them in documentation.
+///
+/// Exporting requires `pdftoppm` (for PNG) or `pdftocairo` (for SVG) from poppler to be available
+/// on the path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfPreview {
+    /// Format of the exported images.
+    pub format: PdfPreviewFormat,
+
+    /// DPI used to render pages as PNG.
+    pub dpi: f32,
+
+    /// Pages (starting at 1) to export, or every page if empty.
+    pub pages: Vec<u32>,
+
+    /// Directory where an image named `page-{N}.{ext}` is written for each page.
+    pub output: PathBuf,
+}
+
+impl PdfPreview {
+    /// Parses a comma-separated list of pages and ranges of pages, e.g. `1,3-5`.
+    pub fn parse_pages(s: &str) -> anyhow::Result<Vec<u32>> {
+        let mut pages = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let parse = |x: &str| {
+                x.trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|x| *x > 0)
+                    .with_context(|| format!("Invalid page {x}, expected a number starting at 1"))
+            };
+
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse(start)?, parse(end)?);
+                    if start > end {
+                        anyhow::bail!("Invalid range of pages {part}");
+                    }
+                    pages.extend(start..=end);
+                }
+                None => pages.push(parse(part)?),
+            }
+        }
+
+        Ok(pages)
+    }
+
+    /// Exports the selected pages of the PDF at `path`, returning the paths of the images that
+    /// were written.
+    pub fn export(&self, path: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let doc =
+            Document::load(path).with_context(|| format!("Failed to load {}", path.display()))?;
+        let cnt = doc.get_pages().len() as u32;
+
+        let pages = if self.pages.is_empty() {
+            (1..=cnt).collect()
+        } else {
+            self.pages.clone()
+        };
+
+        if let Some(page) = pages.iter().find(|x| **x > cnt) {
+            anyhow::bail!("Page {page} does not exist in {}", path.display());
+        }
+
+        fs::create_dir_all(&self.output)
+            .with_context(|| format!("Failed to create directory {}", self.output.display()))?;
+
+        let mut images = Vec::new();
+        for page in pages {
+            debug!("Exporting page {page} / {cnt}");
+            let image = self
+                .output
+                .join(format!("page-{page}.{}", self.format.extension()));
+            self.export_page(path, page, &image)?;
+            images.push(image);
+        }
+
+        Ok(images)
+    }
+
+    /// Exports `page` (starting at 1) of the PDF at `path` as the image at `image`.
+    fn export_page(&self, path: &Path, page: u32, image: &Path) -> anyhow::Result<()> {
+        let mut cmd = match self.format {
+            PdfPreviewFormat::Png => {
+                let mut cmd = Command::new("pdftoppm");
+                cmd.arg("-png")
+                    .arg("-r")
+                    .arg(self.dpi.to_string())
+                    .arg("-singlefile");
+                cmd
+            }
+            PdfPreviewFormat::Svg => {
+                let mut cmd = Command::new("pdftocairo");
+                cmd.arg("-svg");
+                cmd
+            }
+        };
+
+        let program = cmd.get_program().to_string_lossy().to_string();
+        let output = cmd
+            .arg("-f")
+            .arg(page.to_string())
+            .arg("-l")
+            .arg(page.to_string())
+            .arg(path)
+            // pdftoppm appends the extension to the output, so we strip it from the image
+            .arg(match self.format {
+                PdfPreviewFormat::Png => image.with_extension(""),
+                PdfPreviewFormat::Svg => image.to_path_buf(),
+            })
+            .output()
+            .with_context(|| format!("Failed to run {program}, which is required for previews"))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to export page {page} of {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pages_should_support_pages_and_ranges() {
+        assert_eq!(
+            PdfPreview::parse_pages("1, 3-5,8").unwrap(),
+            vec![1, 3, 4, 5, 8]
+        );
+        assert_eq!(PdfPreview::parse_pages("").unwrap(), Vec::<u32>::new());
+
+        PdfPreview::parse_pages("0").expect_err("Unexpectedly parsed page 0");
+        PdfPreview::parse_pages("5-3").expect_err("Unexpectedly parsed backwards range");
+        PdfPreview::parse_pages("a").expect_err("Unexpectedly parsed non-number");
+    }
+
+    #[test]
+    fn format_should_be_parsed_ignoring_case() {
+        assert_eq!("PNG".parse(), Ok(PdfPreviewFormat::Png));
+        assert_eq!("svg".parse(), Ok(PdfPreviewFormat::Svg));
+        "jpg"
+            .parse::<PdfPreviewFormat>()
+            .expect_err("Unexpectedly parsed unknown format");
+    }
+}
@@ -0,0 +1,252 @@
+use crate::manifest::PdfManifestEntry;
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Project-level defaults for making PDFs, loaded from a `makepdf.toml` next to the script so
+/// that the same options do not need to be passed on every build.
+///
+/// ```toml
+/// dimensions = "1404x1872px"
+/// dpi = 226
+/// font = "fonts/Inter.ttf"
+/// title = "My Planner"
+/// output_dir = "out"
+///
+/// [vars]
+/// year = "2025"
+///
+/// [fonts]
+/// heading = "fonts/Lora.ttf"
+/// ```
+///
+/// Options provided on the commandline take precedence over those of the project.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PdfProject {
+    /// Dimensions (WIDTHxHEIGHT) of PDFs, e.g. `210x297mm`.
+    pub dimensions: Option<String>,
+
+    /// DPI of PDFs.
+    pub dpi: Option<f32>,
+
+    /// Path to custom font to use in place of the default font.
+    pub font: Option<String>,
+
+    /// Title of PDF documents.
+    pub title: Option<String>,
+
+    /// Directory where PDFs are created when their output is relative or not provided.
+    pub output_dir: Option<String>,
+
+    /// Arguments passed to scripts as `pdf.args`, where numbers and booleans are converted into
+    /// strings.
+    #[serde(deserialize_with = "deserialize_vars")]
+    pub vars: BTreeMap<String, String>,
+
+    /// Paths of fonts registered before scripts run, by name.
+    pub fonts: BTreeMap<String, String>,
+}
+
+impl PdfProject {
+    /// Name of the file containing the project-level defaults.
+    pub const FILE_NAME: &'static str = "makepdf.toml";
+
+    /// Finds the project file for `script`, looking within the directory containing the script
+    /// followed by the current working directory.
+    pub fn find(script: impl AsRef<Path>) -> Option<PathBuf> {
+        let dir = script
+            .as_ref()
+            .parent()
+            .filter(|x| !x.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+
+        [dir.join(Self::FILE_NAME), PathBuf::from(Self::FILE_NAME)]
+            .into_iter()
+            .find(|x| x.is_file())
+    }
+
+    /// Loads the project from the file at `path`.
+    ///
+    /// Relative paths of fonts and the output directory are resolved relative to the directory
+    /// containing the file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        RuntimeFiles::check_fs(path)?;
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to load project '{}'", path.display()))?;
+
+        let mut project = Self::from_toml(&text)
+            .with_context(|| format!("Failed to parse project '{}'", path.display()))?;
+
+        if let Some(dir) = path.parent().filter(|x| !x.as_os_str().is_empty()) {
+            let resolve = |x: &mut Option<String>| {
+                if let Some(x) = x.as_mut().filter(|x| Path::new(x.as_str()).is_relative()) {
+                    *x = dir.join(x.as_str()).to_string_lossy().to_string();
+                }
+            };
+
+            resolve(&mut project.font);
+            resolve(&mut project.output_dir);
+            for path in project.fonts.values_mut() {
+                if Path::new(path.as_str()).is_relative() {
+                    *path = dir.join(path.as_str()).to_string_lossy().to_string();
+                }
+            }
+        }
+
+        Ok(project)
+    }
+
+    /// Parses the project from TOML `text`.
+    pub fn from_toml(text: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Converts the project into a manifest entry, used as the defaults of PDFs to make.
+    pub fn to_entry(&self) -> PdfManifestEntry {
+        PdfManifestEntry {
+            dimensions: self.dimensions.clone(),
+            dpi: self.dpi,
+            font: self.font.clone(),
+            title: self.title.clone(),
+            output_dir: self.output_dir.clone(),
+            args: self.vars.clone(),
+            fonts: self.fonts.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Deserializes the values of `[vars]` as strings, accepting numbers and booleans alongside
+/// strings so that `year = 2025` works like `year = "2025"`.
+fn deserialize_vars<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::<String, toml::Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| match value {
+            toml::Value::String(x) => Ok((key, x)),
+            toml::Value::Integer(x) => Ok((key, x.to_string())),
+            toml::Value::Float(x) => Ok((key, x.to_string())),
+            toml::Value::Boolean(x) => Ok((key, x.to_string())),
+            x => Err(serde::de::Error::custom(format!(
+                "expected var {key} to be a string, number, or boolean, found {}",
+                x.type_str()
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_toml_should_parse_defaults_and_vars() {
+        let project = PdfProject::from_toml(
+            r#"
+            # Defaults for the planner
+            dimensions = "210x297mm"
+            dpi = 300 # print quality
+            title = 'My "Planner"'
+            output_dir = "out"
+
+            [vars]
+            year = 2025
+            theme = "dark # not a comment"
+
+            [fonts]
+            heading = "/fonts/Lora.ttf"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            project,
+            PdfProject {
+                dimensions: Some(String::from("210x297mm")),
+                dpi: Some(300.0),
+                title: Some(String::from("My \"Planner\"")),
+                output_dir: Some(String::from("out")),
+                vars: [
+                    (String::from("year"), String::from("2025")),
+                    (String::from("theme"), String::from("dark # not a comment")),
+                ]
+                .into_iter()
+                .collect(),
+                fonts: [(String::from("heading"), String::from("/fonts/Lora.ttf"))]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn from_toml_should_support_any_toml_syntax() {
+        let project = PdfProject::from_toml(
+            r#"
+            title = "Say \"hi\" # not a comment"
+            output_dir = """
+out"""
+            vars.theme = "dark"
+            fonts = { heading = "/fonts/Lora.ttf", body = '/fonts/Inter.ttf' }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            project,
+            PdfProject {
+                title: Some(String::from("Say \"hi\" # not a comment")),
+                output_dir: Some(String::from("out")),
+                vars: [(String::from("theme"), String::from("dark"))]
+                    .into_iter()
+                    .collect(),
+                fonts: [
+                    (String::from("heading"), String::from("/fonts/Lora.ttf")),
+                    (String::from("body"), String::from("/fonts/Inter.ttf")),
+                ]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn from_toml_should_fail_if_invalid() {
+        assert!(PdfProject::from_toml("dpi = \"high\"").is_err());
+        assert!(PdfProject::from_toml("unknown = 1").is_err());
+        assert!(PdfProject::from_toml("[page]").is_err());
+        assert!(PdfProject::from_toml("title = \"unclosed").is_err());
+        assert!(PdfProject::from_toml("title").is_err());
+        assert!(PdfProject::from_toml("[fonts]\nheading = 1").is_err());
+        assert!(PdfProject::from_toml("[vars]\nyears = [2024, 2025]").is_err());
+    }
+
+    #[test]
+    fn load_should_resolve_paths_relative_to_project() {
+        let dir = std::env::temp_dir().join(format!("makepdf-project-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(PdfProject::FILE_NAME),
+            "font = \"fonts/Inter.ttf\"\noutput_dir = \"/abs/out\"",
+        )
+        .unwrap();
+
+        let path = PdfProject::find(dir.join("planner.lua")).unwrap();
+        let project = PdfProject::load(path).unwrap();
+        assert_eq!(
+            project.font.as_deref(),
+            Some(dir.join("fonts/Inter.ttf").to_string_lossy().as_ref())
+        );
+        assert_eq!(project.output_dir.as_deref(), Some("/abs/out"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
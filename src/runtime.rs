@@ -1,18 +1,69 @@
+mod alpha;
+mod attachments;
+mod cache;
 mod doc;
+mod files;
 mod fonts;
+mod imports;
+mod imposition;
+mod labels;
+mod layers;
+mod links;
+mod optimize;
 mod pages;
+mod pdfa;
 mod script;
+mod stats;
+mod suite;
+mod toc;
+mod watermarks;
+mod xobjects;
 
+pub use alpha::RuntimeAlpha;
+pub use attachments::{RuntimeAttachment, RuntimeAttachments};
+pub use cache::RuntimeCache;
 pub use doc::RuntimeDoc;
+pub use files::RuntimeFiles;
 pub use fonts::{RuntimeFontId, RuntimeFonts};
-pub(crate) use pages::*;
+pub use imports::{RuntimeImportId, RuntimeImports};
+pub use imposition::RuntimeImposition;
+pub use labels::RuntimeLabels;
+pub use layers::RuntimeLayers;
+pub use links::RuntimeLinks;
+pub use optimize::RuntimeOptimize;
+pub use pages::*;
+pub use pdfa::RuntimePdfA;
 use script::RuntimeScript;
+pub use stats::{RuntimePageStats, RuntimeStats};
+pub use suite::{RuntimeTestReport, RuntimeTestResult, RuntimeTestSuite};
+pub use toc::{RuntimeToc, RuntimeTocEntry};
+pub use watermarks::RuntimeWatermarks;
+pub use xobjects::{RuntimeXObjectId, RuntimeXObjects};
 
 use crate::constants::GLOBAL_PDF_VAR_NAME;
-use crate::pdf::{Pdf, PdfConfig, PdfContext, PdfLink};
+use crate::pdf::{Pdf, PdfBounds, PdfConfig, PdfContext, PdfLink, PdfPages, PdfStyle, PdfUtils};
 use anyhow::Context;
 use log::*;
+use mlua::prelude::*;
 use std::collections::HashMap;
+use std::io::Write;
+use web_time::Instant;
+
+/// State of a runtime after running its script, containing everything needed to build the PDF.
+type RuntimeSetup = (
+    PdfConfig,
+    RuntimePages,
+    RuntimeFonts,
+    RuntimeAttachments,
+    RuntimeXObjects,
+    RuntimeImports,
+    RuntimeToc,
+);
+
+/// Callback provided from Rust, invoked with the Lua runtime once the script finishes, that can
+/// create pages and objects like the script itself, e.g. through the `pdf` global or by inserting
+/// pages into [`RuntimePages`] retrieved with [`Lua::app_data_mut`].
+pub type RuntimeCallback = Box<dyn FnOnce(&Lua) -> LuaResult<()>>;
 
 /// PDF generation runtime, using `T` as a state machine to progress through a series of steps
 /// towards generating and saving a PDF.
@@ -23,29 +74,158 @@ impl Runtime<()> {
     pub fn new(config: PdfConfig) -> Runtime<PdfConfig> {
         Runtime(config)
     }
+
+    /// Checks that `qpdf`, which [`save_linearized`](Runtime::save_linearized) requires, is
+    /// available on the path, so a missing `qpdf` is reported before anything is built.
+    pub fn check_linearize() -> anyhow::Result<()> {
+        RuntimeDoc::check_linearize()
+    }
 }
 
 impl Runtime<PdfConfig> {
     /// Runs the configured Lua script to setup the final configuration and register hooks to
     /// process pages of the PDF among other things.
-    pub fn setup(self) -> anyhow::Result<Runtime<(PdfConfig, RuntimePages, RuntimeFonts)>> {
+    pub fn setup(self) -> anyhow::Result<Runtime<RuntimeSetup>> {
+        self.setup_with_cache(&RuntimeCache::new())
+    }
+
+    /// Like [`Runtime::setup`], but reuses the fonts and compiled stdlib kept within `cache` by
+    /// earlier builds, such as the other builds of a batch, adding to the cache for later builds.
+    pub fn setup_with_cache(self, cache: &RuntimeCache) -> anyhow::Result<Runtime<RuntimeSetup>> {
+        self.setup_with_tests(false, cache)
+            .map(|(runtime, _)| runtime)
+    }
+
+    /// Like [`Runtime::setup`], but also runs the tests registered by the script through
+    /// `pdf.test.it` once the script finishes, returning the results of the tests.
+    pub fn test(self) -> anyhow::Result<(Runtime<RuntimeSetup>, RuntimeTestReport)> {
+        self.setup_with_tests(true, &RuntimeCache::new())
+    }
+
+    /// Compiles the configured Lua script into Luau bytecode, which can be run in place of the
+    /// script when [`PdfConfig::allow_bytecode`] is true to skip parsing and compiling it, such as
+    /// for very large generated scripts. Optimizations are applied by [`PdfConfig::opt_level`].
+    pub fn compile(&self) -> anyhow::Result<Vec<u8>> {
+        info!("Compiling {}", self.0.script);
+        let mut script =
+            RuntimeScript::load_from_script(&self.0.script).context("Failed to load script")?;
+        script.set_opt_level(self.0.opt_level);
+        script.compile()
+    }
+
+    /// Sets up the PDF from Rust without running any Lua script, invoking `f` to create pages and
+    /// push objects onto them, which are drawn like those of a script:
+    ///
+    /// ```
+    /// use makepdf::{PdfConfig, PdfObjectText, Runtime};
+    ///
+    /// let runtime = Runtime::new(PdfConfig::default()).with_pages(|doc| {
+    ///     doc.page("2025-01-01").push(PdfObjectText {
+    ///         text: String::from("Wednesday"),
+    ///         ..Default::default()
+    ///     });
+    /// });
+    ///
+    /// let bytes = runtime.build().unwrap().to_bytes().unwrap();
+    /// assert!(bytes.starts_with(b"%PDF"));
+    /// ```
+    pub fn with_pages(self, f: impl FnOnce(&mut RuntimePages)) -> Runtime<RuntimeSetup> {
+        let mut pages = RuntimePages::new();
+        f(&mut pages);
+
+        Runtime((
+            self.0,
+            pages,
+            RuntimeFonts::new(),
+            RuntimeAttachments::new(),
+            RuntimeXObjects::new(),
+            RuntimeImports::new(),
+            RuntimeToc::new(),
+        ))
+    }
+
+    /// Like [`Runtime::setup`], but runs `code` named `name` in place of the configured script,
+    /// only allowing it to read files within `files`, and invokes each of `callbacks` with the Lua
+    /// runtime once the code finishes so they can create pages and objects alongside the code.
+    pub(crate) fn setup_from_code(
+        self,
+        name: &str,
+        code: Vec<u8>,
+        files: RuntimeFiles,
+        callbacks: Vec<RuntimeCallback>,
+        cache: &RuntimeCache,
+    ) -> anyhow::Result<Runtime<RuntimeSetup>> {
+        let mut script = RuntimeScript::load_from_bytes(code).context("Failed to load script")?;
+        script.set_name(name);
+        script.set_allow_bytecode(self.0.allow_bytecode);
+        script.set_opt_level(self.0.opt_level);
+        script.set_native(self.0.native);
+        self.setup_script(script, files, callbacks, false, cache)
+            .map(|(runtime, _)| runtime)
+    }
+
+    /// Runs the configured Lua script, running its tests afterwards when `run_tests` is true.
+    fn setup_with_tests(
+        self,
+        run_tests: bool,
+        cache: &RuntimeCache,
+    ) -> anyhow::Result<(Runtime<RuntimeSetup>, RuntimeTestReport)> {
+        info!("Loading {}", self.0.script);
+        let mut script =
+            RuntimeScript::load_from_script(&self.0.script).context("Failed to load script")?;
+        script.set_allow_bytecode(self.0.allow_bytecode);
+        script.set_opt_level(self.0.opt_level);
+        script.set_native(self.0.native);
+        let files = RuntimeFiles::for_script(&self.0.script)
+            .context("Failed to determine files available to script")?;
+        self.setup_script(script, files, Vec::new(), run_tests, cache)
+    }
+
+    /// Runs `script`, allowing it to read files within `files`, followed by `callbacks`, running
+    /// the tests registered by either afterwards when `run_tests` is true. Fonts and the stdlib
+    /// are taken from `cache` when they are kept there.
+    fn setup_script(
+        self,
+        mut script: RuntimeScript,
+        files: RuntimeFiles,
+        callbacks: Vec<RuntimeCallback>,
+        run_tests: bool,
+        cache: &RuntimeCache,
+    ) -> anyhow::Result<(Runtime<RuntimeSetup>, RuntimeTestReport)> {
         let config = self.0;
+        script.set_cache(cache.clone());
 
-        // Initialize a script and relevant application data
+        // Initialize relevant application data for the script
         //
         // 1. Fonts need to be configured as available before running our script as the script can
         //    access and load new fonts into the system
         // 2. Pages need to be configured as available before running our script as the script can
         //    access and add new pages into the system
-        info!("Loading {}", config.script);
-        let mut script =
-            RuntimeScript::load_from_script(&config.script).context("Failed to load script")?;
+        // 3. Files need to be configured as available before running our script as the script can
+        //    read data files from the directories it is allowed to access
+        // 4. Attachments need to be configured as available before running our script as the
+        //    script can add files to embed within the PDF
+        // 5. XObjects need to be configured as available before running our script as the script
+        //    can register groups as forms to stamp onto pages
+        // 6. Imports need to be configured as available before running our script as the script
+        //    can import pages of existing PDFs to draw as backgrounds
+        // 7. Table of contents needs to be configured as available before running our script as
+        //    the script can add entries to it
+        // 8. Test suite needs to be configured as available before running our script as the
+        //    script can register tests
         script.set_app_data(RuntimePages::new());
+        script.set_app_data(files);
+        script.set_app_data(RuntimeAttachments::new());
+        script.set_app_data(RuntimeXObjects::new());
+        script.set_app_data(RuntimeImports::new());
+        script.set_app_data(RuntimeToc::new());
+        script.set_app_data(RuntimeTestSuite::new());
 
         // Initialize our fonts with the pre-configured font used as the fallback for now
         info!("Initializing fonts");
         script.set_app_data({
             let mut fonts = RuntimeFonts::new();
+            fonts.set_cache(cache.clone());
 
             // At the beginning, load the configured font as the fallback PRIOR to running our
             // scripts, knowing that this may change when we are done running scripts and we
@@ -62,6 +242,14 @@ impl Runtime<PdfConfig> {
             // Mark the fallback font
             fonts.add_font_as_fallback(fallback_font_id);
 
+            // Register the configured fonts by name so scripts can look them up
+            for (name, path_str) in config.fonts.iter() {
+                let id = fonts
+                    .add_from_path(path_str)
+                    .with_context(|| format!("Failed to load font {name} from {path_str}"))?;
+                fonts.add_name(name, id);
+            }
+
             fonts
         });
 
@@ -74,6 +262,39 @@ impl Runtime<PdfConfig> {
         info!("Executing script");
         script.exec()?;
 
+        // Invoke the callbacks provided from Rust, which can do anything the script can
+        for callback in callbacks {
+            anyhow::Context::context(callback(&script), "Failed to execute callback")?;
+        }
+
+        // Draw onto pages now that every page exists and the total number of pages is known
+        anyhow::Context::context(PdfPages::run_hooks(&script), "Failed to draw pages")?;
+
+        // Run the tests registered by the script, which can still create pages and fonts
+        let suite: RuntimeTestSuite = script
+            .remove_app_data()
+            .context("Missing test suite post-script execution")?;
+        let report = if run_tests && !suite.is_empty() {
+            info!("Running {} test(s)", suite.len());
+            suite.run(&script)
+        } else {
+            RuntimeTestReport::default()
+        };
+
+        // Resolve references to pages now that every page exists
+        for id in anyhow::Context::context(
+            PdfPages::resolve_refs(&script),
+            "Failed to resolve page references",
+        )? {
+            warn!("Page reference {id} did not match any page");
+        }
+
+        // Evaluate functions pushed in place of objects now that everything else is known
+        anyhow::Context::context(
+            PdfPages::evaluate_lazy(&script),
+            "Failed to evaluate functions pushed onto pages",
+        )?;
+
         // Retrieve the post-script PDF information
         let pdf: Pdf = script
             .get_global(GLOBAL_PDF_VAR_NAME)
@@ -89,19 +310,97 @@ impl Runtime<PdfConfig> {
             .remove_app_data()
             .context("Missing fonts post-script execution")?;
 
-        Ok(Runtime((pdf.config, pages, fonts)))
+        // Retrieve the attachments to embed
+        let attachments: RuntimeAttachments = script
+            .remove_app_data()
+            .context("Missing attachments post-script execution")?;
+
+        // Retrieve the groups registered as forms
+        let xobjects: RuntimeXObjects = script
+            .remove_app_data()
+            .context("Missing xobjects post-script execution")?;
+
+        // Retrieve the pages imported from existing PDFs
+        let imports: RuntimeImports = script
+            .remove_app_data()
+            .context("Missing imports post-script execution")?;
+
+        // Retrieve the entries of the table of contents
+        let toc: RuntimeToc = script
+            .remove_app_data()
+            .context("Missing toc post-script execution")?;
+
+        Ok((
+            Runtime((
+                pdf.config,
+                pages,
+                fonts,
+                attachments,
+                xobjects,
+                imports,
+                toc,
+            )),
+            report,
+        ))
     }
 }
 
-impl Runtime<(PdfConfig, RuntimePages, RuntimeFonts)> {
+impl Runtime<RuntimeSetup> {
+    /// Serializes the pages and their objects, as they stand after the script has run and prior
+    /// to anything being drawn, into JSON for inspection by external tools.
+    pub fn dump_json(&self) -> anyhow::Result<serde_json::Value> {
+        let (config, pages, ..) = &self.0;
+
+        // Objects know how to represent themselves in Lua, so convert them there first
+        let lua = Lua::new();
+        let mut list = Vec::new();
+        for page in pages.ids().filter_map(|id| pages.get_page(id)) {
+            let mut json = PdfUtils::to_json(page.clone().into_lua(&lua)?)?;
+            json["crop"] = PdfUtils::to_json(page.crop().into_lua(&lua)?)?;
+            json["art"] = PdfUtils::to_json(page.art().into_lua(&lua)?)?;
+            json["objects"] = PdfUtils::to_json(page.objects().clone().into_lua(&lua)?)?;
+            list.push(json);
+        }
+
+        Ok(serde_json::json!({
+            "title": config.title,
+            "width": config.page.width.0,
+            "height": config.page.height.0,
+            "pages": list,
+        }))
+    }
+
     /// Builds the document representing the PDF.
-    pub fn build(self) -> anyhow::Result<Runtime<RuntimeDoc>> {
-        let (config, pages, mut fonts) = self.0;
+    pub fn build(self) -> anyhow::Result<Runtime<(RuntimeDoc, RuntimeStats)>> {
+        let (config, pages, mut fonts, attachments, mut xobjects, imports, mut toc) = self.0;
+        let mut stats = RuntimeStats::default();
         let (width, height) = (config.page.width, config.page.height);
 
         // Create our actual PDF document (empty)
         debug!("Initializing PDF document");
-        let doc = RuntimeDoc::new(&config.title);
+        let mut doc = RuntimeDoc::new(&config.title);
+
+        // Queue up the files to embed when the document is saved
+        debug!("Attaching {} files", attachments.len());
+        doc.set_attachments(attachments);
+
+        // Queue up the layers to merge and mark optional when the document is saved
+        doc.set_layers(RuntimeLayers::from(config.page.layers.clone()));
+
+        // Convert to PDF/A when saved, if requested
+        if config.pdfa {
+            doc.set_pdfa(Some(RuntimePdfA::new(&config.title)));
+        }
+
+        // Merge duplicate objects and compress streams when saved, if requested
+        doc.set_optimize(config.optimize);
+
+        // Place pages onto sheets for printing when saved, if requested
+        doc.set_imposition(config.imposition.map(RuntimeImposition::from));
+
+        // Decode the images of watermarks once to stamp onto pages while drawing them
+        let watermarks =
+            RuntimeWatermarks::load(&config.watermarks).context("Failed to load watermarks")?;
 
         // Load up our default font to pass into the draw context. We have already done this once,
         // but it may have changed since we ran our script; so, attempt to reload everything.
@@ -122,51 +421,121 @@ impl Runtime<(PdfConfig, RuntimePages, RuntimeFonts)> {
         fonts.add_font_as_fallback(fallback_font_id);
 
         // Attempt to add all the fonts to our document
+        let now = Instant::now();
         for id in fonts.to_ids() {
             debug!("Adding external font: {id}");
             if !fonts.add_font_to_doc(id, doc.as_ref())? {
                 anyhow::bail!("Failed to add font {id} to PDF document");
             }
         }
+        stats.fonts_time = now.elapsed();
 
         // Create pages in order that they were added to ensure that they show up in the right
         // order within the PDF itself
         let mut refs = HashMap::new();
+        let mut numbers = HashMap::new();
         for id in pages.ids() {
             if let Some(page) = pages.get_page(id) {
-                refs.insert(
-                    page.id,
-                    doc.add_empty_page(
-                        page.width.unwrap_or(width),
-                        page.height.unwrap_or(height),
-                        &page.title,
-                    ),
+                let (page_ref, layer_ref) = doc.add_empty_page(
+                    page.width.unwrap_or(width),
+                    page.height.unwrap_or(height),
+                    &page.title,
                 );
+
+                doc.set_page_boxes(&page_ref, page.crop(), page.art());
+                refs.insert(page.id, (page_ref, layer_ref));
+                numbers.insert(page.id, numbers.len() + 1);
+            }
+        }
+
+        // References to pages share the numbers of the pages they resolved to, while links to
+        // them are redirected to those pages when drawn
+        let resolved: HashMap<RuntimePageId, RuntimePageId> = pages.resolved().collect();
+        for (id, target) in resolved.iter() {
+            if let Some(number) = numbers.get(target).copied() {
+                numbers.insert(*id, number);
+            }
+        }
+
+        // Now that every page exists and is numbered, number the entries of the table of contents
+        // before drawing any page that lists them
+        toc.set_numbers(numbers.clone());
+
+        // Queue up the labels of pages to add when the document is saved
+        doc.set_labels(RuntimeLabels::from(pages.labels().to_vec()));
+
+        // Add a bookmark for each section at its first page, skipping sections without pages
+        for section in pages.sections() {
+            if let Some((page_ref, _)) = section.first_page().and_then(|id| refs.get(&id)) {
+                debug!("Adding bookmark for section {}", section.name);
+                doc.add_bookmark(&section.name, page_ref);
             }
         }
 
         // Draw all pages, which can be done in any order, by looking up the PDF references
         // based on the page's id
+        let alpha = RuntimeAlpha::new();
+        let mut links = RuntimeLinks::new();
         let page_cnt = pages.len();
         info!("Building {} PDF pages", page_cnt);
         for (i, page) in pages.into_iter().enumerate() {
             debug!("Building page {} ({} / {})", page.id, i, page_cnt);
             match refs.get(&page.id) {
                 None => warn!("Missing refs for page {}", page.id),
-                Some((_, layer)) => {
+                Some((page_ref, layer)) => {
+                    let now = Instant::now();
+                    let mut page_stats = RuntimePageStats::new(
+                        page.id,
+                        numbers.get(&page.id).copied().unwrap_or_default(),
+                        &page.title,
+                        page.objects().iter(),
+                    );
+
                     let ctx = PdfContext {
                         config: &config,
                         layer,
                         fonts: &fonts,
                         fallback_font_id,
+                        alpha: &alpha,
+                        xobjects: &xobjects,
+                        toc: &toc,
+                        style: PdfStyle::default(),
                     };
 
+                    // Draw the imported background first so everything else is drawn above it
+                    if let Some(id) = page.background() {
+                        trace!("Drawing background of page {}", page.id);
+                        imports.draw(
+                            id,
+                            layer,
+                            page.width.unwrap_or(width),
+                            page.height.unwrap_or(height),
+                        );
+                    }
+
+                    // Stamp watermarks beneath the page on its base layer, and above the page
+                    // on a layer of their own so they are drawn after all other layers
+                    let number = numbers.get(&page.id).copied().unwrap_or_default() as u32;
+                    let (page_width, page_height) =
+                        (page.width.unwrap_or(width), page.height.unwrap_or(height));
+                    watermarks.draw(ctx, number, page_width, page_height, false);
+
                     trace!("Drawing page {}", page.id);
-                    page.draw(ctx);
+                    page.draw(ctx, page_ref);
 
-                    // Get annotations, sorted by depth, that we will add to our layer
+                    if watermarks.has_above(number) {
+                        let layer = page_ref.add_layer("Watermark");
+                        let ctx = PdfContext {
+                            layer: &layer,
+                            ..ctx
+                        };
+                        watermarks.draw(ctx, number, page_width, page_height, true);
+                    }
+
+                    // Get annotations, sorted by depth (keeping the order of annotations with
+                    // the same depth), that we will add to our layer
                     let mut annotations = page.link_annotations(ctx);
-                    annotations.sort_unstable_by(|a, b| a.depth.cmp(&b.depth));
+                    annotations.sort_by_key(|a| a.depth);
 
                     trace!(
                         "Processing {} annotations for page {}",
@@ -174,18 +543,38 @@ impl Runtime<(PdfConfig, RuntimePages, RuntimeFonts)> {
                         page.id
                     );
                     for annotation in annotations {
-                        use printpdf::{Actions, Destination, LinkAnnotation};
+                        use printpdf::{Actions, Destination, LinkAnnotation, Pt};
 
                         // Map our link to an action, which can be none if it's an invalid action
                         // such as linking to a page that does not exist
                         let action = match annotation.link {
-                            PdfLink::GoTo { page } => {
-                                refs.get(&page).map(|x| x.0.page).map(|page| {
+                            PdfLink::GoTo {
+                                page: target,
+                                at,
+                                zoom,
+                                rect,
+                            } => {
+                                let target = resolved.get(&target).copied().unwrap_or(target);
+
+                                // Areas of pages are not supported by printpdf, so we fix up the
+                                // destination of the link once the document is saved
+                                if let (Some(rect), Some(number), Some(target)) =
+                                    (rect, numbers.get(&page.id), numbers.get(&target))
+                                {
+                                    links.add(
+                                        *number as u32,
+                                        annotation.bounds,
+                                        *target as u32,
+                                        rect,
+                                    );
+                                }
+
+                                refs.get(&target).map(|x| x.0.page).map(|page| {
                                     Actions::go_to(Destination::XYZ {
                                         page,
-                                        left: None,
-                                        top: None,
-                                        zoom: None,
+                                        left: at.map(|at| Pt::from(at.x).0),
+                                        top: at.map(|at| Pt::from(at.y).0),
+                                        zoom,
                                     })
                                 })
                             }
@@ -196,27 +585,140 @@ impl Runtime<(PdfConfig, RuntimePages, RuntimeFonts)> {
                         if let Some(action) = action {
                             layer.add_link_annotation(LinkAnnotation::new(
                                 annotation.bounds.into(),
-                                None,
-                                None,
+                                Some(annotation.border.into()),
+                                Some(annotation.border.to_color_array()),
                                 action,
-                                None,
+                                Some(annotation.highlight.into()),
                             ));
                         }
                     }
+
+                    page_stats.draw_time = now.elapsed();
+                    stats.pages.push(page_stats);
                 }
             }
+
+            // Free the objects of the page now that it is drawn, rather than holding the objects
+            // of every page until the document is saved
+            drop(page);
         }
 
-        Ok(Runtime(doc))
+        // Draw each form in use on a page of its own after all other pages, which becomes a form
+        // XObject when the document is saved. Forms can use other forms, so repeat until all
+        // forms in use are drawn.
+        let mut page_number = refs.len() as u32;
+        loop {
+            let ids = xobjects.to_undrawn_ids();
+            if ids.is_empty() {
+                break;
+            }
+
+            for id in ids {
+                debug!("Drawing form {id}");
+                let (_, layer_ref) = doc.add_empty_page(width, height, &format!("Form {id}"));
+                let ctx = PdfContext {
+                    config: &config,
+                    layer: &layer_ref,
+                    fonts: &fonts,
+                    fallback_font_id,
+                    alpha: &alpha,
+                    xobjects: &xobjects,
+                    toc: &toc,
+                    style: PdfStyle::default(),
+                };
+
+                let bounds = match xobjects.get(id) {
+                    Some(group) => {
+                        group.draw(ctx);
+                        group.bounds(ctx)
+                    }
+                    None => PdfBounds::default(),
+                };
+
+                page_number += 1;
+                xobjects.set_page(id, page_number, bounds);
+            }
+        }
+
+        // Queue up the forms to convert into form XObjects when the document is saved
+        doc.set_xobjects(xobjects);
+
+        // Queue up the imported pages to copy into the document when it is saved
+        doc.set_imports(imports);
+
+        // Queue up the images of watermarks to add to the pages when the document is saved
+        doc.set_watermarks(watermarks);
+
+        // Pages are drawn in any order, so report their statistics in the order of the document
+        stats.pages.sort_by_key(|page| page.number);
+
+        // Queue up the opacities used while drawing to add to the pages when the document is saved
+        doc.set_alpha(alpha);
+
+        // Queue up the links to areas of pages to fix up when the document is saved
+        doc.set_links(links);
+
+        Ok(Runtime((doc, stats)))
     }
 }
 
-impl Runtime<RuntimeDoc> {
-    /// Saves the PDF to the specified `filename`.
-    pub fn save(self, filename: impl Into<String>) -> anyhow::Result<()> {
+impl Runtime<(RuntimeDoc, RuntimeStats)> {
+    /// Returns the statistics collected while building the PDF.
+    pub fn stats(&self) -> &RuntimeStats {
+        &self.0 .1
+    }
+
+    /// Saves the PDF to the specified `filename`, returning the statistics collected while
+    /// building and saving it.
+    pub fn save(self, filename: impl Into<String>) -> anyhow::Result<RuntimeStats> {
+        let filename = filename.into();
+        let (doc, mut stats) = self.0;
+
+        info!("Saving PDF to {}", &filename);
+        let now = Instant::now();
+        doc.save(filename.as_str())?;
+        stats.save_time = now.elapsed();
+        stats.file_size = std::fs::metadata(&filename).ok().map(|x| x.len());
+
+        Ok(stats)
+    }
+
+    /// Saves the PDF by writing it to `writer`, returning the statistics collected while building
+    /// and saving it. The size of the PDF is not collected.
+    pub fn save_to_writer(self, writer: impl Write) -> anyhow::Result<RuntimeStats> {
+        let (doc, mut stats) = self.0;
+
+        info!("Writing PDF");
+        let now = Instant::now();
+        doc.save_to_writer(writer)?;
+        stats.save_time = now.elapsed();
+
+        Ok(stats)
+    }
+
+    /// Saves the PDF into memory, returning its bytes, such as to respond to a request without
+    /// creating a temporary file.
+    pub fn to_bytes(self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.save_to_writer(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Saves the PDF to the specified `filename`, linearizing it afterwards for fast web view,
+    /// returning the statistics collected while building and saving it.
+    pub fn save_linearized(self, filename: impl Into<String>) -> anyhow::Result<RuntimeStats> {
         let filename = filename.into();
+        let (doc, mut stats) = self.0;
 
         info!("Saving PDF to {}", &filename);
-        self.0.save(filename)
+        let now = Instant::now();
+        doc.save(filename.as_str())?;
+
+        info!("Linearizing {}", &filename);
+        RuntimeDoc::linearize(&filename)?;
+        stats.save_time = now.elapsed();
+        stats.file_size = std::fs::metadata(&filename).ok().map(|x| x.len());
+
+        Ok(stats)
     }
 }
@@ -0,0 +1,163 @@
+use printpdf::lopdf::{Dictionary, Document, Object};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+/// Prefix of the names of graphics states that set the opacity of fills and strokes.
+const PREFIX: &str = "Alpha";
+
+/// Tracks the opacities of fills and strokes used while drawing.
+///
+/// Objects drawn with translucent colors reference a graphics state by name, which is added to
+/// the resources of every page once the document is saved as printpdf does not support
+/// constant-alpha graphics states directly.
+#[derive(Debug, Default)]
+pub struct RuntimeAlpha {
+    /// Fill and stroke opacities (as bytes) of the graphics states in use.
+    states: RefCell<BTreeSet<(u8, u8)>>,
+}
+
+impl RuntimeAlpha {
+    /// Creates a new instance without any graphics states.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no graphics states are in use.
+    pub fn is_empty(&self) -> bool {
+        self.states.borrow().is_empty()
+    }
+
+    /// Returns the name of the graphics state for the `fill` and `stroke` opacities (between 0
+    /// and 1), marking it as in use.
+    pub fn state_name(&self, fill: f32, stroke: f32) -> String {
+        let to_u8 = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let (fill, stroke) = (to_u8(fill), to_u8(stroke));
+        self.states.borrow_mut().insert((fill, stroke));
+        format!("{PREFIX}{fill:03}-{stroke:03}")
+    }
+
+    /// Applies the graphics states in use to `doc`, adding them to the resources of each page.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        let states = self.states.borrow();
+        if states.is_empty() {
+            return Ok(());
+        }
+
+        // Create each graphics state once, shared by all pages
+        let mut ext_g_states = Dictionary::new();
+        for (fill, stroke) in states.iter().copied() {
+            let id = doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"ExtGState".to_vec())),
+                ("ca", Object::Real(fill as f32 / 255.0)),
+                ("CA", Object::Real(stroke as f32 / 255.0)),
+            ]));
+            ext_g_states.set(
+                format!("{PREFIX}{fill:03}-{stroke:03}"),
+                Object::Reference(id),
+            );
+        }
+
+        for (_, page_id) in doc.get_pages() {
+            // Resources are either referenced or inlined within the page
+            let resources_id = doc
+                .get_dictionary(page_id)?
+                .get(b"Resources")
+                .and_then(Object::as_reference)
+                .ok();
+            let resources = match resources_id {
+                Some(id) => doc.get_dictionary_mut(id)?,
+                None => {
+                    let page = doc.get_dictionary_mut(page_id)?;
+                    if !page.has(b"Resources") {
+                        page.set("Resources", Dictionary::new());
+                    }
+                    page.get_mut(b"Resources")?.as_dict_mut()?
+                }
+            };
+
+            // Merge with any existing graphics states of the page
+            let mut existing = resources
+                .get(b"ExtGState")
+                .and_then(Object::as_dict)
+                .cloned()
+                .unwrap_or_default();
+            for (key, value) in ext_g_states.iter() {
+                existing.set(key.clone(), value.clone());
+            }
+            resources.set("ExtGState", existing);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_add_graphics_states_in_use_to_every_page() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let resources_id = doc.add_object(Dictionary::from_iter(vec![(
+            "ExtGState",
+            Object::Dictionary(Dictionary::from_iter(vec![("GS1", Object::Null)])),
+        )]));
+        let kids = vec![
+            Object::Reference(doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Page".to_vec())),
+                ("Parent", Object::Reference(pages_id)),
+                ("Resources", Object::Reference(resources_id)),
+            ]))),
+            Object::Reference(doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Page".to_vec())),
+                ("Parent", Object::Reference(pages_id)),
+            ]))),
+        ];
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(2)),
+                ("Kids", Object::Array(kids)),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        let alpha = RuntimeAlpha::new();
+        assert!(alpha.is_empty());
+        assert_eq!(alpha.state_name(0.5, 1.0), "Alpha128-255");
+        assert_eq!(alpha.state_name(0.5, 1.0), "Alpha128-255");
+        assert!(!alpha.is_empty());
+        alpha.apply(&mut doc).unwrap();
+
+        for page_id in doc.get_pages().into_values() {
+            let page = doc.get_dictionary(page_id).unwrap();
+            let resources = match page.get(b"Resources").unwrap() {
+                Object::Reference(id) => doc.get_dictionary(*id).unwrap(),
+                x => x.as_dict().unwrap(),
+            };
+            let states = resources
+                .get(b"ExtGState")
+                .and_then(Object::as_dict)
+                .unwrap();
+            let state = doc
+                .get_dictionary(states.get(b"Alpha128-255").unwrap().as_reference().unwrap())
+                .unwrap();
+            assert_eq!(state.get(b"ca").unwrap().as_float().unwrap(), 128.0 / 255.0);
+            assert_eq!(state.get(b"CA").unwrap().as_float().unwrap(), 1.0);
+        }
+
+        // Existing graphics states are kept
+        let resources = doc.get_dictionary(resources_id).unwrap();
+        assert!(resources
+            .get(b"ExtGState")
+            .and_then(Object::as_dict)
+            .unwrap()
+            .has(b"GS1"));
+    }
+}
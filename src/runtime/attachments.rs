@@ -0,0 +1,178 @@
+use printpdf::lopdf::{Dictionary, Document, Object, Stream, StringFormat};
+
+/// File embedded within the PDF, which viewers make available to extract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeAttachment {
+    /// Unique name of the file within the PDF, e.g. `data.csv`.
+    pub name: String,
+
+    /// Contents of the file.
+    pub data: Vec<u8>,
+
+    /// Optional description of the file shown by viewers.
+    pub description: Option<String>,
+
+    /// Optional MIME type of the file, e.g. `text/csv`.
+    pub mime: Option<String>,
+}
+
+/// Manages a collection of files to embed within the PDF.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeAttachments {
+    attachments: Vec<RuntimeAttachment>,
+}
+
+impl RuntimeAttachments {
+    /// Creates a new, empty collection of attachments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the total number of attachments.
+    pub fn len(&self) -> usize {
+        self.attachments.len()
+    }
+
+    /// Returns `true` if there are no attachments.
+    pub fn is_empty(&self) -> bool {
+        self.attachments.is_empty()
+    }
+
+    /// Returns an iterator over the attachments, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &RuntimeAttachment> {
+        self.attachments.iter()
+    }
+
+    /// Adds `attachment` to the collection, failing if an attachment with the same name exists.
+    pub fn add(&mut self, attachment: RuntimeAttachment) -> Result<(), String> {
+        if self.attachments.iter().any(|x| x.name == attachment.name) {
+            return Err(format!("Attachment '{}' already exists", attachment.name));
+        }
+
+        self.attachments.push(attachment);
+        Ok(())
+    }
+
+    /// Embeds the attachments within `doc`, listing them in the embedded files of the catalog.
+    pub fn embed(&self, doc: &mut Document) -> anyhow::Result<()> {
+        if self.attachments.is_empty() {
+            return Ok(());
+        }
+
+        // Embedded files are a name tree, whose names must be sorted
+        let mut attachments: Vec<&RuntimeAttachment> = self.attachments.iter().collect();
+        attachments.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut names = Vec::new();
+        for attachment in attachments {
+            let mut dict = Dictionary::new();
+            dict.set("Type", Object::Name(b"EmbeddedFile".to_vec()));
+            if let Some(mime) = attachment.mime.as_deref() {
+                dict.set("Subtype", Object::Name(mime.as_bytes().to_vec()));
+            }
+            dict.set(
+                "Params",
+                Dictionary::from_iter(vec![(
+                    "Size",
+                    Object::Integer(attachment.data.len() as i64),
+                )]),
+            );
+
+            let mut stream = Stream::new(dict, attachment.data.clone());
+            let _ = stream.compress();
+            let file_id = doc.add_object(stream);
+
+            let name = Object::String(attachment.name.as_bytes().to_vec(), StringFormat::Literal);
+            let mut spec = Dictionary::new();
+            spec.set("Type", Object::Name(b"Filespec".to_vec()));
+            spec.set("F", name.clone());
+            spec.set("UF", utf16_string(&attachment.name));
+            spec.set(
+                "EF",
+                Dictionary::from_iter(vec![("F", Object::Reference(file_id))]),
+            );
+            if let Some(description) = attachment.description.as_deref() {
+                spec.set("Desc", utf16_string(description));
+            }
+
+            names.push(name);
+            names.push(Object::Reference(doc.add_object(spec)));
+        }
+
+        let catalog = doc.catalog_mut()?;
+        let mut names_dict = match catalog.get(b"Names").and_then(Object::as_dict) {
+            Ok(dict) => dict.clone(),
+            Err(_) => Dictionary::new(),
+        };
+        names_dict.set(
+            "EmbeddedFiles",
+            Dictionary::from_iter(vec![("Names", Object::Array(names))]),
+        );
+        catalog.set("Names", names_dict);
+
+        Ok(())
+    }
+}
+
+/// Encodes `s` as a UTF-16BE text string with a byte order mark, supporting any characters.
+pub(crate) fn utf16_string(s: &str) -> Object {
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(s.encode_utf16().flat_map(u16::to_be_bytes));
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(name: &str) -> RuntimeAttachment {
+        RuntimeAttachment {
+            name: name.to_string(),
+            data: name.as_bytes().to_vec(),
+            description: None,
+            mime: None,
+        }
+    }
+
+    #[test]
+    fn add_should_fail_if_attachment_with_same_name_exists() {
+        let mut attachments = RuntimeAttachments::new();
+        attachments.add(attachment("a.csv")).unwrap();
+        attachments.add(attachment("b.csv")).unwrap();
+        assert!(attachments.add(attachment("a.csv")).is_err());
+        assert_eq!(attachments.len(), 2);
+    }
+
+    #[test]
+    fn embed_should_list_attachments_sorted_by_name_in_catalog() {
+        let mut doc = Document::with_version("1.7");
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![(
+            "Type",
+            Object::Name(b"Catalog".to_vec()),
+        )]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        let mut attachments = RuntimeAttachments::new();
+        attachments.add(attachment("b.csv")).unwrap();
+        attachments.add(attachment("a.csv")).unwrap();
+        attachments.embed(&mut doc).unwrap();
+
+        let names = doc
+            .catalog()
+            .unwrap()
+            .get(b"Names")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"EmbeddedFiles"))
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"Names"))
+            .and_then(Object::as_array)
+            .unwrap();
+
+        let names: Vec<&[u8]> = names
+            .iter()
+            .step_by(2)
+            .map(|x| x.as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec![b"a.csv".as_slice(), b"b.csv".as_slice()]);
+    }
+}
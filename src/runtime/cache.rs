@@ -0,0 +1,112 @@
+use crate::constants::SCRIPTS;
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use mlua::Compiler;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Cache kept warm between builds, such as those of a batch, holding the fonts read from disk and
+/// the compiled stdlib so that each build only needs to read and run its own script.
+///
+/// Each build still runs within a fresh Lua runtime, so builds cannot see each other's globals.
+/// Clones share the same cache, which can be used by builds on different threads.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeCache {
+    inner: Arc<Mutex<RuntimeCacheInner>>,
+}
+
+#[derive(Debug, Default)]
+struct RuntimeCacheInner {
+    /// Collection of canonical font path -> time the file was modified & bytes of the font.
+    fonts: HashMap<PathBuf, (Option<SystemTime>, Arc<Vec<u8>>)>,
+
+    /// Bytecode of the stdlib script, compiled the first time it is needed.
+    stdlib: Option<Arc<Vec<u8>>>,
+}
+
+impl RuntimeCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the bytes of the font at `path`, reading the file unless it was read before and
+    /// has not been modified since.
+    pub fn read_font(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        RuntimeFiles::check_fs(path)?;
+        let modified = std::fs::metadata(path).and_then(|x| x.modified()).ok();
+
+        if let Some((cached_modified, bytes)) = self.inner.lock().unwrap().fonts.get(path) {
+            if modified.is_some() && *cached_modified == modified {
+                return Ok(bytes.to_vec());
+            }
+        }
+
+        // Read the font without holding the lock so other builds are not blocked on the disk
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read font file: {}", path.display()))?;
+        self.inner
+            .lock()
+            .unwrap()
+            .fonts
+            .insert(path.to_path_buf(), (modified, Arc::new(bytes.clone())));
+        Ok(bytes)
+    }
+
+    /// Returns the bytecode of the stdlib script, compiling it the first time, or none if there
+    /// is no stdlib script.
+    pub fn stdlib(&self) -> Option<Arc<Vec<u8>>> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.stdlib.is_none() {
+            inner.stdlib = SCRIPTS
+                .get("stdlib")
+                .map(|code| Arc::new(Compiler::new().compile(code)));
+        }
+
+        inner.stdlib.clone()
+    }
+
+    /// Returns the number of fonts read from disk that are kept within the cache.
+    pub fn font_count(&self) -> usize {
+        self.inner.lock().unwrap().fonts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DEFAULT_FONT;
+
+    #[test]
+    fn read_font_should_read_again_once_the_font_changes() {
+        let path = std::env::temp_dir().join(format!(
+            "makepdf-cache-{}-{}.ttf",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        std::fs::write(&path, DEFAULT_FONT).unwrap();
+
+        let cache = RuntimeCache::new();
+        assert_eq!(cache.read_font(&path).unwrap(), DEFAULT_FONT);
+        assert_eq!(cache.clone().read_font(&path).unwrap(), DEFAULT_FONT);
+        assert_eq!(cache.font_count(), 1);
+
+        // Changing the font is noticed through the time it was modified
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_len(4).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(cache.read_font(&path).unwrap(), &DEFAULT_FONT[..4]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stdlib_should_compile_once() {
+        let cache = RuntimeCache::new();
+        let stdlib = cache.stdlib().expect("Missing stdlib");
+        assert!(Arc::ptr_eq(&stdlib, &cache.stdlib().unwrap()));
+    }
+}
@@ -1,10 +1,81 @@
+use crate::pdf::PdfBounds;
+use crate::runtime::{
+    RuntimeAlpha, RuntimeAttachments, RuntimeFiles, RuntimeImports, RuntimeImposition,
+    RuntimeLabels, RuntimeLayers, RuntimeLinks, RuntimeOptimize, RuntimePdfA, RuntimeWatermarks,
+    RuntimeXObjects,
+};
 use anyhow::Context;
-use printpdf::{Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, PdfPageReference};
+use printpdf::lopdf::{Dictionary, Document, Object};
+use printpdf::{Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, PdfPageReference, Pt};
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
+use std::process::Command;
+
+/// Error when `qpdf` cannot be run to linearize a PDF.
+const QPDF_NOT_FOUND: &str = "Failed to run qpdf, which is required to linearize PDFs, so \
+    install qpdf (https://qpdf.readthedocs.io) and make sure it is on the path";
+
+/// Finished document, ready to be written.
+enum RuntimeDocOutput {
+    /// Document written as is.
+    Direct(PdfDocumentReference),
+    /// Document reloaded to apply everything queued up when saved.
+    Reloaded(Box<Document>),
+    /// Document reloaded and converted to PDF/A, which is written marked as binary.
+    PdfA(Box<Document>),
+}
+
+impl RuntimeDocOutput {
+    /// Writes the document to `writer`.
+    fn write(self, writer: impl Write) -> anyhow::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        match self {
+            Self::Direct(doc) => doc.save(&mut writer).context("Failed to write document")?,
+            Self::Reloaded(mut doc) => doc
+                .save_to(&mut writer)
+                .context("Failed to write document")?,
+            Self::PdfA(mut doc) => RuntimePdfA::write(&mut doc, &mut writer)?,
+        }
+
+        writer.flush().context("Failed to write document")
+    }
+}
 
 pub struct RuntimeDoc {
     doc: PdfDocumentReference,
+
+    /// Files to embed within the document when saved.
+    attachments: RuntimeAttachments,
+
+    /// Layers to apply to the document when saved.
+    layers: RuntimeLayers,
+
+    /// Opacities used while drawing to add to the pages when saved.
+    alpha: RuntimeAlpha,
+
+    /// Forms drawn on pages of their own to convert into form XObjects when saved.
+    xobjects: RuntimeXObjects,
+
+    /// Pages imported from existing PDFs to copy into form XObjects when saved.
+    imports: RuntimeImports,
+
+    /// Images of watermarks stamped onto pages to add to the document when saved.
+    watermarks: RuntimeWatermarks,
+
+    /// Links to areas of pages to fix up when saved.
+    links: RuntimeLinks,
+
+    /// Labels of pages to add to the document when saved.
+    labels: RuntimeLabels,
+
+    /// If provided, places pages onto sheets for printing when saved.
+    imposition: Option<RuntimeImposition>,
+
+    /// If provided, converts the document to PDF/A when saved.
+    pdfa: Option<RuntimePdfA>,
+
+    /// If true, merges duplicate objects and compresses streams when saved.
+    optimize: bool,
 }
 
 impl AsRef<PdfDocumentReference> for RuntimeDoc {
@@ -18,9 +89,76 @@ impl RuntimeDoc {
     pub fn new(title: &str) -> Self {
         Self {
             doc: PdfDocument::empty(title),
+            attachments: RuntimeAttachments::new(),
+            layers: RuntimeLayers::new(),
+            alpha: RuntimeAlpha::new(),
+            xobjects: RuntimeXObjects::new(),
+            imports: RuntimeImports::new(),
+            watermarks: RuntimeWatermarks::new(),
+            links: RuntimeLinks::new(),
+            labels: RuntimeLabels::new(),
+            imposition: None,
+            pdfa: None,
+            optimize: false,
         }
     }
 
+    /// Replaces the files to embed within the document when saved.
+    pub fn set_attachments(&mut self, attachments: RuntimeAttachments) {
+        self.attachments = attachments;
+    }
+
+    /// Replaces the layers to apply to the document when saved.
+    pub fn set_layers(&mut self, layers: RuntimeLayers) {
+        self.layers = layers;
+    }
+
+    /// Replaces the opacities used while drawing to add to the pages when saved.
+    pub fn set_alpha(&mut self, alpha: RuntimeAlpha) {
+        self.alpha = alpha;
+    }
+
+    /// Replaces the forms to convert into form XObjects when saved.
+    pub fn set_xobjects(&mut self, xobjects: RuntimeXObjects) {
+        self.xobjects = xobjects;
+    }
+
+    /// Replaces the pages imported from existing PDFs to copy into the document when saved.
+    pub fn set_imports(&mut self, imports: RuntimeImports) {
+        self.imports = imports;
+    }
+
+    /// Replaces the images of watermarks to add to the document when saved.
+    pub fn set_watermarks(&mut self, watermarks: RuntimeWatermarks) {
+        self.watermarks = watermarks;
+    }
+
+    /// Replaces the links to areas of pages to fix up when saved.
+    pub fn set_links(&mut self, links: RuntimeLinks) {
+        self.links = links;
+    }
+
+    /// Replaces the labels of pages to add to the document when saved.
+    pub fn set_labels(&mut self, labels: RuntimeLabels) {
+        self.labels = labels;
+    }
+
+    /// Sets how to place pages onto sheets for printing when saved, if at all.
+    pub fn set_imposition(&mut self, imposition: Option<RuntimeImposition>) {
+        self.imposition = imposition;
+    }
+
+    /// Sets whether to convert the document to PDF/A when saved, which fails to save if the
+    /// document cannot meet the requirements of PDF/A.
+    pub fn set_pdfa(&mut self, pdfa: Option<RuntimePdfA>) {
+        self.pdfa = pdfa;
+    }
+
+    /// Sets whether to optimize the size of the document when saved.
+    pub fn set_optimize(&mut self, optimize: bool) {
+        self.optimize = optimize;
+    }
+
     /// Adds a new, empty page named `title` of `width` x `height` to the document.
     ///
     /// This will be the next page in sequence!
@@ -36,12 +174,192 @@ impl RuntimeDoc {
         (page, layer)
     }
 
+    /// Adds a bookmark named `name` to the document's outline that navigates to `page`.
+    ///
+    /// Each page can have at most one bookmark, so this replaces any existing bookmark of `page`.
+    pub fn add_bookmark(&self, name: &str, page: &PdfPageReference) {
+        self.doc.add_bookmark(name, page.page);
+    }
+
+    /// Overrides the crop and art boxes of `page`, which otherwise cover the entire page.
+    pub fn set_page_boxes(
+        &self,
+        page: &PdfPageReference,
+        crop: Option<PdfBounds>,
+        art: Option<PdfBounds>,
+    ) {
+        fn to_object(bounds: PdfBounds) -> Object {
+            let (llx, lly, urx, ury) = bounds.to_coords();
+            Object::Array(
+                [llx, lly, urx, ury]
+                    .into_iter()
+                    .map(|x| Object::Real(Pt::from(x).0))
+                    .collect(),
+            )
+        }
+
+        let mut dict = Dictionary::new();
+        if let Some(crop) = crop {
+            dict.set("CropBox", to_object(crop));
+        }
+
+        if let Some(art) = art {
+            dict.set("ArtBox", to_object(art));
+        }
+
+        if !dict.is_empty() {
+            page.extend_with(dict);
+        }
+    }
+
     /// Saves the doc to the specified `filename`.
     pub fn save(self, filename: impl Into<String>) -> anyhow::Result<()> {
         let filename = filename.into();
+        RuntimeFiles::check_fs(&filename)?;
+
+        // Finish the document before creating the file so that failing to finish it, such as
+        // failing to meet the requirements of PDF/A, does not leave an empty file behind
+        let output = self
+            .finish()
+            .with_context(|| format!("Failed to save {filename}"))?;
         let f = File::create(&filename).with_context(|| format!("Failed to create {filename}"))?;
-        self.doc
-            .save(&mut BufWriter::new(f))
+        output
+            .write(f)
             .with_context(|| format!("Failed to save {filename}"))
     }
+
+    /// Saves the doc by writing it to `writer`, such as to respond to a request without creating
+    /// a file.
+    pub fn save_to_writer(self, writer: impl Write) -> anyhow::Result<()> {
+        self.finish()?.write(writer)
+    }
+
+    /// Finishes the doc, applying everything queued up to apply when saved.
+    fn finish(self) -> anyhow::Result<RuntimeDocOutput> {
+        // Without attachments, layers, opacities, forms, imported pages, watermark images, links
+        // to areas, page labels, imposition, PDF/A, or optimizing, we can write the document
+        // directly
+        if self.attachments.is_empty()
+            && self.layers.is_empty()
+            && self.alpha.is_empty()
+            && self.xobjects.is_empty()
+            && self.imports.is_empty()
+            && self.watermarks.is_empty()
+            && self.links.is_empty()
+            && self.labels.is_empty()
+            && self.imposition.is_none()
+            && self.pdfa.is_none()
+            && !self.optimize
+        {
+            return Ok(RuntimeDocOutput::Direct(self.doc));
+        }
+
+        // Otherwise, we need to reload the document to embed the files, apply the opacities,
+        // links, forms, imported pages, layers, and page labels, impose pages onto sheets, convert
+        // to PDF/A, and optimize before writing it
+        //
+        // Forms are converted from pages after opacities are added to their resources, and
+        // before layers are applied so the layers of those pages are not listed; links are
+        // fixed up beforehand as removing the pages of forms does not renumber other pages.
+        // Imported pages are copied afterwards so that only the remaining pages reference them
+        let bytes = self
+            .doc
+            .save_to_bytes()
+            .context("Failed to serialize document")?;
+        let mut doc = Document::load_mem(&bytes).context("Failed to reload document")?;
+
+        // Free the serialized document now that it is reloaded, rather than holding both in memory
+        // while everything else is applied, which matters for documents with thousands of pages
+        drop(bytes);
+        self.attachments
+            .embed(&mut doc)
+            .context("Failed to embed attachments")?;
+        self.alpha
+            .apply(&mut doc)
+            .context("Failed to apply opacities")?;
+        self.links
+            .apply(&mut doc)
+            .context("Failed to apply links")?;
+        self.xobjects
+            .apply(&mut doc)
+            .context("Failed to apply forms")?;
+        self.imports
+            .apply(&mut doc)
+            .context("Failed to apply imported pages")?;
+        self.watermarks
+            .apply(&mut doc)
+            .context("Failed to apply watermarks")?;
+        self.layers
+            .apply(&mut doc)
+            .context("Failed to apply layers")?;
+        self.labels
+            .apply(&mut doc)
+            .context("Failed to apply page labels")?;
+
+        if let Some(imposition) = self.imposition.as_ref() {
+            imposition
+                .apply(&mut doc)
+                .context("Failed to impose pages onto sheets")?;
+        }
+
+        if let Some(pdfa) = self.pdfa.as_ref() {
+            pdfa.apply(&mut doc).context("Failed to convert to PDF/A")?;
+
+            let issues = RuntimePdfA::validate(&doc);
+            if !issues.is_empty() {
+                anyhow::bail!(
+                    "Failed to meet the requirements of PDF/A-2b:\n{}",
+                    issues
+                        .iter()
+                        .map(|x| format!("- {x}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                );
+            }
+        }
+
+        if self.optimize {
+            RuntimeOptimize::apply(&mut doc);
+        }
+
+        if self.pdfa.is_some() {
+            Ok(RuntimeDocOutput::PdfA(Box::new(doc)))
+        } else {
+            Ok(RuntimeDocOutput::Reloaded(Box::new(doc)))
+        }
+    }
+
+    /// Checks that `qpdf`, which is required to linearize, is available on the path.
+    pub fn check_linearize() -> anyhow::Result<()> {
+        match Command::new("qpdf").arg("--version").output() {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => anyhow::bail!(
+                "Failed to run qpdf, which is required to linearize: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(x) => Err(x).context(QPDF_NOT_FOUND),
+        }
+    }
+
+    /// Linearizes the PDF at `filename` in place for fast web view, allowing a viewer to load
+    /// the document one page at a time (e.g. over HTTP).
+    ///
+    /// Requires `qpdf` to be available on the path.
+    pub fn linearize(filename: &str) -> anyhow::Result<()> {
+        let output = Command::new("qpdf")
+            .arg("--linearize")
+            .arg("--replace-input")
+            .arg(filename)
+            .output()
+            .context(QPDF_NOT_FOUND)?;
+
+        // Exit code of 3 indicates success with warnings
+        match output.status.code() {
+            Some(0 | 3) => Ok(()),
+            _ => anyhow::bail!(
+                "Failed to linearize {filename}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }
+    }
 }
@@ -0,0 +1,141 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Manages which files a script is allowed to read.
+///
+/// Files can only be read if they are within one of the allowed directories (or their
+/// subdirectories), which prevents scripts from reading arbitrary files on the system.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeFiles {
+    /// Canonical paths of directories whose files can be read.
+    allowed: Vec<PathBuf>,
+}
+
+impl RuntimeFiles {
+    /// Creates a new instance that does not allow reading any files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance that allows reading files within the current working directory and
+    /// the directory containing `script`.
+    pub fn for_script(script: impl AsRef<Path>) -> io::Result<Self> {
+        let mut files = Self::new();
+        files.allow(".")?;
+
+        if let Some(parent) = script.as_ref().parent() {
+            if !parent.as_os_str().is_empty() {
+                files.allow(parent)?;
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Allows reading files within `dir`, failing if the directory does not exist.
+    pub fn allow(&mut self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref().canonicalize()?;
+        if !self.allowed.contains(&dir) {
+            self.allowed.push(dir);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the entire contents of the file at `path` as a string, failing if the file is not
+    /// within an allowed directory.
+    pub fn read_to_string(&self, path: impl AsRef<Path>) -> io::Result<String> {
+        std::fs::read_to_string(self.resolve(path)?)
+    }
+
+    /// Reads the entire contents of the file at `path` as bytes, failing if the file is not
+    /// within an allowed directory.
+    pub fn read(&self, path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        std::fs::read(self.resolve(path)?)
+    }
+
+    /// Resolves `path` into its canonical form, failing if it is not within an allowed directory.
+    ///
+    /// Always fails when built without the `fs` feature, as checked by [`RuntimeFiles::check_fs`].
+    pub fn resolve(&self, path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let path = path.as_ref();
+        Self::check_fs(path)?;
+
+        // Check existence first so missing files are reported as missing rather than disallowed
+        let canonical = path.canonicalize()?;
+        if !self.allowed.iter().any(|dir| canonical.starts_with(dir)) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is not within an allowed directory", path.display()),
+            ));
+        }
+
+        Ok(canonical)
+    }
+
+    /// Fails when built without the `fs` feature, such as for the browser, where there is no
+    /// filesystem to access `path` within. Everything that reads or writes files checks this
+    /// first, so that it fails the same way.
+    pub fn check_fs(path: impl AsRef<Path>) -> io::Result<()> {
+        if cfg!(feature = "fs") {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} cannot be accessed as makepdf was built without the fs feature",
+                path.as_ref().display()
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_to_string_should_fail_if_file_not_within_allowed_directory() {
+        let root = std::env::temp_dir().join(format!("makepdf-files-{}", rand::random::<u32>()));
+        let allowed = root.join("allowed");
+        std::fs::create_dir_all(&allowed).unwrap();
+        std::fs::write(allowed.join("file.txt"), "inside").unwrap();
+        std::fs::write(root.join("file.txt"), "outside").unwrap();
+
+        let mut files = RuntimeFiles::new();
+        files.allow(&allowed).unwrap();
+
+        assert_eq!(
+            files.read_to_string(allowed.join("file.txt")).unwrap(),
+            "inside"
+        );
+        assert_eq!(
+            files
+                .read_to_string(root.join("file.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::PermissionDenied
+        );
+
+        // Escaping the directory through relative paths is also disallowed
+        assert_eq!(
+            files
+                .read_to_string(allowed.join("..").join("file.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::PermissionDenied
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn check_fs_should_fail_only_if_built_without_fs_feature() {
+        let result = RuntimeFiles::check_fs("planner.pdf");
+        assert_eq!(result.is_ok(), cfg!(feature = "fs"));
+        if let Err(x) = result {
+            assert_eq!(x.kind(), io::ErrorKind::Unsupported);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::constants::DEFAULT_FONT;
+use crate::runtime::{RuntimeCache, RuntimeFiles};
 use anyhow::Context;
 use owned_ttf_parser::{AsFaceRef, Face, OwnedFace};
-use printpdf::{IndirectFontRef, PdfDocumentReference};
+use printpdf::{IndirectFontRef, Mm, PdfDocumentReference};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -9,14 +11,30 @@ use std::path::{Path, PathBuf};
 /// retrieve a font face or a document's indirect font reference.
 pub type RuntimeFontId = u32;
 
+/// Maximum number of text widths to remember before forgetting all of them, bounding the memory
+/// used by scripts that measure lots of distinct text.
+const MAX_TEXT_WIDTHS: usize = 65_536;
+
+/// Widths of text measured earlier, keyed by the ids of the fonts used to measure the text, the
+/// text itself, and the bits of the font size.
+#[derive(Debug, Default)]
+struct TextWidths {
+    widths: HashMap<Vec<RuntimeFontId>, HashMap<String, HashMap<u32, Mm>>>,
+    len: usize,
+}
+
 /// Contains fonts used by the runtime.
 #[derive(Debug, Default)]
 pub struct RuntimeFonts {
     paths: HashMap<PathBuf, RuntimeFontId>,
+    names: HashMap<String, RuntimeFontId>,
     faces: HashMap<RuntimeFontId, OwnedFace>,
     refs: HashMap<RuntimeFontId, IndirectFontRef>,
     builtin_font_id: Option<RuntimeFontId>,
     fallback_font_id: Option<RuntimeFontId>,
+    fallback_chain: Vec<RuntimeFontId>,
+    text_widths: RefCell<TextWidths>,
+    cache: RuntimeCache,
 }
 
 impl RuntimeFonts {
@@ -25,10 +43,16 @@ impl RuntimeFonts {
         Self::default()
     }
 
+    /// Reads fonts from disk through `cache`, reusing the fonts that earlier builds read.
+    pub fn set_cache(&mut self, cache: RuntimeCache) {
+        self.cache = cache;
+    }
+
     /// Return the font id for the specified `path` is one has been loaded from that path.
     pub fn font_for_path(&self, path: impl AsRef<Path>) -> anyhow::Result<Option<RuntimeFontId>> {
         // Canonicalize the font's path so we have a consistent path to use
         // when looking up a font by path
+        RuntimeFiles::check_fs(&path)?;
         let path = path
             .as_ref()
             .canonicalize()
@@ -50,6 +74,21 @@ impl RuntimeFonts {
             .cloned()
     }
 
+    /// Return the font id registered under `name`, if any.
+    pub fn font_for_name(&self, name: &str) -> Option<RuntimeFontId> {
+        self.names.get(name).copied()
+    }
+
+    /// Registers the font specified by `id` under `name`, replacing any font registered under the
+    /// same name, so that it can be looked up by name.
+    pub fn add_name(
+        &mut self,
+        name: impl Into<String>,
+        id: RuntimeFontId,
+    ) -> Option<RuntimeFontId> {
+        self.names.insert(name.into(), id)
+    }
+
     /// Loads the font face from `path` into memory, returning an id to access the font
     /// information.
     ///
@@ -58,6 +97,7 @@ impl RuntimeFonts {
     pub fn add_from_path(&mut self, path: impl AsRef<Path>) -> anyhow::Result<RuntimeFontId> {
         // Canonicalize the font's path so we have a consistent path to use
         // when looking up a font by path
+        RuntimeFiles::check_fs(&path)?;
         let path = path
             .as_ref()
             .canonicalize()
@@ -71,8 +111,7 @@ impl RuntimeFonts {
 
         // Otherwise, this is considered a new font and we will read it into memory and add the
         // bytes as a new owned font face
-        let bytes =
-            std::fs::read(path.as_path()).with_context(|| "Failed to read font file: {path}")?;
+        let bytes = self.cache.read_font(&path)?;
         let id = self.add_from_bytes(bytes)?;
 
         // Cache the path so we don't reload the same font in the future
@@ -125,6 +164,61 @@ impl RuntimeFonts {
         self.fallback_font_id
     }
 
+    /// Replaces the ordered list of fonts used for characters missing from both the font of the
+    /// text and the fallback font, such as an emoji or symbol font.
+    ///
+    /// Returns the previous list of font ids.
+    pub fn set_fallback_chain(&mut self, ids: Vec<RuntimeFontId>) -> Vec<RuntimeFontId> {
+        std::mem::replace(&mut self.fallback_chain, ids)
+    }
+
+    /// Returns the ordered list of fonts used for characters missing from the fallback font.
+    #[inline]
+    pub fn fallback_chain(&self) -> &[RuntimeFontId] {
+        &self.fallback_chain
+    }
+
+    /// Returns the width of `text` drawn at `size` using the fonts with `ids`, in order of
+    /// preference, reusing the width from when the same text was measured the same way before.
+    ///
+    /// Otherwise, `measure` is invoked to measure the text and its width is remembered. Faces of
+    /// fonts never change once loaded, so remembered widths never go stale.
+    pub fn text_width(
+        &self,
+        ids: &[RuntimeFontId],
+        text: &str,
+        size: f32,
+        measure: impl FnOnce() -> Mm,
+    ) -> Mm {
+        let bits = size.to_bits();
+        if let Some(width) = self
+            .text_widths
+            .borrow()
+            .widths
+            .get(ids)
+            .and_then(|x| x.get(text))
+            .and_then(|x| x.get(&bits))
+        {
+            return *width;
+        }
+
+        let width = measure();
+        let mut text_widths = self.text_widths.borrow_mut();
+        if text_widths.len >= MAX_TEXT_WIDTHS {
+            *text_widths = TextWidths::default();
+        }
+
+        text_widths
+            .widths
+            .entry(ids.to_vec())
+            .or_default()
+            .entry(text.to_string())
+            .or_default()
+            .insert(bits, width);
+        text_widths.len += 1;
+        width
+    }
+
     /// Returns a distinct collection of font ids.
     ///
     /// These may or may not have been added to the PDF document.
@@ -150,6 +244,8 @@ impl RuntimeFonts {
     /// Returns true if the font exists and was added to the doc, or false if the font does not
     /// exist. Any other error will be captured and returned as an error.
     ///
+    /// Fonts are subset when the document is saved, embedding only the glyphs that were used.
+    ///
     /// NOTE: Because the font is cached, this means that you cannot add the font to more than one
     ///       PDF document. A font collection should really only be used with a singular PDF
     ///       document, so it is considered out of scope to handle multiple documents with the same
@@ -0,0 +1,329 @@
+use super::xobjects::add_xobjects_to_pages;
+use anyhow::Context;
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use printpdf::{Mm, PdfLayerReference, Pt};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Prefix of the names of imported pages within the resources of pages.
+const PREFIX: &str = "Import";
+
+/// Type of unique id associated with an imported page.
+pub type RuntimeImportId = usize;
+
+/// Page of an existing PDF that is imported into the document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeImport {
+    /// Canonical path to the PDF containing the page.
+    pub path: PathBuf,
+
+    /// Number of the page (starting at 1) within the PDF.
+    pub page: u32,
+
+    /// Visible area of the page as `[llx, lly, urx, ury]` in points, which is its crop box or
+    /// (without one) its media box.
+    pub bbox: [f32; 4],
+}
+
+impl RuntimeImport {
+    /// Returns the width and height of the visible area of the page.
+    pub fn size(&self) -> (Mm, Mm) {
+        let [llx, lly, urx, ury] = self.bbox;
+        (Pt(urx - llx).into(), Pt(ury - lly).into())
+    }
+}
+
+/// Manages pages imported from existing PDFs, which are drawn as the backgrounds of pages.
+///
+/// Each page in use is copied (alongside its resources) into the document as a form XObject once
+/// the document is saved, with every page referencing the form by name.
+#[derive(Debug, Default)]
+pub struct RuntimeImports {
+    /// Pages that have been imported, indexed by their ids.
+    imports: Vec<RuntimeImport>,
+
+    /// Ids of the imported pages drawn onto pages.
+    used: RefCell<BTreeSet<RuntimeImportId>>,
+}
+
+impl RuntimeImports {
+    /// Creates a new instance without any imported pages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no imported pages are drawn on pages.
+    pub fn is_empty(&self) -> bool {
+        self.used.borrow().is_empty()
+    }
+
+    /// Imports page `page` (starting at 1) of the PDF at `path`, returning its id. Importing the
+    /// same page more than once returns the same id.
+    ///
+    /// Fails if the PDF cannot be loaded or does not have the page.
+    pub fn add(&mut self, path: impl AsRef<Path>, page: u32) -> anyhow::Result<RuntimeImportId> {
+        let path = path.as_ref();
+        if let Some(id) = self
+            .imports
+            .iter()
+            .position(|x| x.path == path && x.page == page)
+        {
+            return Ok(id);
+        }
+
+        let doc = load(path)?;
+        let page_id = page_id(&doc, path, page)?;
+        let bbox = page_box(&doc, page_id)?;
+
+        self.imports.push(RuntimeImport {
+            path: path.to_path_buf(),
+            page,
+            bbox,
+        });
+        Ok(self.imports.len() - 1)
+    }
+
+    /// Returns the imported page with `id`.
+    pub fn get(&self, id: RuntimeImportId) -> Option<&RuntimeImport> {
+        self.imports.get(id)
+    }
+
+    /// Returns the name of the imported page with `id`, marking it as in use.
+    pub fn form_name(&self, id: RuntimeImportId) -> String {
+        self.used.borrow_mut().insert(id);
+        format!("{PREFIX}{id}")
+    }
+
+    /// Draws the imported page with `id` onto `layer`, scaled to fit (and centered within) a page
+    /// of `width` and `height`.
+    pub fn draw(&self, id: RuntimeImportId, layer: &PdfLayerReference, width: Mm, height: Mm) {
+        let Some(import) = self.get(id) else {
+            return;
+        };
+
+        let [llx, lly, urx, ury] = import.bbox;
+        let (width, height) = (Pt::from(width).0, Pt::from(height).0);
+        let (w, h) = (urx - llx, ury - lly);
+        let scale = (width / w).min(height / h);
+        let x = (width - w * scale) / 2.0 - llx * scale;
+        let y = (height - h * scale) / 2.0 - lly * scale;
+        let name = self.form_name(id);
+
+        layer.save_graphics_state();
+        layer.add_operation(Operation::new(
+            "cm",
+            vec![
+                scale.into(),
+                0.into(),
+                0.into(),
+                scale.into(),
+                x.into(),
+                y.into(),
+            ],
+        ));
+        layer.add_operation(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+        layer.restore_graphics_state();
+    }
+
+    /// Applies the imported pages in use to `doc`, copying each page and its resources into a
+    /// form XObject that is added to the resources of every page.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut docs: HashMap<&Path, Document> = HashMap::new();
+        let mut xobjects = Dictionary::new();
+        for id in self.used.borrow().iter().copied() {
+            let Some(import) = self.get(id) else {
+                continue;
+            };
+
+            if !docs.contains_key(import.path.as_path()) {
+                docs.insert(&import.path, load(&import.path)?);
+            }
+            let src = &docs[import.path.as_path()];
+            let page_id = page_id(src, &import.path, import.page)?;
+
+            // Resources are either within the page or inherited from its ancestors
+            let (resources, ancestors) = src.get_page_resources(page_id);
+            let resources = match resources {
+                Some(x) => Object::Dictionary(x.clone()),
+                None => ancestors
+                    .first()
+                    .map(|id| Object::Reference(*id))
+                    .unwrap_or_else(|| Object::Dictionary(Dictionary::new())),
+            };
+
+            let mut ids = HashMap::new();
+            let resources = copy_object(doc, src, &resources, &mut ids);
+            let form = Stream::new(
+                Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"XObject".to_vec())),
+                    ("Subtype", Object::Name(b"Form".to_vec())),
+                    (
+                        "BBox",
+                        Object::Array(import.bbox.into_iter().map(Object::Real).collect()),
+                    ),
+                    ("Resources", resources),
+                ]),
+                src.get_page_content(page_id)?,
+            );
+            xobjects.set(
+                format!("{PREFIX}{id}"),
+                Object::Reference(doc.add_object(form)),
+            );
+        }
+
+        add_xobjects_to_pages(doc, &xobjects)
+    }
+}
+
+/// Loads the PDF at `path`.
+fn load(path: &Path) -> anyhow::Result<Document> {
+    Document::load(path).with_context(|| format!("Failed to load {}", path.display()))
+}
+
+/// Returns the id of page `page` (starting at 1) of `doc`, failing if the page does not exist.
+fn page_id(doc: &Document, path: &Path, page: u32) -> anyhow::Result<ObjectId> {
+    doc.get_pages().get(&page).copied().with_context(|| {
+        format!(
+            "Page {page} does not exist in {}, which has {} pages",
+            path.display(),
+            doc.get_pages().len()
+        )
+    })
+}
+
+/// Returns the visible area of the page `id` as `[llx, lly, urx, ury]` in points, which is its
+/// crop box or (without one) its media box, either of which can be inherited from its ancestors.
+fn page_box(doc: &Document, id: ObjectId) -> anyhow::Result<[f32; 4]> {
+    let mut node_id = Some(id);
+    while let Some(id) = node_id {
+        let node = doc.get_dictionary(id)?;
+        if let Ok(bbox) = node
+            .get(b"CropBox")
+            .or_else(|_| node.get(b"MediaBox"))
+            .and_then(Object::as_array)
+        {
+            let mut coords = [0.0; 4];
+            for (coord, obj) in coords.iter_mut().zip(bbox.iter()) {
+                *coord = obj.as_float()?;
+            }
+            return Ok(coords);
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    anyhow::bail!("Page is missing a media box")
+}
+
+/// Copies `obj` from `src` into `dst`, copying every object it references (recursively) and
+/// returning the copy. `ids` maps the ids of objects in `src` to their copies in `dst`.
+///
+/// References to parents are dropped, which would otherwise copy the entire page tree of `src`.
+fn copy_object(
+    dst: &mut Document,
+    src: &Document,
+    obj: &Object,
+    ids: &mut HashMap<ObjectId, ObjectId>,
+) -> Object {
+    let mut copy_dict = |dict: &Dictionary, ids: &mut HashMap<ObjectId, ObjectId>| {
+        Dictionary::from_iter(
+            dict.iter()
+                .filter(|(key, _)| key.as_slice() != b"Parent")
+                .map(|(key, value)| (key.clone(), copy_object(dst, src, value, ids)))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    match obj {
+        Object::Reference(id) => {
+            if let Some(new_id) = ids.get(id) {
+                return Object::Reference(*new_id);
+            }
+
+            // Reserve the id before copying so objects that reference each other terminate
+            let new_id = dst.new_object_id();
+            ids.insert(*id, new_id);
+            let copy = match src.get_object(*id) {
+                Ok(obj) => copy_object(dst, src, obj, ids),
+                Err(_) => Object::Null,
+            };
+            dst.objects.insert(new_id, copy);
+            Object::Reference(new_id)
+        }
+        Object::Array(x) => {
+            Object::Array(x.iter().map(|x| copy_object(dst, src, x, ids)).collect())
+        }
+        Object::Dictionary(x) => Object::Dictionary(copy_dict(x, ids)),
+        Object::Stream(x) => {
+            Object::Stream(Stream::new(copy_dict(&x.dict, ids), x.content.clone()))
+        }
+        _ => obj.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::{PdfDocument, Rect};
+
+    #[test]
+    fn apply_should_copy_imported_pages_into_forms_used_by_pages() {
+        // Source PDF whose second page is imported
+        let path =
+            std::env::temp_dir().join(format!("makepdf-import-{}.pdf", rand::random::<u32>()));
+        let src = PdfDocument::empty("src");
+        for size in [100.0, 50.0] {
+            let (page, layer) = src.add_page(Mm(size), Mm(size * 2.0), "page");
+            let layer = src.get_page(page).get_layer(layer);
+            layer.add_rect(Rect::new(Mm(0.0), Mm(0.0), Mm(10.0), Mm(10.0)));
+        }
+        std::fs::write(&path, src.save_to_bytes().unwrap()).unwrap();
+
+        let mut imports = RuntimeImports::new();
+        let id = imports.add(&path, 2).unwrap();
+        assert_eq!(imports.add(&path, 2).unwrap(), id);
+        imports
+            .add(&path, 3)
+            .expect_err("Unexpectedly imported missing page");
+
+        let (width, height) = imports.get(id).unwrap().size();
+        assert!((width.0 - 50.0).abs() < 0.01);
+        assert!((height.0 - 100.0).abs() < 0.01);
+
+        // Nothing is applied until the imported page is used
+        assert!(imports.is_empty());
+        assert_eq!(imports.form_name(id), "Import0");
+        assert!(!imports.is_empty());
+
+        let dst = PdfDocument::empty("dst");
+        dst.add_page(Mm(100.0), Mm(100.0), "page");
+        let mut doc = Document::load_mem(&dst.save_to_bytes().unwrap()).unwrap();
+        imports.apply(&mut doc).unwrap();
+
+        let pages = doc.get_pages();
+        let (resources, ids) = doc.get_page_resources(pages[&1]);
+
+        // printpdf references the resources of pages rather than inlining them
+        let form_id = resources
+            .or_else(|| doc.get_dictionary(ids[0]).ok())
+            .unwrap()
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"Import0"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let form = doc.get_object(form_id).and_then(Object::as_stream).unwrap();
+        assert_eq!(
+            form.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+            b"Form"
+        );
+        assert!(!form.content.is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use crate::pdf::PdfImposition;
+use printpdf::lopdf::content::{Content, Operation};
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use printpdf::Pt;
+
+/// Places the pages of a document onto larger sheets of paper for printing, two pages side by
+/// side on each (landscape) side of a sheet.
+///
+/// Each page is converted into a form XObject that is scaled to fit its half of the sheet. Links,
+/// bookmarks, and page labels refer to the original pages, so they are removed.
+#[derive(Copy, Clone, Debug)]
+pub struct RuntimeImposition(PdfImposition);
+
+impl From<PdfImposition> for RuntimeImposition {
+    fn from(imposition: PdfImposition) -> Self {
+        Self(imposition)
+    }
+}
+
+impl RuntimeImposition {
+    /// Applies the imposition to `doc`, replacing its pages with the sides of the sheets.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        if pages.is_empty() {
+            return Ok(());
+        }
+
+        // Convert each page into a form clipped to the visible area of the page
+        let mut forms = Vec::new();
+        for page_id in pages {
+            let content = doc.get_page_content(page_id)?;
+            let page = doc.get_dictionary(page_id)?;
+            let bbox = page_box(page)?;
+            let resources = page
+                .get(b"Resources")
+                .cloned()
+                .unwrap_or_else(|_| Object::Dictionary(Dictionary::new()));
+
+            let form = Stream::new(
+                Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"XObject".to_vec())),
+                    ("Subtype", Object::Name(b"Form".to_vec())),
+                    (
+                        "BBox",
+                        Object::Array(bbox.into_iter().map(Object::Real).collect()),
+                    ),
+                    ("Resources", resources),
+                ]),
+                content,
+            );
+            forms.push((doc.add_object(form), bbox));
+        }
+
+        // Sheets are turned sideways so that two pages fit side by side
+        let (width, height) = (Pt::from(self.0.width).0, Pt::from(self.0.height).0);
+        let (width, height) = (width.max(height), width.min(height));
+        let slot_width = width / 2.0;
+
+        let pages_id = doc.catalog()?.get(b"Pages")?.as_reference()?;
+        let mut kids = Vec::new();
+        for side in self.0.layout.order(forms.len()) {
+            let mut xobjects = Dictionary::new();
+            let mut operations = Vec::new();
+            for (slot, idx) in side.into_iter().enumerate() {
+                let Some((form_id, [llx, lly, urx, ury])) = idx.map(|i| forms[i]) else {
+                    continue;
+                };
+
+                // Scale the page to fit its half of the sheet, centered within it
+                let (w, h) = (urx - llx, ury - lly);
+                let scale = (slot_width / w).min(height / h);
+                let x = slot_width * slot as f32 + (slot_width - w * scale) / 2.0 - llx * scale;
+                let y = (height - h * scale) / 2.0 - lly * scale;
+
+                let name = format!("Page{slot}");
+                xobjects.set(name.clone(), Object::Reference(form_id));
+                operations.push(Operation::new("q", vec![]));
+                operations.push(Operation::new(
+                    "cm",
+                    vec![
+                        scale.into(),
+                        0.into(),
+                        0.into(),
+                        scale.into(),
+                        x.into(),
+                        y.into(),
+                    ],
+                ));
+                operations.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+                operations.push(Operation::new("Q", vec![]));
+            }
+
+            let content = Content { operations }.encode()?;
+            let content_id = doc.add_object(Stream::new(Dictionary::new(), content));
+            let page_id = doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Page".to_vec())),
+                ("Parent", Object::Reference(pages_id)),
+                (
+                    "MediaBox",
+                    Object::Array(vec![0.into(), 0.into(), width.into(), height.into()]),
+                ),
+                ("Contents", Object::Reference(content_id)),
+                (
+                    "Resources",
+                    Object::Dictionary(Dictionary::from_iter(vec![(
+                        "XObject",
+                        Object::Dictionary(xobjects),
+                    )])),
+                ),
+            ]));
+            kids.push(Object::Reference(page_id));
+        }
+
+        let count = kids.len() as i64;
+        let pages = doc.get_dictionary_mut(pages_id)?;
+        pages.set("Kids", kids);
+        pages.set("Count", count);
+
+        // Bookmarks and page labels refer to the original pages, which are now unused
+        let catalog = doc.catalog_mut()?;
+        catalog.remove(b"Outlines");
+        catalog.remove(b"PageLabels");
+        doc.prune_objects();
+
+        Ok(())
+    }
+}
+
+/// Returns the visible area of `page` as `[llx, lly, urx, ury]` in points, which is its crop box
+/// or (without one) its media box.
+fn page_box(page: &Dictionary) -> anyhow::Result<[f32; 4]> {
+    let bbox = page
+        .get(b"CropBox")
+        .or_else(|_| page.get(b"MediaBox"))?
+        .as_array()?;
+
+    let mut coords = [0.0; 4];
+    for (coord, obj) in coords.iter_mut().zip(bbox.iter()) {
+        *coord = obj.as_float()?;
+    }
+
+    Ok(coords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::PdfImpositionLayout;
+    use printpdf::Mm;
+
+    #[test]
+    fn apply_should_place_pages_side_by_side_on_sheets() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let mut kids = Vec::new();
+        for _ in 0..3 {
+            let contents_id = doc.add_object(Stream::new(Dictionary::new(), b"0 0 m".to_vec()));
+            kids.push(Object::Reference(doc.add_object(Dictionary::from_iter(
+                vec![
+                    ("Type", Object::Name(b"Page".to_vec())),
+                    ("Parent", Object::Reference(pages_id)),
+                    (
+                        "MediaBox",
+                        Object::Array(vec![0.into(), 0.into(), 100.into(), 200.into()]),
+                    ),
+                    ("Contents", Object::Reference(contents_id)),
+                ],
+            ))));
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(3)),
+                ("Kids", Object::Array(kids)),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        // Sheet is 400x200 points when turned sideways, fitting each page at full size
+        RuntimeImposition::from(PdfImposition {
+            layout: PdfImpositionLayout::TwoUp,
+            width: Pt(200.0).into(),
+            height: Mm::from(Pt(400.0)),
+        })
+        .apply(&mut doc)
+        .unwrap();
+
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+
+        let sheet = doc.get_dictionary(pages[&1]).unwrap();
+        let media_box: Vec<f32> = sheet
+            .get(b"MediaBox")
+            .and_then(Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|x| x.as_float().unwrap())
+            .collect();
+        assert_eq!(media_box.len(), 4);
+        assert!((media_box[2] - 400.0).abs() < 0.01);
+        assert!((media_box[3] - 200.0).abs() < 0.01);
+
+        // Pages are centered within their halves, the second page moved into the right half
+        let content = Content::decode(&doc.get_page_content(pages[&1]).unwrap()).unwrap();
+        let offsets: Vec<f32> = content
+            .operations
+            .iter()
+            .filter(|x| x.operator == "cm")
+            .map(|x| x.operands[4].as_float().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), 2);
+        assert!((offsets[0] - 50.0).abs() < 0.01);
+        assert!((offsets[1] - 250.0).abs() < 0.01);
+
+        // Last sheet only holds the third page
+        let (resources, _) = doc.get_page_resources(pages[&2]);
+        let xobjects = resources
+            .unwrap()
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .unwrap();
+        assert_eq!(xobjects.len(), 1);
+    }
+}
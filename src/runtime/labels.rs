@@ -0,0 +1,134 @@
+use crate::pdf::PdfPageLabel;
+use printpdf::lopdf::{Dictionary, Document, Object, StringFormat};
+
+/// Manages the labels of the pages of a document, which viewers show in place of page numbers.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeLabels {
+    labels: Vec<PdfPageLabel>,
+}
+
+impl From<Vec<PdfPageLabel>> for RuntimeLabels {
+    fn from(labels: Vec<PdfPageLabel>) -> Self {
+        Self { labels }
+    }
+}
+
+impl RuntimeLabels {
+    /// Creates a new, empty collection of labels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if there are no labels.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Applies the labels to `doc`, replacing the page labels of the catalog.
+    ///
+    /// PDFs require a label for the first page, so pages before the first range are numbered
+    /// with arabic numerals.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        if self.labels.is_empty() {
+            return Ok(());
+        }
+
+        let mut labels = self.labels.clone();
+        labels.sort_by_key(|label| label.start);
+        if labels.first().map(|label| label.start) != Some(1) {
+            labels.insert(
+                0,
+                PdfPageLabel {
+                    start: 1,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let mut nums = Vec::new();
+        for label in labels {
+            let mut dict = Dictionary::new();
+            if let Some(name) = label.style.to_pdf_name() {
+                dict.set("S", Object::Name(name.as_bytes().to_vec()));
+            }
+            if let Some(prefix) = label.prefix {
+                dict.set(
+                    "P",
+                    Object::String(prefix.into_bytes(), StringFormat::Literal),
+                );
+            }
+            if let Some(first) = label.first {
+                dict.set("St", first as i64);
+            }
+
+            // Page indexes start at 0 within the PDF
+            nums.push(Object::Integer(label.start as i64 - 1));
+            nums.push(Object::Dictionary(dict));
+        }
+
+        let catalog = doc.catalog_mut()?;
+        catalog.set(
+            "PageLabels",
+            Dictionary::from_iter(vec![("Nums", Object::Array(nums))]),
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::PdfPageLabelStyle;
+
+    #[test]
+    fn apply_should_add_page_labels_to_catalog_sorted_by_start() {
+        let mut doc = Document::with_version("1.7");
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![(
+            "Type",
+            Object::Name(b"Catalog".to_vec()),
+        )]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        let labels = RuntimeLabels::from(vec![
+            PdfPageLabel {
+                start: 5,
+                prefix: Some(String::from("A-")),
+                first: Some(3),
+                ..Default::default()
+            },
+            PdfPageLabel {
+                start: 2,
+                style: PdfPageLabelStyle::Roman,
+                ..Default::default()
+            },
+        ]);
+        labels.apply(&mut doc).unwrap();
+
+        let nums = doc
+            .catalog()
+            .unwrap()
+            .get(b"PageLabels")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"Nums"))
+            .and_then(Object::as_array)
+            .unwrap();
+        let starts: Vec<i64> = nums
+            .iter()
+            .step_by(2)
+            .map(|x| x.as_i64().unwrap())
+            .collect();
+        assert_eq!(starts, vec![0, 1, 4]);
+
+        // First page is numbered with arabic numerals as it has no label
+        let first = nums[1].as_dict().unwrap();
+        assert_eq!(first.get(b"S").unwrap().as_name().unwrap(), b"D");
+
+        let roman = nums[3].as_dict().unwrap();
+        assert_eq!(roman.get(b"S").unwrap().as_name().unwrap(), b"r");
+
+        let prefixed = nums[5].as_dict().unwrap();
+        assert_eq!(prefixed.get(b"P").unwrap().as_str().unwrap(), b"A-");
+        assert_eq!(prefixed.get(b"St").unwrap().as_i64().unwrap(), 3);
+    }
+}
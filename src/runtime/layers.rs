@@ -0,0 +1,285 @@
+use crate::pdf::PdfLayer;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::{HashMap, HashSet};
+
+/// Manages the layers of a document, which become optional content groups within the PDF.
+///
+/// Every layer of every page is written as its own optional content group, so this merges the
+/// groups of same-named layers across pages into one that viewers can toggle for the entire
+/// document, and only lists optional layers within the viewer.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeLayers {
+    layers: Vec<PdfLayer>,
+}
+
+impl From<Vec<PdfLayer>> for RuntimeLayers {
+    fn from(layers: Vec<PdfLayer>) -> Self {
+        Self { layers }
+    }
+}
+
+impl RuntimeLayers {
+    /// Creates a new, empty collection of layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if there are no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Applies the layers to `doc`, updating the optional content of the catalog.
+    ///
+    /// 1. The default layer of each page (the first layer) is left as-is.
+    /// 2. Other layers sharing a name are merged into a single optional content group.
+    /// 3. Optional layers are listed by viewers, with hidden layers starting off.
+    /// 4. All other layers are locked on and not listed.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        // Map each named layer to the first group with that name, replacing the others
+        let mut groups: HashMap<Vec<u8>, ObjectId> = HashMap::new();
+        let mut replaced: HashSet<ObjectId> = HashSet::new();
+        for (_, page_id) in doc.get_pages() {
+            let resources_id = match doc
+                .get_dictionary(page_id)?
+                .get(b"Resources")
+                .and_then(Object::as_reference)
+            {
+                Ok(id) => id,
+                Err(_) => continue,
+            };
+
+            let mut properties = match doc
+                .get_dictionary(resources_id)?
+                .get(b"Properties")
+                .and_then(Object::as_dict)
+            {
+                Ok(dict) => dict.clone(),
+                Err(_) => continue,
+            };
+
+            for (key, value) in properties.iter_mut() {
+                // First layer of a page is its default layer
+                if key == b"MC0" {
+                    continue;
+                }
+
+                let id = value.as_reference()?;
+                let name = doc.get_dictionary(id)?.get(b"Name")?.as_str()?.to_vec();
+                match groups.get(&name) {
+                    Some(group_id) if *group_id != id => {
+                        *value = Object::Reference(*group_id);
+                        replaced.insert(id);
+                    }
+                    Some(_) => {}
+                    None => {
+                        groups.insert(name, id);
+                    }
+                }
+            }
+
+            doc.get_dictionary_mut(resources_id)?
+                .set("Properties", properties);
+        }
+
+        for id in replaced.iter() {
+            doc.objects.remove(id);
+        }
+
+        // Collect the remaining groups, splitting them by how they are presented
+        let catalog = doc.catalog()?;
+        let all: Vec<ObjectId> = match catalog
+            .get(b"OCProperties")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"OCGs"))
+            .and_then(Object::as_array)
+        {
+            Ok(ocgs) => ocgs
+                .iter()
+                .filter_map(|x| x.as_reference().ok())
+                .filter(|id| !replaced.contains(id))
+                .collect(),
+            Err(_) => return Ok(()),
+        };
+
+        let group_of = |layer: &PdfLayer| groups.get(layer.name.as_bytes()).copied();
+        let order: Vec<ObjectId> = self
+            .layers
+            .iter()
+            .filter(|x| x.optional)
+            .filter_map(group_of)
+            .collect();
+        let off: Vec<ObjectId> = self
+            .layers
+            .iter()
+            .filter(|x| x.is_hidden())
+            .filter_map(group_of)
+            .collect();
+        let on: Vec<ObjectId> = all.iter().copied().filter(|x| !off.contains(x)).collect();
+        let locked: Vec<ObjectId> = all.iter().copied().filter(|x| !order.contains(x)).collect();
+
+        let refs = |ids: Vec<ObjectId>| Object::Array(ids.into_iter().map(Object::from).collect());
+        let mut config = Dictionary::new();
+        config.set("Order", refs(order));
+        config.set("ON", refs(on));
+        config.set("OFF", refs(off));
+        config.set("Locked", refs(locked));
+        config.set("RBGroups", Object::Array(Vec::new()));
+
+        let mut properties = Dictionary::new();
+        properties.set("OCGs", refs(all));
+        properties.set("D", config);
+        doc.catalog_mut()?.set("OCProperties", properties);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf::StringFormat;
+
+    /// Creates a document of pages whose layers (past the default layer) are named by `pages`.
+    fn doc(pages: &[&[&str]]) -> Document {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let mut ocgs = Vec::new();
+        let mut kids = Vec::new();
+
+        for (i, names) in pages.iter().enumerate() {
+            let mut properties = Dictionary::new();
+            let default = format!("Page {i}");
+            for (j, name) in std::iter::once(default.as_str())
+                .chain(names.iter().copied())
+                .enumerate()
+            {
+                let id = doc.add_object(Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"OCG".to_vec())),
+                    (
+                        "Name",
+                        Object::String(name.as_bytes().to_vec(), StringFormat::Literal),
+                    ),
+                ]));
+                properties.set(format!("MC{j}"), Object::Reference(id));
+                ocgs.push(Object::Reference(id));
+            }
+
+            let resources_id = doc.add_object(Dictionary::from_iter(vec![(
+                "Properties",
+                Object::Dictionary(properties),
+            )]));
+            kids.push(Object::Reference(doc.add_object(Dictionary::from_iter(
+                vec![
+                    ("Type", Object::Name(b"Page".to_vec())),
+                    ("Parent", Object::Reference(pages_id)),
+                    ("Resources", Object::Reference(resources_id)),
+                ],
+            ))));
+        }
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(kids.len() as i64)),
+                ("Kids", Object::Array(kids)),
+            ])),
+        );
+
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+            (
+                "OCProperties",
+                Object::Dictionary(Dictionary::from_iter(vec![("OCGs", Object::Array(ocgs))])),
+            ),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+        doc
+    }
+
+    /// Returns the names of the groups within `key` of the default optional content config.
+    fn names(doc: &Document, key: &[u8]) -> Vec<String> {
+        let properties = doc
+            .catalog()
+            .unwrap()
+            .get(b"OCProperties")
+            .and_then(Object::as_dict)
+            .unwrap();
+        let array = match key {
+            b"OCGs" => properties.get(key),
+            _ => properties
+                .get(b"D")
+                .and_then(Object::as_dict)
+                .and_then(|x| x.get(key)),
+        }
+        .and_then(Object::as_array)
+        .unwrap();
+
+        array
+            .iter()
+            .map(|x| {
+                let dict = doc.get_dictionary(x.as_reference().unwrap()).unwrap();
+                String::from_utf8_lossy(dict.get(b"Name").unwrap().as_str().unwrap()).to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn apply_should_merge_layers_with_the_same_name_across_pages() {
+        let mut doc = doc(&[&["grid", "guides"], &["grid"]]);
+        let layers = RuntimeLayers::from(vec![
+            PdfLayer {
+                name: String::from("grid"),
+                optional: true,
+                visible: true,
+            },
+            PdfLayer {
+                name: String::from("guides"),
+                optional: true,
+                visible: false,
+            },
+        ]);
+        layers.apply(&mut doc).unwrap();
+
+        assert_eq!(names(&doc, b"OCGs"), ["Page 0", "grid", "guides", "Page 1"]);
+        assert_eq!(names(&doc, b"Order"), ["grid", "guides"]);
+        assert_eq!(names(&doc, b"ON"), ["Page 0", "grid", "Page 1"]);
+        assert_eq!(names(&doc, b"OFF"), ["guides"]);
+        assert_eq!(names(&doc, b"Locked"), ["Page 0", "Page 1"]);
+
+        // Both pages should reference the same group for the grid
+        let grids: Vec<ObjectId> = doc
+            .get_pages()
+            .into_values()
+            .map(|id| {
+                let resources = doc
+                    .get_dictionary(id)
+                    .unwrap()
+                    .get(b"Resources")
+                    .and_then(Object::as_reference)
+                    .unwrap();
+                doc.get_dictionary(resources)
+                    .unwrap()
+                    .get(b"Properties")
+                    .and_then(Object::as_dict)
+                    .and_then(|x| x.get(b"MC1"))
+                    .and_then(Object::as_reference)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(grids[0], grids[1]);
+    }
+
+    #[test]
+    fn apply_should_lock_layers_that_are_not_optional() {
+        let mut doc = doc(&[&["background"]]);
+        let layers = RuntimeLayers::from(vec![PdfLayer::new("background")]);
+        layers.apply(&mut doc).unwrap();
+
+        assert!(names(&doc, b"Order").is_empty());
+        assert!(names(&doc, b"OFF").is_empty());
+        assert_eq!(names(&doc, b"Locked"), ["Page 0", "background"]);
+    }
+}
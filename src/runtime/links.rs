@@ -0,0 +1,181 @@
+use crate::pdf::PdfBounds;
+use printpdf::lopdf::{Dictionary, Document, Object};
+use printpdf::Pt;
+
+/// Maximum difference in points between the rect of an annotation and the bounds of a link for
+/// them to be considered the same, as rects lose precision when the document is reloaded.
+const EPSILON: f32 = 0.01;
+
+/// Destination of a link that is beyond what printpdf supports.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct RuntimeLinkDestination {
+    /// Number of the page (starting at 1) containing the link.
+    page: u32,
+    /// Bounds of the clickable area of the link.
+    bounds: PdfBounds,
+    /// Number of the page (starting at 1) that the link goes to.
+    target: u32,
+    /// Area of the target page to fit within the view.
+    rect: PdfBounds,
+}
+
+/// Manages links that go to an area of a page, which are fixed up once the document is saved.
+///
+/// printpdf only supports destinations that scroll to a point on a page, so links are added with
+/// a destination of the top of their page and replaced with the area of the page to fit within
+/// the view (`/FitR`) when saved.
+#[derive(Debug, Default)]
+pub struct RuntimeLinks {
+    destinations: Vec<RuntimeLinkDestination>,
+}
+
+impl RuntimeLinks {
+    /// Creates a new instance without any links.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if there are no links to fix up.
+    pub fn is_empty(&self) -> bool {
+        self.destinations.is_empty()
+    }
+
+    /// Adds a link on page `page` within `bounds` that goes to `rect` on page `target`, where
+    /// pages are numbered starting at 1.
+    pub fn add(&mut self, page: u32, bounds: PdfBounds, target: u32, rect: PdfBounds) {
+        self.destinations.push(RuntimeLinkDestination {
+            page,
+            bounds,
+            target,
+            rect,
+        });
+    }
+
+    /// Applies the links to `doc`, replacing the destination of each matching annotation.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        let pages = doc.get_pages();
+        for dest in self.destinations.iter() {
+            let (page_id, target_id) = match (pages.get(&dest.page), pages.get(&dest.target)) {
+                (Some(page_id), Some(target_id)) => (page_id, target_id),
+                _ => continue,
+            };
+
+            let annots: Vec<_> = doc
+                .get_dictionary(*page_id)?
+                .get(b"Annots")
+                .and_then(Object::as_array)
+                .map(|x| x.iter().filter_map(|x| x.as_reference().ok()).collect())
+                .unwrap_or_default();
+
+            let to_pt = |bounds: PdfBounds| {
+                [bounds.ll.x, bounds.ll.y, bounds.ur.x, bounds.ur.y].map(|x| Pt::from(x).0)
+            };
+            let bounds = to_pt(dest.bounds);
+            for id in annots {
+                let annot = doc.get_dictionary_mut(id)?;
+                let rect: Vec<f32> = annot
+                    .get(b"Rect")
+                    .and_then(Object::as_array)
+                    .map(|x| x.iter().filter_map(|x| x.as_float().ok()).collect())
+                    .unwrap_or_default();
+                let matches = rect.len() == 4
+                    && rect
+                        .iter()
+                        .zip(bounds.iter())
+                        .all(|(a, b)| (a - b).abs() < EPSILON);
+                if !matches {
+                    continue;
+                }
+
+                let mut d = vec![
+                    Object::Reference(*target_id),
+                    Object::Name(b"FitR".to_vec()),
+                ];
+                d.extend(to_pt(dest.rect).map(Object::Real));
+                annot.set(
+                    "A",
+                    Dictionary::from_iter(vec![
+                        ("S", Object::Name(b"GoTo".to_vec())),
+                        ("D", Object::Array(d)),
+                    ]),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_replace_destination_of_matching_annotations() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let rect = |x: f32| {
+            Object::Array(
+                [x, x, x + 10.0, x + 10.0]
+                    .into_iter()
+                    .map(|x| Object::Real(Pt::from(printpdf::Mm(x)).0))
+                    .collect(),
+            )
+        };
+        let annot_ids: Vec<_> = [1.0, 20.0]
+            .into_iter()
+            .map(|x| {
+                doc.add_object(Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"Annot".to_vec())),
+                    ("Subtype", Object::Name(b"Link".to_vec())),
+                    ("Rect", rect(x)),
+                ]))
+            })
+            .collect();
+        let page_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Page".to_vec())),
+            ("Parent", Object::Reference(pages_id)),
+            (
+                "Annots",
+                Object::Array(annot_ids.iter().copied().map(Object::Reference).collect()),
+            ),
+        ]));
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(1)),
+                ("Kids", Object::Array(vec![Object::Reference(page_id)])),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        // Only the second annotation matches the link
+        let mut links = RuntimeLinks::new();
+        links.add(
+            1,
+            PdfBounds::from_coords_f32(20.0, 20.0, 30.0, 30.0),
+            1,
+            PdfBounds::from_coords_f32(0.0, 0.0, 50.0, 25.0),
+        );
+        links.apply(&mut doc).unwrap();
+
+        let first = doc.get_dictionary(annot_ids[0]).unwrap();
+        assert!(!first.has(b"A"));
+
+        let second = doc.get_dictionary(annot_ids[1]).unwrap();
+        let d = second
+            .get(b"A")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"D"))
+            .and_then(Object::as_array)
+            .unwrap();
+        assert_eq!(d[0].as_reference().unwrap(), page_id);
+        assert_eq!(d[1].as_name().unwrap(), b"FitR");
+        assert_eq!(d.len(), 6);
+    }
+}
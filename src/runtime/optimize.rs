@@ -0,0 +1,201 @@
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashMap;
+
+/// Types of dictionaries that are never merged as they are expected to be unique within the
+/// document, even when identical (e.g. two blank pages).
+const UNIQUE_TYPES: [&[u8]; 5] = [b"Catalog", b"Pages", b"Page", b"Annot", b"Outlines"];
+
+/// Optimizes the size of a document when it is saved.
+///
+/// Planners often have many pages that are nearly identical, which printpdf writes out as
+/// separate copies of the same content streams, resources, and other objects.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RuntimeOptimize;
+
+impl RuntimeOptimize {
+    /// Optimizes `doc`, returning the number of duplicate objects that were merged.
+    ///
+    /// 1. Identical streams and dictionaries are merged into one, repeating until nothing changes
+    ///    as merging objects can make the objects referencing them identical.
+    /// 2. Objects that are no longer referenced, along with empty streams, are removed.
+    /// 3. Streams are compressed.
+    pub fn apply(doc: &mut Document) -> usize {
+        let mut merged = 0;
+
+        loop {
+            let mut originals: HashMap<Vec<u8>, ObjectId> = HashMap::new();
+            let mut duplicates: HashMap<ObjectId, ObjectId> = HashMap::new();
+            for (id, obj) in doc.objects.iter() {
+                let key = match obj {
+                    Object::Stream(x) if !is_unique(&x.dict) => key(obj),
+                    Object::Dictionary(x) if !is_unique(x) => key(obj),
+                    _ => continue,
+                };
+
+                match originals.get(&key) {
+                    Some(original) => {
+                        duplicates.insert(*id, *original);
+                    }
+                    None => {
+                        originals.insert(key, *id);
+                    }
+                }
+            }
+
+            if duplicates.is_empty() {
+                break;
+            }
+
+            // Point everything at the original objects, which leaves the duplicates unreferenced
+            merged += duplicates.len();
+            doc.traverse_objects(|obj| {
+                if let Object::Reference(id) = obj {
+                    if let Some(original) = duplicates.get(id) {
+                        *id = *original;
+                    }
+                }
+            });
+            for id in duplicates.keys() {
+                doc.objects.remove(id);
+            }
+        }
+
+        doc.prune_objects();
+        doc.delete_zero_length_streams();
+        doc.compress();
+
+        merged
+    }
+}
+
+/// Returns true if `dict` is of a type that is never merged.
+fn is_unique(dict: &Dictionary) -> bool {
+    dict.get(b"Type")
+        .and_then(Object::as_name)
+        .map(|ty| UNIQUE_TYPES.contains(&ty))
+        .unwrap_or(false)
+}
+
+/// Returns bytes that uniquely identify the content of `obj`.
+fn key(obj: &Object) -> Vec<u8> {
+    fn write(obj: &Object, out: &mut Vec<u8>) {
+        match obj {
+            Object::Null => out.push(b'n'),
+            Object::Boolean(x) => out.extend([b'b', *x as u8]),
+            Object::Integer(x) => {
+                out.push(b'i');
+                out.extend(x.to_le_bytes());
+            }
+            Object::Real(x) => {
+                out.push(b'r');
+                out.extend(x.to_le_bytes());
+            }
+            Object::Name(x) => {
+                out.push(b'/');
+                out.extend((x.len() as u64).to_le_bytes());
+                out.extend(x);
+            }
+            Object::String(x, _) => {
+                out.push(b's');
+                out.extend((x.len() as u64).to_le_bytes());
+                out.extend(x);
+            }
+            Object::Array(x) => {
+                out.push(b'[');
+                out.extend((x.len() as u64).to_le_bytes());
+                for obj in x {
+                    write(obj, out);
+                }
+            }
+            Object::Dictionary(x) => {
+                out.push(b'<');
+                out.extend((x.len() as u64).to_le_bytes());
+                for (key, obj) in x.iter() {
+                    write(&Object::Name(key.clone()), out);
+                    write(obj, out);
+                }
+            }
+            Object::Stream(x) => {
+                out.push(b'S');
+                write(&Object::Dictionary(x.dict.clone()), out);
+                out.extend((x.content.len() as u64).to_le_bytes());
+                out.extend(&x.content);
+            }
+            Object::Reference((id, gen)) => {
+                out.push(b'R');
+                out.extend(id.to_le_bytes());
+                out.extend(gen.to_le_bytes());
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    write(obj, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf::Stream;
+
+    #[test]
+    fn apply_should_merge_identical_objects_referenced_by_pages() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let mut kids = Vec::new();
+        for _ in 0..2 {
+            let font_id = doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Font".to_vec())),
+                ("BaseFont", Object::Name(b"Helvetica".to_vec())),
+            ]));
+            let resources_id = doc.add_object(Dictionary::from_iter(vec![(
+                "Font",
+                Object::Dictionary(Dictionary::from_iter(vec![(
+                    "F1",
+                    Object::Reference(font_id),
+                )])),
+            )]));
+            let contents_id = doc.add_object(Stream::new(
+                Dictionary::new(),
+                b"BT /F1 12 Tf (hello) Tj ET".to_vec(),
+            ));
+            kids.push(Object::Reference(doc.add_object(Dictionary::from_iter(
+                vec![
+                    ("Type", Object::Name(b"Page".to_vec())),
+                    ("Parent", Object::Reference(pages_id)),
+                    ("Resources", Object::Reference(resources_id)),
+                    ("Contents", Object::Reference(contents_id)),
+                ],
+            ))));
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(2)),
+                ("Kids", Object::Array(kids)),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        // Font, resources, and contents of the second page are merged into those of the first,
+        // where resources only become identical once the fonts are merged
+        assert_eq!(RuntimeOptimize::apply(&mut doc), 3);
+
+        // Catalog, pages, two pages, and one each of font, resources, and contents remain
+        assert_eq!(doc.objects.len(), 7);
+        assert_eq!(doc.get_pages().len(), 2);
+
+        let contents: Vec<_> = doc
+            .get_pages()
+            .into_values()
+            .map(|id| doc.get_page_contents(id))
+            .collect();
+        assert_eq!(contents[0], contents[1]);
+    }
+}
@@ -1,8 +1,15 @@
 mod page;
+mod reference;
+mod section;
 
 pub use page::{RuntimePage, RuntimePageId};
+pub use reference::RuntimePageRef;
+pub use section::RuntimeSection;
 
+use crate::pdf::{PdfPageLabel, PdfPageSide};
+use mlua::prelude::*;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// Manages a collection of pages.
 #[derive(Debug, Default)]
@@ -12,6 +19,29 @@ pub struct RuntimePages {
 
     /// Contains manual ordering of pages.
     ids: Vec<RuntimePageId>,
+
+    /// Sections of the document, in the order they began.
+    sections: Vec<RuntimeSection>,
+
+    /// Index of the section that new pages are added to, if any.
+    current_section: Option<usize>,
+
+    /// Labels of ranges of pages, shown by viewers in place of page numbers.
+    labels: Vec<PdfPageLabel>,
+
+    /// References to pages by placeholder id, in the order they were added.
+    refs: Vec<(RuntimePageId, RuntimePageRef)>,
+
+    /// Collection of placeholder id -> id of the page that the reference resolved to.
+    resolved: HashMap<RuntimePageId, RuntimePageId>,
+
+    /// Functions to draw onto pages once every page exists, alongside the id of the page to draw
+    /// onto or none for every page, in the order they were added.
+    hooks: Vec<(Option<RuntimePageId>, Rc<LuaRegistryKey>)>,
+
+    /// Functions pushed onto pages in place of objects, alongside the id of the page and the
+    /// number of objects pushed onto the page before them, in the order they were pushed.
+    lazy: Vec<(RuntimePageId, usize, Rc<LuaRegistryKey>)>,
 }
 
 impl<'a> IntoIterator for &'a RuntimePages {
@@ -66,15 +96,403 @@ impl RuntimePages {
     }
 
     /// Inserts a page by its `id`, adding it to the end of the list, returning the id of the page.
-    pub fn insert_page(&mut self, page: RuntimePage) -> RuntimePageId {
+    ///
+    /// If the page names its section, the page is added to the end of that section, which is
+    /// created (without becoming active) if it does not exist. Otherwise, if a section is active,
+    /// the page is added to the end of that section.
+    pub fn insert_page(&mut self, mut page: RuntimePage) -> RuntimePageId {
         let id = page.id;
+
+        // Pages are numbered in the order they are added, alternating sides of a spread
+        page.side = PdfPageSide::from_number(self.ids.len() + 1);
+
+        if let Some(name) = page.section.as_deref() {
+            match self
+                .sections
+                .iter_mut()
+                .find(|section| section.name == name)
+            {
+                Some(section) => section.pages.push(id),
+                None => {
+                    let mut section = RuntimeSection::new(name);
+                    section.pages.push(id);
+                    self.sections.push(section);
+                }
+            }
+        } else if let Some(section) = self.current_section.map(|i| &mut self.sections[i]) {
+            page.section = Some(section.name.clone());
+            section.pages.push(id);
+        }
+
         self.ids.push(id);
         self.pages.insert(id, page);
         id
     }
 
+    /// Returns the index of the page with `id` within the list, if it exists.
+    pub fn index_of(&self, id: RuntimePageId) -> Option<usize> {
+        self.ids.iter().position(|x| *x == id)
+    }
+
+    /// Moves the page with `id` so that it sits at `index` within the list, shifting the pages
+    /// after it. Fails if the page does not exist or `index` is beyond the end of the list.
+    pub fn move_page(&mut self, id: RuntimePageId, index: usize) -> Result<(), String> {
+        let Some(from) = self.index_of(id) else {
+            return Err(format!("Page {id} does not exist"));
+        };
+
+        if index >= self.ids.len() {
+            return Err(format!(
+                "Cannot move page to index {index} of {} pages",
+                self.ids.len()
+            ));
+        }
+
+        let id = self.ids.remove(from);
+        self.ids.insert(index, id);
+        self.reorder();
+        Ok(())
+    }
+
+    /// Inserts a page directly after the page with `after`, returning the id of the new page.
+    /// Fails if the page to insert after does not exist, leaving the list unchanged.
+    pub fn insert_page_after(
+        &mut self,
+        after: RuntimePageId,
+        page: RuntimePage,
+    ) -> Result<RuntimePageId, String> {
+        let Some(index) = self.index_of(after) else {
+            return Err(format!("Page {after} does not exist"));
+        };
+
+        let id = self.insert_page(page);
+        self.move_page(id, index + 1)?;
+        Ok(id)
+    }
+
+    /// Removes the page with `id` from the list and its section, returning the page if it existed.
+    pub fn remove_page(&mut self, id: RuntimePageId) -> Option<RuntimePage> {
+        let page = self.pages.remove(&id)?;
+        self.ids.retain(|x| *x != id);
+
+        for section in self.sections.iter_mut() {
+            section.pages.retain(|x| *x != id);
+            if section.divider == Some(id) {
+                section.divider = None;
+            }
+        }
+
+        self.reorder();
+        Some(page)
+    }
+
+    /// Updates pages after the order of the list changes, recomputing the side of each page and
+    /// sorting the pages of each section in the order they appear.
+    fn reorder(&mut self) {
+        for (i, id) in self.ids.iter().enumerate() {
+            if let Some(page) = self.pages.get_mut(id) {
+                page.side = PdfPageSide::from_number(i + 1);
+            }
+        }
+
+        let ids = &self.ids;
+        for section in self.sections.iter_mut() {
+            section
+                .pages
+                .sort_by_key(|id| ids.iter().position(|x| x == id));
+        }
+    }
+
+    /// Creates a new page titled `title`, adding it to the end of the list, returning the page so
+    /// that objects can be pushed onto it.
+    pub fn page(&mut self, title: impl Into<String>) -> RuntimePage {
+        let page = RuntimePage::new(title);
+        let id = self.insert_page(page);
+        self.pages[&id].clone()
+    }
+
+    /// Adds a reference to a page that may not exist yet, returning the placeholder id that links
+    /// can target until the reference is resolved.
+    pub fn add_ref(&mut self, page_ref: RuntimePageRef) -> RuntimePageId {
+        let mut id = rand::random();
+        while self.pages.contains_key(&id) || self.is_ref(id) {
+            id = rand::random();
+        }
+
+        self.refs.push((id, page_ref));
+        id
+    }
+
+    /// Returns true if `id` is the placeholder id of a page reference.
+    pub fn is_ref(&self, id: RuntimePageId) -> bool {
+        self.refs.iter().any(|(x, _)| *x == id)
+    }
+
+    /// Returns the references to pages alongside their placeholder ids, in the order they were
+    /// added.
+    pub fn refs(&self) -> &[(RuntimePageId, RuntimePageRef)] {
+        &self.refs
+    }
+
+    /// Marks the reference with placeholder `id` as resolved to the page with id `target`.
+    pub fn set_resolved(&mut self, id: RuntimePageId, target: RuntimePageId) {
+        self.resolved.insert(id, target);
+    }
+
+    /// Returns the placeholder ids of resolved references alongside the ids of the pages they
+    /// resolved to.
+    pub fn resolved(&self) -> impl Iterator<Item = (RuntimePageId, RuntimePageId)> + '_ {
+        self.resolved.iter().map(|(id, target)| (*id, *target))
+    }
+
+    /// Returns the id of the page that the reference with placeholder `id` resolved to, if any.
+    pub fn get_resolved(&self, id: RuntimePageId) -> Option<RuntimePageId> {
+        self.resolved.get(&id).copied()
+    }
+
+    /// Adds a function to draw onto the page with `id`, or every page if none, once every page
+    /// exists.
+    pub fn add_hook(&mut self, id: Option<RuntimePageId>, hook: Rc<LuaRegistryKey>) {
+        self.hooks.push((id, hook));
+    }
+
+    /// Returns the functions to draw onto pages, in the order they were added.
+    pub fn hooks(&self) -> &[(Option<RuntimePageId>, Rc<LuaRegistryKey>)] {
+        &self.hooks
+    }
+
+    /// Adds a function pushed onto the page with `id` in place of objects, after `index` objects
+    /// were pushed, to be evaluated into objects once the script finishes.
+    pub fn add_lazy(&mut self, id: RuntimePageId, index: usize, f: Rc<LuaRegistryKey>) {
+        self.lazy.push((id, index, f));
+    }
+
+    /// Returns the functions pushed onto pages in place of objects, in the order they were pushed.
+    pub fn lazy(&self) -> &[(RuntimePageId, usize, Rc<LuaRegistryKey>)] {
+        &self.lazy
+    }
+
+    /// Returns the ids of the pages that satisfy `f`, in order.
+    pub fn find(&self, f: impl Fn(&RuntimePage) -> bool) -> Vec<RuntimePageId> {
+        self.ids
+            .iter()
+            .copied()
+            .filter(|id| self.pages.get(id).is_some_and(&f))
+            .collect()
+    }
+
     /// Retrieves a copy of a page by its `id`.
     pub fn get_page(&self, id: RuntimePageId) -> Option<RuntimePage> {
         self.pages.get(&id).cloned()
     }
+
+    /// Begins a new `section`, which will contain all pages inserted until another section begins
+    /// or the section is ended. Fails if a section with the same name already exists.
+    pub fn begin_section(&mut self, section: RuntimeSection) -> Result<(), String> {
+        if self.get_section(&section.name).is_some() {
+            return Err(format!("Section '{}' already exists", section.name));
+        }
+
+        self.sections.push(section);
+        self.current_section = Some(self.sections.len() - 1);
+        Ok(())
+    }
+
+    /// Ends the active section, if any, so new pages are no longer added to a section.
+    pub fn end_section(&mut self) {
+        self.current_section = None;
+    }
+
+    /// Returns the active section, if any.
+    pub fn current_section(&self) -> Option<&RuntimeSection> {
+        self.current_section.map(|i| &self.sections[i])
+    }
+
+    /// Returns the active section as mutable, if any.
+    pub fn current_section_mut(&mut self) -> Option<&mut RuntimeSection> {
+        self.current_section.map(|i| &mut self.sections[i])
+    }
+
+    /// Retrieves a section by its `name`.
+    pub fn get_section(&self, name: &str) -> Option<&RuntimeSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns the sections, in the order they began.
+    pub fn sections(&self) -> &[RuntimeSection] {
+        &self.sections
+    }
+
+    /// Replaces the labels of ranges of pages. Fails if a range does not start at a page number
+    /// of at least 1 or more than one range starts at the same page.
+    pub fn set_labels(&mut self, labels: Vec<PdfPageLabel>) -> Result<(), String> {
+        for (i, label) in labels.iter().enumerate() {
+            if label.start == 0 {
+                return Err(String::from("Page labels must start at page 1 or later"));
+            }
+
+            if labels[..i].iter().any(|x| x.start == label.start) {
+                return Err(format!(
+                    "Multiple page labels start at page {}",
+                    label.start
+                ));
+            }
+        }
+
+        self.labels = labels;
+        Ok(())
+    }
+
+    /// Returns the labels of ranges of pages, in the order they were set.
+    pub fn labels(&self) -> &[PdfPageLabel] {
+        &self.labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_page_should_add_page_to_current_section() {
+        let mut pages = RuntimePages::new();
+        let before = pages.insert_page(RuntimePage::new("before"));
+
+        pages.begin_section(RuntimeSection::new("a")).unwrap();
+        let a1 = pages.insert_page(RuntimePage::new("a1"));
+        let a2 = pages.insert_page(RuntimePage::new("a2"));
+
+        pages.begin_section(RuntimeSection::new("b")).unwrap();
+        let b1 = pages.insert_page(RuntimePage::new("b1"));
+
+        pages.end_section();
+        let after = pages.insert_page(RuntimePage::new("after"));
+
+        assert_eq!(pages.get_section("a").unwrap().pages, vec![a1, a2]);
+        assert_eq!(pages.get_section("b").unwrap().pages, vec![b1]);
+        assert_eq!(pages.get_page(a2).unwrap().section.as_deref(), Some("a"));
+        assert_eq!(pages.get_page(before).unwrap().section, None);
+        assert_eq!(pages.get_page(after).unwrap().section, None);
+        assert!(pages.current_section().is_none());
+
+        // Section names must be unique
+        assert!(pages.begin_section(RuntimeSection::new("a")).is_err());
+    }
+
+    #[test]
+    fn insert_page_should_add_page_to_its_own_section() {
+        let mut pages = RuntimePages::new();
+        pages.begin_section(RuntimeSection::new("a")).unwrap();
+
+        let mut page = RuntimePage::new("b1");
+        page.section = Some(String::from("b"));
+        let b1 = pages.insert_page(page);
+        let a1 = pages.insert_page(RuntimePage::new("a1"));
+
+        // Named section is created without becoming active
+        assert_eq!(pages.get_section("a").unwrap().pages, vec![a1]);
+        assert_eq!(pages.get_section("b").unwrap().pages, vec![b1]);
+        assert_eq!(pages.current_section().unwrap().name, "a");
+        assert_eq!(
+            pages.find(|page| page.section.as_deref() == Some("b")),
+            vec![b1]
+        );
+    }
+
+    #[test]
+    fn insert_page_should_alternate_sides_starting_on_the_right() {
+        let mut pages = RuntimePages::new();
+        let ids: Vec<RuntimePageId> = (0..3)
+            .map(|_| pages.insert_page(RuntimePage::new("")))
+            .collect();
+        let sides: Vec<PdfPageSide> = ids
+            .into_iter()
+            .map(|id| pages.get_page(id).unwrap().side)
+            .collect();
+
+        assert_eq!(
+            sides,
+            vec![PdfPageSide::Right, PdfPageSide::Left, PdfPageSide::Right]
+        );
+    }
+
+    #[test]
+    fn move_page_should_reorder_pages_and_their_sections() {
+        let mut pages = RuntimePages::new();
+        pages.begin_section(RuntimeSection::new("a")).unwrap();
+        let a1 = pages.insert_page(RuntimePage::new("a1"));
+        let a2 = pages.insert_page(RuntimePage::new("a2"));
+        pages.end_section();
+        let index = pages.insert_page(RuntimePage::new("index"));
+
+        pages.move_page(index, 0).unwrap();
+        pages.move_page(a2, 1).unwrap();
+        assert_eq!(pages.ids().collect::<Vec<_>>(), vec![index, a2, a1]);
+        assert_eq!(pages.get_section("a").unwrap().pages, vec![a2, a1]);
+        assert_eq!(pages.get_page(index).unwrap().side, PdfPageSide::Right);
+        assert_eq!(pages.get_page(a2).unwrap().side, PdfPageSide::Left);
+
+        assert!(pages.move_page(a1, 3).is_err());
+        assert!(pages.move_page(RuntimePage::new("missing").id, 0).is_err());
+    }
+
+    #[test]
+    fn insert_page_after_should_place_page_after_another() {
+        let mut pages = RuntimePages::new();
+        let first = pages.insert_page(RuntimePage::new("first"));
+        let last = pages.insert_page(RuntimePage::new("last"));
+
+        let middle = pages
+            .insert_page_after(first, RuntimePage::new("middle"))
+            .unwrap();
+        assert_eq!(pages.ids().collect::<Vec<_>>(), vec![first, middle, last]);
+        assert_eq!(pages.get_page(last).unwrap().side, PdfPageSide::Right);
+
+        assert!(pages
+            .insert_page_after(RuntimePage::new("missing").id, RuntimePage::new(""))
+            .is_err());
+        assert_eq!(pages.len(), 3);
+    }
+
+    #[test]
+    fn remove_page_should_remove_page_from_list_and_section() {
+        let mut pages = RuntimePages::new();
+        pages.begin_section(RuntimeSection::new("a")).unwrap();
+        let a1 = pages.insert_page(RuntimePage::new("a1"));
+        let a2 = pages.insert_page(RuntimePage::new("a2"));
+
+        assert_eq!(pages.remove_page(a1).unwrap().title, "a1");
+        assert!(pages.remove_page(a1).is_none());
+        assert_eq!(pages.ids().collect::<Vec<_>>(), vec![a2]);
+        assert_eq!(pages.get_section("a").unwrap().pages, vec![a2]);
+        assert_eq!(pages.get_page(a2).unwrap().side, PdfPageSide::Right);
+    }
+
+    #[test]
+    fn page_should_add_page_whose_objects_are_shared() {
+        let mut pages = RuntimePages::new();
+        let page = pages.page("2025-01-01");
+        page.push(crate::pdf::PdfObjectText::default());
+
+        assert_eq!(pages.ids().collect::<Vec<_>>(), vec![page.id]);
+        let stored = pages.get_page(page.id).unwrap();
+        assert_eq!(stored.title, "2025-01-01");
+        assert_eq!(stored.objects().len(), 1);
+    }
+
+    #[test]
+    fn set_labels_should_fail_if_labels_start_at_invalid_or_same_pages() {
+        let label = |start| PdfPageLabel {
+            start,
+            ..Default::default()
+        };
+
+        let mut pages = RuntimePages::new();
+        assert!(pages.set_labels(vec![label(0)]).is_err());
+        assert!(pages.set_labels(vec![label(3), label(3)]).is_err());
+        assert!(pages.labels().is_empty());
+
+        pages.set_labels(vec![label(1), label(3)]).unwrap();
+        assert_eq!(pages.labels(), &[label(1), label(3)]);
+    }
 }
@@ -1,8 +1,15 @@
-use crate::pdf::{PdfContext, PdfLinkAnnotation, PdfLuaExt, PdfObject};
+use crate::constants::GLOBAL_PDF_VAR_NAME;
+use crate::pdf::{
+    PdfBounds, PdfColor, PdfConfig, PdfContext, PdfDate, PdfLineDashPattern, PdfLinkAnnotation,
+    PdfLuaExt, PdfObject, PdfObjectLine, PdfObjectRect, PdfObjectText, PdfPageSide, PdfPaintMode,
+    PdfPoint, PdfStyle,
+};
+use crate::runtime::{RuntimeImportId, RuntimePages};
 use mlua::prelude::*;
-use printpdf::Mm;
-use std::collections::BTreeMap;
-use std::sync::{Arc, RwLock, Weak};
+use printpdf::{Mm, PdfLayerReference, PdfPageReference};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock, RwLockReadGuard, Weak};
 
 /// Type of unique id associated with a page.
 pub type RuntimePageId = u32;
@@ -23,10 +30,26 @@ pub struct RuntimePage {
     /// Optional, explicit height of the page.
     pub height: Option<Mm>,
 
-    /// Collection of objects to add to the PDF.
-    ///
-    /// Page Id -> Depth -> Objects
-    objects: Arc<RwLock<BTreeMap<i64, Vec<PdfObject>>>>,
+    /// Name of the section containing the page, if any.
+    pub section: Option<String>,
+
+    /// Date that the page is for, if any, such as the day of a daily page.
+    pub date: Option<PdfDate>,
+
+    /// Side of a two-page spread that the page falls on, based on its number.
+    pub side: PdfPageSide,
+
+    /// Collection of objects to add to the PDF in the order they were pushed.
+    objects: Arc<RwLock<Vec<PdfObject>>>,
+
+    /// Optional region of the page to display or print, trimming everything outside of it.
+    crop: Arc<RwLock<Option<PdfBounds>>>,
+
+    /// Optional region of the page containing its meaningful content.
+    art: Arc<RwLock<Option<PdfBounds>>>,
+
+    /// Optional page imported from an existing PDF that is drawn beneath everything else.
+    background: Arc<RwLock<Option<RuntimeImportId>>>,
 }
 
 impl RuntimePage {
@@ -37,67 +60,643 @@ impl RuntimePage {
             title: title.into(),
             width: None,
             height: None,
+            section: None,
+            date: None,
+            side: PdfPageSide::default(),
             objects: Default::default(),
+            crop: Default::default(),
+            art: Default::default(),
+            background: Default::default(),
         }
     }
 
+    /// Returns the crop box of the page, if it has been set.
+    pub fn crop(&self) -> Option<PdfBounds> {
+        *self.crop.read().unwrap()
+    }
+
+    /// Returns the art box of the page, if it has been set.
+    pub fn art(&self) -> Option<PdfBounds> {
+        *self.art.read().unwrap()
+    }
+
+    /// Returns the id of the imported page drawn as the background of the page, if it has been
+    /// set.
+    pub fn background(&self) -> Option<RuntimeImportId> {
+        *self.background.read().unwrap()
+    }
+
+    /// Returns the objects pushed onto the page, in the order they were pushed.
+    pub fn objects(&self) -> RwLockReadGuard<'_, Vec<PdfObject>> {
+        self.objects.read().unwrap()
+    }
+
+    /// Pushes `obj` onto the page to be drawn.
+    pub fn push(&self, obj: impl Into<PdfObject>) {
+        self.objects.write().unwrap().push(obj.into());
+    }
+
+    /// Inserts `objs` at `index` within the objects of the page, shifting the objects after it.
+    pub(crate) fn insert(&self, index: usize, objs: Vec<PdfObject>) {
+        let mut objects = self.objects.write().unwrap();
+        let index = index.min(objects.len());
+        objects.splice(index..index, objs);
+    }
+
     /// Returns a collection of link annotations associated with the page.
     pub fn link_annotations(&self, ctx: PdfContext) -> Vec<PdfLinkAnnotation> {
         let mut annotations = Vec::new();
 
-        for (_, objs) in self.objects.read().unwrap().iter() {
-            for obj in objs {
-                annotations.extend(obj.link_annotations(ctx));
-            }
+        for obj in self.objects.read().unwrap().iter() {
+            annotations.extend(obj.link_annotations(ctx));
         }
 
         annotations
     }
 
     /// Draws the page by adding objects in order based on their depth.
-    pub fn draw(&self, ctx: PdfContext<'_>) {
-        for (_, objs) in self.objects.read().unwrap().iter() {
-            for obj in objs {
+    ///
+    /// This is a painter's algorithm: objects nested within groups are sorted alongside all other
+    /// objects on the page, lower depths being drawn first. Objects without a depth use the
+    /// default depth of the page. The sort is stable, so objects with the same depth are drawn
+    /// in the order they were pushed. Each object is drawn using the style inherited from the
+    /// groups containing it.
+    ///
+    /// Objects within a layer are drawn onto a layer of `page` created for it, stacked above the
+    /// default layer (`ctx.layer`) in the order the layers were configured, meaning that depth
+    /// only orders objects within the same layer.
+    pub fn draw(&self, ctx: PdfContext<'_>, page: &PdfPageReference) {
+        let objects = self.objects.read().unwrap();
+        let objects = draw_order(&objects, ctx.config.page.depth);
+
+        // Create the layers used by the page, stacked in the order they were configured with
+        // unconfigured layers stacked last in the order they are first used
+        let mut names: Vec<&str> = Vec::new();
+        for (_, _, layer) in objects.iter() {
+            if let Some(name) = layer {
+                if !names.contains(name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        let configured = &ctx.config.page.layers;
+        names.sort_by_key(|name| {
+            configured
+                .iter()
+                .position(|x| x.name == *name)
+                .unwrap_or(configured.len())
+        });
+
+        let layers: HashMap<&str, PdfLayerReference> = names
+            .into_iter()
+            .map(|name| (name, page.add_layer(name)))
+            .collect();
+
+        for (obj, style, layer) in objects.iter() {
+            let ctx = PdfContext {
+                layer: layer.and_then(|x| layers.get(x)).unwrap_or(ctx.layer),
+                ..ctx
+            };
+            style.with_context(ctx, |ctx| obj.draw(ctx));
+        }
+
+        // Overlay the page with a debug layer above everything else when requested
+        if let Some(spacing) = ctx.config.page.debug_grid {
+            let layer = page.add_layer("Debug");
+            let ctx = PdfContext {
+                layer: &layer,
+                ..ctx
+            };
+            let bounds = PdfBounds::from_coords(
+                Mm(0.0),
+                Mm(0.0),
+                self.width.unwrap_or(ctx.config.page.width),
+                self.height.unwrap_or(ctx.config.page.height),
+            );
+            let boxes = [self.crop(), self.art()];
+            for obj in debug_objects(&objects, ctx, bounds, boxes, spacing) {
                 obj.draw(ctx);
             }
         }
     }
 }
 
+/// Returns objects that overlay a page within `bounds` with a coordinate grid labeled every
+/// `spacing` millimeters, the outlines of `boxes` (such as the crop and art boxes), and the
+/// bounds of each of `objects` labeled with its depth.
+fn debug_objects(
+    objects: &[(&PdfObject, PdfStyle, Option<&str>)],
+    ctx: PdfContext,
+    bounds: PdfBounds,
+    boxes: [Option<PdfBounds>; 2],
+    spacing: f32,
+) -> Vec<PdfObject> {
+    const LABEL_SIZE: f32 = 6.0;
+    let grid_color = PdfColor::from_rgb_u8(0, 160, 255).with_alpha(0.5);
+    let box_color = PdfColor::red();
+    let bounds_color = PdfColor::from_rgb_u8(255, 0, 255);
+
+    let line = |points: Vec<PdfPoint>, color: PdfColor| {
+        PdfObject::Line(PdfObjectLine {
+            points,
+            color: Some(color),
+            thickness: Some(0.0),
+            dash_pattern: Some(PdfLineDashPattern::solid()),
+            ..Default::default()
+        })
+    };
+    let rect = |bounds: PdfBounds, color: PdfColor, dash_pattern: PdfLineDashPattern| {
+        PdfObject::Rect(PdfObjectRect {
+            bounds,
+            outline_color: Some(color),
+            outline_thickness: Some(0.0),
+            mode: Some(PdfPaintMode::stroke()),
+            dash_pattern: Some(dash_pattern),
+            ..Default::default()
+        })
+    };
+    let label = |x: f32, y: f32, text: String, color: PdfColor| {
+        PdfObject::Text(PdfObjectText {
+            point: PdfPoint::from_coords_f32(x, y),
+            text,
+            size: Some(LABEL_SIZE),
+            color: Some(color),
+            ..Default::default()
+        })
+    };
+
+    let mut debug = Vec::new();
+    let (llx, lly, urx, ury) = bounds.to_coords_f32();
+
+    if spacing > 0.0 {
+        // Vertical lines labeled along the bottom of the page
+        for i in 0..=((urx - llx) / spacing).floor() as usize {
+            let x = llx + spacing * i as f32;
+            debug.push(line(
+                vec![
+                    PdfPoint::from_coords_f32(x, lly),
+                    PdfPoint::from_coords_f32(x, ury),
+                ],
+                grid_color,
+            ));
+            debug.push(label(x + 0.5, lly + 0.5, format!("{x}"), grid_color));
+        }
+
+        // Horizontal lines labeled along the left of the page
+        for i in 0..=((ury - lly) / spacing).floor() as usize {
+            let y = lly + spacing * i as f32;
+            debug.push(line(
+                vec![
+                    PdfPoint::from_coords_f32(llx, y),
+                    PdfPoint::from_coords_f32(urx, y),
+                ],
+                grid_color,
+            ));
+            debug.push(label(llx + 0.5, y + 0.5, format!("{y}"), grid_color));
+        }
+    }
+
+    for bounds in boxes.into_iter().flatten() {
+        debug.push(rect(bounds, box_color, PdfLineDashPattern::dashed(3)));
+    }
+
+    for (obj, style, _) in objects {
+        let bounds = style.with_context(ctx, |ctx| obj.bounds(ctx));
+        let depth = obj.depth_or(ctx.config.page.depth);
+        debug.push(rect(bounds, bounds_color, PdfLineDashPattern::solid()));
+        debug.push(label(
+            bounds.ll.x.0,
+            bounds.ur.y.0 + 0.5,
+            format!("{depth}"),
+            bounds_color,
+        ));
+    }
+
+    debug
+}
+
+/// Returns `objects` (with groups flattened) in the order they will be drawn, using
+/// `default_depth` for objects without a depth, alongside the style and layer inherited by each
+/// object.
+fn draw_order(
+    objects: &[PdfObject],
+    default_depth: i64,
+) -> Vec<(&PdfObject, PdfStyle, Option<&str>)> {
+    let mut objects: Vec<(&PdfObject, PdfStyle, Option<&str>)> = objects
+        .iter()
+        .flat_map(|obj| obj.flatten_styled(PdfStyle::default(), None))
+        .collect();
+    objects.sort_by_key(|(obj, _, _)| obj.depth_or(default_depth));
+    objects
+}
+
 impl<'lua> IntoLua<'lua> for RuntimePage {
     #[inline]
     fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
         let objects = Arc::downgrade(&self.objects);
+        let crop = Arc::downgrade(&self.crop);
+        let art = Arc::downgrade(&self.art);
+        let background = Arc::downgrade(&self.background);
 
         let (table, metatable) = lua.create_table_ext()?;
         table.raw_set("id", self.id)?;
         table.raw_set("title", self.title)?;
         table.raw_set("width", self.width.map(|x| x.0))?;
         table.raw_set("height", self.height.map(|x| x.0))?;
+        table.raw_set("section", self.section)?;
+        table.raw_set("date", self.date)?;
+        table.raw_set("side", self.side)?;
+
+        // Define a method that returns the bounds of the page inset by the margins and unsafe
+        // area of the page configuration, mirroring margins for the side of the page
+        let (side, width, height) = (self.side, self.width, self.height);
+        metatable.raw_set(
+            "content_bounds",
+            lua.create_function(move |lua, _: LuaTable| {
+                let mut config = lua
+                    .globals()
+                    .raw_get::<_, PdfConfig>(GLOBAL_PDF_VAR_NAME)?
+                    .page;
+                config.width = width.unwrap_or(config.width);
+                config.height = height.unwrap_or(config.height);
+                Ok(config.content_bounds(side))
+            })?,
+        )?;
 
         // Define a field function that supports pushing any PDF object into a queue that will be
-        // drawn for the current PDF page. The object's depth will be used to determine where in
-        // the queue to add the object itself.
+        // drawn for the current PDF page. The object's depth will be used when drawing to
+        // determine the order of the objects.
+        //
+        // A function can be pushed in place of an object, which is evaluated into objects once
+        // the script finishes and takes the place of the function in the queue.
+        let id = self.id;
         metatable.raw_set(
             "push",
-            lua.create_function(move |_, obj: PdfObject| {
-                // Add object to list for page `id` at object's depth
-                if let Some(objects) = Weak::upgrade(&objects) {
+            lua.create_function(move |lua, obj: LuaValue| {
+                let Some(objects) = Weak::upgrade(&objects) else {
+                    return Ok(());
+                };
+
+                if let LuaValue::Function(f) = obj {
+                    let index = objects.read().unwrap().len();
+                    let key = Rc::new(lua.create_registry_value(f)?);
+                    lua.app_data_mut::<RuntimePages>()
+                        .ok_or_else(|| LuaError::runtime("Runtime pages are missing"))?
+                        .add_lazy(id, index, key);
+                } else {
                     objects
                         .write()
                         .unwrap()
-                        .entry(obj.depth())
-                        .or_default()
-                        .push(obj);
+                        .push(PdfObject::from_lua(obj, lua)?);
+                }
+
+                Ok(())
+            })?,
+        )?;
+
+        // Define a method that overrides the region of the page that is displayed or printed,
+        // which is the entire page by default. Providing nil restores the default.
+        metatable.raw_set(
+            "set_crop",
+            lua.create_function(move |_, (_, bounds): (LuaTable, Option<PdfBounds>)| {
+                if let Some(crop) = Weak::upgrade(&crop) {
+                    *crop.write().unwrap() = bounds;
+                }
+
+                Ok(())
+            })?,
+        )?;
+
+        // Define a method that overrides the region of the page containing meaningful content,
+        // which is the entire page by default. Providing nil restores the default.
+        metatable.raw_set(
+            "set_art",
+            lua.create_function(move |_, (_, bounds): (LuaTable, Option<PdfBounds>)| {
+                if let Some(art) = Weak::upgrade(&art) {
+                    *art.write().unwrap() = bounds;
                 }
 
                 Ok(())
             })?,
         )?;
 
+        // Define a method that draws a page imported from an existing PDF (via `pdf.import.page`)
+        // beneath everything else on the page. Providing nil removes the background.
+        metatable.raw_set(
+            "set_background",
+            lua.create_function(move |_, (_, import): (LuaTable, Option<LuaTable>)| {
+                let id = import
+                    .map(|x| x.raw_get::<_, RuntimeImportId>("id"))
+                    .transpose()?;
+                if let Some(background) = Weak::upgrade(&background) {
+                    *background.write().unwrap() = id;
+                }
+
+                Ok(())
+            })?,
+        )?;
+
+        // Define a method that registers a function to draw onto the page once every page
+        // exists, invoked with the page and a table of its `index` and the `total` pages
+        metatable.raw_set(
+            "on_draw",
+            lua.create_function(move |lua, (_, f): (LuaTable, LuaFunction)| {
+                let key = Rc::new(lua.create_registry_value(f)?);
+                if let Some(mut pages) = lua.app_data_mut::<RuntimePages>() {
+                    pages.add_hook(Some(id), key);
+                    Ok(())
+                } else {
+                    Err(LuaError::runtime("Runtime pages are missing"))
+                }
+            })?,
+        )?;
+
         // Prevent altering the page object
         lua.mark_readonly(table.clone())?;
 
         Ok(LuaValue::Table(table))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{PdfConfig, PdfObjectGroup};
+    use crate::runtime::{RuntimeAlpha, RuntimeFonts, RuntimeToc, RuntimeXObjects};
+    use printpdf::PdfDocument;
+
+    /// Creates a rect with an optional depth whose thickness is used to identify it.
+    fn rect(id: f32, depth: Option<i64>) -> PdfObject {
+        PdfObject::Rect(PdfObjectRect {
+            depth,
+            outline_thickness: Some(id),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the ids of the rects in draw order.
+    fn ids(objects: Vec<(&PdfObject, PdfStyle, Option<&str>)>) -> Vec<f32> {
+        objects
+            .into_iter()
+            .map(|(obj, _, _)| match obj {
+                PdfObject::Rect(x) => x.outline_thickness.unwrap(),
+                x => panic!("Unexpected object: {x:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_be_able_to_set_crop_and_art_boxes_in_lua() {
+        let lua = Lua::new();
+        let page = RuntimePage::new("page");
+        lua.globals().raw_set("page", page.clone()).unwrap();
+
+        lua.load(mlua::chunk! {
+            page:set_crop({ 1, 2, 3, 4 })
+            page:set_art({ ll = { x = 5, y = 6 }, ur = { x = 7, y = 8 } })
+        })
+        .exec()
+        .unwrap();
+
+        assert_eq!(
+            page.crop(),
+            Some(PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0))
+        );
+        assert_eq!(
+            page.art(),
+            Some(PdfBounds::from_coords_f32(5.0, 6.0, 7.0, 8.0))
+        );
+
+        lua.load(mlua::chunk! {
+            page:set_crop(nil)
+            page:set_art(nil)
+        })
+        .exec()
+        .unwrap();
+
+        assert_eq!(page.crop(), None);
+        assert_eq!(page.art(), None);
+    }
+
+    #[test]
+    fn should_be_able_to_set_background_in_lua() {
+        let lua = Lua::new();
+        let page = RuntimePage::new("page");
+        lua.globals().raw_set("page", page.clone()).unwrap();
+
+        lua.load(mlua::chunk! {
+            page:set_background({ id = 3, page = 1 })
+        })
+        .exec()
+        .unwrap();
+        assert_eq!(page.background(), Some(3));
+
+        lua.load(mlua::chunk! {
+            page:set_background(nil)
+        })
+        .exec()
+        .unwrap();
+        assert_eq!(page.background(), None);
+    }
+
+    #[test]
+    fn should_be_able_to_get_content_bounds_mirrored_for_side_of_page_in_lua() {
+        let lua = Lua::new();
+        lua.globals()
+            .raw_set("pdf", crate::pdf::Pdf::default())
+            .unwrap();
+
+        let mut page = RuntimePage::new("page");
+        page.width = Some(Mm(100.0));
+        page.height = Some(Mm(200.0));
+        page.side = PdfPageSide::Left;
+        lua.globals().raw_set("page", page).unwrap();
+
+        lua.load(mlua::chunk! {
+            pdf.page.margins = { top = 0, right = 5, bottom = 0, left = 20 }
+            pdf.page.mirror_margins = true
+
+            pdf.utils.assert_deep_equal(page.side, "left")
+            pdf.utils.assert_deep_equal(page:content_bounds(), {
+                ll = { x = 5, y = 0 },
+                ur = { x = 80, y = 200 },
+            })
+        })
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn draw_order_should_sort_by_depth_keeping_insertion_order_for_ties() {
+        let objects = vec![
+            rect(1.0, Some(1)),
+            rect(2.0, None),
+            rect(3.0, Some(-1)),
+            rect(4.0, Some(1)),
+            rect(5.0, None),
+        ];
+
+        assert_eq!(ids(draw_order(&objects, 0)), vec![3.0, 2.0, 5.0, 1.0, 4.0]);
+    }
+
+    #[test]
+    fn draw_order_should_sort_objects_nested_in_groups() {
+        let objects = vec![
+            PdfObject::Group(
+                vec![rect(1.0, Some(2)), rect(2.0, None)]
+                    .into_iter()
+                    .collect::<PdfObjectGroup>(),
+            ),
+            rect(3.0, Some(1)),
+        ];
+
+        assert_eq!(ids(draw_order(&objects, 0)), vec![2.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn draw_order_should_use_default_depth_for_objects_without_depth() {
+        let objects = vec![rect(1.0, None), rect(2.0, Some(1)), rect(3.0, Some(3))];
+
+        assert_eq!(ids(draw_order(&objects, 2)), vec![2.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn draw_order_should_skip_hidden_objects_and_groups() {
+        let mut hidden = rect(2.0, None);
+        if let PdfObject::Rect(x) = &mut hidden {
+            x.hidden = Some(true);
+        }
+
+        let mut group = vec![rect(3.0, None)]
+            .into_iter()
+            .collect::<PdfObjectGroup>();
+        group.hidden = Some(true);
+
+        let objects = vec![rect(1.0, None), hidden, PdfObject::Group(group)];
+
+        assert_eq!(ids(draw_order(&objects, 0)), vec![1.0]);
+    }
+
+    #[test]
+    fn draw_order_should_include_layers_inherited_from_groups() {
+        let mut grid = vec![rect(1.0, None), rect(2.0, None)]
+            .into_iter()
+            .collect::<PdfObjectGroup>();
+        grid.layer = Some(String::from("grid"));
+        if let PdfObject::Rect(x) = &mut grid.objects[1] {
+            x.layer = Some(String::from("guides"));
+        }
+
+        let objects = vec![PdfObject::Group(grid), rect(3.0, None)];
+        let layers: Vec<Option<&str>> = draw_order(&objects, 0)
+            .into_iter()
+            .map(|(_, _, layer)| layer)
+            .collect();
+
+        assert_eq!(layers, vec![Some("grid"), Some("guides"), None]);
+    }
+
+    #[test]
+    fn draw_order_should_include_styles_inherited_from_groups() {
+        let mut inner = vec![rect(2.0, None)]
+            .into_iter()
+            .collect::<PdfObjectGroup>();
+        inner.style.size = Some(12.0);
+
+        let mut outer = vec![rect(1.0, None), PdfObject::Group(inner)]
+            .into_iter()
+            .collect::<PdfObjectGroup>();
+        outer.style.size = Some(8.0);
+        outer.style.outline_thickness = Some(3.0);
+
+        let objects = vec![PdfObject::Group(outer), rect(3.0, None)];
+        let styles: Vec<PdfStyle> = draw_order(&objects, 0)
+            .into_iter()
+            .map(|(_, style, _)| style)
+            .collect();
+
+        assert_eq!(
+            styles,
+            vec![
+                PdfStyle {
+                    size: Some(8.0),
+                    outline_thickness: Some(3.0),
+                    ..Default::default()
+                },
+                PdfStyle {
+                    size: Some(12.0),
+                    outline_thickness: Some(3.0),
+                    ..Default::default()
+                },
+                PdfStyle::default(),
+            ]
+        );
+    }
+
+    #[test]
+    fn debug_objects_should_overlay_grid_boxes_and_object_bounds() {
+        // Create a pdf context that we need for bounds calculations
+        let doc = PdfDocument::empty("");
+        let (page_idx, layer_idx) = doc.add_page(Mm(0.0), Mm(0.0), "");
+        let layer = doc.get_page(page_idx).get_layer(layer_idx);
+        let mut font = RuntimeFonts::new();
+        let font_id = font.add_builtin_font().unwrap();
+        font.add_font_as_fallback(font_id);
+        let ctx = PdfContext {
+            config: &PdfConfig::default(),
+            layer: &layer,
+            fonts: &font,
+            fallback_font_id: font_id,
+            alpha: &RuntimeAlpha::new(),
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
+        };
+
+        let obj = PdfObject::Rect(PdfObjectRect {
+            bounds: PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0),
+            depth: Some(7),
+            ..Default::default()
+        });
+        let objects = vec![(&obj, PdfStyle::default(), None)];
+        let debug = debug_objects(
+            &objects,
+            ctx,
+            PdfBounds::from_coords_f32(0.0, 0.0, 10.0, 10.0),
+            [Some(PdfBounds::from_coords_f32(1.0, 1.0, 9.0, 9.0)), None],
+            5.0,
+        );
+
+        // Three lines and labels in each direction, one box, and the bounds of the object
+        let lines = debug
+            .iter()
+            .filter(|x| matches!(x, PdfObject::Line(_)))
+            .count();
+        assert_eq!(lines, 6);
+
+        let labels: Vec<&str> = debug
+            .iter()
+            .filter_map(|x| match x {
+                PdfObject::Text(x) => Some(x.text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(labels, vec!["0", "5", "10", "0", "5", "10", "7"]);
+
+        let rects: Vec<PdfBounds> = debug
+            .iter()
+            .filter_map(|x| match x {
+                PdfObject::Rect(x) => Some(x.bounds),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            rects,
+            vec![
+                PdfBounds::from_coords_f32(1.0, 1.0, 9.0, 9.0),
+                PdfBounds::from_coords_f32(1.0, 2.0, 3.0, 4.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::pdf::PdfPageQuery;
+use crate::runtime::RuntimePage;
+use mlua::prelude::*;
+use std::rc::Rc;
+
+/// Reference to a page that may not exist yet, resolved to the first page satisfying its
+/// predicate once every page has been created.
+#[derive(Clone, Debug)]
+pub enum RuntimePageRef {
+    /// Page matching every field of a query.
+    Query(PdfPageQuery),
+
+    /// Page for which a Lua function returns true when invoked with the page.
+    Function(Rc<LuaRegistryKey>),
+}
+
+impl RuntimePageRef {
+    /// Returns true if `page` satisfies the predicate of the reference.
+    pub fn matches(&self, lua: &Lua, page: &RuntimePage) -> LuaResult<bool> {
+        match self {
+            Self::Query(query) => Ok(query.matches(page)),
+            Self::Function(key) => {
+                let f: LuaFunction = lua.registry_value(key)?;
+                f.call(page.clone())
+            }
+        }
+    }
+}
+
+impl<'lua> FromLua<'lua> for RuntimePageRef {
+    /// Converts from a function invoked with each page or a table of the fields a page must
+    /// match.
+    #[inline]
+    fn from_lua(value: LuaValue<'lua>, lua: &'lua Lua) -> LuaResult<Self> {
+        match value {
+            LuaValue::Function(f) => Ok(Self::Function(Rc::new(lua.create_registry_value(f)?))),
+            LuaValue::Table(_) => Ok(Self::Query(PdfPageQuery::from_lua(value, lua)?)),
+            _ => Err(LuaError::FromLuaConversionError {
+                from: value.type_name(),
+                to: "pdf.runtime.page_ref",
+                message: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::runtime::RuntimePageId;
+use mlua::prelude::*;
+use std::rc::Rc;
+
+/// Named section of the document, grouping together the pages created while it was active.
+#[derive(Clone, Debug)]
+pub struct RuntimeSection {
+    /// Unique name of the section.
+    pub name: String,
+
+    /// Id of the divider page that opens the section, if one was inserted.
+    pub divider: Option<RuntimePageId>,
+
+    /// Ids of pages within the section (including the divider), in the order they appear.
+    pub pages: Vec<RuntimePageId>,
+
+    /// Optional Lua function invoked with each new page (excluding the divider) to draw a
+    /// running header.
+    pub(crate) header: Option<Rc<LuaRegistryKey>>,
+}
+
+impl RuntimeSection {
+    /// Creates a new, empty section named `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            divider: None,
+            pages: Vec::new(),
+            header: None,
+        }
+    }
+
+    /// Returns the id of the first page within the section, which is where the section begins.
+    pub fn first_page(&self) -> Option<RuntimePageId> {
+        self.pages.first().copied()
+    }
+}
+
+impl<'lua> IntoLua<'lua> for RuntimeSection {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("name", self.name)?;
+        table.raw_set("divider", self.divider)?;
+        table.raw_set("pages", self.pages)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
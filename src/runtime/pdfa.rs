@@ -0,0 +1,654 @@
+use crate::runtime::attachments::utf16_string;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use printpdf::lopdf::xref::XrefType;
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use std::io::Write;
+
+/// Version of PDF written in the header of PDF/A-2b documents.
+const PDF_VERSION: &str = "1.7";
+
+/// Comment of non-ASCII bytes written on the line after the header of the PDF so that tools treat
+/// the file as binary, as required by PDF/A.
+const BINARY_COMMENT: &[u8] = b"%\xe2\xe3\xcf\xd3\n";
+
+/// Length of each entry within a cross-reference table, such as `0000000017 00000 n \n`.
+const XREF_ENTRY_LEN: usize = 20;
+
+/// Identifier of the output condition of the document, which is always sRGB.
+const SRGB_IDENTIFIER: &str = "sRGB IEC61966-2.1";
+
+/// Names of actions that PDF/A does not allow.
+const FORBIDDEN_ACTIONS: [&[u8]; 6] = [
+    b"JavaScript",
+    b"Launch",
+    b"Sound",
+    b"Movie",
+    b"ResetForm",
+    b"ImportData",
+];
+
+/// Converts a document into a PDF/A-2b document for archival, which requires an embedded color
+/// profile, XMP metadata, no encryption, and fully embedded fonts.
+#[derive(Clone, Debug)]
+pub struct RuntimePdfA {
+    /// Title of the document, written to the document's metadata.
+    title: String,
+
+    /// Time the document was created, written to the document's metadata.
+    created: DateTime<Utc>,
+}
+
+impl RuntimePdfA {
+    /// Creates a new instance for a document titled `title`, created now.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            created: Utc::now(),
+        }
+    }
+
+    /// Applies the requirements of PDF/A-2b to `doc` that can be met automatically.
+    ///
+    /// 1. Uses the version of PDF that PDF/A-2b is based on.
+    /// 2. Replaces the document information with XMP metadata identifying PDF/A-2b.
+    /// 3. Replaces the output intent with one using an embedded sRGB color profile.
+    /// 4. Names the optional content configuration.
+    /// 5. Marks annotations as printable.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        doc.version = PDF_VERSION.to_string();
+
+        // Document information must match the XMP metadata, so we only keep what we write to both
+        let producer = format!("makepdf {}", env!("CARGO_PKG_VERSION"));
+        let info = Dictionary::from_iter(vec![
+            ("Title", utf16_string(&self.title)),
+            ("Producer", utf16_string(&producer)),
+            ("CreationDate", self.info_date()),
+            ("ModDate", self.info_date()),
+        ]);
+        match doc.trailer.get(b"Info").and_then(Object::as_reference) {
+            Ok(id) => {
+                doc.objects.insert(id, Object::Dictionary(info));
+            }
+            Err(_) => {
+                let id = doc.add_object(info);
+                doc.trailer.set("Info", Object::Reference(id));
+            }
+        }
+
+        let metadata = Stream::new(
+            Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Metadata".to_vec())),
+                ("Subtype", Object::Name(b"XML".to_vec())),
+            ]),
+            self.xmp_metadata(&producer).into_bytes(),
+        );
+        let metadata_id = doc.add_object(metadata);
+
+        let profile = Stream::new(
+            Dictionary::from_iter(vec![
+                ("N", Object::Integer(3)),
+                ("Alternate", Object::Name(b"DeviceRGB".to_vec())),
+            ]),
+            srgb_icc_profile(),
+        );
+        let profile_id = doc.add_object(profile);
+        let identifier = Object::String(SRGB_IDENTIFIER.as_bytes().to_vec(), StringFormat::Literal);
+        let intent = Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"OutputIntent".to_vec())),
+            ("S", Object::Name(b"GTS_PDFA1".to_vec())),
+            ("OutputConditionIdentifier", identifier.clone()),
+            ("Info", identifier),
+            (
+                "RegistryName",
+                Object::String(b"http://www.color.org".to_vec(), StringFormat::Literal),
+            ),
+            ("DestinationOutputProfile", Object::Reference(profile_id)),
+        ]);
+
+        // Remove the previous metadata and color profiles, which are no longer referenced
+        let catalog = doc.catalog()?;
+        let mut unused: Vec<ObjectId> = Vec::new();
+        if let Ok(id) = catalog.get(b"Metadata").and_then(Object::as_reference) {
+            unused.push(id);
+        }
+        if let Ok(intents) = catalog.get(b"OutputIntents").and_then(Object::as_array) {
+            for intent in intents {
+                if let Ok(id) = doc
+                    .dereference(intent)
+                    .and_then(|(_, x)| x.as_dict())
+                    .and_then(|x| x.get(b"DestinationOutputProfile"))
+                    .and_then(Object::as_reference)
+                {
+                    unused.push(id);
+                }
+            }
+        }
+        for id in unused {
+            doc.objects.remove(&id);
+        }
+
+        let catalog = doc.catalog_mut()?;
+        catalog.set("Metadata", Object::Reference(metadata_id));
+        catalog.set(
+            "OutputIntents",
+            Object::Array(vec![Object::Dictionary(intent)]),
+        );
+
+        // Optional content configurations must be named and cannot change state automatically
+        if let Ok(properties) = catalog
+            .get_mut(b"OCProperties")
+            .and_then(Object::as_dict_mut)
+        {
+            if let Ok(config) = properties.get_mut(b"D").and_then(Object::as_dict_mut) {
+                if !config.has(b"Name") {
+                    config.set(
+                        "Name",
+                        Object::String(b"Default".to_vec(), StringFormat::Literal),
+                    );
+                }
+                config.remove(b"AS");
+            }
+        }
+
+        // Annotations must be printed when the document is printed
+        for object in doc.objects.values_mut() {
+            if let Ok(dict) = object.as_dict_mut() {
+                if matches!(dict.get(b"Type").and_then(Object::as_name), Ok(b"Annot")) {
+                    dict.set("F", Object::Integer(4));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes `doc` to `writer`, marking the file as binary with a comment on the line after the
+    /// header.
+    ///
+    /// As lopdf only writes the header itself, the comment is inserted into what it writes and
+    /// the offsets of the cross-reference table are shifted past the comment.
+    pub fn write(doc: &mut Document, mut writer: impl Write) -> anyhow::Result<()> {
+        // Offsets can only be shifted within a cross-reference table, not a stream
+        doc.reference_table.cross_reference_type = XrefType::CrossReferenceTable;
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .context("Failed to write document")?;
+        let bytes = insert_binary_comment(&bytes).context("Failed to mark document as binary")?;
+        writer.write_all(&bytes).context("Failed to write document")
+    }
+
+    /// Checks `doc` against the requirements of PDF/A-2b, returning a description of each
+    /// requirement that is not met.
+    pub fn validate(doc: &Document) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        if doc.trailer.has(b"Encrypt") {
+            issues.push(String::from("document is encrypted"));
+        }
+
+        let catalog = match doc.catalog() {
+            Ok(catalog) => catalog,
+            Err(x) => {
+                issues.push(format!("document is missing its catalog: {x}"));
+                return issues;
+            }
+        };
+
+        let has_pdfa_metadata = catalog
+            .get(b"Metadata")
+            .and_then(Object::as_reference)
+            .and_then(|id| doc.get_object(id))
+            .and_then(Object::as_stream)
+            .map(|x| String::from_utf8_lossy(&x.content).contains("<pdfaid:part>2</pdfaid:part>"))
+            .unwrap_or(false);
+        if !has_pdfa_metadata {
+            issues.push(String::from(
+                "document is missing XMP metadata identifying PDF/A-2",
+            ));
+        }
+
+        let has_output_intent = catalog
+            .get(b"OutputIntents")
+            .and_then(Object::as_array)
+            .map(|intents| {
+                intents.iter().any(|intent| {
+                    doc.dereference(intent)
+                        .and_then(|(_, x)| x.as_dict())
+                        .map(|x| {
+                            matches!(x.get(b"S").and_then(Object::as_name), Ok(b"GTS_PDFA1"))
+                                && x.has(b"DestinationOutputProfile")
+                        })
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false);
+        if !has_output_intent {
+            issues.push(String::from(
+                "document is missing an output intent with an embedded color profile",
+            ));
+        }
+
+        let has_embedded_files = catalog
+            .get(b"Names")
+            .and_then(Object::as_dict)
+            .map(|x| x.has(b"EmbeddedFiles"))
+            .unwrap_or(false);
+        if has_embedded_files {
+            issues.push(String::from(
+                "document has attachments, which PDF/A-2b output cannot have, so remove the \
+                attachments added through pdf.attachments or make the PDF without PDF/A",
+            ));
+        }
+
+        for object in doc.objects.values() {
+            let dict = match object.as_dict() {
+                Ok(dict) => dict,
+                Err(_) => continue,
+            };
+
+            let type_name = dict.get(b"Type").and_then(Object::as_name).ok();
+            let subtype = dict.get(b"Subtype").and_then(Object::as_name).ok();
+
+            // Composite fonts are embedded through their descendants, and Type 3 fonts are
+            // defined entirely within the document
+            if type_name == Some(b"Font") && !matches!(subtype, Some(b"Type0" | b"Type3")) {
+                let embedded = dict
+                    .get(b"FontDescriptor")
+                    .and_then(|x| doc.dereference(x))
+                    .and_then(|(_, x)| x.as_dict())
+                    .map(|x| x.has(b"FontFile") || x.has(b"FontFile2") || x.has(b"FontFile3"))
+                    .unwrap_or(false);
+                if !embedded {
+                    let name = dict
+                        .get(b"BaseFont")
+                        .and_then(Object::as_name_str)
+                        .unwrap_or("unknown");
+                    issues.push(format!("font {name} is not embedded"));
+                }
+            }
+
+            if let Ok(action) = dict.get(b"S").and_then(Object::as_name) {
+                if FORBIDDEN_ACTIONS.contains(&action) {
+                    issues.push(format!(
+                        "document has a {} action",
+                        String::from_utf8_lossy(action)
+                    ));
+                }
+            }
+        }
+
+        // Colors must match the color space of the output intent, which is RGB
+        for (page, id) in doc.get_pages() {
+            let uses_cmyk = doc
+                .get_and_decode_page_content(id)
+                .map(|x| {
+                    x.operations
+                        .iter()
+                        .any(|op| op.operator == "k" || op.operator == "K")
+                })
+                .unwrap_or(false);
+            if uses_cmyk {
+                issues.push(format!(
+                    "page {page} uses CMYK colors, which do not match the sRGB output intent"
+                ));
+            }
+        }
+
+        issues
+    }
+
+    /// Returns the creation time formatted as a date within the document information.
+    fn info_date(&self) -> Object {
+        let date = self.created.format("D:%Y%m%d%H%M%S+00'00'").to_string();
+        Object::String(date.into_bytes(), StringFormat::Literal)
+    }
+
+    /// Returns XMP metadata describing the document as PDF/A-2b.
+    fn xmp_metadata(&self, producer: &str) -> String {
+        let date = self.created.format("%Y-%m-%dT%H:%M:%S+00:00");
+        let title = escape_xml(&self.title);
+        let producer = escape_xml(producer);
+
+        format!(
+            r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
+        xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+      <dc:format>application/pdf</dc:format>
+      <dc:title>
+        <rdf:Alt>
+          <rdf:li xml:lang="x-default">{title}</rdf:li>
+        </rdf:Alt>
+      </dc:title>
+      <xmp:CreateDate>{date}</xmp:CreateDate>
+      <xmp:ModifyDate>{date}</xmp:ModifyDate>
+      <xmp:MetadataDate>{date}</xmp:MetadataDate>
+      <pdf:Producer>{producer}</pdf:Producer>
+      <pdfaid:part>2</pdfaid:part>
+      <pdfaid:conformance>B</pdfaid:conformance>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+            bom = '\u{feff}',
+        )
+    }
+}
+
+/// Inserts [`BINARY_COMMENT`] after the header of the PDF within `bytes`, shifting the offsets
+/// of the cross-reference table and `startxref` by the length of the comment.
+fn insert_binary_comment(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    fn find(bytes: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+        bytes[from..]
+            .windows(needle.len())
+            .position(|x| x == needle)
+            .map(|i| from + i)
+    }
+
+    fn parse_usize(bytes: &[u8]) -> anyhow::Result<usize> {
+        Ok(std::str::from_utf8(bytes)?.trim().parse()?)
+    }
+
+    let shift = BINARY_COMMENT.len();
+    let header_end = find(bytes, b"\n", 0).context("Missing header")? + 1;
+
+    // Find where the cross-reference table starts from the end of the file
+    let startxref = bytes
+        .windows(b"startxref".len())
+        .rposition(|x| x == b"startxref")
+        .context("Missing startxref")?;
+    let startxref_num = find(bytes, b"\n", startxref).context("Missing startxref offset")? + 1;
+    let startxref_num_end = find(bytes, b"\n", startxref_num).unwrap_or(bytes.len());
+    let xref_start = parse_usize(&bytes[startxref_num..startxref_num_end])?;
+    if !bytes
+        .get(xref_start..)
+        .is_some_and(|x| x.starts_with(b"xref"))
+    {
+        anyhow::bail!("Missing cross-reference table at {xref_start}");
+    }
+
+    // Shift the offset of each object in use within each subsection of the table, where each
+    // offset keeps its width of ten digits
+    let mut bytes = bytes.to_vec();
+    let mut i = find(&bytes, b"\n", xref_start).context("Missing cross-reference table")? + 1;
+    while !bytes[i..].starts_with(b"trailer") {
+        let line_end = find(&bytes, b"\n", i).context("Missing cross-reference subsection")?;
+        let cnt = std::str::from_utf8(&bytes[i..line_end])?
+            .split_whitespace()
+            .nth(1)
+            .context("Missing count of cross-reference subsection")?
+            .parse::<usize>()?;
+        i = line_end + 1;
+
+        for _ in 0..cnt {
+            let entry = bytes
+                .get_mut(i..i + XREF_ENTRY_LEN)
+                .context("Missing cross-reference entry")?;
+            if entry[17] == b'n' {
+                let offset = parse_usize(&entry[..10])? + shift;
+                entry[..10].copy_from_slice(format!("{offset:010}").as_bytes());
+            }
+            i += XREF_ENTRY_LEN;
+        }
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() + shift);
+    out.extend_from_slice(&bytes[..header_end]);
+    out.extend_from_slice(BINARY_COMMENT);
+    out.extend_from_slice(&bytes[header_end..startxref_num]);
+    out.extend_from_slice((xref_start + shift).to_string().as_bytes());
+    out.extend_from_slice(&bytes[startxref_num_end..]);
+    Ok(out)
+}
+
+/// Escapes the characters of `s` that have special meaning within XML.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Builds an ICC (version 2) color profile for sRGB, using the sRGB primaries adapted to the D50
+/// white point of the profile connection space and the sRGB transfer function.
+fn srgb_icc_profile() -> Vec<u8> {
+    fn s15_fixed16(x: f64) -> [u8; 4] {
+        ((x * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    fn xyz(x: f64, y: f64, z: f64) -> Vec<u8> {
+        let mut data = b"XYZ \0\0\0\0".to_vec();
+        data.extend([x, y, z].into_iter().flat_map(s15_fixed16));
+        data
+    }
+
+    fn text(s: &str) -> Vec<u8> {
+        let mut data = b"text\0\0\0\0".to_vec();
+        data.extend(s.as_bytes());
+        data.push(0);
+        data
+    }
+
+    fn desc(s: &str) -> Vec<u8> {
+        let mut data = b"desc\0\0\0\0".to_vec();
+        data.extend((s.len() as u32 + 1).to_be_bytes());
+        data.extend(s.as_bytes());
+        data.push(0);
+
+        // Unicode and ScriptCode descriptions are empty
+        data.extend([0; 8]);
+        data.extend([0; 3]);
+        data.extend([0; 67]);
+        data
+    }
+
+    fn curve(points: u16) -> Vec<u8> {
+        let mut data = b"curv\0\0\0\0".to_vec();
+        data.extend(u32::from(points).to_be_bytes());
+        for i in 0..points {
+            let x = f64::from(i) / f64::from(points - 1);
+            let y = if x <= 0.04045 {
+                x / 12.92
+            } else {
+                ((x + 0.055) / 1.055).powf(2.4)
+            };
+            data.extend(((y * 65535.0).round() as u16).to_be_bytes());
+        }
+        data
+    }
+
+    const D50: (f64, f64, f64) = (0.9642, 1.0, 0.8249);
+    let trc = curve(1024);
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", desc(SRGB_IDENTIFIER)),
+        (b"cprt", text("No copyright, use freely")),
+        (b"wtpt", xyz(D50.0, D50.1, D50.2)),
+        (b"rXYZ", xyz(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", trc.clone()),
+        (b"bTRC", trc),
+    ];
+
+    // Tag data follows the header and tag table, with each tag aligned to four bytes
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    let data_start = 128 + 4 + 12 * tags.len();
+    for (signature, mut tag) in tags {
+        let size = tag.len() as u32;
+        table.extend(signature);
+        table.extend(((data_start + data.len()) as u32).to_be_bytes());
+        table.extend(size.to_be_bytes());
+
+        tag.resize(tag.len().next_multiple_of(4), 0);
+        data.extend(tag);
+    }
+
+    let size = (data_start + data.len()) as u32;
+    let mut profile = Vec::with_capacity(size as usize);
+    profile.extend(size.to_be_bytes());
+    profile.extend([0; 4]); // Preferred CMM
+    profile.extend([0x02, 0x10, 0x00, 0x00]); // Version 2.1
+    profile.extend(b"mntr");
+    profile.extend(b"RGB ");
+    profile.extend(b"XYZ ");
+    profile.extend(
+        [2024u16, 1, 1, 0, 0, 0]
+            .into_iter()
+            .flat_map(u16::to_be_bytes),
+    );
+    profile.extend(b"acsp");
+    profile.extend([0; 16]); // Platform, flags, manufacturer, model
+    profile.extend([0; 8]); // Attributes
+    profile.extend([0; 4]); // Perceptual rendering intent
+    profile.extend([D50.0, D50.1, D50.2].into_iter().flat_map(s15_fixed16));
+    profile.extend([0; 4]); // Creator
+    profile.extend([0; 44]); // Reserved
+    profile.extend(table);
+    profile.extend(data);
+
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a document with an empty catalog, optionally using a font that is not embedded.
+    fn doc(with_unembedded_font: bool) -> Document {
+        let mut doc = Document::with_version("1.3");
+        let mut catalog = Dictionary::from_iter(vec![("Type", Object::Name(b"Catalog".to_vec()))]);
+        catalog.set(
+            "OCProperties",
+            Dictionary::from_iter(vec![("D", Object::Dictionary(Dictionary::new()))]),
+        );
+
+        if with_unembedded_font {
+            let descriptor = doc.add_object(Dictionary::from_iter(vec![(
+                "Type",
+                Object::Name(b"FontDescriptor".to_vec()),
+            )]));
+            doc.add_object(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Font".to_vec())),
+                ("Subtype", Object::Name(b"TrueType".to_vec())),
+                ("BaseFont", Object::Name(b"Missing".to_vec())),
+                ("FontDescriptor", Object::Reference(descriptor)),
+            ]));
+        }
+
+        let catalog_id = doc.add_object(catalog);
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+        doc
+    }
+
+    #[test]
+    fn srgb_icc_profile_should_have_valid_header() {
+        let profile = srgb_icc_profile();
+        assert_eq!(
+            u32::from_be_bytes(profile[0..4].try_into().unwrap()) as usize,
+            profile.len()
+        );
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
+        assert_eq!(u32::from_be_bytes(profile[128..132].try_into().unwrap()), 9);
+    }
+
+    #[test]
+    fn validate_should_fail_if_pdfa_requirements_not_applied() {
+        let doc = doc(false);
+        let issues = RuntimePdfA::validate(&doc);
+        assert_eq!(issues.len(), 2, "{issues:?}");
+    }
+
+    #[test]
+    fn validate_should_pass_once_pdfa_requirements_applied() {
+        let mut doc = doc(false);
+        RuntimePdfA::new("Journal & Notes").apply(&mut doc).unwrap();
+        assert_eq!(RuntimePdfA::validate(&doc), Vec::<String>::new());
+
+        let catalog = doc.catalog().unwrap();
+        let config = catalog
+            .get(b"OCProperties")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"D"))
+            .and_then(Object::as_dict)
+            .unwrap();
+        assert!(config.has(b"Name"));
+        assert!(doc.trailer.has(b"Info"));
+    }
+
+    #[test]
+    fn validate_should_fail_if_fonts_are_not_embedded() {
+        let mut doc = doc(true);
+        RuntimePdfA::new("Journal").apply(&mut doc).unwrap();
+        assert_eq!(
+            RuntimePdfA::validate(&doc),
+            vec![String::from("font Missing is not embedded")]
+        );
+    }
+
+    #[test]
+    fn validate_should_fail_if_document_has_attachments() {
+        let mut doc = doc(false);
+        RuntimePdfA::new("Journal").apply(&mut doc).unwrap();
+        doc.catalog_mut().unwrap().set(
+            "Names",
+            Dictionary::from_iter(vec![(
+                "EmbeddedFiles",
+                Object::Dictionary(Dictionary::new()),
+            )]),
+        );
+
+        let issues = RuntimePdfA::validate(&doc);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(
+            issues[0].starts_with("document has attachments, which PDF/A-2b output cannot have"),
+            "{issues:?}"
+        );
+    }
+
+    #[test]
+    fn write_should_mark_document_as_binary_after_header() {
+        let mut doc = doc(false);
+        RuntimePdfA::new("Journal").apply(&mut doc).unwrap();
+
+        let mut bytes = Vec::new();
+        RuntimePdfA::write(&mut doc, &mut bytes).unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n"));
+
+        // Offsets of the cross-reference table point at their objects past the comment
+        let tail = &bytes[bytes.len() - 32..];
+        let xref_start: usize = String::from_utf8_lossy(tail)
+            .rsplit_once("startxref\n")
+            .and_then(|(_, x)| x.lines().next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        let xref = String::from_utf8_lossy(&bytes[xref_start..]);
+        assert!(xref.starts_with("xref\n0 "));
+        let entries = xref
+            .lines()
+            .skip(2)
+            .take_while(|x| !x.starts_with("trailer"));
+        let mut cnt = 0;
+        for (id, entry) in entries.enumerate() {
+            if entry.ends_with("n ") {
+                let offset: usize = entry[..10].parse().unwrap();
+                let expected = format!("{id} 0 obj");
+                assert!(bytes[offset..].starts_with(expected.as_bytes()), "{entry}");
+                cnt += 1;
+            }
+        }
+        assert_eq!(cnt, doc.objects.len());
+
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert!(doc.catalog().is_ok());
+    }
+}
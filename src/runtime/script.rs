@@ -1,6 +1,6 @@
-use crate::constants::SCRIPTS;
+use crate::runtime::{RuntimeCache, RuntimeFiles};
 use anyhow::Context;
-use mlua::{FromLua, IntoLua, Lua};
+use mlua::{Compiler, FromLua, IntoLua, Lua};
 use std::ops::{Deref, DerefMut};
 
 /// Represents a script that can be executed to generate a PDF.
@@ -8,8 +8,23 @@ pub struct RuntimeScript {
     /// Lua runtime that is used to execute the code
     lua: Lua,
 
+    /// Name of the script used when reporting errors and logging
+    name: String,
+
     /// Code loaded as raw bytes
     bytes: Vec<u8>,
+
+    /// Cache holding the compiled stdlib, shared with other scripts
+    cache: RuntimeCache,
+
+    /// If true, the script can be precompiled Luau bytecode instead of source code
+    allow_bytecode: bool,
+
+    /// Level (0 to 2) of optimizations applied when compiling the script
+    opt_level: u8,
+
+    /// If true, the script is compiled to native code where supported
+    native: bool,
 }
 
 impl RuntimeScript {
@@ -17,10 +32,13 @@ impl RuntimeScript {
     ///
     /// The act of loading the script does not even parse the code, only loading it into memory.
     pub fn load_from_script(script: impl AsRef<str>) -> anyhow::Result<Self> {
+        RuntimeFiles::check_fs(script.as_ref())?;
         let bytes = std::fs::read(script.as_ref())
             .with_context(|| format!("Failed to load script '{}'", script.as_ref()))?;
 
-        Self::load_from_bytes(bytes)
+        let mut this = Self::load_from_bytes(bytes)?;
+        this.name = script.as_ref().to_string();
+        Ok(this)
     }
 
     /// Loads a script for a series of bytes.
@@ -34,17 +52,107 @@ impl RuntimeScript {
 
         Ok(Self {
             lua,
+            name: String::from("script"),
             bytes: bytes.into_iter().collect(),
+            cache: RuntimeCache::new(),
+            allow_bytecode: false,
+            opt_level: 1,
+            native: false,
         })
     }
 
+    /// Sets the name of the script used when reporting errors and logging.
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Sets the cache holding the compiled stdlib, reusing the stdlib compiled by other scripts.
+    pub fn set_cache(&mut self, cache: RuntimeCache) {
+        self.cache = cache;
+    }
+
+    /// Sets whether the script can be precompiled Luau bytecode, which is refused by default as
+    /// bytecode is not verified by Luau and malformed bytecode can crash the runtime.
+    pub fn set_allow_bytecode(&mut self, allow_bytecode: bool) {
+        self.allow_bytecode = allow_bytecode;
+    }
+
+    /// Sets the level (0 to 2) of optimizations applied when compiling the script and the modules
+    /// it requires, where 2 inlines functions and unrolls loops at the cost of less helpful errors.
+    pub fn set_opt_level(&mut self, opt_level: u8) {
+        self.opt_level = opt_level.min(2);
+    }
+
+    /// Sets whether the script and the modules it requires are compiled to native code, which is
+    /// only supported when makepdf is built with the jit feature and ignored by platforms other
+    /// than x64 and arm64.
+    pub fn set_native(&mut self, native: bool) {
+        self.native = native;
+    }
+
+    /// Returns true if the script is precompiled Luau bytecode rather than source code.
+    ///
+    /// Luau bytecode starts with its version, which is always less than a newline, while source
+    /// code never starts with such a byte.
+    pub fn is_bytecode(&self) -> bool {
+        self.bytes.first().is_some_and(|b| *b < b'\n')
+    }
+
+    /// Compiles the script into Luau bytecode, which can be loaded in place of the script when
+    /// bytecode is allowed to skip parsing and compiling it each time it is executed.
+    pub fn compile(&self) -> anyhow::Result<Vec<u8>> {
+        if self.is_bytecode() {
+            anyhow::bail!("Script '{}' is already compiled", self.name);
+        }
+
+        // Luau reports errors as bytecode with a version of 0 followed by the error message
+        let bytecode = self.compiler().compile(&self.bytes);
+        match bytecode.split_first() {
+            Some((0, message)) => anyhow::bail!(
+                "Failed to compile script '{}': {}",
+                self.name,
+                String::from_utf8_lossy(message)
+            ),
+            _ => Ok(bytecode),
+        }
+    }
+
+    /// Returns the compiler used for the script and the modules it requires.
+    fn compiler(&self) -> Compiler {
+        Compiler::new().set_optimization_level(self.opt_level)
+    }
+
     /// Executes the script. This will eagerly parse and execute the code.
     pub fn exec(&self) -> anyhow::Result<()> {
+        if self.is_bytecode() && !self.allow_bytecode {
+            anyhow::bail!(
+                "Script '{}' is precompiled bytecode, which is only loaded when allowed",
+                self.name
+            );
+        }
+
+        // Configure how code is compiled prior to loading anything, as this applies to each chunk
+        // as it is loaded, including modules required later by the script
+        self.lua.set_compiler(self.compiler());
+
+        #[cfg(feature = "jit")]
+        self.lua.enable_jit(self.native);
+
+        #[cfg(not(feature = "jit"))]
+        if self.native {
+            anyhow::bail!(
+                "Compiling script '{}' to native code requires makepdf to be built with the jit \
+                feature",
+                self.name
+            );
+        }
+
         // Before running our user script, we first want to set up additional functionality
         // via the stdlib script, which should augment what we can do
-        if let Some(stdlib) = SCRIPTS.get("stdlib") {
+        if let Some(stdlib) = self.cache.stdlib() {
             self.lua
-                .load(*stdlib)
+                .load(stdlib.as_slice())
+                .set_name("@stdlib")
                 .exec()
                 .context("Failed to execute stdlib script")?;
         }
@@ -52,6 +160,7 @@ impl RuntimeScript {
         // Now, execute the user script
         self.lua
             .load(&self.bytes)
+            .set_name(format!("@{}", self.name))
             .exec()
             .context("Failed to execute script")
     }
@@ -90,3 +199,196 @@ impl DerefMut for RuntimeScript {
         &mut self.lua
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::GLOBAL_PDF_VAR_NAME;
+    use crate::pdf::Pdf;
+    use crate::runtime::{RuntimeFonts, RuntimePages};
+
+    /// Loads `code` as a script with the global pdf instance and fonts available, mirroring what
+    /// the runtime sets up prior to executing a script.
+    fn load_script(code: &str) -> RuntimeScript {
+        let mut script = RuntimeScript::load_from_bytes(code.bytes()).unwrap();
+        script
+            .set_global(GLOBAL_PDF_VAR_NAME, Pdf::default())
+            .unwrap();
+        script.set_app_data({
+            let mut fonts = RuntimeFonts::new();
+            let id = fonts.add_builtin_font().unwrap();
+            fonts.add_font_as_fallback(id);
+            fonts
+        });
+        script.set_app_data(RuntimePages::new());
+        script
+    }
+
+    #[test]
+    fn exec_should_report_script_name_and_line_number() {
+        let script = load_script("pdf.log.info('starting')\nerror('oops')");
+        let err = format!("{:#}", script.exec().unwrap_err());
+        assert!(err.contains("script:2: oops"), "Unexpected error: {err}");
+    }
+
+    #[test]
+    fn exec_should_only_load_bytecode_when_allowed() {
+        let bytecode = load_script("pdf.pages.create('compiled')")
+            .compile()
+            .unwrap();
+
+        let mut script = load_script("");
+        script.bytes = bytecode;
+        assert!(script.is_bytecode());
+        script
+            .exec()
+            .expect_err("Unexpectedly loaded bytecode without allowing it");
+
+        script.set_allow_bytecode(true);
+        script.exec().unwrap();
+        assert_eq!(
+            script.app_data_ref::<RuntimePages>().unwrap().len(),
+            1,
+            "Bytecode did not create page"
+        );
+
+        script
+            .compile()
+            .expect_err("Unexpectedly compiled bytecode again");
+    }
+
+    #[test]
+    fn compile_should_fail_if_script_is_invalid() {
+        let err = format!("{:#}", load_script("local = 3").compile().unwrap_err());
+        assert!(
+            err.contains("Failed to compile script"),
+            "Unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn exec_should_produce_same_results_at_every_opt_level() {
+        for opt_level in 0..=2 {
+            let mut script = load_script(
+                r#"
+                local function area(w, h) return w * h end
+                local total = 0
+                for i = 1, 10 do total += area(i, 2) end
+                assert(total == 110, "unexpected total: " .. total)
+                "#,
+            );
+            script.set_opt_level(opt_level);
+            script
+                .exec()
+                .unwrap_or_else(|x| panic!("Failed at opt level {opt_level}: {x:#}"));
+        }
+    }
+
+    #[test]
+    fn stdlib_should_support_creating_a_habit_tracker() {
+        load_script(
+            r##"
+            local tracker = pdf.object.habit_tracker({
+                bounds = { 0, 0, 80, 30 },
+                habits = { "Read", "Exercise" },
+                from = { year = 2024, month = 9, day = 1 },
+                to = { year = 2024, month = 9, day = 7 },
+                text_size = 12,
+                weekend_color = "#EEEEEE",
+                link = function(date) return date.day end,
+            })
+
+            -- 2 weekend shades, 7 date headers, 14 checkboxes, and 2 habit labels
+            assert(#tracker == 25, "unexpected object count: " .. #tracker)
+
+            -- Sunday is the first date, so it is shaded before its header is drawn
+            assert(tracker[1].type == "rect", "expected weekend shade")
+            pdf.utils.assert_deep_equal(tracker[2].link, { type = "goto", page = 1 })
+
+            -- Everything should fit within the bounds provided
+            local bounds = tracker:bounds()
+            assert(bounds.ll.x >= 0 and bounds.ll.y >= 0, "tracker outside of bounds")
+            assert(bounds.ur.x <= 80 and bounds.ur.y <= 30, "tracker outside of bounds")
+            "##,
+        )
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn stdlib_should_support_creating_charts() {
+        load_script(
+            r##"
+            local data = {
+                { label = "Jan", value = 3 },
+                { label = "Feb", value = 5 },
+                { label = "Mar", value = 2 },
+            }
+
+            -- Axes, 5 ticks with labels, and a bar with a label per value
+            local bar = pdf.object.chart({ type = "bar", bounds = { 0, 0, 80, 40 }, data = data, text_size = 12 })
+            assert(#bar == 1 + 10 + 6, "unexpected bar object count: " .. #bar)
+
+            -- Tallest bar reaches the top of the plot
+            local tallest = bar[1 + 10 + 3]
+            assert(tallest.type == "rect", "expected bar")
+            assert(math.abs(tallest.ur.y - bar[1]:bounds().ur.y) < 0.001, "tallest bar not at top")
+
+            -- Lines replace the bars with a single line through the values
+            local line = pdf.object.chart({ type = "line", bounds = { 0, 0, 80, 40 }, data = data, text_size = 12 })
+            assert(line[#line].type == "line" and #line[#line] == 3, "expected line through values")
+
+            -- Pie charts have a slice and label per value
+            local pie = pdf.object.chart({ type = "pie", bounds = { 0, 0, 40, 40 }, data = data, text_size = 12 })
+            assert(#pie == 6, "unexpected pie object count: " .. #pie)
+
+            -- Everything should fit within the bounds provided
+            for _, chart in ipairs({ bar, line, pie }) do
+                local bounds = chart:bounds()
+                assert(bounds.ll.x >= -0.001 and bounds.ll.y >= -0.001, "chart outside of bounds")
+                assert(bounds.ur.x <= 80.001 and bounds.ur.y <= 40.001, "chart outside of bounds")
+            end
+            "##,
+        )
+        .exec()
+        .expect("Assertion failed");
+    }
+
+    #[test]
+    fn stdlib_should_support_creating_a_gauge() {
+        load_script(
+            r##"
+            local gauge = pdf.object.gauge({
+                percent = 0.4,
+                bounds = { 0, 0, 40, 40 },
+                thickness = 4,
+                label = true,
+                text_size = 12,
+            })
+
+            -- Track, progress, and label
+            assert(#gauge == 3, "unexpected object count: " .. #gauge)
+            assert(gauge[3].text == "40%", "unexpected label: " .. gauge[3].text)
+
+            -- Progress starts at the top of the ring and ends on its inner edge
+            local function near(point, x, y)
+                return math.abs(point.x - x) < 0.001 and math.abs(point.y - y) < 0.001
+            end
+            local progress = gauge[2]
+            assert(near(progress[1], 20, 40), "progress does not start at top")
+            assert(near(progress[#progress], 20, 36), "progress does not end on inner edge")
+
+            -- Completed gauges have no track
+            local complete = pdf.object.gauge({ percent = 1, bounds = { 0, 0, 40, 40 } })
+            assert(#complete == 1, "unexpected object count: " .. #complete)
+
+            -- Everything should fit within the bounds provided
+            local bounds = gauge:bounds()
+            assert(bounds.ll.x >= -0.001 and bounds.ll.y >= -0.001, "gauge outside of bounds")
+            assert(bounds.ur.x <= 40.001 and bounds.ur.y <= 40.001, "gauge outside of bounds")
+            "##,
+        )
+        .exec()
+        .expect("Assertion failed");
+    }
+}
@@ -0,0 +1,190 @@
+use crate::pdf::PdfObject;
+use crate::runtime::RuntimePageId;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Maximum number of the slowest pages to include when displaying statistics.
+const SLOWEST_PAGES: usize = 5;
+
+/// Statistics collected while building and saving a PDF, used to find pathological pages.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeStats {
+    /// Statistics of each page in the order that they appear within the document.
+    pub pages: Vec<RuntimePageStats>,
+
+    /// Time spent adding fonts to the document.
+    pub fonts_time: Duration,
+
+    /// Time spent saving the document, which includes subsetting fonts.
+    pub save_time: Duration,
+
+    /// Size of the saved PDF in bytes, available once the document has been saved.
+    pub file_size: Option<u64>,
+}
+
+/// Statistics collected while drawing a single page.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimePageStats {
+    /// Unique id associated with the page.
+    pub id: RuntimePageId,
+
+    /// Number of the page within the document, starting at 1.
+    pub number: usize,
+
+    /// Title associated with the page.
+    pub title: String,
+
+    /// Count of objects on the page by type, including those nested within groups.
+    pub objects: BTreeMap<&'static str, usize>,
+
+    /// Time spent drawing the page and its annotations.
+    pub draw_time: Duration,
+}
+
+impl RuntimePageStats {
+    /// Creates statistics for the page with `id`, `number`, and `title`, counting its `objects`.
+    pub fn new<'a>(
+        id: RuntimePageId,
+        number: usize,
+        title: impl Into<String>,
+        objects: impl IntoIterator<Item = &'a PdfObject>,
+    ) -> Self {
+        fn count(obj: &PdfObject, counts: &mut BTreeMap<&'static str, usize>) {
+            *counts.entry(obj.to_type_name()).or_default() += 1;
+            if let PdfObject::Group(group) = obj {
+                for obj in group.iter() {
+                    count(obj, counts);
+                }
+            }
+        }
+
+        let mut objects_cnt = BTreeMap::new();
+        for obj in objects {
+            count(obj, &mut objects_cnt);
+        }
+
+        Self {
+            id,
+            number,
+            title: title.into(),
+            objects: objects_cnt,
+            draw_time: Duration::ZERO,
+        }
+    }
+
+    /// Returns the total count of objects on the page.
+    pub fn total_objects(&self) -> usize {
+        self.objects.values().sum()
+    }
+}
+
+impl RuntimeStats {
+    /// Returns the count of objects across all pages by type.
+    pub fn objects(&self) -> BTreeMap<&'static str, usize> {
+        let mut objects = BTreeMap::new();
+        for (ty, cnt) in self.pages.iter().flat_map(|page| page.objects.iter()) {
+            *objects.entry(*ty).or_default() += cnt;
+        }
+        objects
+    }
+
+    /// Returns the total time spent drawing pages.
+    pub fn draw_time(&self) -> Duration {
+        self.pages.iter().map(|page| page.draw_time).sum()
+    }
+
+    /// Returns up to `n` pages that took the longest to draw, slowest first.
+    pub fn slowest_pages(&self, n: usize) -> Vec<&RuntimePageStats> {
+        let mut pages: Vec<&RuntimePageStats> = self.pages.iter().collect();
+        pages.sort_by_key(|page| std::cmp::Reverse(page.draw_time));
+        pages.truncate(n);
+        pages
+    }
+}
+
+impl fmt::Display for RuntimeStats {
+    /// Writes a multi-line summary of the statistics.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let objects = self.objects();
+        writeln!(
+            f,
+            "Drew {} pages in {:.2?}",
+            self.pages.len(),
+            self.draw_time()
+        )?;
+        writeln!(
+            f,
+            "Objects: {} ({})",
+            objects.values().sum::<usize>(),
+            objects
+                .iter()
+                .map(|(ty, cnt)| format!("{ty}: {cnt}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(f, "Fonts: {:.2?}", self.fonts_time)?;
+        write!(f, "Save: {:.2?}", self.save_time)?;
+
+        if let Some(size) = self.file_size {
+            write!(f, "\nFile size: {size} bytes")?;
+        }
+
+        for page in self.slowest_pages(SLOWEST_PAGES) {
+            write!(
+                f,
+                "\nPage {} ({}): {:.2?} for {} objects",
+                page.number,
+                page.title,
+                page.draw_time,
+                page.total_objects()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{PdfObjectGroup, PdfObjectRect, PdfObjectText};
+
+    #[test]
+    fn should_count_objects_by_type_including_those_within_groups() {
+        let group: PdfObjectGroup = vec![
+            PdfObject::Rect(PdfObjectRect::default()),
+            PdfObject::Text(PdfObjectText::default()),
+        ]
+        .into_iter()
+        .collect();
+        let objects = vec![
+            PdfObject::Rect(PdfObjectRect::default()),
+            PdfObject::Group(group),
+        ];
+
+        let mut first = RuntimePageStats::new(1, 1, "first", &objects);
+        first.draw_time = Duration::from_millis(5);
+        let mut second = RuntimePageStats::new(2, 2, "second", &objects[..1]);
+        second.draw_time = Duration::from_millis(10);
+
+        assert_eq!(
+            first.objects,
+            BTreeMap::from([("group", 1), ("rect", 2), ("text", 1)])
+        );
+        assert_eq!(first.total_objects(), 4);
+
+        let stats = RuntimeStats {
+            pages: vec![first, second],
+            ..Default::default()
+        };
+        assert_eq!(
+            stats.objects(),
+            BTreeMap::from([("group", 1), ("rect", 3), ("text", 1)])
+        );
+        assert_eq!(stats.draw_time(), Duration::from_millis(15));
+
+        let slowest: Vec<RuntimePageId> = stats.slowest_pages(1).iter().map(|x| x.id).collect();
+        assert_eq!(slowest, vec![2]);
+    }
+}
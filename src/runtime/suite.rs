@@ -0,0 +1,157 @@
+use mlua::prelude::*;
+use std::fmt;
+
+/// Collection of tests registered by a script through `pdf.test.it`, which are only run when
+/// testing the script (e.g. `makepdf test`).
+#[derive(Debug, Default)]
+pub struct RuntimeTestSuite {
+    /// Names of the `describe` blocks currently being collected, outermost first.
+    prefix: Vec<String>,
+
+    /// Full name of each test alongside the function to run it.
+    tests: Vec<(String, LuaRegistryKey)>,
+}
+
+impl RuntimeTestSuite {
+    /// Creates a new, empty suite.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the total tests registered.
+    pub fn len(&self) -> usize {
+        self.tests.len()
+    }
+
+    /// Returns `true` if no tests are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    /// Enters a `describe` block named `name`, which prefixes the names of tests registered
+    /// until the block is exited.
+    pub fn enter(&mut self, name: impl Into<String>) {
+        self.prefix.push(name.into());
+    }
+
+    /// Exits the innermost `describe` block.
+    pub fn exit(&mut self) {
+        self.prefix.pop();
+    }
+
+    /// Registers a test named `name` (within the current `describe` blocks) that is run by
+    /// calling `func`.
+    pub fn add(&mut self, lua: &Lua, name: &str, func: LuaFunction) -> LuaResult<()> {
+        let name = self
+            .prefix
+            .iter()
+            .map(String::as_str)
+            .chain([name])
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.tests.push((name, lua.create_registry_value(func)?));
+        Ok(())
+    }
+
+    /// Runs every test in the order they were registered, continuing past failures.
+    pub fn run(self, lua: &Lua) -> RuntimeTestReport {
+        let results = self
+            .tests
+            .into_iter()
+            .map(|(name, key)| {
+                let error = lua
+                    .registry_value::<LuaFunction>(&key)
+                    .and_then(|func| func.call::<_, ()>(()))
+                    .err()
+                    .map(|x| x.to_string());
+                RuntimeTestResult { name, error }
+            })
+            .collect();
+
+        RuntimeTestReport { results }
+    }
+}
+
+/// Results of running the tests of a script.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuntimeTestReport {
+    pub results: Vec<RuntimeTestResult>,
+}
+
+impl RuntimeTestReport {
+    /// Returns the results of the tests that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &RuntimeTestResult> {
+        self.results.iter().filter(|x| !x.passed())
+    }
+
+    /// Returns `true` if every test passed.
+    pub fn passed(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+/// Result of running a single test.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuntimeTestResult {
+    /// Full name of the test, including the names of the `describe` blocks containing it.
+    pub name: String,
+
+    /// Error produced by the test, if it failed.
+    pub error: Option<String>,
+}
+
+impl RuntimeTestResult {
+    /// Returns `true` if the test did not fail.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for RuntimeTestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error.as_deref() {
+            None => write!(f, "[PASS] {}", self.name),
+            Some(error) => write!(f, "[FAIL] {}: {error}", self.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mlua::chunk;
+
+    #[test]
+    fn run_should_report_each_test_by_its_full_name() {
+        let lua = Lua::new();
+        let mut suite = RuntimeTestSuite::new();
+
+        suite.enter("layout");
+        suite
+            .add(
+                &lua,
+                "passes",
+                lua.load(chunk!(function() end)).eval().unwrap(),
+            )
+            .unwrap();
+        suite.exit();
+        suite
+            .add(
+                &lua,
+                "fails",
+                lua.load(chunk!(function() error("oops") end))
+                    .eval()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(suite.len(), 2);
+
+        let report = suite.run(&lua);
+        assert!(!report.passed());
+        assert_eq!(report.results[0].name, "layout passes");
+        assert!(report.results[0].passed());
+        assert_eq!(report.results[1].name, "fails");
+        assert!(report.results[1].error.as_deref().unwrap().contains("oops"));
+        assert_eq!(report.failed().count(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::runtime::RuntimePageId;
+use mlua::prelude::*;
+use std::collections::HashMap;
+
+/// Entry within the table of contents of the document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeTocEntry {
+    /// Title of the entry.
+    pub title: String,
+
+    /// Id of the page the entry refers to.
+    pub page: RuntimePageId,
+
+    /// Level of the entry starting at 1, where entries of higher levels are nested beneath the
+    /// entry of a lower level before them.
+    pub level: usize,
+}
+
+impl<'lua> IntoLua<'lua> for RuntimeTocEntry {
+    #[inline]
+    fn into_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
+        let table = lua.create_table()?;
+
+        table.raw_set("title", self.title)?;
+        table.raw_set("page", self.page)?;
+        table.raw_set("level", self.level)?;
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+/// Manages the entries of the table of contents.
+///
+/// Entries are collected while the script runs, but pages are only numbered once every page
+/// exists, so the numbers are filled in when the document is built and before any page is drawn.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeToc {
+    entries: Vec<RuntimeTocEntry>,
+
+    /// Number of each page (starting at 1) within the document.
+    numbers: HashMap<RuntimePageId, usize>,
+}
+
+impl RuntimeToc {
+    /// Creates a new, empty table of contents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &RuntimeTocEntry> {
+        self.entries.iter()
+    }
+
+    /// Adds an entry to the end of the table of contents.
+    pub fn add(&mut self, entry: RuntimeTocEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Replaces the numbers of the pages within the document.
+    pub fn set_numbers(&mut self, numbers: HashMap<RuntimePageId, usize>) {
+        self.numbers = numbers;
+    }
+
+    /// Returns an iterator over the entries alongside the numbers of their pages, skipping entries
+    /// of pages that do not exist.
+    pub fn iter_numbered(&self) -> impl Iterator<Item = (&RuntimeTocEntry, usize)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| self.numbers.get(&entry.page).map(|x| (entry, *x)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iter_numbered_should_skip_entries_of_missing_pages() {
+        let mut toc = RuntimeToc::new();
+        for (title, page) in [("January", 3), ("Missing", 99), ("February", 7)] {
+            toc.add(RuntimeTocEntry {
+                title: title.to_string(),
+                page,
+                level: 1,
+            });
+        }
+        toc.set_numbers(HashMap::from([(3, 2), (7, 5)]));
+
+        let numbered: Vec<(&str, usize)> = toc
+            .iter_numbered()
+            .map(|(entry, number)| (entry.title.as_str(), number))
+            .collect();
+        assert_eq!(numbered, vec![("January", 2), ("February", 5)]);
+    }
+}
@@ -0,0 +1,324 @@
+use super::xobjects::add_xobjects_to_pages;
+use crate::pdf::{PdfConfigWatermark, PdfContext, PdfObjectText, PdfPoint};
+use crate::runtime::RuntimeFiles;
+use anyhow::Context;
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::{Dictionary, Document, Object, Stream};
+use printpdf::{Mm, Pt};
+use std::cell::Cell;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Prefix of the names of the images of watermarks within the resources of pages.
+const PREFIX: &str = "Watermark";
+
+/// Default size of the text of watermarks.
+const DEFAULT_SIZE: f32 = 72.0;
+
+/// Image of a watermark, decoded from a PNG.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RuntimeWatermarkImage {
+    /// Width of the image in pixels.
+    width: u32,
+    /// Height of the image in pixels.
+    height: u32,
+    /// Color of each pixel as RGB.
+    rgb: Vec<u8>,
+    /// Opacity of each pixel, if the image has transparency.
+    alpha: Option<Vec<u8>>,
+}
+
+impl RuntimeWatermarkImage {
+    /// Decodes the PNG at `path` into RGB with an optional alpha channel.
+    fn load(path: &str) -> anyhow::Result<Self> {
+        RuntimeFiles::check_fs(path)?;
+        let f = File::open(path).with_context(|| format!("Failed to open {path}"))?;
+        let mut decoder = png::Decoder::new(BufReader::new(f));
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .with_context(|| format!("Failed to read {path} as a PNG"))?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buf)
+            .with_context(|| format!("Failed to decode {path}"))?;
+        buf.truncate(info.buffer_size());
+
+        let (rgb, alpha) = match info.color_type {
+            png::ColorType::Rgb => (buf, None),
+            png::ColorType::Rgba => (
+                buf.chunks(4).flat_map(|x| [x[0], x[1], x[2]]).collect(),
+                Some(buf.chunks(4).map(|x| x[3]).collect()),
+            ),
+            png::ColorType::Grayscale => (buf.iter().flat_map(|x| [*x, *x, *x]).collect(), None),
+            png::ColorType::GrayscaleAlpha => (
+                buf.chunks(2).flat_map(|x| [x[0], x[0], x[0]]).collect(),
+                Some(buf.chunks(2).map(|x| x[1]).collect()),
+            ),
+            color_type => anyhow::bail!("Unsupported color type {color_type:?} of {path}"),
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            rgb,
+            alpha,
+        })
+    }
+}
+
+/// Manages the watermarks stamped onto pages while they are built.
+///
+/// Text is drawn like any other text, while images are stored once as image XObjects that are
+/// added to the resources of every page when the document is saved.
+#[derive(Debug, Default)]
+pub struct RuntimeWatermarks {
+    /// Watermarks alongside their decoded images, if any.
+    watermarks: Vec<(PdfConfigWatermark, Option<RuntimeWatermarkImage>)>,
+
+    /// Whether the image of any watermark has been drawn.
+    used: Cell<bool>,
+}
+
+impl RuntimeWatermarks {
+    /// Creates a new instance without any watermarks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance for `watermarks`, decoding their images.
+    pub fn load(watermarks: &[PdfConfigWatermark]) -> anyhow::Result<Self> {
+        let watermarks = watermarks
+            .iter()
+            .map(|x| match x.image.as_deref() {
+                Some(path) => Ok((x.clone(), Some(RuntimeWatermarkImage::load(path)?))),
+                None => Ok((x.clone(), None)),
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            watermarks,
+            used: Cell::new(false),
+        })
+    }
+
+    /// Returns `true` if no images of watermarks are drawn on pages.
+    pub fn is_empty(&self) -> bool {
+        !self.used.get()
+    }
+
+    /// Returns `true` if any watermarks are drawn above everything else on page `number`.
+    pub fn has_above(&self, number: u32) -> bool {
+        self.watermarks
+            .iter()
+            .any(|(x, _)| x.above && x.pages.contains(number))
+    }
+
+    /// Draws the watermarks for page `number` (starting at 1) of `width` and `height` onto the
+    /// layer of `ctx`, drawing those above everything else when `above` is true and those
+    /// beneath everything else otherwise.
+    pub fn draw(&self, ctx: PdfContext, number: u32, width: Mm, height: Mm, above: bool) {
+        let center = PdfPoint::new(width / 2.0, height / 2.0);
+        for (i, (watermark, image)) in self.watermarks.iter().enumerate() {
+            if watermark.above != above || !watermark.pages.contains(number) {
+                continue;
+            }
+
+            // Rotate around the center of the page, drawing the watermark centered on the origin
+            let (sin, cos) = watermark.angle.to_radians().sin_cos();
+            ctx.layer.save_graphics_state();
+            add_transform(ctx, [cos, sin, -sin, cos], center);
+
+            match (image, watermark.text.as_deref()) {
+                (Some(image), _) => {
+                    let w = Mm(watermark.width.unwrap_or(width.0 / 2.0));
+                    let h = w * (image.height as f32 / image.width as f32);
+                    ctx.with_alpha(watermark.opacity, watermark.opacity, || {
+                        add_transform(
+                            ctx,
+                            [Pt::from(w).0, 0.0, 0.0, Pt::from(h).0],
+                            PdfPoint::new(w / -2.0, h / -2.0),
+                        );
+                        ctx.layer.add_operation(Operation::new(
+                            "Do",
+                            vec![Object::Name(format!("{PREFIX}{i}").into_bytes())],
+                        ));
+                    });
+                    self.used.set(true);
+                }
+                (None, Some(text)) => {
+                    let color = watermark.color.unwrap_or_else(|| ctx.fill_color(None));
+                    let mut text = PdfObjectText {
+                        text: text.to_string(),
+                        font: watermark.font,
+                        size: Some(watermark.size.unwrap_or(DEFAULT_SIZE)),
+                        color: Some(color.with_alpha(color.alpha() * watermark.opacity)),
+                        ..Default::default()
+                    };
+                    let bounds = text.bounds(ctx);
+                    text.point = PdfPoint::new(
+                        bounds.ll.x * -1.0 - bounds.width() / 2.0,
+                        bounds.ll.y * -1.0 - bounds.height() / 2.0,
+                    );
+                    text.draw(ctx);
+                }
+                (None, None) => {}
+            }
+
+            ctx.layer.restore_graphics_state();
+        }
+    }
+
+    /// Applies the images of watermarks to `doc`, adding each as an image XObject to the
+    /// resources of every page.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut xobjects = Dictionary::new();
+        for (i, (_, image)) in self.watermarks.iter().enumerate() {
+            let Some(image) = image else {
+                continue;
+            };
+
+            let mut dict = Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"XObject".to_vec())),
+                ("Subtype", Object::Name(b"Image".to_vec())),
+                ("Width", Object::Integer(image.width as i64)),
+                ("Height", Object::Integer(image.height as i64)),
+                ("ColorSpace", Object::Name(b"DeviceRGB".to_vec())),
+                ("BitsPerComponent", Object::Integer(8)),
+            ]);
+
+            // Transparency of the image is stored as a grayscale image of its own
+            if let Some(alpha) = image.alpha.as_ref() {
+                let mut mask = Stream::new(
+                    Dictionary::from_iter(vec![
+                        ("Type", Object::Name(b"XObject".to_vec())),
+                        ("Subtype", Object::Name(b"Image".to_vec())),
+                        ("Width", Object::Integer(image.width as i64)),
+                        ("Height", Object::Integer(image.height as i64)),
+                        ("ColorSpace", Object::Name(b"DeviceGray".to_vec())),
+                        ("BitsPerComponent", Object::Integer(8)),
+                    ]),
+                    alpha.clone(),
+                );
+                mask.compress()?;
+                dict.set("SMask", Object::Reference(doc.add_object(mask)));
+            }
+
+            let mut stream = Stream::new(dict, image.rgb.clone());
+            stream.compress()?;
+            xobjects.set(
+                format!("{PREFIX}{i}"),
+                Object::Reference(doc.add_object(stream)),
+            );
+        }
+
+        add_xobjects_to_pages(doc, &xobjects)
+    }
+}
+
+/// Concatenates the linear part `[a, b, c, d]` of a transform, followed by a translation to
+/// `point`, onto the current transform of the layer of `ctx`.
+fn add_transform(ctx: PdfContext, [a, b, c, d]: [f32; 4], point: PdfPoint) {
+    let (x, y) = (Pt::from(point.x).0, Pt::from(point.y).0);
+    ctx.layer.add_operation(Operation::new(
+        "cm",
+        vec![a.into(), b.into(), c.into(), d.into(), x.into(), y.into()],
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{PdfConfig, PdfStyle};
+    use crate::runtime::{RuntimeAlpha, RuntimeFonts, RuntimeToc, RuntimeXObjects};
+    use printpdf::PdfDocument;
+
+    #[test]
+    fn should_draw_text_and_images_onto_matching_pages() {
+        // Half-transparent 2x1 image
+        let path =
+            std::env::temp_dir().join(format!("makepdf-watermark-{}.png", rand::random::<u32>()));
+        {
+            let f = File::create(&path).unwrap();
+            let mut encoder = png::Encoder::new(f, 2, 1);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[255, 0, 0, 128, 0, 0, 255, 128])
+                .unwrap();
+        }
+
+        let watermarks = RuntimeWatermarks::load(&[
+            PdfConfigWatermark {
+                text: Some(String::from("DRAFT")),
+                angle: 45.0,
+                ..Default::default()
+            },
+            PdfConfigWatermark {
+                image: Some(path.to_string_lossy().to_string()),
+                pages: crate::pdf::PdfWatermarkPages::Numbers(vec![2]),
+                above: true,
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let doc = PdfDocument::empty("test");
+        let (page, layer) = doc.add_page(Mm(100.0), Mm(100.0), "page");
+        let layer = doc.get_page(page).get_layer(layer);
+        let mut fonts = RuntimeFonts::new();
+        let font_id = fonts.add_builtin_font().unwrap();
+        fonts.add_font_as_fallback(font_id);
+        fonts.add_font_to_doc(font_id, &doc).unwrap();
+        let config = PdfConfig::default();
+        let alpha = RuntimeAlpha::new();
+        let ctx = PdfContext {
+            config: &config,
+            layer: &layer,
+            fonts: &fonts,
+            fallback_font_id: font_id,
+            alpha: &alpha,
+            xobjects: &RuntimeXObjects::new(),
+            toc: &RuntimeToc::new(),
+            style: PdfStyle::default(),
+        };
+
+        // Image is only drawn above the second page
+        watermarks.draw(ctx, 1, Mm(100.0), Mm(100.0), true);
+        assert!(watermarks.is_empty());
+        watermarks.draw(ctx, 2, Mm(100.0), Mm(100.0), true);
+        assert!(!watermarks.is_empty());
+
+        // Text is drawn translucent beneath every page
+        watermarks.draw(ctx, 1, Mm(100.0), Mm(100.0), false);
+        assert!(!alpha.is_empty());
+
+        let mut doc = Document::load_mem(&doc.save_to_bytes().unwrap()).unwrap();
+        watermarks.apply(&mut doc).unwrap();
+
+        let pages = doc.get_pages();
+        let (resources, ids) = doc.get_page_resources(pages[&1]);
+        let image_id = resources
+            .or_else(|| doc.get_dictionary(ids[0]).ok())
+            .unwrap()
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"Watermark1"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let image = &doc
+            .get_object(image_id)
+            .and_then(Object::as_stream)
+            .unwrap()
+            .dict;
+        assert_eq!(image.get(b"Width").unwrap().as_i64().unwrap(), 2);
+        assert!(image.get(b"SMask").and_then(Object::as_reference).is_ok());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,256 @@
+use crate::pdf::{PdfBounds, PdfObjectGroup};
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use printpdf::Pt;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+/// Prefix of the names of form XObjects within the resources of pages.
+const PREFIX: &str = "Form";
+
+/// Type of unique id associated with a form XObject.
+pub type RuntimeXObjectId = usize;
+
+/// Manages groups registered as form XObjects, which are drawn once and stamped onto pages.
+///
+/// printpdf only supports XObjects that belong to a single page, so each form in use is drawn on
+/// a page of its own that is converted into a form XObject (and removed) once the document is
+/// saved, with every page referencing the form by name.
+#[derive(Debug, Default)]
+pub struct RuntimeXObjects {
+    /// Groups registered as forms, indexed by their ids.
+    groups: Vec<PdfObjectGroup>,
+
+    /// Ids of the forms drawn onto pages.
+    used: RefCell<BTreeSet<RuntimeXObjectId>>,
+
+    /// Number of the page (starting at 1) that each form in use was drawn on, alongside the
+    /// bounds of the form.
+    pages: Vec<(RuntimeXObjectId, u32, PdfBounds)>,
+}
+
+impl RuntimeXObjects {
+    /// Creates a new instance without any forms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no forms are drawn on pages.
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Registers `group` as a form, returning its id.
+    pub fn register(&mut self, group: PdfObjectGroup) -> RuntimeXObjectId {
+        self.groups.push(PdfObjectGroup {
+            xobject: None,
+            ..group
+        });
+        self.groups.len() - 1
+    }
+
+    /// Returns the group registered as the form with `id`.
+    pub fn get(&self, id: RuntimeXObjectId) -> Option<&PdfObjectGroup> {
+        self.groups.get(id)
+    }
+
+    /// Returns the name of the form with `id`, marking it as in use.
+    pub fn form_name(&self, id: RuntimeXObjectId) -> String {
+        self.used.borrow_mut().insert(id);
+        format!("{PREFIX}{id}")
+    }
+
+    /// Returns the ids of the forms in use that have not yet been drawn onto a page.
+    pub fn to_undrawn_ids(&self) -> Vec<RuntimeXObjectId> {
+        self.used
+            .borrow()
+            .iter()
+            .copied()
+            .filter(|id| !self.pages.iter().any(|(x, _, _)| x == id))
+            .collect()
+    }
+
+    /// Marks the form with `id` as drawn within `bounds` on page `number` (starting at 1).
+    pub fn set_page(&mut self, id: RuntimeXObjectId, number: u32, bounds: PdfBounds) {
+        self.pages.push((id, number, bounds));
+    }
+
+    /// Applies the forms to `doc`, converting the pages they were drawn on into form XObjects
+    /// that are added to the resources of every other page.
+    pub fn apply(&self, doc: &mut Document) -> anyhow::Result<()> {
+        if self.pages.is_empty() {
+            return Ok(());
+        }
+
+        let pages = doc.get_pages();
+        let mut xobjects = Dictionary::new();
+        let mut form_page_ids = Vec::new();
+        for (id, number, bounds) in self.pages.iter().copied() {
+            let page_id = *pages
+                .get(&number)
+                .ok_or_else(|| anyhow::anyhow!("Missing page {number} of form {id}"))?;
+
+            // Forms can use other forms, so their resources need to reference them as well
+            let form_id = doc.new_object_id();
+            xobjects.set(format!("{PREFIX}{id}"), Object::Reference(form_id));
+            form_page_ids.push((form_id, page_id, bounds));
+        }
+
+        add_xobjects_to_pages(doc, &xobjects)?;
+
+        // Convert each page with a form into a form XObject, removing the page afterwards
+        for (form_id, page_id, bounds) in form_page_ids {
+            let content = doc.get_page_content(page_id)?;
+            let page = doc.get_dictionary(page_id)?;
+            let resources = page.get(b"Resources")?.clone();
+            let parent_id = page.get(b"Parent")?.as_reference()?;
+
+            let bbox = [bounds.ll.x, bounds.ll.y, bounds.ur.x, bounds.ur.y]
+                .into_iter()
+                .map(|x| Object::Real(Pt::from(x).0))
+                .collect();
+            let form = Stream::new(
+                Dictionary::from_iter(vec![
+                    ("Type", Object::Name(b"XObject".to_vec())),
+                    ("Subtype", Object::Name(b"Form".to_vec())),
+                    ("BBox", Object::Array(bbox)),
+                    ("Resources", resources),
+                ]),
+                content,
+            );
+            doc.objects.insert(form_id, Object::Stream(form));
+
+            for id in doc.get_page_contents(page_id) {
+                doc.objects.remove(&id);
+            }
+            doc.objects.remove(&page_id);
+            remove_page_from_tree(doc, parent_id, page_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds `xobjects` to the resources of every page of `doc`, merging them with any XObjects the
+/// pages already have.
+pub(super) fn add_xobjects_to_pages(
+    doc: &mut Document,
+    xobjects: &Dictionary,
+) -> anyhow::Result<()> {
+    for page_id in doc.get_pages().into_values() {
+        // Resources are either referenced or inlined within the page
+        let resources_id = doc
+            .get_dictionary(page_id)?
+            .get(b"Resources")
+            .and_then(Object::as_reference)
+            .ok();
+        let resources = match resources_id {
+            Some(id) => doc.get_dictionary_mut(id)?,
+            None => {
+                let page = doc.get_dictionary_mut(page_id)?;
+                if !page.has(b"Resources") {
+                    page.set("Resources", Dictionary::new());
+                }
+                page.get_mut(b"Resources")?.as_dict_mut()?
+            }
+        };
+
+        // Merge with any existing XObjects of the page
+        let mut existing = resources
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .cloned()
+            .unwrap_or_default();
+        for (key, value) in xobjects.iter() {
+            existing.set(key.clone(), value.clone());
+        }
+        resources.set("XObject", existing);
+    }
+
+    Ok(())
+}
+
+/// Removes `page_id` from the kids of the page tree node `parent_id`, updating the count of pages
+/// of it and its ancestors.
+fn remove_page_from_tree(
+    doc: &mut Document,
+    parent_id: ObjectId,
+    page_id: ObjectId,
+) -> anyhow::Result<()> {
+    let parent = doc.get_dictionary_mut(parent_id)?;
+    if let Ok(kids) = parent.get_mut(b"Kids").and_then(Object::as_array_mut) {
+        kids.retain(|x| x.as_reference().ok() != Some(page_id));
+    }
+
+    let mut node_id = Some(parent_id);
+    while let Some(id) = node_id {
+        let node = doc.get_dictionary_mut(id)?;
+        if let Ok(count) = node.get(b"Count").and_then(Object::as_i64) {
+            node.set("Count", count - 1);
+        }
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_convert_form_pages_into_xobjects_shared_by_other_pages() {
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let mut kids = Vec::new();
+        for content in [b"0 0 m 10 10 l S".to_vec(), b"/Form0 Do".to_vec()] {
+            let contents_id = doc.add_object(Stream::new(Dictionary::new(), content));
+            kids.push(Object::Reference(doc.add_object(Dictionary::from_iter(
+                vec![
+                    ("Type", Object::Name(b"Page".to_vec())),
+                    ("Parent", Object::Reference(pages_id)),
+                    ("Contents", Object::Reference(contents_id)),
+                ],
+            ))));
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Pages".to_vec())),
+                ("Count", Object::Integer(2)),
+                ("Kids", Object::Array(kids)),
+            ])),
+        );
+        let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Catalog".to_vec())),
+            ("Pages", Object::Reference(pages_id)),
+        ]));
+        doc.trailer.set("Root", Object::Reference(catalog_id));
+
+        // Form is drawn on the first page and used by the second page
+        let mut xobjects = RuntimeXObjects::new();
+        let id = xobjects.register(PdfObjectGroup::default());
+        assert_eq!(xobjects.form_name(id), "Form0");
+        assert_eq!(xobjects.to_undrawn_ids(), vec![id]);
+        xobjects.set_page(id, 1, PdfBounds::from_coords_f32(0.0, 0.0, 10.0, 10.0));
+        assert!(xobjects.to_undrawn_ids().is_empty());
+        xobjects.apply(&mut doc).unwrap();
+
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 1);
+
+        let (resources, _) = doc.get_page_resources(pages[&1]);
+        let form_id = resources
+            .unwrap()
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .and_then(|x| x.get(b"Form0"))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let form = doc.get_object(form_id).and_then(Object::as_stream).unwrap();
+        assert_eq!(
+            form.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+            b"Form"
+        );
+        assert_eq!(form.content, b"0 0 m 10 10 l S");
+    }
+}
@@ -0,0 +1,20 @@
+use printpdf::lopdf::Document;
+use printpdf::{Mm, PdfDocument, Rect};
+
+/// Page of a test document as its size and the `(llx, lly, urx, ury)` of the rects on it.
+pub type TestPage<'a> = (f32, &'a [(f32, f32, f32, f32)]);
+
+/// Creates a document with a page for each entry in `pages`, where each entry is the size of
+/// the page and the rects to draw on it.
+pub fn make_doc(pages: &[TestPage]) -> Document {
+    let doc = PdfDocument::empty("test");
+    for (size, rects) in pages {
+        let (page, layer) = doc.add_page(Mm(*size), Mm(*size), "page");
+        let layer = doc.get_page(page).get_layer(layer);
+        for (llx, lly, urx, ury) in rects.iter() {
+            layer.add_rect(Rect::new(Mm(*llx), Mm(*lly), Mm(*urx), Mm(*ury)));
+        }
+    }
+
+    Document::load_mem(&doc.save_to_bytes().unwrap()).unwrap()
+}
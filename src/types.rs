@@ -0,0 +1,1095 @@
+use crate::constants::{DEFINITIONS, GLOBAL_PDF_VAR_NAME, SCRIPTS};
+use anyhow::Context;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Names of types built into LuaLS, by the Luau type each is written as.
+const BUILTIN_TYPES: &[(&str, &str)] = &[
+    ("any", "any"),
+    ("boolean", "boolean"),
+    ("function", "((...any) -> ...any)"),
+    ("integer", "number"),
+    ("nil", "nil"),
+    ("number", "number"),
+    ("string", "string"),
+    ("table", "{ [any]: any }"),
+    ("thread", "thread"),
+    ("userdata", "any"),
+];
+
+/// Keywords of Luau, which cannot be used as the names of fields without quotes.
+const KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in", "local",
+    "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+/// Luau type definitions of the `pdf` global, used by luau-lsp to provide completions and type
+/// checking for scripts.
+///
+/// Definitions are translated from the LuaLS annotations of the Lua library (`definitions.lua`
+/// and the stdlib), so fields, parameters, and returns carry the types they are documented with.
+/// Named types are written in PascalCase (`pdf.common.Point` as `PdfCommonPoint`), where the
+/// tables of modules like `pdf.object` end with `Module` (`PdfObjectModule`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PdfTypes {
+    /// Named types of values shared across the API, such as points and objects, by name.
+    pub types: BTreeMap<String, String>,
+    /// Type of the `pdf` global.
+    pub global: String,
+}
+
+impl PdfTypes {
+    /// Generates the type definitions from the annotations of `definitions.lua` and the stdlib,
+    /// failing if an annotation is malformed or refers to a type that is not declared.
+    pub fn generate() -> anyhow::Result<Self> {
+        let stdlib = SCRIPTS.get("stdlib").context("Missing stdlib")?;
+        let stdlib = std::str::from_utf8(stdlib).context("Stdlib is not utf-8")?;
+
+        let mut annotations = Annotations::default();
+        annotations.parse("definitions.lua", DEFINITIONS)?;
+        annotations.parse("stdlib.lua", stdlib)?;
+        annotations.to_types()
+    }
+
+    /// Returns the definitions in the format of a luau-lsp definitions file (`pdf.d.luau`).
+    pub fn to_definitions(&self) -> String {
+        let mut out = String::from(
+            "-- Luau type definitions of the pdf global, generated by `makepdf types`.\n",
+        );
+
+        for (name, ty) in self.types.iter() {
+            let _ = write!(out, "\ntype {name} = {ty}\n");
+        }
+
+        let _ = write!(out, "\ndeclare {GLOBAL_PDF_VAR_NAME}: {}\n", self.global);
+        out
+    }
+}
+
+/// Type written within an annotation.
+#[derive(Clone, Debug, PartialEq)]
+enum Ty {
+    /// Builtin, generic, or declared type referenced by name, e.g. `pdf.common.Point`.
+    Name(String),
+    /// Literal string or boolean, e.g. `"text"`.
+    Literal(String),
+    /// List of items, e.g. `T[]`.
+    Array(Box<Ty>),
+    /// Table of keys to values, e.g. `table<K, V>`.
+    Map(Box<Ty>, Box<Ty>),
+    /// Table of fields, e.g. `{x:number, y?:number}`.
+    Table(Vec<Field>),
+    /// Function, e.g. `fun(x:number):string`.
+    Function(Function),
+    /// Value of any of the types, e.g. `A|B`.
+    Union(Vec<Ty>),
+    /// Value of all of the types, such as a class and its parent.
+    Intersection(Vec<Ty>),
+    /// Value of the type or nil, e.g. `T?` or `T|nil`.
+    Optional(Box<Ty>),
+}
+
+/// Field of a table or class.
+#[derive(Clone, Debug, PartialEq)]
+struct Field {
+    key: Key,
+    ty: Ty,
+}
+
+/// Key of a field.
+#[derive(Clone, Debug, PartialEq)]
+enum Key {
+    /// Field accessed by name, e.g. `x`.
+    Name(String),
+    /// Fields accessed by keys of a type, e.g. `[number]` or `[1]`.
+    Index(Ty),
+}
+
+/// Signature of a function.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Function {
+    /// Names of the generic types of the function.
+    generics: Vec<String>,
+    /// Names & types of the parameters, where `...` is the type of any remaining arguments.
+    params: Vec<(String, Ty)>,
+    /// Types of the values returned.
+    returns: Vec<Ty>,
+}
+
+/// Class declared by `---@class`.
+#[derive(Clone, Debug, PartialEq)]
+struct Class {
+    name: String,
+    /// Classes inherited by the class, e.g. `pdf.object.RectLikeBase`.
+    parents: Vec<Ty>,
+    fields: Vec<Field>,
+    /// Signatures of calling the class, declared by `---@overload`.
+    overloads: Vec<Function>,
+}
+
+impl Class {
+    /// Adds `field`, replacing an earlier field with the same key.
+    fn add_field(&mut self, field: Field) {
+        match self.fields.iter_mut().find(|x| x.key == field.key) {
+            Some(existing) => *existing = field,
+            None => self.fields.push(field),
+        }
+    }
+}
+
+/// Annotations preceding a line of code.
+#[derive(Debug, Default)]
+struct Block {
+    class: Option<Class>,
+    /// Name & variants of an alias declared by `---@alias`.
+    alias: Option<(String, Vec<Ty>)>,
+    generics: Vec<String>,
+    params: Vec<(String, Ty)>,
+    returns: Vec<Ty>,
+    overloads: Vec<Function>,
+    ty: Option<Ty>,
+}
+
+/// Classes & aliases declared by annotations.
+#[derive(Debug, Default)]
+struct Annotations {
+    /// Classes in the order they were declared.
+    classes: Vec<Class>,
+    /// Types of aliases in the order they were declared, by name.
+    aliases: Vec<(String, Ty)>,
+    /// Names of classes by the local variable or path of the table annotated with the class.
+    bindings: HashMap<String, String>,
+}
+
+impl Annotations {
+    /// Parses the annotations within the Lua `code` of `file`.
+    fn parse(&mut self, file: &str, code: &str) -> anyhow::Result<()> {
+        let mut block = Block::default();
+
+        // Name of the class of a table being constructed across lines, whose fields are declared
+        // by the `---@type` preceding them
+        let mut table: Option<String> = None;
+
+        for (i, line) in code.lines().enumerate() {
+            self.parse_line(line.trim(), &mut block, &mut table)
+                .with_context(|| format!("Failed to parse {file}:{}", i + 1))?;
+        }
+
+        self.finish(std::mem::take(&mut block))
+    }
+
+    /// Parses a single `line`, adding to the annotations of `block` until the code they annotate.
+    fn parse_line(
+        &mut self,
+        line: &str,
+        block: &mut Block,
+        table: &mut Option<String>,
+    ) -> anyhow::Result<()> {
+        if line.is_empty() {
+            return self.finish(std::mem::take(block));
+        } else if let Some(variant) = line.strip_prefix("---|") {
+            let (_, variants) = block.alias.as_mut().context("Variant outside of alias")?;
+            variants.push(TypeParser::new(variant).parse()?);
+            return Ok(());
+        } else if let Some(tag) = line.strip_prefix("---@") {
+            return self.parse_tag(tag, block);
+        } else if line.starts_with("--") {
+            return Ok(());
+        }
+
+        let block = std::mem::take(block);
+        if line == "}" {
+            *table = None;
+        } else if let Some(rest) = line.strip_prefix("function ") {
+            let (target, rest) = rest.split_once('(').context("Missing parameters")?;
+            let (params, _) = rest.split_once(')').context("Missing end of parameters")?;
+            self.declare_function(target, params, &block)?;
+        } else if let Some((target, value)) = line.split_once('=') {
+            let target = target.trim();
+            let value = value.trim();
+            let name = block.class.as_ref().map(|x| x.name.clone());
+
+            if let Some(local) = target.strip_prefix("local ") {
+                match name.clone() {
+                    Some(name) => self.bindings.insert(local.trim().to_string(), name),
+                    None => self.bindings.remove(local.trim()),
+                };
+            } else if is_path(target) && target.split('.').next() == Some(GLOBAL_PDF_VAR_NAME) {
+                self.declare_value(target, name.clone(), block.ty.clone())?;
+            } else if let Some(owner) = table.as_ref().filter(|_| is_path(target)) {
+                // Fields of tables without a type are declared by the class of the table instead
+                if let Some(ty) = block.ty.clone() {
+                    let key = Key::Name(target.to_string());
+                    self.class_mut(owner)?.add_field(Field { key, ty });
+                }
+            }
+
+            if value == "{" {
+                *table = name;
+            }
+        }
+
+        self.finish(block)
+    }
+
+    /// Parses the annotation `tag` (without its leading `---@`), adding it to `block`.
+    fn parse_tag(&mut self, tag: &str, block: &mut Block) -> anyhow::Result<()> {
+        let (tag, rest) = tag.split_once(' ').unwrap_or((tag, ""));
+        let rest = rest.trim();
+
+        match tag {
+            "class" => {
+                self.finish(std::mem::take(block))?;
+                let (name, parents) = rest.split_once(':').unwrap_or((rest, ""));
+                let parents = parents
+                    .split(',')
+                    .filter(|x| !x.trim().is_empty())
+                    .map(|x| TypeParser::new(x).parse())
+                    .collect::<anyhow::Result<_>>()?;
+
+                block.class = Some(Class {
+                    name: name.trim().to_string(),
+                    parents,
+                    fields: Vec::new(),
+                    overloads: Vec::new(),
+                });
+            }
+            "alias" => {
+                self.finish(std::mem::take(block))?;
+                let (name, ty) = rest.split_once(' ').unwrap_or((rest, ""));
+                let mut variants = Vec::new();
+                if !ty.trim().is_empty() {
+                    variants.push(TypeParser::new(ty).parse()?);
+                }
+                block.alias = Some((name.to_string(), variants));
+            }
+            "field" => {
+                let class = block.class.as_mut().context("Field outside of class")?;
+                let mut parser = TypeParser::new(rest);
+                let key = if parser.eat("[") {
+                    let key = parser.parse()?;
+                    parser.expect("]")?;
+                    Key::Index(key)
+                } else {
+                    Key::Name(parser.ident().context("Missing name of field")?.to_string())
+                };
+                let optional = parser.eat("?");
+                let ty = parser.parse()?;
+                class.add_field(Field {
+                    key,
+                    ty: if optional { optional_of(ty) } else { ty },
+                });
+            }
+            "param" => {
+                let mut parser = TypeParser::new(rest);
+                let name = match parser.eat("...") {
+                    true => "...",
+                    false => parser.ident().context("Missing name of parameter")?,
+                };
+                let optional = parser.eat("?");
+                let ty = parser.parse()?;
+                let ty = if optional { optional_of(ty) } else { ty };
+                block.params.push((name.to_string(), ty));
+            }
+            "return" => block.returns.push(TypeParser::new(rest).parse()?),
+            "generic" => {
+                for generic in rest.split(',') {
+                    let (name, _) = generic.split_once(':').unwrap_or((generic, ""));
+                    block.generics.push(name.trim().to_string());
+                }
+            }
+            "overload" => {
+                let mut parser = TypeParser::new(rest);
+                let f = match parser.parse()? {
+                    Ty::Function(f) => f,
+                    _ => anyhow::bail!("Overload is not a function"),
+                };
+                match block.class.as_mut() {
+                    Some(class) => class.overloads.push(f),
+                    None => block.overloads.push(f),
+                }
+            }
+            "type" => block.ty = Some(TypeParser::new(rest).parse()?),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Finishes `block`, declaring its class or alias.
+    fn finish(&mut self, block: Block) -> anyhow::Result<()> {
+        if let Some(class) = block.class {
+            match self.classes.iter_mut().find(|x| x.name == class.name) {
+                Some(existing) => {
+                    for field in class.fields {
+                        existing.add_field(field);
+                    }
+                    existing.parents.extend(class.parents);
+                    existing.overloads.extend(class.overloads);
+                }
+                None => self.classes.push(class),
+            }
+        }
+
+        if let Some((name, variants)) = block.alias {
+            let ty = union_of(variants).with_context(|| format!("Alias {name} has no type"))?;
+            self.aliases.push((name, ty));
+        }
+
+        Ok(())
+    }
+
+    /// Declares the function at `target` accepting the comma-separated `params`, whose types are
+    /// annotated by `block`.
+    fn declare_function(
+        &mut self,
+        target: &str,
+        params: &str,
+        block: &Block,
+    ) -> anyhow::Result<()> {
+        let (owner, name, is_method) = match target.rfind([':', '.']) {
+            Some(i) => (&target[..i], &target[i + 1..], target[i..].starts_with(':')),
+            None => return Ok(()),
+        };
+
+        // Functions of local tables without a class are implementation details
+        let owner = match self.bindings.get(owner) {
+            Some(owner) => owner.clone(),
+            None if owner.starts_with(GLOBAL_PDF_VAR_NAME) => {
+                anyhow::bail!("Missing class of {owner}")
+            }
+            None => return Ok(()),
+        };
+
+        // Metamethods like __tostring cannot be written within the type of a table
+        if name.starts_with("__") {
+            return Ok(());
+        }
+
+        let mut f = Function {
+            generics: block.generics.clone(),
+            params: Vec::new(),
+            returns: block.returns.clone(),
+        };
+        if is_method {
+            f.params
+                .push((String::from("self"), Ty::Name(owner.clone())));
+        }
+        for param in params.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let (_, ty) = block
+                .params
+                .iter()
+                .find(|(name, _)| name == param)
+                .with_context(|| format!("Missing type of parameter {param} of {target}"))?;
+            f.params.push((param.to_string(), ty.clone()));
+        }
+        if let Some((name, _)) = block
+            .params
+            .iter()
+            .find(|(name, _)| !params.split(',').any(|x| x.trim() == name))
+        {
+            anyhow::bail!("Type of {name} is not a parameter of {target}");
+        }
+
+        // Overloads are written as the intersection of each signature of the function
+        let ty = if block.overloads.is_empty() {
+            Ty::Function(f)
+        } else {
+            let mut fs = vec![Ty::Function(f)];
+            fs.extend(block.overloads.iter().cloned().map(Ty::Function));
+            Ty::Intersection(fs)
+        };
+
+        let key = Key::Name(name.to_string());
+        self.class_mut(&owner)?.add_field(Field { key, ty });
+        Ok(())
+    }
+
+    /// Declares the value at `path`, which is a table of the class `name` or a value of type `ty`.
+    fn declare_value(
+        &mut self,
+        path: &str,
+        name: Option<String>,
+        ty: Option<Ty>,
+    ) -> anyhow::Result<()> {
+        let ty = match name {
+            Some(name) => {
+                self.bindings.insert(path.to_string(), name.clone());
+                Ty::Name(name)
+            }
+            None => ty.with_context(|| format!("Missing type of {path}"))?,
+        };
+
+        if let Some((owner, key)) = path.rsplit_once('.') {
+            let owner = self
+                .bindings
+                .get(owner)
+                .cloned()
+                .with_context(|| format!("Missing class of {owner}"))?;
+            let key = Key::Name(key.to_string());
+            self.class_mut(&owner)?.add_field(Field { key, ty });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the class declared as `name`.
+    fn class_mut(&mut self, name: &str) -> anyhow::Result<&mut Class> {
+        self.classes
+            .iter_mut()
+            .find(|x| x.name == name)
+            .with_context(|| format!("Missing class {name}"))
+    }
+
+    /// Translates the classes & aliases into Luau types, where the class of the `pdf` global is
+    /// the type of the global.
+    fn to_types(&self) -> anyhow::Result<PdfTypes> {
+        let mut names = HashMap::new();
+        let mut declared = HashMap::new();
+        let declarations = self
+            .classes
+            .iter()
+            .map(|x| x.name.as_str())
+            .chain(self.aliases.iter().map(|(name, _)| name.as_str()))
+            .filter(|x| *x != GLOBAL_PDF_VAR_NAME);
+        for name in declarations {
+            let luau = luau_name(name);
+            if let Some(other) = declared.insert(luau.clone(), name) {
+                anyhow::ensure!(other == name, "Types {other} and {name} are both {luau}");
+            }
+            names.insert(name.to_string(), luau);
+        }
+
+        let writer = Writer { names: &names };
+        let mut types = BTreeMap::new();
+        let mut global = None;
+        for class in self.classes.iter() {
+            let ty = writer
+                .class(class)
+                .with_context(|| format!("Failed to translate class {}", class.name))?;
+            match names.get(&class.name) {
+                Some(name) => types.insert(name.clone(), ty),
+                None => global.replace(ty),
+            };
+        }
+
+        for (name, ty) in self.aliases.iter() {
+            let ty = writer
+                .ty(ty, &[])
+                .with_context(|| format!("Failed to translate alias {name}"))?;
+            types.insert(names[name].clone(), ty);
+        }
+
+        let global = global.with_context(|| format!("Missing class {GLOBAL_PDF_VAR_NAME}"))?;
+        Ok(PdfTypes { types, global })
+    }
+}
+
+/// Writes the types of annotations as Luau types.
+struct Writer<'a> {
+    /// Luau names of the declared classes & aliases, by their names within annotations.
+    names: &'a HashMap<String, String>,
+}
+
+impl Writer<'_> {
+    /// Writes `class` as a table type, intersected with its parents and the signatures of calling
+    /// it.
+    fn class(&self, class: &Class) -> anyhow::Result<String> {
+        let mut out = String::new();
+        for parent in class.parents.iter() {
+            let _ = write!(out, "{} & ", self.atom(parent, &[])?);
+        }
+        for f in class.overloads.iter() {
+            let _ = write!(out, "({}) & ", self.function(f, &[])?);
+        }
+
+        out.push_str("{\n");
+        for (key, ty) in self.fields(&class.fields, &[])? {
+            let _ = writeln!(out, "    {key}: {ty},");
+        }
+        out.push('}');
+        Ok(out)
+    }
+
+    /// Writes `ty`, where the names of `generics` refer to generic types.
+    fn ty(&self, ty: &Ty, generics: &[String]) -> anyhow::Result<String> {
+        Ok(match ty {
+            Ty::Name(name) => self.name(name, generics)?,
+            Ty::Literal(literal) => literal.clone(),
+            Ty::Array(item) => format!("{{ {} }}", self.ty(item, generics)?),
+            Ty::Map(key, value) => format!(
+                "{{ [{}]: {} }}",
+                self.ty(key, generics)?,
+                self.ty(value, generics)?
+            ),
+            Ty::Table(fields) => {
+                let fields = self.fields(fields, generics)?;
+                match fields.as_slice() {
+                    [] => return Ok(String::from("{}")),
+                    [(key, ty)] if key == "[number]" => return Ok(format!("{{ {ty} }}")),
+                    _ => {}
+                }
+
+                let fields: Vec<_> = fields
+                    .into_iter()
+                    .map(|(key, ty)| format!("{key}: {ty}"))
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            Ty::Function(f) => self.function(f, generics)?,
+            Ty::Union(tys) => self.join(tys, " | ", generics)?,
+            Ty::Intersection(tys) => self.join(tys, " & ", generics)?,
+            Ty::Optional(ty) => format!("{}?", self.atom(ty, generics)?),
+        })
+    }
+
+    /// Writes `ty`, wrapped in parentheses if it cannot be combined with other types as is.
+    fn atom(&self, ty: &Ty, generics: &[String]) -> anyhow::Result<String> {
+        let out = self.ty(ty, generics)?;
+        Ok(match ty {
+            Ty::Function(_) | Ty::Union(_) | Ty::Intersection(_) => format!("({out})"),
+            _ => out,
+        })
+    }
+
+    /// Writes `tys` separated by `sep`, dropping duplicates.
+    fn join(&self, tys: &[Ty], sep: &str, generics: &[String]) -> anyhow::Result<String> {
+        let mut out: Vec<String> = Vec::new();
+        for ty in tys {
+            let ty = self.atom(ty, generics)?;
+            if !out.contains(&ty) {
+                out.push(ty);
+            }
+        }
+        Ok(out.join(sep))
+    }
+
+    /// Writes the signature of `f`.
+    fn function(&self, f: &Function, generics: &[String]) -> anyhow::Result<String> {
+        let mut generics = generics.to_vec();
+        generics.extend(f.generics.iter().cloned());
+
+        let mut params = Vec::new();
+        for (name, ty) in f.params.iter() {
+            params.push(match name.as_str() {
+                "..." => format!("...{}", self.atom(ty, &generics)?),
+                _ => format!("{name}: {}", self.ty(ty, &generics)?),
+            });
+        }
+
+        let returns = match f.returns.as_slice() {
+            [] => String::from("()"),
+            [ty] => self.atom(ty, &generics)?,
+            tys => {
+                let mut returns = Vec::new();
+                for ty in tys {
+                    returns.push(self.ty(ty, &generics)?);
+                }
+                format!("({})", returns.join(", "))
+            }
+        };
+
+        let generics = match f.generics.is_empty() {
+            true => String::new(),
+            false => format!("<{}>", f.generics.join(", ")),
+        };
+        Ok(format!("{generics}({}) -> {returns}", params.join(", ")))
+    }
+
+    /// Writes the keys & types of `fields`, merging fields accessed by keys of a type (such as
+    /// `[1]` and `[2]`) into a single indexer as Luau has no tuples.
+    fn fields(
+        &self,
+        fields: &[Field],
+        generics: &[String],
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let mut out = Vec::new();
+        let mut index: Option<(usize, Ty, Vec<Ty>)> = None;
+        for field in fields {
+            match &field.key {
+                Key::Name(name) if KEYWORDS.contains(&name.as_str()) => {
+                    out.push((format!("[\"{name}\"]"), self.ty(&field.ty, generics)?));
+                }
+                Key::Name(name) => out.push((name.clone(), self.ty(&field.ty, generics)?)),
+                Key::Index(key) => {
+                    let (_, _, tys) =
+                        index.get_or_insert_with(|| (out.len(), key.clone(), Vec::new()));
+                    tys.push(field.ty.clone());
+                }
+            }
+        }
+
+        if let Some((i, key, tys)) = index {
+            let ty = union_of(tys).context("Missing type of index")?;
+            out.insert(
+                i,
+                (
+                    format!("[{}]", self.ty(&key, generics)?),
+                    self.ty(&ty, generics)?,
+                ),
+            );
+        }
+
+        Ok(out)
+    }
+
+    /// Writes the type referenced by `name`.
+    fn name(&self, name: &str, generics: &[String]) -> anyhow::Result<String> {
+        if generics.iter().any(|x| x == name) {
+            return Ok(name.to_string());
+        }
+
+        if let Some((_, ty)) = BUILTIN_TYPES.iter().find(|(x, _)| *x == name) {
+            return Ok(ty.to_string());
+        }
+
+        self.names
+            .get(name)
+            .cloned()
+            .with_context(|| format!("Unknown type {name}"))
+    }
+}
+
+/// Parses the types written within annotations, such as `{x:number, y?:number}[]|nil`.
+struct TypeParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> TypeParser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s, pos: 0 }
+    }
+
+    /// Returns the text that has yet to be parsed.
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    /// Skips whitespace, returning true if the next text is `token`, which is then skipped.
+    fn eat(&mut self, token: &str) -> bool {
+        self.pos = self.s.len() - self.rest().trim_start().len();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    /// Skips `token`, failing if it is not the next text.
+    fn expect(&mut self, token: &str) -> anyhow::Result<()> {
+        anyhow::ensure!(self.eat(token), "Expected {token} at {:?}", self.rest());
+        Ok(())
+    }
+
+    /// Parses a name, which can be made up of several names separated by dots.
+    fn ident(&mut self) -> Option<&'a str> {
+        self.eat("");
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        if len == 0 || !rest.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return None;
+        }
+
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// Parses a type, such as `A|B`.
+    fn parse(&mut self) -> anyhow::Result<Ty> {
+        let mut tys = vec![self.parse_postfix()?];
+        while self.eat("|") {
+            tys.push(self.parse_postfix()?);
+        }
+        union_of(tys).context("Missing type")
+    }
+
+    /// Parses a type followed by any number of `[]` and `?`, such as `T[]?`.
+    fn parse_postfix(&mut self) -> anyhow::Result<Ty> {
+        let mut ty = self.parse_primary()?;
+        loop {
+            if self.rest().starts_with("[]") {
+                self.pos += 2;
+                ty = Ty::Array(Box::new(ty));
+            } else if self.rest().starts_with('?') {
+                self.pos += 1;
+                ty = optional_of(ty);
+            } else {
+                return Ok(ty);
+            }
+        }
+    }
+
+    /// Parses a type that is not combined with others, such as a name or table.
+    fn parse_primary(&mut self) -> anyhow::Result<Ty> {
+        if self.eat("(") {
+            let ty = self.parse()?;
+            self.expect(")")?;
+            return Ok(ty);
+        } else if self.eat("{") {
+            return self.parse_table();
+        }
+
+        let rest = self.rest();
+        if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            let len = rest[1..]
+                .find(quote)
+                .with_context(|| format!("Unterminated string at {rest:?}"))?;
+            self.pos += len + 2;
+            return Ok(Ty::Literal(format!("\"{}\"", &rest[1..len + 1])));
+        } else if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            self.pos += len;
+            return Ok(Ty::Name(String::from("number")));
+        }
+
+        match self.ident() {
+            Some("fun") => Ok(Ty::Function(self.parse_function()?)),
+            Some("table") if self.eat("<") => {
+                let key = self.parse()?;
+                self.expect(",")?;
+                let value = self.parse()?;
+                self.expect(">")?;
+                Ok(Ty::Map(Box::new(key), Box::new(value)))
+            }
+            Some(literal @ ("true" | "false")) => Ok(Ty::Literal(literal.to_string())),
+            Some(name) => Ok(Ty::Name(name.to_string())),
+            None => anyhow::bail!("Expected type at {rest:?}"),
+        }
+    }
+
+    /// Parses the fields of a table after its opening `{`, such as `x:number, [1]:number}`.
+    fn parse_table(&mut self) -> anyhow::Result<Ty> {
+        let mut fields = Vec::new();
+        while !self.eat("}") {
+            let key = if self.eat("[") {
+                let key = self.parse()?;
+                self.expect("]")?;
+                Key::Index(key)
+            } else {
+                Key::Name(self.ident().context("Missing name of field")?.to_string())
+            };
+            let optional = self.eat("?");
+            self.expect(":")?;
+            let ty = self.parse()?;
+            fields.push(Field {
+                key,
+                ty: if optional { optional_of(ty) } else { ty },
+            });
+
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+
+        Ok(Ty::Table(fields))
+    }
+
+    /// Parses the signature of a function after `fun`, such as `(x:number):string`.
+    fn parse_function(&mut self) -> anyhow::Result<Function> {
+        let mut f = Function::default();
+        self.expect("(")?;
+        while !self.eat(")") {
+            let name = match self.eat("...") {
+                true => "...",
+                false => self.ident().context("Missing name of parameter")?,
+            };
+            let optional = self.eat("?");
+            let ty = match self.eat(":") {
+                true => self.parse()?,
+                false => Ty::Name(String::from("any")),
+            };
+            let ty = if optional { optional_of(ty) } else { ty };
+            f.params.push((name.to_string(), ty));
+
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+
+        if self.eat(":") {
+            f.returns.push(self.parse()?);
+        }
+
+        Ok(f)
+    }
+}
+
+/// Returns the union of `tys`, which is optional if any of them is nil or optional.
+fn union_of(tys: Vec<Ty>) -> Option<Ty> {
+    let mut optional = false;
+    let mut out = Vec::new();
+    for ty in tys {
+        match ty {
+            Ty::Name(name) if name == "nil" => optional = true,
+            Ty::Optional(ty) => {
+                optional = true;
+                out.push(*ty);
+            }
+            Ty::Union(tys) => out.extend(tys),
+            ty => out.push(ty),
+        }
+    }
+
+    let ty = match out.len() {
+        0 if optional => return Some(Ty::Name(String::from("nil"))),
+        0 => return None,
+        1 => out.remove(0),
+        _ => Ty::Union(out),
+    };
+    Some(if optional { optional_of(ty) } else { ty })
+}
+
+/// Returns `ty` or nil.
+fn optional_of(ty: Ty) -> Ty {
+    match ty {
+        Ty::Optional(_) => ty,
+        ty => Ty::Optional(Box::new(ty)),
+    }
+}
+
+/// Returns true if `s` is a name or several names separated by dots, such as `pdf.page`.
+fn is_path(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Returns the Luau name of the class or alias `name`, such as `PdfCommonPoint` for
+/// `pdf.common.Point` and `PdfObjectModule` for the module `pdf.object`.
+fn luau_name(name: &str) -> String {
+    let mut out: String = name
+        .split(['.', '_'])
+        .map(|x| {
+            let mut chars = x.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .concat();
+
+    let is_module = name
+        .rsplit('.')
+        .next()
+        .is_some_and(|x| x.starts_with(char::is_lowercase));
+    if is_module {
+        out.push_str("Module");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{
+        PdfBounds, PdfColor, PdfConfig, PdfDate, PdfObjectCircle, PdfObjectGroup, PdfObjectLine,
+        PdfObjectRect, PdfObjectShape, PdfObjectText, PdfObjectToc, PdfPoint,
+    };
+    use crate::runtime::{Runtime, RuntimeCache, RuntimeCallback, RuntimeFiles};
+    use mlua::prelude::*;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    /// Returns the string keys of `table`, of the tables it inherits through `__index`, and of
+    /// its aliases, excluding metamethods.
+    fn keys(table: &LuaTable) -> Vec<String> {
+        let mut keys = HashSet::new();
+        let mut next = Some(table.clone());
+        while let Some(table) = next.take() {
+            for pair in table.clone().pairs::<LuaValue, LuaValue>() {
+                if let (LuaValue::String(key), _) = pair.unwrap() {
+                    let key = key.to_str().unwrap();
+                    if !key.starts_with("__") {
+                        keys.insert(key.to_string());
+                    }
+                }
+            }
+
+            // Aliases of fields are read through an __index function instead of a table
+            let metatable = table.get_metatable();
+            if let Some(aliases) = metatable
+                .as_ref()
+                .and_then(|x| x.raw_get::<_, Option<LuaTable>>("__aliases").unwrap())
+            {
+                for pair in aliases.pairs::<String, LuaValue>() {
+                    keys.insert(pair.unwrap().0);
+                }
+            }
+
+            next = metatable
+                .and_then(|x| match x.raw_get::<_, LuaValue>("__index") {
+                    Ok(LuaValue::Table(index)) => Some(index),
+                    Ok(LuaValue::Function(_)) => Some(x),
+                    _ => None,
+                })
+                .filter(|x| x.to_pointer() != table.to_pointer());
+        }
+
+        let mut keys: Vec<_> = keys.into_iter().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Records the keys of `table` at `path` missing from the Luau type `ty`, checking the tables
+    /// of modules against the types of those modules.
+    fn find_missing(types: &PdfTypes, table: &LuaTable, path: &str, ty: &str) -> Vec<String> {
+        let mut missing = Vec::new();
+        for key in keys(table) {
+            let field = ty.lines().find_map(|line| {
+                line.strip_prefix(&format!("    {key}: "))
+                    .or_else(|| line.strip_prefix(&format!("    [\"{key}\"]: ")))
+            });
+
+            let Some(field) = field.map(|x| x.trim_end_matches(',')) else {
+                missing.push(format!("{path}.{key}"));
+                continue;
+            };
+
+            if let (Some(module), LuaValue::Table(value)) = (
+                types.types.get(field).filter(|_| field.ends_with("Module")),
+                table.get(key.as_str()).unwrap(),
+            ) {
+                missing.extend(find_missing(
+                    types,
+                    &value,
+                    &format!("{path}.{key}"),
+                    module,
+                ));
+            }
+        }
+        missing
+    }
+
+    /// Sets up a runtime without a script, invoking `f` with it.
+    fn with_runtime(f: impl FnOnce(&Lua) -> LuaResult<()> + 'static) {
+        let callback: RuntimeCallback = Box::new(f);
+        Runtime::new(PdfConfig::default())
+            .setup_from_code(
+                "types",
+                Vec::new(),
+                RuntimeFiles::new(),
+                vec![callback],
+                &RuntimeCache::new(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn shipped_definitions_should_match_generated_definitions() {
+        let types = PdfTypes::generate().unwrap();
+        assert_eq!(
+            types.to_definitions(),
+            include_str!("../assets/scripts/pdf.d.luau"),
+            "Regenerate assets/scripts/pdf.d.luau with `makepdf types`"
+        );
+    }
+
+    #[test]
+    fn generate_should_translate_annotations_into_signatures() {
+        let types = PdfTypes::generate().unwrap();
+
+        let text = types.types.get("PdfObjectText").expect("Missing text type");
+        assert!(
+            text.contains("    type: \"text\",\n"),
+            "Unexpected text: {text}"
+        );
+        assert!(
+            text.contains("    bounds: (self: PdfObjectText) -> PdfCommonBounds,\n"),
+            "Unexpected text: {text}"
+        );
+
+        let object = types
+            .types
+            .get("PdfObjectModule")
+            .expect("Missing object type");
+        assert!(
+            object.contains("    text: (tbl: PdfObjectTextLike) -> PdfObjectText,\n"),
+            "Unexpected object: {object}"
+        );
+
+        let shape = types
+            .types
+            .get("PdfObjectShapeModule")
+            .expect("Missing shape type");
+        assert!(
+            shape.starts_with("((tbl: PdfObjectShapeLike) -> PdfObjectShape) & {"),
+            "Unexpected shape: {shape}"
+        );
+
+        assert!(
+            types.global.contains("    object: PdfObjectModule,\n"),
+            "Unexpected global: {}",
+            types.global
+        );
+    }
+
+    #[test]
+    fn generate_should_produce_valid_luau() {
+        let definitions = PdfTypes::generate().unwrap().to_definitions();
+
+        // Definitions files declare globals, which are otherwise written as typed locals
+        let code = definitions.replace(
+            &format!("declare {GLOBAL_PDF_VAR_NAME}:"),
+            &format!("local {GLOBAL_PDF_VAR_NAME}:"),
+        );
+        Lua::new()
+            .load(code)
+            .into_function()
+            .expect("Definitions are not valid Luau");
+    }
+
+    #[test]
+    fn generate_should_declare_everything_within_the_runtime() {
+        let types = PdfTypes::generate().unwrap();
+        let missing = Rc::new(RefCell::new(Vec::new()));
+
+        with_runtime({
+            let missing = Rc::clone(&missing);
+            move |lua| {
+                let pdf: LuaTable = lua.globals().raw_get(GLOBAL_PDF_VAR_NAME)?;
+                let mut missing = missing.borrow_mut();
+                missing.extend(find_missing(
+                    &types,
+                    &pdf,
+                    GLOBAL_PDF_VAR_NAME,
+                    &types.global,
+                ));
+
+                let samples: Vec<(&str, LuaValue)> = vec![
+                    ("PdfCommonBounds", PdfBounds::default().into_lua(lua)?),
+                    ("PdfCommonColor", PdfColor::default().into_lua(lua)?),
+                    ("PdfCommonDate", PdfDate::default().into_lua(lua)?),
+                    ("PdfCommonPoint", PdfPoint::default().into_lua(lua)?),
+                    ("PdfObjectCircle", PdfObjectCircle::default().into_lua(lua)?),
+                    ("PdfObjectGroup", PdfObjectGroup::default().into_lua(lua)?),
+                    ("PdfObjectLine", PdfObjectLine::default().into_lua(lua)?),
+                    ("PdfObjectRect", PdfObjectRect::default().into_lua(lua)?),
+                    ("PdfObjectShape", PdfObjectShape::default().into_lua(lua)?),
+                    ("PdfObjectText", PdfObjectText::default().into_lua(lua)?),
+                    ("PdfObjectToc", PdfObjectToc::default().into_lua(lua)?),
+                ];
+                for (name, value) in samples {
+                    let table = value.as_table().expect("Sample is not a table");
+                    missing.extend(find_missing(&types, table, name, &types.types[name]));
+                }
+
+                Ok(())
+            }
+        });
+
+        let missing = missing.borrow();
+        assert!(
+            missing.is_empty(),
+            "Missing from definitions.lua: {missing:?}"
+        );
+    }
+}
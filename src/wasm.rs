@@ -0,0 +1,26 @@
+use crate::{Builder, PdfConfig};
+use wasm_bindgen::prelude::*;
+
+/// Generates a PDF by running the Lua `script`, returning the bytes of the PDF, so that planners
+/// can be made in the browser using the same engine as the commandline.
+///
+/// The `config` is an object of the fields of `pdf.config`, such as
+/// `{ title: "Planner", page: { width: 210, height: 297 } }`, where missing fields (or a missing
+/// object altogether) use the defaults. Scripts cannot read files.
+#[wasm_bindgen]
+pub fn generate(script: &str, config: JsValue) -> Result<Vec<u8>, JsValue> {
+    let config = if config.is_undefined() || config.is_null() {
+        PdfConfig::default()
+    } else {
+        let text = js_sys::JSON::stringify(&config)?
+            .as_string()
+            .unwrap_or_default();
+        PdfConfig::from_json(&text).map_err(|x| JsValue::from_str(&format!("{x:#}")))?
+    };
+
+    Builder::new()
+        .config(config)
+        .script(script)
+        .to_bytes()
+        .map_err(|x| JsValue::from_str(&format!("{x:#}")))
+}